@@ -0,0 +1,524 @@
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::{
+    cubiomes::enums::{BiomeID, MCVersion},
+    pipeline::{
+        BuriedTreasureCountEvidence, CrackingEvidence, CrackingPipeline, EvidenceCheck,
+        PipelineOptions,
+    },
+    search::WorldExtraData,
+};
+
+use crate::{
+    parse_pillar_args,
+    treasure_counts::{as_i32, parse_bound, treasure_item},
+    tui::tabs::biomes::list_biomes,
+};
+
+/// The only version the searches support
+pub const VERSION: &str = "1.16.5";
+
+const CHECK_EVIDENCE_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("version"),
+        optional: true,
+        argtype: StringOrSlice::Sl("string"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("treasure_x"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("treasure_z"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("luck"),
+        optional: true,
+        argtype: StringOrSlice::Sl("f32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("counts"),
+        optional: true,
+        argtype: StringOrSlice::Sl("list(item_count_constraint)"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("overworld_biomes"),
+        optional: true,
+        argtype: StringOrSlice::Sl("list(biome_sample)"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("nether_biomes"),
+        optional: true,
+        argtype: StringOrSlice::Sl("list(biome_sample)"),
+    },
+];
+
+/// A biome from the name F3 shows in `version`, with or without the `minecraft:` namespace
+pub fn biome_named(name: &str, version: MCVersion) -> Option<BiomeID> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    list_biomes()
+        .iter()
+        .find(|b| b.to_mc_biome_str(version) == name)
+        .copied()
+}
+
+/// The report as a tuple of the errors, the warnings, the number of pillar seeds left and the estimated bits of seed
+/// information
+pub fn report_value(report: &EvidenceCheck) -> ParsetimeProtocolValue<'_> {
+    fn lines(lines: &[String]) -> Vec<ParsetimeProtocolValue<'_>> {
+        lines
+            .iter()
+            .map(|line| ParsetimeProtocolValue::String(StringOrSlice::Sl(line)))
+            .collect()
+    }
+    ParsetimeProtocolValue::Tuple(
+        None,
+        vec![
+            ParsetimeProtocolValue::List(None, lines(&report.errors)),
+            ParsetimeProtocolValue::List(None, lines(&report.warnings)),
+            ParsetimeProtocolValue::I64(report.pillar_seed_count as i64),
+            ParsetimeProtocolValue::F64(report.estimated_bits),
+        ],
+    )
+}
+
+fn string_value<'a>(value: &'a ParsetimeProtocolValue) -> Option<&'a str> {
+    match value {
+        ParsetimeProtocolValue::String(s) | ParsetimeProtocolValue::RawString(s) => {
+            Some(s.as_slice())
+        }
+        _ => None,
+    }
+}
+
+/// A `biome_sample` value
+pub(crate) fn parse_sample(value: &ParsetimeProtocolValue) -> Option<(i32, i32, i32, &str)> {
+    let ParsetimeProtocolValue::Tuple(_, fields) = value else {
+        return None;
+    };
+    let [x, y, z, name] = fields.as_slice() else {
+        return None;
+    };
+    Some((as_i32(x)?, as_i32(y)?, as_i32(z)?, string_value(name)?))
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckEvidenceProblem {
+    args: Vec<V0ProblemArgumentDescription<'static>>,
+}
+
+impl Default for CheckEvidenceProblem {
+    fn default() -> Self {
+        let mut args = CHECK_EVIDENCE_ARGS.to_vec();
+        for i in 0..10 {
+            args.push(V0ProblemArgumentDescription {
+                name: StringOrSlice::St(format!("pillar{}height", i)),
+                optional: true,
+                argtype: StringOrSlice::Sl("pillar_height_hint"),
+            });
+            args.push(V0ProblemArgumentDescription {
+                name: StringOrSlice::St(format!("pillar{}caged", i)),
+                optional: true,
+                argtype: StringOrSlice::Sl("pillar_caged_status"),
+            });
+        }
+        Self { args }
+    }
+}
+
+impl SimpleV0Problem for CheckEvidenceProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("check-evidence")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Checks evidence without searching: the pillars, the buried treasure at (treasure_x, treasure_z) with its item counts and the biome samples. The result is a tuple of the errors, the warnings, the number of pillar seeds left and the estimated bits of seed information.",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(&self.args)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        let invalid_value =
+            || ParsetimeProtocolValue::String(StringOrSlice::Sl("Invalid argument value"));
+        let (pillar_args, args): (Vec<_>, Vec<_>) = args
+            .as_slice()
+            .iter()
+            .cloned()
+            .partition(|(name, _)| name.as_slice().starts_with("pillar"));
+        let mut evidence = CrackingEvidence {
+            pillars: parse_pillar_args(&pillar_args)?,
+            ..Default::default()
+        };
+
+        // the evidence a player got wrong is reported, only the values that aren't evidence are refused
+        let mut errors = Vec::new();
+        let (mut x, mut z, mut luck, mut counts) = (None, None, 0.0, Vec::new());
+        let (mut overworld, mut nether) = (Vec::new(), Vec::new());
+        for (name, value) in args.iter() {
+            match (name.as_slice(), value) {
+                ("version", v) => match string_value(v).ok_or_else(invalid_value)? {
+                    VERSION => {}
+                    version => errors.push(format!(
+                        "Version: {} is not supported, only {} is",
+                        version, VERSION
+                    )),
+                },
+                ("treasure_x", v) => x = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("treasure_z", v) => z = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("luck", ParsetimeProtocolValue::F32(v)) => luck = *v,
+                ("luck", ParsetimeProtocolValue::F64(v)) => luck = *v as f32,
+                ("counts", ParsetimeProtocolValue::List(_, values)) => {
+                    for value in values.iter() {
+                        let ParsetimeProtocolValue::Tuple(_, fields) = value else {
+                            return Err(invalid_value());
+                        };
+                        let [name, bound] = fields.as_slice() else {
+                            return Err(invalid_value());
+                        };
+                        let name = string_value(name).ok_or_else(invalid_value)?;
+                        match (treasure_item(name), parse_bound(bound)) {
+                            (Some(item), Some(bound)) => counts.push((item, bound)),
+                            (None, _) => errors.push(format!(
+                                "Buried treasure: no buried treasure item is named {:?}",
+                                name
+                            )),
+                            (_, None) => errors.push(format!(
+                                "Buried treasure: the count bound of {} is invalid",
+                                name
+                            )),
+                        }
+                    }
+                }
+                ("overworld_biomes" | "nether_biomes", ParsetimeProtocolValue::List(_, values)) => {
+                    let samples = values
+                        .iter()
+                        .map(parse_sample)
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(invalid_value)?;
+                    if name.as_slice() == "overworld_biomes" {
+                        overworld = samples;
+                    } else {
+                        nether = samples;
+                    }
+                }
+                ("luck" | "counts" | "overworld_biomes" | "nether_biomes", _) => {
+                    return Err(invalid_value());
+                }
+                _ => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument name",
+                    )));
+                }
+            }
+        }
+
+        match (x, z) {
+            (Some(pos_x), Some(pos_z)) => {
+                evidence.buried_treasure_counts = Some(BuriedTreasureCountEvidence {
+                    pos_x,
+                    pos_z,
+                    luck,
+                    counts,
+                    observed_at: None,
+                });
+            }
+            (None, None) if counts.is_empty() => {}
+            _ => {
+                return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                    "Missing argument",
+                )));
+            }
+        }
+
+        let dimensions: [(_, _, fn(_) -> _); 2] = [
+            ("Overworld", overworld, WorldExtraData::OverworldBiomeData),
+            ("Nether", nether, WorldExtraData::NetherBiomeData),
+        ];
+        for (dimension, samples, data) in dimensions {
+            let mut biomes = Vec::new();
+            for (x, y, z, name) in samples {
+                match biome_named(name, MCVersion::MC_1_16_5) {
+                    Some(biome) => biomes.push((x, y, z, biome)),
+                    None => errors.push(format!(
+                        "{} biomes: no biome of {} is named {:?}",
+                        dimension, VERSION, name
+                    )),
+                }
+            }
+            if !biomes.is_empty() {
+                evidence.world.push(data(biomes));
+            }
+        }
+
+        let mut report = CrackingPipeline::check_evidence(&evidence, &PipelineOptions::default());
+        errors.append(&mut report.errors);
+        report.errors = errors;
+        Ok(Box::new(CheckEvidenceComputation {
+            report,
+            done: false,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct CheckEvidenceComputation {
+    report: EvidenceCheck,
+    done: bool,
+}
+
+impl SimpleV0ProblemBruteCalculation for CheckEvidenceComputation {
+    fn go(&mut self) {
+        self.done = true;
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    fn is_failed(&self) -> bool {
+        false
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        (self.done as u64, 1)
+    }
+
+    fn stop(&mut self) {}
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        self.done.then(|| report_value(&self.report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcsci::{
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{ParsetimeProtocolValue, SimpleV0Problem},
+    };
+    use mcseedcracker::{
+        features::end_pillars::{EndPillars, PartialEndPillars, PillarHeightHint},
+        self_test::{BURIED_TREASURE_POS, PILLAR_SEED},
+    };
+
+    use crate::{check_evidence::CheckEvidenceProblem, emit::pillar_args};
+
+    type Arg = (StringOrSlice<'static>, ParsetimeProtocolValue<'static>);
+
+    struct Report {
+        errors: Vec<String>,
+        warnings: Vec<String>,
+        pillar_seeds: i64,
+        bits: f64,
+    }
+
+    fn arg(name: &'static str, value: ParsetimeProtocolValue<'static>) -> Arg {
+        (StringOrSlice::Sl(name), value)
+    }
+
+    fn string(s: &'static str) -> ParsetimeProtocolValue<'static> {
+        ParsetimeProtocolValue::String(StringOrSlice::Sl(s))
+    }
+
+    fn count(name: &'static str, ctor: &'static str, n: i8) -> ParsetimeProtocolValue<'static> {
+        ParsetimeProtocolValue::Tuple(
+            None,
+            vec![
+                string(name),
+                ParsetimeProtocolValue::Enumeration(
+                    None,
+                    StringOrSlice::Sl(ctor),
+                    Some(Box::new(ParsetimeProtocolValue::I8(n))),
+                ),
+            ],
+        )
+    }
+
+    fn sample(x: i32, y: i32, z: i32, biome: &'static str) -> ParsetimeProtocolValue<'static> {
+        ParsetimeProtocolValue::Tuple(
+            None,
+            vec![
+                ParsetimeProtocolValue::I32(x),
+                ParsetimeProtocolValue::I32(y),
+                ParsetimeProtocolValue::I32(z),
+                string(biome),
+            ],
+        )
+    }
+
+    fn exact_pillars() -> PartialEndPillars {
+        let mut real_pillars = EndPillars::new();
+        real_pillars.from_seed(PILLAR_SEED);
+        let mut pillars = PartialEndPillars::new();
+        for (ppillar, pillar) in pillars.iter_mut().zip(real_pillars.iter()) {
+            ppillar.caged = Some(pillar.caged);
+            ppillar.height = PillarHeightHint::Exact(pillar.height);
+        }
+        pillars
+    }
+
+    fn check(args: Vec<Arg>) -> Result<Report, String> {
+        let mut computation = CheckEvidenceProblem::default()
+            .setup(VecOrSlice::V(args))
+            .map_err(|err| match err {
+                ParsetimeProtocolValue::String(s) => s.as_slice().to_string(),
+                _ => "not a string".to_string(),
+            })?;
+        computation.go();
+
+        let lines = |value: &ParsetimeProtocolValue| match value {
+            ParsetimeProtocolValue::List(_, lines) => lines
+                .iter()
+                .map(|line| match line {
+                    ParsetimeProtocolValue::String(s) => s.as_slice().to_string(),
+                    _ => panic!("not a string"),
+                })
+                .collect(),
+            _ => panic!("not a list"),
+        };
+        match computation.result() {
+            Some(ParsetimeProtocolValue::Tuple(_, fields)) => match fields.as_slice() {
+                [
+                    errors,
+                    warnings,
+                    ParsetimeProtocolValue::I64(pillar_seeds),
+                    ParsetimeProtocolValue::F64(bits),
+                ] => Ok(Report {
+                    errors: lines(errors),
+                    warnings: lines(warnings),
+                    pillar_seeds: *pillar_seeds,
+                    bits: *bits,
+                }),
+                _ => panic!("not a report"),
+            },
+            _ => panic!("no result"),
+        }
+    }
+
+    #[test]
+    fn test_clean_evidence() {
+        let mut args = pillar_args(&exact_pillars());
+        args.extend([
+            arg("version", string("1.16.5")),
+            arg(
+                "treasure_x",
+                ParsetimeProtocolValue::I16(BURIED_TREASURE_POS.0 as i16),
+            ),
+            arg(
+                "treasure_z",
+                ParsetimeProtocolValue::I16(BURIED_TREASURE_POS.1 as i16),
+            ),
+            arg(
+                "counts",
+                ParsetimeProtocolValue::List(None, vec![count("Iron Ingot", "AtLeast", 9)]),
+            ),
+            arg(
+                "overworld_biomes",
+                ParsetimeProtocolValue::List(None, vec![sample(400, 60, 800, "minecraft:beach")]),
+            ),
+        ]);
+        let report = check(args).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert!((1..=5).contains(&report.pillar_seeds));
+        // the pillars, the treasure chunk and a biome
+        assert!(report.bits > 16.0 && report.bits < 48.0);
+
+        // nothing observed is fine too, but leaves every pillar seed
+        let report = check(Vec::new()).unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.pillar_seeds, 65536);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("End pillars: 65536 pillar seeds left"));
+        assert_eq!(report.bits, 0.0);
+    }
+
+    #[test]
+    fn test_every_kind_of_problem() {
+        let mut pillars = exact_pillars();
+        for pillar in pillars.iter_mut() {
+            pillar.caged = Some(false);
+        }
+        let mut args = pillar_args(&pillars);
+        args.extend([
+            arg("version", string("1.12.2")),
+            arg("treasure_x", ParsetimeProtocolValue::I32(40_000_000)),
+            arg("treasure_z", ParsetimeProtocolValue::I32(809)),
+            arg(
+                "counts",
+                ParsetimeProtocolValue::List(
+                    None,
+                    vec![
+                        count("Dirt", "AtLeast", 1),
+                        count("Iron Ingot", "AtLeast", -2),
+                        count("TNT", "Exactly", 50),
+                    ],
+                ),
+            ),
+            arg(
+                "overworld_biomes",
+                ParsetimeProtocolValue::List(
+                    None,
+                    vec![
+                        sample(400, 60, 800, "not_a_biome"),
+                        sample(400, 5000, 800, "beach"),
+                    ],
+                ),
+            ),
+        ]);
+        let report = check(args).unwrap();
+        assert_eq!(
+            report.errors[..4],
+            [
+                "Version: 1.12.2 is not supported, only 1.16.5 is",
+                "Buried treasure: no buried treasure item is named \"Dirt\"",
+                "Buried treasure: the count bound of Iron Ingot is invalid",
+                "Overworld biomes: no biome of 1.16.5 is named \"not_a_biome\"",
+            ]
+        );
+        let found = |prefix: &str| report.errors[4..].iter().any(|e| e.starts_with(prefix));
+        assert!(found(
+            "overworld biome sample at (400, 5000, 800) is outside of the world"
+        ));
+        assert!(found("End pillars: 10 pillars are marked uncaged"));
+        assert!(found("Buried treasure: item "));
+        assert!(found(
+            "Buried treasure: (40000000, 809) is outside of the world border"
+        ));
+        assert_eq!(report.errors.len(), 8);
+        assert_eq!(report.pillar_seeds, 0);
+
+        // values that aren't evidence are refused
+        assert_eq!(
+            check(vec![arg("treasure_x", string("409"))]).err(),
+            Some("Invalid argument value".to_string())
+        );
+        assert_eq!(
+            check(vec![arg("treasure_x", ParsetimeProtocolValue::I32(409))]).err(),
+            Some("Missing argument".to_string())
+        );
+        assert_eq!(
+            check(vec![arg("seed", ParsetimeProtocolValue::I64(1))]).err(),
+            Some("Invalid argument name".to_string())
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! The `single_chest` protocol type: 3 rows of 9 `chest_slot` values, each `Empty` or
+//! `Stack((item, count, [item_property, ...]))`. Items are written by their resource name, the list of properties can
+//! be left out. The properties are `Damage((damage, max_durability))` and `Enchantment((enchantment, level))`.
+
+use mcsci::{traits::StringOrSlice, v0::ParsetimeProtocolValue};
+use mcseedcracker::loot_table::{
+    ChestRow, InvalidStack, ItemProperty, ItemRegistry, ItemStack, RegisteredItem, SingleChest,
+};
+
+use crate::treasure_counts::as_i32;
+
+/// Why a `single_chest` value was rejected, or a chest couldn't be written as one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidChestValue {
+    WrongArity {
+        what: String,
+        expected: usize,
+        found: usize,
+    },
+    /// Not a value of the type it should be
+    WrongType {
+        what: String,
+        expected: &'static str,
+    },
+    UnknownItem {
+        slot: usize,
+        name: String,
+    },
+    InvalidStack {
+        slot: usize,
+        error: InvalidStack,
+    },
+    /// The chest holds an item the registry can't name
+    UnregisteredItem {
+        slot: usize,
+        item: usize,
+    },
+}
+
+impl std::fmt::Display for InvalidChestValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongArity {
+                what,
+                expected,
+                found,
+            } => write!(f, "{} has {} values instead of {}", what, found, expected),
+            Self::WrongType { what, expected } => write!(f, "{} is not a {}", what, expected),
+            Self::UnknownItem { slot, name } => write!(f, "slot {}: unknown item {:?}", slot, name),
+            Self::InvalidStack { slot, error } => write!(f, "slot {}: {}", slot, error),
+            Self::UnregisteredItem { slot, item } => {
+                write!(f, "slot {}: item {} is not registered", slot, item)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidChestValue {}
+
+pub trait ChestProtocolValue: Sized {
+    fn to_protocol_value(
+        &self,
+        registry: &ItemRegistry,
+    ) -> Result<ParsetimeProtocolValue<'static>, InvalidChestValue>;
+
+    /// `item_resolver` finds the items by the names written in the stacks
+    fn from_protocol_value<'r>(
+        value: &ParsetimeProtocolValue,
+        item_resolver: impl Fn(&str) -> Option<&'r RegisteredItem>,
+    ) -> Result<Self, InvalidChestValue>;
+}
+
+fn enumeration(
+    alias: &'static str,
+    constructor: &'static str,
+    value: Option<ParsetimeProtocolValue<'static>>,
+) -> ParsetimeProtocolValue<'static> {
+    ParsetimeProtocolValue::Enumeration(
+        Some(StringOrSlice::Sl(alias)),
+        StringOrSlice::Sl(constructor),
+        value.map(Box::new),
+    )
+}
+
+fn property_value(property: &ItemProperty) -> ParsetimeProtocolValue<'static> {
+    let (constructor, a, b) = match property {
+        ItemProperty::Damage {
+            max_durability,
+            damage,
+        } => ("Damage", *damage, *max_durability),
+        ItemProperty::Enchantment { enchantment, level } => ("Enchantment", *enchantment, *level),
+    };
+    enumeration(
+        "item_property",
+        constructor,
+        Some(ParsetimeProtocolValue::Tuple(
+            None,
+            vec![
+                ParsetimeProtocolValue::I32(a),
+                ParsetimeProtocolValue::I32(b),
+            ],
+        )),
+    )
+}
+
+/// The elements of a list or tuple of `expected` values
+fn elements<'v, 'a>(
+    value: &'v ParsetimeProtocolValue<'a>,
+    what: impl Fn() -> String,
+    expected: usize,
+) -> Result<&'v [ParsetimeProtocolValue<'a>], InvalidChestValue> {
+    let (ParsetimeProtocolValue::List(_, values) | ParsetimeProtocolValue::Tuple(_, values)) =
+        value
+    else {
+        return Err(InvalidChestValue::WrongType {
+            what: what(),
+            expected: "list",
+        });
+    };
+    if values.len() != expected {
+        return Err(InvalidChestValue::WrongArity {
+            what: what(),
+            expected,
+            found: values.len(),
+        });
+    }
+    Ok(values.as_slice())
+}
+
+fn parse_property(
+    value: &ParsetimeProtocolValue,
+    slot: usize,
+) -> Result<ItemProperty, InvalidChestValue> {
+    let wrong_type = || InvalidChestValue::WrongType {
+        what: format!("a property of slot {}", slot),
+        expected: "item_property",
+    };
+    let ParsetimeProtocolValue::Enumeration(_, constructor, Some(arg)) = value else {
+        return Err(wrong_type());
+    };
+    let fields = elements(arg, || format!("a property of slot {}", slot), 2)?;
+    let (a, b) = (
+        as_i32(&fields[0]).ok_or_else(wrong_type)?,
+        as_i32(&fields[1]).ok_or_else(wrong_type)?,
+    );
+    match constructor.as_slice() {
+        "Damage" => Ok(ItemProperty::Damage {
+            max_durability: b,
+            damage: a,
+        }),
+        "Enchantment" => Ok(ItemProperty::Enchantment {
+            enchantment: a,
+            level: b,
+        }),
+        _ => Err(wrong_type()),
+    }
+}
+
+fn parse_slot<'r>(
+    value: &ParsetimeProtocolValue,
+    slot: usize,
+    item_resolver: &impl Fn(&str) -> Option<&'r RegisteredItem>,
+) -> Result<Option<ItemStack>, InvalidChestValue> {
+    let wrong_type = || InvalidChestValue::WrongType {
+        what: format!("slot {}", slot),
+        expected: "chest_slot",
+    };
+    let stack = match value {
+        ParsetimeProtocolValue::Enumeration(_, constructor, None)
+            if constructor.as_slice() == "Empty" =>
+        {
+            return Ok(None);
+        }
+        ParsetimeProtocolValue::Enumeration(_, constructor, Some(stack))
+            if constructor.as_slice() == "Stack" =>
+        {
+            stack
+        }
+        _ => return Err(wrong_type()),
+    };
+    let ParsetimeProtocolValue::Tuple(_, fields) = stack.as_ref() else {
+        return Err(wrong_type());
+    };
+    let (name, count, properties) = match fields.as_slice() {
+        [name, count] => (name, count, &[][..]),
+        [name, count, ParsetimeProtocolValue::List(_, properties)] => {
+            (name, count, properties.as_slice())
+        }
+        [_, _, _] => return Err(wrong_type()),
+        fields => {
+            return Err(InvalidChestValue::WrongArity {
+                what: format!("the stack of slot {}", slot),
+                expected: 3,
+                found: fields.len(),
+            });
+        }
+    };
+    let (ParsetimeProtocolValue::String(name) | ParsetimeProtocolValue::RawString(name)) = name
+    else {
+        return Err(wrong_type());
+    };
+    let count = as_i32(count).ok_or_else(wrong_type)?;
+
+    let item = item_resolver(name.as_slice()).ok_or_else(|| InvalidChestValue::UnknownItem {
+        slot,
+        name: name.as_slice().to_string(),
+    })?;
+    if count < 1 || count > item.max_count {
+        return Err(InvalidChestValue::InvalidStack {
+            slot,
+            error: InvalidStack::CountOutOfRange {
+                count,
+                max_count: item.max_count,
+            },
+        });
+    }
+    let mut stack = ItemStack::new(item.id, count, item.max_count);
+    for property in properties {
+        stack.properties.push(parse_property(property, slot)?);
+    }
+    Ok(Some(stack))
+}
+
+impl ChestProtocolValue for SingleChest {
+    fn to_protocol_value(
+        &self,
+        registry: &ItemRegistry,
+    ) -> Result<ParsetimeProtocolValue<'static>, InvalidChestValue> {
+        let mut rows = Vec::with_capacity(3);
+        for (r, row) in self.rows.iter().enumerate() {
+            let mut slots = Vec::with_capacity(9);
+            for (i, stack) in row.items.iter().enumerate() {
+                let Some(stack) = stack else {
+                    slots.push(enumeration("chest_slot", "Empty", None));
+                    continue;
+                };
+                let slot = r * 9 + i;
+                let name =
+                    registry
+                        .name_of(stack.item)
+                        .ok_or(InvalidChestValue::UnregisteredItem {
+                            slot,
+                            item: stack.item,
+                        })?;
+                slots.push(enumeration(
+                    "chest_slot",
+                    "Stack",
+                    Some(ParsetimeProtocolValue::Tuple(
+                        None,
+                        vec![
+                            ParsetimeProtocolValue::String(StringOrSlice::St(name.to_string())),
+                            ParsetimeProtocolValue::I32(stack.count),
+                            ParsetimeProtocolValue::List(
+                                None,
+                                stack.properties.iter().map(property_value).collect(),
+                            ),
+                        ],
+                    )),
+                ));
+            }
+            rows.push(ParsetimeProtocolValue::List(None, slots));
+        }
+        Ok(ParsetimeProtocolValue::List(
+            Some(StringOrSlice::Sl("single_chest")),
+            rows,
+        ))
+    }
+
+    fn from_protocol_value<'r>(
+        value: &ParsetimeProtocolValue,
+        item_resolver: impl Fn(&str) -> Option<&'r RegisteredItem>,
+    ) -> Result<Self, InvalidChestValue> {
+        let mut chest = SingleChest::new();
+        let rows = elements(value, || "the chest".to_string(), 3)?;
+        for (r, (row, chest_row)) in rows.iter().zip(chest.rows.iter_mut()).enumerate() {
+            let slots = elements(row, || format!("row {}", r), 9)?;
+            let ChestRow { items } = chest_row;
+            for (i, (slot, stack)) in slots.iter().zip(items.iter_mut()).enumerate() {
+                *stack = parse_slot(slot, r * 9 + i, &item_resolver)?;
+            }
+        }
+        Ok(chest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcsci::{
+        McSeedCrackingProtocol,
+        current_impl::{self, MCSCIProtocol},
+        traits::StringOrSlice,
+        v0::{ParsetimeProtocolValue, ProtocolCommand},
+    };
+    use mcseedcracker::{
+        features::buried_treasure::{
+            item_registry,
+            items::{IRON_INGOT, IRON_SWORD, TNT},
+        },
+        loot_table::{InvalidStack, ItemProperty, ItemStack, SingleChest},
+    };
+
+    use crate::{
+        chest_value::{ChestProtocolValue, InvalidChestValue},
+        emit::setup_problem_command,
+    };
+
+    fn sample_chest() -> SingleChest {
+        let mut chest = SingleChest::new();
+        *chest.get_slot_mut(0).unwrap() = Some(ItemStack::new(IRON_INGOT, 5, 64));
+        *chest.get_slot_mut(13).unwrap() = Some(ItemStack::with_properties(
+            IRON_SWORD,
+            1,
+            1,
+            &[
+                ItemProperty::Damage {
+                    max_durability: 250,
+                    damage: 37,
+                },
+                ItemProperty::Enchantment {
+                    enchantment: 9,
+                    level: 2,
+                },
+            ],
+        ));
+        *chest.get_slot_mut(26).unwrap() = Some(ItemStack::new(TNT, 2, 64));
+        chest
+    }
+
+    /// Parses a chest after writing it as a `setup-problem` argument and parsing the command back
+    fn parse_text(
+        value: ParsetimeProtocolValue<'static>,
+    ) -> Result<SingleChest, InvalidChestValue> {
+        let registry = item_registry();
+        let command =
+            setup_problem_command("check-chest", &[(StringOrSlice::Sl("chest"), value)]).unwrap();
+        let parsed = MCSCIProtocol::default().parse_command(&command).unwrap();
+        let current_impl::ProtocolCommand::V0(ProtocolCommand::SetupProblem { args, .. }) = parsed
+        else {
+            panic!("not a setup-problem command: {}", command);
+        };
+        SingleChest::from_protocol_value(&args[0].1, |name| registry.get(name))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let registry = item_registry();
+        let chest = sample_chest();
+        let value = chest.to_protocol_value(&registry).unwrap();
+
+        let parsed = SingleChest::from_protocol_value(&value, |name| registry.get(name));
+        assert_eq!(parsed, Ok(chest.clone()));
+        assert_eq!(parse_text(value), Ok(chest));
+
+        let empty = SingleChest::new().to_protocol_value(&registry).unwrap();
+        assert_eq!(
+            SingleChest::from_protocol_value(&empty, |name| registry.get(name)),
+            Ok(SingleChest::new())
+        );
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let registry = item_registry();
+        let parse = |value: &ParsetimeProtocolValue| {
+            SingleChest::from_protocol_value(value, |name| registry.get(name))
+        };
+        let ParsetimeProtocolValue::List(alias, rows) =
+            sample_chest().to_protocol_value(&registry).unwrap()
+        else {
+            unreachable!()
+        };
+        let with_row = |r: usize, row: ParsetimeProtocolValue<'static>| {
+            let mut rows = rows.clone();
+            rows[r] = row;
+            ParsetimeProtocolValue::List(alias.clone(), rows)
+        };
+
+        assert_eq!(
+            parse(&ParsetimeProtocolValue::List(None, rows[..2].to_vec())),
+            Err(InvalidChestValue::WrongArity {
+                what: "the chest".to_string(),
+                expected: 3,
+                found: 2
+            })
+        );
+        let ParsetimeProtocolValue::List(_, slots) = &rows[1] else {
+            unreachable!()
+        };
+        assert_eq!(
+            parse(&with_row(
+                1,
+                ParsetimeProtocolValue::List(None, slots[..8].to_vec())
+            ))
+            .unwrap_err()
+            .to_string(),
+            "row 1 has 8 values instead of 9"
+        );
+
+        let mut stacks = slots.clone();
+        let stack = |fields: Vec<ParsetimeProtocolValue<'static>>| {
+            ParsetimeProtocolValue::Enumeration(
+                None,
+                StringOrSlice::Sl("Stack"),
+                Some(Box::new(ParsetimeProtocolValue::Tuple(None, fields))),
+            )
+        };
+        let name = |name: &'static str| ParsetimeProtocolValue::String(StringOrSlice::Sl(name));
+        // the properties can be left out
+        stacks[0] = stack(vec![name("iron_ingot"), ParsetimeProtocolValue::I8(64)]);
+        assert_eq!(
+            parse(&with_row(
+                1,
+                ParsetimeProtocolValue::List(None, stacks.clone())
+            ))
+            .unwrap()
+            .get_slot(9),
+            Some(Some(&ItemStack::new(IRON_INGOT, 64, 64)))
+        );
+        stacks[0] = stack(vec![name("minecraft:stone"), ParsetimeProtocolValue::I8(1)]);
+        assert_eq!(
+            parse(&with_row(
+                1,
+                ParsetimeProtocolValue::List(None, stacks.clone())
+            )),
+            Err(InvalidChestValue::UnknownItem {
+                slot: 9,
+                name: "minecraft:stone".to_string()
+            })
+        );
+        stacks[0] = stack(vec![name("iron_sword"), ParsetimeProtocolValue::I8(2)]);
+        assert_eq!(
+            parse(&with_row(
+                1,
+                ParsetimeProtocolValue::List(None, stacks.clone())
+            )),
+            Err(InvalidChestValue::InvalidStack {
+                slot: 9,
+                error: InvalidStack::CountOutOfRange {
+                    count: 2,
+                    max_count: 1
+                }
+            })
+        );
+        stacks[0] = stack(vec![name("iron_sword")]);
+        assert_eq!(
+            parse(&with_row(
+                1,
+                ParsetimeProtocolValue::List(None, stacks.clone())
+            ))
+            .unwrap_err()
+            .to_string(),
+            "the stack of slot 9 has 1 values instead of 3"
+        );
+        stacks[0] = ParsetimeProtocolValue::I32(0);
+        assert_eq!(
+            parse(&with_row(1, ParsetimeProtocolValue::List(None, stacks)))
+                .unwrap_err()
+                .to_string(),
+            "slot 9 is not a chest_slot"
+        );
+
+        let mut chest = SingleChest::new();
+        *chest.get_slot_mut(4).unwrap() = Some(ItemStack::of(999, 1));
+        assert_eq!(
+            chest.to_protocol_value(&registry).unwrap_err(),
+            InvalidChestValue::UnregisteredItem { slot: 4, item: 999 }
+        );
+    }
+}
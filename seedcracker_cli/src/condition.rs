@@ -0,0 +1,453 @@
+use std::sync::Arc;
+
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::{
+    biome_cache::BiomeCache,
+    cubiomes::enums::MCVersion,
+    expr::EvidenceExpr,
+    features::buried_treasure::{
+        build_fast_inventory_compare_context, get_loot_table, item_registry,
+    },
+    loot_table::{ItemCountConstraints, SingleChest},
+    math::Math,
+    search::{StructureData, WorldExtraData},
+};
+
+use crate::{
+    check_evidence::{biome_named, parse_sample},
+    chest_value::ChestProtocolValue,
+    treasure_counts::{as_i32, parse_constraint},
+};
+
+const CHECK_CONDITION_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("seed"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i64"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("condition"),
+        optional: false,
+        argtype: StringOrSlice::Sl("evidence_expr"),
+    },
+];
+
+/// The block position and luck of a buried treasure leaf
+fn parse_treasure_pos(
+    x: &ParsetimeProtocolValue,
+    z: &ParsetimeProtocolValue,
+    luck: &ParsetimeProtocolValue,
+) -> Result<((i32, i32), f32), String> {
+    let (Some(x), Some(z)) = (as_i32(x), as_i32(z)) else {
+        return Err("Invalid buried treasure position".to_string());
+    };
+    let luck = match luck {
+        ParsetimeProtocolValue::F32(v) => *v,
+        ParsetimeProtocolValue::F64(v) => *v as f32,
+        _ => return Err("Invalid buried treasure luck".to_string()),
+    };
+    let chunk =
+        Math::checked_block_coords_to_chunk_coords((x, z)).map_err(|err| err.to_string())?;
+    Ok((chunk, luck))
+}
+
+fn parse_leaf(ctor: &str, arg: &ParsetimeProtocolValue) -> Result<EvidenceExpr, String> {
+    match (ctor, arg) {
+        ("OverworldBiome" | "NetherBiome", sample) => {
+            let (x, y, z, name) =
+                parse_sample(sample).ok_or_else(|| "Invalid biome sample".to_string())?;
+            let biome = biome_named(name, MCVersion::MC_1_16_5)
+                .ok_or_else(|| format!("No biome of 1.16.5 is named {:?}", name))?;
+            let samples = vec![(x, y, z, biome)];
+            Ok(EvidenceExpr::world(if ctor == "OverworldBiome" {
+                WorldExtraData::OverworldBiomeData(samples)
+            } else {
+                WorldExtraData::NetherBiomeData(samples)
+            }))
+        }
+        ("BuriedTreasure", ParsetimeProtocolValue::Tuple(_, fields)) => {
+            let [x, z, luck, chest] = fields.as_slice() else {
+                return Err("Invalid buried treasure".to_string());
+            };
+            let (chunk, luck) = parse_treasure_pos(x, z, luck)?;
+            let registry = item_registry();
+            let chest = SingleChest::from_protocol_value(chest, |name| registry.get(name))
+                .map_err(|err| format!("Invalid chest: {}", err))?;
+            let contents = build_fast_inventory_compare_context(chest, &get_loot_table())
+                .map_err(|err| format!("Invalid chest: {}", err))?;
+            Ok(EvidenceExpr::structure(
+                StructureData::BuriedTreasureContents {
+                    chunk_x: chunk.0,
+                    chunk_z: chunk.1,
+                    luck,
+                    contents,
+                    loot_table: None,
+                },
+            ))
+        }
+        ("BuriedTreasureCounts", ParsetimeProtocolValue::Tuple(_, fields)) => {
+            let [x, z, luck, ParsetimeProtocolValue::List(_, counts)] = fields.as_slice() else {
+                return Err("Invalid buried treasure counts".to_string());
+            };
+            let (chunk, luck) = parse_treasure_pos(x, z, luck)?;
+            let counts = counts
+                .iter()
+                .map(parse_constraint)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| "Invalid item count".to_string())?;
+            let counts = ItemCountConstraints::new(counts);
+            if !counts.unsatisfiable_bounds(&get_loot_table()).is_empty() {
+                return Err("Impossible item count".to_string());
+            }
+            Ok(EvidenceExpr::structure(
+                StructureData::BuriedTreasureCounts {
+                    chunk_x: chunk.0,
+                    chunk_z: chunk.1,
+                    luck,
+                    counts,
+                    loot_table: None,
+                },
+            ))
+        }
+        _ => Err(format!("Invalid {} condition", ctor)),
+    }
+}
+
+/// An `evidence_expr` value, checked against the restrictions of [`EvidenceExpr::validate`]
+pub fn parse_condition(value: &ParsetimeProtocolValue) -> Result<EvidenceExpr, String> {
+    let ParsetimeProtocolValue::Enumeration(_, ctor, Some(arg)) = value else {
+        return Err("Invalid condition".to_string());
+    };
+    let expr = match (ctor.as_slice(), arg.as_ref()) {
+        ("All" | "Any", ParsetimeProtocolValue::List(_, values)) => {
+            let exprs = values
+                .iter()
+                .map(parse_condition)
+                .collect::<Result<Vec<_>, _>>()?;
+            if ctor.as_slice() == "All" {
+                EvidenceExpr::All(exprs)
+            } else {
+                EvidenceExpr::Any(exprs)
+            }
+        }
+        ("Not", value) => EvidenceExpr::Not(Box::new(parse_condition(value)?)),
+        (ctor, arg) => parse_leaf(ctor, arg)?,
+    };
+    expr.validate().map_err(|err| err.to_string())?;
+    Ok(expr)
+}
+
+/// The checks of a server session share their biome lookups, checking a seed again after editing the condition
+/// only looks up the new samples
+#[derive(Debug, Clone, Default)]
+pub struct CheckConditionProblem {
+    biome_cache: Arc<BiomeCache>,
+}
+
+impl CheckConditionProblem {
+    pub fn new(biome_cache: Arc<BiomeCache>) -> Self {
+        Self { biome_cache }
+    }
+}
+
+impl SimpleV0Problem for CheckConditionProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("check-condition")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Checks whether a world seed satisfies a condition combining evidence with All, Any and Not. Not can only negate biome samples. The result is a bool.",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(CHECK_CONDITION_ARGS)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        let invalid_value =
+            || ParsetimeProtocolValue::String(StringOrSlice::Sl("Invalid argument value"));
+        let error = |message: String| ParsetimeProtocolValue::String(StringOrSlice::St(message));
+        let (mut seed, mut condition) = (None, None);
+        for (name, value) in args.as_slice().iter() {
+            match (name.as_slice(), value) {
+                ("seed", ParsetimeProtocolValue::I64(v)) => seed = Some(*v),
+                ("condition", v) => condition = Some(parse_condition(v).map_err(error)?),
+                ("seed", _) => return Err(invalid_value()),
+                _ => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument name",
+                    )));
+                }
+            }
+        }
+        let (Some(seed), Some(condition)) = (seed, condition) else {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Missing argument",
+            )));
+        };
+        if let Some(invalid) = condition.invalid_samples(MCVersion::MC_1_16_5).first() {
+            return Err(error(invalid.to_string()));
+        }
+
+        Ok(Box::new(CheckConditionComputation {
+            seed,
+            condition,
+            biome_cache: Arc::clone(&self.biome_cache),
+            matches: None,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct CheckConditionComputation {
+    seed: i64,
+    condition: EvidenceExpr,
+    biome_cache: Arc<BiomeCache>,
+    matches: Option<bool>,
+}
+
+impl SimpleV0ProblemBruteCalculation for CheckConditionComputation {
+    /// A biome that can't be looked up fails its check, like in the searches
+    fn go(&mut self) {
+        self.matches = Some(
+            self.condition.check_world_seed_cached(
+                self.seed,
+                MCVersion::MC_1_16_5,
+                &self.biome_cache,
+            ) == Ok(true),
+        );
+    }
+
+    fn is_done(&self) -> bool {
+        self.matches.is_some()
+    }
+
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    fn is_failed(&self) -> bool {
+        false
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        (self.matches.is_some() as u64, 1)
+    }
+
+    fn stop(&mut self) {}
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        self.matches.map(ParsetimeProtocolValue::Bool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcsci::{
+        current_impl::MCSCIProtocol,
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{ParsetimeProtocolValue, SimpleV0Problem},
+    };
+    use mcseedcracker::{
+        expr::EvidenceExpr,
+        features::buried_treasure::item_registry,
+        self_test::{self, BURIED_TREASURE_POS, WORLD_SEED},
+    };
+
+    use crate::{
+        Extension,
+        chest_value::ChestProtocolValue,
+        condition::{CheckConditionProblem, parse_condition},
+        emit::setup_problem_command,
+    };
+
+    fn node(
+        ctor: &'static str,
+        arg: ParsetimeProtocolValue<'static>,
+    ) -> ParsetimeProtocolValue<'static> {
+        ParsetimeProtocolValue::Enumeration(
+            Some(StringOrSlice::Sl("evidence_expr")),
+            StringOrSlice::Sl(ctor),
+            Some(Box::new(arg)),
+        )
+    }
+
+    fn list(
+        ctor: &'static str,
+        exprs: Vec<ParsetimeProtocolValue<'static>>,
+    ) -> ParsetimeProtocolValue<'static> {
+        node(ctor, ParsetimeProtocolValue::List(None, exprs))
+    }
+
+    fn biome(x: i32, y: i32, z: i32, name: &'static str) -> ParsetimeProtocolValue<'static> {
+        node(
+            "OverworldBiome",
+            ParsetimeProtocolValue::Tuple(
+                None,
+                vec![
+                    ParsetimeProtocolValue::I32(x),
+                    ParsetimeProtocolValue::I32(y),
+                    ParsetimeProtocolValue::I32(z),
+                    ParsetimeProtocolValue::String(StringOrSlice::Sl(name)),
+                ],
+            ),
+        )
+    }
+
+    fn treasure() -> ParsetimeProtocolValue<'static> {
+        node(
+            "BuriedTreasure",
+            ParsetimeProtocolValue::Tuple(
+                None,
+                vec![
+                    ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.0),
+                    ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.1),
+                    ParsetimeProtocolValue::F32(0.0),
+                    self_test::buried_treasure_contents()
+                        .to_protocol_value(&item_registry())
+                        .unwrap(),
+                ],
+            ),
+        )
+    }
+
+    fn check(condition: ParsetimeProtocolValue<'static>) -> Result<bool, String> {
+        check_with(&CheckConditionProblem::default(), condition)
+    }
+
+    fn check_with(
+        problem: &CheckConditionProblem,
+        condition: ParsetimeProtocolValue<'static>,
+    ) -> Result<bool, String> {
+        let args = vec![
+            (
+                StringOrSlice::Sl("seed"),
+                ParsetimeProtocolValue::I64(WORLD_SEED),
+            ),
+            (StringOrSlice::Sl("condition"), condition),
+        ];
+        let mut computation = problem
+            .setup(VecOrSlice::V(args))
+            .map_err(|err| match err {
+                ParsetimeProtocolValue::String(s) => s.as_slice().to_string(),
+                _ => "not a string".to_string(),
+            })?;
+        computation.go();
+        match computation.result() {
+            Some(ParsetimeProtocolValue::Bool(matches)) => Ok(matches),
+            _ => Err("no result".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_nested_condition() {
+        let value = list(
+            "Any",
+            vec![
+                biome(400, 60, 800, "jungle"),
+                list(
+                    "All",
+                    vec![biome(137, 73, -90, "minecraft:jungle"), treasure()],
+                ),
+            ],
+        );
+        let EvidenceExpr::Any(exprs) = parse_condition(&value).unwrap() else {
+            panic!("not an Any");
+        };
+        assert!(
+            matches!(exprs.as_slice(), [EvidenceExpr::Leaf(_), EvidenceExpr::All(all)] if all.len() == 2)
+        );
+        assert_eq!(check(value), Ok(true));
+
+        let not_beach = node("Not", biome(400, 60, 800, "beach"));
+        assert_eq!(
+            check(list("All", vec![treasure(), not_beach.clone()])),
+            Ok(false)
+        );
+        assert_eq!(check(list("Any", vec![treasure(), not_beach])), Ok(true));
+
+        assert_eq!(
+            parse_condition(&biome(0, 60, 0, "nowhere")).unwrap_err(),
+            "No biome of 1.16.5 is named \"nowhere\""
+        );
+        assert_eq!(
+            check(biome(0, 1000, 0, "plains")).unwrap_err(),
+            "overworld biome sample at (0, 1000, 0) is outside of the world"
+        );
+    }
+
+    #[test]
+    fn test_checks_share_the_biome_cache() {
+        let problem = CheckConditionProblem::default();
+        let jungle = biome(137, 73, -90, "jungle");
+        let beach = biome(400, 60, 800, "beach");
+
+        assert_eq!(
+            check_with(&problem, list("All", vec![jungle.clone(), beach.clone()])),
+            Ok(true)
+        );
+        assert_eq!(
+            check_with(&problem, list("All", vec![jungle, node("Not", beach)])),
+            Ok(false)
+        );
+        let stats = problem.biome_cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+    }
+
+    #[test]
+    fn test_not_restriction_errors() {
+        assert_eq!(
+            check(node("Not", treasure())).unwrap_err(),
+            "Not can only negate biome samples, not a buried treasure"
+        );
+        let nested = node("Not", list("Any", vec![biome(0, 60, 0, "plains")]));
+        assert_eq!(
+            check(list("All", vec![nested])).unwrap_err(),
+            "Not can only negate biome samples, not All, Any or Not"
+        );
+    }
+
+    #[test]
+    fn test_protocol_check_condition() {
+        let condition = list(
+            "Any",
+            vec![
+                node("Not", biome(400, 60, 800, "beach")),
+                list("All", vec![biome(137, 73, -90, "jungle"), treasure()]),
+            ],
+        );
+        let command = setup_problem_command(
+            "check-condition",
+            &[
+                (
+                    StringOrSlice::Sl("seed"),
+                    ParsetimeProtocolValue::I64(WORLD_SEED),
+                ),
+                (StringOrSlice::Sl("condition"), condition),
+            ],
+        )
+        .unwrap();
+        let input = format!("hello\n{}\ngo\n", command);
+
+        let mut protocol = MCSCIProtocol::default();
+        protocol.register_extension(Extension::new());
+        let mut output = Vec::new();
+        protocol
+            .server_loop(&mut input.as_bytes(), &mut output, &mut std::io::sink())
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("setup-ok\n"), "{}", output);
+        assert!(output.ends_with("result true\n"), "{}", output);
+    }
+}
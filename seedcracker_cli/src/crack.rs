@@ -0,0 +1,371 @@
+use std::{
+    io::Write,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use clap::Args;
+use mcseedcracker::{
+    calibration::Calibration,
+    candidates::WorldSeedCandidate,
+    cooperative::SearchOptions,
+    export::{SeedExportOptions, SeedNumberFormat, write_json_lines, write_world_seeds},
+    features::end_pillars::{PartialEndPillar, PillarHeightHint},
+    heartbeat::{Heartbeat, STALL_THRESHOLD},
+    pipeline::{CrackingEvidence, CrackingPipeline, ObservedAt, PipelineOptions},
+    schedule::{EarlyTermination, PillarSeedOutcome},
+    search::{SearchOutcome, StructureSeedSearch},
+    utils::format::{format_count, format_duration},
+    world_seed::SeedOrigin,
+};
+
+use crate::{emit, loot_override::LootTableOverrides};
+
+#[derive(Args)]
+pub struct CrackArgs {
+    #[clap(
+        long = "pillar",
+        value_name = "INDEX=HEIGHT[,caged|,uncaged]",
+        value_parser = parse_pillar_observation,
+        help = "A pillar observation. HEIGHT is an exact height, a range (76-85), small, medium, big, medium-small, medium-big or unknown"
+    )]
+    pillars: Vec<(usize, PartialEndPillar)>,
+    #[clap(
+        long,
+        value_name = "WHEN",
+        help = "When the pillars were observed, e.g. \"day 3\""
+    )]
+    pillars_observed_at: Option<String>,
+    #[clap(
+        long,
+        help = "The pillars might have been modified before they were observed, e.g. cages broken by a previous visit"
+    )]
+    pillars_modified: bool,
+    #[clap(
+        long,
+        default_value_t = 5,
+        help = "Maximum number of pillar seeds to search structure seeds for"
+    )]
+    max_pillar_seeds: usize,
+    #[clap(
+        long,
+        default_value_t = 5,
+        help = "Maximum number of structure seeds to search world seeds for"
+    )]
+    max_structure_seeds: usize,
+    #[clap(
+        long,
+        default_value_t = 5,
+        help = "Maximum number of world seeds per structure seed"
+    )]
+    max_world_seeds: u16,
+    #[clap(
+        long,
+        value_name = "never|exact|N",
+        default_value_t = EarlyTermination::Never,
+        value_parser = parse_early_termination,
+        help = "Stop searching the remaining pillar seeds after a structure seed satisfying every piece of evidence exactly, or after N structure seeds"
+    )]
+    early_termination: EarlyTermination,
+    #[clap(
+        long,
+        help = "The world seed was chosen by the player, not randomly generated"
+    )]
+    custom_world_seed: bool,
+    #[clap(
+        long,
+        value_name = "any|nextlong|hashcode|number",
+        value_parser = parse_seed_origin,
+        help = "Only search the world seeds chosen this way: left blank (nextlong), a text (hashcode) or a number. Much faster than searching all 65536 upper bits"
+    )]
+    seed_origin: Option<SeedOrigin>,
+    #[clap(
+        long,
+        conflicts_with = "custom_world_seed",
+        help = "Drop the structure seeds no generated world seed has, before the world seed search. Loses the world seeds that were typed as a number"
+    )]
+    nextlong_only: bool,
+    #[clap(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Search on this many threads instead of one per core"
+    )]
+    threads: Option<u32>,
+    #[clap(
+        long,
+        help = "Print the world seeds as JSON records with their structure seed, origin and score, one per line"
+    )]
+    json: bool,
+    #[clap(long, help = "Print the search plan and exit without searching")]
+    dry_run: bool,
+    #[clap(
+        long,
+        help = "Print the protocol commands that run this search on the server and exit without searching"
+    )]
+    emit_commands: bool,
+    #[clap(
+        long,
+        help = "Print the progress of each search thread every few seconds, to find a stuck one"
+    )]
+    debug: bool,
+}
+
+/// Time between two reports of the thread progress with `--debug`
+const DEBUG_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Waits for a search to finish, warning when it stops making progress
+fn watch_search(heartbeat: &Heartbeat, is_done: impl Fn() -> bool, debug: bool) {
+    let mut stalled = false;
+    let mut last_report = Instant::now();
+    while !is_done() {
+        std::thread::sleep(Duration::from_millis(100));
+        if heartbeat.is_stalled(STALL_THRESHOLD) != stalled {
+            stalled = !stalled;
+            if stalled {
+                eprintln!(
+                    "warn:  No progress for {}, the search may be stuck",
+                    format_duration(heartbeat.since_last_progress())
+                );
+            } else {
+                eprintln!("info:  The search is making progress again");
+            }
+        }
+        if debug && last_report.elapsed() >= DEBUG_REPORT_INTERVAL {
+            last_report = Instant::now();
+            eprintln!("debug: Thread progress: {}", heartbeat.thread_report());
+        }
+    }
+}
+
+pub fn parse_early_termination(s: &str) -> Result<EarlyTermination, String> {
+    EarlyTermination::from_name(s).ok_or_else(|| {
+        format!(
+            "invalid early termination policy '{}', expected never, exact or a number of structure seeds",
+            s
+        )
+    })
+}
+
+pub fn parse_seed_origin(s: &str) -> Result<SeedOrigin, String> {
+    SeedOrigin::from_name(s).ok_or_else(|| {
+        format!(
+            "invalid seed origin '{}', expected any, nextlong, hashcode or number",
+            s
+        )
+    })
+}
+
+fn parse_pillar_observation(s: &str) -> Result<(usize, PartialEndPillar), String> {
+    let (index, rest) = s
+        .split_once('=')
+        .ok_or_else(|| "expected INDEX=HEIGHT".to_string())?;
+    let index = match index.trim().parse::<usize>() {
+        Ok(i) if i < 10 => i,
+        _ => return Err(format!("invalid pillar index '{}', expected 0-9", index)),
+    };
+
+    let (height, caged) = match rest.split_once(',') {
+        Some((height, "caged")) => (height, Some(true)),
+        Some((height, "uncaged")) => (height, Some(false)),
+        Some((_, cage)) => return Err(format!("invalid cage status '{}'", cage)),
+        None => (rest, None),
+    };
+
+    let parse_height = |h: &str| match h.trim().parse::<i32>() {
+        Ok(h) if (76..=103).contains(&h) && (h - 76) % 3 == 0 => Ok(h),
+        _ => Err(format!("invalid pillar height '{}'", h)),
+    };
+
+    let height = match height.trim() {
+        "unknown" => PillarHeightHint::Unknown,
+        "small" => PillarHeightHint::Small,
+        "medium" => PillarHeightHint::Medium,
+        "big" => PillarHeightHint::Big,
+        "medium-small" => PillarHeightHint::MediumSmall,
+        "medium-big" => PillarHeightHint::MediumBig,
+        h => match h.split_once('-') {
+            Some((min, max)) => PillarHeightHint::Range(parse_height(min)?, parse_height(max)?),
+            None => PillarHeightHint::Exact(parse_height(h)?),
+        },
+    };
+
+    Ok((index, PartialEndPillar::new(caged, height)))
+}
+
+pub fn run(args: CrackArgs, overrides: LootTableOverrides) -> Result<(), std::io::Error> {
+    let pillars_observed_at =
+        (args.pillars_observed_at.is_some() || args.pillars_modified).then(|| {
+            ObservedAt::new(
+                args.pillars_observed_at.clone().unwrap_or_default(),
+                args.pillars_modified,
+            )
+        });
+    let mut evidence = CrackingEvidence {
+        pillars_observed_at,
+        buried_treasure_loot_table: overrides.buried_treasure,
+        ..Default::default()
+    };
+    for (index, pillar) in args.pillars.iter() {
+        evidence.pillars.0[*index] = *pillar;
+    }
+
+    let options = PipelineOptions {
+        max_pillar_seeds: args.max_pillar_seeds,
+        max_structure_seeds: args.max_structure_seeds,
+        max_world_seeds_per_structure_seed: args.max_world_seeds,
+        is_random_world_seed: !args.custom_world_seed,
+        origin: args.seed_origin.unwrap_or_default(),
+        early_termination: args.early_termination,
+        nextlong_only: args.nextlong_only,
+        search: SearchOptions {
+            threads: args.threads.map(|threads| threads as usize),
+            ..SearchOptions::DEFAULT
+        },
+        ..Default::default()
+    };
+
+    if args.emit_commands {
+        let script = emit::protocol_commands(&evidence)?;
+        if !script.skipped.is_empty() {
+            eprintln!(
+                "warn:  The server can't take {} yet",
+                script.skipped.join(", ")
+            );
+        }
+        print!("{}", script.to_text());
+        return Ok(());
+    }
+
+    let plan = CrackingPipeline::plan(&evidence, &options);
+    let estimate =
+        Calibration::load_stored(options.search.thread_count()).map(|c| c.estimate(&plan));
+    if args.dry_run {
+        print!("{}", plan);
+        if let Some(estimate) = estimate {
+            println!("Estimated time: up to {}", format_duration(estimate));
+        }
+        return Ok(());
+    }
+    eprint!("{}", plan);
+    match estimate {
+        Some(estimate) => eprintln!("Estimated time: up to {}", format_duration(estimate)),
+        None => match args.threads {
+            Some(threads) => eprintln!(
+                "info:  Run 'seedcracker --calibrate --threads {}' to get search time estimates",
+                threads
+            ),
+            None => eprintln!("info:  Run 'seedcracker --calibrate' to get search time estimates"),
+        },
+    }
+    if plan.has_invalid_evidence() {
+        return Err(std::io::Error::other(
+            "some evidence can't be checked, fix it before searching",
+        ));
+    }
+
+    let panicked = || std::io::Error::other("search thread panicked");
+    let join_error = |_| panicked();
+
+    let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+    let jobs = CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds);
+    let mut schedule = CrackingPipeline::structure_schedule(&options, &jobs);
+    // one slice per job, handed out in order
+    let mut jobs = jobs.into_iter();
+    let mut structure_seeds = Vec::new();
+    while let Some((slice, job)) = schedule.next_slice().zip(jobs.next()) {
+        eprintln!(
+            "info:  Searching structure seeds of pillar seed {}",
+            job.pillar_seed
+        );
+        let handle = StructureSeedSearch::start(job);
+        watch_search(handle.heartbeat(), || handle.is_done(), args.debug);
+        eprintln!("info:    Rejected: {}", handle.rejections());
+        let found = match handle.join() {
+            SearchOutcome::Failed => return Err(panicked()),
+            outcome => outcome.seeds().to_vec(),
+        };
+        let strengths = found
+            .iter()
+            .map(|&seed| CrackingPipeline::match_strength(&evidence, &options, seed))
+            .collect::<Vec<_>>();
+        schedule.record(&slice, &strengths);
+        eprintln!(
+            "info:    Found {} structure seed(s)",
+            format_count(found.len() as u64)
+        );
+        structure_seeds.extend(found);
+    }
+    for stats in schedule.stats() {
+        if let PillarSeedOutcome::Skipped(policy) = stats.outcome {
+            eprintln!(
+                "info:  Skipped pillar seed {} (early termination: {})",
+                stats.pillar_seed, policy
+            );
+        }
+    }
+
+    let reverification =
+        CrackingPipeline::reverify(&evidence, &options, &structure_seeds, |checked| {
+            if args.debug {
+                eprintln!(
+                    "debug: Re-verified {}/{} structure seed(s)",
+                    checked,
+                    structure_seeds.len()
+                );
+            }
+        });
+    for (seed, reason) in reverification.eliminated.iter() {
+        eprintln!(
+            "info:  Structure seed {} contradicts the evidence: {}",
+            seed, reason
+        );
+    }
+    if !reverification.eliminated.is_empty() {
+        eprintln!("info:  {}", reverification);
+    }
+
+    let mut world_seeds = Vec::new();
+    for mut job in CrackingPipeline::world_jobs(&evidence, &options, &reverification.kept) {
+        eprintln!(
+            "info:  Searching world seeds of structure seed {}",
+            job.structure_seed
+        );
+        job.order_constraints();
+        for (i, check) in job.verification_order().iter().enumerate() {
+            eprintln!("info:    Check {}: {}", i + 1, check);
+        }
+        let handle = job.spawn_multithreaded();
+        let searcher = Arc::clone(&handle.searcher);
+        watch_search(handle.heartbeat(), || handle.is_done(), args.debug);
+        let seeds = handle.join().map_err(join_error)?;
+        eprintln!(
+            "info:    Found {} world seed(s)",
+            format_count(seeds.len() as u64)
+        );
+        eprintln!("info:    Rejected: {}", searcher.rejections());
+        world_seeds.extend(searcher.candidates(&seeds));
+        if let Some(error) = searcher.first_error() {
+            eprintln!(
+                "warn:  {} candidates skipped due to errors, first error: {}",
+                searcher.skipped_candidates(),
+                error
+            );
+        }
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    if args.json {
+        write_json_lines(
+            &mut stdout,
+            world_seeds.iter().map(WorldSeedCandidate::to_json),
+        )?;
+    } else {
+        write_world_seeds(
+            &mut stdout,
+            world_seeds.iter().map(|candidate| (candidate.seed, None)),
+            &SeedExportOptions::new(false, SeedNumberFormat::Decimal),
+        )?;
+    }
+    stdout.flush()
+}
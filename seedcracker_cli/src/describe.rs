@@ -0,0 +1,180 @@
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::{
+    analysis::{DescribeRequest, WorldDescriber, WorldReport},
+    cubiomes::enums::MCVersion,
+};
+
+pub const DEFAULT_RADIUS: i32 = 512;
+
+const DESCRIBE_WORLD_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("seed"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i64"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("x"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("z"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("radius"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct DescribeWorldProblem;
+
+impl SimpleV0Problem for DescribeWorldProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("describe-world")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Lists the evidence that can be collected around (x, z) in a world seed, to confirm a crack. The result is a tuple of the features as (kind, x, z, distance), the biome samples as (biome, count) and the end pillars as (height, caged).",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(DESCRIBE_WORLD_ARGS)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        let (mut seed, mut x, mut z, mut radius) = (None, None, None, DEFAULT_RADIUS);
+        for (name, value) in args.as_slice().iter() {
+            match (name.as_slice(), value) {
+                ("seed", ParsetimeProtocolValue::I64(v)) => seed = Some(*v),
+                ("x", ParsetimeProtocolValue::I32(v)) => x = Some(*v),
+                ("z", ParsetimeProtocolValue::I32(v)) => z = Some(*v),
+                ("radius", ParsetimeProtocolValue::I32(v)) => radius = *v,
+                ("seed" | "x" | "z" | "radius", _) => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument value",
+                    )));
+                }
+                _ => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument name",
+                    )));
+                }
+            }
+        }
+        let (Some(seed), Some(x), Some(z)) = (seed, x, z) else {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Missing argument",
+            )));
+        };
+
+        Ok(Box::new(DescribeWorldComputation {
+            describer: WorldDescriber::new(DescribeRequest::new(
+                seed,
+                (x, z),
+                radius,
+                MCVersion::MC_1_16_5,
+            )),
+            report: None,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct DescribeWorldComputation {
+    describer: WorldDescriber,
+    report: Option<WorldReport>,
+}
+
+impl SimpleV0ProblemBruteCalculation for DescribeWorldComputation {
+    fn go(&mut self) {
+        self.report = self.describer.run();
+    }
+
+    fn is_done(&self) -> bool {
+        self.describer.is_done()
+    }
+
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    fn is_failed(&self) -> bool {
+        false
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        (self.describer.get_progress(), self.describer.total_steps())
+    }
+
+    fn stop(&mut self) {
+        self.describer.stop();
+    }
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        let report = self.report.as_ref()?;
+        let features = report
+            .features
+            .iter()
+            .map(|f| {
+                ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![
+                        ParsetimeProtocolValue::String(StringOrSlice::Sl(f.kind.name())),
+                        ParsetimeProtocolValue::I32(f.block_x),
+                        ParsetimeProtocolValue::I32(f.block_z),
+                        ParsetimeProtocolValue::F64(f.distance),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+        let biomes = report
+            .biomes
+            .iter()
+            .map(|(biome, count)| {
+                ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![
+                        ParsetimeProtocolValue::String(StringOrSlice::St(format!("{:?}", biome))),
+                        ParsetimeProtocolValue::U32(*count),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+        let pillars = report
+            .pillars
+            .iter()
+            .map(|(height, caged)| {
+                ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![
+                        ParsetimeProtocolValue::I32(*height),
+                        ParsetimeProtocolValue::Bool(*caged),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(ParsetimeProtocolValue::Tuple(
+            None,
+            vec![
+                ParsetimeProtocolValue::List(None, features),
+                ParsetimeProtocolValue::List(None, biomes),
+                ParsetimeProtocolValue::List(None, pillars),
+            ],
+        ))
+    }
+}
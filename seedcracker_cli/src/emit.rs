@@ -0,0 +1,310 @@
+//! The evidence as protocol commands, to hand a search started in the TUI over to a headless server. The values are
+//! written by [`v0_format_value`], like the server's own responses, so the server can parse them back.
+
+use mcsci::{
+    traits::StringOrSlice,
+    v0::{ParsetimeProtocolValue, v0_format_string, v0_format_value},
+};
+use mcseedcracker::{
+    features::{
+        buried_treasure::item_registry,
+        end_pillars::{PartialEndPillars, PillarHeightHint},
+    },
+    pipeline::{BuriedTreasureEvidence, CrackingEvidence},
+    search::WorldExtraData,
+};
+
+use crate::chest_value::ChestProtocolValue;
+
+/// Index of the mcseedcracker extension on the server
+pub const EXTENSION_INDEX: u32 = 0;
+
+/// Commands to paste into the server
+pub struct ProtocolScript {
+    /// One command per line
+    pub commands: Vec<String>,
+    /// The evidence no problem of the server takes yet
+    pub skipped: Vec<&'static str>,
+}
+
+impl ProtocolScript {
+    /// The commands, one per line
+    pub fn to_text(&self) -> String {
+        let mut text = self.commands.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+fn pillar_height(height: i32) -> ParsetimeProtocolValue<'static> {
+    ParsetimeProtocolValue::Enumeration(
+        Some(StringOrSlice::Sl("pillar_height")),
+        StringOrSlice::St(format!("h{}", height)),
+        None,
+    )
+}
+
+/// The `pillar-seed-cracker` arguments of the known pillars
+pub fn pillar_args(
+    pillars: &PartialEndPillars,
+) -> Vec<(StringOrSlice<'static>, ParsetimeProtocolValue<'static>)> {
+    let hint = |constructor: &'static str, value: Option<ParsetimeProtocolValue<'static>>| {
+        ParsetimeProtocolValue::Enumeration(
+            Some(StringOrSlice::Sl("pillar_height_hint")),
+            StringOrSlice::Sl(constructor),
+            value.map(Box::new),
+        )
+    };
+
+    let mut args = Vec::new();
+    for (i, pillar) in pillars.0.iter().enumerate() {
+        let height = match pillar.height {
+            PillarHeightHint::Unknown => None,
+            PillarHeightHint::Small => Some(hint("Small", None)),
+            PillarHeightHint::Medium => Some(hint("Medium", None)),
+            PillarHeightHint::Big => Some(hint("Big", None)),
+            PillarHeightHint::MediumSmall => Some(hint("MediumSmall", None)),
+            PillarHeightHint::MediumBig => Some(hint("MediumBig", None)),
+            PillarHeightHint::Exact(h) => Some(hint("Exact", Some(pillar_height(h)))),
+            PillarHeightHint::Range(min, max) => Some(hint(
+                "Range",
+                Some(ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![pillar_height(min), pillar_height(max)],
+                )),
+            )),
+        };
+        if let Some(height) = height {
+            args.push((StringOrSlice::St(format!("pillar{}height", i)), height));
+        }
+        if let Some(caged) = pillar.caged {
+            args.push((
+                StringOrSlice::St(format!("pillar{}caged", i)),
+                ParsetimeProtocolValue::Enumeration(
+                    Some(StringOrSlice::Sl("pillar_caged_status")),
+                    StringOrSlice::Sl(if caged { "Caged" } else { "Uncaged" }),
+                    None,
+                ),
+            ));
+        }
+    }
+    args
+}
+
+/// The `buried-treasure-cracker` arguments of a buried treasure, searched for the pillar seeds of `pillars`. None if
+/// the server can't take it: its chunk isn't known or the chest holds items of another loot table.
+pub fn treasure_args(
+    treasure: &BuriedTreasureEvidence,
+    pillars: &PartialEndPillars,
+) -> Option<Vec<(StringOrSlice<'static>, ParsetimeProtocolValue<'static>)>> {
+    if treasure.area_corner.is_some() {
+        return None;
+    }
+    let chest = treasure.contents.to_protocol_value(&item_registry()).ok()?;
+    let mut args = vec![
+        (
+            StringOrSlice::Sl("x"),
+            ParsetimeProtocolValue::I32(treasure.pos_x),
+        ),
+        (
+            StringOrSlice::Sl("z"),
+            ParsetimeProtocolValue::I32(treasure.pos_z),
+        ),
+        (
+            StringOrSlice::Sl("luck"),
+            ParsetimeProtocolValue::F32(treasure.luck),
+        ),
+        (StringOrSlice::Sl("chest"), chest),
+    ];
+    args.extend(pillar_args(pillars));
+    Some(args)
+}
+
+/// A `setup-problem` command for the mcseedcracker extension
+pub fn setup_problem_command(
+    name: &str,
+    args: &[(StringOrSlice, ParsetimeProtocolValue)],
+) -> std::io::Result<String> {
+    let mut line = format!("setup-problem {} ", EXTENSION_INDEX).into_bytes();
+    v0_format_string(&mut line, name)?;
+    for (name, value) in args.iter() {
+        line.push(b' ');
+        v0_format_string(&mut line, name.as_slice())?;
+        line.push(b'=');
+        v0_format_value(&mut line, value)?;
+    }
+    String::from_utf8(line).map_err(std::io::Error::other)
+}
+
+/// The commands that run the search of the evidence the server can take, and what it can't
+pub fn protocol_commands(evidence: &CrackingEvidence) -> std::io::Result<ProtocolScript> {
+    let mut commands = vec![
+        "hello".to_string(),
+        setup_problem_command("pillar-seed-cracker", &pillar_args(&evidence.pillars))?,
+        "go".to_string(),
+    ];
+    let mut skipped = Vec::new();
+    // the server takes a single buried treasure
+    let mut treasures = evidence.buried_treasures.iter();
+    if let Some(treasure) = treasures.next() {
+        let args = treasure_args(treasure, &evidence.pillars)
+            .filter(|_| evidence.buried_treasure_loot_table.is_none());
+        match args {
+            Some(args) => {
+                commands.push(setup_problem_command("buried-treasure-cracker", &args)?);
+                commands.push("go".to_string());
+            }
+            None => skipped.push("the buried treasure chest"),
+        }
+    }
+    if treasures.next().is_some() {
+        skipped.push("the other buried treasure chests");
+    }
+    if evidence.buried_treasure_counts.is_some() {
+        skipped.push("the buried treasure item counts");
+    }
+    if evidence.buried_treasure_loot_table.is_some() {
+        skipped.push("the custom buried treasure loot table");
+    }
+    if evidence
+        .world
+        .iter()
+        .any(|d| d.as_overworld().or(d.as_nether()).is_some())
+    {
+        skipped.push("the biome samples");
+    }
+    if evidence
+        .world
+        .iter()
+        .any(|d| matches!(d, WorldExtraData::StructurePresence { .. }))
+    {
+        skipped.push("the known structures");
+    }
+    if evidence.world.iter().any(|d| d.is_tie_breaker()) {
+        skipped.push("the decoration counts");
+    }
+    if evidence.has_observation_times() {
+        skipped.push("the observation times");
+    }
+
+    Ok(ProtocolScript { commands, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use mcsci::{
+        McSeedCrackingProtocol,
+        current_impl::{self, MCSCIProtocol},
+        v0::ProtocolCommand,
+    };
+    use mcseedcracker::{
+        features::end_pillars::{PartialEndPillar, PartialEndPillars, PillarHeightHint},
+        pipeline::{BuriedTreasureEvidence, CrackingEvidence},
+        search::WorldExtraData,
+        self_test::{self, BURIED_TREASURE_POS},
+    };
+
+    use crate::{
+        Extension,
+        emit::{EXTENSION_INDEX, protocol_commands},
+        parse_pillar_args,
+    };
+
+    #[test]
+    fn test_protocol_commands_round_trip() {
+        let mut pillars = PartialEndPillars::default();
+        pillars.0[0] = PartialEndPillar::new(Some(true), PillarHeightHint::Exact(103));
+        pillars.0[2] = PartialEndPillar::new(None, PillarHeightHint::Range(76, 85));
+        pillars.0[4] = PartialEndPillar::new(Some(false), PillarHeightHint::Unknown);
+        pillars.0[7] = PartialEndPillar::new(None, PillarHeightHint::MediumBig);
+        let evidence = CrackingEvidence {
+            pillars,
+            world: vec![WorldExtraData::OverworldBiomeData(vec![])],
+            ..Default::default()
+        };
+
+        let script = protocol_commands(&evidence).unwrap();
+        assert_eq!(script.skipped, vec!["the biome samples"]);
+        assert_eq!(script.commands.len(), 3);
+        assert!(script.to_text().ends_with("go\n"));
+
+        let protocol = MCSCIProtocol::default();
+        let mut setups = 0;
+        for command in script.to_text().lines() {
+            let parsed = protocol
+                .parse_command(command)
+                .unwrap_or_else(|err| panic!("can't parse {:?}: {:?}", command, err));
+            if let current_impl::ProtocolCommand::V0(ProtocolCommand::SetupProblem {
+                extension,
+                name,
+                args,
+            }) = parsed
+            {
+                setups += 1;
+                assert_eq!(extension, EXTENSION_INDEX);
+                assert_eq!(name.as_slice(), "pillar-seed-cracker");
+                assert!(parse_pillar_args(&args).unwrap() == pillars);
+            }
+        }
+        assert_eq!(setups, 1);
+
+        // nothing known, the search runs on every pillar seed
+        let script = protocol_commands(&CrackingEvidence::default()).unwrap();
+        assert_eq!(
+            script.commands,
+            vec!["hello", "setup-problem 0 \"pillar-seed-cracker\"", "go"]
+        );
+        assert!(script.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_buried_treasure_commands() {
+        let mut evidence = CrackingEvidence {
+            buried_treasures: vec![BuriedTreasureEvidence {
+                pos_x: BURIED_TREASURE_POS.0,
+                pos_z: BURIED_TREASURE_POS.1,
+                luck: 0.0,
+                contents: self_test::buried_treasure_contents(),
+                area_corner: None,
+                observed_at: None,
+            }],
+            ..Default::default()
+        };
+        evidence.pillars.0[0] = PartialEndPillar::new(None, PillarHeightHint::Exact(103));
+
+        let script = protocol_commands(&evidence).unwrap();
+        assert!(script.skipped.is_empty());
+        assert_eq!(script.commands.len(), 5);
+        assert!(
+            script.commands[3]
+                .starts_with("setup-problem 0 \"buried-treasure-cracker\" \"x\"=i32(409)")
+        );
+        assert!(script.commands[3].contains("\"pillar0height\"="));
+
+        // the server takes the chest, without running the searches
+        let mut protocol = MCSCIProtocol::default();
+        protocol.register_extension(Extension::new());
+        let setup = script.commands[..2].join("\n") + "\n" + &script.commands[3] + "\n";
+        let mut output = Vec::new();
+        protocol
+            .server_loop(&mut setup.as_bytes(), &mut output, &mut std::io::sink())
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("setup-ok\n").count(), 2, "{}", output);
+
+        // the server can't search an area
+        let mut other = evidence.buried_treasures[0].clone();
+        evidence.buried_treasures[0].area_corner = Some((500, 900));
+        let script = protocol_commands(&evidence).unwrap();
+        assert_eq!(script.commands.len(), 3);
+        assert_eq!(script.skipped, vec!["the buried treasure chest"]);
+
+        // nor more than one chest
+        other.pos_x += 16;
+        evidence.buried_treasures = vec![evidence.buried_treasures[0].clone(), other];
+        evidence.buried_treasures[0].area_corner = None;
+        let script = protocol_commands(&evidence).unwrap();
+        assert_eq!(script.commands.len(), 5);
+        assert_eq!(script.skipped, vec!["the other buried treasure chests"]);
+    }
+}
@@ -0,0 +1,129 @@
+//! Computations of the server that run on their own thread, so `stop` and the other commands are read while they
+//! search
+
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread::{self, JoinHandle},
+};
+
+use mcsci::v0::ParsetimeProtocolValue;
+
+/// What the thread of a [`BackgroundJob`] shares with the server
+#[derive(Debug, Default)]
+pub struct JobControl {
+    progress: AtomicU64,
+    stopped: AtomicBool,
+    /// Found but not taken by the server yet
+    found: Mutex<Vec<ParsetimeProtocolValue<'static>>>,
+}
+
+impl JobControl {
+    #[inline]
+    pub fn advance(&self, work: u64) {
+        self.progress.fetch_add(work, Ordering::Relaxed);
+    }
+
+    /// To check between batches, the job then ends without a result
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Hands values to the server before the job ends, it sends them as partial results
+    pub fn found(&self, values: impl IntoIterator<Item = ParsetimeProtocolValue<'static>>) {
+        if let Ok(mut found) = self.found.lock() {
+            found.extend(values);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BackgroundJob<T> {
+    control: Arc<JobControl>,
+    output: Arc<Mutex<Option<T>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T> Default for BackgroundJob<T> {
+    fn default() -> Self {
+        Self {
+            control: Arc::default(),
+            output: Arc::default(),
+            handle: None,
+        }
+    }
+}
+
+impl<T: Send + 'static> BackgroundJob<T> {
+    /// Runs `job` on a new thread, its output is the result unless it returns None. Starting again forgets the
+    /// previous run, which is stopped.
+    pub fn start(&mut self, job: impl FnOnce(&JobControl) -> Option<T> + Send + 'static) {
+        self.stop();
+        *self = Self::default();
+        let (control, output) = (Arc::clone(&self.control), Arc::clone(&self.output));
+        self.handle = Some(thread::spawn(move || {
+            let result = job(&control);
+            if let Ok(mut output) = output.lock() {
+                *output = result;
+            }
+        }));
+    }
+
+    #[inline]
+    pub fn stop(&self) {
+        self.control.stopped.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn progress(&self) -> u64 {
+        self.control.progress.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|h| !h.is_finished())
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.output.lock().is_ok_and(|output| output.is_some())
+    }
+
+    /// The thread ended without an output but wasn't stopped, it panicked
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.handle.as_ref().is_some_and(|h| h.is_finished())
+            && !self.control.is_stopped()
+            && !self.is_done()
+    }
+
+    /// The values found since the last call
+    pub fn take_found(&self) -> Vec<ParsetimeProtocolValue<'static>> {
+        self.control
+            .found
+            .lock()
+            .map(|mut found| std::mem::take(&mut *found))
+            .unwrap_or_default()
+    }
+
+    /// Calls `f` with the output once the job is done
+    pub fn with_output<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.output.lock().ok()?.as_ref().map(f)
+    }
+}
+
+/// Waits for a computation the tests started, at most a minute
+#[cfg(test)]
+pub fn wait_until_finished(computation: &dyn mcsci::v0::SimpleV0ProblemBruteCalculation) {
+    let start = std::time::Instant::now();
+    while computation.is_running() {
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(60),
+            "the computation never ends"
+        );
+        thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fs::File, io::BufReader, path::PathBuf, sync::Arc};
+
+use mcseedcracker::{
+    features::buried_treasure,
+    loot_table::{LootTable, from_datapack_json},
+};
+
+const SUPPORTED_STRUCTURES: [&str; 1] = ["buried_treasure"];
+
+#[derive(Debug, Clone)]
+pub struct LootTableOverrideArg {
+    pub structure: String,
+    pub path: PathBuf,
+}
+
+pub fn parse_loot_table_override(s: &str) -> Result<LootTableOverrideArg, String> {
+    let (structure, path) = s
+        .split_once('=')
+        .ok_or_else(|| "expected STRUCTURE=PATH".to_string())?;
+    let structure = structure.trim();
+    if !SUPPORTED_STRUCTURES.contains(&structure) {
+        return Err(format!(
+            "unsupported structure '{}', expected one of: {}",
+            structure,
+            SUPPORTED_STRUCTURES.join(", ")
+        ));
+    }
+    Ok(LootTableOverrideArg {
+        structure: structure.to_string(),
+        path: PathBuf::from(path),
+    })
+}
+
+/// Loot tables replacing the vanilla ones, loaded from datapack JSON files
+#[derive(Debug, Clone, Default)]
+pub struct LootTableOverrides {
+    pub buried_treasure: Option<Arc<LootTable>>,
+}
+
+impl LootTableOverrides {
+    pub fn load(args: &[LootTableOverrideArg]) -> Result<Self, std::io::Error> {
+        let mut overrides = Self::default();
+        for arg in args {
+            let file = BufReader::new(File::open(&arg.path)?);
+            match arg.structure.as_str() {
+                "buried_treasure" => {
+                    let table = from_datapack_json(file, &buried_treasure::item_registry())
+                        .map_err(|e| {
+                            std::io::Error::other(format!("{}: {}", arg.path.display(), e))
+                        })?;
+                    overrides.buried_treasure = Some(Arc::new(table));
+                }
+                _ => unreachable!(),
+            }
+        }
+        Ok(overrides)
+    }
+}
@@ -1,19 +1,44 @@
-use std::io::Write;
+use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use mcsci::{
     current_impl::MCSCIProtocol,
     traits::{RefToStringOrSlice, RefToVecOrSlice, StringOrSlice, VecOrSlice},
     v0::{
         EnumerationConstructor, ParsetimeProtocolValue, ProtocolExtensionInfo, SimpleV0Extension,
         SimpleV0Problem, SimpleV0ProblemBruteCalculation, TypeDeclaration,
-        V0ProblemArgumentDescription, v0_format_value,
+        V0ProblemArgumentDescription,
     },
 };
-use mcseedcracker::features::end_pillars::{
-    PartialEndPillars, PillarHeightHint, PillarMatchResult,
+use mcseedcracker::{
+    analysis::DEFAULT_BIOME_ROW_CAP,
+    biome_cache::BiomeCache,
+    calibration::{Calibration, CalibrationWorkload, SystemClock},
+    candidates::PillarSeedCandidate,
+    cooperative::{Cooperation, SearchOptions},
+    export::{SeedExportOptions, SeedNumberFormat, write_structure_seed_completions},
+    features::end_pillars::{PartialEndPillars, PillarHeightHint},
+    loot_table::SlotThreshold,
+    pipeline::DEFAULT_MAX_TREASURE_AREA,
+    schedule::EarlyTermination,
+    utils::format::format_rate,
+    world_seed::SeedOrigin,
 };
 
+use crate::job::BackgroundJob;
+
+mod check_evidence;
+mod chest_value;
+mod condition;
+mod crack;
+mod describe;
+mod emit;
+mod job;
+mod loot_override;
+mod self_test;
+mod trace_chest;
+mod treasure_counts;
+mod treasure_cracker;
 mod tui;
 mod tui_handler;
 
@@ -21,30 +46,214 @@ mod tui_handler;
 #[command(name = "seedcracker")]
 #[command(bin_name = "seedcracker")]
 pub struct Cli {
-    #[clap(long, help = "Runs the TUI", exclusive = true)]
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[clap(
+        long,
+        help = "Runs the TUI",
+        conflicts_with_all = ["expand_structure_seed", "hex", "comments"]
+    )]
     tui: bool,
+    #[clap(
+        long,
+        help = "Runs the TUI with a tutorial that walks through cracking a known world",
+        conflicts_with_all = ["expand_structure_seed", "hex", "comments"]
+    )]
+    tutorial: bool,
+    #[clap(
+        long,
+        value_name = "MIB",
+        help = "Drops the pillar seed cache, then the oldest search results, when the TUI keeps more than this many MiB of them"
+    )]
+    memory_cap: Option<usize>,
+    #[clap(
+        long,
+        help = "Runs the TUI searches in small chunks on their own thread so the interface stays responsive, always on when the machine has a single core"
+    )]
+    cooperative: bool,
+    #[clap(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Runs the TUI searches and --calibrate on this many threads instead of one per core, leaving the others to the rest of the machine. Ignored by the cooperative searches"
+    )]
+    threads: Option<u32>,
+    #[clap(
+        long,
+        value_name = "CANDIDATES",
+        default_value_t = SearchOptions::DEFAULT.batch_size,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "The TUI progress bars move every this many candidates of a search"
+    )]
+    progress_batch: u64,
+    #[clap(
+        long,
+        value_name = "SLOTS",
+        value_parser = clap::value_parser!(u32).range(1..=27),
+        help = "Accepts buried treasures whose chest matches the observed one in only this many of the 27 slots, for chests remembered with a few mistakes"
+    )]
+    min_matching_slots: Option<u32>,
+    #[clap(
+        long,
+        value_name = "ROWS",
+        default_value_t = DEFAULT_BIOME_ROW_CAP,
+        help = "Biome rows of a panel past this many show a diminishing returns hint in the TUI"
+    )]
+    biome_row_cap: usize,
+    #[clap(
+        long,
+        help = "Checks the typed world seeds of every structure seed as soon as the TUI finds it, so a typed seed shows up before the world seed search"
+    )]
+    early_typed_hits: bool,
+    #[clap(
+        long,
+        value_name = "any|nextlong|hashcode|number",
+        value_parser = crack::parse_seed_origin,
+        help = "Only searches the world seeds chosen this way in the TUI: left blank (nextlong), a text (hashcode) or a number"
+    )]
+    seed_origin: Option<SeedOrigin>,
+    #[clap(
+        long,
+        value_name = "never|exact|N",
+        default_value_t = EarlyTermination::Never,
+        value_parser = crack::parse_early_termination,
+        help = "Stops the TUI structure seed search before the remaining pillar seeds after a structure seed satisfying every piece of evidence exactly, or after N structure seeds"
+    )]
+    early_termination: EarlyTermination,
+    #[clap(
+        long,
+        value_name = "CHUNKS",
+        default_value_t = DEFAULT_MAX_TREASURE_AREA,
+        help = "Largest buried treasure search area of the TUI, for chests whose position is only roughly known. Every chunk of the area is checked for every candidate, the structure seed search takes up to this many times longer"
+    )]
+    max_treasure_area: u64,
+    #[clap(
+        long,
+        value_name = "STRUCTURE_SEED",
+        help = "Writes the 65536 world seed candidates of a structure seed to stdout, one per line"
+    )]
+    expand_structure_seed: Option<i64>,
+    #[clap(
+        long,
+        help = "Measures the search speed of this machine and stores it for search time estimates",
+        conflicts_with_all = ["tui", "tutorial", "expand_structure_seed"]
+    )]
+    calibrate: bool,
+    #[clap(long, help = "Export seeds in hexadecimal")]
+    hex: bool,
+    #[clap(long, help = "Include comments in exported seed lists")]
+    comments: bool,
+    #[clap(
+        long = "loot-table-override",
+        value_name = "STRUCTURE=PATH",
+        value_parser = loot_override::parse_loot_table_override,
+        global = true,
+        help = "Replaces the vanilla loot table of a structure with a datapack loot table JSON file. Supported structures: buried_treasure"
+    )]
+    loot_table_overrides: Vec<loot_override::LootTableOverrideArg>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Runs the whole search from the command line
+    Crack(crack::CrackArgs),
+    /// Checks the installation against known answers, exits with an error if any check fails
+    SelfTest,
+    /// Prints the RNG calls made while generating a chest, to debug a loot table
+    #[command(hide = true)]
+    TraceChest(trace_chest::TraceChestArgs),
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    let result = if cli.tui {
-        tui_handler::run_tui()
+    let overrides = match loot_override::LootTableOverrides::load(&cli.loot_table_overrides) {
+        Ok(overrides) => overrides,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let result = if cli.tui || cli.tutorial {
+        tui_handler::run_tui(
+            overrides,
+            tui_handler::TuiOptions {
+                tutorial: cli.tutorial,
+                memory_cap: cli.memory_cap.map(|mib| mib.saturating_mul(1 << 20)),
+                cooperation: Cooperation::detect(cli.cooperative),
+                search: SearchOptions {
+                    threads: cli.threads.map(|threads| threads as usize),
+                    batch_size: cli.progress_batch,
+                },
+                slot_threshold: cli
+                    .min_matching_slots
+                    .map(|slots| SlotThreshold::new(slots, 27)),
+                biome_row_cap: cli.biome_row_cap,
+                early_typed_hits: cli.early_typed_hits,
+                seed_origin: cli.seed_origin.unwrap_or_default(),
+                early_termination: cli.early_termination,
+                max_treasure_area: cli.max_treasure_area,
+            },
+        )
+    } else if cli.calibrate {
+        run_calibration(&SearchOptions {
+            threads: cli.threads.map(|threads| threads as usize),
+            ..SearchOptions::DEFAULT
+        })
+    } else if let Some(command) = cli.command {
+        match command {
+            Command::Crack(args) => crack::run(args, overrides),
+            Command::SelfTest => self_test::run(),
+            Command::TraceChest(args) => trace_chest::run(args, overrides),
+        }
+    } else if let Some(structure_seed) = cli.expand_structure_seed {
+        write_structure_seed_completions(
+            &mut std::io::stdout().lock(),
+            structure_seed,
+            &SeedExportOptions::new(
+                cli.comments,
+                if cli.hex {
+                    SeedNumberFormat::Hex
+                } else {
+                    SeedNumberFormat::Decimal
+                },
+            ),
+        )
     } else {
         run_stdin_loop()
     };
 
     if let Err(err) = result {
         eprintln!("Error: {}", err);
-        std::process::exit(err.raw_os_error().unwrap_or(1));
+        std::process::exit(1);
     }
 
     std::process::exit(0);
 }
 
+fn run_calibration(search: &SearchOptions) -> Result<(), std::io::Error> {
+    eprintln!("info:  Measuring the search speed, this takes a few seconds");
+    let calibration = Calibration::measure(
+        &SystemClock::new(),
+        &CalibrationWorkload::standard(),
+        search,
+    )
+    .map_err(std::io::Error::other)?;
+    let path = calibration.store()?;
+    println!(
+        "Structure seeds: {}\nWorld seeds: {}\n{} thread(s), saved to {}",
+        format_rate(calibration.structure_seeds_per_second, "seeds"),
+        format_rate(calibration.world_seeds_per_second, "seeds"),
+        calibration.threads,
+        path.display()
+    );
+    Ok(())
+}
+
 fn run_stdin_loop() -> Result<(), std::io::Error> {
-    let stdin = std::io::stdin();
-    let mut handle = stdin.lock();
+    // read on the reader thread of the server, which can't take the stdin lock
+    let mut input = std::io::BufReader::new(std::io::stdin());
 
     let mut stdout = std::io::stdout();
     let mut stderr = std::io::stderr();
@@ -58,13 +267,15 @@ fn run_stdin_loop() -> Result<(), std::io::Error> {
 
     protocol.register_extension(Extension::new());
 
-    protocol.server_loop(&mut handle, &mut stdout, &mut stderr)
+    protocol.server_loop(&mut input, &mut stdout, &mut stderr)
 }
 
 #[derive(Debug)]
 pub struct Extension {
     types: Vec<(StringOrSlice<'static>, TypeDeclaration<'static>)>,
     problems: Vec<Box<dyn SimpleV0Problem>>,
+    /// Biome lookups of the checks of single seeds in this session
+    biome_cache: Arc<BiomeCache>,
 }
 
 macro_rules! typedef {
@@ -75,6 +286,7 @@ macro_rules! typedef {
 
 impl Extension {
     pub fn new() -> Extension {
+        let biome_cache = Arc::new(BiomeCache::default());
         let ext = Extension {
             types: vec![
                 typedef!(
@@ -205,8 +417,186 @@ impl Extension {
                         },
                     ])
                 ),
+                typedef!(
+                    "item_count_bound",
+                    TypeDeclaration::Enumeration(vec![
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Exactly"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl("i32"))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("AtLeast"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl("i32"))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("AtMost"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl("i32"))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Between"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                    ])
+                ),
+                typedef!(
+                    "item_count_constraint",
+                    TypeDeclaration::Tuple(
+                        [
+                            TypeDeclaration::Alias(StringOrSlice::Sl("string")),
+                            TypeDeclaration::Alias(StringOrSlice::Sl("item_count_bound"))
+                        ]
+                        .ref_to_vec_or_slice()
+                    )
+                ),
+                typedef!(
+                    "biome_sample",
+                    TypeDeclaration::Tuple(
+                        [
+                            TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                            TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                            TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                            TypeDeclaration::Alias(StringOrSlice::Sl("string"))
+                        ]
+                        .ref_to_vec_or_slice()
+                    )
+                ),
+                typedef!(
+                    "item_property",
+                    TypeDeclaration::Enumeration(vec![
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Damage"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Enchantment"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                    ])
+                ),
+                typedef!(
+                    "chest_slot",
+                    TypeDeclaration::Enumeration(vec![
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Empty"),
+                            argtype: None,
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Stack"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("string")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::List(Box::new(TypeDeclaration::Alias(
+                                        StringOrSlice::Sl("item_property")
+                                    ))),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                    ])
+                ),
+                typedef!(
+                    "single_chest",
+                    TypeDeclaration::Array(
+                        Box::new(TypeDeclaration::Array(
+                            Box::new(TypeDeclaration::Alias(StringOrSlice::Sl("chest_slot"))),
+                            9
+                        )),
+                        3
+                    )
+                ),
+                typedef!(
+                    "evidence_expr",
+                    TypeDeclaration::Enumeration(vec![
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("All"),
+                            argtype: Some(TypeDeclaration::List(Box::new(TypeDeclaration::Alias(
+                                StringOrSlice::Sl("evidence_expr")
+                            )))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Any"),
+                            argtype: Some(TypeDeclaration::List(Box::new(TypeDeclaration::Alias(
+                                StringOrSlice::Sl("evidence_expr")
+                            )))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("Not"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl(
+                                "evidence_expr"
+                            ))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("OverworldBiome"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl(
+                                "biome_sample"
+                            ))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("NetherBiome"),
+                            argtype: Some(TypeDeclaration::Alias(StringOrSlice::Sl(
+                                "biome_sample"
+                            ))),
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("BuriedTreasure"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("f32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("single_chest")),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                        EnumerationConstructor {
+                            name: StringOrSlice::Sl("BuriedTreasureCounts"),
+                            argtype: Some(TypeDeclaration::Tuple(
+                                [
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("i32")),
+                                    TypeDeclaration::Alias(StringOrSlice::Sl("f32")),
+                                    TypeDeclaration::List(Box::new(TypeDeclaration::Alias(
+                                        StringOrSlice::Sl("item_count_constraint")
+                                    ))),
+                                ]
+                                .ref_to_vec_or_slice()
+                            ))
+                        },
+                    ])
+                ),
             ],
-            problems: vec![Box::new(PillarCrackingProblem::default())],
+            problems: vec![
+                Box::new(PillarCrackingProblem::default()),
+                Box::new(self_test::SelfTestProblem),
+                Box::new(describe::DescribeWorldProblem),
+                Box::new(treasure_counts::TreasureCountsProblem),
+                Box::new(check_evidence::CheckEvidenceProblem::default()),
+                Box::new(treasure_cracker::TreasureCrackingProblem::default()),
+                Box::new(condition::CheckConditionProblem::new(Arc::clone(
+                    &biome_cache,
+                ))),
+            ],
+            biome_cache,
         };
         ext
     }
@@ -236,6 +626,16 @@ impl SimpleV0Extension for Extension {
     fn get_problem<'a>(&'a self, name: &str) -> Option<Box<dyn SimpleV0Problem>> {
         match name {
             "pillar-seed-cracker" => Some(Box::new(PillarCrackingProblem::default())),
+            "self-test" => Some(Box::new(self_test::SelfTestProblem)),
+            "describe-world" => Some(Box::new(describe::DescribeWorldProblem)),
+            "buried-treasure-counts" => Some(Box::new(treasure_counts::TreasureCountsProblem)),
+            "check-evidence" => Some(Box::new(check_evidence::CheckEvidenceProblem::default())),
+            "buried-treasure-cracker" => Some(Box::new(
+                treasure_cracker::TreasureCrackingProblem::default(),
+            )),
+            "check-condition" => Some(Box::new(condition::CheckConditionProblem::new(Arc::clone(
+                &self.biome_cache,
+            )))),
             _ => None,
         }
     }
@@ -284,257 +684,395 @@ impl SimpleV0Problem for PillarCrackingProblem {
         &'a self,
         args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
     ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
-        macro_rules! inv_arg {
-            () => {
-                return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
-                    "Invalid argument name",
-                )))
-            };
+        let pillars = parse_pillar_args(args.as_slice())?;
+        // no pillar seed can match, say why instead of answering with an empty result
+        if let Err(invalid) = pillars.validate() {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::St(
+                invalid.to_string(),
+            )));
         }
+        Ok(Box::new(PillarCrackingProblemComputation {
+            pillars,
+            job: BackgroundJob::default(),
+        }))
+    }
+}
 
-        macro_rules! inv_val {
-            () => {
-                return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
-                    "Invalid argument value",
-                )))
-            };
+/// The pillars described by the arguments of `pillar-seed-cracker`, the ones not given are unknown
+pub fn parse_pillar_args(
+    args: &[(StringOrSlice, ParsetimeProtocolValue)],
+) -> Result<PartialEndPillars, ParsetimeProtocolValue<'static>> {
+    macro_rules! inv_arg {
+        () => {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Invalid argument name",
+            )))
+        };
+    }
+
+    macro_rules! inv_val {
+        () => {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Invalid argument value",
+            )))
+        };
+    }
+
+    fn parse_height_value(v: &ParsetimeProtocolValue) -> Option<i32> {
+        match v {
+            ParsetimeProtocolValue::Enumeration(Some(n), _, _)
+                if n.as_slice() != "pillar_height" =>
+            {
+                None
+            }
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h76" => {
+                Some(76)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h79" => {
+                Some(79)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h82" => {
+                Some(82)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h85" => {
+                Some(85)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h88" => {
+                Some(88)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h91" => {
+                Some(91)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h94" => {
+                Some(94)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h97" => {
+                Some(97)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h100" => {
+                Some(100)
+            }
+
+            ParsetimeProtocolValue::Enumeration(_, constr, None) if constr.as_slice() == "h103" => {
+                Some(103)
+            }
+            _ => None,
         }
+    }
 
-        fn parse_height_value(v: &ParsetimeProtocolValue) -> Option<i32> {
-            match v {
+    let mut partial = PartialEndPillars::default();
+    for (argn, argv) in args {
+        if argn.as_slice().get(0..6) != Some("pillar") {
+            inv_arg!();
+        }
+        let pillar_index = match argn.as_slice().chars().nth(6) {
+            Some(c) if c.is_ascii_digit() => c as u8 - b'0',
+            _ => inv_arg!(),
+        };
+        match argn.as_slice().get(7..) {
+            Some("height") => match argv {
                 ParsetimeProtocolValue::Enumeration(Some(n), _, _)
-                    if n.as_slice() != "pillar_height" =>
+                    if n.as_slice() != "pillar_height_hint" =>
                 {
-                    None
+                    inv_val!()
                 }
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h76" =>
+                    if constr.as_slice() == "Small" =>
                 {
-                    Some(76)
+                    partial.0[pillar_index as usize].height = PillarHeightHint::Small;
                 }
-
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h79" =>
+                    if constr.as_slice() == "Medium" =>
                 {
-                    Some(79)
+                    partial.0[pillar_index as usize].height = PillarHeightHint::Medium;
                 }
-
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h82" =>
+                    if constr.as_slice() == "Big" =>
                 {
-                    Some(82)
+                    partial.0[pillar_index as usize].height = PillarHeightHint::Big;
                 }
 
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h85" =>
+                    if constr.as_slice() == "MediumSmall" =>
                 {
-                    Some(85)
+                    partial.0[pillar_index as usize].height = PillarHeightHint::MediumSmall;
                 }
 
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h88" =>
+                    if constr.as_slice() == "MediumBig" =>
                 {
-                    Some(88)
+                    partial.0[pillar_index as usize].height = PillarHeightHint::MediumBig;
                 }
 
-                ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h91" =>
+                ParsetimeProtocolValue::Enumeration(_, constr, Some(v))
+                    if constr.as_slice() == "Exact" =>
                 {
-                    Some(91)
+                    let Some(h) = parse_height_value(v) else {
+                        inv_val!()
+                    };
+                    partial.0[pillar_index as usize].height = PillarHeightHint::Exact(h);
                 }
 
-                ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h94" =>
+                ParsetimeProtocolValue::Enumeration(_, constr, Some(v))
+                    if constr.as_slice() == "Range" =>
                 {
-                    Some(94)
+                    match &**v {
+                        ParsetimeProtocolValue::Tuple(None, vals) if vals.len() == 2 => {
+                            let Some(a) = parse_height_value(&vals[0]) else {
+                                inv_val!()
+                            };
+                            let Some(b) = parse_height_value(&vals[1]) else {
+                                inv_val!()
+                            };
+                            partial.0[pillar_index as usize].height = PillarHeightHint::Range(a, b);
+                        }
+                        _ => inv_val!(),
+                    }
+                }
+                _ => inv_val!(),
+            },
+            Some("caged") => match argv {
+                ParsetimeProtocolValue::Enumeration(Some(n), _, _)
+                    if n.as_slice() != "pillar_caged_status" =>
+                {
+                    inv_val!()
                 }
-
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h97" =>
+                    if constr.as_slice() == "Caged" =>
                 {
-                    Some(97)
+                    partial.0[pillar_index as usize].caged = Some(true);
                 }
-
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h100" =>
+                    if constr.as_slice() == "Uncaged" =>
                 {
-                    Some(100)
+                    partial.0[pillar_index as usize].caged = Some(false);
                 }
-
                 ParsetimeProtocolValue::Enumeration(_, constr, None)
-                    if constr.as_slice() == "h103" =>
+                    if constr.as_slice() == "Unknown" =>
                 {
-                    Some(103)
+                    partial.0[pillar_index as usize].caged = None;
                 }
-                _ => None,
-            }
+                _ => inv_val!(),
+            },
+            _ => inv_arg!(),
         }
-
-        let mut partial = PartialEndPillars::default();
-        for (argn, argv) in args.as_slice() {
-            if argn.as_slice().get(0..6) != Some("pillar") {
-                inv_arg!();
-            }
-            let pillar_index = match argn.as_slice().chars().nth(6) {
-                Some(c) if c.is_ascii_digit() => c as u8 - b'0',
-                _ => inv_arg!(),
-            };
-            match argn.as_slice().get(7..) {
-                Some("height") => match argv {
-                    ParsetimeProtocolValue::Enumeration(Some(n), _, _)
-                        if n.as_slice() != "pillar_height_hint" =>
-                    {
-                        inv_val!()
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Small" =>
-                    {
-                        partial.0[pillar_index as usize].height = PillarHeightHint::Small;
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Medium" =>
-                    {
-                        partial.0[pillar_index as usize].height = PillarHeightHint::Medium;
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Big" =>
-                    {
-                        partial.0[pillar_index as usize].height = PillarHeightHint::Big;
-                    }
-
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "MediumSmall" =>
-                    {
-                        partial.0[pillar_index as usize].height = PillarHeightHint::MediumSmall;
-                    }
-
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "MediumBig" =>
-                    {
-                        partial.0[pillar_index as usize].height = PillarHeightHint::MediumBig;
-                    }
-
-                    ParsetimeProtocolValue::Enumeration(_, constr, Some(v))
-                        if constr.as_slice() == "Exact" =>
-                    {
-                        let Some(h) = parse_height_value(v) else {
-                            inv_val!()
-                        };
-                        partial.0[pillar_index as usize].height = PillarHeightHint::Exact(h);
-                    }
-
-                    ParsetimeProtocolValue::Enumeration(_, constr, Some(v))
-                        if constr.as_slice() == "Range" =>
-                    {
-                        match &**v {
-                            ParsetimeProtocolValue::Tuple(None, vals) if vals.len() == 2 => {
-                                let Some(a) = parse_height_value(&vals[0]) else {
-                                    inv_val!()
-                                };
-                                let Some(b) = parse_height_value(&vals[0]) else {
-                                    inv_val!()
-                                };
-                                partial.0[pillar_index as usize].height =
-                                    PillarHeightHint::Range(a, b);
-                            }
-                            _ => inv_val!(),
-                        }
-                    }
-                    _ => inv_val!(),
-                },
-                Some("caged") => match argv {
-                    ParsetimeProtocolValue::Enumeration(Some(n), _, _)
-                        if n.as_slice() != "pillar_caged_status" =>
-                    {
-                        inv_val!()
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Caged" =>
-                    {
-                        partial.0[pillar_index as usize].caged = Some(true);
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Uncaged" =>
-                    {
-                        partial.0[pillar_index as usize].caged = Some(false);
-                    }
-                    ParsetimeProtocolValue::Enumeration(_, constr, None)
-                        if constr.as_slice() == "Unknown" =>
-                    {
-                        partial.0[pillar_index as usize].caged = None;
-                    }
-                    _ => inv_val!(),
-                },
-                _ => inv_arg!(),
-            }
-        }
-
-        Ok(Box::new(PillarCrackingProblemComputation {
-            pillars: partial,
-            done: false,
-            seeds: Vec::new(),
-        }))
     }
+
+    Ok(partial)
 }
 
+/// Pillar seeds whose pillars are generated between two checks of `stop`
+const PILLAR_SEED_BATCH: i64 = 256;
+
 #[derive(Debug)]
 pub struct PillarCrackingProblemComputation {
     pillars: PartialEndPillars,
-    seeds: Vec<(i64, PillarMatchResult)>,
-    done: bool,
+    job: BackgroundJob<Vec<PillarSeedCandidate>>,
 }
 
 impl SimpleV0ProblemBruteCalculation for PillarCrackingProblemComputation {
-    fn go(&mut self, output: &mut dyn Write) {
-        self.seeds = self.pillars.seed_results();
-        self.done = true;
-
-        match self.result() {
-            Some(result) => {
-                let mut line = b"result ".iter().copied().collect::<Vec<_>>();
-                v0_format_value(&mut line, &result).unwrap();
-                line.push(b'\n');
-                output.write(&line).unwrap();
-            }
-            None => {
-                output.write(b"result\n").unwrap();
+    fn go(&mut self) {
+        let pillars = self.pillars;
+        self.job.start(move |control| {
+            let (mut seeds, mut found) = (Vec::new(), 0);
+            for (seed, result) in pillars.seed_results_iter() {
+                if seed % PILLAR_SEED_BATCH == 0 && control.is_stopped() {
+                    return None;
+                }
+                if !result.is_impossible_match() {
+                    seeds.push(PillarSeedCandidate::new(seed, result));
+                }
+                if seed % PILLAR_SEED_BATCH == PILLAR_SEED_BATCH - 1 {
+                    control.found(seeds[found..].iter().map(candidate_value));
+                    found = seeds.len();
+                    control.advance(PILLAR_SEED_BATCH as u64);
+                }
             }
-        }
+            Some(seeds)
+        });
     }
 
     fn is_done(&self) -> bool {
-        self.done
+        self.job.is_done()
     }
 
     fn is_running(&self) -> bool {
-        false
+        self.job.is_running()
+    }
+
+    fn is_failed(&self) -> bool {
+        self.job.is_failed()
     }
 
     fn progress(&self) -> (u64, u64) {
-        (if self.done { 65536 } else { 0 }, 65536)
+        (self.job.progress(), 65536)
     }
 
-    fn stop(&mut self) {}
+    fn stop(&mut self) {
+        self.job.stop();
+    }
 
     fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
-        if self.done {
-            let values = self
-                .seeds
-                .iter()
-                .filter(|(_, r)| !r.is_impossible_match())
-                .map(|(s, r)| {
-                    ParsetimeProtocolValue::Tuple(
-                        None,
-                        vec![
-                            ParsetimeProtocolValue::I64(*s),
-                            ParsetimeProtocolValue::F64(r.chance()),
-                        ],
-                    )
-                })
-                .collect::<Vec<_>>();
-            Some(ParsetimeProtocolValue::List(None, values))
-        } else {
-            None
+        self.job.with_output(|seeds| {
+            ParsetimeProtocolValue::List(None, seeds.iter().map(candidate_value).collect())
+        })
+    }
+
+    /// The candidates of each batch of pillar seeds
+    fn take_new_results(&mut self) -> Vec<ParsetimeProtocolValue<'static>> {
+        self.job.take_found()
+    }
+}
+
+/// A pillar seed and its probability
+fn candidate_value(candidate: &PillarSeedCandidate) -> ParsetimeProtocolValue<'static> {
+    ParsetimeProtocolValue::Tuple(
+        None,
+        vec![
+            ParsetimeProtocolValue::I64(candidate.seed),
+            ParsetimeProtocolValue::F64(candidate.probability),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use mcsci::{
+        current_impl::MCSCIProtocol,
+        traits::VecOrSlice,
+        v0::{ParsetimeProtocolValue, SimpleV0Extension, SimpleV0ProblemBruteCalculation},
+    };
+
+    use mcseedcracker::{features::end_pillars::PartialEndPillar, pipeline::CrackingEvidence};
+
+    use crate::{Extension, emit::protocol_commands, job::wait_until_finished};
+
+    /// Accepts `remaining` bytes, at most 8 per write, then fails like a closed pipe
+    struct FailingWriter {
+        remaining: usize,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+            }
+            let n = buf.len().min(self.remaining).min(8);
+            self.remaining -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
         }
     }
+
+    fn pillar_computation() -> Box<dyn SimpleV0ProblemBruteCalculation> {
+        Extension::new()
+            .get_problem("pillar-seed-cracker")
+            .unwrap()
+            .setup(VecOrSlice::V(vec![]))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_pillar_computation_in_background() {
+        let mut computation = pillar_computation();
+        assert!(!computation.is_running());
+        computation.go();
+        wait_until_finished(computation.as_ref());
+        assert!(computation.is_done());
+        assert!(!computation.is_failed());
+        assert_eq!(computation.progress(), (65536, 65536));
+        assert!(matches!(
+            computation.result(),
+            Some(ParsetimeProtocolValue::List(_, seeds)) if seeds.len() == 65536
+        ));
+        // every candidate was also found as it was searched
+        assert_eq!(computation.take_new_results().len(), 65536);
+        assert!(computation.take_new_results().is_empty());
+
+        // stopped before the first batch
+        let mut computation = pillar_computation();
+        computation.go();
+        computation.stop();
+        wait_until_finished(computation.as_ref());
+        assert!(!computation.is_done());
+        assert!(!computation.is_failed());
+        assert!(computation.result().is_none());
+    }
+
+    #[test]
+    fn test_setup_rejects_invalid_pillars() {
+        let mut evidence = CrackingEvidence::default();
+        for pillar in evidence.pillars.iter_mut().take(3) {
+            *pillar = PartialEndPillar::new(Some(true), Default::default());
+        }
+        let setup = |evidence: &CrackingEvidence| {
+            let mut protocol = MCSCIProtocol::default();
+            protocol.register_extension(Extension::new());
+            let mut output = Vec::new();
+            protocol
+                .server_loop(
+                    &mut protocol_commands(evidence).unwrap().to_text().as_bytes(),
+                    &mut output,
+                    &mut io::sink(),
+                )
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert!(setup(&evidence).contains(
+            "setup-error \"3 pillars are marked caged, but only 2 pillars are caged\"\n"
+        ));
+
+        // two caged pillars are fine
+        evidence.pillars.0[2] = PartialEndPillar::default();
+        let output = setup(&evidence);
+        assert!(output.contains("setup-ok\n"));
+        assert!(!output.contains("setup-error"));
+    }
+
+    #[test]
+    fn test_server_ends_session_on_output_failure() {
+        let setup = "hello\nsetup-problem 0 \"pillar-seed-cracker\"\n";
+        let server = || {
+            let mut protocol = MCSCIProtocol::default();
+            protocol.register_extension(Extension::new());
+            protocol
+        };
+
+        let mut output = Vec::new();
+        server()
+            .server_loop(&mut setup.as_bytes(), &mut output, &mut io::sink())
+            .unwrap();
+
+        // the client disconnects while the result is being sent
+        let input = format!("{}go\n", setup);
+        let mut errout = Vec::new();
+        server()
+            .server_loop(
+                &mut input.as_bytes(),
+                &mut FailingWriter {
+                    remaining: output.len() + 10,
+                },
+                &mut errout,
+            )
+            .unwrap();
+        assert!(
+            String::from_utf8(errout)
+                .unwrap()
+                .starts_with("Ending the session, output failed:")
+        );
+    }
 }
@@ -0,0 +1,114 @@
+use std::io::Write;
+
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::self_test::{SELF_TEST_CHECKS, SelfTestResult, run_self_test};
+
+pub fn run() -> Result<(), std::io::Error> {
+    let mut stdout = std::io::stdout().lock();
+    let results = run_self_test();
+    for result in results.iter() {
+        match &result.result {
+            Ok(()) => writeln!(stdout, "pass  {}", result.name)?,
+            Err(err) => writeln!(stdout, "FAIL  {}: {}", result.name, err)?,
+        }
+    }
+    stdout.flush()?;
+
+    let failed = results.iter().filter(|r| r.result.is_err()).count();
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{} of {} self test check(s) failed",
+            failed,
+            results.len()
+        )))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestProblem;
+
+impl SimpleV0Problem for SelfTestProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("self-test")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Runs known-answer checks of the loot, pillar, structure search and biome code. The result is a list of (check, passed, error) tuples.",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(&[])
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        if !args.as_slice().is_empty() {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Invalid argument name",
+            )));
+        }
+        Ok(Box::new(SelfTestComputation::default()))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SelfTestComputation {
+    results: Option<Vec<SelfTestResult>>,
+}
+
+impl SimpleV0ProblemBruteCalculation for SelfTestComputation {
+    fn go(&mut self) {
+        self.results = Some(run_self_test());
+    }
+
+    fn is_done(&self) -> bool {
+        self.results.is_some()
+    }
+
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    fn is_failed(&self) -> bool {
+        false
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        let total = SELF_TEST_CHECKS.len() as u64;
+        (if self.results.is_some() { total } else { 0 }, total)
+    }
+
+    fn stop(&mut self) {}
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        let results = self.results.as_ref()?;
+        let values = results
+            .iter()
+            .map(|r| {
+                ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![
+                        ParsetimeProtocolValue::String(StringOrSlice::Sl(r.name)),
+                        ParsetimeProtocolValue::Bool(r.result.is_ok()),
+                        ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                            r.result.as_ref().err().map(String::as_str).unwrap_or(""),
+                        )),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+        Some(ParsetimeProtocolValue::List(None, values))
+    }
+}
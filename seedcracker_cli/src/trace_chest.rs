@@ -0,0 +1,117 @@
+use std::io::Write;
+
+use clap::Args;
+use mcseedcracker::{
+    features::{
+        bastion::{self, BastionChestType},
+        buried_treasure,
+    },
+    loot_table::{ItemRegistry, LootTable},
+    trace::ChestTrace,
+};
+
+use crate::loot_override::LootTableOverrides;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChestStructure {
+    BuriedTreasure,
+    Bastion(BastionChestType),
+}
+
+const STRUCTURES: [(&str, ChestStructure); 5] = [
+    ("buried-treasure", ChestStructure::BuriedTreasure),
+    (
+        "bastion-other",
+        ChestStructure::Bastion(BastionChestType::Other),
+    ),
+    (
+        "bastion-bridges",
+        ChestStructure::Bastion(BastionChestType::Bridges),
+    ),
+    (
+        "bastion-hoglin-stables",
+        ChestStructure::Bastion(BastionChestType::HoglinStables),
+    ),
+    (
+        "bastion-treasure-room",
+        ChestStructure::Bastion(BastionChestType::TreasureRoom),
+    ),
+];
+
+fn parse_structure(s: &str) -> Result<ChestStructure, String> {
+    STRUCTURES
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map(|(_, structure)| *structure)
+        .ok_or_else(|| {
+            format!(
+                "unknown structure '{}', expected one of: {}",
+                s,
+                STRUCTURES.map(|(name, _)| name).join(", ")
+            )
+        })
+}
+
+#[derive(Args)]
+pub struct TraceChestArgs {
+    #[clap(
+        long,
+        value_name = "STRUCTURE",
+        value_parser = parse_structure,
+        help = "The loot table: buried-treasure, bastion-other, bastion-bridges, bastion-hoglin-stables or bastion-treasure-room"
+    )]
+    structure: ChestStructure,
+    #[clap(
+        long,
+        allow_hyphen_values = true,
+        help = "The loot table seed the chest's RNG is created with"
+    )]
+    seed: i64,
+    #[clap(
+        long,
+        default_value_t = 0.0,
+        help = "Luck of the player opening the chest"
+    )]
+    luck: f32,
+    #[clap(
+        long,
+        value_name = "SEED",
+        allow_hyphen_values = true,
+        help = "Compares with the chest of another loot table seed. Without it, a buried treasure is compared with the --loot-table-override table if one is given"
+    )]
+    compare_seed: Option<i64>,
+}
+
+/// Prints the RNG calls of a chest generation and the generated chest, see [`ChestTrace`]
+pub fn run(args: TraceChestArgs, overrides: LootTableOverrides) -> Result<(), std::io::Error> {
+    let (loot_table, registry, modified): (LootTable, Option<ItemRegistry>, Option<&LootTable>) =
+        match args.structure {
+            ChestStructure::BuriedTreasure => (
+                buried_treasure::get_loot_table(),
+                Some(buried_treasure::item_registry()),
+                overrides.buried_treasure.as_deref(),
+            ),
+            ChestStructure::Bastion(chest_type) => {
+                (bastion::get_loot_table(chest_type), None, None)
+            }
+        };
+
+    let trace = ChestTrace::generate(&loot_table, args.seed, args.luck);
+    let other = match (args.compare_seed, modified) {
+        (Some(seed), _) => Some(ChestTrace::generate(&loot_table, seed, args.luck)),
+        (None, Some(modified)) => Some(ChestTrace::generate(modified, args.seed, args.luck)),
+        (None, None) => None,
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    write!(
+        stdout,
+        "{}",
+        trace.render(registry.as_ref(), other.as_ref())
+    )?;
+    if let Some(other) = &other {
+        writeln!(stdout, "\nCompared chest:")?;
+        write!(stdout, "{}", other.render_chest(registry.as_ref()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::{
+    features::buried_treasure::{
+        compare_buried_treasure_counts, generates_at, get_loot_table, item_registry,
+    },
+    loot_table::{CountBound, ItemCountConstraints},
+    math::Math,
+    tooltip::LanguageTable,
+};
+
+const TREASURE_COUNTS_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("seed"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i64"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("x"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("z"),
+        optional: false,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("luck"),
+        optional: true,
+        argtype: StringOrSlice::Sl("f32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("counts"),
+        optional: false,
+        argtype: StringOrSlice::Sl("list(item_count_constraint)"),
+    },
+];
+
+/// Integers are parsed as the smallest type that fits them
+pub(crate) fn as_i32(value: &ParsetimeProtocolValue) -> Option<i32> {
+    match value {
+        ParsetimeProtocolValue::I8(v) => Some(*v as i32),
+        ParsetimeProtocolValue::I16(v) => Some(*v as i32),
+        ParsetimeProtocolValue::I32(v) => Some(*v),
+        ParsetimeProtocolValue::U8(v) => Some(*v as i32),
+        ParsetimeProtocolValue::U16(v) => Some(*v as i32),
+        ParsetimeProtocolValue::U32(v) => i32::try_from(*v).ok(),
+        ParsetimeProtocolValue::I64(v) => i32::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+/// An `item_count_bound` value
+pub(crate) fn parse_bound(value: &ParsetimeProtocolValue) -> Option<CountBound> {
+    let ParsetimeProtocolValue::Enumeration(_, ctor, Some(arg)) = value else {
+        return None;
+    };
+    let bound = match (ctor.as_slice(), arg.as_ref()) {
+        ("Exactly", n) => CountBound::Exactly(as_i32(n)?),
+        ("AtLeast", n) => CountBound::AtLeast(as_i32(n)?),
+        ("AtMost", n) => CountBound::AtMost(as_i32(n)?),
+        ("Between", ParsetimeProtocolValue::Tuple(_, bounds)) => match bounds.as_slice() {
+            [min, max] => CountBound::Between(as_i32(min)?, as_i32(max)?),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    (bound.min() >= 0 && bound.min() <= bound.max()).then_some(bound)
+}
+
+/// An `item_count_constraint` value, the item is its resource or display name
+pub(crate) fn parse_constraint(value: &ParsetimeProtocolValue) -> Option<(usize, CountBound)> {
+    let ParsetimeProtocolValue::Tuple(_, fields) = value else {
+        return None;
+    };
+    let [name, bound] = fields.as_slice() else {
+        return None;
+    };
+    let name = match name {
+        ParsetimeProtocolValue::String(s) | ParsetimeProtocolValue::RawString(s) => s.as_slice(),
+        _ => return None,
+    };
+    Some((treasure_item(name)?, parse_bound(bound)?))
+}
+
+/// Id of a buried treasure item from its resource or display name
+pub(crate) fn treasure_item(name: &str) -> Option<usize> {
+    let registry = item_registry();
+    let item = registry.get(name).or_else(|| {
+        LanguageTable::en_us()
+            .item_named(name)
+            .and_then(|name| registry.get(name))
+    })?;
+    Some(item.id)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TreasureCountsProblem;
+
+impl SimpleV0Problem for TreasureCountsProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("buried-treasure-counts")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Checks whether the buried treasure at the block (x, z) of a world seed exists and holds the given item totals. The result is a bool.",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(TREASURE_COUNTS_ARGS)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        let invalid_value =
+            || ParsetimeProtocolValue::String(StringOrSlice::Sl("Invalid argument value"));
+        let (mut seed, mut x, mut z, mut luck, mut counts) = (None, None, None, 0.0, None);
+        for (name, value) in args.as_slice().iter() {
+            match (name.as_slice(), value) {
+                ("seed", ParsetimeProtocolValue::I64(v)) => seed = Some(*v),
+                ("x" | "z", v) => {
+                    let v = as_i32(v).ok_or_else(invalid_value)?;
+                    if name.as_slice() == "x" {
+                        x = Some(v);
+                    } else {
+                        z = Some(v);
+                    }
+                }
+                ("luck", ParsetimeProtocolValue::F32(v)) => luck = *v,
+                ("luck", ParsetimeProtocolValue::F64(v)) => luck = *v as f32,
+                ("counts", ParsetimeProtocolValue::List(_, values)) => {
+                    counts = Some(
+                        values
+                            .iter()
+                            .map(parse_constraint)
+                            .collect::<Option<Vec<_>>>()
+                            .ok_or_else(invalid_value)?,
+                    );
+                }
+                ("seed" | "luck" | "counts", _) => return Err(invalid_value()),
+                _ => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument name",
+                    )));
+                }
+            }
+        }
+        let (Some(seed), Some(x), Some(z), Some(counts)) = (seed, x, z, counts) else {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Missing argument",
+            )));
+        };
+
+        let chunk = Math::checked_block_coords_to_chunk_coords((x, z))
+            .map_err(|err| ParsetimeProtocolValue::String(StringOrSlice::St(err.to_string())))?;
+        let constraints = ItemCountConstraints::new(counts);
+        if !constraints
+            .unsatisfiable_bounds(&get_loot_table())
+            .is_empty()
+        {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Impossible item count",
+            )));
+        }
+
+        Ok(Box::new(TreasureCountsComputation {
+            seed,
+            chunk,
+            luck,
+            constraints,
+            matches: None,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TreasureCountsComputation {
+    seed: i64,
+    chunk: (i32, i32),
+    luck: f32,
+    constraints: ItemCountConstraints<12>,
+    matches: Option<bool>,
+}
+
+impl SimpleV0ProblemBruteCalculation for TreasureCountsComputation {
+    fn go(&mut self) {
+        self.matches = Some(
+            generates_at(self.seed, self.chunk)
+                && compare_buried_treasure_counts(
+                    self.seed,
+                    self.chunk,
+                    self.luck,
+                    &self.constraints,
+                ),
+        );
+    }
+
+    fn is_done(&self) -> bool {
+        self.matches.is_some()
+    }
+
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    fn is_failed(&self) -> bool {
+        false
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        (self.matches.is_some() as u64, 1)
+    }
+
+    fn stop(&mut self) {}
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        self.matches.map(ParsetimeProtocolValue::Bool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcsci::{
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{ParsetimeProtocolValue, SimpleV0Problem},
+    };
+    use mcseedcracker::self_test::{BURIED_TREASURE_POS, WORLD_SEED};
+
+    use crate::treasure_counts::TreasureCountsProblem;
+
+    fn count(
+        name: &'static str,
+        ctor: &'static str,
+        arg: ParsetimeProtocolValue<'static>,
+    ) -> ParsetimeProtocolValue<'static> {
+        ParsetimeProtocolValue::Tuple(
+            None,
+            vec![
+                ParsetimeProtocolValue::String(StringOrSlice::Sl(name)),
+                ParsetimeProtocolValue::Enumeration(
+                    None,
+                    StringOrSlice::Sl(ctor),
+                    Some(Box::new(arg)),
+                ),
+            ],
+        )
+    }
+
+    fn check(x: i32, counts: Vec<ParsetimeProtocolValue<'static>>) -> Result<Option<bool>, String> {
+        let args = vec![
+            (
+                StringOrSlice::Sl("seed"),
+                ParsetimeProtocolValue::I64(WORLD_SEED),
+            ),
+            (StringOrSlice::Sl("x"), ParsetimeProtocolValue::I32(x)),
+            (
+                StringOrSlice::Sl("z"),
+                ParsetimeProtocolValue::I16(BURIED_TREASURE_POS.1 as i16),
+            ),
+            (
+                StringOrSlice::Sl("counts"),
+                ParsetimeProtocolValue::List(None, counts),
+            ),
+        ];
+        let mut computation = TreasureCountsProblem
+            .setup(VecOrSlice::V(args))
+            .map_err(|err| match err {
+                ParsetimeProtocolValue::String(s) => s.as_slice().to_string(),
+                _ => "not a string".to_string(),
+            })?;
+        computation.go();
+        Ok(match computation.result() {
+            Some(ParsetimeProtocolValue::Bool(matches)) => Some(matches),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_buried_treasure_counts_problem() {
+        let iron = |bound| count("Iron Ingot", "AtLeast", ParsetimeProtocolValue::I8(bound));
+        let cod = count(
+            "minecraft:cooked_cod",
+            "Between",
+            ParsetimeProtocolValue::Tuple(
+                None,
+                vec![ParsetimeProtocolValue::I8(6), ParsetimeProtocolValue::I8(8)],
+            ),
+        );
+        let x = BURIED_TREASURE_POS.0;
+
+        assert_eq!(check(x, vec![iron(9), cod.clone()]), Ok(Some(true)));
+        assert_eq!(check(x, vec![iron(10), cod.clone()]), Ok(Some(false)));
+        // no buried treasure one chunk away
+        assert_eq!(check(x + 16, vec![iron(9)]), Ok(Some(false)));
+
+        assert_eq!(
+            check(
+                x,
+                vec![count("Dirt", "AtLeast", ParsetimeProtocolValue::I8(1))]
+            ),
+            Err("Invalid argument value".to_string())
+        );
+        assert_eq!(
+            check(
+                x,
+                vec![count("TNT", "Exactly", ParsetimeProtocolValue::I8(50))]
+            ),
+            Err("Impossible item count".to_string())
+        );
+        assert!(check(40_000_000, vec![iron(9)]).is_err());
+    }
+}
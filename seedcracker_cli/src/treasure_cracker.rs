@@ -0,0 +1,495 @@
+use mcsci::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+use mcseedcracker::{
+    cooperative::SearchOptions,
+    expr::EvidenceExpr,
+    features::buried_treasure::{
+        build_fast_inventory_compare_context, get_loot_table, item_registry,
+    },
+    loot_table::SingleChest,
+    math::Math,
+    search::{StructureData, StructureSeedSearchData},
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    chest_value::ChestProtocolValue,
+    condition::parse_condition,
+    job::{BackgroundJob, JobControl},
+    parse_pillar_args,
+    treasure_counts::as_i32,
+};
+
+/// Candidates of one pillar seed: the lower 16 bits of the RNG state are the pillar seed
+const CANDIDATES_PER_PILLAR_SEED: u64 = 1 << 32;
+
+const TREASURE_CRACKER_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("chunk_x"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("chunk_z"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("x"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("z"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("luck"),
+        optional: true,
+        argtype: StringOrSlice::Sl("f32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("pillar_seed"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("chest"),
+        optional: false,
+        argtype: StringOrSlice::Sl("single_chest"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("condition"),
+        optional: true,
+        argtype: StringOrSlice::Sl("evidence_expr"),
+    },
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl("threads"),
+        optional: true,
+        argtype: StringOrSlice::Sl("i32"),
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct TreasureCrackingProblem {
+    args: Vec<V0ProblemArgumentDescription<'static>>,
+}
+
+impl Default for TreasureCrackingProblem {
+    fn default() -> Self {
+        let mut args = TREASURE_CRACKER_ARGS.to_vec();
+        for i in 0..10 {
+            args.push(V0ProblemArgumentDescription {
+                name: StringOrSlice::St(format!("pillar{}height", i)),
+                optional: true,
+                argtype: StringOrSlice::Sl("pillar_height_hint"),
+            });
+            args.push(V0ProblemArgumentDescription {
+                name: StringOrSlice::St(format!("pillar{}caged", i)),
+                optional: true,
+                argtype: StringOrSlice::Sl("pillar_caged_status"),
+            });
+        }
+        Self { args }
+    }
+}
+
+impl SimpleV0Problem for TreasureCrackingProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl("buried-treasure-cracker")
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(
+            "Cracks the structure seeds whose buried treasure in the chunk (chunk_x, chunk_z), or at the block (x, z), holds the given chest, and that satisfy the optional condition. The biome samples of the condition are unknown to a structure seed: only the seeds for which it can't hold whatever the biomes are dropped. Each pillar seed takes a search of 2^32 seeds: give pillar_seed, or the pillars to search the pillar seeds they match, every pillar seed is searched otherwise. The result is the list of structure seeds found.",
+        )
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(&self.args)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        let invalid_value =
+            || ParsetimeProtocolValue::String(StringOrSlice::Sl("Invalid argument value"));
+        let error = |message: String| ParsetimeProtocolValue::String(StringOrSlice::St(message));
+        let (pillar_args, args): (Vec<_>, Vec<_>) =
+            args.as_slice().iter().cloned().partition(|(name, _)| {
+                name.as_slice().starts_with("pillar") && name.as_slice() != "pillar_seed"
+            });
+        let pillars = parse_pillar_args(&pillar_args)?;
+
+        let registry = item_registry();
+        let (mut chunk, mut block, mut luck) = ([None, None], [None, None], 0.0);
+        let (mut pillar_seed, mut chest, mut condition) = (None, None, None);
+        let mut search_options = SearchOptions::DEFAULT;
+        for (name, value) in args.iter() {
+            match (name.as_slice(), value) {
+                ("chunk_x", v) => chunk[0] = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("chunk_z", v) => chunk[1] = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("x", v) => block[0] = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("z", v) => block[1] = Some(as_i32(v).ok_or_else(invalid_value)?),
+                ("luck", ParsetimeProtocolValue::F32(v)) => luck = *v,
+                ("luck", ParsetimeProtocolValue::F64(v)) => luck = *v as f32,
+                ("pillar_seed", v) => match as_i32(v) {
+                    Some(seed @ 0..65536) => pillar_seed = Some(seed as i64),
+                    _ => return Err(invalid_value()),
+                },
+                ("chest", v) => {
+                    chest = Some(
+                        SingleChest::from_protocol_value(v, |name| registry.get(name))
+                            .map_err(|err| error(format!("Invalid chest: {}", err)))?,
+                    );
+                }
+                ("condition", v) => condition = Some(parse_condition(v).map_err(error)?),
+                ("threads", v) => match as_i32(v) {
+                    Some(threads @ 1..) => search_options.threads = Some(threads as usize),
+                    _ => return Err(invalid_value()),
+                },
+                ("luck", _) => return Err(invalid_value()),
+                _ => {
+                    return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                        "Invalid argument name",
+                    )));
+                }
+            }
+        }
+
+        let block = match (chunk, block) {
+            ([Some(x), Some(z)], [None, None]) => (x.saturating_mul(16), z.saturating_mul(16)),
+            ([None, None], [Some(x), Some(z)]) => (x, z),
+            _ => {
+                return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                    "Give either chunk_x and chunk_z or x and z",
+                )));
+            }
+        };
+        let chunk = Math::checked_block_coords_to_chunk_coords(block)
+            .map_err(|err| error(err.to_string()))?;
+        let Some(chest) = chest else {
+            return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Missing argument",
+            )));
+        };
+        let contents = build_fast_inventory_compare_context(chest, &get_loot_table())
+            .map_err(|err| error(format!("Invalid chest: {}", err)))?;
+
+        let pillar_seeds = match pillar_seed {
+            Some(_) if !pillar_args.is_empty() => {
+                return Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                    "Give either pillar_seed or the pillars",
+                )));
+            }
+            Some(pillar_seed) => vec![pillar_seed],
+            None => {
+                pillars.validate().map_err(|err| error(err.to_string()))?;
+                let mut seeds = Vec::new();
+                pillars.for_each_matching(|seed, result| {
+                    if !result.is_impossible_match() {
+                        seeds.push(seed);
+                    }
+                });
+                seeds
+            }
+        };
+
+        Ok(Box::new(TreasureCrackingComputation {
+            pillar_seeds,
+            data: vec![StructureData::BuriedTreasureContents {
+                chunk_x: chunk.0,
+                chunk_z: chunk.1,
+                luck,
+                contents,
+                loot_table: None,
+            }],
+            condition,
+            search_options,
+            job: BackgroundJob::default(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TreasureCrackingComputation {
+    pillar_seeds: Vec<i64>,
+    data: Vec<StructureData>,
+    condition: Option<EvidenceExpr>,
+    /// Only the threads are used, the progress is always counted per slice of 2^16 candidates
+    search_options: SearchOptions,
+    /// Its progress counts the candidates searched, out of 2^32 per pillar seed
+    job: BackgroundJob<Vec<i64>>,
+}
+
+/// The structure seeds of one pillar seed, in the order of the RNG states, None once stopped
+fn search_pillar_seed(search: &StructureSeedSearchData, control: &JobControl) -> Option<Vec<i64>> {
+    let seeds = (0i64..65536)
+        .into_par_iter()
+        .map(|state_hi| {
+            if control.is_stopped() {
+                return None;
+            }
+            let seeds = search.search_bounded(state_hi..state_hi + 1);
+            control.found(seeds.iter().map(|seed| ParsetimeProtocolValue::I64(*seed)));
+            control.advance(65536);
+            Some(seeds)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(seeds.concat())
+}
+
+impl SimpleV0ProblemBruteCalculation for TreasureCrackingComputation {
+    fn go(&mut self) {
+        let pillar_seeds = self.pillar_seeds.clone();
+        let (data, condition) = (self.data.clone(), self.condition.clone());
+        let search_options = self.search_options;
+        self.job.start(move |control| {
+            let mut seeds = Vec::new();
+            for pillar_seed in pillar_seeds {
+                let search = StructureSeedSearchData::new(pillar_seed, data.clone(), u16::MAX)
+                    .with_condition(condition.clone());
+                seeds.extend(search_options.install(|| search_pillar_seed(&search, control))?);
+            }
+            Some(seeds)
+        });
+    }
+
+    fn is_done(&self) -> bool {
+        self.job.is_done()
+    }
+
+    fn is_running(&self) -> bool {
+        self.job.is_running()
+    }
+
+    fn is_failed(&self) -> bool {
+        self.job.is_failed()
+    }
+
+    fn progress(&self) -> (u64, u64) {
+        (
+            self.job.progress(),
+            self.pillar_seeds.len() as u64 * CANDIDATES_PER_PILLAR_SEED,
+        )
+    }
+
+    /// The search stops at the next slice of 2^16 candidates, without a result
+    fn stop(&mut self) {
+        self.job.stop();
+    }
+
+    fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+        self.job.with_output(|seeds| {
+            ParsetimeProtocolValue::List(
+                None,
+                seeds
+                    .iter()
+                    .map(|seed| ParsetimeProtocolValue::I64(*seed))
+                    .collect(),
+            )
+        })
+    }
+
+    /// The structure seeds as each slice of the search finds them, in no particular order
+    fn take_new_results(&mut self) -> Vec<ParsetimeProtocolValue<'static>> {
+        self.job.take_found()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use mcsci::{
+        current_impl::MCSCIProtocol,
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation},
+    };
+    use mcseedcracker::{
+        features::buried_treasure::item_registry,
+        self_test::{self, BURIED_TREASURE_POS, PILLAR_SEED, STRUCTURE_SEED},
+    };
+
+    use crate::{
+        Extension, chest_value::ChestProtocolValue, emit::setup_problem_command,
+        job::wait_until_finished, treasure_cracker::TreasureCrackingProblem,
+    };
+
+    fn chest() -> ParsetimeProtocolValue<'static> {
+        self_test::buried_treasure_contents()
+            .to_protocol_value(&item_registry())
+            .unwrap()
+    }
+
+    fn setup(
+        args: Vec<(&'static str, ParsetimeProtocolValue<'static>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, String> {
+        let args = args
+            .into_iter()
+            .map(|(name, value)| (StringOrSlice::Sl(name), value))
+            .collect();
+        TreasureCrackingProblem::default()
+            .setup(VecOrSlice::V(args))
+            .map_err(|err| match err {
+                ParsetimeProtocolValue::String(s) => s.as_slice().to_string(),
+                _ => "not a string".to_string(),
+            })
+    }
+
+    #[test]
+    fn test_setup_validation() {
+        let chunk = |x: i32, z: i32| {
+            vec![
+                ("chunk_x", ParsetimeProtocolValue::I32(x)),
+                ("chunk_z", ParsetimeProtocolValue::I32(z)),
+                ("chest", chest()),
+            ]
+        };
+        assert!(setup(chunk(25, 50)).is_ok());
+
+        let mut args = chunk(25, 50);
+        args.push(("x", ParsetimeProtocolValue::I32(409)));
+        assert_eq!(
+            setup(args).unwrap_err(),
+            "Give either chunk_x and chunk_z or x and z"
+        );
+        assert!(setup(chunk(2_000_000, 50)).is_err());
+        assert_eq!(
+            setup(chunk(25, 50)[..2].to_vec()).unwrap_err(),
+            "Missing argument"
+        );
+
+        let mut args = chunk(25, 50);
+        args.push(("pillar_seed", ParsetimeProtocolValue::I32(65536)));
+        assert_eq!(setup(args).unwrap_err(), "Invalid argument value");
+
+        let mut args = chunk(25, 50);
+        args.push(("threads", ParsetimeProtocolValue::I32(2)));
+        assert!(setup(args).is_ok());
+        let mut args = chunk(25, 50);
+        args.push(("threads", ParsetimeProtocolValue::I32(0)));
+        assert_eq!(setup(args).unwrap_err(), "Invalid argument value");
+
+        let mut args = chunk(25, 50);
+        args[2].1 = ParsetimeProtocolValue::List(None, vec![]);
+        assert_eq!(
+            setup(args).unwrap_err(),
+            "Invalid chest: the chest has 0 values instead of 3"
+        );
+    }
+
+    #[test]
+    fn test_stop_while_running() {
+        let mut computation = setup(vec![
+            ("x", ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.0)),
+            ("z", ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.1)),
+            ("chest", chest()),
+        ])
+        .unwrap();
+        // no pillars, every pillar seed is searched
+        assert_eq!(computation.progress(), (0, 65536 << 32));
+        assert!(computation.result().is_none());
+
+        computation.go();
+        let start = Instant::now();
+        while computation.progress().0 == 0 {
+            assert!(start.elapsed() < Duration::from_secs(60), "no progress");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(computation.is_running());
+        let (searched, total) = computation.progress();
+        assert!(searched < total);
+
+        computation.stop();
+        wait_until_finished(computation.as_ref());
+        assert!(!computation.is_done());
+        assert!(!computation.is_failed());
+        assert!(computation.result().is_none());
+    }
+
+    #[test]
+    fn test_protocol_stop() {
+        let command = setup_problem_command(
+            "buried-treasure-cracker",
+            &[
+                (
+                    StringOrSlice::Sl("chunk_x"),
+                    ParsetimeProtocolValue::I32(25),
+                ),
+                (
+                    StringOrSlice::Sl("chunk_z"),
+                    ParsetimeProtocolValue::I32(50),
+                ),
+                (StringOrSlice::Sl("chest"), chest()),
+            ],
+        )
+        .unwrap();
+        let input = format!("hello\n{}\ngo\nversion\nstop\n", command);
+
+        let mut protocol = MCSCIProtocol::default();
+        protocol.register_extension(Extension::new());
+        let mut output = Vec::new();
+        protocol
+            .server_loop(&mut input.as_bytes(), &mut output, &mut std::io::sink())
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("unexpected \"computation running\"\n"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("result"), "{}", output);
+    }
+
+    #[test]
+    fn test_protocol_search() {
+        let command = setup_problem_command(
+            "buried-treasure-cracker",
+            &[
+                (
+                    StringOrSlice::Sl("x"),
+                    ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.0),
+                ),
+                (
+                    StringOrSlice::Sl("z"),
+                    ParsetimeProtocolValue::I32(BURIED_TREASURE_POS.1),
+                ),
+                (
+                    StringOrSlice::Sl("pillar_seed"),
+                    ParsetimeProtocolValue::I32(PILLAR_SEED as i32),
+                ),
+                (StringOrSlice::Sl("chest"), chest()),
+            ],
+        )
+        .unwrap();
+        let input = format!("hello\n{}\ngo\n", command);
+
+        let mut protocol = MCSCIProtocol::default();
+        protocol.register_extension(Extension::new());
+        let mut output = Vec::new();
+        protocol
+            .server_loop(&mut input.as_bytes(), &mut output, &mut std::io::sink())
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("setup-ok\n"), "{}", output);
+        assert!(
+            output.ends_with(&format!(
+                "result-partial i64({0})\nresult [i64({0})]\n",
+                STRUCTURE_SEED
+            )),
+            "{}",
+            output
+        );
+    }
+}
@@ -1,28 +1,55 @@
-use std::collections::VecDeque;
+use std::{sync::Arc, thread::JoinHandle};
 
 use mcseedcracker::{
+    analysis::{DEFAULT_BIOME_ROW_CAP, WorldDescriberHandle},
+    candidates::{StructureSeedCandidate, WorldSeedCandidate},
+    cooperative::{Cooperation, SearchOptions},
     features::end_pillars::{PartialEndPillars, PillarMatchResult},
+    fingerprint::EvidenceFingerprint,
+    loot_table::{CountBound, InvalidObservation, LootTable, SingleChest, SlotThreshold},
+    math::OutOfWorldBorder,
+    pipeline::{
+        BuriedTreasureCountEvidence, BuriedTreasureEvidence, CrackingEvidence, CrackingPipeline,
+        DEFAULT_MAX_TREASURE_AREA, EarlyHits, ObservedAt, OversizedArea, PipelineOptions,
+        Reverification, TreasureAreaCost,
+    },
+    rejections::RejectionStats,
+    schedule::{EarlyTermination, ScheduledSlice, StructureStageScheduler},
     search::{
-        StructureSeedSearchData, StructureSeedSearcherHandle, WorldSeedSearchData,
+        SearchHandle, StructureSeedSearchData, WorldExtraData, WorldSeedFit, WorldSeedSearchData,
         WorldSeedSearcherHandle,
     },
+    templates::{EVIDENCE_TEMPLATES, EvidenceSection, EvidenceTemplate},
+    world_seed::SeedOrigin,
 };
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{Event, KeyCode},
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
-    widgets::{Block, Borders, Tabs, Widget},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Tabs, Widget},
 };
 
 use crate::tui::{
     EventContext, EventResult, FullComponent,
+    components::observed_at::{ObservedAtAction, ObservedAtEditor},
+    evidence::EvidenceRegistry,
+    get_area_centered,
+    history::{EvidenceHistory, EvidenceSnapshot},
+    hitmap::HitMap,
+    job_queue::JobQueue,
+    memory::{MemoryBudget, MemoryConsumer},
     tabs::{
+        bastion::{BastionTab, BastionTabSharedData},
         biomes::{BiomesTab, BiomesTabSharedData},
         buried_treasure::{BuriedTreasureTab, BuriedTreasureTabSharedData},
+        decorations::{DecorationsTab, DecorationsTabSharedData},
         end_pillars::EndPillarsTab,
         output::OutputTab,
+        structures::{StructuresTab, StructuresTabSharedData},
     },
+    tutorial::Tutorial,
 };
 
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
@@ -32,17 +59,24 @@ pub struct ApplicationTabs {
     pub end_pillars: ApplicationTab<EndPillarsTab>,
     pub buried_treasure: ApplicationTab<BuriedTreasureTab>,
     pub biomes: ApplicationTab<BiomesTab>,
+    pub decorations: ApplicationTab<DecorationsTab>,
+    pub bastion: ApplicationTab<BastionTab>,
+    pub structures: ApplicationTab<StructuresTab>,
     pub output: ApplicationTab<OutputTab>,
 }
 
 impl ApplicationTabs {
-    const SIZE: usize = 4;
+    const SIZE: usize = 7;
+    pub const OUTPUT: usize = 6;
 
     pub fn titles(&self) -> [String; Self::SIZE] {
         [
             self.end_pillars.title.clone(),
             self.buried_treasure.title.clone(),
             self.biomes.title.clone(),
+            self.decorations.title.clone(),
+            self.bastion.title.clone(),
+            self.structures.title.clone(),
             self.output.title.clone(),
         ]
     }
@@ -58,7 +92,10 @@ impl ApplicationTabs {
             0 => self.end_pillars.component.render(area, buf, shared),
             1 => self.buried_treasure.component.render(area, buf, shared),
             2 => self.biomes.component.render(area, buf, shared),
-            3 => self.output.component.render(area, buf, shared),
+            3 => self.decorations.component.render(area, buf, shared),
+            4 => self.bastion.component.render(area, buf, shared),
+            5 => self.structures.component.render(area, buf, shared),
+            6 => self.output.component.render(area, buf, shared),
             _ => {}
         }
     }
@@ -80,7 +117,16 @@ impl ApplicationTabs {
                 .component
                 .handle_event(event, context, shared),
             2 => self.biomes.component.handle_event(event, context, shared),
-            3 => self.output.component.handle_event(event, context, shared),
+            3 => self
+                .decorations
+                .component
+                .handle_event(event, context, shared),
+            4 => self.bastion.component.handle_event(event, context, shared),
+            5 => self
+                .structures
+                .component
+                .handle_event(event, context, shared),
+            6 => self.output.component.handle_event(event, context, shared),
             _ => EventResult::BubbleUp(event),
         }
     }
@@ -90,7 +136,10 @@ impl ApplicationTabs {
             0 => self.end_pillars.component.on_focus(shared),
             1 => self.buried_treasure.component.on_focus(shared),
             2 => self.biomes.component.on_focus(shared),
-            3 => self.output.component.on_focus(shared),
+            3 => self.decorations.component.on_focus(shared),
+            4 => self.bastion.component.on_focus(shared),
+            5 => self.structures.component.on_focus(shared),
+            6 => self.output.component.on_focus(shared),
             _ => {}
         }
     }
@@ -100,11 +149,59 @@ impl ApplicationTabs {
             0 => self.end_pillars.component.on_unfocus(shared),
             1 => self.buried_treasure.component.on_unfocus(shared),
             2 => self.biomes.component.on_unfocus(shared),
-            3 => self.output.component.on_unfocus(shared),
+            3 => self.decorations.component.on_unfocus(shared),
+            4 => self.bastion.component.on_unfocus(shared),
+            5 => self.structures.component.on_unfocus(shared),
+            6 => self.output.component.on_unfocus(shared),
             _ => {}
         }
     }
 
+    pub fn apply_template(
+        &mut self,
+        template: &EvidenceTemplate,
+        shared: &mut SharedApplicationState,
+    ) {
+        self.end_pillars.component.apply_template(template, shared);
+        self.buried_treasure
+            .component
+            .apply_template(template, shared);
+        self.biomes.component.apply_template(template, shared);
+        self.decorations.component.apply_template(template, shared);
+        self.bastion.component.apply_template(template, shared);
+        self.structures.component.apply_template(template, shared);
+        self.output.component.apply_template(template, shared);
+    }
+
+    pub fn sync_from_shared(&mut self, shared: &mut SharedApplicationState) {
+        self.end_pillars.component.sync_from_shared(shared);
+        self.buried_treasure.component.sync_from_shared(shared);
+        self.biomes.component.sync_from_shared(shared);
+        self.decorations.component.sync_from_shared(shared);
+        self.bastion.component.sync_from_shared(shared);
+        self.structures.component.sync_from_shared(shared);
+        self.output.component.sync_from_shared(shared);
+    }
+
+    /// Evidence sections edited in each tab
+    pub const fn sections(idx: usize) -> &'static [EvidenceSection] {
+        match idx {
+            0 => &[EvidenceSection::EndPillars],
+            1 => &[EvidenceSection::BuriedTreasure],
+            2 => &[
+                EvidenceSection::OverworldBiomes,
+                EvidenceSection::NetherBiomes,
+            ],
+            3 => &[EvidenceSection::Decorations],
+            4 => &[EvidenceSection::BastionChest],
+            _ => &[],
+        }
+    }
+
+    pub fn tab_of(section: EvidenceSection) -> Option<usize> {
+        (0..Self::SIZE).find(|idx| Self::sections(*idx).contains(&section))
+    }
+
     pub const fn size(&self) -> usize {
         Self::SIZE
     }
@@ -115,12 +212,18 @@ pub enum StructureSeedSimResultType {
     Success,
     TooManySeeds,
     Cancelled,
+    /// Left out once the early termination policy triggered
+    Skipped(EarlyTermination),
+    /// Taken out of the job queue before it was searched
+    Removed,
 }
 
 pub struct PillarSeedStructureSim {
     pub pillar_seed: i64,
     pub result: StructureSeedSimResultType,
-    pub structure_seeds: Vec<i64>,
+    pub structure_seeds: Vec<StructureSeedCandidate>,
+    /// Why the sampled candidates were rejected, nothing checked if the search didn't run
+    pub rejections: RejectionStats,
 }
 
 pub struct StructureSeedSim {
@@ -130,45 +233,429 @@ pub struct StructureSeedSim {
 
 pub struct StructureSeedSimData {
     pub outdated_data: bool,
+    /// Fingerprint of the evidence the simulation was started with
+    pub evidence: Option<EvidenceFingerprint>,
     pub data: Option<StructureSeedSim>,
+    /// World seeds found for the structure seeds before the world seed search
+    pub early_hits: EarlyHits,
+    /// Order of the queued jobs and the match counts so far, None when nothing was queued
+    pub schedule: Option<StructureStageScheduler>,
+    /// Slice of the schedule the current searcher is searching
+    pub searching: Option<ScheduledSlice>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum WorldSeedSimResultType {
     Success,
     TooManySeeds,
+    /// Taken out of the job queue before it was searched
+    Removed,
 }
 
 pub struct StructureSeedWorldSim {
     pub structure_seed: i64,
     pub result: WorldSeedSimResultType,
-    pub world_seeds: Vec<i64>,
+    pub world_seeds: Vec<WorldSeedCandidate>,
+    /// Candidates treated as non-matching because a biome lookup failed
+    pub skipped_candidates: u64,
+    /// How well the biome samples fit each of `world_seeds`
+    pub fits: Vec<WorldSeedFit>,
+    pub rejections: RejectionStats,
 }
 
 pub struct WorldSeedSimData {
     pub count_seeds: i64,
     pub per_structure: Vec<StructureSeedWorldSim>,
+    /// Number of structure seeds found, when there were too many and only the best supported ones are searched
+    pub truncated_from: Option<usize>,
+    /// The re-verification of the found structure seeds before the search
+    pub reverification: Option<Reverification>,
+    /// What the search was started from, see [`SharedApplicationState::world_sim_evidence`]
+    pub started_from: Option<WorldSimEvidence>,
 }
 
+/// The structure seed results and the world evidence a world seed search was started from
+pub type WorldSimEvidence = (Option<EvidenceFingerprint>, Vec<WorldExtraData>);
+
 pub struct SharedApplicationState {
     pub pillar_data: PartialEndPillars,
-    pub last_pillar_sim: Option<(PartialEndPillars, Vec<(i64, PillarMatchResult)>)>,
+    /// The pillar observation is kept but left out of the evidence while false
+    pub pillars_enabled: bool,
+    pub pillars_observed_at: Option<ObservedAt>,
+    pub last_pillar_sim: Option<(EvidenceFingerprint, Vec<(i64, PillarMatchResult)>)>,
 
     pub max_pillars_to_simulate: usize,
     pub max_structure_seeds_to_simulate: usize,
     pub max_world_seeds_per_structure_seed: u16,
 
     pub buried_treasure_data: BuriedTreasureTabSharedData,
+    pub buried_treasure_loot_table: Option<Arc<LootTable>>,
 
     pub last_structure_seed_sim: StructureSeedSimData,
-    pub current_structure_seed_searcher: Option<StructureSeedSearcherHandle>,
-    pub structure_seed_search_jobs: VecDeque<StructureSeedSearchData>,
+    pub current_structure_seed_searcher: Option<SearchHandle>,
+    pub structure_seed_search_jobs: JobQueue<StructureSeedSearchData>,
 
     pub biome_data: BiomesTabSharedData,
+    pub decoration_data: DecorationsTabSharedData,
+    pub bastion_data: BastionTabSharedData,
+    pub structure_data: StructuresTabSharedData,
     pub current_world_seed_searcher: Option<WorldSeedSearcherHandle>,
-    pub world_seed_search_jobs: VecDeque<WorldSeedSearchData>,
+    pub world_seed_search_jobs: JobQueue<WorldSeedSearchData>,
     pub world_seed_sim: WorldSeedSimData,
     pub is_random_world_seed: bool,
+    /// How the world seed was chosen, the world seed search only tries the seeds it can give
+    pub seed_origin: SeedOrigin,
+    /// Evidence around a found world seed, listed to confirm it
+    pub world_description: Option<WorldDescriberHandle>,
+    /// Started with [C] in the output tab, its output is the status line to show once it is done
+    pub calibration: Option<JoinHandle<(String, bool)>>,
+
+    /// Evidence edits, undone with [CTRL + Z] and redone with [CTRL + Y]
+    pub history: EvidenceHistory,
+
+    pub hit_map: HitMap,
+    /// Sizes of the pillar cache and of the results, see [`SharedApplicationState::enforce_memory_cap`]
+    pub memory: MemoryBudget,
+    /// Searches run in small chunks so the interface stays responsive, see [`Cooperation`]
+    pub cooperation: Option<Cooperation>,
+    /// Threads of the searches and how often they report progress
+    pub search_options: SearchOptions,
+    /// Accepts buried treasures matching only most of the observed slots
+    pub slot_threshold: Option<SlotThreshold>,
+    /// Biome rows of a panel past this many get a diminishing returns hint
+    pub biome_row_cap: usize,
+    /// Checks the typed world seeds of each structure seed as soon as it is found
+    pub early_typed_hits: bool,
+    /// When the structure seed search stops before the remaining pillar seeds
+    pub early_termination: EarlyTermination,
+    /// Largest buried treasure area, in chunks
+    pub max_treasure_area: u64,
+    /// Template picked on the start screen, its guidance is shown under the tabs
+    pub active_template: Option<&'static EvidenceTemplate>,
+    /// What each evidence tab adds to the searches
+    pub evidence_registry: EvidenceRegistry,
+}
+
+impl SharedApplicationState {
+    pub fn is_evidence_empty(&self) -> bool {
+        let bt = &self.buried_treasure_data;
+        self.pillar_data == PartialEndPillars::new()
+            && !bt.usable
+            && bt.pos_x == 0
+            && bt.pos_z == 0
+            && bt.contents == SingleChest::new()
+            && bt.kept.is_empty()
+            && self
+                .biome_data
+                .overworld_biomes
+                .as_overworld()
+                .is_none_or(|v| v.is_empty())
+            && self
+                .biome_data
+                .nether_biomes
+                .as_nether()
+                .is_none_or(|v| v.is_empty())
+            && self.decoration_data.decorations.is_empty()
+            && self.bastion_data == BastionTabSharedData::default()
+            && self.structure_data.structures.is_empty()
+    }
+
+    /// The pillar observation, or no observation at all while it is disabled
+    #[inline]
+    pub fn enabled_pillar_data(&self) -> PartialEndPillars {
+        if self.pillars_enabled {
+            self.pillar_data
+        } else {
+            PartialEndPillars::new()
+        }
+    }
+
+    /// Valid pillar seeds for the current pillar data, recomputed only when the data changes
+    pub fn pillar_sim(&mut self) -> &[(i64, PillarMatchResult)] {
+        let pillars = self.enabled_pillar_data();
+        let fingerprint = EvidenceFingerprint::of(&pillars);
+        if !matches!(&self.last_pillar_sim, Some((f, _)) if *f == fingerprint) {
+            let mut results = Vec::new();
+            pillars.for_each_matching(|seed, result| {
+                if !matches!(result, PillarMatchResult::PossibleMatch(v) if v == 0.0) {
+                    results.push((seed, result));
+                }
+            });
+            self.last_pillar_sim = Some((fingerprint, results));
+        } else {
+            self.memory.touch(MemoryConsumer::PillarCache);
+        }
+        &self.last_pillar_sim.as_ref().unwrap().1
+    }
+
+    /// Approximate size in bytes of a store, from the lengths of its collections
+    pub fn memory_usage(&self, consumer: MemoryConsumer) -> usize {
+        match consumer {
+            MemoryConsumer::PillarCache => self.last_pillar_sim.as_ref().map_or(0, |(_, sim)| {
+                sim.capacity() * size_of::<(i64, PillarMatchResult)>()
+            }),
+            MemoryConsumer::StructureSeedResults => {
+                self.last_structure_seed_sim.data.as_ref().map_or(0, |sim| {
+                    sim.per_pillar
+                        .iter()
+                        .map(|p| {
+                            size_of::<PillarSeedStructureSim>()
+                                + p.structure_seeds.capacity() * size_of::<StructureSeedCandidate>()
+                                + p.structure_seeds
+                                    .iter()
+                                    .flat_map(|s| s.evidence_annotations.iter())
+                                    .map(|a| size_of::<String>() + a.capacity())
+                                    .sum::<usize>()
+                        })
+                        .sum()
+                })
+            }
+            MemoryConsumer::WorldSeedResults => self
+                .world_seed_sim
+                .per_structure
+                .iter()
+                .map(|s| {
+                    size_of::<StructureSeedWorldSim>()
+                        + s.world_seeds.capacity() * size_of::<WorldSeedCandidate>()
+                        + s.fits
+                            .iter()
+                            .map(|f| size_of::<WorldSeedFit>() + f.margins.capacity() * 4)
+                            .sum::<usize>()
+                })
+                .sum(),
+        }
+    }
+
+    /// Measures the stores again and drops the ones [`MemoryBudget::evictions`] picks, call it after the searches
+    pub fn enforce_memory_cap(&mut self) {
+        for consumer in [
+            MemoryConsumer::PillarCache,
+            MemoryConsumer::StructureSeedResults,
+            MemoryConsumer::WorldSeedResults,
+        ] {
+            self.memory.update(consumer, self.memory_usage(consumer));
+        }
+
+        for consumer in self.memory.evictions() {
+            match consumer {
+                MemoryConsumer::PillarCache => self.last_pillar_sim = None,
+                MemoryConsumer::StructureSeedResults => {
+                    self.last_structure_seed_sim = StructureSeedSimData {
+                        outdated_data: true,
+                        evidence: None,
+                        data: None,
+                        early_hits: EarlyHits::default(),
+                        schedule: None,
+                        searching: None,
+                    };
+                }
+                MemoryConsumer::WorldSeedResults => {
+                    self.world_seed_sim = WorldSeedSimData {
+                        count_seeds: 0,
+                        per_structure: Vec::new(),
+                        truncated_from: None,
+                        reverification: None,
+                        started_from: None,
+                    };
+                }
+            }
+            if !consumer.is_cache() {
+                self.memory.warning = Some(format!(
+                    "Dropped the {} to stay under the memory cap",
+                    consumer.name()
+                ));
+            }
+            self.memory.update(consumer, 0);
+        }
+    }
+
+    /// The edited treasure first, then the kept ones
+    pub fn buried_treasure_evidence(&self) -> Vec<BuriedTreasureEvidence> {
+        let bt = &self.buried_treasure_data;
+        // with only item counts given, the chest is unknown rather than empty
+        let chest_known = bt.counts.is_empty() || bt.contents != SingleChest::new();
+        let edited = (bt.usable && chest_known).then(|| bt.edited_treasure());
+        edited.into_iter().chain(bt.kept.iter().cloned()).collect()
+    }
+
+    pub fn buried_treasure_count_evidence(&self) -> Option<BuriedTreasureCountEvidence> {
+        let bt = &self.buried_treasure_data;
+        (bt.usable && !bt.counts.is_empty()).then(|| BuriedTreasureCountEvidence {
+            pos_x: bt.pos_x,
+            pos_z: bt.pos_z,
+            luck: bt.luck,
+            counts: bt.counts.clone(),
+            observed_at: bt.observed_at.clone(),
+        })
+    }
+
+    /// The biome and decoration evidence, without the disabled rows
+    pub fn world_extra_data(&self) -> Vec<WorldExtraData> {
+        self.cracking_evidence().world
+    }
+
+    /// The evidence of every registered tab, see [`EvidenceRegistry`]
+    #[inline]
+    pub fn cracking_evidence(&self) -> CrackingEvidence {
+        self.evidence_registry.cracking_evidence(self)
+    }
+
+    /// When the evidence of the tab was observed, `None` for the tabs without evidence
+    pub fn observed_at_mut(&mut self, tab: usize) -> Option<&mut Option<ObservedAt>> {
+        match tab {
+            0 => Some(&mut self.pillars_observed_at),
+            1 => Some(&mut self.buried_treasure_data.observed_at),
+            2 => Some(&mut self.biome_data.observed_at),
+            3 => Some(&mut self.decoration_data.observed_at),
+            _ => None,
+        }
+    }
+
+    /// Chest contents that their loot table can't generate, checked without building the whole evidence
+    pub fn invalid_observations(&self) -> Vec<InvalidObservation> {
+        CrackingPipeline::invalid_observations(&CrackingEvidence {
+            buried_treasures: self.buried_treasure_evidence(),
+            buried_treasure_loot_table: self.buried_treasure_loot_table.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Item counts that their loot table can't generate
+    pub fn invalid_count_bounds(&self) -> Vec<(usize, CountBound)> {
+        CrackingPipeline::invalid_count_bounds(&CrackingEvidence {
+            buried_treasure_counts: self.buried_treasure_count_evidence(),
+            buried_treasure_loot_table: self.buried_treasure_loot_table.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// The buried treasure position, if it is outside of the world border
+    pub fn invalid_positions(&self) -> Vec<OutOfWorldBorder> {
+        CrackingPipeline::invalid_positions(&CrackingEvidence {
+            buried_treasures: self.buried_treasure_evidence(),
+            buried_treasure_counts: self.buried_treasure_count_evidence(),
+            ..Default::default()
+        })
+    }
+
+    /// The buried treasure area, if it has more chunks than the search allows
+    pub fn oversized_areas(&self) -> Vec<OversizedArea> {
+        CrackingPipeline::oversized_areas(
+            &CrackingEvidence {
+                buried_treasures: self.buried_treasure_evidence(),
+                ..Default::default()
+            },
+            &self.pipeline_options(),
+        )
+    }
+
+    /// What the buried treasure area adds to the structure seed search, `None` without an area
+    pub fn treasure_area_cost(&self) -> Option<TreasureAreaCost> {
+        CrackingPipeline::treasure_area_cost(&CrackingEvidence {
+            buried_treasures: self.buried_treasure_evidence(),
+            ..Default::default()
+        })
+    }
+
+    pub fn pipeline_options(&self) -> PipelineOptions {
+        PipelineOptions {
+            max_pillar_seeds: self.max_pillars_to_simulate,
+            max_structure_seeds: self.max_structure_seeds_to_simulate,
+            max_world_seeds_per_structure_seed: self.max_world_seeds_per_structure_seed,
+            is_random_world_seed: self.is_random_world_seed,
+            origin: self.seed_origin,
+            cooperation: self.cooperation,
+            search: self.search_options,
+            slot_threshold: self.slot_threshold,
+            early_typed_hits: self.early_typed_hits,
+            early_termination: self.early_termination,
+            max_treasure_area: self.max_treasure_area,
+            ..Default::default()
+        }
+    }
+
+    /// Fingerprint of everything the structure seed search depends on
+    pub fn structure_evidence_fingerprint(&self) -> EvidenceFingerprint {
+        let (bt, bastion) = (&self.buried_treasure_data, &self.bastion_data);
+        EvidenceFingerprint::of(&(
+            &self.enabled_pillar_data(),
+            &bt.contents,
+            &bt.counts,
+            bt.pos_x,
+            bt.pos_z,
+            bt.area.then_some((bt.area_corner_x, bt.area_corner_z)),
+            bt.luck.to_bits(),
+            bt.usable,
+            bt.kept
+                .iter()
+                .map(|t| {
+                    (
+                        &t.contents,
+                        t.pos_x,
+                        t.pos_z,
+                        t.area_corner,
+                        t.luck.to_bits(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            (
+                &bastion.contents,
+                bastion.pos_x,
+                bastion.pos_z,
+                bastion.index,
+                bastion.chest_type,
+                bastion.luck.to_bits(),
+                bastion.usable,
+            ),
+        ))
+    }
+
+    /// The first reason the structure seeds can't be searched with the current evidence
+    pub fn structure_search_error(&self) -> Option<String> {
+        if let Some(invalid) = self.invalid_observations().first() {
+            return Some(format!("invalid chest: {}", invalid));
+        }
+        if let Some(invalid) = self.invalid_positions().first() {
+            return Some(format!("buried treasure {}", invalid));
+        }
+        if let Some(oversized) = self.oversized_areas().first() {
+            return Some(format!("buried treasure {}", oversized));
+        }
+        self.bastion_data.error()
+    }
+
+    /// The first biome sample that can't be checked for any seed
+    pub fn world_search_error(&self) -> Option<String> {
+        let version = self.pipeline_options().version;
+        self.world_extra_data()
+            .iter()
+            .flat_map(|d| d.invalid_samples(version))
+            .next()
+            .map(|invalid| format!("invalid biome sample: {}", invalid))
+    }
+
+    /// What a world seed search started now depends on, see [`WorldSeedSimData::started_from`]
+    pub fn world_sim_evidence(&self) -> WorldSimEvidence {
+        (
+            self.last_structure_seed_sim.evidence,
+            self.world_extra_data(),
+        )
+    }
+
+    /// True if the world seed results were searched from other structure seeds or other world evidence
+    pub fn is_world_sim_stale(&self) -> bool {
+        self.world_seed_sim
+            .started_from
+            .as_ref()
+            .is_some_and(|started_from| *started_from != self.world_sim_evidence())
+    }
+
+    /// Marks the structure seed results as outdated if the evidence changed since they were computed
+    pub fn refresh_structure_sim_outdated(&mut self) {
+        if self.last_structure_seed_sim.evidence != Some(self.structure_evidence_fingerprint()) {
+            self.last_structure_seed_sim.outdated_data = true;
+        }
+    }
 }
 
 pub struct ApplicationComponentState {
@@ -177,6 +664,15 @@ pub struct ApplicationComponentState {
 
     pub tabs: ApplicationTabs,
     pub shared: SharedApplicationState,
+
+    /// Selected entry of the start screen template picker, `None` once it was closed
+    pub template_picker: Option<usize>,
+    pub tutorial: Option<Tutorial>,
+    /// Opened with [CTRL + O] on a tab with evidence
+    pub observed_at_editor: Option<ObservedAtEditor>,
+    /// Evidence of a previous run that didn't exit cleanly, offered for restoring until it is answered, see
+    /// [`crate::tui::recovery`]
+    pub recovery: Option<EvidenceSnapshot>,
 }
 
 pub struct ApplicationTab<T: FullComponent> {
@@ -194,35 +690,225 @@ impl ApplicationComponentState {
                     end_pillars: EndPillarsTab::apptab(),
                     buried_treasure: BuriedTreasureTab::apptab(),
                     biomes: BiomesTab::apptab(),
+                    decorations: DecorationsTab::apptab(),
+                    bastion: BastionTab::apptab(),
+                    structures: StructuresTab::apptab(),
                     output: OutputTab::apptab(),
                 }
             },
             shared: SharedApplicationState {
                 pillar_data: PartialEndPillars::new(),
+                pillars_enabled: true,
+                pillars_observed_at: None,
                 last_pillar_sim: None,
                 max_pillars_to_simulate: 5,
                 max_structure_seeds_to_simulate: 5,
                 max_world_seeds_per_structure_seed: 5,
                 buried_treasure_data: BuriedTreasureTabSharedData::default(),
+                buried_treasure_loot_table: None,
                 last_structure_seed_sim: StructureSeedSimData {
                     outdated_data: true,
+                    evidence: None,
                     data: None,
+                    early_hits: EarlyHits::default(),
+                    schedule: None,
+                    searching: None,
                 },
                 current_structure_seed_searcher: None,
-                structure_seed_search_jobs: VecDeque::new(),
+                structure_seed_search_jobs: JobQueue::new(),
                 biome_data: BiomesTabSharedData::default(),
+                decoration_data: DecorationsTabSharedData::default(),
+                bastion_data: BastionTabSharedData::default(),
+                structure_data: StructuresTabSharedData::default(),
                 current_world_seed_searcher: None,
-                world_seed_search_jobs: VecDeque::new(),
+                world_seed_search_jobs: JobQueue::new(),
                 world_seed_sim: WorldSeedSimData {
                     count_seeds: 0,
                     per_structure: Vec::new(),
+                    truncated_from: None,
+                    reverification: None,
+                    started_from: None,
                 },
                 is_random_world_seed: true,
+                seed_origin: SeedOrigin::Any,
+                world_description: None,
+                calibration: None,
+                history: EvidenceHistory::new(),
+                hit_map: HitMap::new(),
+                memory: MemoryBudget::new(None),
+                cooperation: None,
+                search_options: SearchOptions::DEFAULT,
+                slot_threshold: None,
+                biome_row_cap: DEFAULT_BIOME_ROW_CAP,
+                early_typed_hits: false,
+                early_termination: EarlyTermination::Never,
+                max_treasure_area: DEFAULT_MAX_TREASURE_AREA,
+                active_template: None,
+                evidence_registry: EvidenceRegistry::new(),
             },
+            template_picker: Some(0),
+            tutorial: None,
+            observed_at_editor: None,
+            recovery: None,
+        }
+    }
+}
+
+impl ApplicationComponentState {
+    pub fn is_template_picker_open(&self) -> bool {
+        self.template_picker.is_some() && self.shared.is_evidence_empty()
+    }
+
+    /// Prepares the tabs for the template and focuses the first field to fill
+    pub fn use_template(&mut self, template: &'static EvidenceTemplate) {
+        self.template_picker = None;
+        self.shared.active_template = Some(template);
+        let before = EvidenceSnapshot::of(&self.shared);
+        self.tabs.apply_template(template, &mut self.shared);
+        self.shared
+            .history
+            .record(before, EvidenceSnapshot::of(&self.shared));
+
+        if let Some(tab) = template.first_section().and_then(ApplicationTabs::tab_of) {
+            if !self.focused_on_tab_selector {
+                self.tabs.on_unfocus(self.selected_tab, &mut self.shared);
+            }
+            self.selected_tab = tab;
+            self.focused_on_tab_selector = false;
+            self.tabs.on_focus(tab, &mut self.shared);
+        }
+    }
+
+    /// Fills in the evidence of the first tutorial step, see [`Tutorial`]
+    pub fn start_tutorial(&mut self) {
+        self.tutorial = Some(Tutorial::start(self));
+    }
+
+    /// Moves the tutorial on if the checkpoint of its step passed, call it after the events and the searches
+    pub fn update_tutorial(&mut self) {
+        if let Some(mut tutorial) = self.tutorial.take() {
+            tutorial.update(self);
+            self.tutorial = Some(tutorial);
+        }
+    }
+
+    /// Opens the observation time editor of the selected tab, false if the tab has no evidence
+    pub fn open_observed_at_editor(&mut self) -> bool {
+        let tab = self.selected_tab;
+        let Some(current) = self.shared.observed_at_mut(tab) else {
+            return false;
+        };
+        self.observed_at_editor = Some(ObservedAtEditor::open(tab, current.as_ref()));
+        true
+    }
+
+    /// Closes the observation time editor, keeping its value as an edit
+    fn save_observed_at(&mut self) {
+        let Some(editor) = self.observed_at_editor.take() else {
+            return;
+        };
+        let before = EvidenceSnapshot::of(&self.shared);
+        if let Some(observed_at) = self.shared.observed_at_mut(editor.tab) {
+            *observed_at = editor.result();
+        }
+        self.shared
+            .history
+            .record(before, EvidenceSnapshot::of(&self.shared));
+    }
+
+    /// Replaces the evidence with the recovered one, as an edit that can be undone. False if nothing was recovered.
+    pub fn restore_recovery(&mut self) -> bool {
+        let Some(snapshot) = self.recovery.take() else {
+            return false;
+        };
+        self.template_picker = None;
+        let before = EvidenceSnapshot::of(&self.shared);
+        snapshot.restore(&mut self.shared);
+        self.shared
+            .history
+            .record(before, EvidenceSnapshot::of(&self.shared));
+        self.tabs.sync_from_shared(&mut self.shared);
+        true
+    }
+
+    /// Reverts the last evidence edit, false if there is none
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.shared.history.undo() else {
+            return false;
+        };
+        snapshot.restore(&mut self.shared);
+        self.tabs.sync_from_shared(&mut self.shared);
+        true
+    }
+
+    /// Applies the last reverted evidence edit again, false if there is none
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.shared.history.redo() else {
+            return false;
+        };
+        snapshot.restore(&mut self.shared);
+        self.tabs.sync_from_shared(&mut self.shared);
+        true
+    }
+
+    /// Sends the event to the selected tab, and records the edit if the evidence changed
+    fn tab_handle_event(&mut self, event: Event, context: EventContext) -> EventResult {
+        let before = EvidenceSnapshot::of(&self.shared);
+        let result = self
+            .tabs
+            .handle_event(self.selected_tab, event, context, &mut self.shared);
+        self.shared
+            .history
+            .record(before, EvidenceSnapshot::of(&self.shared));
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+/// [CTRL + Z] and [CTRL + Y] work everywhere, even while typing in an input
+fn history_step(event: &Event) -> Option<HistoryStep> {
+    match event {
+        Event::Key(key)
+            if key.kind != KeyEventKind::Release
+                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            match key.code {
+                KeyCode::Char('z' | 'Z') => Some(HistoryStep::Undo),
+                KeyCode::Char('y' | 'Y') => Some(HistoryStep::Redo),
+                _ => None,
+            }
         }
+        _ => None,
     }
 }
 
+/// [CTRL + T] closes the tutorial overlay, the evidence it filled in is kept
+fn is_leave_tutorial_key(event: &Event) -> bool {
+    matches!(event, Event::Key(key) if key.kind != KeyEventKind::Release
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('t' | 'T')))
+}
+
+/// [CTRL + O] edits when the evidence of the selected tab was observed
+fn is_observed_at_key(event: &Event) -> bool {
+    matches!(event, Event::Key(key) if key.kind != KeyEventKind::Release
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('o' | 'O')))
+}
+
+/// Whether the TUI has every tab the template needs
+fn is_template_available(template: &EvidenceTemplate) -> bool {
+    template
+        .fields
+        .iter()
+        .all(|f| ApplicationTabs::tab_of(f.section).is_some())
+}
+
 impl Default for ApplicationComponentState {
     fn default() -> Self {
         Self::new()
@@ -231,6 +917,8 @@ impl Default for ApplicationComponentState {
 
 impl ApplicationComponent {
     pub fn render(area: Rect, buf: &mut Buffer, state: &mut ApplicationComponentState) {
+        state.shared.hit_map.clear();
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
@@ -255,15 +943,59 @@ impl ApplicationComponent {
                     } else {
                         Style::default()
                     })
-                    .title("Tabs"),
+                    .title("Tabs")
+                    .title(
+                        Line::from("[CTRL + Z] Undo    [CTRL + Y] Redo    [CTRL + O] Observed at")
+                            .right_aligned(),
+                    ),
             )
             .highlight_style(Style::default().add_modifier(Modifier::BOLD).underlined());
 
         tabs.render(chunks[0], buf);
 
         // Placeholder for tab content
+        let guidance = state.shared.active_template.map(|template| {
+            ApplicationTabs::sections(state.selected_tab)
+                .iter()
+                .flat_map(|section| template.fields_in(*section))
+                .map(|f| {
+                    format!(
+                        "{}{}: {}",
+                        f.label,
+                        if f.required { "*" } else { "" },
+                        f.guidance
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" | ")
+        });
+
+        let memory = match &state.shared.memory.warning {
+            Some(warning) => Line::from(format!("{} ({})", state.shared.memory.summary(), warning))
+                .style(Style::default().fg(Color::Yellow)),
+            None => Line::from(state.shared.memory.summary()),
+        };
+        let observed_at = match state
+            .shared
+            .observed_at_mut(state.selected_tab)
+            .and_then(|o| o.as_ref())
+        {
+            Some(o) if o.might_be_modified => {
+                Line::from(format!("Observed: {}", o)).style(Style::default().fg(Color::Yellow))
+            }
+            Some(o) => Line::from(format!("Observed: {}", o)),
+            None => Line::default(),
+        };
         let content_block = Block::default()
             .title(selected_title)
+            .title(observed_at.right_aligned())
+            .title_bottom(match guidance {
+                Some(guidance) if !guidance.is_empty() => {
+                    Line::from(guidance).style(Style::default().fg(Color::Yellow))
+                }
+                _ => Line::default(),
+            })
+            .title_bottom(memory.right_aligned())
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL)
             .border_style(if state.focused_on_tab_selector {
@@ -279,6 +1011,99 @@ impl ApplicationComponent {
         state
             .tabs
             .render(state.selected_tab, content_area, buf, &mut state.shared);
+
+        if state.is_template_picker_open() {
+            Self::render_template_picker(area, buf, state.template_picker.unwrap_or(0));
+        }
+        if let Some(tutorial) = &state.tutorial {
+            tutorial.render(content_area, buf);
+        }
+        if let Some(editor) = &mut state.observed_at_editor {
+            editor.render(area, buf);
+        }
+        if state.recovery.is_some() {
+            Self::render_recovery_prompt(area, buf);
+        }
+    }
+
+    fn render_recovery_prompt(area: Rect, buf: &mut Buffer) {
+        let lines = vec![
+            Line::from("The last session did not exit cleanly."),
+            Line::from("Its evidence was saved and can be restored."),
+        ];
+        let width = 60;
+        let height = lines.len() as u16 + 2;
+        let popup_area = get_area_centered(
+            Rect::new(0, 0, width.min(area.width), height.min(area.height)),
+            area,
+        );
+
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::Yellow))
+                    .title("Recovery")
+                    .title_bottom("[ENTER] Restore    [ESC] Discard")
+                    .title_alignment(Alignment::Center),
+            )
+            .render(popup_area, buf);
+    }
+
+    fn render_template_picker(area: Rect, buf: &mut Buffer, selected: usize) {
+        let mut lines = vec![
+            Line::from("Not sure where to start? Pick the evidence you have:"),
+            Line::default(),
+        ];
+        for (i, template) in EVIDENCE_TEMPLATES.iter().enumerate() {
+            let available = is_template_available(template);
+            let style = match (i == selected, available) {
+                (true, true) => Style::default().fg(Color::LightCyan).bold(),
+                (true, false) => Style::default().fg(Color::Red).bold(),
+                (false, true) => Style::default().fg(Color::White),
+                (false, false) => Style::default().fg(Color::DarkGray),
+            };
+            lines.push(
+                Line::from(format!(
+                    "{} {} (~{} bits){}",
+                    if i == selected { ">" } else { " " },
+                    template.title,
+                    template.estimated_bits,
+                    if available {
+                        ""
+                    } else {
+                        ", not available in the TUI yet"
+                    }
+                ))
+                .style(style),
+            );
+            lines.push(
+                Line::from(format!("    {}", template.description))
+                    .style(Style::default().fg(Color::Gray)),
+            );
+        }
+
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+        let height = lines.len() as u16 + 2;
+        let popup_area = get_area_centered(
+            Rect::new(0, 0, width.min(area.width), height.min(area.height)),
+            area,
+        );
+
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::LightCyan))
+                    .title("Quick start")
+                    .title_bottom(
+                        "[UP] [DOWN] Select    [ENTER] Use template    [ESC] Start from scratch",
+                    )
+                    .title_alignment(Alignment::Center),
+            )
+            .render(popup_area, buf);
     }
 
     pub fn handle_event(
@@ -286,6 +1111,75 @@ impl ApplicationComponent {
         event: Event,
         context: EventContext,
     ) -> EventResult {
+        if context == EventContext::BubblingDown
+            && state.tutorial.is_some()
+            && is_leave_tutorial_key(&event)
+        {
+            state.tutorial = None;
+            return EventResult::Captured;
+        }
+
+        if context == EventContext::BubblingDown && state.recovery.is_some() {
+            match &event {
+                Event::Key(key)
+                    if key.kind != KeyEventKind::Release && key.code == KeyCode::Enter =>
+                {
+                    state.restore_recovery();
+                }
+                Event::Key(key)
+                    if key.kind != KeyEventKind::Release && key.code == KeyCode::Esc =>
+                {
+                    state.recovery = None;
+                }
+                _ => {}
+            }
+            return EventResult::Captured;
+        }
+
+        if context == EventContext::BubblingDown && state.is_template_picker_open() {
+            if let Event::Key(key) = &event {
+                if key.kind != KeyEventKind::Release {
+                    let selected = state.template_picker.unwrap_or(0);
+                    let count = EVIDENCE_TEMPLATES.len();
+                    match key.code {
+                        KeyCode::Up => state.template_picker = Some((selected + count - 1) % count),
+                        KeyCode::Down => state.template_picker = Some((selected + 1) % count),
+                        KeyCode::Enter if is_template_available(&EVIDENCE_TEMPLATES[selected]) => {
+                            state.use_template(&EVIDENCE_TEMPLATES[selected]);
+                        }
+                        KeyCode::Esc => state.template_picker = None,
+                        _ => {}
+                    }
+                }
+            }
+            return EventResult::Captured;
+        }
+
+        if context == EventContext::BubblingDown {
+            if let Some(editor) = &mut state.observed_at_editor {
+                match editor.handle_event(event) {
+                    ObservedAtAction::Editing => {}
+                    ObservedAtAction::Save => state.save_observed_at(),
+                    ObservedAtAction::Cancel => state.observed_at_editor = None,
+                }
+                return EventResult::Captured;
+            }
+            if is_observed_at_key(&event) && state.open_observed_at_editor() {
+                return EventResult::Captured;
+            }
+            match history_step(&event) {
+                Some(HistoryStep::Undo) => {
+                    state.undo();
+                    return EventResult::Captured;
+                }
+                Some(HistoryStep::Redo) => {
+                    state.redo();
+                    return EventResult::Captured;
+                }
+                None => {}
+            }
+        }
+
         match context {
             EventContext::BubblingDown => {
                 if state.focused_on_tab_selector {
@@ -309,12 +1203,7 @@ impl ApplicationComponent {
                         _ => EventResult::BubbleUp(event),
                     }
                 } else {
-                    match state.tabs.handle_event(
-                        state.selected_tab,
-                        event,
-                        context,
-                        &mut state.shared,
-                    ) {
+                    match state.tab_handle_event(event, context) {
                         EventResult::Captured => EventResult::Captured,
                         EventResult::BubbleUp(event) => {
                             Self::handle_event(state, event, EventContext::BubblingUp)
@@ -1,2 +1,4 @@
 pub mod chest;
+pub mod observed_at;
 pub mod text_input;
+pub mod tree_list;
@@ -0,0 +1,114 @@
+//! Popup editing when the evidence of a tab was observed, see [`ObservedAt`]
+
+use mcseedcracker::pipeline::ObservedAt;
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::tui::{
+    EventContext,
+    components::text_input::{TextInputState, TextInputStyle, TextInputWidget},
+    get_area_centered,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservedAtAction {
+    Editing,
+    Save,
+    Cancel,
+}
+
+pub struct ObservedAtEditor {
+    /// Tab whose evidence is edited
+    pub tab: usize,
+    pub when: TextInputState<String>,
+    pub value: ObservedAt,
+}
+
+impl ObservedAtEditor {
+    pub fn open(tab: usize, current: Option<&ObservedAt>) -> Self {
+        let mut value = current.cloned().unwrap_or_default();
+        let mut when = TextInputState::new(
+            "When",
+            Some(Box::new(
+                |text: &mut Vec<char>, _: &mut usize, _: &mut TextInputStyle, when: &mut String| {
+                    *when = text.iter().collect();
+                },
+            )),
+        );
+        let text = value.when.clone();
+        when.set_text(&text, &mut value.when);
+        Self { tab, when, value }
+    }
+
+    /// The observation to keep, none if nothing was entered
+    pub fn result(&self) -> Option<ObservedAt> {
+        (!self.value.when.trim().is_empty() || self.value.might_be_modified)
+            .then(|| self.value.clone())
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> ObservedAtAction {
+        if let Event::Key(key) = &event {
+            if key.kind == KeyEventKind::Release {
+                return ObservedAtAction::Editing;
+            }
+            match key.code {
+                KeyCode::Enter => return ObservedAtAction::Save,
+                KeyCode::Esc => return ObservedAtAction::Cancel,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    self.value.might_be_modified = !self.value.might_be_modified;
+                    return ObservedAtAction::Editing;
+                }
+                _ => {}
+            }
+        }
+        TextInputWidget::handle_event(
+            &mut self.when,
+            event,
+            EventContext::BubblingDown,
+            &mut self.value.when,
+        );
+        ObservedAtAction::Editing
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = get_area_centered(
+            Rect::new(0, 0, 60.min(area.width), 7.min(area.height)),
+            area,
+        );
+        Clear.render(popup_area, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::LightCyan))
+            .title("Observed at")
+            .title_bottom("[TAB] Toggle    [ENTER] Save    [ESC] Cancel")
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        TextInputWidget::default().render(
+            Rect::new(inner.x, inner.y, inner.width, 3.min(inner.height)),
+            buf,
+            &mut self.when,
+        );
+        let (text, color) = if self.value.might_be_modified {
+            ("The world might have been modified before", Color::Yellow)
+        } else {
+            ("The world was fresh", Color::Green)
+        };
+        Paragraph::new(Line::from(text).style(Style::default().fg(color))).render(
+            Rect::new(
+                inner.x + 1,
+                inner.y + 3.min(inner.height),
+                inner.width.saturating_sub(1),
+                inner.height.saturating_sub(3).min(1),
+            ),
+            buf,
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use std::{iter, marker::PhantomData, time::SystemTime};
 
+use mcseedcracker::math::Math;
+
 use ratatui::{
     buffer::Buffer,
     crossterm::event::{Event, KeyCode, KeyModifiers},
-    layout::{Position, Rect},
+    layout::Rect,
     style::{Color, Style},
     symbols::border,
     widgets::{Block, Borders, StatefulWidget, Widget},
@@ -54,14 +56,9 @@ pub struct TextInputState<T> {
     pub value: Vec<char>,
     pub cursor: usize,
     pub style: TextInputStyle,
-    pub last_render: Rect,
 }
 
 impl<T> TextInputState<T> {
-    pub fn in_rect(&self, x: u16, y: u16) -> bool {
-        self.last_render.contains(Position::new(x, y))
-    }
-
     pub fn new<U>(title: U, validator: Validator<T>) -> Self
     where
         U: ToString,
@@ -71,6 +68,26 @@ impl<T> TextInputState<T> {
         state.validator = validator;
         state
     }
+
+    /// Replaces the text and puts the cursor at its end, validated as if it was typed
+    pub fn set_text(&mut self, text: &str, shared: &mut T) {
+        self.value = text.chars().collect();
+        self.cursor = self.value.len();
+        if let Some(validator) = &self.validator {
+            validator(&mut self.value, &mut self.cursor, &mut self.style, shared);
+        }
+    }
+}
+
+impl TextInputState<i32> {
+    /// Shows the number, the text is kept if it already reads as it. An empty text reads as 0.
+    pub fn show_number(&mut self, number: &mut i32) {
+        let text = self.value.iter().collect::<String>();
+        if text.parse::<i32>().ok() == Some(*number) || (text.is_empty() && *number == 0) {
+            return;
+        }
+        self.set_text(&number.to_string(), number);
+    }
 }
 
 impl<T> Default for TextInputState<T> {
@@ -80,7 +97,6 @@ impl<T> Default for TextInputState<T> {
             cursor: 0,
             validator: None,
             style: TextInputStyle::default(),
-            last_render: Rect::default(),
         }
     }
 }
@@ -201,8 +217,6 @@ impl<T> StatefulWidget for TextInputWidget<T> {
     type State = TextInputState<T>;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        state.last_render = area;
-
         let blk = Block::new()
             .borders(Borders::ALL)
             .border_set(state.style.border_set)
@@ -259,6 +273,27 @@ impl<T> StatefulWidget for TextInputWidget<T> {
     }
 }
 
+/// Like [`i32_validator`], but coordinates outside of the world border are shown in yellow. They are kept, so the
+/// search preflight can name them.
+pub fn block_coord_validator() -> Validator<i32> {
+    Some(Box::new(
+        |value: &mut Vec<char>, _: &mut usize, style: &mut TextInputStyle, i: &mut i32| {
+            if value.len() > 15 {
+                style.cursor_style.bg = Some(Color::Red);
+                style.text_style.fg = Some(Color::Red);
+            } else if let Ok(n) = value.iter().collect::<String>().parse::<i32>() {
+                let inside = Math::is_inside_world_border((n, 0));
+                style.text_style.fg = Some(if inside { Color::White } else { Color::Yellow });
+                style.cursor_style.bg = Some(if inside { Color::Green } else { Color::Yellow });
+                *i = n;
+            } else {
+                style.cursor_style.bg = Some(Color::Red);
+                style.text_style.fg = Some(Color::Red);
+            }
+        },
+    ))
+}
+
 pub fn i32_validator() -> Validator<i32> {
     Some(Box::new(
         |value: &mut Vec<char>, _: &mut usize, style: &mut TextInputStyle, i: &mut i32| {
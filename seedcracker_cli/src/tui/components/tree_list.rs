@@ -0,0 +1,414 @@
+//! List of collapsible groups, e.g. the world seeds found for each structure seed. Only the rows in view are built,
+//! so a group can have any number of children.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{StatefulWidget, Widget},
+};
+
+pub trait TreeSource {
+    /// Identifies a group when the data is refreshed, the collapsed groups are remembered by key
+    type Key: PartialEq + Clone;
+
+    fn group_count(&self) -> usize;
+    fn group_key(&self, group: usize) -> Self::Key;
+    fn child_count(&self, group: usize) -> usize;
+    fn header(&self, group: usize) -> Line<'_>;
+    fn child(&self, group: usize, child: usize) -> Line<'_>;
+}
+
+/// A group header when `child` is `None`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TreeCursor {
+    pub group: usize,
+    pub child: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeListState<K> {
+    pub collapsed: Vec<K>,
+    pub cursor: TreeCursor,
+    /// First row in view
+    pub offset: usize,
+}
+
+impl<K> Default for TreeListState<K> {
+    fn default() -> Self {
+        Self {
+            collapsed: Vec::new(),
+            cursor: TreeCursor::default(),
+            offset: 0,
+        }
+    }
+}
+
+impl<K: PartialEq + Clone> TreeListState<K> {
+    #[inline]
+    pub fn is_collapsed(&self, key: &K) -> bool {
+        self.collapsed.contains(key)
+    }
+
+    pub fn set_collapsed(&mut self, key: K, collapsed: bool) {
+        if collapsed && !self.is_collapsed(&key) {
+            self.collapsed.push(key);
+        } else if !collapsed {
+            self.collapsed.retain(|k| *k != key);
+        }
+    }
+
+    /// Children shown under the group, 0 when it is collapsed
+    fn shown_children<S: TreeSource<Key = K>>(&self, source: &S, group: usize) -> usize {
+        if self.is_collapsed(&source.group_key(group)) {
+            0
+        } else {
+            source.child_count(group)
+        }
+    }
+
+    pub fn row_count<S: TreeSource<Key = K>>(&self, source: &S) -> usize {
+        (0..source.group_count())
+            .map(|g| 1 + self.shown_children(source, g))
+            .sum()
+    }
+
+    /// Row of the cursor, counting the rows out of view
+    pub fn cursor_row<S: TreeSource<Key = K>>(&self, source: &S) -> usize {
+        let before = (0..self.cursor.group)
+            .map(|g| 1 + self.shown_children(source, g))
+            .sum::<usize>();
+        before + self.cursor.child.map_or(0, |c| c + 1)
+    }
+
+    /// Moves the cursor back on a shown row after the data changed
+    pub fn clamp<S: TreeSource<Key = K>>(&mut self, source: &S) {
+        let groups = source.group_count();
+        if groups == 0 {
+            self.cursor = TreeCursor::default();
+            return;
+        }
+        if self.cursor.group >= groups {
+            self.cursor = TreeCursor {
+                group: groups - 1,
+                child: None,
+            };
+        }
+        let shown = self.shown_children(source, self.cursor.group);
+        if let Some(child) = self.cursor.child {
+            self.cursor.child = if shown == 0 {
+                None
+            } else {
+                Some(child.min(shown - 1))
+            };
+        }
+    }
+
+    /// Up and down move between the shown rows, left collapses a group and right expands it. Returns false for the
+    /// other keys.
+    pub fn handle_key<S: TreeSource<Key = K>>(&mut self, source: &S, key: KeyCode) -> bool {
+        self.clamp(source);
+        let groups = source.group_count();
+        if groups == 0 {
+            return matches!(
+                key,
+                KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+            );
+        }
+
+        let TreeCursor { group, child } = self.cursor;
+        let shown = self.shown_children(source, group);
+        match key {
+            KeyCode::Up => {
+                self.cursor = match child {
+                    Some(0) => TreeCursor { group, child: None },
+                    Some(c) => TreeCursor {
+                        group,
+                        child: Some(c - 1),
+                    },
+                    None if group == 0 => self.cursor,
+                    None => {
+                        let previous = self.shown_children(source, group - 1);
+                        TreeCursor {
+                            group: group - 1,
+                            child: previous.checked_sub(1),
+                        }
+                    }
+                };
+            }
+            KeyCode::Down => {
+                let next = child.map_or(0, |c| c + 1);
+                if next < shown {
+                    self.cursor.child = Some(next);
+                } else if group + 1 < groups {
+                    self.cursor = TreeCursor {
+                        group: group + 1,
+                        child: None,
+                    };
+                }
+            }
+            KeyCode::Left => {
+                if child.is_some() {
+                    self.cursor.child = None;
+                } else {
+                    self.set_collapsed(source.group_key(group), true);
+                }
+            }
+            KeyCode::Right => self.set_collapsed(source.group_key(group), false),
+            _ => return false,
+        }
+        true
+    }
+}
+
+pub struct TreeListWidget<'a, S> {
+    source: &'a S,
+    /// Highlights the row of the cursor
+    focused: bool,
+}
+
+impl<'a, S: TreeSource> TreeListWidget<'a, S> {
+    #[inline]
+    pub const fn new(source: &'a S, focused: bool) -> Self {
+        Self { source, focused }
+    }
+}
+
+impl<S: TreeSource> StatefulWidget for TreeListWidget<'_, S> {
+    type State = TreeListState<S::Key>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let height = area.height as usize;
+        if height == 0 {
+            return;
+        }
+
+        // keep the cursor in view, without leaving empty rows at the bottom
+        state.clamp(self.source);
+        let cursor_row = state.cursor_row(self.source);
+        let max_offset = state.row_count(self.source).saturating_sub(height);
+        state.offset = state
+            .offset
+            .min(max_offset)
+            .min(cursor_row)
+            .max((cursor_row + 1).saturating_sub(height));
+
+        let mut row = 0;
+        for group in 0..self.source.group_count() {
+            if row >= state.offset + height {
+                break;
+            }
+            let key = self.source.group_key(group);
+            let collapsed = state.is_collapsed(&key);
+            let shown = state.shown_children(self.source, group);
+            if row + 1 + shown <= state.offset {
+                row += 1 + shown;
+                continue;
+            }
+
+            let first_child = state.offset.saturating_sub(row + 1);
+            let children = first_child..shown.min(state.offset + height - row - 1);
+            let lines = (row >= state.offset)
+                .then(|| {
+                    let mut header = self.source.header(group);
+                    header
+                        .spans
+                        .insert(0, Span::raw(if collapsed { "▶ " } else { "▼ " }));
+                    (None, header)
+                })
+                .into_iter()
+                .chain(children.map(|c| {
+                    let mut line = self.source.child(group, c);
+                    line.spans.insert(0, Span::raw("  "));
+                    (Some(c), line)
+                }));
+
+            for (child, line) in lines {
+                let y = (row + child.map_or(0, |c| c + 1) - state.offset) as u16;
+                let line_area = Rect::new(area.x, area.y + y, area.width, 1);
+                line.render(line_area, buf);
+                if self.focused && state.cursor == (TreeCursor { group, child }) {
+                    buf.set_style(line_area, Style::new().bg(Color::DarkGray));
+                }
+            }
+            row += 1 + shown;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        buffer::Buffer, crossterm::event::KeyCode, layout::Rect, style::Color, text::Line,
+        widgets::StatefulWidget,
+    };
+
+    use crate::tui::components::tree_list::{
+        TreeCursor, TreeListState, TreeListWidget, TreeSource,
+    };
+
+    /// Groups named after their key, with children counted from 0
+    struct Groups(Vec<(i64, usize)>);
+
+    impl TreeSource for Groups {
+        type Key = i64;
+
+        fn group_count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn group_key(&self, group: usize) -> i64 {
+            self.0[group].0
+        }
+
+        fn child_count(&self, group: usize) -> usize {
+            self.0[group].1
+        }
+
+        fn header(&self, group: usize) -> Line<'_> {
+            Line::from(format!("seed {}", self.0[group].0))
+        }
+
+        fn child(&self, group: usize, child: usize) -> Line<'_> {
+            Line::from(format!("{}.{}", self.0[group].0, child))
+        }
+    }
+
+    fn render(source: &Groups, state: &mut TreeListState<i64>, height: u16) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, height));
+        TreeListWidget::new(source, true).render(buf.area, &mut buf, state);
+        buf
+    }
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tree_list_collapse() {
+        let source = Groups(vec![(7, 2), (8, 0), (9, 3)]);
+        let mut state = TreeListState::default();
+
+        let buf = render(&source, &mut state, 8);
+        assert_eq!(
+            lines(&buf),
+            vec![
+                "▼ seed 7",
+                "  7.0",
+                "  7.1",
+                "▼ seed 8",
+                "▼ seed 9",
+                "  9.0",
+                "  9.1",
+                "  9.2",
+            ]
+        );
+        // the cursor row is highlighted, the others aren't
+        assert_eq!(buf[(0, 0)].bg, Color::DarkGray);
+        assert_eq!(buf[(0, 1)].bg, Color::Reset);
+
+        assert!(state.handle_key(&source, KeyCode::Left));
+        state.cursor.group = 2;
+        assert!(state.handle_key(&source, KeyCode::Left));
+        assert_eq!(state.collapsed, vec![7, 9]);
+        assert_eq!(
+            lines(&render(&source, &mut state, 4)),
+            vec!["▶ seed 7", "▼ seed 8", "▶ seed 9", ""]
+        );
+
+        // the collapsed groups are remembered by key when the results change
+        let source = Groups(vec![(9, 3), (7, 2)]);
+        assert!(state.handle_key(&source, KeyCode::Right));
+        assert_eq!(
+            lines(&render(&source, &mut state, 4)),
+            vec!["▶ seed 9", "▼ seed 7", "  7.0", "  7.1"]
+        );
+        assert!(!state.handle_key(&source, KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_tree_list_navigation_and_scrolling() {
+        let source = Groups(vec![(1, 1), (2, 100), (3, 1)]);
+        let mut state = TreeListState::default();
+
+        for _ in 0..3 {
+            state.handle_key(&source, KeyCode::Down);
+        }
+        assert_eq!(
+            state.cursor,
+            TreeCursor {
+                group: 1,
+                child: Some(0)
+            }
+        );
+        assert_eq!(
+            lines(&render(&source, &mut state, 3)),
+            vec!["  1.0", "▼ seed 2", "  2.0"]
+        );
+        // only the row leaving the view moves the window
+        state.handle_key(&source, KeyCode::Down);
+        assert_eq!(
+            lines(&render(&source, &mut state, 3)),
+            vec!["▼ seed 2", "  2.0", "  2.1"]
+        );
+
+        // from the last child of a group, up from the next header
+        state.cursor = TreeCursor {
+            group: 2,
+            child: None,
+        };
+        state.handle_key(&source, KeyCode::Up);
+        assert_eq!(
+            state.cursor,
+            TreeCursor {
+                group: 1,
+                child: Some(99)
+            }
+        );
+        assert_eq!(
+            lines(&render(&source, &mut state, 3)),
+            vec!["  2.97", "  2.98", "  2.99"]
+        );
+
+        // left goes back to the header, then collapses it
+        state.handle_key(&source, KeyCode::Left);
+        state.handle_key(&source, KeyCode::Left);
+        assert_eq!(
+            lines(&render(&source, &mut state, 3)),
+            vec!["▶ seed 2", "▼ seed 3", "  3.0"]
+        );
+        state.handle_key(&source, KeyCode::Down);
+        state.handle_key(&source, KeyCode::Down);
+        state.handle_key(&source, KeyCode::Down);
+        assert_eq!(
+            state.cursor,
+            TreeCursor {
+                group: 2,
+                child: Some(0)
+            }
+        );
+
+        // a cursor left past the end of new results is moved back
+        let source = Groups(vec![(1, 1)]);
+        assert_eq!(
+            lines(&render(&source, &mut state, 3)),
+            vec!["▼ seed 1", "  1.0", ""]
+        );
+        assert_eq!(state.cursor.group, 0);
+        assert_eq!(state.offset, 0);
+
+        let empty = Groups(vec![]);
+        assert!(state.handle_key(&empty, KeyCode::Down));
+        assert_eq!(lines(&render(&empty, &mut state, 2)), vec!["", ""]);
+    }
+}
@@ -0,0 +1,156 @@
+//! The evidence of each tab, gathered by the search triggers without knowing the tabs. A new kind of evidence
+//! registers its provider in [`EvidenceRegistry::new`] instead of editing the output tab.
+
+use mcseedcracker::pipeline::CrackingEvidence;
+
+use crate::tui::{
+    application::SharedApplicationState,
+    tabs::{bastion, biomes, buried_treasure, decorations, end_pillars, structures},
+};
+
+/// Adds the evidence of a tab, returns true if the tab has evidence the search can use
+pub type ContributeEvidence = fn(&SharedApplicationState, &mut CrackingEvidence) -> bool;
+
+#[derive(Clone, Copy)]
+pub struct EvidenceProvider {
+    pub name: &'static str,
+    pub contribute: ContributeEvidence,
+}
+
+#[derive(Clone)]
+pub struct EvidenceRegistry {
+    providers: Vec<EvidenceProvider>,
+}
+
+impl Default for EvidenceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvidenceRegistry {
+    /// The providers of the evidence tabs, in the order of the tabs
+    pub fn new() -> Self {
+        let mut registry = Self {
+            providers: Vec::new(),
+        };
+        registry.register("end pillars", end_pillars::contribute_evidence);
+        registry.register("buried treasure", buried_treasure::contribute_evidence);
+        registry.register("biomes", biomes::contribute_evidence);
+        registry.register("decorations", decorations::contribute_evidence);
+        registry.register("bastion chest", bastion::contribute_evidence);
+        registry.register("known structures", structures::contribute_evidence);
+        registry
+    }
+
+    /// Providers contribute in the order they were registered
+    pub fn register(&mut self, name: &'static str, contribute: ContributeEvidence) {
+        self.providers.push(EvidenceProvider { name, contribute });
+    }
+
+    /// The evidence of every provider, and the names of the providers with usable evidence
+    pub fn collect(
+        &self,
+        shared: &SharedApplicationState,
+    ) -> (CrackingEvidence, Vec<&'static str>) {
+        let mut evidence = CrackingEvidence::default();
+        let usable = self
+            .providers
+            .iter()
+            .filter(|provider| (provider.contribute)(shared, &mut evidence))
+            .map(|provider| provider.name)
+            .collect();
+        (evidence, usable)
+    }
+
+    #[inline]
+    pub fn cracking_evidence(&self, shared: &SharedApplicationState) -> CrackingEvidence {
+        self.collect(shared).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::{
+        cubiomes::enums::BiomeID,
+        features::end_pillars::PillarMatchResult,
+        loot_table::{CountBound, ItemCountConstraints},
+        pipeline::{CrackingEvidence, CrackingPipeline},
+        search::{StructureData, WorldExtraData},
+    };
+
+    use crate::tui::application::{ApplicationComponentState, SharedApplicationState};
+
+    const PILLAR_SEED: i64 = 13847;
+    const STRUCTURE_SEED: i64 = 49200715220;
+
+    fn fake_structure() -> StructureData {
+        StructureData::BuriedTreasureCounts {
+            chunk_x: 25,
+            chunk_z: -7,
+            luck: 0.0,
+            counts: ItemCountConstraints::new(vec![(0, CountBound::AtLeast(1))]),
+            loot_table: None,
+        }
+    }
+
+    fn fake_world() -> WorldExtraData {
+        WorldExtraData::OverworldBiomeData(vec![(400, 60, 800, BiomeID::beach)])
+    }
+
+    fn fake_provider(_shared: &SharedApplicationState, evidence: &mut CrackingEvidence) -> bool {
+        evidence.structures.push(fake_structure());
+        evidence.world.push(fake_world());
+        true
+    }
+
+    fn empty_provider(_shared: &SharedApplicationState, _evidence: &mut CrackingEvidence) -> bool {
+        false
+    }
+
+    #[test]
+    fn test_default_providers() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let (_, usable) = shared.evidence_registry.collect(&shared);
+        assert!(usable.is_empty());
+
+        shared.pillar_data.0[0].caged = Some(true);
+        shared.buried_treasure_data.usable = true;
+        let (evidence, usable) = shared.evidence_registry.collect(&shared);
+        assert_eq!(usable, vec!["end pillars", "buried treasure"]);
+        assert_eq!(evidence.pillars, shared.pillar_data);
+        assert_eq!(evidence.buried_treasures.len(), 1);
+        // the empty overworld and nether samples
+        assert_eq!(evidence.world.len(), 2);
+    }
+
+    #[test]
+    fn test_fake_provider_reaches_the_jobs() {
+        let mut shared = ApplicationComponentState::new().shared;
+        shared.evidence_registry.register("empty", empty_provider);
+        shared.evidence_registry.register("fake", fake_provider);
+
+        let (evidence, usable) = shared.evidence_registry.collect(&shared);
+        assert_eq!(usable, vec!["fake"]);
+        let options = shared.pipeline_options();
+
+        let jobs = CrackingPipeline::structure_jobs(
+            &evidence,
+            &options,
+            &[(PILLAR_SEED, PillarMatchResult::ExactMatch)],
+        );
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].pillar_seed, PILLAR_SEED);
+        assert!(jobs[0].data.contains(&fake_structure()));
+
+        let jobs = CrackingPipeline::world_jobs(&evidence, &options, &[STRUCTURE_SEED]);
+        assert_eq!(jobs.len(), 1);
+        assert!(jobs[0].data.contains(&fake_world()));
+
+        // the search triggers read the registry through the shared state
+        assert!(
+            CrackingPipeline::structure_data(&shared.cracking_evidence(), &options)
+                .contains(&fake_structure())
+        );
+    }
+}
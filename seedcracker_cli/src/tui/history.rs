@@ -0,0 +1,401 @@
+//! Undo and redo of the evidence edits. An edit is any event that changed the evidence, it is kept as the evidence
+//! before and after it so it can be reverted and applied again.
+
+use std::collections::VecDeque;
+
+use mcseedcracker::{features::end_pillars::PartialEndPillars, pipeline::ObservedAt};
+
+use crate::tui::{
+    application::SharedApplicationState,
+    tabs::{
+        bastion::BastionTabSharedData, biomes::BiomesTabSharedData,
+        buried_treasure::BuriedTreasureTabSharedData, decorations::DecorationsTabSharedData,
+        structures::StructuresTabSharedData,
+    },
+};
+
+/// Edits kept for undo, the oldest ones are dropped first
+pub const MAX_HISTORY: usize = 100;
+
+/// Every piece of evidence edited in the tabs. The searches copy their evidence when they start, so they are not
+/// affected when a snapshot is restored.
+#[derive(Clone, PartialEq)]
+pub struct EvidenceSnapshot {
+    pub pillar_data: PartialEndPillars,
+    pub pillars_enabled: bool,
+    pub pillars_observed_at: Option<ObservedAt>,
+    pub buried_treasure_data: BuriedTreasureTabSharedData,
+    pub biome_data: BiomesTabSharedData,
+    pub decoration_data: DecorationsTabSharedData,
+    pub bastion_data: BastionTabSharedData,
+    pub structure_data: StructuresTabSharedData,
+}
+
+impl EvidenceSnapshot {
+    pub fn of(shared: &SharedApplicationState) -> Self {
+        Self {
+            pillar_data: shared.pillar_data,
+            pillars_enabled: shared.pillars_enabled,
+            pillars_observed_at: shared.pillars_observed_at.clone(),
+            buried_treasure_data: shared.buried_treasure_data.clone(),
+            biome_data: shared.biome_data.clone(),
+            decoration_data: shared.decoration_data.clone(),
+            bastion_data: shared.bastion_data.clone(),
+            structure_data: shared.structure_data.clone(),
+        }
+    }
+
+    /// Replaces the evidence of the shared state, the tabs must then resync their inputs
+    pub fn restore(self, shared: &mut SharedApplicationState) {
+        shared.pillar_data = self.pillar_data;
+        shared.pillars_enabled = self.pillars_enabled;
+        shared.pillars_observed_at = self.pillars_observed_at;
+        shared.buried_treasure_data = self.buried_treasure_data;
+        shared.biome_data = self.biome_data;
+        shared.decoration_data = self.decoration_data;
+        shared.bastion_data = self.bastion_data;
+        shared.structure_data = self.structure_data;
+        shared.hit_map.clear();
+    }
+}
+
+pub struct EvidenceEdit {
+    pub before: EvidenceSnapshot,
+    pub after: EvidenceSnapshot,
+}
+
+#[derive(Default)]
+pub struct EvidenceHistory {
+    done: VecDeque<EvidenceEdit>,
+    undone: Vec<EvidenceEdit>,
+    /// Set by every edit, undo and redo, see [`EvidenceHistory::take_changed`]
+    changed: bool,
+}
+
+impl EvidenceHistory {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the edit if it changed anything, the undone edits can't be redone after it
+    pub fn record(&mut self, before: EvidenceSnapshot, after: EvidenceSnapshot) {
+        if before == after {
+            return;
+        }
+        if self.done.len() == MAX_HISTORY {
+            self.done.pop_front();
+        }
+        self.done.push_back(EvidenceEdit { before, after });
+        self.undone.clear();
+        self.changed = true;
+    }
+
+    /// The evidence to restore to revert the last edit
+    pub fn undo(&mut self) -> Option<EvidenceSnapshot> {
+        let edit = self.done.pop_back()?;
+        let before = edit.before.clone();
+        self.undone.push(edit);
+        self.changed = true;
+        Some(before)
+    }
+
+    /// The evidence to restore to apply the last reverted edit again
+    pub fn redo(&mut self) -> Option<EvidenceSnapshot> {
+        let edit = self.undone.pop()?;
+        let after = edit.after.clone();
+        self.done.push_back(edit);
+        self.changed = true;
+        Some(after)
+    }
+
+    /// Whether the evidence changed since the last call
+    #[inline]
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    #[inline]
+    pub fn undo_len(&self) -> usize {
+        self.done.len()
+    }
+
+    #[inline]
+    pub fn redo_len(&self) -> usize {
+        self.undone.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::{
+        cubiomes::enums::BiomeID,
+        features::buried_treasure::items::{DIAMOND, EMERALD},
+        pipeline::ObservedAt,
+        search::StructureSeedSearchData,
+    };
+    use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::tui::{
+        Component, EventContext,
+        application::{
+            ApplicationComponent, ApplicationComponentState, ApplicationTabs,
+            SharedApplicationState,
+        },
+        components::text_input::TextInputState,
+        history::{EvidenceSnapshot, MAX_HISTORY},
+        tabs::{
+            biomes::{BiomesTabComponent, BiomesTabState},
+            buried_treasure::{BuriedTreasureTabComponent, BuriedTreasureTabState, Focus},
+        },
+    };
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn ctrl(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::CONTROL))
+    }
+
+    /// Sends the event to the tab and records the edit, like the application does
+    fn send<C: Component<Shared = SharedApplicationState>>(
+        component: &C,
+        state: &mut C::State,
+        shared: &mut SharedApplicationState,
+        event: Event,
+    ) {
+        let before = EvidenceSnapshot::of(shared);
+        component.handle_event(state, shared, event, EventContext::BubblingDown);
+        shared.history.record(before, EvidenceSnapshot::of(shared));
+    }
+
+    fn undo<C: Component<Shared = SharedApplicationState>>(
+        component: &C,
+        state: &mut C::State,
+        shared: &mut SharedApplicationState,
+    ) {
+        let snapshot = shared.history.undo().expect("nothing to undo");
+        snapshot.restore(shared);
+        component.sync_from_shared(state, shared);
+    }
+
+    fn redo<C: Component<Shared = SharedApplicationState>>(
+        component: &C,
+        state: &mut C::State,
+        shared: &mut SharedApplicationState,
+    ) {
+        let snapshot = shared.history.redo().expect("nothing to redo");
+        snapshot.restore(shared);
+        component.sync_from_shared(state, shared);
+    }
+
+    fn overworld(shared: &SharedApplicationState) -> Vec<(i32, i32, i32, BiomeID)> {
+        shared
+            .biome_data
+            .overworld_biomes
+            .as_overworld()
+            .unwrap()
+            .to_vec()
+    }
+
+    fn text<T>(input: &TextInputState<T>) -> String {
+        input.value.iter().collect()
+    }
+
+    #[test]
+    fn test_undo_redo_biome_rows() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BiomesTabState::default();
+        let tab = BiomesTabComponent;
+        tab.on_focus(&mut state, &mut shared);
+
+        // add a row and type its X and biome
+        send(&tab, &mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char('5')));
+        state.selected_x = 3;
+        for c in "plains".chars() {
+            send(&tab, &mut state, &mut shared, key(KeyCode::Char(c)));
+        }
+        assert_eq!(overworld(&shared), vec![(5, 0, 0, BiomeID::plains)]);
+
+        // keys that don't change the biome are not edits
+        let edits = shared.history.undo_len();
+        assert!(edits < 2 + "plains".len());
+
+        undo(&tab, &mut state, &mut shared);
+        assert_eq!(overworld(&shared), vec![(5, 0, 0, BiomeID::none)]);
+        assert_eq!(text(&state.overworld_biomes[0].3), "");
+        redo(&tab, &mut state, &mut shared);
+        assert_eq!(text(&state.overworld_biomes[0].3), "plains");
+
+        // delete the row, then bring it back
+        send(&tab, &mut state, &mut shared, ctrl(KeyCode::Delete));
+        assert!(overworld(&shared).is_empty());
+        assert!(state.overworld_biomes.is_empty());
+        undo(&tab, &mut state, &mut shared);
+        assert_eq!(overworld(&shared), vec![(5, 0, 0, BiomeID::plains)]);
+        assert_eq!(state.overworld_biomes.len(), 1);
+        assert_eq!(text(&state.overworld_biomes[0].0), "5");
+
+        // back to before the row was added
+        while shared.history.undo_len() > 0 {
+            undo(&tab, &mut state, &mut shared);
+        }
+        assert!(overworld(&shared).is_empty());
+        assert!(state.overworld_biomes.is_empty());
+        assert_eq!(state.selected_y, 0);
+
+        redo(&tab, &mut state, &mut shared);
+        redo(&tab, &mut state, &mut shared);
+        assert_eq!(overworld(&shared), vec![(5, 0, 0, BiomeID::none)]);
+        assert_eq!(text(&state.overworld_biomes[0].0), "5");
+
+        // a new edit drops what could be redone
+        state.selected_x = 1;
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char('7')));
+        assert_eq!(shared.history.redo_len(), 0);
+        assert_eq!(overworld(&shared), vec![(5, 7, 0, BiomeID::none)]);
+    }
+
+    #[test]
+    fn test_undo_redo_chest_slots() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BuriedTreasureTabState::default();
+        let tab = BuriedTreasureTabComponent;
+        state.focus = Focus::Chest;
+
+        let slot = |shared: &SharedApplicationState, x: usize| {
+            shared.buried_treasure_data.contents.rows[0].items[x].map(|stack| stack.item)
+        };
+
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char('d')));
+        send(&tab, &mut state, &mut shared, key(KeyCode::Right));
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char('e')));
+        assert_eq!(slot(&shared, 0), Some(DIAMOND));
+        assert_eq!(slot(&shared, 1), Some(EMERALD));
+        // moving the selection is not an edit
+        assert_eq!(shared.history.undo_len(), 2);
+
+        undo(&tab, &mut state, &mut shared);
+        assert_eq!(slot(&shared, 1), None);
+        assert_eq!(state.contents.contents[0][1].0, "");
+        redo(&tab, &mut state, &mut shared);
+        assert_eq!(state.contents.contents[0][1].0, "Emerald");
+
+        // edit the count, then clear the slot
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char(' ')));
+        send(&tab, &mut state, &mut shared, key(KeyCode::Char('3')));
+        assert_eq!(
+            shared.buried_treasure_data.contents.rows[0].items[1].map(|s| s.count),
+            Some(13)
+        );
+        send(&tab, &mut state, &mut shared, key(KeyCode::Delete));
+        assert_eq!(slot(&shared, 1), None);
+
+        undo(&tab, &mut state, &mut shared);
+        assert_eq!(slot(&shared, 1), Some(EMERALD));
+        assert_eq!(state.contents.contents[0][1].1, 13);
+        undo(&tab, &mut state, &mut shared);
+        undo(&tab, &mut state, &mut shared);
+        assert!(!shared.buried_treasure_data.usable);
+        assert_eq!(state.contents.contents[0][1].1, 1);
+    }
+
+    #[test]
+    fn test_history_keys() {
+        let mut app = ApplicationComponentState::new();
+        app.template_picker = None;
+        let send = |app: &mut ApplicationComponentState, event: Event| {
+            ApplicationComponent::handle_event(app, event, EventContext::BubblingDown);
+        };
+
+        // focus the biomes tab and add more rows than the history keeps
+        send(&mut app, key(KeyCode::Right));
+        send(&mut app, key(KeyCode::Right));
+        send(&mut app, key(KeyCode::Tab));
+        assert_eq!(app.selected_tab, 2);
+        for _ in 0..MAX_HISTORY + 5 {
+            send(&mut app, ctrl(KeyCode::Char('n')));
+        }
+        assert_eq!(overworld(&app.shared).len(), MAX_HISTORY + 5);
+        assert_eq!(app.shared.history.undo_len(), MAX_HISTORY);
+
+        // a search that was already queued is left alone
+        app.shared
+            .structure_seed_search_jobs
+            .extend([StructureSeedSearchData::new(1, vec![], 1)]);
+
+        send(&mut app, ctrl(KeyCode::Char('z')));
+        assert_eq!(overworld(&app.shared).len(), MAX_HISTORY + 4);
+        send(&mut app, ctrl(KeyCode::Char('y')));
+        assert_eq!(overworld(&app.shared).len(), MAX_HISTORY + 5);
+
+        while app.undo() {}
+        assert_eq!(overworld(&app.shared).len(), 5);
+        assert!(!app.undo());
+        assert_eq!(app.shared.structure_seed_search_jobs.len(), 1);
+
+        // undo also works from the tab selector
+        send(&mut app, ctrl(KeyCode::Char('y')));
+        // from the overworld panel to the nether panel, then out of the tab
+        send(&mut app, key(KeyCode::Tab));
+        send(&mut app, key(KeyCode::Tab));
+        assert!(app.focused_on_tab_selector);
+        send(&mut app, ctrl(KeyCode::Char('z')));
+        assert_eq!(overworld(&app.shared).len(), 5);
+    }
+
+    #[test]
+    fn test_observed_at_editor() {
+        let mut app = ApplicationComponentState::new();
+        app.template_picker = None;
+        let send = |app: &mut ApplicationComponentState, event: Event| {
+            ApplicationComponent::handle_event(app, event, EventContext::BubblingDown);
+        };
+
+        // the output tab has no evidence to date
+        app.selected_tab = ApplicationTabs::OUTPUT;
+        send(&mut app, ctrl(KeyCode::Char('o')));
+        assert!(app.observed_at_editor.is_none());
+
+        app.selected_tab = 1;
+        send(&mut app, ctrl(KeyCode::Char('o')));
+        for c in "day 3".chars() {
+            send(&mut app, key(KeyCode::Char(c)));
+        }
+        send(&mut app, key(KeyCode::Tab));
+        // typing in the popup doesn't reach the tabs
+        assert_eq!(app.selected_tab, 1);
+        assert!(app.focused_on_tab_selector);
+        send(&mut app, key(KeyCode::Enter));
+        assert!(app.observed_at_editor.is_none());
+        assert_eq!(
+            app.shared.buried_treasure_data.observed_at,
+            Some(ObservedAt::new("day 3", true))
+        );
+        assert_eq!(app.shared.history.undo_len(), 1);
+
+        // cancelling keeps the old value, clearing everything removes it
+        send(&mut app, ctrl(KeyCode::Char('o')));
+        send(&mut app, key(KeyCode::Backspace));
+        send(&mut app, key(KeyCode::Esc));
+        assert_eq!(
+            app.shared.buried_treasure_data.observed_at,
+            Some(ObservedAt::new("day 3", true))
+        );
+        send(&mut app, ctrl(KeyCode::Char('o')));
+        for _ in 0.."day 3".len() {
+            send(&mut app, key(KeyCode::Backspace));
+        }
+        send(&mut app, key(KeyCode::Tab));
+        send(&mut app, key(KeyCode::Enter));
+        assert_eq!(app.shared.buried_treasure_data.observed_at, None);
+
+        send(&mut app, ctrl(KeyCode::Char('z')));
+        assert_eq!(
+            app.shared.buried_treasure_data.observed_at,
+            Some(ObservedAt::new("day 3", true))
+        );
+    }
+}
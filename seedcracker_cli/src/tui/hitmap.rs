@@ -0,0 +1,120 @@
+use ratatui::layout::{Position, Rect};
+
+use crate::tui::tabs::{biomes, output};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitTarget {
+    BiomesPanel(biomes::Focus),
+    BiomeInput {
+        panel: biomes::Focus,
+        row: usize,
+        column: usize,
+    },
+    OutputPanel(output::Focus),
+}
+
+/// Clickable regions of the frame that is currently on screen.
+///
+/// It is cleared before every frame and filled while rendering, so mouse events are always tested against what the
+/// user sees. Handlers that change the layout (adding or removing rows) must clear it too: until the next frame is
+/// rendered a click then hits nothing, instead of whatever used to be there.
+#[derive(Debug, Default, Clone)]
+pub struct HitMap {
+    regions: Vec<(Rect, HitTarget)>,
+}
+
+impl HitMap {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    #[inline]
+    pub fn record(&mut self, area: Rect, target: HitTarget) {
+        if !area.is_empty() {
+            self.regions.push((area, target));
+        }
+    }
+
+    /// Every target under the given terminal cell, the last recorded (topmost) first
+    pub fn hits(&self, column: u16, row: u16) -> impl Iterator<Item = HitTarget> + '_ {
+        let position = Position::new(column, row);
+        self.regions
+            .iter()
+            .rev()
+            .filter(move |(area, _)| area.contains(position))
+            .map(|(_, target)| *target)
+    }
+
+    /// The topmost target under the given terminal cell
+    #[inline]
+    pub fn hit(&self, column: u16, row: u16) -> Option<HitTarget> {
+        self.hits(column, row).next()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use crate::tui::{
+        hitmap::{HitMap, HitTarget},
+        tabs::{biomes, output},
+    };
+
+    #[test]
+    fn test_hitmap_topmost_first() {
+        let mut map = HitMap::new();
+        map.record(
+            Rect::new(0, 0, 20, 10),
+            HitTarget::BiomesPanel(biomes::Focus::Overworld),
+        );
+        map.record(
+            Rect::new(2, 2, 5, 3),
+            HitTarget::BiomeInput {
+                panel: biomes::Focus::Overworld,
+                row: 0,
+                column: 0,
+            },
+        );
+        map.record(
+            Rect::new(30, 0, 0, 10),
+            HitTarget::OutputPanel(output::Focus::WorldSeedButton),
+        );
+
+        assert_eq!(
+            map.hits(3, 3).collect::<Vec<_>>(),
+            vec![
+                HitTarget::BiomeInput {
+                    panel: biomes::Focus::Overworld,
+                    row: 0,
+                    column: 0,
+                },
+                HitTarget::BiomesPanel(biomes::Focus::Overworld),
+            ]
+        );
+        assert_eq!(
+            map.hit(10, 9),
+            Some(HitTarget::BiomesPanel(biomes::Focus::Overworld))
+        );
+        // empty areas are never recorded
+        assert_eq!(map.hit(30, 5), None);
+        assert_eq!(map.hit(20, 0), None);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.hit(3, 3), None);
+    }
+}
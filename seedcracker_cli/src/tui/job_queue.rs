@@ -0,0 +1,535 @@
+use std::collections::{VecDeque, vec_deque};
+
+use mcseedcracker::{
+    rejections::RejectionStats,
+    search::{
+        KnownStructure, StructureData, StructureSeedSearchData, WorldExtraData, WorldSeedSearchData,
+    },
+    world_seed::SeedOrigin,
+};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{Color, Stylize},
+    text::Line,
+};
+
+use crate::tui::application::{
+    PillarSeedStructureSim, SharedApplicationState, StructureSeedSim, StructureSeedSimResultType,
+    StructureSeedWorldSim, WorldSeedSimResultType,
+};
+
+/// The searches waiting for the running one. The running search was taken out of the queue when it started, so the
+/// positions here only cover the pending ones and nothing can be moved before or in place of it.
+#[derive(Debug, Clone)]
+pub struct JobQueue<T> {
+    jobs: VecDeque<T>,
+}
+
+impl<T> Default for JobQueue<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> JobQueue<T> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            jobs: VecDeque::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    #[inline]
+    pub fn get(&self, position: usize) -> Option<&T> {
+        self.jobs.get(position)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.jobs.iter()
+    }
+
+    /// The next job to run
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.jobs.pop_front()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+    }
+
+    /// Takes every pending job out
+    #[inline]
+    pub fn drain(&mut self) -> vec_deque::Drain<'_, T> {
+        self.jobs.drain(..)
+    }
+
+    /// Swaps the job with the one before it, false if it is the first one or there is no such job
+    pub fn move_up(&mut self, position: usize) -> bool {
+        if position == 0 || position >= self.jobs.len() {
+            return false;
+        }
+        self.jobs.swap(position - 1, position);
+        true
+    }
+
+    /// Swaps the job with the one after it, false if it is the last one or there is no such job
+    pub fn move_down(&mut self, position: usize) -> bool {
+        if position + 1 >= self.jobs.len() {
+            return false;
+        }
+        self.jobs.swap(position, position + 1);
+        true
+    }
+
+    #[inline]
+    pub fn remove(&mut self, position: usize) -> Option<T> {
+        self.jobs.remove(position)
+    }
+}
+
+impl<T> Extend<T> for JobQueue<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.jobs.extend(iter);
+    }
+}
+
+/// Shown when a key would move or remove the running search
+pub const RUNNING_JOB_LOCKED: &str =
+    "The running search can't be moved or removed, cancel it with [ENTER]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueKind {
+    StructureSeeds,
+    WorldSeeds,
+}
+
+/// The job queue popup of the output tab, opened with [J]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobQueuePopup {
+    pub kind: QueueKind,
+    /// Selected row, the running search is the first row while there is one
+    pub selected: usize,
+    /// Lists the evidence of the selected search under the queue
+    pub inspect: bool,
+    /// Why the last key did nothing
+    pub message: Option<&'static str>,
+}
+
+fn structure_job_line(shared: &SharedApplicationState, pillar_seed: i64) -> String {
+    let chance = shared
+        .last_pillar_sim
+        .as_ref()
+        .and_then(|(_, sim)| sim.iter().find(|(seed, _)| *seed == pillar_seed))
+        .map(|(_, result)| result.chance());
+    match chance {
+        Some(chance) => format!("Pillar seed {} ({:.2}%)", pillar_seed, chance * 100.0),
+        None => format!("Pillar seed {}", pillar_seed),
+    }
+}
+
+fn structure_job_evidence(job: &StructureSeedSearchData) -> Vec<String> {
+    let loot_table = |table: bool| if table { ", custom loot table" } else { "" };
+    let mut lines = job
+        .data
+        .iter()
+        .map(|data| match data {
+            StructureData::BuriedTreasureContents {
+                chunk_x,
+                chunk_z,
+                luck,
+                contents,
+                loot_table: table,
+            } => format!(
+                "Buried treasure in chunk ({}, {}), luck {}, {} item(s) seen{}",
+                chunk_x,
+                chunk_z,
+                luck,
+                contents.total_items,
+                loot_table(table.is_some())
+            ),
+            StructureData::BuriedTreasureCounts {
+                chunk_x,
+                chunk_z,
+                luck,
+                counts,
+                loot_table: table,
+            } => format!(
+                "Buried treasure item counts in chunk ({}, {}), luck {}, {} bound(s){}",
+                chunk_x,
+                chunk_z,
+                luck,
+                counts.bounds.len(),
+                loot_table(table.is_some())
+            ),
+            StructureData::BuriedTreasureContentsInArea {
+                area,
+                luck,
+                contents,
+                loot_table: table,
+            } => format!(
+                "Buried treasure in chunks ({}, {}) to ({}, {}), luck {}, {} item(s) seen{}",
+                area.min.0,
+                area.min.1,
+                area.max.0,
+                area.max.1,
+                luck,
+                contents.total_items,
+                loot_table(table.is_some())
+            ),
+            StructureData::BastionChestContents {
+                chunk_x,
+                chunk_z,
+                index,
+                chest_type,
+                luck,
+                contents,
+                ..
+            } => format!(
+                "Bastion {} chest in chunk ({}, {}), {}, luck {}, {} item(s) seen",
+                chest_type.name(),
+                chunk_x,
+                chunk_z,
+                index,
+                luck,
+                contents.total_items
+            ),
+        })
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        lines.push("No structure evidence, only the pillars".to_string());
+    }
+    if job.nextlong_only {
+        lines.push("Only structure seeds of a random world seed".to_string());
+    }
+    lines.push(format!("Stops after {} structure seed(s)", job.max_results));
+    lines
+}
+
+fn world_job_evidence(job: &WorldSeedSearchData) -> Vec<String> {
+    let mut lines = job
+        .data
+        .iter()
+        .map(|data| match data {
+            WorldExtraData::OverworldBiomeData(samples) => {
+                format!("{} overworld biome sample(s)", samples.len())
+            }
+            WorldExtraData::NetherBiomeData(samples) => {
+                format!("{} nether biome sample(s)", samples.len())
+            }
+            WorldExtraData::DecorationCount {
+                chunk,
+                decoration,
+                observed_count,
+                tolerance,
+                ..
+            } => format!(
+                "{} {} in chunk ({}, {}), give or take {}",
+                observed_count,
+                decoration.name(),
+                chunk.0,
+                chunk.1,
+                tolerance
+            ),
+            WorldExtraData::StructurePresence {
+                structure_type,
+                block_x,
+                block_z,
+                ..
+            } => format!(
+                "{} at ({}, {})",
+                KnownStructure::of(*structure_type).map_or("structure", |s| s.name()),
+                block_x,
+                block_z
+            ),
+        })
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        lines.push("No world evidence".to_string());
+    }
+    if job.origin != SeedOrigin::Any {
+        lines.push(format!("Only the {} world seeds", job.origin.name()));
+    } else if job.is_random_world_seed {
+        lines.push("The world seed was generated by the game".to_string());
+    }
+    lines.push(format!("Stops after {} world seed(s)", job.max_results));
+    lines
+}
+
+impl JobQueuePopup {
+    #[inline]
+    pub const fn new(kind: QueueKind) -> Self {
+        Self {
+            kind,
+            selected: 0,
+            inspect: false,
+            message: None,
+        }
+    }
+
+    /// The first row is the running search
+    fn has_running(&self, shared: &SharedApplicationState) -> bool {
+        match self.kind {
+            QueueKind::StructureSeeds => shared.current_structure_seed_searcher.is_some(),
+            QueueKind::WorldSeeds => shared.current_world_seed_searcher.is_some(),
+        }
+    }
+
+    fn rows(&self, shared: &SharedApplicationState) -> usize {
+        let pending = match self.kind {
+            QueueKind::StructureSeeds => shared.structure_seed_search_jobs.len(),
+            QueueKind::WorldSeeds => shared.world_seed_search_jobs.len(),
+        };
+        pending + self.has_running(shared) as usize
+    }
+
+    /// Position in the queue of the selected row, None for the running search
+    fn selected_position(&self, shared: &SharedApplicationState) -> Option<usize> {
+        if self.has_running(shared) {
+            self.selected.checked_sub(1)
+        } else {
+            Some(self.selected)
+        }
+    }
+
+    /// The structure stage schedule hands out the pillar seeds in the order of the queue
+    fn structure_queue_changed(shared: &mut SharedApplicationState) {
+        if let Some(schedule) = &mut shared.last_structure_seed_sim.schedule {
+            schedule.reschedule(
+                shared
+                    .structure_seed_search_jobs
+                    .iter()
+                    .map(|j| j.pillar_seed),
+            );
+        }
+    }
+
+    fn move_selected(&mut self, shared: &mut SharedApplicationState, up: bool) {
+        let Some(position) = self.selected_position(shared) else {
+            self.message = Some(RUNNING_JOB_LOCKED);
+            return;
+        };
+        if up && position == 0 && self.has_running(shared) {
+            self.message = Some(RUNNING_JOB_LOCKED);
+            return;
+        }
+        let moved = match (self.kind, up) {
+            (QueueKind::StructureSeeds, true) => {
+                shared.structure_seed_search_jobs.move_up(position)
+            }
+            (QueueKind::StructureSeeds, false) => {
+                shared.structure_seed_search_jobs.move_down(position)
+            }
+            (QueueKind::WorldSeeds, true) => shared.world_seed_search_jobs.move_up(position),
+            (QueueKind::WorldSeeds, false) => shared.world_seed_search_jobs.move_down(position),
+        };
+        if moved {
+            self.selected = if up {
+                self.selected - 1
+            } else {
+                self.selected + 1
+            };
+            if self.kind == QueueKind::StructureSeeds {
+                Self::structure_queue_changed(shared);
+            }
+        }
+    }
+
+    /// The removed search is listed with the results, so they don't look complete
+    fn remove_selected(&mut self, shared: &mut SharedApplicationState) {
+        let Some(position) = self.selected_position(shared) else {
+            self.message = Some(RUNNING_JOB_LOCKED);
+            return;
+        };
+        match self.kind {
+            QueueKind::StructureSeeds => {
+                let Some(job) = shared.structure_seed_search_jobs.remove(position) else {
+                    return;
+                };
+                Self::structure_queue_changed(shared);
+                shared
+                    .last_structure_seed_sim
+                    .data
+                    .get_or_insert_with(|| StructureSeedSim {
+                        count_seeds: 0,
+                        per_pillar: Vec::new(),
+                    })
+                    .per_pillar
+                    .push(PillarSeedStructureSim {
+                        pillar_seed: job.pillar_seed,
+                        result: StructureSeedSimResultType::Removed,
+                        structure_seeds: Vec::new(),
+                        rejections: RejectionStats::default(),
+                    });
+                if shared.current_structure_seed_searcher.is_none()
+                    && shared.structure_seed_search_jobs.is_empty()
+                {
+                    shared.last_structure_seed_sim.outdated_data = false;
+                }
+            }
+            QueueKind::WorldSeeds => {
+                let Some(job) = shared.world_seed_search_jobs.remove(position) else {
+                    return;
+                };
+                shared
+                    .world_seed_sim
+                    .per_structure
+                    .push(StructureSeedWorldSim {
+                        structure_seed: job.structure_seed,
+                        result: WorldSeedSimResultType::Removed,
+                        world_seeds: Vec::new(),
+                        skipped_candidates: 0,
+                        fits: Vec::new(),
+                        rejections: RejectionStats::default(),
+                    });
+            }
+        }
+        self.selected = self.selected.min(self.rows(shared).saturating_sub(1));
+    }
+
+    /// Applies a key press, false once the popup is closed
+    pub fn handle_key(&mut self, shared: &mut SharedApplicationState, key: &KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return true;
+        }
+        self.message = None;
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('j') => return false,
+            KeyCode::Up if shift => self.move_selected(shared, true),
+            KeyCode::Down if shift => self.move_selected(shared, false),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.rows(shared).saturating_sub(1));
+            }
+            KeyCode::Delete | KeyCode::Char('x') => self.remove_selected(shared),
+            KeyCode::Enter => self.inspect = !self.inspect,
+            KeyCode::Tab => {
+                *self = Self::new(match self.kind {
+                    QueueKind::StructureSeeds => QueueKind::WorldSeeds,
+                    QueueKind::WorldSeeds => QueueKind::StructureSeeds,
+                });
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// The rows of the queue, then the evidence of the selected search when it is inspected
+    pub fn lines(&self, shared: &SharedApplicationState) -> Vec<Line<'static>> {
+        let mut rows = Vec::new();
+        let mut evidence = None;
+        match self.kind {
+            QueueKind::StructureSeeds => {
+                if let Some(searcher) = &shared.current_structure_seed_searcher {
+                    rows.push(structure_job_line(shared, searcher.pillar_seed()));
+                }
+                for job in shared.structure_seed_search_jobs.iter() {
+                    rows.push(structure_job_line(shared, job.pillar_seed));
+                }
+                if let Some(position) = self.selected_position(shared) {
+                    evidence = shared
+                        .structure_seed_search_jobs
+                        .get(position)
+                        .map(structure_job_evidence);
+                }
+            }
+            QueueKind::WorldSeeds => {
+                if let Some(searcher) = &shared.current_world_seed_searcher {
+                    rows.push(format!("Structure seed {}", searcher.get_structure_seed()));
+                }
+                for job in shared.world_seed_search_jobs.iter() {
+                    rows.push(format!("Structure seed {}", job.structure_seed));
+                }
+                if let Some(position) = self.selected_position(shared) {
+                    evidence = shared
+                        .world_seed_search_jobs
+                        .get(position)
+                        .map(world_job_evidence);
+                }
+            }
+        }
+
+        let running = self.has_running(shared);
+        let mut lines: Vec<Line<'static>> = vec![
+            match self.kind {
+                QueueKind::StructureSeeds => "Structure seed searches",
+                QueueKind::WorldSeeds => "World seed searches",
+            }
+            .bold()
+            .into(),
+        ];
+        if rows.is_empty() {
+            lines.push("Nothing queued".gray().into());
+        }
+        for (i, row) in rows.into_iter().enumerate() {
+            let selected = i == self.selected;
+            let mut line = Line::from(if selected {
+                format!("> {}", row).light_cyan()
+            } else {
+                format!("  {}", row).white()
+            });
+            if running && i == 0 {
+                line.push_span(" (running)".yellow());
+            }
+            lines.push(line);
+        }
+        if self.inspect {
+            lines.push(Line::default());
+            match evidence {
+                Some(evidence) => lines.extend(evidence.into_iter().map(|l| l.gray().into())),
+                None if running && self.selected == 0 => lines.push(
+                    "Searching with the evidence it was started with"
+                        .gray()
+                        .into(),
+                ),
+                None => {}
+            }
+        }
+        if let Some(message) = self.message {
+            lines.push(Line::default());
+            lines.push(message.fg(Color::Red).into());
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tui::job_queue::JobQueue;
+
+    #[test]
+    fn test_job_queue() {
+        let mut queue = JobQueue::new();
+        queue.extend([1, 2, 3]);
+
+        assert!(queue.move_down(0));
+        assert!(queue.move_up(2));
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [2, 3, 1]);
+        // nothing before the first job or after the last one
+        assert!(!queue.move_up(0));
+        assert!(!queue.move_down(2));
+        assert!(!queue.move_down(3));
+        assert!(!queue.move_up(3));
+
+        assert_eq!(queue.remove(1), Some(3));
+        assert_eq!(queue.remove(2), None);
+        assert_eq!(queue.pop_front(), Some(2));
+        assert_eq!(queue.drain().collect::<Vec<_>>(), [1]);
+        assert!(queue.is_empty());
+    }
+}
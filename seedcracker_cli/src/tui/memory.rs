@@ -0,0 +1,209 @@
+/// What keeps memory around between searches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryConsumer {
+    /// The pillar seeds matching the pillar evidence
+    PillarCache,
+    StructureSeedResults,
+    WorldSeedResults,
+}
+
+impl MemoryConsumer {
+    /// Caches are recomputed when they are needed again, evicting them loses nothing
+    #[inline]
+    pub const fn is_cache(self) -> bool {
+        matches!(self, Self::PillarCache)
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PillarCache => "pillar seed cache",
+            Self::StructureSeedResults => "structure seed results",
+            Self::WorldSeedResults => "world seed results",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MemoryEntry {
+    consumer: MemoryConsumer,
+    bytes: usize,
+    /// Tick of the last use of a cache, or of the last change of results
+    last_used: u64,
+}
+
+/// Approximate sizes of the caches and results of the session, against an optional cap.
+///
+/// The sizes are estimates from the lengths of the collections, they leave out the allocator overhead but are close
+/// enough to tell which store grew.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBudget {
+    entries: Vec<MemoryEntry>,
+    tick: u64,
+    /// Bytes the stores may use before they are evicted, `None` for no cap
+    pub cap: Option<usize>,
+    /// The last results that were dropped to stay under the cap, shown in the footer
+    pub warning: Option<String>,
+}
+
+impl MemoryBudget {
+    #[inline]
+    pub const fn new(cap: Option<usize>) -> Self {
+        Self {
+            entries: Vec::new(),
+            tick: 0,
+            cap,
+            warning: None,
+        }
+    }
+
+    /// Registers the size of a store, a store of 0 bytes is unregistered. A change of size counts as a use.
+    pub fn update(&mut self, consumer: MemoryConsumer, bytes: usize) {
+        let index = self.entries.iter().position(|e| e.consumer == consumer);
+        match index {
+            _ if bytes == 0 => self.entries.retain(|e| e.consumer != consumer),
+            Some(i) if self.entries[i].bytes == bytes => {}
+            Some(i) => {
+                self.tick += 1;
+                self.entries[i].bytes = bytes;
+                self.entries[i].last_used = self.tick;
+            }
+            None => {
+                self.tick += 1;
+                self.entries.push(MemoryEntry {
+                    consumer,
+                    bytes,
+                    last_used: self.tick,
+                });
+            }
+        }
+    }
+
+    /// Marks a store as used without changing its size
+    pub fn touch(&mut self, consumer: MemoryConsumer) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.consumer == consumer) {
+            self.tick += 1;
+            entry.last_used = self.tick;
+        }
+    }
+
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|e| e.bytes).sum()
+    }
+
+    /// The stores to drop to get under the cap: the caches least recently used first, then the results oldest first
+    pub fn evictions(&self) -> Vec<MemoryConsumer> {
+        let Some(cap) = self.cap else {
+            return Vec::new();
+        };
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|e| (!e.consumer.is_cache(), e.last_used));
+
+        let mut total = self.total();
+        let mut evictions = Vec::new();
+        for entry in entries {
+            if total <= cap {
+                break;
+            }
+            total -= entry.bytes;
+            evictions.push(entry.consumer);
+        }
+        evictions
+    }
+
+    /// The total, and the cap if there is one, e.g. `"Memory: 1.5 MiB / 64.0 MiB"`
+    pub fn summary(&self) -> String {
+        match self.cap {
+            Some(cap) => format!(
+                "Memory: {} / {}",
+                format_bytes(self.total()),
+                format_bytes(cap)
+            ),
+            None => format!("Memory: {}", format_bytes(self.total())),
+        }
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tui::memory::{MemoryBudget, MemoryConsumer, format_bytes};
+
+    #[test]
+    fn test_registration_and_update() {
+        let mut budget = MemoryBudget::new(None);
+        assert_eq!(budget.total(), 0);
+
+        budget.update(MemoryConsumer::PillarCache, 1000);
+        budget.update(MemoryConsumer::StructureSeedResults, 24);
+        assert_eq!(budget.total(), 1024);
+        assert_eq!(budget.summary(), "Memory: 1.0 KiB");
+
+        budget.update(MemoryConsumer::PillarCache, 2000);
+        assert_eq!(budget.total(), 2024);
+        budget.update(MemoryConsumer::StructureSeedResults, 0);
+        assert_eq!(budget.total(), 2000);
+        // no cap, nothing to evict however large the stores are
+        budget.update(MemoryConsumer::WorldSeedResults, usize::MAX / 2);
+        assert!(budget.evictions().is_empty());
+
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(3 << 29), "1.5 GiB");
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let mut budget = MemoryBudget::new(Some(100));
+        budget.update(MemoryConsumer::StructureSeedResults, 40);
+        budget.update(MemoryConsumer::PillarCache, 40);
+        budget.update(MemoryConsumer::WorldSeedResults, 20);
+        assert!(budget.evictions().is_empty());
+
+        // the cache goes first even though it was used last, then the oldest results
+        budget.update(MemoryConsumer::WorldSeedResults, 30);
+        assert_eq!(budget.evictions(), vec![MemoryConsumer::PillarCache]);
+        budget.update(MemoryConsumer::WorldSeedResults, 70);
+        assert_eq!(
+            budget.evictions(),
+            vec![
+                MemoryConsumer::PillarCache,
+                MemoryConsumer::StructureSeedResults
+            ]
+        );
+
+        // the same size isn't a change, the structure seed results stay the oldest
+        budget.update(MemoryConsumer::StructureSeedResults, 40);
+        budget.update(MemoryConsumer::PillarCache, 0);
+        assert_eq!(
+            budget.evictions(),
+            vec![MemoryConsumer::StructureSeedResults]
+        );
+        budget.update(MemoryConsumer::StructureSeedResults, 41);
+        budget.touch(MemoryConsumer::WorldSeedResults);
+        assert_eq!(
+            budget.evictions(),
+            vec![MemoryConsumer::StructureSeedResults]
+        );
+        budget.update(MemoryConsumer::WorldSeedResults, 71);
+        budget.touch(MemoryConsumer::StructureSeedResults);
+        assert_eq!(budget.evictions(), vec![MemoryConsumer::WorldSeedResults]);
+
+        // a store larger than the cap on its own is dropped too
+        budget.update(MemoryConsumer::StructureSeedResults, 0);
+        budget.update(MemoryConsumer::WorldSeedResults, 101);
+        assert_eq!(budget.evictions(), vec![MemoryConsumer::WorldSeedResults]);
+    }
+}
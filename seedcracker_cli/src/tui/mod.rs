@@ -1,36 +1,70 @@
-use ratatui::{buffer::Buffer, crossterm::event::Event, layout::Rect};
+use mcseedcracker::templates::EvidenceTemplate;
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
 
 use crate::tui::application::SharedApplicationState;
 
 pub mod application;
 pub mod components;
+pub mod evidence;
+pub mod history;
+pub mod hitmap;
+pub mod job_queue;
+pub mod memory;
+pub mod recovery;
 pub mod tabs;
+pub mod tutorial;
 
 pub trait Component {
     type State;
+    /// State shared with the other components, `()` for components that don't need any. Tabs use
+    /// [`SharedApplicationState`].
+    type Shared;
 
     fn render(
         &self,
         area: Rect,
         buf: &mut Buffer,
         state: &mut Self::State,
-        shared: &mut SharedApplicationState,
+        shared: &mut Self::Shared,
     );
 
     fn handle_event(
         &self,
         state: &mut Self::State,
-        shared: &mut SharedApplicationState,
+        shared: &mut Self::Shared,
         event: Event,
         context: EventContext,
     ) -> EventResult;
 
-    fn on_focus(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+    fn on_focus(&self, state: &mut Self::State, shared: &mut Self::Shared) {
         let _ = state;
         let _ = shared;
     }
 
-    fn on_unfocus(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+    fn on_unfocus(&self, state: &mut Self::State, shared: &mut Self::Shared) {
+        let _ = state;
+        let _ = shared;
+    }
+
+    /// Prepares the inputs the template asks for, only called while all evidence is empty
+    fn apply_template(
+        &self,
+        state: &mut Self::State,
+        shared: &mut Self::Shared,
+        template: &EvidenceTemplate,
+    ) {
+        let _ = state;
+        let _ = shared;
+        let _ = template;
+    }
+
+    /// Rebuilds the inputs from the shared state, after an undo or a redo replaced the evidence
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut Self::Shared) {
         let _ = state;
         let _ = shared;
     }
@@ -61,6 +95,10 @@ pub trait FullComponent {
     fn on_focus(&mut self, shared: &mut SharedApplicationState);
 
     fn on_unfocus(&mut self, shared: &mut SharedApplicationState);
+
+    fn apply_template(&mut self, template: &EvidenceTemplate, shared: &mut SharedApplicationState);
+
+    fn sync_from_shared(&mut self, shared: &mut SharedApplicationState);
 }
 
 #[macro_export]
@@ -105,6 +143,18 @@ macro_rules! make_full_component {
             fn on_unfocus(&mut self, shared: &mut SharedApplicationState) {
                 $component::default().on_unfocus(&mut self.state, shared);
             }
+
+            fn apply_template(
+                &mut self,
+                template: &mcseedcracker::templates::EvidenceTemplate,
+                shared: &mut SharedApplicationState,
+            ) {
+                $component::default().apply_template(&mut self.state, shared, template);
+            }
+
+            fn sync_from_shared(&mut self, shared: &mut SharedApplicationState) {
+                $component::default().sync_from_shared(&mut self.state, shared);
+            }
         }
     };
 }
@@ -132,3 +182,80 @@ pub const fn limit_area_width(area: Rect, max_width: u16) -> Rect {
     };
     Rect::new(area.x, area.y, width, area.height)
 }
+
+/// Renders the area the way disabled evidence is shown, call it after drawing the evidence
+pub fn dim_area(area: Rect, buf: &mut Buffer) {
+    buf.set_style(
+        area.intersection(buf.area),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM),
+    );
+}
+
+/// [SPACE] enables or disables the focused evidence row in every tab
+pub fn is_toggle_evidence_key(event: &Event) -> bool {
+    matches!(event, Event::Key(key) if key.code == KeyCode::Char(' ')
+        && key.kind == KeyEventKind::Press
+        && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        layout::Rect,
+    };
+
+    use crate::tui::{Component, EventContext, EventResult};
+
+    /// Counts the key presses it sees, without any shared state
+    #[derive(Default)]
+    struct KeyCounter;
+
+    impl Component for KeyCounter {
+        type State = usize;
+        type Shared = ();
+
+        fn render(&self, _area: Rect, _buf: &mut Buffer, _state: &mut usize, _shared: &mut ()) {}
+
+        fn handle_event(
+            &self,
+            state: &mut usize,
+            _shared: &mut (),
+            event: Event,
+            _context: EventContext,
+        ) -> EventResult {
+            match event {
+                Event::Key(_) => {
+                    *state += 1;
+                    EventResult::Captured
+                }
+                event => EventResult::BubbleUp(event),
+            }
+        }
+    }
+
+    #[test]
+    fn test_component_without_shared_state() {
+        let mut count = 0;
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+
+        KeyCounter.on_focus(&mut count, &mut ());
+        assert!(matches!(
+            KeyCounter.handle_event(&mut count, &mut (), key, EventContext::BubblingDown),
+            EventResult::Captured
+        ));
+        assert!(matches!(
+            KeyCounter.handle_event(
+                &mut count,
+                &mut (),
+                Event::FocusLost,
+                EventContext::BubblingDown
+            ),
+            EventResult::BubbleUp(Event::FocusLost)
+        ));
+        assert_eq!(count, 1);
+    }
+}
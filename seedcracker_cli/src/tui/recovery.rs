@@ -0,0 +1,916 @@
+//! Crash recovery of the evidence. Every evidence change is written to a recovery file in the cache directory shortly
+//! after it was made, the file is removed when the TUI exits cleanly. A file left over by a panic or a closed
+//! terminal is offered for restoring on the next start.
+//!
+//! The file holds one piece of evidence per line, e.g. `slot 4 12 1 64` for a stack of the buried treasure chest.
+
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use mcseedcracker::{
+    cubiomes::enums::BiomeID,
+    features::{
+        bastion::BastionChestType,
+        decoration::DecorationKind,
+        decorator_chest::ChestIndex,
+        end_pillars::{PartialEndPillar, PartialEndPillars, PillarHeightHint},
+    },
+    loot_table::{CountBound, ItemProperty, ItemStack, SingleChest},
+    pipeline::{BuriedTreasureEvidence, ObservedAt},
+    search::KnownStructure,
+    view::EnabledRows,
+};
+
+use crate::tui::{
+    history::EvidenceSnapshot,
+    tabs::{
+        bastion::BastionTabSharedData,
+        biomes::{BiomesTabSharedData, list_biomes},
+        buried_treasure::BuriedTreasureTabSharedData,
+        decorations::{DecorationEntry, DecorationsTabSharedData},
+        structures::{StructureEntry, StructuresTabSharedData},
+    },
+};
+
+/// First line of a recovery file, a file of another version is not restored
+const HEADER: &str = "mcseedcracker recovery 1";
+
+/// `mcseedcracker/recovery.txt` in the cache directory of the platform, None if it can't be found
+pub fn recovery_path() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+    let cache = if cfg!(windows) {
+        var("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| PathBuf::from(home).join("Library").join("Caches"))
+    } else {
+        var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    cache.map(|dir| dir.join("mcseedcracker").join("recovery.txt"))
+}
+
+/// Writes a temporary file next to `path` and renames it, a crash while writing never leaves half a file behind
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    {
+        let mut file = fs::File::create(&temp)?;
+        io::Write::write_all(&mut file, contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp, path)
+}
+
+/// When to save after a change: once no change came for `delay`, or `max_delay` after the first unsaved change while
+/// the changes keep coming
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Debounce {
+    pub delay: Duration,
+    pub max_delay: Duration,
+    /// First and last unsaved change
+    pending: Option<(Instant, Instant)>,
+}
+
+impl Debounce {
+    pub const DEFAULT_DELAY: Duration = Duration::from_secs(1);
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+    #[inline]
+    pub const fn new(delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            delay,
+            max_delay,
+            pending: None,
+        }
+    }
+
+    #[inline]
+    pub fn mark_dirty(&mut self, now: Instant) {
+        self.pending = Some(match self.pending {
+            Some((first, _)) => (first, now),
+            None => (now, now),
+        });
+    }
+
+    /// Whether the changes are to be saved now, they then count as saved
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        let Some((first, last)) = self.pending else {
+            return false;
+        };
+        let due = now.saturating_duration_since(last) >= self.delay
+            || now.saturating_duration_since(first) >= self.max_delay;
+        if due {
+            self.pending = None;
+        }
+        due
+    }
+}
+
+impl Default for Debounce {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DELAY, Self::DEFAULT_MAX_DELAY)
+    }
+}
+
+/// Writes the recovery file on its own thread, so a slow disk never holds up a frame
+pub struct AutoSave {
+    path: PathBuf,
+    pub debounce: Debounce,
+    sender: Option<Sender<String>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl AutoSave {
+    pub fn start(path: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            while let Ok(mut contents) = receiver.recv() {
+                // only the latest evidence matters when several saves queued up
+                while let Ok(newer) = receiver.try_recv() {
+                    contents = newer;
+                }
+                // the next change tries again, there is no one to show the error to mid-frame
+                let _ = write_atomic(&writer_path, &contents);
+            }
+        });
+        Self {
+            path,
+            debounce: Debounce::default(),
+            sender: Some(sender),
+            writer: Some(writer),
+        }
+    }
+
+    /// The evidence of a previous run that didn't exit cleanly, if its recovery file is still there
+    pub fn leftover(&self) -> Option<EvidenceSnapshot> {
+        decode(&fs::read_to_string(&self.path).ok()?)
+    }
+
+    /// Call once per frame, `changed` tells whether the evidence changed since the last frame
+    pub fn update(
+        &mut self,
+        changed: bool,
+        now: Instant,
+        evidence: impl FnOnce() -> EvidenceSnapshot,
+    ) {
+        if changed {
+            self.debounce.mark_dirty(now);
+        }
+        if !self.debounce.take_due(now) {
+            return;
+        }
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(encode(&evidence()));
+        }
+    }
+
+    /// Waits for the queued writes and removes the recovery file, on a clean exit
+    pub fn finish(mut self) -> io::Result<()> {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn encode_height(height: PillarHeightHint) -> String {
+    match height {
+        PillarHeightHint::Unknown => "?".to_string(),
+        PillarHeightHint::Small => "small".to_string(),
+        PillarHeightHint::MediumSmall => "medium_small".to_string(),
+        PillarHeightHint::Medium => "medium".to_string(),
+        PillarHeightHint::MediumBig => "medium_big".to_string(),
+        PillarHeightHint::Big => "big".to_string(),
+        PillarHeightHint::Exact(h) => format!("={}", h),
+        PillarHeightHint::Range(min, max) => format!("{}..{}", min, max),
+    }
+}
+
+fn decode_height(text: &str) -> Option<PillarHeightHint> {
+    Some(match text {
+        "?" => PillarHeightHint::Unknown,
+        "small" => PillarHeightHint::Small,
+        "medium_small" => PillarHeightHint::MediumSmall,
+        "medium" => PillarHeightHint::Medium,
+        "medium_big" => PillarHeightHint::MediumBig,
+        "big" => PillarHeightHint::Big,
+        _ => match text.strip_prefix('=') {
+            Some(h) => PillarHeightHint::Exact(h.parse().ok()?),
+            None => {
+                let (min, max) = text.split_once("..")?;
+                PillarHeightHint::Range(min.parse().ok()?, max.parse().ok()?)
+            }
+        },
+    })
+}
+
+fn encode_bound(bound: CountBound) -> String {
+    match bound {
+        CountBound::Exactly(n) => format!("={}", n),
+        CountBound::AtLeast(n) => format!(">={}", n),
+        CountBound::AtMost(n) => format!("<={}", n),
+        CountBound::Between(min, max) => format!("{}..{}", min, max),
+    }
+}
+
+fn decode_bound(text: &str) -> Option<CountBound> {
+    if let Some(n) = text.strip_prefix(">=") {
+        Some(CountBound::AtLeast(n.parse().ok()?))
+    } else if let Some(n) = text.strip_prefix("<=") {
+        Some(CountBound::AtMost(n.parse().ok()?))
+    } else if let Some(n) = text.strip_prefix('=') {
+        Some(CountBound::Exactly(n.parse().ok()?))
+    } else {
+        let (min, max) = text.split_once("..")?;
+        Some(CountBound::Between(min.parse().ok()?, max.parse().ok()?))
+    }
+}
+
+#[inline]
+const fn flag(value: bool) -> char {
+    if value { '1' } else { '0' }
+}
+
+fn decode_flag(text: &str) -> Option<bool> {
+    match text {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn encode_flags(flags: &[bool]) -> String {
+    flags.iter().map(|f| flag(*f)).collect()
+}
+
+fn decode_flags(text: &str) -> Option<Vec<bool>> {
+    text.chars().map(|c| decode_flag(&c.to_string())).collect()
+}
+
+/// One `<kind> <slot> <item> <count> <max count> <properties>` line per stack of the chest
+fn encode_slots(out: &mut String, kind: &str, chest: &SingleChest) {
+    for (i, stack) in chest.rows.iter().flat_map(|r| r.items.iter()).enumerate() {
+        let Some(stack) = stack else {
+            continue;
+        };
+        let _ = write!(
+            out,
+            "{} {} {} {} {}",
+            kind, i, stack.item, stack.count, stack.max_count
+        );
+        for property in stack.properties.iter() {
+            let _ = match property {
+                ItemProperty::Damage {
+                    max_durability,
+                    damage,
+                } => write!(out, " damage:{}:{}", max_durability, damage),
+                ItemProperty::Enchantment { enchantment, level } => {
+                    write!(out, " enchantment:{}:{}", enchantment, level)
+                }
+            };
+        }
+        out.push('\n');
+    }
+}
+
+/// Puts the stack of a line written by [`encode_slots`] in the chest
+fn decode_slot(chest: &mut SingleChest, fields: &[&str]) -> Option<()> {
+    let int = |i: usize| fields.get(i)?.parse::<i32>().ok();
+    let slot = fields[0].parse::<usize>().ok()?;
+    let mut stack = ItemStack::new(fields[1].parse().ok()?, int(2)?, int(3)?);
+    for property in fields[4..].iter() {
+        let mut parts = property.split(':');
+        let (name, a, b) = (parts.next()?, parts.next()?, parts.next()?);
+        let (a, b) = (a.parse().ok()?, b.parse().ok()?);
+        stack.properties.push(match name {
+            "damage" => ItemProperty::Damage {
+                max_durability: a,
+                damage: b,
+            },
+            "enchantment" => ItemProperty::Enchantment {
+                enchantment: a,
+                level: b,
+            },
+            _ => return None,
+        });
+    }
+    chest.rows.get_mut(slot / 9)?.items[slot % 9] = Some(stack);
+    Some(())
+}
+
+fn encode_chest_index(index: ChestIndex) -> String {
+    match index {
+        ChestIndex::Known(index) => index.to_string(),
+        ChestIndex::AutoDetect { max } => format!("<{}", max),
+    }
+}
+
+fn decode_chest_index(text: &str) -> Option<ChestIndex> {
+    match text.strip_prefix('<') {
+        Some(max) => Some(ChestIndex::AutoDetect {
+            max: max.parse().ok()?,
+        }),
+        None => Some(ChestIndex::Known(text.parse().ok()?)),
+    }
+}
+
+/// The chest type without spaces, e.g. `treasure_room`
+fn encode_chest_type(chest_type: BastionChestType) -> String {
+    chest_type.name().replace(' ', "_")
+}
+
+fn decode_chest_type(text: &str) -> Option<BastionChestType> {
+    BastionChestType::ALL
+        .into_iter()
+        .find(|t| encode_chest_type(*t) == text)
+}
+
+/// The evidence as the text of a recovery file
+pub fn encode(snapshot: &EvidenceSnapshot) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", HEADER);
+
+    let _ = writeln!(out, "pillars {}", flag(snapshot.pillars_enabled));
+    for (i, pillar) in snapshot.pillar_data.0.iter().enumerate() {
+        if *pillar == PartialEndPillar::default() {
+            continue;
+        }
+        let caged = match pillar.caged {
+            Some(caged) => flag(caged),
+            None => '-',
+        };
+        let _ = writeln!(
+            out,
+            "pillar {} {} {}",
+            i,
+            caged,
+            encode_height(pillar.height)
+        );
+    }
+
+    let bt = &snapshot.buried_treasure_data;
+    let _ = writeln!(
+        out,
+        "treasure {} {} {} {} {} {} {}",
+        flag(bt.usable),
+        bt.pos_x,
+        bt.pos_z,
+        bt.luck,
+        flag(bt.area),
+        bt.area_corner_x,
+        bt.area_corner_z
+    );
+    encode_slots(&mut out, "slot", &bt.contents);
+    for (item, bound) in bt.counts.iter() {
+        let _ = writeln!(out, "count {} {}", item, encode_bound(*bound));
+    }
+    // the lines after a kept treasure are its own
+    for kept in bt.kept.iter() {
+        let corner = kept.area_corner.unwrap_or((kept.pos_x, kept.pos_z));
+        let _ = writeln!(
+            out,
+            "kept {} {} {} {} {} {}",
+            kept.pos_x,
+            kept.pos_z,
+            kept.luck,
+            flag(kept.area_corner.is_some()),
+            corner.0,
+            corner.1
+        );
+        encode_slots(&mut out, "kept_slot", &kept.contents);
+        if let Some(o) = &kept.observed_at {
+            encode_observed_at(&mut out, "kept", o);
+        }
+    }
+
+    let bastion = &snapshot.bastion_data;
+    if *bastion != BastionTabSharedData::default() {
+        let _ = writeln!(
+            out,
+            "bastion {} {} {} {} {} {}",
+            flag(bastion.usable),
+            bastion.pos_x,
+            bastion.pos_z,
+            encode_chest_index(bastion.index),
+            encode_chest_type(bastion.chest_type),
+            bastion.luck
+        );
+        encode_slots(&mut out, "bastion_slot", &bastion.contents);
+    }
+
+    let biomes = &snapshot.biome_data;
+    for (name, rows) in [
+        ("overworld", biomes.overworld_biomes.as_overworld()),
+        ("nether", biomes.nether_biomes.as_nether()),
+    ] {
+        for (x, y, z, biome) in rows.unwrap_or_default() {
+            let _ = writeln!(out, "{} {} {} {} {}", name, x, y, z, *biome as i32);
+        }
+    }
+    for (name, rows) in [
+        ("overworld_enabled", &biomes.overworld_enabled),
+        ("nether_enabled", &biomes.nether_enabled),
+    ] {
+        if !rows.is_empty() {
+            let _ = writeln!(out, "{} {}", name, encode_flags(rows.flags()));
+        }
+    }
+
+    for d in snapshot.decoration_data.decorations.iter() {
+        let _ = writeln!(
+            out,
+            "decoration {} {} {} {} {} {} {}",
+            d.chunk_x,
+            d.chunk_z,
+            d.surface_y,
+            d.kind.map_or("-", |k| k.name()),
+            d.observed_count,
+            d.tolerance,
+            flag(d.enabled)
+        );
+    }
+
+    for s in snapshot.structure_data.structures.iter() {
+        let _ = writeln!(
+            out,
+            "structure {} {} {} {}",
+            s.structure.map_or("-", |s| s.name()),
+            s.block_x,
+            s.block_z,
+            flag(s.enabled)
+        );
+    }
+
+    for (name, observed_at) in [
+        ("pillars", &snapshot.pillars_observed_at),
+        ("treasure", &bt.observed_at),
+        ("biomes", &biomes.observed_at),
+        ("decorations", &snapshot.decoration_data.observed_at),
+    ] {
+        if let Some(o) = observed_at {
+            encode_observed_at(&mut out, name, o);
+        }
+    }
+    out
+}
+
+fn encode_observed_at(out: &mut String, name: &str, o: &ObservedAt) {
+    // the text is the rest of the line
+    let when = o.when.replace(['\n', '\r'], " ");
+    let _ = writeln!(
+        out,
+        "observed {} {} {}",
+        name,
+        flag(o.might_be_modified),
+        when
+    );
+}
+
+/// The evidence of a recovery file, None if the file is damaged or of another version
+pub fn decode(text: &str) -> Option<EvidenceSnapshot> {
+    let mut lines = text.lines();
+    if lines.next()? != HEADER {
+        return None;
+    }
+    let mut snapshot = EvidenceSnapshot {
+        pillar_data: PartialEndPillars::new(),
+        pillars_enabled: true,
+        pillars_observed_at: None,
+        buried_treasure_data: BuriedTreasureTabSharedData::default(),
+        biome_data: BiomesTabSharedData::default(),
+        decoration_data: DecorationsTabSharedData::default(),
+        bastion_data: BastionTabSharedData::default(),
+        structure_data: StructuresTabSharedData::default(),
+    };
+    for line in lines.filter(|l| !l.is_empty()) {
+        decode_line(&mut snapshot, line)?;
+    }
+    Some(snapshot)
+}
+
+fn decode_line(snapshot: &mut EvidenceSnapshot, line: &str) -> Option<()> {
+    let (kind, rest) = line.split_once(' ')?;
+    if kind == "observed" {
+        let mut parts = rest.splitn(3, ' ');
+        let section = parts.next()?;
+        let might_be_modified = decode_flag(parts.next()?)?;
+        let observed_at = Some(ObservedAt::new(
+            parts.next().unwrap_or(""),
+            might_be_modified,
+        ));
+        match section {
+            "pillars" => snapshot.pillars_observed_at = observed_at,
+            "treasure" => snapshot.buried_treasure_data.observed_at = observed_at,
+            "kept" => snapshot.buried_treasure_data.kept.last_mut()?.observed_at = observed_at,
+            "biomes" => snapshot.biome_data.observed_at = observed_at,
+            "decorations" => snapshot.decoration_data.observed_at = observed_at,
+            _ => return None,
+        }
+        return Some(());
+    }
+
+    let fields = rest.split(' ').collect::<Vec<_>>();
+    let int = |i: usize| fields.get(i)?.parse::<i32>().ok();
+    match (kind, fields.len()) {
+        ("pillars", 1) => snapshot.pillars_enabled = decode_flag(fields[0])?,
+        ("pillar", 3) => {
+            let pillar = snapshot
+                .pillar_data
+                .0
+                .get_mut(fields[0].parse::<usize>().ok()?)?;
+            pillar.caged = match fields[1] {
+                "-" => None,
+                caged => Some(decode_flag(caged)?),
+            };
+            pillar.height = decode_height(fields[2])?;
+        }
+        ("treasure", 7) => {
+            let bt = &mut snapshot.buried_treasure_data;
+            bt.usable = decode_flag(fields[0])?;
+            bt.pos_x = int(1)?;
+            bt.pos_z = int(2)?;
+            bt.luck = fields[3].parse().ok()?;
+            bt.area = decode_flag(fields[4])?;
+            bt.area_corner_x = int(5)?;
+            bt.area_corner_z = int(6)?;
+        }
+        ("slot", 4..) => decode_slot(&mut snapshot.buried_treasure_data.contents, &fields)?,
+        ("kept", 6) => {
+            let corner = (int(4)?, int(5)?);
+            snapshot
+                .buried_treasure_data
+                .kept
+                .push(BuriedTreasureEvidence {
+                    pos_x: int(0)?,
+                    pos_z: int(1)?,
+                    luck: fields[2].parse().ok()?,
+                    contents: SingleChest::new(),
+                    area_corner: decode_flag(fields[3])?.then_some(corner),
+                    observed_at: None,
+                });
+        }
+        ("kept_slot", 4..) => {
+            let kept = snapshot.buried_treasure_data.kept.last_mut()?;
+            decode_slot(&mut kept.contents, &fields)?;
+        }
+        ("bastion", 6) => {
+            let bastion = &mut snapshot.bastion_data;
+            bastion.usable = decode_flag(fields[0])?;
+            bastion.pos_x = int(1)?;
+            bastion.pos_z = int(2)?;
+            bastion.index = decode_chest_index(fields[3])?;
+            bastion.chest_type = decode_chest_type(fields[4])?;
+            bastion.luck = fields[5].parse().ok()?;
+        }
+        ("bastion_slot", 4..) => decode_slot(&mut snapshot.bastion_data.contents, &fields)?,
+        ("count", 2) => {
+            let item = fields[0].parse().ok()?;
+            let bound = decode_bound(fields[1])?;
+            snapshot.buried_treasure_data.counts.push((item, bound));
+        }
+        ("overworld" | "nether", 4) => {
+            let id = int(3)?;
+            let biome = list_biomes()
+                .iter()
+                .copied()
+                .find(|b| *b as i32 == id)
+                .unwrap_or(BiomeID::none);
+            let biomes = &mut snapshot.biome_data;
+            let rows = if kind == "overworld" {
+                biomes.overworld_biomes.as_overworld_mut()?
+            } else {
+                biomes.nether_biomes.as_nether_mut()?
+            };
+            rows.push((int(0)?, int(1)?, int(2)?, biome));
+        }
+        ("overworld_enabled", 1) => {
+            snapshot.biome_data.overworld_enabled =
+                EnabledRows::from_flags(decode_flags(fields[0])?);
+        }
+        ("nether_enabled", 1) => {
+            snapshot.biome_data.nether_enabled = EnabledRows::from_flags(decode_flags(fields[0])?);
+        }
+        ("decoration", 7) => {
+            snapshot.decoration_data.decorations.push(DecorationEntry {
+                chunk_x: int(0)?,
+                chunk_z: int(1)?,
+                surface_y: int(2)?,
+                kind: match fields[3] {
+                    "-" => None,
+                    name => Some(DecorationKind::from_name(name)?),
+                },
+                observed_count: int(4)?,
+                tolerance: int(5)?,
+                enabled: decode_flag(fields[6])?,
+            });
+        }
+        ("structure", 4) => {
+            snapshot.structure_data.structures.push(StructureEntry {
+                structure: match fields[0] {
+                    "-" => None,
+                    name => Some(KnownStructure::from_name(name)?),
+                },
+                block_x: int(1)?,
+                block_z: int(2)?,
+                enabled: decode_flag(fields[3])?,
+            });
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    use mcseedcracker::{
+        cubiomes::enums::BiomeID,
+        features::{
+            bastion::{BastionChestType, items::hoglin_stables::GOLD_BLOCK},
+            buried_treasure::items::{DIAMOND, HEART_OF_THE_SEA, IRON_INGOT},
+            decoration::DecorationKind,
+            decorator_chest::ChestIndex,
+            end_pillars::{PartialEndPillar, PillarHeightHint},
+        },
+        loot_table::{CountBound, Inventory, ItemProperty, ItemStack},
+        pipeline::ObservedAt,
+        search::KnownStructure,
+    };
+    use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::tui::{
+        EventContext,
+        application::{ApplicationComponent, ApplicationComponentState},
+        history::EvidenceSnapshot,
+        recovery::{AutoSave, Debounce, decode, encode, write_atomic},
+        tabs::{decorations::DecorationEntry, structures::StructureEntry},
+    };
+
+    /// A fresh directory for the files of one test
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mcseedcracker-recovery-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn sample_evidence() -> EvidenceSnapshot {
+        let mut app = ApplicationComponentState::new();
+        let shared = &mut app.shared;
+        shared.pillar_data.0[0] = PartialEndPillar::new(Some(true), PillarHeightHint::Exact(79));
+        shared.pillar_data.0[3] = PartialEndPillar::new(None, PillarHeightHint::Range(88, 94));
+        shared.pillar_data.0[9] = PartialEndPillar::new(Some(false), PillarHeightHint::MediumBig);
+        shared.pillars_enabled = false;
+        shared.pillars_observed_at = Some(ObservedAt::new("day 3, after the raid", true));
+
+        let bt = &mut shared.buried_treasure_data;
+        bt.usable = true;
+        bt.pos_x = -1263;
+        bt.pos_z = 391;
+        bt.luck = 1.5;
+        bt.area = true;
+        bt.area_corner_x = -1250;
+        bt.area_corner_z = 400;
+        bt.contents
+            .set_item(4, Some(ItemStack::of(HEART_OF_THE_SEA, 1)));
+        bt.contents.set_item(26, Some(ItemStack::of(IRON_INGOT, 3)));
+        bt.contents.set_item(
+            13,
+            Some(ItemStack::with_properties(
+                DIAMOND,
+                1,
+                64,
+                &[
+                    ItemProperty::Damage {
+                        max_durability: 250,
+                        damage: 12,
+                    },
+                    ItemProperty::Enchantment {
+                        enchantment: 3,
+                        level: 2,
+                    },
+                ],
+            )),
+        );
+        bt.counts = vec![
+            (IRON_INGOT, CountBound::AtLeast(8)),
+            (DIAMOND, CountBound::Between(1, 2)),
+        ];
+        bt.observed_at = Some(ObservedAt::new("", false));
+        let mut kept = bt.edited_treasure();
+        (kept.pos_x, kept.area_corner) = (16, None);
+        kept.contents
+            .set_item(0, Some(ItemStack::of(IRON_INGOT, 1)));
+        bt.kept = vec![kept.clone(), kept];
+        bt.kept[0].observed_at = Some(ObservedAt::new("in the second ocean", true));
+
+        let biomes = &mut shared.biome_data;
+        let overworld = biomes.overworld_biomes.as_overworld_mut().unwrap();
+        overworld.push((100, 64, -20, BiomeID::desert));
+        overworld.push((0, 0, 0, BiomeID::none));
+        let nether = biomes.nether_biomes.as_nether_mut().unwrap();
+        nether.push((-5, 40, 5, BiomeID::nether_wastes));
+        biomes.overworld_enabled.toggle(1, 2);
+
+        shared.decoration_data.decorations = vec![
+            DecorationEntry {
+                chunk_x: 3,
+                chunk_z: -7,
+                surface_y: 63,
+                kind: Some(DecorationKind::SugarCane),
+                observed_count: 11,
+                tolerance: 2,
+                enabled: true,
+            },
+            DecorationEntry::default(),
+        ];
+        shared.structure_data.structures = vec![
+            StructureEntry {
+                structure: Some(KnownStructure::EndCity),
+                block_x: 1000,
+                block_z: -2000,
+                enabled: false,
+            },
+            StructureEntry::default(),
+        ];
+
+        let bastion = &mut shared.bastion_data;
+        bastion.usable = true;
+        (bastion.pos_x, bastion.pos_z) = (-674, -820);
+        bastion.index = ChestIndex::Known(1);
+        bastion.chest_type = BastionChestType::HoglinStables;
+        bastion.luck = 0.5;
+        bastion
+            .contents
+            .set_item(7, Some(ItemStack::of(GOLD_BLOCK, 2)));
+        EvidenceSnapshot::of(shared)
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let evidence = sample_evidence();
+        let text = encode(&evidence);
+        assert!(text.starts_with("mcseedcracker recovery 1\npillars 0\npillar 0 1 =79\n"));
+        assert!(text.contains("\nslot 4 "));
+        assert!(text.contains("\nkept 16 391 1.5 0 16 391\nkept_slot 0 "));
+        assert!(text.contains("\nobserved kept 1 in the second ocean\nkept 16 "));
+        assert!(text.contains("\nbastion 1 -674 -820 1 hoglin_stables 0.5\nbastion_slot 7 "));
+        assert!(text.contains("\nobserved pillars 1 day 3, after the raid\n"));
+        assert!(text.contains("\nstructure end_city 1000 -2000 0\nstructure - 0 0 1\n"));
+        assert!(decode(&text).is_some_and(|decoded| decoded == evidence));
+
+        let empty = EvidenceSnapshot::of(&ApplicationComponentState::new().shared);
+        assert!(decode(&encode(&empty)).is_some_and(|decoded| decoded == empty));
+
+        // damaged files and files of another version are not restored
+        assert!(decode("").is_none());
+        assert!(decode(&text.replace("recovery 1", "recovery 2")).is_none());
+        assert!(decode(&text.replace("=79", "=seventy")).is_none());
+        assert!(decode(&format!("{}slot 27 1 1 64\n", text)).is_none());
+        assert!(decode(&text[..text.len() / 2]).is_none_or(|decoded| decoded != evidence));
+    }
+
+    #[test]
+    fn test_debounce() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut debounce = Debounce::new(Duration::from_secs(1), Duration::from_secs(5));
+        assert!(!debounce.take_due(at(10_000)));
+
+        // the delay starts over with every change
+        debounce.mark_dirty(at(0));
+        assert!(!debounce.take_due(at(500)));
+        debounce.mark_dirty(at(900));
+        assert!(!debounce.take_due(at(1500)));
+        assert!(debounce.take_due(at(1900)));
+        assert!(!debounce.take_due(at(2000)));
+
+        // changes every half second are still saved after the longest delay
+        let mut saves = Vec::new();
+        for ms in (0..8000).step_by(500) {
+            debounce.mark_dirty(at(ms));
+            if debounce.take_due(at(ms)) {
+                saves.push(ms);
+            }
+        }
+        assert_eq!(saves, vec![5000]);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = test_dir("atomic");
+        let path = dir.join("nested").join("recovery.txt");
+        write_atomic(&path, "first").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        // only the renamed file is left
+        let files = fs::read_dir(dir.join("nested")).unwrap().count();
+        assert_eq!(files, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_background_save() {
+        let dir = test_dir("background");
+        let path = dir.join("recovery.txt");
+        let evidence = sample_evidence();
+
+        let mut autosave = AutoSave::start(path.clone());
+        autosave.debounce = Debounce::new(Duration::ZERO, Duration::ZERO);
+        assert!(autosave.leftover().is_none());
+        autosave.update(false, Instant::now(), || unreachable!());
+        autosave.update(true, Instant::now(), || evidence.clone());
+
+        // waits for the writer without removing the file, like a crash after the save
+        autosave.sender = None;
+        autosave.writer.take().unwrap().join().unwrap();
+        assert!(
+            autosave
+                .leftover()
+                .is_some_and(|leftover| leftover == evidence)
+        );
+
+        // a clean exit removes it
+        let autosave = AutoSave::start(path.clone());
+        autosave.finish().unwrap();
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_prompt() {
+        let dir = test_dir("prompt");
+        let path = dir.join("recovery.txt");
+        let evidence = sample_evidence();
+        // left over by a run that crashed
+        write_atomic(&path, &encode(&evidence)).unwrap();
+
+        let autosave = AutoSave::start(path.clone());
+        let mut app = ApplicationComponentState::new();
+        app.recovery = autosave.leftover();
+        assert!(app.recovery.is_some());
+
+        // the prompt takes every key until it is answered
+        ApplicationComponent::handle_event(
+            &mut app,
+            key(KeyCode::Down),
+            EventContext::BubblingDown,
+        );
+        assert!(app.recovery.is_some() && app.shared.is_evidence_empty());
+        ApplicationComponent::handle_event(
+            &mut app,
+            key(KeyCode::Enter),
+            EventContext::BubblingDown,
+        );
+        assert!(app.recovery.is_none());
+        assert!(EvidenceSnapshot::of(&app.shared) == evidence);
+        assert!(!app.is_template_picker_open());
+        assert!(app.shared.history.take_changed());
+
+        // the restore is an edit of its own
+        assert!(app.undo());
+        assert!(app.shared.is_evidence_empty());
+
+        // discarding keeps the fresh evidence
+        let mut app = ApplicationComponentState::new();
+        app.recovery = autosave.leftover();
+        ApplicationComponent::handle_event(&mut app, key(KeyCode::Esc), EventContext::BubblingDown);
+        assert!(app.recovery.is_none());
+        assert!(app.shared.is_evidence_empty());
+        assert!(!app.restore_recovery());
+
+        autosave.finish().unwrap();
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,848 @@
+use mcseedcracker::{
+    features::{
+        bastion::{self, BastionChestType},
+        decorator_chest::ChestIndex,
+    },
+    loot_table::{ItemRegistry, ItemStack, SingleChest},
+    math::Math,
+    pipeline::CrackingEvidence,
+    search::StructureData,
+    templates::{EvidenceSection, EvidenceTemplate, FieldId},
+    tooltip::{LanguageTable, parse_tooltip_dump},
+};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Direction, Layout, Offset, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{
+    make_full_component,
+    tui::{
+        Component, EventContext, EventResult,
+        application::ApplicationTab,
+        components::{
+            chest::{ChestState, ChestWidget},
+            text_input::{
+                TextInputState, TextInputStyle, TextInputWidget, Validator, block_coord_validator,
+            },
+        },
+        dim_area, limit_area_height, limit_area_width,
+        tabs::buried_treasure::{ChestImportState, render_text_popup},
+    },
+};
+
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Focus {
+    #[default]
+    Outside,
+    CoordX,
+    CoordZ,
+    ChestIndex,
+    Luck,
+    Chest,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Outside => Focus::CoordX,
+            Focus::CoordX => Focus::CoordZ,
+            Focus::CoordZ => Focus::ChestIndex,
+            Focus::ChestIndex => Focus::Luck,
+            Focus::Luck => Focus::Chest,
+            Focus::Chest => Focus::Outside,
+        }
+    }
+}
+
+pub struct BastionTabState {
+    pub contents: ChestState,
+    pub focus: Focus,
+    pub xstate: TextInputState<i32>,
+    pub zstate: TextInputState<i32>,
+    pub index_state: TextInputState<ChestIndex>,
+    pub luck_state: TextInputState<f32>,
+    /// Chest import popup, open while `Some`
+    pub import: Option<ChestImportState>,
+}
+
+impl Default for BastionTabState {
+    fn default() -> Self {
+        Self {
+            contents: ChestState {
+                title: "Bastion chest".to_string(),
+                ..Default::default()
+            },
+            focus: Focus::default(),
+            xstate: TextInputState::new("Chest X (i32)", block_coord_validator()),
+            zstate: TextInputState::new("Chest Z (i32)", block_coord_validator()),
+            index_state: TextInputState::new(
+                "Chest in chunk (empty: any)",
+                chest_index_validator(),
+            ),
+            luck_state: TextInputState::new("Luck (f32)", luck_validator()),
+            import: None,
+        }
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct BastionTabSharedData {
+    pub contents: SingleChest,
+    pub pos_x: i32,
+    pub pos_z: i32,
+    pub index: ChestIndex,
+    pub chest_type: BastionChestType,
+    pub luck: f32,
+    pub usable: bool,
+}
+
+impl BastionTabSharedData {
+    /// The chest as structure evidence, `None` while it is disabled, empty or can't be searched
+    pub fn structure_data(&self) -> Option<StructureData> {
+        if !self.usable || self.contents == SingleChest::new() {
+            return None;
+        }
+        let chunk = Math::checked_block_coords_to_chunk_coords((self.pos_x, self.pos_z)).ok()?;
+        let contents = bastion::build_fast_inventory_compare_context(
+            self.contents.clone(),
+            &bastion::get_loot_table(self.chest_type),
+        )
+        .ok()?;
+        Some(StructureData::bastion_chest(
+            chunk,
+            self.index,
+            self.chest_type,
+            self.luck,
+            contents,
+        ))
+    }
+
+    /// Why the enabled chest can't be searched
+    pub fn error(&self) -> Option<String> {
+        if !self.usable || self.contents == SingleChest::new() {
+            return None;
+        }
+        if let Err(invalid) = Math::checked_block_coords_to_chunk_coords((self.pos_x, self.pos_z)) {
+            return Some(format!("bastion chest {}", invalid));
+        }
+        bastion::build_fast_inventory_compare_context(
+            self.contents.clone(),
+            &bastion::get_loot_table(self.chest_type),
+        )
+        .err()
+        .map(|invalid| format!("invalid bastion chest: {}", invalid))
+    }
+
+    /// Switches to the next chest type. The items are kept by name, the ones the new loot table doesn't have are
+    /// removed.
+    pub fn cycle_chest_type(&mut self) {
+        let types = BastionChestType::ALL;
+        let current = types
+            .iter()
+            .position(|t| *t == self.chest_type)
+            .unwrap_or(0);
+        let (from, to) = (
+            bastion::item_registry(self.chest_type),
+            bastion::item_registry(types[(current + 1) % types.len()]),
+        );
+        self.chest_type = types[(current + 1) % types.len()];
+
+        for row in self.contents.rows.iter_mut() {
+            for slot in row.items.iter_mut() {
+                let item = slot
+                    .as_ref()
+                    .and_then(|stack| from.name_of(stack.item))
+                    .and_then(|name| to.get(name));
+                match (slot.as_mut(), item) {
+                    (Some(stack), Some(item)) => {
+                        stack.item = item.id;
+                        stack.max_count = item.max_count;
+                    }
+                    _ => *slot = None,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BastionTabComponent;
+
+make_full_component!(BastionTab, state: BastionTabState, component: BastionTabComponent);
+
+/// The bastion chest, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    match shared.bastion_data.structure_data() {
+        Some(data) => {
+            evidence.structures.push(data);
+            true
+        }
+        None => false,
+    }
+}
+
+impl BastionTab {
+    pub fn apptab() -> ApplicationTab<Self> {
+        ApplicationTab {
+            title: "Bastion".to_string(),
+            component: BastionTab::create(),
+        }
+    }
+}
+
+/// An empty text tries the first chests of the chunk, see [`ChestIndex::default`]
+fn chest_index_validator() -> Validator<ChestIndex> {
+    Some(Box::new(
+        |value: &mut Vec<char>,
+         _: &mut usize,
+         style: &mut TextInputStyle,
+         index: &mut ChestIndex| {
+            let text = value.iter().collect::<String>();
+            let color = if text.is_empty() {
+                *index = ChestIndex::default();
+                Color::White
+            } else {
+                match text.parse::<i32>() {
+                    Ok(n) if n >= 0 => {
+                        *index = ChestIndex::Known(n);
+                        Color::Green
+                    }
+                    _ => Color::Red,
+                }
+            };
+            style.text_style.fg = Some(color);
+            style.cursor_style.bg = Some(color);
+        },
+    ))
+}
+
+fn luck_validator() -> Validator<f32> {
+    Some(Box::new(
+        |value: &mut Vec<char>, _: &mut usize, style: &mut TextInputStyle, luck: &mut f32| {
+            let text = value.iter().collect::<String>();
+            let color = if text.is_empty() {
+                *luck = 0.0;
+                Color::White
+            } else {
+                match text.parse::<f32>() {
+                    Ok(n) if n.is_finite() => {
+                        *luck = n;
+                        Color::Green
+                    }
+                    _ => Color::Red,
+                }
+            };
+            style.text_style.fg = Some(color);
+            style.cursor_style.bg = Some(color);
+        },
+    ))
+}
+
+/// The items of the loot table, in the order [+] and [-] cycle through them
+fn registry_items(registry: &ItemRegistry) -> Vec<usize> {
+    let mut items = Vec::new();
+    for (_, item) in registry.iter() {
+        if !items.contains(&item.id) {
+            items.push(item.id);
+        }
+    }
+    items
+}
+
+fn item_to_string(item: usize, registry: &ItemRegistry, language: &LanguageTable) -> String {
+    match registry.name_of(item) {
+        Some(name) => language.item_display_name(name).unwrap_or(name).to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// The chest cell of a stack, highlighted if its count doesn't fit in one stack
+fn stack_style(item: usize, count: i32, registry: &ItemRegistry) -> Style {
+    match ItemStack::validated(item, count, registry) {
+        Ok(_) => Style::default().fg(Color::LightYellow).not_bold(),
+        Err(_) => Style::default().fg(Color::White).bg(Color::Red).not_bold(),
+    }
+}
+
+/// Shows the chest in the chest widget
+fn show_contents(contents: &mut ChestState, chest: &SingleChest, chest_type: BastionChestType) {
+    let (registry, language) = (bastion::item_registry(chest_type), LanguageTable::en_us());
+    for (y, row) in chest.rows.iter().enumerate() {
+        for (x, stack) in row.items.iter().enumerate() {
+            contents.contents[y][x] = match stack {
+                Some(stack) => (
+                    item_to_string(stack.item, &registry, &language),
+                    stack.count,
+                    stack_style(stack.item, stack.count, &registry),
+                ),
+                None => ("".to_string(), 0, Style::default()),
+            };
+        }
+    }
+}
+
+fn render_input<T>(area: Rect, buf: &mut Buffer, input: &mut TextInputState<T>, focused: bool) {
+    input.style.border_style = Style::default().fg(if focused {
+        Color::LightCyan
+    } else {
+        Color::White
+    });
+    input.style.show_cursor = focused;
+    TextInputWidget::default().render(area, buf, input);
+}
+
+fn render_control(area: Rect, buf: &mut Buffer, y: i32, key: &str, description: &str) {
+    Paragraph::new(key)
+        .style(Style::default().fg(Color::Magenta).not_bold())
+        .render(
+            limit_area_width(area, key.len() as u16).offset(Offset { x: 0, y }),
+            buf,
+        );
+    Paragraph::new(description)
+        .style(Style::default().fg(Color::Green).not_bold())
+        .render(
+            limit_area_width(area, description.len() as u16).offset(Offset {
+                x: key.len() as i32,
+                y,
+            }),
+            buf,
+        );
+}
+
+impl BastionTabComponent {
+    fn render_import_popup(&self, area: Rect, buf: &mut Buffer, import: &ChestImportState) {
+        let help = [
+            "One slot per line, rows 1 to 3 and columns 1 to 9:",
+            "  slot 1,1: Gold Ingot x9",
+            "  slot 2,5: Diamond Pickaxe durability 1200/1561, Efficiency IV",
+            "  slot 3,9: minecraft:ancient_debris x2",
+        ];
+        render_text_popup(
+            area,
+            buf,
+            import,
+            &help,
+            "Import bastion chest from tooltips",
+            "[CTRL + S] Import    [ESC] Cancel",
+        );
+    }
+
+    fn handle_import_event(
+        &self,
+        state: &mut BastionTabState,
+        shared: &mut SharedApplicationState,
+        event: Event,
+    ) -> EventResult {
+        let Some(import) = &mut state.import else {
+            return EventResult::BubbleUp(event);
+        };
+
+        if import.edit(&event) {
+            return EventResult::Captured;
+        }
+        match &event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Esc => state.import = None,
+                KeyCode::Char('s' | 'S') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    match parse_tooltip_dump(
+                        &import.text,
+                        &bastion::item_registry(shared.bastion_data.chest_type),
+                        &LanguageTable::en_us(),
+                    ) {
+                        Ok(chest) => {
+                            state.import = None;
+                            show_contents(
+                                &mut state.contents,
+                                &chest,
+                                shared.bastion_data.chest_type,
+                            );
+                            shared.bastion_data.contents = chest;
+                            shared.bastion_data.usable = true;
+                        }
+                        Err(errors) => {
+                            import.errors = errors.iter().map(|e| e.to_string()).collect();
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        EventResult::Captured
+    }
+
+    /// Replaces the item of the selected slot with the next or previous item of the loot table
+    fn cycle_selected_item(
+        &self,
+        state: &mut BastionTabState,
+        shared: &mut SharedApplicationState,
+        forward: bool,
+    ) {
+        let registry = bastion::item_registry(shared.bastion_data.chest_type);
+        let items = registry_items(&registry);
+        let (x, y) = state.contents.selected;
+        let slot = &mut shared.bastion_data.contents.rows[y].items[x];
+        let current = slot
+            .as_ref()
+            .and_then(|stack| items.iter().position(|item| *item == stack.item));
+        let next = match (current, forward) {
+            (None, true) => 0,
+            (None, false) => items.len() - 1,
+            (Some(i), true) => (i + 1) % items.len(),
+            (Some(i), false) => (i + items.len() - 1) % items.len(),
+        };
+        let item = items[next];
+        let count = slot.as_ref().map_or(1, |stack| stack.count.max(1));
+        let max_count = registry.by_id(item).map_or(64, |i| i.max_count);
+        *slot = Some(ItemStack::new(item, count, max_count));
+
+        state.contents.contents[y][x] = (
+            item_to_string(item, &registry, &LanguageTable::en_us()),
+            count,
+            stack_style(item, count, &registry),
+        );
+    }
+}
+
+impl Component for BastionTabComponent {
+    type State = BastionTabState;
+    type Shared = SharedApplicationState;
+
+    fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(13), Constraint::Min(0)])
+            .split(area);
+        let controls_area = limit_area_height(chunks[0], 1);
+        let contents_area = chunks[1];
+        let data = &shared.bastion_data;
+
+        Paragraph::new("Bastion chest available: ")
+            .style(Style::new().fg(Color::White))
+            .render(controls_area, buf);
+        let (text, color) = if data.usable {
+            ("yes", Color::Green)
+        } else {
+            ("no", Color::Red)
+        };
+        Paragraph::new(text)
+            .style(if state.focus == Focus::Chest {
+                Style::new().fg(Color::White).bold().bg(color)
+            } else {
+                Style::new().fg(color)
+            })
+            .render(
+                limit_area_width(controls_area, text.len() as u16)
+                    .offset(Offset { x: 25, y: 0 })
+                    .intersection(chunks[0]),
+                buf,
+            );
+
+        if let Some(error) = data.error() {
+            Paragraph::new(error)
+                .style(Style::new().fg(Color::Red))
+                .render(controls_area.offset(Offset { x: 0, y: 1 }), buf);
+        }
+        Paragraph::new(format!("Chest type: {}", data.chest_type.name()))
+            .style(Style::new().fg(Color::LightCyan))
+            .render(controls_area.offset(Offset { x: 0, y: 2 }), buf);
+
+        Paragraph::new("General controls")
+            .style(Style::default().fg(Color::Yellow).bold())
+            .render(controls_area.offset(Offset { x: 0, y: 3 }), buf);
+        render_control(
+            controls_area,
+            buf,
+            4,
+            "[LEFT] [RIGHT] [UP] [DOWN]",
+            " Move selection",
+        );
+        render_control(
+            controls_area,
+            buf,
+            5,
+            "[SPACE]",
+            " Toggle bastion chest availability",
+        );
+        render_control(controls_area, buf, 6, "[SHIFT + DEL]", " Delete all");
+        render_control(controls_area, buf, 7, "[CTRL + O]", " Import from tooltips");
+        render_control(controls_area, buf, 8, "[CTRL + N]", " Next chest type");
+        Paragraph::new("Edit selection")
+            .style(Style::default().fg(Color::Yellow).bold())
+            .render(controls_area.offset(Offset { x: 0, y: 9 }), buf);
+        render_control(
+            controls_area,
+            buf,
+            10,
+            "[0] [1] [2] [3] [4] [5] [6] [7] [8] [9]",
+            " Set quantity",
+        );
+        render_control(controls_area, buf, 11, "[+] [-]", " Change item");
+        render_control(controls_area, buf, 12, "[DEL] [BACKSPACE]", " Remove item");
+
+        for (y, focus) in [
+            (0, Focus::CoordX),
+            (3, Focus::CoordZ),
+            (6, Focus::ChestIndex),
+            (9, Focus::Luck),
+        ] {
+            let input_area = limit_area_width(
+                limit_area_height(area, 3),
+                area.width.saturating_sub(62).min(30).max(10),
+            )
+            .offset(Offset { x: 60, y });
+            let focused = state.focus == focus;
+            match focus {
+                Focus::CoordX => render_input(input_area, buf, &mut state.xstate, focused),
+                Focus::CoordZ => render_input(input_area, buf, &mut state.zstate, focused),
+                Focus::ChestIndex => render_input(input_area, buf, &mut state.index_state, focused),
+                _ => render_input(input_area, buf, &mut state.luck_state, focused),
+            }
+        }
+
+        if data.usable {
+            state.contents.show_selected = state.focus == Focus::Chest;
+            ChestWidget.render(contents_area, buf, &mut state.contents);
+        } else if data.contents != SingleChest::new() {
+            // disabled, the contents are kept for when it gets enabled again
+            state.contents.show_selected = false;
+            ChestWidget.render(contents_area, buf, &mut state.contents);
+            dim_area(contents_area, buf);
+        }
+
+        if let Some(import) = &state.import {
+            self.render_import_popup(area, buf, import);
+        }
+    }
+
+    fn handle_event(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        event: Event,
+        context: EventContext,
+    ) -> EventResult {
+        match context {
+            EventContext::BubblingDown => {
+                if state.import.is_some() {
+                    return self.handle_import_event(state, shared, event);
+                }
+                let result = match state.focus {
+                    Focus::CoordX => TextInputWidget::handle_event(
+                        &mut state.xstate,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.bastion_data.pos_x,
+                    ),
+                    Focus::CoordZ => TextInputWidget::handle_event(
+                        &mut state.zstate,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.bastion_data.pos_z,
+                    ),
+                    Focus::ChestIndex => TextInputWidget::handle_event(
+                        &mut state.index_state,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.bastion_data.index,
+                    ),
+                    Focus::Luck => TextInputWidget::handle_event(
+                        &mut state.luck_state,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.bastion_data.luck,
+                    ),
+                    Focus::Outside => {
+                        return match &event {
+                            Event::Key(key)
+                                if key.code == KeyCode::Tab
+                                    && key.kind != KeyEventKind::Release =>
+                            {
+                                state.focus = Focus::CoordX;
+                                EventResult::Captured
+                            }
+                            _ => EventResult::BubbleUp(event),
+                        };
+                    }
+                    Focus::Chest => EventResult::BubbleUp(event),
+                };
+                let event = match result {
+                    EventResult::BubbleUp(event) if state.focus != Focus::Chest => {
+                        return self.handle_event(state, shared, event, EventContext::BubblingUp);
+                    }
+                    EventResult::BubbleUp(event) => event,
+                    result => return result,
+                };
+
+                let Event::Key(key) = &event else {
+                    return EventResult::BubbleUp(event);
+                };
+                if key.kind == KeyEventKind::Release {
+                    return EventResult::BubbleUp(event);
+                }
+                let (x, y) = state.contents.selected;
+                match key.code {
+                    KeyCode::Char('o' | 'O') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        state.import = Some(ChestImportState::default());
+                    }
+                    KeyCode::Char('n' | 'N') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        shared.bastion_data.cycle_chest_type();
+                        let data = &shared.bastion_data;
+                        show_contents(&mut state.contents, &data.contents, data.chest_type);
+                    }
+                    KeyCode::Char(' ') => {
+                        shared.bastion_data.usable = !shared.bastion_data.usable;
+                    }
+                    KeyCode::Char('+' | '-') => {
+                        self.cycle_selected_item(state, shared, key.code == KeyCode::Char('+'));
+                    }
+                    KeyCode::Right => {
+                        state.contents.selected.0 = (x + 1) % state.contents.width;
+                    }
+                    KeyCode::Left => {
+                        state.contents.selected.0 =
+                            (x + state.contents.width - 1) % state.contents.width;
+                    }
+                    KeyCode::Down => {
+                        state.contents.selected.1 = (y + 1) % state.contents.height;
+                    }
+                    KeyCode::Up => {
+                        state.contents.selected.1 =
+                            (y + state.contents.height - 1) % state.contents.height;
+                    }
+                    KeyCode::Char(c) if shared.bastion_data.usable && c.is_ascii_digit() => {
+                        let cell = &mut state.contents.contents[y][x];
+                        let res = (cell.1 * 10 + c.to_digit(10).unwrap() as i32) % 100;
+                        cell.1 = res;
+
+                        if let Some(stack) = &mut shared.bastion_data.contents.rows[y].items[x] {
+                            stack.count = res;
+                            cell.2 = stack_style(
+                                stack.item,
+                                res,
+                                &bastion::item_registry(shared.bastion_data.chest_type),
+                            );
+                        }
+                    }
+                    KeyCode::Tab => {
+                        state.focus = state.focus.next();
+                        return EventResult::BubbleUp(event);
+                    }
+                    KeyCode::Backspace | KeyCode::Delete if shared.bastion_data.usable => {
+                        if key.modifiers.contains(KeyModifiers::SHIFT) {
+                            shared.bastion_data.contents = SingleChest::new();
+                        } else {
+                            shared.bastion_data.contents.rows[y].items[x] = None;
+                        }
+                        let data = &shared.bastion_data;
+                        show_contents(&mut state.contents, &data.contents, data.chest_type);
+                    }
+                    _ => return EventResult::BubbleUp(event),
+                }
+                EventResult::Captured
+            }
+
+            EventContext::BubblingUp => match &event {
+                Event::Key(key)
+                    if key.code == KeyCode::Tab && key.kind != KeyEventKind::Release =>
+                {
+                    state.focus = state.focus.next();
+                    if state.focus == Focus::Outside {
+                        EventResult::BubbleUp(event)
+                    } else {
+                        EventResult::Captured
+                    }
+                }
+                _ => EventResult::BubbleUp(event),
+            },
+        }
+    }
+
+    fn on_focus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focus = Focus::CoordX;
+    }
+
+    fn on_unfocus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focus = Focus::Outside;
+    }
+
+    fn apply_template(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        template: &EvidenceTemplate,
+    ) {
+        for field in template.fields_in(EvidenceSection::BastionChest) {
+            if let FieldId::BastionChestContents(chest_type) = field.id {
+                shared.bastion_data.chest_type = chest_type;
+                if field.required {
+                    state.contents.title.push_str(" *");
+                }
+            }
+        }
+    }
+
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+        let data = &mut shared.bastion_data;
+        show_contents(&mut state.contents, &data.contents, data.chest_type);
+        state.xstate.show_number(&mut data.pos_x);
+        state.zstate.show_number(&mut data.pos_z);
+        let index = state.index_state.value.iter().collect::<String>();
+        let typed = match index.parse::<i32>() {
+            Ok(index) => Some(ChestIndex::Known(index)),
+            Err(_) => index.is_empty().then(ChestIndex::default),
+        };
+        if typed != Some(data.index) {
+            let text = match data.index {
+                ChestIndex::Known(index) => index.to_string(),
+                ChestIndex::AutoDetect { .. } => String::new(),
+            };
+            state.index_state.set_text(&text, &mut data.index);
+        }
+        let luck = state.luck_state.value.iter().collect::<String>();
+        if luck.parse::<f32>().ok() != Some(data.luck) && !(luck.is_empty() && data.luck == 0.0) {
+            state
+                .luck_state
+                .set_text(&data.luck.to_string(), &mut data.luck);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::{
+        features::{
+            bastion::{self, BastionChestType},
+            decorator_chest::ChestIndex,
+        },
+        math::Math,
+        search::StructureData,
+        tooltip::{ChestDump, LanguageTable},
+    };
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        layout::Rect,
+    };
+
+    use crate::tui::{
+        Component, EventContext,
+        application::ApplicationComponentState,
+        tabs::bastion::{BastionTabComponent, BastionTabState, Focus},
+    };
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn test_treasure_room_chest() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = Math::block_coords_to_chunk_coords((-674, -820));
+        let chest_type = BastionChestType::TreasureRoom;
+        let chest = bastion::get_bastion_chest(world_seed, chunk, 1, chest_type, 0.0);
+        let dump = ChestDump::new(
+            &chest,
+            &bastion::item_registry(chest_type),
+            &LanguageTable::en_us(),
+        )
+        .to_string();
+
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BastionTabState::default();
+        let tab = BastionTabComponent;
+        let area = Rect::new(0, 0, 160, 40);
+        tab.render(area, &mut Buffer::empty(area), &mut state, &mut shared);
+        tab.on_focus(&mut state, &mut shared);
+
+        let mut send = |state: &mut BastionTabState, event: Event| {
+            tab.handle_event(state, &mut shared, event, EventContext::BubblingDown);
+        };
+        for c in "-674".chars() {
+            send(&mut state, key(KeyCode::Char(c)));
+        }
+        send(&mut state, key(KeyCode::Tab));
+        for c in "-820".chars() {
+            send(&mut state, key(KeyCode::Char(c)));
+        }
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Char('1')));
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Tab));
+        assert_eq!(state.focus, Focus::Chest);
+
+        send(&mut state, ctrl('o'));
+        send(&mut state, Event::Paste(dump));
+        send(&mut state, ctrl('s'));
+        assert!(state.import.is_none());
+
+        let data = &shared.bastion_data;
+        assert!(data.usable);
+        assert_eq!((data.pos_x, data.pos_z), (-674, -820));
+        assert_eq!(data.index, ChestIndex::Known(1));
+        assert_eq!(data.chest_type, chest_type);
+        assert_eq!(data.contents, chest);
+        assert_eq!(shared.structure_search_error(), None);
+        match shared.cracking_evidence().structures.as_slice() {
+            [
+                StructureData::BastionChestContents {
+                    chunk_x, chunk_z, ..
+                },
+            ] => {
+                assert_eq!((*chunk_x, *chunk_z), chunk);
+            }
+            _ => panic!("expected the bastion chest"),
+        }
+
+        // disabled, the chest is kept but left out of the evidence
+        tab.handle_event(
+            &mut state,
+            &mut shared,
+            key(KeyCode::Char(' ')),
+            EventContext::BubblingDown,
+        );
+        assert!(shared.cracking_evidence().structures.is_empty());
+        assert_eq!(shared.bastion_data.contents, chest);
+
+        // the items other chest types don't have are removed
+        tab.handle_event(
+            &mut state,
+            &mut shared,
+            ctrl('n'),
+            EventContext::BubblingDown,
+        );
+        assert_eq!(shared.bastion_data.chest_type, BastionChestType::Bridges);
+        let registry = bastion::item_registry(BastionChestType::Bridges);
+        for stack in shared
+            .bastion_data
+            .contents
+            .rows
+            .iter()
+            .flat_map(|r| r.items.iter())
+        {
+            assert!(
+                stack
+                    .as_ref()
+                    .is_none_or(|s| registry.by_id(s.item).is_some())
+            );
+        }
+    }
+}
@@ -1,11 +1,18 @@
 use mcseedcracker::{
-    cubiomes::enums::{BiomeID, MCVersion},
+    analysis::{BiomeRowEstimate, estimate_biome_rows, world_stage_time},
+    calibration::Calibration,
+    cubiomes::enums::{BiomeID, Dimension, MCVersion},
+    pipeline::{CrackingEvidence, ObservedAt},
+    rarity::BiomeRarityTable,
     search::WorldExtraData,
+    templates::{EvidenceSection, EvidenceTemplate},
+    utils::format::{format_count, format_duration},
+    view::EnabledRows,
 };
 use ratatui::{
     buffer::Buffer,
     crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind},
-    layout::{Alignment, Constraint, Direction, Layout, Offset, Position, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Offset, Rect},
     style::{Color, Style, Stylize},
     widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
 };
@@ -15,14 +22,24 @@ use crate::{
     tui::{
         Component, EventContext, EventResult,
         application::ApplicationTab,
-        components::text_input::{TextInputState, TextInputWidget, Validator, i32_validator},
-        limit_area_height, limit_area_width,
+        components::text_input::{
+            TextInputState, TextInputWidget, Validator, block_coord_validator, i32_validator,
+        },
+        dim_area,
+        hitmap::HitTarget,
+        is_toggle_evidence_key, limit_area_height, limit_area_width,
     },
 };
 
+#[derive(Clone, PartialEq)]
 pub struct BiomesTabSharedData {
     pub overworld_biomes: WorldExtraData,
     pub nether_biomes: WorldExtraData,
+    /// Per row of `overworld_biomes`
+    pub overworld_enabled: EnabledRows,
+    /// Per row of `nether_biomes`
+    pub nether_enabled: EnabledRows,
+    pub observed_at: Option<ObservedAt>,
 }
 
 impl Default for BiomesTabSharedData {
@@ -30,10 +47,110 @@ impl Default for BiomesTabSharedData {
         Self {
             overworld_biomes: WorldExtraData::OverworldBiomeData(vec![]),
             nether_biomes: WorldExtraData::NetherBiomeData(vec![]),
+            overworld_enabled: EnabledRows::new(),
+            nether_enabled: EnabledRows::new(),
+            observed_at: None,
         }
     }
 }
 
+impl BiomesTabSharedData {
+    #[inline]
+    fn enabled_rows(&self, panel: Focus) -> Option<&EnabledRows> {
+        match panel {
+            Focus::Overworld => Some(&self.overworld_enabled),
+            Focus::Nether => Some(&self.nether_enabled),
+            Focus::Outside => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_enabled(&self, panel: Focus, row: usize) -> bool {
+        self.enabled_rows(panel)
+            .is_none_or(|rows| rows.is_enabled(row))
+    }
+
+    fn row_count(&self, panel: Focus) -> usize {
+        match panel {
+            Focus::Overworld => self.overworld_biomes.as_overworld().map_or(0, |v| v.len()),
+            Focus::Nether => self.nether_biomes.as_nether().map_or(0, |v| v.len()),
+            Focus::Outside => 0,
+        }
+    }
+
+    /// Enables or disables a row without touching its data, does nothing if there is no such row
+    pub fn toggle_row(&mut self, panel: Focus, row: usize) {
+        let row_count = self.row_count(panel);
+        match panel {
+            Focus::Overworld => self.overworld_enabled.toggle(row, row_count),
+            Focus::Nether => self.nether_enabled.toggle(row, row_count),
+            Focus::Outside => {}
+        }
+    }
+
+    fn remove_row(&mut self, panel: Focus, row: usize) {
+        let (data, rows) = match panel {
+            Focus::Overworld => (
+                self.overworld_biomes.as_overworld_mut(),
+                &mut self.overworld_enabled,
+            ),
+            Focus::Nether => (self.nether_biomes.as_nether_mut(), &mut self.nether_enabled),
+            Focus::Outside => return,
+        };
+        if let Some(data) = data {
+            if row < data.len() {
+                data.remove(row);
+            }
+        }
+        rows.remove(row);
+    }
+
+    /// Cost and benefit of each row of a panel, see [`estimate_biome_rows`]. The disabled rows have none and don't
+    /// count towards the cap.
+    pub fn row_estimates(&self, panel: Focus, cap: usize) -> Vec<Option<BiomeRowEstimate>> {
+        let (samples, dimension) = match panel {
+            Focus::Overworld => (
+                self.overworld_biomes.as_overworld(),
+                Dimension::DIM_OVERWORLD,
+            ),
+            Focus::Nether => (self.nether_biomes.as_nether(), Dimension::DIM_NETHER),
+            Focus::Outside => return Vec::new(),
+        };
+        let samples = samples.unwrap_or(&[]);
+        let enabled = (0..samples.len())
+            .filter(|row| self.is_enabled(panel, *row))
+            .collect::<Vec<_>>();
+        let biomes = enabled
+            .iter()
+            .map(|row| samples[*row].3)
+            .collect::<Vec<_>>();
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+
+        let mut estimates = vec![None; samples.len()];
+        for (row, estimate) in enabled
+            .into_iter()
+            .zip(estimate_biome_rows(dimension, &biomes, table, cap))
+        {
+            estimates[row] = Some(estimate);
+        }
+        estimates
+    }
+
+    /// The overworld and nether samples, without the disabled rows
+    pub fn world_extra_data(&self) -> Vec<WorldExtraData> {
+        vec![
+            WorldExtraData::OverworldBiomeData(
+                self.overworld_enabled
+                    .filter(self.overworld_biomes.as_overworld().unwrap_or(&[])),
+            ),
+            WorldExtraData::NetherBiomeData(
+                self.nether_enabled
+                    .filter(self.nether_biomes.as_nether().unwrap_or(&[])),
+            ),
+        ]
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Focus {
     #[default]
@@ -42,25 +159,59 @@ pub enum Focus {
     Nether,
 }
 
+pub type BiomeRowInputs = (
+    TextInputState<i32>,
+    TextInputState<i32>,
+    TextInputState<i32>,
+    TextInputState<BiomeID>,
+);
+
+/// Inputs of a biome observation, `required` marks them with a `*`
+fn biome_row_inputs(required: bool) -> BiomeRowInputs {
+    let marker = if required { " *" } else { "" };
+    (
+        TextInputState::new(format!("X (i32){}", marker), block_coord_validator()),
+        TextInputState::new(format!("Y (i32){}", marker), i32_validator()),
+        TextInputState::new(format!("Z (i32){}", marker), block_coord_validator()),
+        TextInputState::new(format!("Biome id{}", marker), biome_id_validator()),
+    )
+}
+
+/// Right-aligned after the summary of a row
+fn render_row_estimate(area: Rect, buf: &mut Buffer, estimate: &BiomeRowEstimate) {
+    let (text, color) = if estimate.past_cap {
+        (
+            format!(
+                "diminishing returns, {:.3} lookups",
+                estimate.lookups_per_candidate
+            ),
+            Color::Yellow,
+        )
+    } else {
+        (
+            format!(
+                "rejects {:.2}%, {:.3} lookups",
+                estimate.selectivity * 100.0,
+                estimate.lookups_per_candidate
+            ),
+            Color::DarkGray,
+        )
+    };
+    Paragraph::new(text)
+        .style(Style::default().fg(color))
+        .alignment(Alignment::Right)
+        .render(area, buf);
+}
+
 #[derive(Default)]
 pub struct BiomesTabState {
-    pub overworld_biomes: Vec<(
-        TextInputState<i32>,
-        TextInputState<i32>,
-        TextInputState<i32>,
-        TextInputState<BiomeID>,
-    )>,
-    pub nether_biomes: Vec<(
-        TextInputState<i32>,
-        TextInputState<i32>,
-        TextInputState<i32>,
-        TextInputState<BiomeID>,
-    )>,
+    pub overworld_biomes: Vec<BiomeRowInputs>,
+    pub nether_biomes: Vec<BiomeRowInputs>,
     pub focus: Focus,
     pub selected_x: usize,
     pub selected_y: usize,
-    pub overworld_rect: Rect,
-    pub nether_rect: Rect,
+    /// Loaded when the tab is focused, for the world stage time estimate
+    pub calibration: Option<Calibration>,
 }
 
 #[derive(Default)]
@@ -68,6 +219,22 @@ pub struct BiomesTabComponent;
 
 make_full_component!(BiomesTab, state: BiomesTabState, component: BiomesTabComponent);
 
+/// The enabled biome samples, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    let world = shared.biome_data.world_extra_data();
+    let usable = world.iter().any(|data| {
+        data.as_overworld()
+            .or(data.as_nether())
+            .is_some_and(|samples| !samples.is_empty())
+    });
+    evidence.world.extend(world);
+    evidence.biomes_observed_at = shared.biome_data.observed_at.clone();
+    usable
+}
+
 impl BiomesTab {
     pub fn apptab() -> ApplicationTab<Self> {
         ApplicationTab {
@@ -79,6 +246,7 @@ impl BiomesTab {
 
 impl Component for BiomesTabComponent {
     type State = BiomesTabState;
+    type Shared = SharedApplicationState;
 
     fn render(
         &self,
@@ -118,6 +286,9 @@ impl Component for BiomesTabComponent {
             Paragraph::new("[LEFT CLICK]").style(Style::default().fg(Color::Magenta).not_bold());
         let l5_2 =
             Paragraph::new(" Focus input").style(Style::default().fg(Color::Green).not_bold());
+        let l6_1 = Paragraph::new("[SPACE]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l6_2 = Paragraph::new(" Enable / disable selected line")
+            .style(Style::default().fg(Color::Green).not_bold());
 
         let controls_area = limit_area_height(controls, 1);
 
@@ -157,6 +328,40 @@ impl Component for BiomesTabComponent {
             limit_area_width(controls_area, 12).offset(Offset { x: 12, y: 4 }),
             buf,
         );
+        l6_1.render(
+            limit_area_width(controls_area, 7).offset(Offset { x: 0, y: 5 }),
+            buf,
+        );
+        l6_2.render(
+            limit_area_width(controls_area, 31).offset(Offset { x: 7, y: 5 }),
+            buf,
+        );
+
+        // the upper bound of the world seed stage, like the search plan
+        let candidates = shared.max_structure_seeds_to_simulate as u64 * 65536;
+        let world_stage = match &state.calibration {
+            Some(calibration) => format!(
+                "World stage: up to ~{} for {} candidates",
+                format_duration(world_stage_time(
+                    calibration,
+                    &shared.biome_data.world_extra_data(),
+                    candidates
+                )),
+                format_count(candidates)
+            ),
+            None => "World stage: press [C] in the output tab to calibrate".to_string(),
+        };
+        Paragraph::new(world_stage)
+            .style(Style::default().fg(Color::White))
+            .alignment(Alignment::Right)
+            .render(controls_area, buf);
+
+        let overworld_estimates = shared
+            .biome_data
+            .row_estimates(Focus::Overworld, shared.biome_row_cap);
+        let nether_estimates = shared
+            .biome_data
+            .row_estimates(Focus::Nether, shared.biome_row_cap);
 
         let overworld_block = Block::default()
             .borders(Borders::ALL)
@@ -181,8 +386,12 @@ impl Component for BiomesTabComponent {
         let overworld = overworld_block.inner(cols[0]);
         let nether = nether_block.inner(cols[1]);
 
-        state.overworld_rect = overworld;
-        state.nether_rect = nether;
+        shared
+            .hit_map
+            .record(overworld, HitTarget::BiomesPanel(Focus::Overworld));
+        shared
+            .hit_map
+            .record(nether, HitTarget::BiomesPanel(Focus::Nether));
 
         overworld_block.render(cols[0], buf);
         nether_block.render(cols[1], buf);
@@ -227,7 +436,18 @@ impl Component for BiomesTabComponent {
             TextInputWidget::default().render(overworld_areas[1], buf, &mut ov_data.1);
             TextInputWidget::default().render(overworld_areas[2], buf, &mut ov_data.2);
             TextInputWidget::default().render(overworld_areas[3], buf, &mut ov_data.3);
+            for (column, input_area) in overworld_areas.iter().enumerate() {
+                shared.hit_map.record(
+                    *input_area,
+                    HitTarget::BiomeInput {
+                        panel: Focus::Overworld,
+                        row: i,
+                        column,
+                    },
+                );
+            }
 
+            let enabled = shared.biome_data.is_enabled(Focus::Overworld, i);
             if let Some(data) = shared
                 .biome_data
                 .overworld_biomes
@@ -235,8 +455,12 @@ impl Component for BiomesTabComponent {
                 .and_then(|v| v.get(i))
             {
                 Paragraph::new(format!(
-                    "X: {} | Y: {} | Z: {} | Biome: {:?}",
-                    data.0, data.1, data.2, data.3
+                    "X: {} | Y: {} | Z: {} | Biome: {:?}{}",
+                    data.0,
+                    data.1,
+                    data.2,
+                    data.3,
+                    if enabled { "" } else { " (disabled)" }
                 ))
                 .render(
                     limit_area_height(overworld, 1).offset(Offset {
@@ -246,6 +470,23 @@ impl Component for BiomesTabComponent {
                     buf,
                 );
             }
+            if let Some(estimate) = overworld_estimates.get(i).copied().flatten() {
+                render_row_estimate(
+                    limit_area_height(overworld, 1).offset(Offset {
+                        x: 0,
+                        y: 5 * (i as i32) + 3,
+                    }),
+                    buf,
+                    &estimate,
+                );
+            }
+            if !enabled {
+                dim_area(
+                    Rect::new(overworld.x, overworld_areas[0].y, overworld.width, 4)
+                        .intersection(overworld),
+                    buf,
+                );
+            }
 
             overworld_areas[0].y += 5;
             overworld_areas[1].y += 5;
@@ -292,7 +533,18 @@ impl Component for BiomesTabComponent {
             TextInputWidget::default().render(nether_areas[1], buf, &mut ne_data.1);
             TextInputWidget::default().render(nether_areas[2], buf, &mut ne_data.2);
             TextInputWidget::default().render(nether_areas[3], buf, &mut ne_data.3);
+            for (column, input_area) in nether_areas.iter().enumerate() {
+                shared.hit_map.record(
+                    *input_area,
+                    HitTarget::BiomeInput {
+                        panel: Focus::Nether,
+                        row: i,
+                        column,
+                    },
+                );
+            }
 
+            let enabled = shared.biome_data.is_enabled(Focus::Nether, i);
             if let Some(data) = shared
                 .biome_data
                 .nether_biomes
@@ -300,8 +552,12 @@ impl Component for BiomesTabComponent {
                 .and_then(|v| v.get(i))
             {
                 Paragraph::new(format!(
-                    "X: {} | Y: {} | Z: {} | Biome: {:?}",
-                    data.0, data.1, data.2, data.3
+                    "X: {} | Y: {} | Z: {} | Biome: {:?}{}",
+                    data.0,
+                    data.1,
+                    data.2,
+                    data.3,
+                    if enabled { "" } else { " (disabled)" }
                 ))
                 .render(
                     limit_area_height(nether, 1).offset(Offset {
@@ -311,6 +567,22 @@ impl Component for BiomesTabComponent {
                     buf,
                 );
             }
+            if let Some(estimate) = nether_estimates.get(i).copied().flatten() {
+                render_row_estimate(
+                    limit_area_height(nether, 1).offset(Offset {
+                        x: 0,
+                        y: 5 * (i as i32) + 3,
+                    }),
+                    buf,
+                    &estimate,
+                );
+            }
+            if !enabled {
+                dim_area(
+                    Rect::new(nether.x, nether_areas[0].y, nether.width, 4).intersection(nether),
+                    buf,
+                );
+            }
 
             nether_areas[0].y += 5;
             nether_areas[1].y += 5;
@@ -381,25 +653,16 @@ impl Component for BiomesTabComponent {
                 {
                     if state.focus == Focus::Overworld {
                         if let Some(v) = shared.biome_data.overworld_biomes.as_overworld_mut() {
-                            state.overworld_biomes.push((
-                                TextInputState::new("X (i32)", i32_validator()),
-                                TextInputState::new("Y (i32)", i32_validator()),
-                                TextInputState::new("Z (i32)", i32_validator()),
-                                TextInputState::new("Biome id", biome_id_validator()),
-                            ));
+                            state.overworld_biomes.push(biome_row_inputs(false));
                             v.push((0, 0, 0, BiomeID::none));
                         }
                     } else if state.focus == Focus::Nether {
                         if let Some(v) = shared.biome_data.nether_biomes.as_nether_mut() {
-                            state.nether_biomes.push((
-                                TextInputState::new("X (i32)", i32_validator()),
-                                TextInputState::new("Y (i32)", i32_validator()),
-                                TextInputState::new("Z (i32)", i32_validator()),
-                                TextInputState::new("Biome id", biome_id_validator()),
-                            ));
+                            state.nether_biomes.push(biome_row_inputs(false));
                             v.push((0, 0, 0, BiomeID::none));
                         }
                     };
+                    shared.hit_map.clear();
                     EventResult::Captured
                 }
                 Event::Key(key)
@@ -421,40 +684,28 @@ impl Component for BiomesTabComponent {
                 Event::Mouse(mouse) if mouse.kind == MouseEventKind::Moved => {
                     let def_style = Style::default().fg(Color::White);
                     let hover_style = Style::default().fg(Color::LightCyan);
+                    let hovered = shared.hit_map.hit(mouse.column, mouse.row);
+                    let focused = state.focus != Focus::Outside;
 
-                    for data in state
-                        .overworld_biomes
-                        .iter_mut()
-                        .chain(state.nether_biomes.iter_mut())
-                    {
-                        data.0.style.border_style = if state.focus != Focus::Outside
-                            && data.0.in_rect(mouse.column, mouse.row)
-                        {
-                            hover_style
-                        } else {
-                            def_style
-                        };
-                        data.1.style.border_style = if state.focus != Focus::Outside
-                            && data.1.in_rect(mouse.column, mouse.row)
-                        {
-                            hover_style
-                        } else {
-                            def_style
-                        };
-                        data.2.style.border_style = if state.focus != Focus::Outside
-                            && data.2.in_rect(mouse.column, mouse.row)
-                        {
-                            hover_style
-                        } else {
-                            def_style
-                        };
-                        data.3.style.border_style = if state.focus != Focus::Outside
-                            && data.3.in_rect(mouse.column, mouse.row)
-                        {
-                            hover_style
-                        } else {
-                            def_style
-                        };
+                    for (panel, rows) in [
+                        (Focus::Overworld, &mut state.overworld_biomes),
+                        (Focus::Nether, &mut state.nether_biomes),
+                    ] {
+                        for (row, data) in rows.iter_mut().enumerate() {
+                            let style = |column| {
+                                if focused
+                                    && hovered == Some(HitTarget::BiomeInput { panel, row, column })
+                                {
+                                    hover_style
+                                } else {
+                                    def_style
+                                }
+                            };
+                            data.0.style.border_style = style(0);
+                            data.1.style.border_style = style(1);
+                            data.2.style.border_style = style(2);
+                            data.3.style.border_style = style(3);
+                        }
                     }
 
                     EventResult::BubbleUp(event)
@@ -464,52 +715,15 @@ impl Component for BiomesTabComponent {
                         return EventResult::BubbleUp(event);
                     }
 
-                    if state
-                        .overworld_rect
-                        .contains(Position::new(mouse.column, mouse.row))
-                    {
-                        state.focus = Focus::Overworld;
-                    } else if state
-                        .nether_rect
-                        .contains(Position::new(mouse.column, mouse.row))
-                    {
-                        state.focus = Focus::Nether;
-                    }
-
-                    for (i, data) in state.overworld_biomes.iter().enumerate() {
-                        if data.0.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 0;
-                            state.selected_y = i;
-                        }
-                        if data.1.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 1;
-                            state.selected_y = i;
-                        }
-                        if data.2.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 2;
-                            state.selected_y = i;
-                        }
-                        if data.3.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 3;
-                            state.selected_y = i;
-                        }
-                    }
-                    for (i, data) in state.nether_biomes.iter().enumerate() {
-                        if data.0.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 0;
-                            state.selected_y = i;
-                        }
-                        if data.1.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 1;
-                            state.selected_y = i;
-                        }
-                        if data.2.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 2;
-                            state.selected_y = i;
-                        }
-                        if data.3.in_rect(mouse.column, mouse.row) {
-                            state.selected_x = 3;
-                            state.selected_y = i;
+                    for target in shared.hit_map.hits(mouse.column, mouse.row) {
+                        match target {
+                            HitTarget::BiomesPanel(panel) => state.focus = panel,
+                            HitTarget::BiomeInput { panel, row, column } => {
+                                state.focus = panel;
+                                state.selected_x = column;
+                                state.selected_y = row;
+                            }
+                            _ => {}
                         }
                     }
 
@@ -517,6 +731,12 @@ impl Component for BiomesTabComponent {
                 }
                 _ => EventResult::BubbleUp(event),
             },
+            EventContext::BubblingDown
+                if state.focus != Focus::Outside && is_toggle_evidence_key(&event) =>
+            {
+                shared.biome_data.toggle_row(state.focus, state.selected_y);
+                EventResult::Captured
+            }
             EventContext::BubblingDown => match state.focus {
                 Focus::Outside => EventResult::BubbleUp(event),
                 Focus::Overworld => {
@@ -524,12 +744,11 @@ impl Component for BiomesTabComponent {
                     {
                         if state.overworld_biomes.get(state.selected_y).is_some() {
                             state.overworld_biomes.remove(state.selected_y);
+                            shared.hit_map.clear();
                         }
-                        if let Some(data) = shared.biome_data.overworld_biomes.as_overworld_mut() {
-                            if data.get(state.selected_y).is_some() {
-                                data.remove(state.selected_y);
-                            }
-                        }
+                        shared
+                            .biome_data
+                            .remove_row(Focus::Overworld, state.selected_y);
                         return EventResult::Captured;
                     }
 
@@ -583,12 +802,11 @@ impl Component for BiomesTabComponent {
                     {
                         if state.nether_biomes.get(state.selected_y).is_some() {
                             state.nether_biomes.remove(state.selected_y);
+                            shared.hit_map.clear();
                         }
-                        if let Some(data) = shared.biome_data.nether_biomes.as_overworld_mut() {
-                            if data.get(state.selected_y).is_some() {
-                                data.remove(state.selected_y);
-                            }
-                        }
+                        shared
+                            .biome_data
+                            .remove_row(Focus::Nether, state.selected_y);
                         return EventResult::Captured;
                     }
 
@@ -641,10 +859,11 @@ impl Component for BiomesTabComponent {
         }
     }
 
-    fn on_focus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+    fn on_focus(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
         state.focus = Focus::Overworld;
         state.selected_x = 0;
         state.selected_y = 0;
+        state.calibration = Calibration::load_stored(shared.search_options.thread_count());
     }
 
     fn on_unfocus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
@@ -652,6 +871,77 @@ impl Component for BiomesTabComponent {
         state.selected_x = 0;
         state.selected_y = 0;
     }
+
+    fn apply_template(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        template: &EvidenceTemplate,
+    ) {
+        for field in template.fields.iter().filter(|f| f.rows > 0) {
+            let (inputs, data) = match field.section {
+                EvidenceSection::OverworldBiomes => (
+                    &mut state.overworld_biomes,
+                    shared.biome_data.overworld_biomes.as_overworld_mut(),
+                ),
+                EvidenceSection::NetherBiomes => (
+                    &mut state.nether_biomes,
+                    shared.biome_data.nether_biomes.as_nether_mut(),
+                ),
+                _ => continue,
+            };
+            let Some(data) = data else {
+                continue;
+            };
+            for _ in 0..field.rows {
+                inputs.push(biome_row_inputs(field.required));
+                data.push((0, 0, 0, BiomeID::none));
+            }
+        }
+        shared.hit_map.clear();
+    }
+
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+        for (inputs, samples) in [
+            (
+                &mut state.overworld_biomes,
+                shared.biome_data.overworld_biomes.as_overworld_mut(),
+            ),
+            (
+                &mut state.nether_biomes,
+                shared.biome_data.nether_biomes.as_nether_mut(),
+            ),
+        ] {
+            let Some(samples) = samples else {
+                continue;
+            };
+            inputs.truncate(samples.len());
+            while inputs.len() < samples.len() {
+                inputs.push(biome_row_inputs(false));
+            }
+            for (input, (x, y, z, biome)) in inputs.iter_mut().zip(samples.iter_mut()) {
+                input.0.show_number(x);
+                input.1.show_number(y);
+                input.2.show_number(z);
+                let text = if *biome == BiomeID::none {
+                    ""
+                } else {
+                    biome.to_mc_biome_str(MCVersion::MC_1_16_5)
+                };
+                if input.3.value.iter().collect::<String>() != text {
+                    input.3.set_text(text, biome);
+                }
+            }
+        }
+
+        let len = match state.focus {
+            Focus::Overworld => state.overworld_biomes.len(),
+            Focus::Nether => state.nether_biomes.len(),
+            Focus::Outside => 0,
+        };
+        state.selected_y = state.selected_y.min(len.saturating_sub(1));
+        shared.hit_map.clear();
+    }
 }
 
 pub fn list_biomes() -> &'static [BiomeID] {
@@ -795,3 +1085,300 @@ pub fn biome_id_validator() -> Validator<BiomeID> {
         }
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{
+            Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+        },
+        layout::Rect,
+    };
+
+    use mcseedcracker::{
+        cubiomes::enums::BiomeID, features::end_pillars::PartialEndPillars, search::WorldExtraData,
+    };
+
+    use crate::tui::{
+        Component, EventContext,
+        application::{ApplicationComponentState, SharedApplicationState},
+        hitmap::HitTarget,
+        tabs::{
+            biomes::{BiomesTabComponent, BiomesTabState, Focus},
+            decorations::DecorationEntry,
+            end_pillars::{EndPillarsTabComponent, EndPillarsTabState},
+        },
+    };
+
+    const AREA: Rect = Rect::new(0, 0, 160, 40);
+
+    fn frame(state: &mut BiomesTabState, shared: &mut SharedApplicationState) -> Buffer {
+        let mut buf = Buffer::empty(AREA);
+        shared.hit_map.clear();
+        BiomesTabComponent.render(AREA, &mut buf, state, shared);
+        buf
+    }
+
+    fn send(state: &mut BiomesTabState, shared: &mut SharedApplicationState, event: Event) {
+        BiomesTabComponent.handle_event(state, shared, event, EventContext::BubblingDown);
+    }
+
+    fn ctrl(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::CONTROL))
+    }
+
+    fn click((column, row): (u16, u16)) -> Event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    fn find_input(shared: &SharedApplicationState, row: usize, column: usize) -> (u16, u16) {
+        let target = HitTarget::BiomeInput {
+            panel: Focus::Overworld,
+            row,
+            column,
+        };
+        (AREA.top()..AREA.bottom())
+            .flat_map(|y| (AREA.left()..AREA.right()).map(move |x| (x, y)))
+            .find(|(x, y)| shared.hit_map.hit(*x, *y) == Some(target))
+            .expect("input not rendered")
+    }
+
+    #[test]
+    fn test_click_after_row_removal() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BiomesTabState::default();
+        BiomesTabComponent.on_focus(&mut state, &mut shared);
+
+        send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        frame(&mut state, &mut shared);
+
+        let row0 = find_input(&shared, 0, 2);
+        let row1 = find_input(&shared, 1, 2);
+        send(&mut state, &mut shared, click(row1));
+        assert_eq!((state.selected_x, state.selected_y), (2, 1));
+        send(&mut state, &mut shared, click(row0));
+        assert_eq!((state.selected_x, state.selected_y), (2, 0));
+
+        // remove the first row, the second one moves into its place
+        send(&mut state, &mut shared, ctrl(KeyCode::Delete));
+        assert_eq!(state.overworld_biomes.len(), 1);
+
+        // the row that was there before the removal must not be selectable anymore
+        send(&mut state, &mut shared, click(row1));
+        assert_eq!((state.selected_x, state.selected_y), (2, 0));
+
+        frame(&mut state, &mut shared);
+        send(&mut state, &mut shared, click(row1));
+        assert_eq!((state.selected_x, state.selected_y), (2, 0));
+        assert_eq!(find_input(&shared, 0, 2), row0);
+    }
+
+    #[test]
+    fn test_click_after_row_insertion() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BiomesTabState::default();
+        BiomesTabComponent.on_focus(&mut state, &mut shared);
+
+        send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        frame(&mut state, &mut shared);
+        let row0 = find_input(&shared, 0, 1);
+
+        send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        // nothing was rendered for the new row yet
+        send(&mut state, &mut shared, click(row0));
+        assert_eq!((state.selected_x, state.selected_y), (0, 0));
+
+        frame(&mut state, &mut shared);
+        let row1 = find_input(&shared, 1, 3);
+        send(&mut state, &mut shared, click(row1));
+        assert_eq!((state.selected_x, state.selected_y), (3, 1));
+        assert_eq!(state.focus, Focus::Overworld);
+
+        // clicking the nether panel moves the focus there
+        let (x, y) = row1;
+        send(&mut state, &mut shared, click((x + 80, y)));
+        assert_eq!(state.focus, Focus::Nether);
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn overworld_samples(shared: &SharedApplicationState) -> Vec<(i32, i32, i32, BiomeID)> {
+        match &shared.cracking_evidence().world[0] {
+            WorldExtraData::OverworldBiomeData(samples) => samples.clone(),
+            _ => panic!("the first world evidence must be the overworld biomes"),
+        }
+    }
+
+    #[test]
+    fn test_toggle_rows() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BiomesTabState::default();
+        BiomesTabComponent.on_focus(&mut state, &mut shared);
+
+        for _ in 0..3 {
+            send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        }
+        let samples = shared
+            .biome_data
+            .overworld_biomes
+            .as_overworld_mut()
+            .unwrap();
+        samples[0] = (1, 60, 1, BiomeID::plains);
+        samples[1] = (2, 60, 2, BiomeID::desert);
+        samples[2] = (3, 60, 3, BiomeID::forest);
+
+        state.selected_y = 1;
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        assert!(!shared.biome_data.is_enabled(Focus::Overworld, 1));
+        assert_eq!(
+            overworld_samples(&shared),
+            vec![(1, 60, 1, BiomeID::plains), (3, 60, 3, BiomeID::forest)]
+        );
+        // disabled rows keep their data
+        assert_eq!(
+            shared
+                .biome_data
+                .overworld_biomes
+                .as_overworld()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // removing a row above keeps the flags aligned with their rows
+        state.selected_y = 0;
+        send(&mut state, &mut shared, ctrl(KeyCode::Delete));
+        assert!(!shared.biome_data.is_enabled(Focus::Overworld, 0));
+        assert_eq!(
+            overworld_samples(&shared),
+            vec![(3, 60, 3, BiomeID::forest)]
+        );
+
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        assert_eq!(
+            overworld_samples(&shared),
+            vec![(2, 60, 2, BiomeID::desert), (3, 60, 3, BiomeID::forest)]
+        );
+
+        // nothing to toggle past the last row, or outside of the panels
+        state.selected_y = 5;
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        assert!(shared.biome_data.overworld_enabled.len() <= 2);
+        state.focus = Focus::Outside;
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        assert_eq!(overworld_samples(&shared).len(), 2);
+
+        frame(&mut state, &mut shared);
+    }
+
+    #[test]
+    fn test_snapshot_with_disabled_evidence() {
+        let mut shared = ApplicationComponentState::new().shared;
+        shared.pillar_data.0[0].caged = Some(true);
+        shared.decoration_data.decorations = vec![
+            DecorationEntry::default(),
+            DecorationEntry {
+                chunk_x: 5,
+                enabled: false,
+                ..Default::default()
+            },
+        ];
+        for decoration in shared.decoration_data.decorations.iter_mut() {
+            decoration.kind = Some(DecorationKind::SugarCane);
+        }
+        shared
+            .biome_data
+            .nether_biomes
+            .as_nether_mut()
+            .unwrap()
+            .push((0, 64, 0, BiomeID::crimson_forest));
+        shared.biome_data.toggle_row(Focus::Nether, 0);
+
+        let evidence = shared.cracking_evidence();
+        assert_eq!(evidence.pillars, shared.pillar_data);
+        assert_eq!(evidence.world.len(), 3);
+        assert!(evidence.world[1] == WorldExtraData::NetherBiomeData(vec![]));
+        assert!(matches!(
+            evidence.world[2],
+            WorldExtraData::DecorationCount { chunk: (0, 0), .. }
+        ));
+
+        // space toggles the whole pillar observation while no pillar is selected
+        let mut pillars = EndPillarsTabState::default();
+        EndPillarsTabComponent.handle_event(
+            &mut pillars,
+            &mut shared,
+            key(KeyCode::Char(' ')),
+            EventContext::BubblingDown,
+        );
+        assert!(!shared.pillars_enabled);
+        assert_eq!(shared.cracking_evidence().pillars, PartialEndPillars::new());
+        assert_eq!(shared.pillar_sim().len(), 65536);
+        assert_eq!(shared.pillar_data.0[0].caged, Some(true));
+
+        pillars.focused_on_pillar = Some(0);
+        EndPillarsTabComponent.handle_event(
+            &mut pillars,
+            &mut shared,
+            key(KeyCode::Char(' ')),
+            EventContext::BubblingDown,
+        );
+        assert!(!shared.pillars_enabled);
+        assert_eq!(shared.pillar_data.0[0].caged, None);
+    }
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (AREA.top()..AREA.bottom())
+            .map(|y| {
+                (AREA.left()..AREA.right())
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_biome_row_cap() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BiomesTabState::default();
+        BiomesTabComponent.on_focus(&mut state, &mut shared);
+        shared.biome_row_cap = 2;
+
+        for _ in 0..2 {
+            send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        }
+        let hints = |buf: &Buffer| {
+            lines(buf)
+                .iter()
+                .filter(|l| l.contains("diminishing returns"))
+                .count()
+        };
+        let buf = frame(&mut state, &mut shared);
+        assert_eq!(hints(&buf), 0);
+        assert!(lines(&buf).iter().any(|l| l.contains("rejects 99.90%")));
+        assert!(lines(&buf).iter().any(|l| l.contains("World stage: ")));
+
+        send(&mut state, &mut shared, ctrl(KeyCode::Char('n')));
+        assert_eq!(hints(&frame(&mut state, &mut shared)), 1);
+
+        // a disabled row doesn't count towards the cap
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        assert_eq!(hints(&frame(&mut state, &mut shared)), 0);
+        let estimates = shared.biome_data.row_estimates(Focus::Overworld, 2);
+        assert_eq!(estimates[0], None);
+        assert!(
+            estimates[1..]
+                .iter()
+                .all(|e| e.is_some_and(|e| !e.past_cap))
+        );
+    }
+}
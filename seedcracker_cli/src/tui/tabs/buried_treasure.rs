@@ -5,18 +5,19 @@ use crate::{
         application::ApplicationTab,
         components::{
             chest::{ChestState, ChestWidget},
-            text_input::{TextInputState, TextInputWidget},
+            text_input::{TextInputState, TextInputWidget, block_coord_validator},
         },
-        limit_area_height, limit_area_width,
+        dim_area, get_area_centered, limit_area_height, limit_area_width,
     },
 };
 
 use ratatui::{
     buffer::Buffer,
     crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
-    layout::{Constraint, Direction, Layout, Offset, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Offset, Rect},
     style::{Color, Style, Stylize},
-    widgets::{Paragraph, StatefulWidget, Widget},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget},
 };
 
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
@@ -26,6 +27,22 @@ pub enum Focus {
     Chest,
     CoordX,
     CoordZ,
+    AreaCornerX,
+    AreaCornerZ,
+}
+
+impl Focus {
+    /// The focus after [TAB], the area corner inputs are skipped while there is no area
+    fn next(self, area: bool) -> Self {
+        match self {
+            Focus::Outside => Focus::CoordX,
+            Focus::CoordX => Focus::CoordZ,
+            Focus::CoordZ if area => Focus::AreaCornerX,
+            Focus::AreaCornerX => Focus::AreaCornerZ,
+            Focus::CoordZ | Focus::AreaCornerZ => Focus::Chest,
+            Focus::Chest => Focus::Outside,
+        }
+    }
 }
 
 pub struct BuriedTreasureTabState {
@@ -33,6 +50,43 @@ pub struct BuriedTreasureTabState {
     pub focus: Focus,
     pub xstate: TextInputState<i32>,
     pub zstate: TextInputState<i32>,
+    pub corner_xstate: TextInputState<i32>,
+    pub corner_zstate: TextInputState<i32>,
+    /// Chest import popup, open while `Some`
+    pub import: Option<ChestImportState>,
+    /// Item counts popup, open while `Some`
+    pub counts_editor: Option<ChestImportState>,
+}
+
+/// Text typed or pasted in a popup, in one of the line formats of [`mcseedcracker::tooltip`]
+#[derive(Default)]
+pub struct ChestImportState {
+    pub text: String,
+    pub errors: Vec<String>,
+}
+
+impl ChestImportState {
+    /// Edits the text, true when the event was a text edit
+    pub fn edit(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Paste(paste) => {
+                self.text
+                    .push_str(&paste.replace("\r\n", "\n").replace('\r', "\n"));
+            }
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.text.push(c);
+                }
+                KeyCode::Enter => self.text.push('\n'),
+                KeyCode::Backspace => {
+                    self.text.pop();
+                }
+                _ => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
 }
 
 impl Default for BuriedTreasureTabState {
@@ -42,21 +96,111 @@ impl Default for BuriedTreasureTabState {
             focus: Focus::default(),
             xstate: TextInputState::default(),
             zstate: TextInputState::default(),
+            corner_xstate: TextInputState::default(),
+            corner_zstate: TextInputState::default(),
+            import: None,
+            counts_editor: None,
         };
         value.xstate.style.title = "Treasure X (i32)".to_string();
         value.zstate.style.title = "Treasure Z (i32)".to_string();
+        value.corner_xstate.style.title = "Area corner X (i32)".to_string();
+        value.corner_zstate.style.title = "Area corner Z (i32)".to_string();
 
         value
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, PartialEq)]
 pub struct BuriedTreasureTabSharedData {
     pub contents: SingleChest,
     pub pos_x: i32,
     pub pos_z: i32,
     pub luck: f32,
     pub usable: bool,
+    /// Remembered item totals, used instead of the chest when it is empty
+    pub counts: Vec<(usize, CountBound)>,
+    /// The chest is somewhere between the treasure position and the area corner
+    pub area: bool,
+    pub area_corner_x: i32,
+    pub area_corner_z: i32,
+    pub observed_at: Option<ObservedAt>,
+    /// The other treasures of the world, searched together with the edited one
+    pub kept: Vec<BuriedTreasureEvidence>,
+}
+
+impl BuriedTreasureTabSharedData {
+    /// The edited chest, whether or not it is usable
+    pub fn edited_treasure(&self) -> BuriedTreasureEvidence {
+        BuriedTreasureEvidence {
+            pos_x: self.pos_x,
+            pos_z: self.pos_z,
+            luck: self.luck,
+            contents: self.contents.clone(),
+            area_corner: self
+                .area
+                .then_some((self.area_corner_x, self.area_corner_z)),
+            observed_at: self.observed_at.clone(),
+        }
+    }
+
+    /// Only a usable chest without item counts is kept, the counts are a single treasure's
+    fn can_keep(&self) -> bool {
+        self.usable && self.counts.is_empty() && self.contents != SingleChest::new()
+    }
+
+    /// Keeps the edited chest and starts a new one, false if it can't be kept
+    pub fn keep_treasure(&mut self) -> bool {
+        if !self.can_keep() {
+            return false;
+        }
+        self.kept.push(self.edited_treasure());
+        self.edit(None);
+        true
+    }
+
+    /// Edits the first kept treasure and keeps the edited one after the others, an empty chest is dropped
+    pub fn next_treasure(&mut self) -> bool {
+        let blank = self.counts.is_empty() && self.contents == SingleChest::new();
+        if self.kept.is_empty() || !(blank || self.can_keep()) {
+            return false;
+        }
+        let next = self.kept.remove(0);
+        if !blank {
+            self.kept.push(self.edited_treasure());
+        }
+        self.edit(Some(next));
+        true
+    }
+
+    /// Drops the edited chest and edits the first kept treasure, if any
+    pub fn remove_treasure(&mut self) {
+        let next = (!self.kept.is_empty()).then(|| self.kept.remove(0));
+        self.edit(next);
+    }
+
+    fn edit(&mut self, treasure: Option<BuriedTreasureEvidence>) {
+        let kept = std::mem::take(&mut self.kept);
+        *self = match treasure {
+            Some(t) => Self {
+                contents: t.contents,
+                pos_x: t.pos_x,
+                pos_z: t.pos_z,
+                luck: t.luck,
+                usable: true,
+                area: t.area_corner.is_some(),
+                area_corner_x: t.area_corner.map_or(t.pos_x, |c| c.0),
+                area_corner_z: t.area_corner.map_or(t.pos_z, |c| c.1),
+                observed_at: t.observed_at,
+                ..Default::default()
+            },
+            // the luck is the player's, it stays for the next chest
+            None => Self {
+                luck: self.luck,
+                ..Default::default()
+            },
+        };
+        self.kept = kept;
+    }
 }
 
 #[derive(Default)]
@@ -64,6 +208,17 @@ pub struct BuriedTreasureTabComponent;
 
 make_full_component!(BuriedTreasureTab, state: BuriedTreasureTabState, component: BuriedTreasureTabComponent);
 
+/// The chest contents or its item counts, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    evidence.buried_treasures = shared.buried_treasure_evidence();
+    evidence.buried_treasure_counts = shared.buried_treasure_count_evidence();
+    evidence.buried_treasure_loot_table = shared.buried_treasure_loot_table.clone();
+    !evidence.buried_treasures.is_empty() || evidence.buried_treasure_counts.is_some()
+}
+
 impl BuriedTreasureTab {
     pub fn apptab() -> ApplicationTab<Self> {
         ApplicationTab {
@@ -74,11 +229,17 @@ impl BuriedTreasureTab {
 }
 
 use mcseedcracker::{
-    features::buried_treasure::items::{
-        COOKED_COD, COOKED_SALMON, DIAMOND, EMERALD, GOLD_INGOT, HEART_OF_THE_SEA, IRON_INGOT,
-        IRON_SWORD, LEATHER_CHESTPLATE, PRISMARINE_CRYSTALS, TNT,
+    features::buried_treasure::{
+        item_registry,
+        items::{
+            COOKED_COD, COOKED_SALMON, DIAMOND, EMERALD, GOLD_INGOT, HEART_OF_THE_SEA, IRON_INGOT,
+            IRON_SWORD, LEATHER_CHESTPLATE, PRISMARINE_CRYSTALS, TNT,
+        },
     },
-    loot_table::{ItemStack, SingleChest},
+    loot_table::{CountBound, ItemStack, SingleChest},
+    pipeline::{BuriedTreasureEvidence, CrackingEvidence, ObservedAt},
+    templates::{EvidenceTemplate, FieldId},
+    tooltip::{LanguageTable, count_constraint_line, parse_count_constraints, parse_tooltip_dump},
 };
 
 #[inline(always)]
@@ -99,8 +260,254 @@ fn item_to_string(item: usize) -> &'static str {
     }
 }
 
+#[inline(always)]
+const fn item_color(item: usize) -> Color {
+    match item {
+        COOKED_COD => Color::Indexed(186),
+        COOKED_SALMON => Color::Indexed(202),
+        DIAMOND => Color::LightCyan,
+        EMERALD => Color::Indexed(28),
+        GOLD_INGOT => Color::LightYellow,
+        HEART_OF_THE_SEA => Color::Blue,
+        IRON_INGOT | IRON_SWORD => Color::DarkGray,
+        LEATHER_CHESTPLATE => Color::Indexed(172),
+        PRISMARINE_CRYSTALS => Color::Indexed(79),
+        TNT => Color::LightRed,
+        _ => Color::White,
+    }
+}
+
+/// The chest cell of a stack, highlighted if its count doesn't fit in one stack
+fn stack_style(item: usize, count: i32) -> Style {
+    match ItemStack::validated(item, count, &item_registry()) {
+        Ok(_) => Style::default().fg(item_color(item)).not_bold(),
+        Err(_) => Style::default().fg(Color::White).bg(Color::Red).not_bold(),
+    }
+}
+
+/// A popup showing the help lines above the text of `import` and its errors
+pub fn render_text_popup(
+    area: Rect,
+    buf: &mut Buffer,
+    import: &ChestImportState,
+    help: &[&str],
+    title: &str,
+    bottom: &str,
+) {
+    let mut lines: Vec<Line> = help
+        .iter()
+        .map(|line| Line::from(*line).style(Style::default().fg(Color::Gray)))
+        .collect();
+    lines.push(Line::from(""));
+
+    let text_lines: Vec<&str> = import.text.split('\n').collect();
+    for (i, line) in text_lines.iter().enumerate() {
+        if i + 1 == text_lines.len() {
+            lines.push(Line::from(format!("{}\u{2588}", line)));
+        } else {
+            lines.push(Line::from(*line));
+        }
+    }
+
+    if !import.errors.is_empty() {
+        lines.push(Line::from(""));
+        for error in import.errors.iter() {
+            lines.push(Line::from(error.as_str()).style(Style::default().fg(Color::Red)));
+        }
+    }
+
+    let width = lines.iter().map(|l| l.width()).max().unwrap_or(0).max(60) as u16 + 4;
+    let height = lines.len() as u16 + 2;
+    let popup_area = get_area_centered(
+        Rect::new(0, 0, width.min(area.width), height.min(area.height)),
+        area,
+    );
+
+    // keep the end of the text visible when it doesn't fit
+    let scroll = (lines.len() as u16 + 2).saturating_sub(popup_area.height);
+
+    Clear.render(popup_area, buf);
+    Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .scroll((scroll, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::LightCyan))
+                .title(title)
+                .title_bottom(bottom)
+                .title_alignment(Alignment::Center),
+        )
+        .render(popup_area, buf);
+}
+
+impl BuriedTreasureTabComponent {
+    fn render_import_popup(&self, area: Rect, buf: &mut Buffer, import: &ChestImportState) {
+        let help = [
+            "One slot per line, rows 1 to 3 and columns 1 to 9:",
+            "  slot 1,1: Iron Ingot x4",
+            "  slot 2,5: Iron Sword durability 123/250",
+            "  slot 3,9: minecraft:tnt x2",
+        ];
+        render_text_popup(
+            area,
+            buf,
+            import,
+            &help,
+            "Import chest from tooltips",
+            "[CTRL + S] Import    [ESC] Cancel",
+        );
+    }
+
+    fn render_counts_popup(&self, area: Rect, buf: &mut Buffer, editor: &ChestImportState) {
+        let help = [
+            "Total of an item in the chest, one item per line:",
+            "  Iron Ingot 9",
+            "  Cooked Cod 8+",
+            "  minecraft:tnt <=2",
+            "  Gold Ingot 2-5",
+        ];
+        render_text_popup(
+            area,
+            buf,
+            editor,
+            &help,
+            "Buried treasure item counts",
+            "[CTRL + S] Save    [ESC] Cancel",
+        );
+    }
+
+    fn handle_import_event(
+        &self,
+        state: &mut BuriedTreasureTabState,
+        shared: &mut SharedApplicationState,
+        event: Event,
+    ) -> EventResult {
+        let Some(import) = &mut state.import else {
+            return EventResult::BubbleUp(event);
+        };
+
+        if import.edit(&event) {
+            return EventResult::Captured;
+        }
+        match &event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Esc => state.import = None,
+                KeyCode::Char('s' | 'S') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    match parse_tooltip_dump(
+                        &import.text,
+                        &item_registry(),
+                        &LanguageTable::en_us(),
+                    ) {
+                        Ok(chest) => {
+                            state.import = None;
+                            self.set_contents(state, shared, chest);
+                        }
+                        Err(errors) => {
+                            import.errors = errors.iter().map(|e| e.to_string()).collect();
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        EventResult::Captured
+    }
+
+    fn open_counts_editor(
+        &self,
+        state: &mut BuriedTreasureTabState,
+        shared: &SharedApplicationState,
+    ) {
+        let (registry, language) = (item_registry(), LanguageTable::en_us());
+        let text = shared
+            .buried_treasure_data
+            .counts
+            .iter()
+            .map(|&(item, bound)| count_constraint_line(item, bound, &registry, &language))
+            .collect::<Vec<_>>()
+            .join("\n");
+        state.counts_editor = Some(ChestImportState {
+            text,
+            errors: Vec::new(),
+        });
+    }
+
+    fn handle_counts_event(
+        &self,
+        state: &mut BuriedTreasureTabState,
+        shared: &mut SharedApplicationState,
+        event: Event,
+    ) -> EventResult {
+        let Some(editor) = &mut state.counts_editor else {
+            return EventResult::BubbleUp(event);
+        };
+        if editor.edit(&event) {
+            return EventResult::Captured;
+        }
+
+        if let Event::Key(key) = &event {
+            if key.kind == KeyEventKind::Release {
+                return EventResult::Captured;
+            }
+            match key.code {
+                KeyCode::Esc => state.counts_editor = None,
+                KeyCode::Char('s' | 'S') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    match parse_count_constraints(
+                        &editor.text,
+                        &item_registry(),
+                        &LanguageTable::en_us(),
+                    ) {
+                        Ok(counts) => {
+                            state.counts_editor = None;
+                            shared.buried_treasure_data.usable |= !counts.is_empty();
+                            shared.buried_treasure_data.counts = counts;
+                        }
+                        Err(errors) => {
+                            editor.errors = errors.iter().map(|e| e.to_string()).collect();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        EventResult::Captured
+    }
+
+    fn set_contents(
+        &self,
+        state: &mut BuriedTreasureTabState,
+        shared: &mut SharedApplicationState,
+        chest: SingleChest,
+    ) {
+        show_contents(&mut state.contents, &chest);
+        shared.buried_treasure_data.contents = chest;
+        shared.buried_treasure_data.usable = true;
+    }
+}
+
+/// Shows the chest in the chest widget
+fn show_contents(contents: &mut ChestState, chest: &SingleChest) {
+    for (y, row) in chest.rows.iter().enumerate() {
+        for (x, stack) in row.items.iter().enumerate() {
+            contents.contents[y][x] = match stack {
+                Some(stack) => (
+                    item_to_string(stack.item).to_string(),
+                    stack.count,
+                    stack_style(stack.item, stack.count),
+                ),
+                None => ("".to_string(), 0, Style::default()),
+            };
+        }
+    }
+}
+
 impl Component for BuriedTreasureTabComponent {
     type State = BuriedTreasureTabState;
+    type Shared = SharedApplicationState;
 
     fn render(
         &self,
@@ -125,7 +532,7 @@ impl Component for BuriedTreasureTabComponent {
 
         let layout1 = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(12), Constraint::Min(0)]);
+            .constraints([Constraint::Length(13), Constraint::Min(0)]);
 
         let layout2 = Layout::default()
             .direction(Direction::Horizontal)
@@ -180,6 +587,35 @@ impl Component for BuriedTreasureTabComponent {
             Paragraph::new("[SHIFT + DEL]").style(Style::default().fg(Color::Magenta).not_bold());
         let l4_2 =
             Paragraph::new(" Delete all").style(Style::default().fg(Color::Green).not_bold());
+        let l8_1 =
+            Paragraph::new("[CTRL + O]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l8_2 = Paragraph::new(" Import from tooltips")
+            .style(Style::default().fg(Color::Green).not_bold());
+        let l9_1 =
+            Paragraph::new("[CTRL + K]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l9_2 =
+            Paragraph::new(" Item counts").style(Style::default().fg(Color::Green).not_bold());
+        let l10_1 =
+            Paragraph::new("[CTRL + A]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l10_2 = Paragraph::new(if shared.buried_treasure_data.area {
+            " Single chunk"
+        } else {
+            " Search area"
+        })
+        .style(Style::default().fg(Color::Green).not_bold());
+
+        let l11_1 =
+            Paragraph::new("[CTRL + N]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l11_2 =
+            Paragraph::new(" New treasure").style(Style::default().fg(Color::Green).not_bold());
+        let l12_1 =
+            Paragraph::new("[CTRL + E]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l12_2 =
+            Paragraph::new(" Next treasure").style(Style::default().fg(Color::Green).not_bold());
+        let l13_1 =
+            Paragraph::new("[CTRL + D]").style(Style::default().fg(Color::Magenta).not_bold());
+        let l13_2 =
+            Paragraph::new(" Drop treasure").style(Style::default().fg(Color::Green).not_bold());
 
         let l5 = Paragraph::new("Edit selection").style(Style::default().fg(Color::Yellow).bold());
         let l6_1 = Paragraph::new("[0] [1] [2] [3] [4] [5] [6] [7] [8] [9]")
@@ -209,6 +645,77 @@ impl Component for BuriedTreasureTabComponent {
             buf,
         );
 
+        let kept = shared.buried_treasure_data.kept.len();
+        if kept > 0 {
+            Paragraph::new(format!("+ {} kept", kept))
+                .style(Style::new().fg(Color::LightCyan))
+                .render(
+                    limit_area_height(controls_area, 1)
+                        .offset(Offset { x: 31, y: 0 })
+                        .intersection(controls_area),
+                    buf,
+                );
+        }
+
+        let invalid = match shared.invalid_positions().first() {
+            Some(invalid) => Some(format!("Invalid position: {}", invalid)),
+            None => shared
+                .invalid_observations()
+                .first()
+                .map(|invalid| format!("Invalid chest: {}", invalid))
+                .or_else(|| {
+                    shared
+                        .oversized_areas()
+                        .first()
+                        .map(|oversized| format!("Area too large: {}", oversized))
+                })
+                .or_else(|| {
+                    shared.invalid_count_bounds().first().map(|&(item, bound)| {
+                        format!(
+                            "Impossible count: {}",
+                            count_constraint_line(
+                                item,
+                                bound,
+                                &item_registry(),
+                                &LanguageTable::en_us()
+                            )
+                        )
+                    })
+                }),
+        };
+        let status = match invalid {
+            Some(invalid) => Some((invalid, Color::Red)),
+            None => shared
+                .treasure_area_cost()
+                .map(|cost| (format!("Area: {}", cost), Color::DarkGray)),
+        };
+        if let Some((status, color)) = status {
+            Paragraph::new(status).style(Style::new().fg(color)).render(
+                limit_area_height(controls_area, 1)
+                    .offset(Offset { x: 0, y: 1 })
+                    .intersection(controls_area),
+                buf,
+            );
+        }
+
+        if !shared.buried_treasure_data.counts.is_empty() {
+            let counts = shared
+                .buried_treasure_data
+                .counts
+                .iter()
+                .map(|&(item, bound)| format!("{} {}", item_to_string(item), bound))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Paragraph::new(format!("Item counts: {}", counts))
+                .style(Style::new().fg(Color::LightCyan))
+                .render(
+                    limit_area_height(controls_area, 1)
+                        .offset(Offset { x: 0, y: 2 })
+                        .intersection(controls_area),
+                    buf,
+                );
+        }
+
         let controls_area = limit_area_height(area, 1);
 
         l1.render(
@@ -239,6 +746,14 @@ impl Component for BuriedTreasureTabComponent {
             limit_area_width(controls_area, 11).offset(Offset { x: 13, y: 6 }),
             buf,
         );
+        l8_1.render(
+            limit_area_width(controls_area, 10).offset(Offset { x: 0, y: 7 }),
+            buf,
+        );
+        l8_2.render(
+            limit_area_width(controls_area, 21).offset(Offset { x: 10, y: 7 }),
+            buf,
+        );
         l5.render(
             limit_area_width(controls_area, 14).offset(Offset { x: 0, y: 8 }),
             buf,
@@ -259,8 +774,39 @@ impl Component for BuriedTreasureTabComponent {
             limit_area_width(controls_area, 12).offset(Offset { x: 17, y: 10 }),
             buf,
         );
+        l9_1.render(
+            limit_area_width(controls_area, 10).offset(Offset { x: 0, y: 11 }),
+            buf,
+        );
+        l9_2.render(
+            limit_area_width(controls_area, 12).offset(Offset { x: 10, y: 11 }),
+            buf,
+        );
+        l10_1.render(
+            limit_area_width(controls_area, 10).offset(Offset { x: 24, y: 11 }),
+            buf,
+        );
+        l10_2.render(
+            limit_area_width(controls_area, 13).offset(Offset { x: 34, y: 11 }),
+            buf,
+        );
 
-        let mut y = if vert { 0 } else { 12 };
+        for (x, y, key, action) in [
+            (31, 10, l13_1, l13_2),
+            (0, 12, l11_1, l11_2),
+            (24, 12, l12_1, l12_2),
+        ] {
+            key.render(
+                limit_area_width(controls_area, 10).offset(Offset { x, y }),
+                buf,
+            );
+            action.render(
+                limit_area_width(controls_area, 14).offset(Offset { x: x + 10, y }),
+                buf,
+            );
+        }
+
+        let mut y = if vert { 0 } else { 13 };
         Paragraph::new("Set item")
             .style(Style::default().fg(Color::Yellow).bold())
             .render(
@@ -311,47 +857,31 @@ impl Component for BuriedTreasureTabComponent {
         }
 
         if state.xstate.validator.is_none() {
-            state.xstate.validator = Some(Box::new(|value, _cursor, style, i| {
-                if value.len() > 15 {
-                    style.cursor_style.bg = Some(Color::Red);
-                    style.text_style.fg = Some(Color::Red);
-                } else if let Ok(n) = value.iter().collect::<String>().parse::<i32>() {
-                    style.text_style.fg = Some(Color::White);
-                    style.cursor_style.bg = Some(Color::Green);
-                    *i = n;
-                } else {
-                    style.cursor_style.bg = Some(Color::Red);
-                    style.text_style.fg = Some(Color::Red);
-                }
-            }));
+            state.xstate.validator = block_coord_validator();
         }
         if state.zstate.validator.is_none() {
-            state.zstate.validator = Some(Box::new(|value, _cursor, style, i| {
-                if value.len() > 15 {
-                    style.cursor_style.bg = Some(Color::Red);
-                    style.text_style.fg = Some(Color::Red);
-                } else if let Ok(n) = value.iter().collect::<String>().parse::<i32>() {
-                    style.text_style.fg = Some(Color::White);
-                    style.cursor_style.bg = Some(Color::Green);
-                    *i = n;
-                } else {
-                    style.cursor_style.bg = Some(Color::Red);
-                    style.text_style.fg = Some(Color::Red);
-                }
-            }));
+            state.zstate.validator = block_coord_validator();
+        }
+        if state.corner_xstate.validator.is_none() {
+            state.corner_xstate.validator = block_coord_validator();
+        }
+        if state.corner_zstate.validator.is_none() {
+            state.corner_zstate.validator = block_coord_validator();
         }
 
-        state.xstate.style.border_style = Style::default().fg(Color::White);
-        state.zstate.style.border_style = Style::default().fg(Color::White);
-        state.xstate.style.show_cursor = false;
-        state.zstate.style.show_cursor = false;
-
-        if state.focus == Focus::CoordX {
-            state.xstate.style.border_style = Style::default().fg(Color::LightCyan);
-            state.xstate.style.show_cursor = true;
-        } else if state.focus == Focus::CoordZ {
-            state.zstate.style.border_style = Style::default().fg(Color::LightCyan);
-            state.zstate.style.show_cursor = true;
+        for (input, focus) in [
+            (&mut state.xstate, Focus::CoordX),
+            (&mut state.zstate, Focus::CoordZ),
+            (&mut state.corner_xstate, Focus::AreaCornerX),
+            (&mut state.corner_zstate, Focus::AreaCornerZ),
+        ] {
+            let focused = state.focus == focus;
+            input.style.border_style = Style::default().fg(if focused {
+                Color::LightCyan
+            } else {
+                Color::White
+            });
+            input.style.show_cursor = focused;
         }
 
         if vert {
@@ -373,6 +903,19 @@ impl Component for BuriedTreasureTabComponent {
                 buf,
                 &mut state.zstate,
             );
+            if shared.buried_treasure_data.area {
+                for (y, input) in [(6, &mut state.corner_xstate), (9, &mut state.corner_zstate)] {
+                    TextInputWidget::default().render(
+                        limit_area_width(
+                            limit_area_height(area, 3),
+                            area.width.saturating_sub(82).min(30).max(10),
+                        )
+                        .offset(Offset { x: 80, y }),
+                        buf,
+                        input,
+                    );
+                }
+            }
         }
 
         if shared.buried_treasure_data.usable {
@@ -381,6 +924,18 @@ impl Component for BuriedTreasureTabComponent {
             state.contents.show_selected = state.focus == Focus::Chest;
 
             chest.render(contents_area, buf, &mut state.contents);
+        } else if shared.buried_treasure_data.contents != SingleChest::new() {
+            // disabled, the contents are kept for when it gets enabled again
+            state.contents.show_selected = false;
+            ChestWidget.render(contents_area, buf, &mut state.contents);
+            dim_area(contents_area, buf);
+        }
+
+        if let Some(import) = &state.import {
+            self.render_import_popup(area, buf, import);
+        }
+        if let Some(editor) = &state.counts_editor {
+            self.render_counts_popup(area, buf, editor);
         }
     }
 
@@ -392,25 +947,31 @@ impl Component for BuriedTreasureTabComponent {
         context: EventContext,
     ) -> EventResult {
         #[inline(always)]
-        const fn char_to_item(c: char) -> Option<(usize, Color)> {
+        const fn char_to_item(c: char) -> Option<usize> {
             match c {
-                'c' => Some((COOKED_COD, Color::Indexed(186))),
-                's' => Some((COOKED_SALMON, Color::Indexed(202))),
-                'd' => Some((DIAMOND, Color::LightCyan)),
-                'e' => Some((EMERALD, Color::Indexed(28))),
-                'g' => Some((GOLD_INGOT, Color::LightYellow)),
-                'h' => Some((HEART_OF_THE_SEA, Color::Blue)),
-                'i' => Some((IRON_INGOT, Color::DarkGray)),
-                'w' => Some((IRON_SWORD, Color::DarkGray)),
-                'l' => Some((LEATHER_CHESTPLATE, Color::Indexed(172))),
-                'p' => Some((PRISMARINE_CRYSTALS, Color::Indexed(79))),
-                't' => Some((TNT, Color::LightRed)),
+                'c' => Some(COOKED_COD),
+                's' => Some(COOKED_SALMON),
+                'd' => Some(DIAMOND),
+                'e' => Some(EMERALD),
+                'g' => Some(GOLD_INGOT),
+                'h' => Some(HEART_OF_THE_SEA),
+                'i' => Some(IRON_INGOT),
+                'w' => Some(IRON_SWORD),
+                'l' => Some(LEATHER_CHESTPLATE),
+                'p' => Some(PRISMARINE_CRYSTALS),
+                't' => Some(TNT),
                 _ => None,
             }
         }
 
         match context {
             EventContext::BubblingDown => {
+                if state.import.is_some() {
+                    return self.handle_import_event(state, shared, event);
+                }
+                if state.counts_editor.is_some() {
+                    return self.handle_counts_event(state, shared, event);
+                }
                 if state.focus == Focus::CoordX {
                     return match TextInputWidget::handle_event(
                         &mut state.xstate,
@@ -435,6 +996,30 @@ impl Component for BuriedTreasureTabComponent {
                         }
                         result => result,
                     };
+                } else if state.focus == Focus::AreaCornerX {
+                    return match TextInputWidget::handle_event(
+                        &mut state.corner_xstate,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.buried_treasure_data.area_corner_x,
+                    ) {
+                        EventResult::BubbleUp(event) => {
+                            self.handle_event(state, shared, event, EventContext::BubblingUp)
+                        }
+                        result => result,
+                    };
+                } else if state.focus == Focus::AreaCornerZ {
+                    return match TextInputWidget::handle_event(
+                        &mut state.corner_zstate,
+                        event,
+                        EventContext::BubblingDown,
+                        &mut shared.buried_treasure_data.area_corner_z,
+                    ) {
+                        EventResult::BubbleUp(event) => {
+                            self.handle_event(state, shared, event, EventContext::BubblingUp)
+                        }
+                        result => result,
+                    };
                 } else if state.focus == Focus::Outside {
                     return match &event {
                         Event::Key(key)
@@ -448,36 +1033,74 @@ impl Component for BuriedTreasureTabComponent {
                 }
                 match &event {
                     Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                        KeyCode::Char('o' | 'O')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            state.import = Some(ChestImportState::default());
+                            EventResult::Captured
+                        }
+                        KeyCode::Char('k' | 'K')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            self.open_counts_editor(state, shared);
+                            EventResult::Captured
+                        }
+                        KeyCode::Char('a' | 'A')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            let bt = &mut shared.buried_treasure_data;
+                            bt.area = !bt.area;
+                            if bt.area {
+                                // starts as the single chunk of the treasure
+                                (bt.area_corner_x, bt.area_corner_z) = (bt.pos_x, bt.pos_z);
+                                state.corner_xstate.show_number(&mut bt.area_corner_x);
+                                state.corner_zstate.show_number(&mut bt.area_corner_z);
+                            }
+                            EventResult::Captured
+                        }
+                        KeyCode::Char('n' | 'N')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            if shared.buried_treasure_data.keep_treasure() {
+                                self.sync_from_shared(state, shared);
+                            }
+                            EventResult::Captured
+                        }
+                        KeyCode::Char('e' | 'E')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            if shared.buried_treasure_data.next_treasure() {
+                                self.sync_from_shared(state, shared);
+                            }
+                            EventResult::Captured
+                        }
+                        KeyCode::Char('d' | 'D')
+                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            shared.buried_treasure_data.remove_treasure();
+                            self.sync_from_shared(state, shared);
+                            EventResult::Captured
+                        }
                         KeyCode::Char(' ') => {
                             shared.buried_treasure_data.usable =
                                 !shared.buried_treasure_data.usable;
                             EventResult::Captured
                         }
                         KeyCode::Char(c) if char_to_item(c.to_ascii_lowercase()).is_some() => {
-                            let (item, fg) = char_to_item(c.to_ascii_lowercase()).unwrap();
-
-                            state.contents.contents[state.contents.selected.1]
-                                [state.contents.selected.0] = (
+                            let item = char_to_item(c.to_ascii_lowercase()).unwrap();
+                            let cell = &mut state.contents.contents[state.contents.selected.1]
+                                [state.contents.selected.0];
+                            let count = cell.1.max(1);
+                            *cell = (
                                 item_to_string(item).to_string(),
-                                state.contents.contents[state.contents.selected.1]
-                                    [state.contents.selected.0]
-                                    .1
-                                    .max(1),
-                                Style::default().fg(fg).not_bold(),
+                                count,
+                                stack_style(item, count),
                             );
 
+                            let max_count = item_registry().by_id(item).map_or(64, |i| i.max_count);
                             shared.buried_treasure_data.contents.rows[state.contents.selected.1]
-                                .items[state.contents.selected.0] = Some(ItemStack::new(
-                                item,
-                                state.contents.contents[state.contents.selected.1]
-                                    [state.contents.selected.0]
-                                    .1,
-                                if item == IRON_SWORD || item == LEATHER_CHESTPLATE {
-                                    1
-                                } else {
-                                    64
-                                },
-                            ));
+                                .items[state.contents.selected.0] =
+                                Some(ItemStack::new(item, count, max_count));
 
                             EventResult::Captured
                         }
@@ -506,40 +1129,30 @@ impl Component for BuriedTreasureTabComponent {
                         KeyCode::Char(c)
                             if shared.buried_treasure_data.usable && c.is_ascii_digit() =>
                         {
-                            let count = &mut state.contents.contents[state.contents.selected.1]
-                                [state.contents.selected.0]
-                                .1;
+                            let cell = &mut state.contents.contents[state.contents.selected.1]
+                                [state.contents.selected.0];
 
-                            let res = (*count * 10 + c.to_digit(10).unwrap() as i32) % 100;
-                            *count = res;
+                            let res = (cell.1 * 10 + c.to_digit(10).unwrap() as i32) % 100;
+                            cell.1 = res;
 
                             if let Some(bt) = &mut shared.buried_treasure_data.contents.rows
                                 [state.contents.selected.1]
                                 .items[state.contents.selected.0]
                             {
                                 bt.count = res;
+                                cell.2 = stack_style(bt.item, res);
                             }
 
                             EventResult::Captured
                         }
-                        KeyCode::Tab => match state.focus {
-                            Focus::CoordX => {
-                                state.focus = Focus::CoordZ;
-                                EventResult::Captured
-                            }
-                            Focus::CoordZ => {
-                                state.focus = Focus::Chest;
-                                EventResult::Captured
-                            }
-                            Focus::Chest => {
-                                state.focus = Focus::Outside;
+                        KeyCode::Tab => {
+                            state.focus = state.focus.next(shared.buried_treasure_data.area);
+                            if state.focus == Focus::Outside {
                                 EventResult::BubbleUp(event)
-                            }
-                            Focus::Outside => {
-                                state.focus = Focus::CoordX;
+                            } else {
                                 EventResult::Captured
                             }
-                        },
+                        }
                         KeyCode::Backspace | KeyCode::Delete
                             if shared.buried_treasure_data.usable =>
                         {
@@ -571,23 +1184,11 @@ impl Component for BuriedTreasureTabComponent {
                 Event::Key(key)
                     if key.code == KeyCode::Tab && key.kind != KeyEventKind::Release =>
                 {
-                    match state.focus {
-                        Focus::CoordX => {
-                            state.focus = Focus::CoordZ;
-                            EventResult::Captured
-                        }
-                        Focus::CoordZ => {
-                            state.focus = Focus::Chest;
-                            EventResult::Captured
-                        }
-                        Focus::Chest => {
-                            state.focus = Focus::Outside;
-                            EventResult::BubbleUp(event)
-                        }
-                        Focus::Outside => {
-                            state.focus = Focus::CoordX;
-                            EventResult::Captured
-                        }
+                    state.focus = state.focus.next(shared.buried_treasure_data.area);
+                    if state.focus == Focus::Outside {
+                        EventResult::BubbleUp(event)
+                    } else {
+                        EventResult::Captured
                     }
                 }
                 _ => EventResult::BubbleUp(event),
@@ -602,4 +1203,170 @@ impl Component for BuriedTreasureTabComponent {
     fn on_unfocus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
         state.focus = Focus::Outside;
     }
+
+    fn apply_template(
+        &self,
+        state: &mut Self::State,
+        _shared: &mut SharedApplicationState,
+        template: &EvidenceTemplate,
+    ) {
+        for (id, label, input) in [
+            (FieldId::TreasureX, "Treasure X", &mut state.xstate),
+            (FieldId::TreasureZ, "Treasure Z", &mut state.zstate),
+        ] {
+            if template.is_required(id) {
+                input.style.title = format!("{} (i32) *", label);
+            }
+        }
+    }
+
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+        let bt = &mut shared.buried_treasure_data;
+        show_contents(&mut state.contents, &bt.contents);
+        state.xstate.show_number(&mut bt.pos_x);
+        state.zstate.show_number(&mut bt.pos_z);
+        state.corner_xstate.show_number(&mut bt.area_corner_x);
+        state.corner_zstate.show_number(&mut bt.area_corner_z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::self_test::{BURIED_TREASURE_POS, buried_treasure_contents};
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        layout::Rect,
+    };
+
+    use crate::tui::{
+        Component, EventContext,
+        application::{ApplicationComponentState, SharedApplicationState},
+        tabs::buried_treasure::{BuriedTreasureTabComponent, BuriedTreasureTabState, Focus},
+    };
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_area_corner_input() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BuriedTreasureTabState::default();
+        let tab = BuriedTreasureTabComponent;
+        let bt = &mut shared.buried_treasure_data;
+        (bt.pos_x, bt.pos_z) = BURIED_TREASURE_POS;
+        bt.contents = buried_treasure_contents();
+        bt.usable = true;
+        tab.sync_from_shared(&mut state, &mut shared);
+        let area = Rect::new(0, 0, 160, 40);
+        tab.render(area, &mut Buffer::empty(area), &mut state, &mut shared);
+
+        tab.on_focus(&mut state, &mut shared);
+
+        let mut send = |state: &mut BuriedTreasureTabState, event: Event| {
+            tab.handle_event(state, &mut shared, event, EventContext::BubblingDown);
+        };
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Tab));
+        assert_eq!(state.focus, Focus::Chest);
+        send(
+            &mut state,
+            Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL)),
+        );
+
+        // the corner inputs come after the treasure position
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Tab));
+        send(&mut state, key(KeyCode::Tab));
+        assert_eq!(state.focus, Focus::AreaCornerX);
+        for _ in 0..3 {
+            send(&mut state, key(KeyCode::Backspace));
+        }
+        for c in "352".chars() {
+            send(&mut state, key(KeyCode::Char(c)));
+        }
+        send(&mut state, key(KeyCode::Tab));
+        assert_eq!(state.focus, Focus::AreaCornerZ);
+        send(&mut state, key(KeyCode::Char('0')));
+
+        let evidence = shared.cracking_evidence();
+        let bt = &evidence.buried_treasures[0];
+        assert_eq!((bt.pos_x, bt.pos_z), BURIED_TREASURE_POS);
+        assert_eq!(bt.area_corner, Some((352, 8090)));
+        // 4 by 456 chunks
+        assert_eq!(shared.oversized_areas().len(), 1);
+        assert_eq!(shared.treasure_area_cost().map(|c| c.chunks), Some(1824));
+        shared.max_treasure_area = 4096;
+        assert!(shared.oversized_areas().is_empty());
+
+        // without an area, the corner inputs are skipped
+        shared.buried_treasure_data.area = false;
+        assert_eq!(
+            shared.cracking_evidence().buried_treasures[0].area_corner,
+            None
+        );
+        assert!(shared.oversized_areas().is_empty());
+        assert_eq!(shared.treasure_area_cost(), None);
+        state.focus = Focus::CoordZ;
+        tab.handle_event(
+            &mut state,
+            &mut shared,
+            key(KeyCode::Tab),
+            EventContext::BubblingDown,
+        );
+        assert_eq!(state.focus, Focus::Chest);
+    }
+
+    #[test]
+    fn test_several_treasures() {
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = BuriedTreasureTabState::default();
+        let tab = BuriedTreasureTabComponent;
+        let bt = &mut shared.buried_treasure_data;
+        (bt.pos_x, bt.pos_z) = BURIED_TREASURE_POS;
+        bt.contents = buried_treasure_contents();
+        bt.usable = true;
+        tab.sync_from_shared(&mut state, &mut shared);
+        state.focus = Focus::Chest;
+
+        let ctrl = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+        let send =
+            |state: &mut BuriedTreasureTabState, shared: &mut SharedApplicationState, event| {
+                tab.handle_event(state, shared, event, EventContext::BubblingDown);
+            };
+        send(&mut state, &mut shared, ctrl('n'));
+        // an empty chest can't be kept
+        send(&mut state, &mut shared, ctrl('n'));
+        send(&mut state, &mut shared, key(KeyCode::Char(' ')));
+        send(&mut state, &mut shared, key(KeyCode::Char('h')));
+        assert_eq!(state.contents.contents[0][0].0, "Heart of the Sea");
+
+        let positions = |shared: &SharedApplicationState| {
+            shared
+                .cracking_evidence()
+                .buried_treasures
+                .iter()
+                .map(|t| (t.pos_x, t.pos_z))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shared.buried_treasure_data.kept.len(), 1);
+        assert_eq!(positions(&shared), vec![(0, 0), BURIED_TREASURE_POS]);
+
+        // the edited chest goes after the others
+        send(&mut state, &mut shared, ctrl('e'));
+        assert_eq!(
+            shared.buried_treasure_data.contents,
+            buried_treasure_contents()
+        );
+        assert_eq!(state.contents.contents[0][0].0, "Cooked Cod");
+        assert_eq!(positions(&shared), vec![BURIED_TREASURE_POS, (0, 0)]);
+
+        send(&mut state, &mut shared, ctrl('d'));
+        assert_eq!(positions(&shared), vec![(0, 0)]);
+        send(&mut state, &mut shared, ctrl('d'));
+        assert!(shared.cracking_evidence().buried_treasures.is_empty());
+        assert!(shared.is_evidence_empty());
+    }
 }
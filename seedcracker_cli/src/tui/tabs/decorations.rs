@@ -0,0 +1,526 @@
+use mcseedcracker::{
+    features::decoration::DecorationKind,
+    pipeline::{CrackingEvidence, ObservedAt},
+    search::WorldExtraData,
+    templates::{EvidenceSection, EvidenceTemplate},
+};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Alignment, Constraint, Direction, Layout, Offset, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{
+    make_full_component,
+    tui::{
+        Component, EventContext, EventResult,
+        application::ApplicationTab,
+        components::text_input::{TextInputState, TextInputWidget, Validator, i32_validator},
+        dim_area, is_toggle_evidence_key, limit_area_height, limit_area_width,
+    },
+};
+
+const FIELDS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecorationEntry {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub surface_y: i32,
+    pub kind: Option<DecorationKind>,
+    pub observed_count: i32,
+    pub tolerance: i32,
+    /// Disabled entries are kept but left out of the evidence
+    pub enabled: bool,
+}
+
+impl Default for DecorationEntry {
+    fn default() -> Self {
+        Self {
+            chunk_x: 0,
+            chunk_z: 0,
+            surface_y: 63,
+            kind: None,
+            observed_count: 0,
+            tolerance: 1,
+            enabled: true,
+        }
+    }
+}
+
+impl DecorationEntry {
+    pub fn as_world_extra_data(&self) -> Option<WorldExtraData> {
+        let kind = self.kind?;
+        Some(WorldExtraData::DecorationCount {
+            chunk: (self.chunk_x, self.chunk_z),
+            decoration: kind,
+            feature_index: kind.default_feature_index(),
+            surface_y: self.surface_y,
+            observed_count: self.observed_count.max(0) as u32,
+            tolerance: self.tolerance.max(0) as u32,
+        })
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct DecorationsTabSharedData {
+    pub decorations: Vec<DecorationEntry>,
+    pub observed_at: Option<ObservedAt>,
+}
+
+impl DecorationsTabSharedData {
+    pub fn world_extra_data(&self) -> Vec<WorldExtraData> {
+        self.decorations
+            .iter()
+            .filter(|d| d.enabled)
+            .filter_map(|d| d.as_world_extra_data())
+            .collect()
+    }
+}
+
+pub struct DecorationInputs {
+    pub chunk_x: TextInputState<i32>,
+    pub chunk_z: TextInputState<i32>,
+    pub surface_y: TextInputState<i32>,
+    pub kind: TextInputState<Option<DecorationKind>>,
+    pub observed_count: TextInputState<i32>,
+    pub tolerance: TextInputState<i32>,
+}
+
+impl DecorationInputs {
+    fn new() -> Self {
+        let mut surface_y = TextInputState::new("Surface Y (i32)", i32_validator());
+        surface_y.value = "63".chars().collect();
+        surface_y.cursor = 2;
+        let mut tolerance = TextInputState::new("Tolerance", i32_validator());
+        tolerance.value = vec!['1'];
+        tolerance.cursor = 1;
+
+        Self {
+            chunk_x: TextInputState::new("Chunk X (i32)", i32_validator()),
+            chunk_z: TextInputState::new("Chunk Z (i32)", i32_validator()),
+            surface_y,
+            kind: TextInputState::new("sugar_cane/cactus", decoration_kind_validator()),
+            observed_count: TextInputState::new("Count", i32_validator()),
+            tolerance,
+        }
+    }
+
+    fn mark_required(&mut self) {
+        for style in [
+            &mut self.chunk_x.style,
+            &mut self.chunk_z.style,
+            &mut self.surface_y.style,
+            &mut self.kind.style,
+            &mut self.observed_count.style,
+            &mut self.tolerance.style,
+        ] {
+            style.title.push_str(" *");
+        }
+    }
+
+    fn set_cursor_visible(&mut self, selected: Option<usize>) {
+        self.chunk_x.style.show_cursor = selected == Some(0);
+        self.chunk_z.style.show_cursor = selected == Some(1);
+        self.surface_y.style.show_cursor = selected == Some(2);
+        self.kind.style.show_cursor = selected == Some(3);
+        self.observed_count.style.show_cursor = selected == Some(4);
+        self.tolerance.style.show_cursor = selected == Some(5);
+    }
+}
+
+#[derive(Default)]
+pub struct DecorationsTabState {
+    pub inputs: Vec<DecorationInputs>,
+    pub focused: bool,
+    pub selected_x: usize,
+    pub selected_y: usize,
+}
+
+#[derive(Default)]
+pub struct DecorationsTabComponent;
+
+make_full_component!(DecorationsTab, state: DecorationsTabState, component: DecorationsTabComponent);
+
+/// The enabled decoration counts, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    let world = shared.decoration_data.world_extra_data();
+    let usable = !world.is_empty();
+    evidence.world.extend(world);
+    evidence.decorations_observed_at = shared.decoration_data.observed_at.clone();
+    usable
+}
+
+impl DecorationsTab {
+    pub fn apptab() -> ApplicationTab<Self> {
+        ApplicationTab {
+            title: "Decorations".to_string(),
+            component: DecorationsTab::create(),
+        }
+    }
+}
+
+impl Component for DecorationsTabComponent {
+    type State = DecorationsTabState;
+    type Shared = SharedApplicationState;
+
+    fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+    ) {
+        let layoutvert = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(0)]);
+        let vert = layoutvert.split(area);
+
+        let controls_area = limit_area_height(vert[0], 1);
+        Paragraph::new("General controls")
+            .style(Style::default().fg(Color::Yellow).bold())
+            .render(limit_area_width(controls_area, 16), buf);
+        Paragraph::new("[CTRL + LEFT] [CTRL + RIGHT] [CTRL + UP] [CTRL + DOWN]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 54).offset(Offset { x: 0, y: 1 }),
+                buf,
+            );
+        Paragraph::new(" Change focused input")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 21).offset(Offset { x: 54, y: 1 }),
+                buf,
+            );
+        Paragraph::new("[CTRL + N]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 10).offset(Offset { x: 0, y: 2 }),
+                buf,
+            );
+        Paragraph::new(" Add new decoration count")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 25).offset(Offset { x: 10, y: 2 }),
+                buf,
+            );
+        Paragraph::new("[CTRL + DEL]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 12).offset(Offset { x: 0, y: 3 }),
+                buf,
+            );
+        Paragraph::new(" Delete selected line")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 21).offset(Offset { x: 12, y: 3 }),
+                buf,
+            );
+        Paragraph::new("[SPACE]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 7).offset(Offset { x: 0, y: 4 }),
+                buf,
+            );
+        Paragraph::new(" Enable / disable selected line")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 31).offset(Offset { x: 7, y: 4 }),
+                buf,
+            );
+        Paragraph::new(
+            "Counts of plant columns are only used to rank the last world seeds, as terrain can make placements fail",
+        )
+        .style(Style::default().fg(Color::White).italic())
+        .render(controls_area.offset(Offset { x: 0, y: 5 }), buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(if state.focused {
+                Style::default().fg(Color::LightCyan)
+            } else {
+                Style::default()
+            })
+            .title("Sugar cane / cactus counts (1.16.5)")
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(vert[1]);
+        block.render(vert[1], buf);
+
+        let row_areas = Layout::new(Direction::Horizontal, [Constraint::Fill(0); FIELDS])
+            .split(limit_area_height(inner, 3));
+        let mut row_areas = [
+            row_areas[0],
+            row_areas[1],
+            row_areas[2],
+            row_areas[3],
+            row_areas[4],
+            row_areas[5],
+        ];
+
+        for (i, inputs) in state.inputs.iter_mut().enumerate() {
+            if 3 * i + 3 > inner.height as usize {
+                break;
+            }
+
+            inputs.set_cursor_visible(if state.focused && state.selected_y == i {
+                Some(state.selected_x)
+            } else {
+                None
+            });
+
+            TextInputWidget::default().render(row_areas[0], buf, &mut inputs.chunk_x);
+            TextInputWidget::default().render(row_areas[1], buf, &mut inputs.chunk_z);
+            TextInputWidget::default().render(row_areas[2], buf, &mut inputs.surface_y);
+            TextInputWidget::default().render(row_areas[3], buf, &mut inputs.kind);
+            TextInputWidget::default().render(row_areas[4], buf, &mut inputs.observed_count);
+            TextInputWidget::default().render(row_areas[5], buf, &mut inputs.tolerance);
+            if shared
+                .decoration_data
+                .decorations
+                .get(i)
+                .is_some_and(|d| !d.enabled)
+            {
+                dim_area(Rect::new(inner.x, row_areas[0].y, inner.width, 3), buf);
+            }
+
+            for a in row_areas.iter_mut() {
+                a.y += 3;
+            }
+        }
+
+        if shared.decoration_data.decorations.is_empty() {
+            Paragraph::new("No decoration counts, press [CTRL + N] to add one")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(limit_area_height(inner, 1), buf);
+        }
+    }
+
+    fn handle_event(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        event: Event,
+        context: EventContext,
+    ) -> EventResult {
+        match context {
+            EventContext::BubblingUp => match event {
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Right =>
+                {
+                    state.selected_x = (state.selected_x + 1) % FIELDS;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Left =>
+                {
+                    state.selected_x = (state.selected_x + FIELDS - 1) % FIELDS;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && (key.code == KeyCode::Down || key.code == KeyCode::Up) =>
+                {
+                    let len = state.inputs.len();
+                    if len > 0 {
+                        state.selected_y = if key.code == KeyCode::Down {
+                            (state.selected_y + 1) % len
+                        } else {
+                            (state.selected_y + len - 1) % len
+                        };
+                    }
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && (key.code == KeyCode::Char('n') || key.code == KeyCode::Char('N')) =>
+                {
+                    state.inputs.push(DecorationInputs::new());
+                    shared
+                        .decoration_data
+                        .decorations
+                        .push(DecorationEntry::default());
+                    state.selected_y = state.inputs.len() - 1;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Delete =>
+                {
+                    if state.selected_y < state.inputs.len() {
+                        state.inputs.remove(state.selected_y);
+                    }
+                    if state.selected_y < shared.decoration_data.decorations.len() {
+                        shared.decoration_data.decorations.remove(state.selected_y);
+                    }
+                    state.selected_y = state.selected_y.min(state.inputs.len().saturating_sub(1));
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.kind != KeyEventKind::Release && key.code == KeyCode::Tab =>
+                {
+                    state.focused = !state.focused;
+                    if state.focused {
+                        EventResult::Captured
+                    } else {
+                        EventResult::BubbleUp(event)
+                    }
+                }
+                _ => EventResult::BubbleUp(event),
+            },
+            EventContext::BubblingDown => {
+                if !state.focused {
+                    return EventResult::BubbleUp(event);
+                }
+
+                if is_toggle_evidence_key(&event) {
+                    if let Some(data) = shared.decoration_data.decorations.get_mut(state.selected_y)
+                    {
+                        data.enabled = !data.enabled;
+                    }
+                    return EventResult::Captured;
+                }
+
+                if let (Some(inputs), Some(data)) = (
+                    state.inputs.get_mut(state.selected_y),
+                    shared.decoration_data.decorations.get_mut(state.selected_y),
+                ) {
+                    let event_result = match state.selected_x {
+                        0 => TextInputWidget::handle_event(
+                            &mut inputs.chunk_x,
+                            event,
+                            context,
+                            &mut data.chunk_x,
+                        ),
+                        1 => TextInputWidget::handle_event(
+                            &mut inputs.chunk_z,
+                            event,
+                            context,
+                            &mut data.chunk_z,
+                        ),
+                        2 => TextInputWidget::handle_event(
+                            &mut inputs.surface_y,
+                            event,
+                            context,
+                            &mut data.surface_y,
+                        ),
+                        3 => TextInputWidget::handle_event(
+                            &mut inputs.kind,
+                            event,
+                            context,
+                            &mut data.kind,
+                        ),
+                        4 => TextInputWidget::handle_event(
+                            &mut inputs.observed_count,
+                            event,
+                            context,
+                            &mut data.observed_count,
+                        ),
+                        5 => TextInputWidget::handle_event(
+                            &mut inputs.tolerance,
+                            event,
+                            context,
+                            &mut data.tolerance,
+                        ),
+                        _ => unreachable!(),
+                    };
+                    match event_result {
+                        EventResult::Captured => EventResult::Captured,
+                        EventResult::BubbleUp(event) => {
+                            self.handle_event(state, shared, event, EventContext::BubblingUp)
+                        }
+                    }
+                } else {
+                    self.handle_event(state, shared, event, EventContext::BubblingUp)
+                }
+            }
+        }
+    }
+
+    fn on_focus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focused = true;
+        state.selected_x = 0;
+        state.selected_y = 0;
+    }
+
+    fn on_unfocus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focused = false;
+        state.selected_x = 0;
+        state.selected_y = 0;
+    }
+
+    fn apply_template(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        template: &EvidenceTemplate,
+    ) {
+        for field in template.fields_in(EvidenceSection::Decorations) {
+            for _ in 0..field.rows {
+                let mut inputs = DecorationInputs::new();
+                if field.required {
+                    inputs.mark_required();
+                }
+                state.inputs.push(inputs);
+                shared
+                    .decoration_data
+                    .decorations
+                    .push(DecorationEntry::default());
+            }
+        }
+    }
+
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+        let decorations = &mut shared.decoration_data.decorations;
+        state.inputs.truncate(decorations.len());
+        while state.inputs.len() < decorations.len() {
+            state.inputs.push(DecorationInputs::new());
+        }
+        for (inputs, entry) in state.inputs.iter_mut().zip(decorations.iter_mut()) {
+            inputs.chunk_x.show_number(&mut entry.chunk_x);
+            inputs.chunk_z.show_number(&mut entry.chunk_z);
+            inputs.surface_y.show_number(&mut entry.surface_y);
+            inputs.observed_count.show_number(&mut entry.observed_count);
+            inputs.tolerance.show_number(&mut entry.tolerance);
+            if DecorationKind::from_name(&inputs.kind.value.iter().collect::<String>())
+                != entry.kind
+            {
+                let text = entry.kind.map_or("", |kind| kind.name());
+                inputs.kind.set_text(text, &mut entry.kind);
+            }
+        }
+        state.selected_y = state.selected_y.min(state.inputs.len().saturating_sub(1));
+    }
+}
+
+pub fn decoration_kind_validator() -> Validator<Option<DecorationKind>> {
+    Some(Box::new(|text, _, style, kind| {
+        let text = text.iter().collect::<String>();
+        *kind = DecorationKind::from_name(&text);
+        if kind.is_some() {
+            style.text_style.fg = Some(Color::Green);
+            style.cursor_style.bg = Some(Color::Green);
+        } else if ["sugar_cane", "cactus"]
+            .iter()
+            .any(|n| n.starts_with(&text))
+        {
+            style.text_style.fg = Some(Color::White);
+            style.cursor_style.bg = Some(Color::White);
+        } else {
+            style.text_style.fg = Some(Color::Red);
+            style.cursor_style.bg = Some(Color::Red);
+        }
+    }))
+}
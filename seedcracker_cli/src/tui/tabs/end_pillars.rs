@@ -2,14 +2,15 @@ use std::f64::consts::{FRAC_PI_2, PI};
 
 use mcseedcracker::{
     CHARACTER_ASPECT_RATIO,
-    features::end_pillars::{PillarHeightHint, PillarMatchResult},
+    features::end_pillars::{PartialEndPillars, PillarHeightHint},
+    pipeline::CrackingEvidence,
 };
 
 use crate::{
     make_full_component,
     tui::{
-        Component, EventContext, EventResult, application::ApplicationTab, limit_area_height,
-        limit_area_width,
+        Component, EventContext, EventResult, application::ApplicationTab, dim_area,
+        is_toggle_evidence_key, limit_area_height, limit_area_width,
     },
 };
 
@@ -42,6 +43,16 @@ pub struct EndPillarsTabComponent;
 
 make_full_component!(EndPillarsTab, state: EndPillarsTabState, component: EndPillarsTabComponent);
 
+/// The pillar observation, usable once a pillar is known, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    evidence.pillars = shared.enabled_pillar_data();
+    evidence.pillars_observed_at = shared.pillars_observed_at.clone();
+    evidence.pillars != PartialEndPillars::new()
+}
+
 impl EndPillarsTab {
     pub fn apptab() -> ApplicationTab<Self> {
         ApplicationTab {
@@ -63,6 +74,7 @@ macro_rules! selected {
 
 impl Component for EndPillarsTabComponent {
     type State = EndPillarsTabState;
+    type Shared = SharedApplicationState;
 
     fn render(
         &self,
@@ -71,30 +83,25 @@ impl Component for EndPillarsTabComponent {
         state: &mut Self::State,
         shared: &mut SharedApplicationState,
     ) {
-        let seed_results = if matches!(shared.last_pillar_sim.as_ref(), Some((p, _)) if p == &shared.pillar_data)
-        {
-            shared.last_structure_seed_sim.outdated_data = true;
-            &shared.last_pillar_sim.as_ref().unwrap().1
-        } else {
-            shared.last_pillar_sim = Some((
-                shared.pillar_data,
-                shared
-                    .pillar_data
-                    .seed_results()
-                    .into_iter()
-                    .filter(|(_, result)| match result {
-                        PillarMatchResult::ImpossibleMatch => false,
-                        PillarMatchResult::PossibleMatch(v) => *v != 0.0,
-                        _ => true,
-                    })
-                    .collect::<Vec<_>>(),
-            ));
-            &shared.last_pillar_sim.as_ref().unwrap().1
-        };
-
-        let title = Paragraph::new(format!("Valid pillar seeds count: {}", seed_results.len()))
-            .style(Style::default().fg(Color::Yellow))
-            .alignment(Alignment::Center);
+        let invalid = shared.enabled_pillar_data().validate().err();
+        let pillars_enabled = shared.pillars_enabled;
+        let seed_results = shared.pillar_sim();
+
+        let title = match invalid {
+            Some(invalid) => Paragraph::new(format!("Invalid pillar info: {}", invalid))
+                .style(Style::default().fg(Color::Red)),
+            None => Paragraph::new(format!(
+                "Valid pillar seeds count: {}{}",
+                seed_results.len(),
+                if pillars_enabled {
+                    ""
+                } else {
+                    " (pillar evidence disabled)"
+                }
+            ))
+            .style(Style::default().fg(Color::Yellow)),
+        }
+        .alignment(Alignment::Center);
 
         title.render(area, buf);
 
@@ -149,6 +156,10 @@ impl Component for EndPillarsTabComponent {
             let l11_1 = Paragraph::new("[R]").style(Style::default().fg(Color::Magenta).not_bold());
             let l11_2 = Paragraph::new(" Wait for height range")
                 .style(Style::default().fg(Color::Green).not_bold());
+            let l15_1 =
+                Paragraph::new("[SPACE]").style(Style::default().fg(Color::Magenta).not_bold());
+            let l15_2 = Paragraph::new(" No pillar selected: enable / disable")
+                .style(Style::default().fg(Color::Green).not_bold());
 
             let l12 = Paragraph::new("[0] [1] [2] [3] [4] [5] [6] [7] [8] [9]")
                 .style(Style::default().fg(Color::Yellow).bold());
@@ -191,6 +202,9 @@ impl Component for EndPillarsTabComponent {
             larea.y += 1;
             l11_1.render(larea, buf);
             l11_2.render(larea.offset(Offset { x: 3, y: 0 }), buf);
+            larea.y += 1;
+            l15_1.render(larea, buf);
+            l15_2.render(larea.offset(Offset { x: 7, y: 0 }), buf);
             larea.y += 2;
             l12.render(larea, buf);
             larea.y += 1;
@@ -343,6 +357,10 @@ impl Component for EndPillarsTabComponent {
 
             height_text.render(inner_area, buf);
         }
+
+        if !shared.pillars_enabled {
+            dim_area(area, buf);
+        }
     }
 
     fn handle_event(
@@ -354,6 +372,11 @@ impl Component for EndPillarsTabComponent {
     ) -> EventResult {
         match context {
             EventContext::BubblingDown => match &event {
+                _ if state.focused_on_pillar.is_none() && is_toggle_evidence_key(&event) => {
+                    shared.pillars_enabled = !shared.pillars_enabled;
+                    state.waiting = WaitingOf::Nothing;
+                    EventResult::Captured
+                }
                 Event::Key(key)
                     if matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
                         && key.code == KeyCode::Tab =>
@@ -1,4 +1,7 @@
+pub mod bastion;
 pub mod biomes;
 pub mod buried_treasure;
+pub mod decorations;
 pub mod end_pillars;
 pub mod output;
+pub mod structures;
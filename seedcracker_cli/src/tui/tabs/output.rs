@@ -1,19 +1,45 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Range,
+    thread,
+    time::Duration,
+};
+
 use mcseedcracker::{
+    analysis::{DescribeRequest, FeatureKind, WorldDescriber, WorldReport},
+    calibration::{Calibration, CalibrationWorkload, SystemClock},
+    candidates::{CandidateScore, WorldSeedOrigin},
+    cooperative::SearchOptions,
+    cubiomes::enums::MCVersion,
+    export::{
+        SeedExportOptions, SeedNumberFormat, write_structure_seed_completions, write_world_seeds,
+    },
     features::{
-        buried_treasure::build_fast_inventory_compare_context, end_pillars::PillarMatchResult,
+        buried_treasure,
+        end_pillars::{EndPillars, PillarMatchResult},
     },
+    fingerprint::EvidenceFingerprint,
+    heartbeat::STALL_THRESHOLD,
     math::Math,
-    search::{StructureData, StructureSeedSearchData, WorldSeedSearchData},
+    pipeline::{CrackingPipeline, EarlyHits},
+    rejections::RejectionStats,
+    tooltip::{ChestDump, LanguageTable},
+    utils::format::{format_count, format_duration, format_rate},
+    view::{format_progress, progress_percent, valid_pillar_seeds},
+    world_seed::SeedOrigin,
 };
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{Event, KeyCode, KeyEventKind},
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind},
     layout::{Alignment, Constraint, Direction, Layout, Offset, Rect},
     style::{Color, Style, Stylize},
-    widgets::{Block, Borders, Gauge, Paragraph, Widget},
+    text::Line,
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
 use crate::{
+    emit::protocol_commands,
     make_full_component,
     tui::{
         Component, EventContext, EventResult,
@@ -21,7 +47,11 @@ use crate::{
             ApplicationTab, StructureSeedSimData, StructureSeedSimResultType, WorldSeedSimData,
             WorldSeedSimResultType,
         },
-        get_area_centered, limit_area_height, limit_area_width,
+        components::tree_list::{TreeListState, TreeListWidget, TreeSource},
+        get_area_centered,
+        hitmap::HitTarget,
+        job_queue::{JobQueuePopup, QueueKind},
+        limit_area_height, limit_area_width,
     },
 };
 
@@ -38,6 +68,448 @@ pub enum Focus {
 pub struct OutputTabState {
     pub focus: Focus,
     pub valid_pillar_count: usize,
+    pub export_status: Option<(String, bool)>,
+    /// Rendered search plan, shown in a popup until the next key press
+    pub plan: Option<String>,
+    /// The world description shown in the popup, if it is the one shown
+    pub report: Option<WorldReport>,
+    /// World seeds grouped by structure seed
+    pub world_tree: TreeListState<i64>,
+    /// Structure seeds grouped by pillar seed
+    pub structure_tree: TreeListState<i64>,
+    /// First pillar seed shown, scrolled with [PGUP] / [PGDN]
+    pub pillar_offset: usize,
+    /// Rows of the pillar seed list in the last render, the scrolling page
+    pub pillar_rows: usize,
+    /// The pending searches, listed until the popup is closed
+    pub jobs: Option<JobQueuePopup>,
+}
+
+/// What a result panel shows, see [`pillar_panel_state`], [`structure_panel_state`] and [`world_panel_state`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PanelState {
+    /// Nothing was searched yet
+    NotRun,
+    Running,
+    /// The search finished without finding anything
+    ZeroResults,
+    Results,
+    /// The evidence changed since the results were found
+    Stale,
+    /// The evidence can't be searched
+    Error(String),
+}
+
+impl PanelState {
+    /// The line shown for the state in a panel listing `what`, `None` for [`PanelState::Results`]
+    pub fn message(&self, what: &str) -> Option<(String, Color)> {
+        match self {
+            PanelState::NotRun => Some((format!("No {} searched yet", what), Color::Gray)),
+            PanelState::Running => Some((format!("Searching {}...", what), Color::Yellow)),
+            PanelState::ZeroResults => Some((
+                format!("Search finished, no {} match the evidence", what),
+                Color::Red,
+            )),
+            PanelState::Results => None,
+            PanelState::Stale => Some((
+                "The evidence changed, search again to update these".to_string(),
+                Color::Yellow,
+            )),
+            PanelState::Error(error) => Some((format!("Can't search: {}", error), Color::Red)),
+        }
+    }
+
+    /// The results of the previous search are still listed under the message
+    #[inline]
+    pub const fn keeps_results(&self) -> bool {
+        matches!(
+            self,
+            PanelState::Results | PanelState::Stale | PanelState::Running
+        )
+    }
+}
+
+/// The pillar seeds are computed as the pillars are edited, they are never running or stale
+pub fn pillar_panel_state(invalid: Option<String>, valid_count: usize) -> PanelState {
+    match (invalid, valid_count) {
+        (Some(invalid), _) => PanelState::Error(invalid),
+        (None, 0) => PanelState::ZeroResults,
+        (None, _) => PanelState::Results,
+    }
+}
+
+/// `running` if a search is running or queued, `error` if the current evidence can't be searched
+pub fn structure_panel_state(
+    sim: &StructureSeedSimData,
+    running: bool,
+    error: Option<String>,
+) -> PanelState {
+    match (&sim.data, error) {
+        _ if running => PanelState::Running,
+        (_, Some(error)) => PanelState::Error(error),
+        (None, None) => PanelState::NotRun,
+        (Some(_), None) if sim.outdated_data => PanelState::Stale,
+        (Some(data), None) if data.count_seeds == 0 => PanelState::ZeroResults,
+        (Some(_), None) => PanelState::Results,
+    }
+}
+
+/// `stale` if the world seeds were searched from other structure seeds or other evidence, `early_hits` the typed
+/// world seeds found before the search
+pub fn world_panel_state(
+    sim: &WorldSeedSimData,
+    running: bool,
+    stale: bool,
+    early_hits: usize,
+    error: Option<String>,
+) -> PanelState {
+    match (&sim.started_from, error) {
+        _ if running => PanelState::Running,
+        (_, Some(error)) => PanelState::Error(error),
+        (None, None) if early_hits == 0 => PanelState::NotRun,
+        (None, None) => PanelState::Results,
+        (Some(_), None) if stale => PanelState::Stale,
+        (Some(_), None) if sim.count_seeds == 0 && early_hits == 0 => PanelState::ZeroResults,
+        (Some(_), None) => PanelState::Results,
+    }
+}
+
+/// The rows of a list of `total` rows shown in `height` lines starting at `offset`, and the number of rows left out.
+/// When some don't fit the last line is kept for a footer.
+pub fn visible_rows(total: usize, offset: usize, height: usize) -> (Range<usize>, usize) {
+    if total <= height {
+        return (0..total, 0);
+    }
+    let shown = height.saturating_sub(1);
+    let offset = offset.min(total - shown);
+    (offset..offset + shown, total - shown)
+}
+
+/// The message line of a panel state, returns the number of lines used
+fn render_panel_message(
+    area: Rect,
+    buf: &mut Buffer,
+    y: i32,
+    state: &PanelState,
+    what: &str,
+) -> i32 {
+    match state.message(what) {
+        Some((message, color)) => {
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(color).bold())
+                .render(limit_area_height(area, 1).offset(Offset { x: 0, y }), buf);
+            1
+        }
+        None => 0,
+    }
+}
+
+struct StructureSeedTree<'a>(&'a StructureSeedSimData);
+
+impl TreeSource for StructureSeedTree<'_> {
+    type Key = i64;
+
+    fn group_count(&self) -> usize {
+        self.0.per_pillar.len()
+    }
+
+    fn group_key(&self, group: usize) -> i64 {
+        self.0.per_pillar[group].pillar_seed
+    }
+
+    fn child_count(&self, group: usize) -> usize {
+        self.0.per_pillar[group].structure_seeds.len()
+    }
+
+    fn header(&self, group: usize) -> Line<'_> {
+        let sim = &self.0.per_pillar[group];
+        let result = match sim.result {
+            StructureSeedSimResultType::Success => "(success)".green(),
+            StructureSeedSimResultType::TooManySeeds => "(too many seeds, search stopped)".yellow(),
+            StructureSeedSimResultType::Cancelled => "(search cancelled)".red(),
+            StructureSeedSimResultType::Skipped(policy) => {
+                format!("(skipped, early termination: {})", policy).dark_gray()
+            }
+            StructureSeedSimResultType::Removed => "(removed from the queue)".dark_gray(),
+        };
+        let mut line = Line::from(vec![
+            format!("Pillar seed {} ", sim.pillar_seed).light_yellow(),
+            result,
+        ])
+        .bold();
+        if sim.rejections.checked > 0 {
+            line.push_span(format!(" {}", sim.rejections).dark_gray().not_bold());
+        }
+        line
+    }
+
+    fn child(&self, group: usize, child: usize) -> Line<'_> {
+        let candidate = &self.0.per_pillar[group].structure_seeds[child];
+        let score = &candidate.score;
+        let mut line = Line::from(format!("{:<16}", candidate.seed).fg(score_color(score)));
+        if score.pillar_chance < 1.0 {
+            line.push_span(format!("pillars {:>3.0}% ", score.pillar_chance * 100.0).dark_gray());
+        }
+        if !score.chest_exact {
+            line.push_span("chest not exact".dark_gray());
+        }
+        line
+    }
+}
+
+/// Green for the seeds whose pillars and chests match exactly, otherwise by the chance of their pillars like in the
+/// pillar seed list
+fn score_color(score: &CandidateScore) -> Color {
+    if score.chest_exact && score.pillar_chance >= 1.0 {
+        Color::Green
+    } else if score.pillar_chance > 0.25 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+struct WorldSeedTree<'a>(&'a WorldSeedSimData);
+
+impl TreeSource for WorldSeedTree<'_> {
+    type Key = i64;
+
+    fn group_count(&self) -> usize {
+        self.0.per_structure.len()
+    }
+
+    fn group_key(&self, group: usize) -> i64 {
+        self.0.per_structure[group].structure_seed
+    }
+
+    fn child_count(&self, group: usize) -> usize {
+        self.0.per_structure[group].world_seeds.len()
+    }
+
+    fn header(&self, group: usize) -> Line<'_> {
+        let sim = &self.0.per_structure[group];
+        let mut line = Line::from(vec![
+            format!("Structure seed {} ", sim.structure_seed)
+                .light_yellow()
+                .bold(),
+            match sim.result {
+                WorldSeedSimResultType::Success => {
+                    format!("({})", sim.world_seeds.len()).green().bold()
+                }
+                WorldSeedSimResultType::TooManySeeds => {
+                    format!("({} + more)", sim.world_seeds.len())
+                        .yellow()
+                        .bold()
+                }
+                WorldSeedSimResultType::Removed => "(removed from the queue)".dark_gray(),
+            },
+        ]);
+        if sim.skipped_candidates > 0 {
+            line.push_span(format!(" {} skipped (errors)", sim.skipped_candidates).red());
+        }
+        if sim.rejections.checked > 0 {
+            line.push_span(format!(" {}", sim.rejections).dark_gray());
+        }
+        line
+    }
+
+    fn child(&self, group: usize, child: usize) -> Line<'_> {
+        let sim = &self.0.per_structure[group];
+        let candidate = &sim.world_seeds[child];
+        let mut line = Line::from(format!("{:<21}", candidate.seed).green());
+        // how far inside their biomes the samples are, low when they are at an edge
+        if let Some(fit) = sim.fits.get(child).filter(|f| !f.margins.is_empty()) {
+            let score = fit.score();
+            line.push_span(format!("fit {:>3.0}%", score * 100.0).fg(if score >= 0.5 {
+                Color::Green
+            } else {
+                Color::Yellow
+            }));
+        }
+        // a seed in the int range could have been typed as a number or as a text
+        let origins = SeedOrigin::possible(candidate.seed);
+        if !origins.is_empty() {
+            let names = origins.iter().map(|o| o.name()).collect::<Vec<_>>();
+            line.push_span(format!(" ({})", names.join(", ")).light_blue());
+        }
+        line
+    }
+}
+
+/// Radius in blocks of the evidence listed by [V]
+const DESCRIBE_RADIUS: i32 = 512;
+
+const WORLD_SEEDS_EXPORT_PATH: &str = "world_seeds.txt";
+const STRUCTURE_SEEDS_EXPORT_PATH: &str = "structure_seeds.txt";
+const LOOT_EXPORT_PATH: &str = "buried_treasure_loot.txt";
+const COMMANDS_EXPORT_PATH: &str = "protocol_commands.txt";
+
+/// Under the progress gauge, see [`mcseedcracker::heartbeat`]
+fn render_stall_warning(area: Rect, buf: &mut Buffer, since: Duration) {
+    Paragraph::new(format!("(no progress for {})", format_duration(since)))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow))
+        .render(
+            limit_area_height(area, 1).offset(Offset { x: 0, y: 5 }),
+            buf,
+        );
+}
+
+/// Under the progress gauge, in place of the stall warning
+fn render_rejections(area: Rect, buf: &mut Buffer, rejections: &RejectionStats) {
+    if rejections.checked == 0 {
+        return;
+    }
+    Paragraph::new(rejections.to_string())
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::DarkGray))
+        .render(
+            limit_area_height(area, 1).offset(Offset { x: 0, y: 5 }),
+            buf,
+        );
+}
+
+/// Names the evidence a search started with, nothing if there is nothing to search
+fn searching_status<T>(jobs: &[T], usable: &[&str]) -> Option<(String, bool)> {
+    if jobs.is_empty() || usable.is_empty() {
+        return None;
+    }
+    Some((
+        format!(
+            "Searching {} job(s) with the {} evidence",
+            jobs.len(),
+            usable.join(", ")
+        ),
+        true,
+    ))
+}
+
+/// Measures and stores the calibration, returns the status line telling how it went
+fn calibrate(search: &SearchOptions) -> (String, bool) {
+    let calibration = match Calibration::measure(
+        &SystemClock::new(),
+        &CalibrationWorkload::standard(),
+        search,
+    ) {
+        Ok(calibration) => calibration,
+        Err(err) => return (format!("Calibration failed: {}", err), false),
+    };
+    match calibration.store() {
+        Ok(path) => (
+            format!(
+                "Calibrated: {} structure, {} world, saved to {}",
+                format_rate(calibration.structure_seeds_per_second, "seeds"),
+                format_rate(calibration.world_seeds_per_second, "seeds"),
+                path.display()
+            ),
+            true,
+        ),
+        Err(err) => (format!("Calibration not saved: {}", err), false),
+    }
+}
+
+/// Writes the commands to [`COMMANDS_EXPORT_PATH`] and returns the text of the popup showing them
+fn export_protocol_commands(shared: &SharedApplicationState) -> std::io::Result<String> {
+    let script = protocol_commands(&shared.cracking_evidence())?;
+    let mut file = BufWriter::new(File::create(COMMANDS_EXPORT_PATH)?);
+    file.write_all(script.to_text().as_bytes())?;
+    file.flush()?;
+
+    let mut text = format!(
+        "Paste into the protocol server (saved to {}):\n\n{}",
+        COMMANDS_EXPORT_PATH,
+        script.to_text()
+    );
+    if !script.skipped.is_empty() {
+        text.push_str(&format!(
+            "\nThe server can't take {} yet",
+            script.skipped.join(", ")
+        ));
+    }
+    Ok(text)
+}
+
+fn export_world_seeds(shared: &SharedApplicationState) -> std::io::Result<usize> {
+    let mut seeds = Vec::new();
+    for sim in shared.world_seed_sim.per_structure.iter() {
+        let pillar_seed = shared.last_structure_seed_sim.data.as_ref().and_then(|s| {
+            s.per_pillar
+                .iter()
+                .find(|p| {
+                    p.structure_seeds
+                        .iter()
+                        .any(|c| c.seed == sim.structure_seed)
+                })
+                .map(|p| p.pillar_seed)
+        });
+        let comment = match pillar_seed {
+            Some(pillar_seed) => format!(
+                "pillar seed {}, structure seed {}",
+                pillar_seed, sim.structure_seed
+            ),
+            None => format!("structure seed {}", sim.structure_seed),
+        };
+        for candidate in sim.world_seeds.iter() {
+            seeds.push(match candidate.origin {
+                WorldSeedOrigin::Random => (candidate.seed, comment.clone()),
+                origin => (candidate.seed, format!("{}, {}", comment, origin.name())),
+            });
+        }
+    }
+
+    let mut file = BufWriter::new(File::create(WORLD_SEEDS_EXPORT_PATH)?);
+    write_world_seeds(
+        &mut file,
+        seeds
+            .iter()
+            .map(|(seed, comment)| (*seed, Some(comment.as_str()))),
+        &SeedExportOptions::new(true, SeedNumberFormat::Decimal),
+    )?;
+    file.flush()?;
+    Ok(seeds.len())
+}
+
+fn export_structure_seeds(shared: &SharedApplicationState) -> std::io::Result<usize> {
+    let mut file = BufWriter::new(File::create(STRUCTURE_SEEDS_EXPORT_PATH)?);
+    let mut count = 0;
+    if let Some(sim) = &shared.last_structure_seed_sim.data {
+        for candidate in sim.per_pillar.iter().flat_map(|p| p.structure_seeds.iter()) {
+            write_structure_seed_completions(
+                &mut file,
+                candidate.seed,
+                &SeedExportOptions::new(true, SeedNumberFormat::Decimal),
+            )?;
+            count += 1;
+        }
+    }
+    file.flush()?;
+    Ok(count)
+}
+
+/// Predicted contents of the buried treasures of the report, with the `/give` commands to try them in a copy of the
+/// world
+fn export_buried_treasure_loot(report: &WorldReport, luck: f32) -> std::io::Result<usize> {
+    let (registry, language) = (buried_treasure::item_registry(), LanguageTable::en_us());
+    let mut file = BufWriter::new(File::create(LOOT_EXPORT_PATH)?);
+    let mut count = 0;
+    for feature in report.features_of(FeatureKind::BuriedTreasure) {
+        let chunk = Math::block_coords_to_chunk_coords((feature.block_x, feature.block_z));
+        let chest = buried_treasure::get_buried_treasure(report.world_seed, chunk, luck);
+        writeln!(
+            file,
+            "# Buried treasure at ({}, {}) in world seed {}",
+            feature.block_x, feature.block_z, report.world_seed
+        )?;
+        writeln!(
+            file,
+            "{}",
+            ChestDump::new(&chest, &registry, &language).with_give_commands(true)
+        )?;
+        count += 1;
+    }
+    file.flush()?;
+    Ok(count)
 }
 
 #[derive(Default)]
@@ -54,18 +526,6 @@ impl OutputTab {
     }
 }
 
-#[inline(always)]
-fn format_progress(mut value: f64) -> String {
-    if value > 100.0 {
-        value = 100.0;
-    }
-    if (value - 100.0).abs() < f64::EPSILON {
-        "100.0".to_string()
-    } else {
-        format!("{:05.2}", value)
-    }
-}
-
 impl OutputTabComponent {
     fn render_pillars(
         &self,
@@ -74,30 +534,8 @@ impl OutputTabComponent {
         state: &mut <Self as Component>::State,
         shared: &mut SharedApplicationState,
     ) {
-        let seed_results = if matches!(shared.last_pillar_sim.as_ref(), Some((p, _)) if p == &shared.pillar_data)
-        {
-            &shared.last_pillar_sim.as_ref().unwrap().1
-        } else {
-            shared.last_pillar_sim = Some((
-                shared.pillar_data,
-                shared
-                    .pillar_data
-                    .seed_results()
-                    .into_iter()
-                    .filter(|(_, result)| match result {
-                        PillarMatchResult::ImpossibleMatch => false,
-                        PillarMatchResult::PossibleMatch(v) => *v != 0.0,
-                        _ => true,
-                    })
-                    .collect::<Vec<_>>(),
-            ));
-            &shared.last_pillar_sim.as_ref().unwrap().1
-        };
-
-        let valid_count = seed_results
-            .iter()
-            .filter(|(_, result)| !result.is_impossible_match())
-            .count();
+        let valid = valid_pillar_seeds(shared.pillar_sim());
+        let valid_count = valid.len();
         state.valid_pillar_count = valid_count;
 
         let title =
@@ -120,59 +558,102 @@ impl OutputTabComponent {
             buf,
         );
 
-        if valid_count < area.height as usize - 2 {
-            let mut sorted = seed_results
-                .iter()
-                .filter(|(_, result)| !result.is_impossible_match())
-                .copied()
-                .collect::<Vec<_>>();
-            sorted.sort_by(|a, b| b.1.compare(&a.1));
-
-            for (i, (seed, result)) in sorted.into_iter().enumerate() {
-                let seed_str = format!("{}", seed);
-                match result {
-                    PillarMatchResult::ExactMatch => {
-                        Paragraph::new(seed_str)
-                            .style(Style::default().fg(Color::Green))
-                            .render(
-                                limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
-                                    x: 0,
-                                    y: i as i32 + 2,
-                                }),
-                                buf,
-                            );
-                    }
-                    PillarMatchResult::PossibleMatch(v) => {
-                        let prob_str = format!("{:.2}%", v * 100.0);
-                        Paragraph::new(seed_str)
-                            .style(Style::default().fg(if v > 0.75 {
-                                Color::Green
-                            } else if v > 0.25 {
-                                Color::Yellow
-                            } else {
-                                Color::Red
-                            }))
-                            .render(
-                                limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
-                                    x: 0,
-                                    y: i as i32 + 2,
-                                }),
-                                buf,
-                            );
-                        Paragraph::new(prob_str)
-                            .style(Style::default().fg(Color::Yellow))
-                            .render(
-                                limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
-                                    x: 25,
-                                    y: i as i32 + 2,
-                                }),
-                                buf,
-                            );
+        let invalid = shared
+            .enabled_pillar_data()
+            .validate()
+            .err()
+            .map(|invalid| format!("invalid pillar info: {}", invalid));
+        let panel = pillar_panel_state(invalid, valid_count);
+        if render_panel_message(area, buf, 2, &panel, "pillar seeds") > 0 {
+            return;
+        }
+
+        let height = (area.height as usize).saturating_sub(2);
+        let (rows, hidden) = visible_rows(valid_count, state.pillar_offset, height);
+        state.pillar_offset = rows.start;
+        state.pillar_rows = rows.len();
+        let observed = shared.enabled_pillar_data();
+        let mut pillars = EndPillars::new();
+        for (i, (seed, result)) in valid
+            .into_iter()
+            .skip(rows.start)
+            .take(rows.len())
+            .enumerate()
+        {
+            let seed_str = format!("{}", seed);
+            match result {
+                PillarMatchResult::ExactMatch => {
+                    Paragraph::new(seed_str)
+                        .style(Style::default().fg(Color::Green))
+                        .render(
+                            limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
+                                x: 0,
+                                y: i as i32 + 2,
+                            }),
+                            buf,
+                        );
+                }
+                PillarMatchResult::PossibleMatch(v) => {
+                    let prob_str = format!("{:.2}%", v * 100.0);
+                    Paragraph::new(seed_str)
+                        .style(Style::default().fg(if v > 0.75 {
+                            Color::Green
+                        } else if v > 0.25 {
+                            Color::Yellow
+                        } else {
+                            Color::Red
+                        }))
+                        .render(
+                            limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
+                                x: 0,
+                                y: i as i32 + 2,
+                            }),
+                            buf,
+                        );
+                    Paragraph::new(prob_str)
+                        .style(Style::default().fg(Color::Yellow))
+                        .render(
+                            limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
+                                x: 25,
+                                y: i as i32 + 2,
+                            }),
+                            buf,
+                        );
+
+                    // the hint that lowers the probability the most
+                    pillars.from_seed(seed);
+                    if let Some((pillar, weakest)) = observed.weakest_pillar(&pillars) {
+                        Paragraph::new(format!(
+                            "pillar {}: {:.2}%",
+                            pillar,
+                            weakest.chance() * 100.0
+                        ))
+                        .style(Style::default().fg(Color::Gray))
+                        .render(
+                            limit_area_width(limit_area_height(area, 1), 25).offset(Offset {
+                                x: 35,
+                                y: i as i32 + 2,
+                            }),
+                            buf,
+                        );
                     }
-                    PillarMatchResult::ImpossibleMatch => unreachable!(),
+                }
+                PillarMatchResult::ImpossibleMatch | PillarMatchResult::InvalidObservation(_) => {
+                    unreachable!()
                 }
             }
         }
+        if hidden > 0 {
+            Paragraph::new(format!("+{} more ([PGUP] / [PGDN] to scroll)", hidden))
+                .style(Style::default().fg(Color::Gray))
+                .render(
+                    limit_area_height(area, 1).offset(Offset {
+                        x: 0,
+                        y: rows.len() as i32 + 2,
+                    }),
+                    buf,
+                );
+        }
     }
 
     fn render_structures(
@@ -182,6 +663,8 @@ impl OutputTabComponent {
         state: &mut <Self as Component>::State,
         shared: &mut SharedApplicationState,
     ) {
+        shared.refresh_structure_sim_outdated();
+
         let outdated_label =
             Paragraph::new("Outdated data: ").style(Style::default().fg(Color::White));
         outdated_label.render(limit_area_height(area, 1), buf);
@@ -210,10 +693,10 @@ impl OutputTabComponent {
                         buf,
                     );
 
-                    let pgint = searcher.get_progress();
-                    let pg = pgint as f64 / (1i64 << 32) as f64;
+                    let (pgint, total) = searcher.progress();
+                    let pg = pgint as f64 / total.max(1) as f64;
                     let pgtext = format!(
-                        "({} job{}) [{pgint:10}/4294967296] {}%",
+                        "({} job{}) [{pgint:10}/{total}] {}%",
                         shared.structure_seed_search_jobs.len() + 1,
                         if shared.structure_seed_search_jobs.is_empty() {
                             ""
@@ -241,6 +724,11 @@ impl OutputTabComponent {
                         limit_area_height(area, 1).offset(Offset { x: 0, y: 4 }),
                         buf,
                     );
+                    if searcher.is_stalled(STALL_THRESHOLD) {
+                        render_stall_warning(area, buf, searcher.heartbeat().since_last_progress());
+                    } else {
+                        render_rejections(area, buf, &searcher.rejections());
+                    }
 
                     6
                 } else {
@@ -281,14 +769,33 @@ impl OutputTabComponent {
             1
         };
 
+        let running = shared.current_structure_seed_searcher.is_some()
+            || !shared.structure_seed_search_jobs.is_empty();
+        let panel = structure_panel_state(
+            &shared.last_structure_seed_sim,
+            running,
+            shared.structure_search_error(),
+        );
+        let y = y + render_panel_message(area, buf, y, &panel, "structure seeds");
+        if !panel.keeps_results() {
+            return;
+        }
+
         if let Some(sim) = &shared.last_structure_seed_sim.data {
-            let num_str = format!("{}", sim.count_seeds);
+            let num_str = format_count(sim.count_seeds as u64);
             let num_str_len = num_str.len() as i32;
 
             let sim_text1 = Paragraph::new("Found ").style(Style::default().fg(Color::White));
             let sim_text2 = Paragraph::new(num_str).style(Style::default().fg(Color::Yellow));
-            let sim_text3 =
-                Paragraph::new(" structure seeds:").style(Style::default().fg(Color::White));
+            let text3 = match shared.slot_threshold {
+                Some(threshold) => format!(
+                    " structure seeds ({}+ matching slots):",
+                    threshold.min_matching_slots
+                ),
+                None => " structure seeds:".to_string(),
+            };
+            let text3_len = text3.len() as u16;
+            let sim_text3 = Paragraph::new(text3).style(Style::default().fg(Color::White));
 
             sim_text1.render(
                 limit_area_width(limit_area_height(area, 1), 6).offset(Offset { x: 0, y }),
@@ -300,102 +807,23 @@ impl OutputTabComponent {
                 buf,
             );
             sim_text3.render(
-                limit_area_width(limit_area_height(area, 1), 17).offset(Offset {
+                limit_area_width(limit_area_height(area, 1), text3_len).offset(Offset {
                     x: 6 + num_str_len,
                     y,
                 }),
                 buf,
             );
 
-            let orig_y = y + 1;
-            let mut y = orig_y;
-            let mut x = 0;
-            let mut cur_pillar_i: isize = -1;
-            let mut cur_seed_i = 0;
-            while x + 20 < area.width as i32
-                && y < area.height as i32
-                && cur_pillar_i < sim.per_pillar.len() as isize
-            {
-                if cur_pillar_i == -1
-                    || cur_seed_i >= sim.per_pillar[cur_pillar_i as usize].structure_seeds.len()
-                {
-                    cur_pillar_i += 1;
-                    cur_seed_i = 0;
-                    if y + 4 >= area.height as i32 || cur_pillar_i >= sim.per_pillar.len() as isize
-                    {
-                        break;
-                    }
-                    y += 1;
-
-                    Paragraph::new(format!(
-                        "Pillar seed {}",
-                        sim.per_pillar[cur_pillar_i as usize].pillar_seed
-                    ))
-                    .style(Style::default().fg(Color::LightYellow).bold())
-                    .render(
-                        limit_area_width(limit_area_height(area, 1), 17).offset(Offset { x, y }),
-                        buf,
-                    );
-
-                    y += 1;
-
-                    match sim.per_pillar[cur_pillar_i as usize].result {
-                        StructureSeedSimResultType::Success => {
-                            Paragraph::new("(success)")
-                                .style(Style::default().fg(Color::Green).bold())
-                                .render(
-                                    limit_area_width(limit_area_height(area, 1), 9)
-                                        .offset(Offset { x, y }),
-                                    buf,
-                                );
-                        }
-                        StructureSeedSimResultType::TooManySeeds => {
-                            Paragraph::new("(too many seeds, search stopped)")
-                                .style(Style::default().fg(Color::Yellow).bold())
-                                .render(
-                                    limit_area_width(limit_area_height(area, 1), 32)
-                                        .offset(Offset { x, y }),
-                                    buf,
-                                );
-                        }
-                        StructureSeedSimResultType::Cancelled => {
-                            Paragraph::new("(search cancelled)")
-                                .style(Style::default().fg(Color::Red).bold())
-                                .render(
-                                    limit_area_width(limit_area_height(area, 1), 18)
-                                        .offset(Offset { x, y }),
-                                    buf,
-                                );
-                        }
-                    }
-
-                    y += 1;
-
-                    continue;
-                }
-
-                if y == orig_y {
-                    y += 1;
-                }
-
-                Paragraph::new(format!(
-                    "{}",
-                    sim.per_pillar[cur_pillar_i as usize].structure_seeds[cur_seed_i]
-                ))
-                .style(Style::new().fg(Color::Green).not_bold())
-                .render(
-                    limit_area_width(limit_area_height(area, 1), 20).offset(Offset { x, y }),
-                    buf,
-                );
-                y += 1;
-
-                cur_seed_i += 1;
-
-                if y >= area.height as i32 {
-                    x += 35;
-                    y = orig_y;
-                }
-            }
+            let tree_area = Rect {
+                y: area.y + (y as u16 + 2).min(area.height),
+                height: area.height.saturating_sub(y as u16 + 2),
+                ..area
+            };
+            TreeListWidget::new(
+                &StructureSeedTree(sim),
+                state.focus == Focus::StructureSeedButton,
+            )
+            .render(tree_area, buf, &mut state.structure_tree);
         }
     }
 
@@ -408,8 +836,32 @@ impl OutputTabComponent {
     ) {
         if let Some(ssim) = &shared.last_structure_seed_sim.data {
             if ssim.count_seeds >= 1
-                && ssim.count_seeds as usize <= shared.max_structure_seeds_to_simulate
+                && (ssim.count_seeds as usize <= shared.max_structure_seeds_to_simulate
+                    || shared.world_seed_sim.truncated_from.is_some()
+                    || shared.world_seed_sim.reverification.is_some())
             {
+                let mut notes = Vec::new();
+                let reverification = shared.world_seed_sim.reverification.as_ref();
+                if let Some(reverification) = reverification.filter(|r| !r.eliminated.is_empty()) {
+                    notes.push(reverification.to_string());
+                }
+                if let Some(total) = shared.world_seed_sim.truncated_from {
+                    notes.push(format!(
+                        "Only searching the top {} of {} structure seeds",
+                        shared.max_structure_seeds_to_simulate.min(total),
+                        total
+                    ));
+                }
+                if !notes.is_empty() {
+                    Paragraph::new(notes.join(", "))
+                        .alignment(Alignment::Center)
+                        .style(Style::default().fg(Color::Yellow).bold())
+                        .render(
+                            limit_area_height(area, 1).offset(Offset { x: 0, y: 2 }),
+                            buf,
+                        );
+                }
+
                 let y = if let Some(searcher) = &shared.current_world_seed_searcher {
                     let cancel_btn = Paragraph::new("[Cancel search]").style(
                         if state.focus == Focus::WorldSeedButton {
@@ -457,6 +909,11 @@ impl OutputTabComponent {
                         limit_area_height(area, 1).offset(Offset { x: 0, y: 4 }),
                         buf,
                     );
+                    if searcher.is_stalled(STALL_THRESHOLD) {
+                        render_stall_warning(area, buf, searcher.heartbeat().since_last_progress());
+                    } else {
+                        render_rejections(area, buf, &searcher.get_rejections());
+                    }
 
                     6
                 } else {
@@ -478,12 +935,29 @@ impl OutputTabComponent {
                     3
                 };
 
-                let num_str = if shared.world_seed_sim.count_seeds
-                    >= shared.max_world_seeds_per_structure_seed as i64
-                {
-                    format!(">{}", shared.max_world_seeds_per_structure_seed)
+                let early_hits = shared.last_structure_seed_sim.early_hits.pending();
+                let running = shared.current_world_seed_searcher.is_some()
+                    || !shared.world_seed_search_jobs.is_empty();
+                let panel = world_panel_state(
+                    &shared.world_seed_sim,
+                    running,
+                    shared.is_world_sim_stale(),
+                    early_hits.len(),
+                    shared.world_search_error(),
+                );
+                let y = y + render_panel_message(area, buf, y, &panel, "world seeds");
+                if !panel.keeps_results() {
+                    return;
+                }
+
+                let count_seeds = shared.world_seed_sim.count_seeds + early_hits.len() as i64;
+                let num_str = if count_seeds >= shared.max_world_seeds_per_structure_seed as i64 {
+                    format!(
+                        ">{}",
+                        format_count(shared.max_world_seeds_per_structure_seed as u64)
+                    )
                 } else {
-                    format!("{}", shared.world_seed_sim.count_seeds)
+                    format_count(count_seeds as u64)
                 };
                 let num_str_len = num_str.len();
 
@@ -509,94 +983,33 @@ impl OutputTabComponent {
                     buf,
                 );
 
-                let orig_y = y + 1;
-                let mut y = orig_y;
-                let mut x = 0;
-                let mut cur_struct_i: isize = -1;
-                let mut cur_seed_i = 0;
-                while x + 30 < area.width as i32
-                    && y < area.height as i32
-                    && cur_struct_i < shared.world_seed_sim.per_structure.len() as isize
-                {
-                    if cur_struct_i == -1
-                        || cur_seed_i
-                            >= shared.world_seed_sim.per_structure[cur_struct_i as usize]
-                                .world_seeds
-                                .len()
-                                .min(5)
-                    {
-                        cur_struct_i += 1;
-                        cur_seed_i = 0;
-                        if y + 4 >= area.height as i32
-                            || cur_struct_i >= shared.world_seed_sim.per_structure.len() as isize
-                        {
-                            break;
-                        }
-                        y += 1;
-
-                        Paragraph::new(format!(
-                            "Structure seed {}",
-                            shared.world_seed_sim.per_structure[cur_struct_i as usize]
-                                .structure_seed
-                        ))
-                        .style(Style::default().fg(Color::LightYellow).bold())
-                        .render(
-                            limit_area_width(limit_area_height(area, 1), 25)
-                                .offset(Offset { x, y }),
-                            buf,
-                        );
-
-                        y += 1;
-
-                        match shared.world_seed_sim.per_structure[cur_struct_i as usize].result {
-                            WorldSeedSimResultType::Success => {
-                                Paragraph::new("(success)")
-                                    .style(Style::default().fg(Color::Green).bold())
-                                    .render(
-                                        limit_area_width(limit_area_height(area, 1), 9)
-                                            .offset(Offset { x, y }),
-                                        buf,
-                                    );
-                            }
-                            WorldSeedSimResultType::TooManySeeds => {
-                                Paragraph::new("(+ more)")
-                                    .style(Style::default().fg(Color::Yellow).bold())
-                                    .render(
-                                        limit_area_width(limit_area_height(area, 1), 8)
-                                            .offset(Offset { x, y }),
-                                        buf,
-                                    );
-                            }
-                        }
-
-                        y += 1;
-
-                        continue;
-                    }
-
-                    if y == orig_y {
-                        y += 1;
-                    }
-
-                    Paragraph::new(format!(
-                        "{}",
-                        shared.world_seed_sim.per_structure[cur_struct_i as usize].world_seeds
-                            [cur_seed_i]
-                    ))
-                    .style(Style::new().fg(Color::Green).not_bold())
+                // the early hits aren't in the tree until the world seed search finds them again
+                for (i, hit) in early_hits.iter().enumerate() {
+                    Paragraph::new(Line::from(vec![
+                        format!("{:<21}", hit.seed).green(),
+                        " (early hit — typed seed)".light_blue(),
+                    ]))
                     .render(
-                        limit_area_width(limit_area_height(area, 1), 20).offset(Offset { x, y }),
+                        limit_area_height(area, 1).offset(Offset {
+                            x: 0,
+                            y: y + 2 + i as i32,
+                        }),
                         buf,
                     );
-                    y += 1;
-
-                    cur_seed_i += 1;
-
-                    if y >= area.height as i32 {
-                        x += 35;
-                        y = orig_y;
-                    }
                 }
+                let y = y + early_hits.len() as i32;
+
+                // a blank line between the title and the results
+                let tree_area = Rect {
+                    y: area.y + (y as u16 + 2).min(area.height),
+                    height: area.height.saturating_sub(y as u16 + 2),
+                    ..area
+                };
+                TreeListWidget::new(
+                    &WorldSeedTree(&shared.world_seed_sim),
+                    state.focus == Focus::WorldSeedButton,
+                )
+                .render(tree_area, buf, &mut state.world_tree);
             } else if ssim.count_seeds == 0 {
                 Paragraph::new("No structure seeds to search")
                     .alignment(Alignment::Center)
@@ -614,6 +1027,25 @@ impl OutputTabComponent {
                         limit_area_height(area, 1).offset(Offset { x: 0, y: 1 }),
                         buf,
                     );
+
+                let anyway_text = format!(
+                    "[Search the top {} anyway]",
+                    shared.max_structure_seeds_to_simulate
+                );
+                let anyway_text_len = anyway_text.len();
+                Paragraph::new(anyway_text)
+                    .style(if state.focus == Focus::WorldSeedButton {
+                        Style::new().fg(Color::White).bold().bg(Color::LightMagenta)
+                    } else {
+                        Style::default().fg(Color::LightYellow).not_bold()
+                    })
+                    .render(
+                        get_area_centered(
+                            limit_area_width(limit_area_height(area, 1), anyway_text_len as u16),
+                            limit_area_height(area, 1).offset(Offset { x: 0, y: 3 }),
+                        ),
+                        buf,
+                    );
                 return;
             }
         } else {
@@ -631,6 +1063,7 @@ impl OutputTabComponent {
 
 impl Component for OutputTabComponent {
     type State = OutputTabState;
+    type Shared = SharedApplicationState;
 
     fn render(
         &self,
@@ -701,9 +1134,118 @@ impl Component for OutputTabComponent {
             inner
         };
 
+        shared.hit_map.record(
+            parts_areas[1],
+            HitTarget::OutputPanel(Focus::StructureSeedButton),
+        );
+        shared.hit_map.record(
+            parts_areas[2],
+            HitTarget::OutputPanel(Focus::WorldSeedButton),
+        );
+
         self.render_pillars(part_pillar_area, buf, state, shared);
         self.render_structures(part_structure_area, buf, state, shared);
         self.render_world(part_world_area, buf, state, shared);
+
+        if let Some(job) = &shared.world_description {
+            if job.is_done() {
+                let job = shared.world_description.take().unwrap();
+                match job.join() {
+                    Ok(Some(report)) => {
+                        state.export_status = None;
+                        state.plan = Some(report.to_string());
+                        state.report = Some(report);
+                    }
+                    _ => {
+                        state.export_status =
+                            Some(("The world description was stopped".to_string(), false));
+                    }
+                }
+            } else {
+                state.export_status = Some((
+                    format!(
+                        "Listing the evidence around the world seed... {}%",
+                        format_progress(progress_percent(job.get_progress(), job.total_steps()))
+                    ),
+                    true,
+                ));
+            }
+        }
+
+        if shared
+            .calibration
+            .as_ref()
+            .is_some_and(|c| !c.is_finished())
+        {
+            state.export_status = Some(("Calibrating...".to_string(), true));
+        } else if let Some(calibration) = shared.calibration.take() {
+            state.export_status = Some(
+                calibration
+                    .join()
+                    .unwrap_or_else(|_| ("The calibration failed".to_string(), false)),
+            );
+        }
+
+        let bottom_area = areas[1];
+        Paragraph::new(
+            "[E] Export the focused seeds (cubiomes viewer format)    [P] / [SHIFT+ENTER] Show the search plan    [C] Calibrate    [S] Protocol commands    [V] Evidence to verify the world seed    [J] Job queue    [ARROWS] Browse / collapse the seeds    [PGUP] / [PGDN] Scroll the pillar seeds    [LEFT CLICK] Focus a panel",
+        )
+        .style(Style::default().fg(Color::Magenta))
+        .render(limit_area_height(bottom_area, 1), buf);
+        if let Some((status, success)) = &state.export_status {
+            Paragraph::new(status.as_str())
+                .style(Style::default().fg(if *success { Color::Green } else { Color::Red }))
+                .render(
+                    limit_area_height(bottom_area, 1).offset(Offset { x: 0, y: 1 }),
+                    buf,
+                );
+        }
+
+        if let Some(plan) = &state.plan {
+            let width = plan.lines().map(|l| l.len()).max().unwrap_or(0) as u16 + 4;
+            let height = plan.lines().count() as u16 + 2;
+            let popup_area = get_area_centered(
+                Rect::new(0, 0, width.min(area.width), height.min(area.height)),
+                area,
+            );
+
+            Clear.render(popup_area, buf);
+            Paragraph::new(plan.as_str())
+                .style(Style::default().fg(Color::White))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::LightCyan))
+                        .title_bottom(if state.report.is_some() {
+                            "[L] Export the buried treasure loot    [Any key] Close"
+                        } else {
+                            "[Any key] Close"
+                        })
+                        .title_alignment(Alignment::Center),
+                )
+                .render(popup_area, buf);
+        }
+
+        if let Some(popup) = &state.jobs {
+            let lines = popup.lines(shared);
+            let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+            let height = lines.len() as u16 + 2;
+            let popup_area = get_area_centered(
+                Rect::new(0, 0, width.min(area.width), height.min(area.height)),
+                area,
+            );
+
+            Clear.render(popup_area, buf);
+            Paragraph::new(lines)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(Color::LightCyan))
+                        .title_bottom("[SHIFT+UP] / [SHIFT+DOWN] Move    [DEL] Remove    [ENTER] Inspect    [TAB] Other queue    [ESC] Close")
+                        .title_alignment(Alignment::Center),
+                )
+                .render(popup_area, buf);
+        }
     }
 
     fn handle_event(
@@ -713,6 +1255,32 @@ impl Component for OutputTabComponent {
         event: Event,
         context: EventContext,
     ) -> EventResult {
+        if context == EventContext::BubblingDown {
+            if let Some(popup) = &mut state.jobs {
+                let closed = match &event {
+                    Event::Key(key) => !popup.handle_key(shared, key),
+                    _ => false,
+                };
+                if closed {
+                    state.jobs = None;
+                }
+                return EventResult::Captured;
+            }
+            // the queue can be edited while the structure seed search takes every other key
+            if matches!(&event, Event::Key(key) if key.kind != KeyEventKind::Release && key.code == KeyCode::Char('j'))
+                && state.plan.is_none()
+            {
+                state.jobs = Some(JobQueuePopup::new(
+                    if state.focus == Focus::WorldSeedButton {
+                        QueueKind::WorldSeeds
+                    } else {
+                        QueueKind::StructureSeeds
+                    },
+                ));
+                return EventResult::Captured;
+            }
+        }
+
         if (shared.current_structure_seed_searcher.is_some()
             || !shared.structure_seed_search_jobs.is_empty())
             && (context == EventContext::BubblingUp
@@ -723,6 +1291,41 @@ impl Component for OutputTabComponent {
 
         match context {
             EventContext::BubblingDown => match &event {
+                Event::Key(key) if state.plan.is_some() => {
+                    if key.kind != KeyEventKind::Release {
+                        state.plan = None;
+                        let report = state.report.take();
+                        if let (Some(report), KeyCode::Char('l')) = (report, key.code) {
+                            let luck = shared.buried_treasure_data.luck;
+                            state.export_status =
+                                Some(match export_buried_treasure_loot(&report, luck) {
+                                    Ok(count) => (
+                                        format!(
+                                            "Exported the loot of {} buried treasure(s) to {}",
+                                            count, LOOT_EXPORT_PATH
+                                        ),
+                                        true,
+                                    ),
+                                    Err(err) => (format!("Export failed: {}", err), false),
+                                });
+                        }
+                    }
+                    EventResult::Captured
+                }
+                Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                    if state.plan.is_some() {
+                        state.plan = None;
+                        state.report = None;
+                        return EventResult::Captured;
+                    }
+                    match shared.hit_map.hit(mouse.column, mouse.row) {
+                        Some(HitTarget::OutputPanel(focus)) => {
+                            state.focus = focus;
+                            EventResult::Captured
+                        }
+                        _ => EventResult::BubbleUp(event),
+                    }
+                }
                 Event::Key(key) if key.code == KeyCode::Tab => {
                     state.focus = match state.focus {
                         Focus::StructureSeedButton => Focus::WorldSeedButton,
@@ -737,13 +1340,125 @@ impl Component for OutputTabComponent {
                     }
                 }
                 Event::Key(key) if key.kind != KeyEventKind::Release => match state.focus {
+                    _ if matches!(key.code, KeyCode::PageUp | KeyCode::PageDown) => {
+                        // clamped by the next render
+                        let page = state.pillar_rows.max(1);
+                        state.pillar_offset = if key.code == KeyCode::PageDown {
+                            state.pillar_offset.saturating_add(page)
+                        } else {
+                            state.pillar_offset.saturating_sub(page)
+                        };
+                        EventResult::Captured
+                    }
+                    Focus::StructureSeedButton | Focus::WorldSeedButton
+                        if key.code == KeyCode::Char('p')
+                            || (key.code == KeyCode::Enter
+                                && key.modifiers.contains(KeyModifiers::SHIFT)) =>
+                    {
+                        let plan = CrackingPipeline::plan(
+                            &shared.cracking_evidence(),
+                            &shared.pipeline_options(),
+                        );
+                        let estimate =
+                            match Calibration::load_stored(shared.search_options.thread_count()) {
+                                Some(calibration) => format!(
+                                    "Estimated time: up to {}",
+                                    format_duration(calibration.estimate(&plan))
+                                ),
+                                None => "Press [C] to calibrate and get time estimates".to_string(),
+                            };
+                        state.plan = Some(format!("{}{}", plan, estimate));
+                        EventResult::Captured
+                    }
+                    Focus::StructureSeedButton | Focus::WorldSeedButton
+                        if key.code == KeyCode::Char('c') =>
+                    {
+                        // a second run at the same time would slow both down
+                        if shared.calibration.is_none() {
+                            let search = shared.search_options;
+                            shared.calibration = Some(thread::spawn(move || calibrate(&search)));
+                        }
+                        EventResult::Captured
+                    }
+                    Focus::StructureSeedButton | Focus::WorldSeedButton
+                        if key.code == KeyCode::Char('s') =>
+                    {
+                        match export_protocol_commands(shared) {
+                            Ok(text) => {
+                                state.export_status = None;
+                                state.plan = Some(text);
+                            }
+                            Err(err) => {
+                                state.export_status =
+                                    Some((format!("Export failed: {}", err), false));
+                            }
+                        }
+                        EventResult::Captured
+                    }
+                    Focus::WorldSeedButton if key.code == KeyCode::Char('v') => {
+                        let Some(world_seed) = shared
+                            .world_seed_sim
+                            .per_structure
+                            .iter()
+                            .flat_map(|sim| sim.world_seeds.iter())
+                            .next()
+                            .map(|w| w.seed)
+                        else {
+                            state.export_status =
+                                Some(("No world seed found yet".to_string(), false));
+                            return EventResult::Captured;
+                        };
+                        let bt = &shared.buried_treasure_data;
+                        let center = if bt.usable {
+                            (bt.pos_x, bt.pos_z)
+                        } else {
+                            (0, 0)
+                        };
+                        if let Some(job) = shared.world_description.take() {
+                            let _ = job.cancel_join();
+                        }
+                        shared.world_description =
+                            Some(WorldDescriber::spawn(DescribeRequest::new(
+                                world_seed,
+                                center,
+                                DESCRIBE_RADIUS,
+                                MCVersion::MC_1_16_5,
+                            )));
+                        EventResult::Captured
+                    }
+                    Focus::StructureSeedButton if key.code == KeyCode::Char('e') => {
+                        state.export_status = Some(match export_structure_seeds(shared) {
+                            Ok(count) => (
+                                format!(
+                                    "Exported the completions of {} structure seed(s) to {}",
+                                    count, STRUCTURE_SEEDS_EXPORT_PATH
+                                ),
+                                true,
+                            ),
+                            Err(err) => (format!("Export failed: {}", err), false),
+                        });
+                        EventResult::Captured
+                    }
+                    Focus::WorldSeedButton if key.code == KeyCode::Char('e') => {
+                        state.export_status = Some(match export_world_seeds(shared) {
+                            Ok(count) => (
+                                format!(
+                                    "Exported {} world seed(s) to {}",
+                                    count, WORLD_SEEDS_EXPORT_PATH
+                                ),
+                                true,
+                            ),
+                            Err(err) => (format!("Export failed: {}", err), false),
+                        });
+                        EventResult::Captured
+                    }
                     Focus::StructureSeedButton
                         if key.code == KeyCode::Enter
                             && shared.current_structure_seed_searcher.is_some() =>
                     {
                         if let Some(job) = shared.current_structure_seed_searcher.take() {
                             shared.structure_seed_search_jobs.clear();
-                            job.cancel_join().unwrap();
+                            job.cancel_join();
 
                             EventResult::Captured
                         } else {
@@ -755,52 +1470,35 @@ impl Component for OutputTabComponent {
                         if key.code == KeyCode::Enter
                             && shared.last_structure_seed_sim.outdated_data =>
                     {
+                        if let Some(error) = shared.structure_search_error() {
+                            state.export_status =
+                                Some((format!("Can't search structure seeds, {}", error), false));
+                            return EventResult::Captured;
+                        }
+
                         shared.structure_seed_search_jobs.clear();
                         shared.last_structure_seed_sim = StructureSeedSimData {
                             outdated_data: true,
+                            evidence: Some(shared.structure_evidence_fingerprint()),
                             data: None,
+                            early_hits: EarlyHits::default(),
+                            schedule: None,
+                            searching: None,
                         };
                         if let Some(job) = shared.current_structure_seed_searcher.take() {
-                            job.cancel_join().unwrap();
+                            job.cancel_join();
                         }
 
                         if let Some(sim) = &shared.last_pillar_sim {
-                            if sim.0 == shared.pillar_data {
-                                let mut pillar_seeds = sim
-                                    .1
-                                    .iter()
-                                    .filter(|p| !p.1.is_impossible_match())
-                                    .collect::<Vec<_>>();
-                                if pillar_seeds.len() <= shared.max_pillars_to_simulate {
-                                    pillar_seeds.sort_by(|a, b| b.1.compare(&a.1));
-
-                                    let mut data = Vec::new();
-                                    if shared.buried_treasure_data.usable {
-                                        let c = Math::block_coords_to_chunk_coords((
-                                            shared.buried_treasure_data.pos_x,
-                                            shared.buried_treasure_data.pos_z,
-                                        ));
-
-                                        data.push(StructureData::BuriedTreasureContents {
-                                            chunk_x: c.0,
-                                            chunk_z: c.1,
-                                            luck: shared.buried_treasure_data.luck,
-                                            contents: build_fast_inventory_compare_context(
-                                                shared.buried_treasure_data.contents.clone(),
-                                            ),
-                                        });
-                                    }
-
-                                    for pillar_seed in pillar_seeds {
-                                        shared.structure_seed_search_jobs.push_back(
-                                            StructureSeedSearchData {
-                                                data: data.clone(),
-                                                max_results: 10,
-                                                pillar_seed: pillar_seed.0,
-                                            },
-                                        );
-                                    }
-                                }
+                            if sim.0 == EvidenceFingerprint::of(&shared.enabled_pillar_data()) {
+                                let (evidence, usable) = shared.evidence_registry.collect(shared);
+                                let options = shared.pipeline_options();
+                                let jobs =
+                                    CrackingPipeline::structure_jobs(&evidence, &options, &sim.1);
+                                state.export_status = searching_status(&jobs, &usable);
+                                shared.last_structure_seed_sim.schedule =
+                                    Some(CrackingPipeline::structure_schedule(&options, &jobs));
+                                shared.structure_seed_search_jobs.extend(jobs);
                             }
                         }
 
@@ -821,10 +1519,30 @@ impl Component for OutputTabComponent {
                         }
                     }
                     Focus::WorldSeedButton if key.code == KeyCode::Enter => {
+                        let (evidence, usable) = shared.evidence_registry.collect(shared);
+                        let invalid = CrackingPipeline::invalid_samples(
+                            &evidence,
+                            &shared.pipeline_options(),
+                        );
+                        if let Some(first) = invalid.first() {
+                            state.export_status = Some((
+                                format!(
+                                    "Can't search world seeds, {} invalid biome sample(s): {}",
+                                    invalid.len(),
+                                    first
+                                ),
+                                false,
+                            ));
+                            return EventResult::Captured;
+                        }
+
                         shared.world_seed_search_jobs.clear();
                         shared.world_seed_sim = WorldSeedSimData {
                             count_seeds: 0,
                             per_structure: Vec::new(),
+                            truncated_from: None,
+                            reverification: None,
+                            started_from: Some(shared.world_sim_evidence()),
                         };
 
                         if let Some(job) = shared.current_world_seed_searcher.take() {
@@ -832,34 +1550,60 @@ impl Component for OutputTabComponent {
                         }
 
                         if let Some(sim) = &shared.last_structure_seed_sim.data {
-                            if sim.count_seeds >= 1
-                                && sim.count_seeds as usize
-                                    <= shared.max_structure_seeds_to_simulate
-                            {
-                                let mut data = Vec::new();
-                                data.push(shared.biome_data.overworld_biomes.clone());
-                                data.push(shared.biome_data.nether_biomes.clone());
-
-                                for &structure_seed in sim
-                                    .per_pillar
-                                    .iter()
-                                    .map(|p| p.structure_seeds.iter())
-                                    .flatten()
-                                {
-                                    shared
-                                        .world_seed_search_jobs
-                                        .push_back(WorldSeedSearchData {
-                                            structure_seed,
-                                            is_random_world_seed: shared.is_random_world_seed,
-                                            max_results: shared.max_world_seeds_per_structure_seed,
-                                            data: data.clone(),
-                                        });
-                                }
-                            }
+                            let options = shared.pipeline_options();
+                            let found = sim
+                                .per_pillar
+                                .iter()
+                                .flat_map(|p| {
+                                    p.structure_seeds.iter().map(|s| (p.pillar_seed, s.seed))
+                                })
+                                .collect::<Vec<_>>();
+                            // the evidence may have changed since the structure seed search
+                            let reverification = CrackingPipeline::reverify(
+                                &evidence,
+                                &options,
+                                &found.iter().map(|f| f.1).collect::<Vec<_>>(),
+                                |_| {},
+                            );
+                            let found = found
+                                .into_iter()
+                                .filter(|f| reverification.kept.contains(&f.1))
+                                .collect::<Vec<_>>();
+                            shared.world_seed_sim.reverification = Some(reverification);
+                            let structure_seeds = if found.len() > options.max_structure_seeds {
+                                shared.world_seed_sim.truncated_from = Some(found.len());
+                                CrackingPipeline::top_structure_seeds(&evidence, &options, &found)
+                            } else {
+                                found.iter().map(|f| f.1).collect()
+                            };
+                            let jobs =
+                                CrackingPipeline::world_jobs(&evidence, &options, &structure_seeds);
+                            state.export_status = searching_status(&jobs, &usable);
+                            shared.world_seed_search_jobs.extend(jobs);
                         }
 
                         EventResult::Captured
                     }
+                    Focus::WorldSeedButton
+                        if state
+                            .world_tree
+                            .handle_key(&WorldSeedTree(&shared.world_seed_sim), key.code) =>
+                    {
+                        EventResult::Captured
+                    }
+                    Focus::StructureSeedButton
+                        if shared
+                            .last_structure_seed_sim
+                            .data
+                            .as_ref()
+                            .is_some_and(|sim| {
+                                state
+                                    .structure_tree
+                                    .handle_key(&StructureSeedTree(sim), key.code)
+                            }) =>
+                    {
+                        EventResult::Captured
+                    }
                     _ => EventResult::BubbleUp(event),
                 },
                 _ => EventResult::BubbleUp(event),
@@ -868,3 +1612,364 @@ impl Component for OutputTabComponent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::{
+        fingerprint::EvidenceFingerprint,
+        pipeline::EarlyHits,
+        schedule::{EarlyTermination, StructureStageScheduler},
+        search::{StructureSeedSearch, StructureSeedSearchData},
+    };
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        layout::Rect,
+    };
+
+    use crate::tui::{
+        Component, EventContext,
+        application::{
+            ApplicationComponentState, SharedApplicationState, StructureSeedSim,
+            StructureSeedSimData, StructureSeedSimResultType, WorldSeedSimData,
+        },
+        job_queue::{JobQueuePopup, QueueKind, RUNNING_JOB_LOCKED},
+        tabs::output::{
+            OutputTabComponent, OutputTabState, PanelState, pillar_panel_state, searching_status,
+            structure_panel_state, visible_rows, world_panel_state,
+        },
+    };
+
+    fn structure_sim(data: Option<i64>, outdated_data: bool) -> StructureSeedSimData {
+        StructureSeedSimData {
+            outdated_data,
+            evidence: None,
+            data: data.map(|count_seeds| StructureSeedSim {
+                count_seeds,
+                per_pillar: Vec::new(),
+            }),
+            early_hits: EarlyHits::default(),
+            schedule: None,
+            searching: None,
+        }
+    }
+
+    fn world_sim(count_seeds: i64, searched: bool) -> WorldSeedSimData {
+        WorldSeedSimData {
+            count_seeds,
+            per_structure: Vec::new(),
+            truncated_from: None,
+            reverification: None,
+            started_from: searched.then(|| (None, Vec::new())),
+        }
+    }
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn render(
+        shared: &mut SharedApplicationState,
+        state: &mut OutputTabState,
+        height: u16,
+        panel: fn(
+            &OutputTabComponent,
+            Rect,
+            &mut Buffer,
+            &mut OutputTabState,
+            &mut SharedApplicationState,
+        ),
+    ) -> Vec<String> {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, height));
+        panel(&OutputTabComponent, buf.area, &mut buf, state, shared);
+        lines(&buf)
+    }
+
+    #[test]
+    fn test_structure_panel_states() {
+        let error = || Some("invalid chest".to_string());
+        // never searched, then searching with nothing found yet
+        assert_eq!(
+            structure_panel_state(&structure_sim(None, true), false, None),
+            PanelState::NotRun
+        );
+        assert_eq!(
+            structure_panel_state(&structure_sim(None, true), true, None),
+            PanelState::Running
+        );
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(2), true), true, error()),
+            PanelState::Running
+        );
+        // finished
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(0), false), false, None),
+            PanelState::ZeroResults
+        );
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(2), false), false, None),
+            PanelState::Results
+        );
+        // the evidence changed, or was cancelled halfway
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(2), true), false, None),
+            PanelState::Stale
+        );
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(0), true), false, None),
+            PanelState::Stale
+        );
+        assert_eq!(
+            structure_panel_state(&structure_sim(Some(2), false), false, error()),
+            PanelState::Error("invalid chest".to_string())
+        );
+    }
+
+    #[test]
+    fn test_world_and_pillar_panel_states() {
+        assert_eq!(
+            world_panel_state(&world_sim(0, false), false, false, 0, None),
+            PanelState::NotRun
+        );
+        // the typed seeds found before the search are results
+        assert_eq!(
+            world_panel_state(&world_sim(0, false), false, false, 1, None),
+            PanelState::Results
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(0, true), true, false, 0, None),
+            PanelState::Running
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(0, true), false, false, 0, None),
+            PanelState::ZeroResults
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(0, true), false, false, 1, None),
+            PanelState::Results
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(3, true), false, false, 0, None),
+            PanelState::Results
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(3, true), false, true, 0, None),
+            PanelState::Stale
+        );
+        assert_eq!(
+            world_panel_state(&world_sim(3, true), false, true, 0, Some("x".to_string())),
+            PanelState::Error("x".to_string())
+        );
+
+        assert_eq!(pillar_panel_state(None, 0), PanelState::ZeroResults);
+        assert_eq!(pillar_panel_state(None, 4), PanelState::Results);
+        assert_eq!(
+            pillar_panel_state(Some("x".to_string()), 4),
+            PanelState::Error("x".to_string())
+        );
+        assert!(PanelState::Stale.keeps_results());
+        assert!(!PanelState::ZeroResults.keeps_results());
+        assert_eq!(PanelState::Results.message("seeds"), None);
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        assert_eq!(visible_rows(3, 0, 5), (0..3, 0));
+        assert_eq!(visible_rows(5, 2, 5), (0..5, 0));
+        // the last line is the footer
+        assert_eq!(visible_rows(10, 0, 5), (0..4, 6));
+        assert_eq!(visible_rows(10, 4, 5), (4..8, 6));
+        assert_eq!(visible_rows(10, 100, 5), (6..10, 6));
+        assert_eq!(visible_rows(10, 0, 0), (0..0, 10));
+    }
+
+    #[test]
+    fn test_panel_messages() {
+        let mut app = ApplicationComponentState::new();
+        let shared = &mut app.shared;
+        let mut state = OutputTabState::default();
+
+        let lines = render(shared, &mut state, 5, OutputTabComponent::render_structures);
+        assert_eq!(lines[0], "Outdated data: Yes");
+        assert_eq!(lines[1], "[Find structure seeds]");
+        assert_eq!(lines[3], "No structure seeds searched yet");
+
+        // finished without a match, distinct from never searched
+        shared.last_structure_seed_sim = structure_sim(Some(0), false);
+        shared.last_structure_seed_sim.evidence = Some(shared.structure_evidence_fingerprint());
+        let lines = render(shared, &mut state, 5, OutputTabComponent::render_structures);
+        assert_eq!(lines[0], "Outdated data: No");
+        assert_eq!(
+            lines[1],
+            "Search finished, no structure seeds match the evidence"
+        );
+        assert_eq!(lines[2], "");
+
+        // the leftovers of other evidence are marked
+        shared.last_structure_seed_sim = structure_sim(Some(2), false);
+        shared.last_structure_seed_sim.evidence = Some(EvidenceFingerprint(0));
+        let lines = render(shared, &mut state, 8, OutputTabComponent::render_structures);
+        assert_eq!(
+            lines[3],
+            "The evidence changed, search again to update these"
+        );
+        assert_eq!(lines[4], "Found 2 structure seeds:");
+    }
+
+    #[test]
+    fn test_searching_status() {
+        assert_eq!(searching_status::<i64>(&[], &["end pillars"]), None);
+        assert_eq!(searching_status(&[1, 2], &[]), None);
+        assert_eq!(
+            searching_status(&[1, 2], &["end pillars", "buried treasure"]),
+            Some((
+                "Searching 2 job(s) with the end pillars, buried treasure evidence".to_string(),
+                true
+            ))
+        );
+    }
+
+    #[test]
+    fn test_pillar_panel_footer() {
+        let mut app = ApplicationComponentState::new();
+        let shared = &mut app.shared;
+        let mut state = OutputTabState::default();
+
+        // nothing observed, every pillar seed is possible
+        let lines = render(shared, &mut state, 6, OutputTabComponent::render_pillars);
+        assert_eq!(lines[1], "Valid pillar seeds count: 65536");
+        assert!(lines[2..5].iter().all(|l| !l.is_empty()));
+        assert_eq!(lines[5], "+65533 more ([PGUP] / [PGDN] to scroll)");
+        assert_eq!(state.pillar_rows, 3);
+
+        let first = lines[2].clone();
+        state.pillar_offset = 3;
+        let lines = render(shared, &mut state, 6, OutputTabComponent::render_pillars);
+        assert_ne!(lines[2], first);
+        assert_eq!(lines[5], "+65533 more ([PGUP] / [PGDN] to scroll)");
+    }
+
+    #[test]
+    fn test_job_queue_popup() {
+        let mut app = ApplicationComponentState::new();
+        let shared = &mut app.shared;
+        let mut state = OutputTabState::default();
+        let send = |state: &mut OutputTabState,
+                    shared: &mut SharedApplicationState,
+                    code: KeyCode,
+                    modifiers: KeyModifiers| {
+            OutputTabComponent.handle_event(
+                state,
+                shared,
+                Event::Key(KeyEvent::new(code, modifiers)),
+                EventContext::BubblingDown,
+            );
+        };
+        let queued = |shared: &SharedApplicationState| {
+            shared
+                .structure_seed_search_jobs
+                .iter()
+                .map(|job| job.pillar_seed)
+                .collect::<Vec<_>>()
+        };
+        let scheduled = |shared: &SharedApplicationState| {
+            let schedule = shared.last_structure_seed_sim.schedule.as_ref().unwrap();
+            schedule
+                .stats()
+                .iter()
+                .map(|s| s.pillar_seed)
+                .collect::<Vec<_>>()
+        };
+
+        // pillar seed 0 is searched, the others wait
+        let mut schedule = StructureStageScheduler::new([0, 1, 2, 3], 1, EarlyTermination::Never);
+        schedule.next_slice();
+        shared.last_structure_seed_sim.schedule = Some(schedule);
+        shared.current_structure_seed_searcher = Some(StructureSeedSearch::start(
+            StructureSeedSearchData::new(0, vec![], 1),
+        ));
+        shared
+            .structure_seed_search_jobs
+            .extend((1..4).map(|seed| StructureSeedSearchData::new(seed, vec![], 1)));
+
+        // the popup opens while the search takes the other keys
+        send(&mut state, shared, KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(
+            state.jobs,
+            Some(JobQueuePopup::new(QueueKind::StructureSeeds))
+        );
+        let lines = state.jobs.as_ref().unwrap().lines(shared);
+        assert_eq!(lines[1].to_string(), "> Pillar seed 0 (running)");
+        assert_eq!(lines.len(), 5);
+
+        // the running search can't be moved, removed or passed
+        send(&mut state, shared, KeyCode::Down, KeyModifiers::SHIFT);
+        assert_eq!(
+            state.jobs.as_ref().unwrap().message,
+            Some(RUNNING_JOB_LOCKED)
+        );
+        send(&mut state, shared, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(
+            state.jobs.as_ref().unwrap().message,
+            Some(RUNNING_JOB_LOCKED)
+        );
+        send(&mut state, shared, KeyCode::Down, KeyModifiers::NONE);
+        send(&mut state, shared, KeyCode::Up, KeyModifiers::SHIFT);
+        assert_eq!(
+            state.jobs.as_ref().unwrap().message,
+            Some(RUNNING_JOB_LOCKED)
+        );
+        assert_eq!(queued(shared), [1, 2, 3]);
+        assert_eq!(scheduled(shared), [0, 1, 2, 3]);
+
+        // the selection follows the moved search, the schedule the queue
+        send(&mut state, shared, KeyCode::Down, KeyModifiers::SHIFT);
+        assert_eq!(state.jobs.as_ref().unwrap().message, None);
+        assert_eq!(state.jobs.as_ref().unwrap().selected, 2);
+        assert_eq!(queued(shared), [2, 1, 3]);
+        assert_eq!(scheduled(shared), [0, 2, 1, 3]);
+        send(&mut state, shared, KeyCode::Down, KeyModifiers::SHIFT);
+        send(&mut state, shared, KeyCode::Down, KeyModifiers::SHIFT);
+        assert_eq!(queued(shared), [2, 3, 1]);
+        assert_eq!(state.jobs.as_ref().unwrap().selected, 3);
+
+        // the removed search is listed with the results
+        send(&mut state, shared, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(queued(shared), [2, 3]);
+        assert_eq!(scheduled(shared), [0, 2, 3]);
+        assert_eq!(state.jobs.as_ref().unwrap().selected, 2);
+        let per_pillar = &shared
+            .last_structure_seed_sim
+            .data
+            .as_ref()
+            .unwrap()
+            .per_pillar;
+        assert_eq!(per_pillar.len(), 1);
+        assert_eq!(per_pillar[0].pillar_seed, 1);
+        assert_eq!(per_pillar[0].result, StructureSeedSimResultType::Removed);
+
+        send(&mut state, shared, KeyCode::Enter, KeyModifiers::NONE);
+        let lines = state.jobs.as_ref().unwrap().lines(shared);
+        assert_eq!(lines[3].to_string(), "> Pillar seed 3");
+        assert_eq!(
+            lines[5].to_string(),
+            "No structure evidence, only the pillars"
+        );
+
+        send(&mut state, shared, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(state.jobs, None);
+        shared
+            .current_structure_seed_searcher
+            .take()
+            .unwrap()
+            .cancel_join();
+    }
+}
@@ -0,0 +1,567 @@
+use mcseedcracker::{
+    pipeline::CrackingEvidence,
+    search::{KnownStructure, WorldExtraData},
+};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Alignment, Constraint, Direction, Layout, Offset, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{
+    make_full_component,
+    tui::{
+        Component, EventContext, EventResult,
+        application::ApplicationTab,
+        components::text_input::{
+            TextInputState, TextInputWidget, Validator, block_coord_validator,
+        },
+        dim_area, is_toggle_evidence_key, limit_area_height, limit_area_width,
+    },
+};
+
+const FIELDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureEntry {
+    pub structure: Option<KnownStructure>,
+    pub block_x: i32,
+    pub block_z: i32,
+    /// Disabled entries are kept but left out of the evidence
+    pub enabled: bool,
+}
+
+impl Default for StructureEntry {
+    fn default() -> Self {
+        Self {
+            structure: None,
+            block_x: 0,
+            block_z: 0,
+            enabled: true,
+        }
+    }
+}
+
+impl StructureEntry {
+    pub fn as_world_extra_data(&self) -> Option<WorldExtraData> {
+        let structure = self.structure?;
+        Some(WorldExtraData::structure_presence(
+            structure,
+            self.block_x,
+            self.block_z,
+        ))
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct StructuresTabSharedData {
+    pub structures: Vec<StructureEntry>,
+}
+
+impl StructuresTabSharedData {
+    pub fn world_extra_data(&self) -> Vec<WorldExtraData> {
+        self.structures
+            .iter()
+            .filter(|s| s.enabled)
+            .filter_map(|s| s.as_world_extra_data())
+            .collect()
+    }
+}
+
+pub struct StructureInputs {
+    pub structure: TextInputState<Option<KnownStructure>>,
+    pub block_x: TextInputState<i32>,
+    pub block_z: TextInputState<i32>,
+}
+
+impl StructureInputs {
+    fn new() -> Self {
+        Self {
+            structure: TextInputState::new("Structure", known_structure_validator()),
+            block_x: TextInputState::new("Block X (i32)", block_coord_validator()),
+            block_z: TextInputState::new("Block Z (i32)", block_coord_validator()),
+        }
+    }
+
+    fn set_cursor_visible(&mut self, selected: Option<usize>) {
+        self.structure.style.show_cursor = selected == Some(0);
+        self.block_x.style.show_cursor = selected == Some(1);
+        self.block_z.style.show_cursor = selected == Some(2);
+    }
+}
+
+#[derive(Default)]
+pub struct StructuresTabState {
+    pub inputs: Vec<StructureInputs>,
+    pub focused: bool,
+    pub selected_x: usize,
+    pub selected_y: usize,
+}
+
+#[derive(Default)]
+pub struct StructuresTabComponent;
+
+make_full_component!(StructuresTab, state: StructuresTabState, component: StructuresTabComponent);
+
+/// The enabled known structures, see [`crate::tui::evidence::EvidenceRegistry`]
+pub fn contribute_evidence(
+    shared: &SharedApplicationState,
+    evidence: &mut CrackingEvidence,
+) -> bool {
+    let world = shared.structure_data.world_extra_data();
+    let usable = !world.is_empty();
+    evidence.world.extend(world);
+    usable
+}
+
+impl StructuresTab {
+    pub fn apptab() -> ApplicationTab<Self> {
+        ApplicationTab {
+            title: "Structures".to_string(),
+            component: StructuresTab::create(),
+        }
+    }
+}
+
+/// The next structure of [`KnownStructure::ALL`], the first one if none is picked
+fn cycle_structure(structure: Option<KnownStructure>, forward: bool) -> KnownStructure {
+    let len = KnownStructure::ALL.len();
+    let index = match structure.and_then(|s| KnownStructure::ALL.iter().position(|k| *k == s)) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    };
+    KnownStructure::ALL[index]
+}
+
+impl Component for StructuresTabComponent {
+    type State = StructuresTabState;
+    type Shared = SharedApplicationState;
+
+    fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+    ) {
+        let layoutvert = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(7), Constraint::Min(0)]);
+        let vert = layoutvert.split(area);
+
+        let controls_area = limit_area_height(vert[0], 1);
+        Paragraph::new("General controls")
+            .style(Style::default().fg(Color::Yellow).bold())
+            .render(limit_area_width(controls_area, 16), buf);
+        Paragraph::new("[CTRL + LEFT] [CTRL + RIGHT] [CTRL + UP] [CTRL + DOWN]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 54).offset(Offset { x: 0, y: 1 }),
+                buf,
+            );
+        Paragraph::new(" Change focused input")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 21).offset(Offset { x: 54, y: 1 }),
+                buf,
+            );
+        Paragraph::new("[UP] [DOWN]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 11).offset(Offset { x: 0, y: 2 }),
+                buf,
+            );
+        Paragraph::new(" Pick the structure")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 19).offset(Offset { x: 11, y: 2 }),
+                buf,
+            );
+        Paragraph::new("[CTRL + N]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 10).offset(Offset { x: 0, y: 3 }),
+                buf,
+            );
+        Paragraph::new(" Add new structure")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 18).offset(Offset { x: 10, y: 3 }),
+                buf,
+            );
+        Paragraph::new("[CTRL + DEL]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 12).offset(Offset { x: 0, y: 4 }),
+                buf,
+            );
+        Paragraph::new(" Delete selected line")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 21).offset(Offset { x: 12, y: 4 }),
+                buf,
+            );
+        Paragraph::new("[SPACE]")
+            .style(Style::default().fg(Color::Magenta).not_bold())
+            .render(
+                limit_area_width(controls_area, 7).offset(Offset { x: 0, y: 5 }),
+                buf,
+            );
+        Paragraph::new(" Enable / disable selected line")
+            .style(Style::default().fg(Color::Green).not_bold())
+            .render(
+                limit_area_width(controls_area, 31).offset(Offset { x: 7, y: 5 }),
+                buf,
+            );
+        Paragraph::new(
+            "Block coordinates of the structure's start chunk, in its dimension: the end for end cities, the nether for fortresses and bastions",
+        )
+        .style(Style::default().fg(Color::White).italic())
+        .render(controls_area.offset(Offset { x: 0, y: 6 }), buf);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(if state.focused {
+                Style::default().fg(Color::LightCyan)
+            } else {
+                Style::default()
+            })
+            .title("Known structures")
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(vert[1]);
+        block.render(vert[1], buf);
+
+        let row_areas = Layout::new(Direction::Horizontal, [Constraint::Fill(0); FIELDS])
+            .split(limit_area_height(inner, 3));
+        let mut row_areas = [row_areas[0], row_areas[1], row_areas[2]];
+
+        for (i, inputs) in state.inputs.iter_mut().enumerate() {
+            if 3 * i + 3 > inner.height as usize {
+                break;
+            }
+
+            inputs.set_cursor_visible(if state.focused && state.selected_y == i {
+                Some(state.selected_x)
+            } else {
+                None
+            });
+
+            TextInputWidget::default().render(row_areas[0], buf, &mut inputs.structure);
+            TextInputWidget::default().render(row_areas[1], buf, &mut inputs.block_x);
+            TextInputWidget::default().render(row_areas[2], buf, &mut inputs.block_z);
+            if shared
+                .structure_data
+                .structures
+                .get(i)
+                .is_some_and(|s| !s.enabled)
+            {
+                dim_area(Rect::new(inner.x, row_areas[0].y, inner.width, 3), buf);
+            }
+
+            for a in row_areas.iter_mut() {
+                a.y += 3;
+            }
+        }
+
+        if shared.structure_data.structures.is_empty() {
+            Paragraph::new("No known structures, press [CTRL + N] to add one")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(limit_area_height(inner, 1), buf);
+        }
+    }
+
+    fn handle_event(
+        &self,
+        state: &mut Self::State,
+        shared: &mut SharedApplicationState,
+        event: Event,
+        context: EventContext,
+    ) -> EventResult {
+        match context {
+            EventContext::BubblingUp => match event {
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Right =>
+                {
+                    state.selected_x = (state.selected_x + 1) % FIELDS;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Left =>
+                {
+                    state.selected_x = (state.selected_x + FIELDS - 1) % FIELDS;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && (key.code == KeyCode::Down || key.code == KeyCode::Up) =>
+                {
+                    let len = state.inputs.len();
+                    if len > 0 {
+                        state.selected_y = if key.code == KeyCode::Down {
+                            (state.selected_y + 1) % len
+                        } else {
+                            (state.selected_y + len - 1) % len
+                        };
+                    }
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && (key.code == KeyCode::Char('n') || key.code == KeyCode::Char('N')) =>
+                {
+                    state.inputs.push(StructureInputs::new());
+                    shared
+                        .structure_data
+                        .structures
+                        .push(StructureEntry::default());
+                    state.selected_y = state.inputs.len() - 1;
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.kind != KeyEventKind::Release
+                        && key.code == KeyCode::Delete =>
+                {
+                    if state.selected_y < state.inputs.len() {
+                        state.inputs.remove(state.selected_y);
+                    }
+                    if state.selected_y < shared.structure_data.structures.len() {
+                        shared.structure_data.structures.remove(state.selected_y);
+                    }
+                    state.selected_y = state.selected_y.min(state.inputs.len().saturating_sub(1));
+                    EventResult::Captured
+                }
+                Event::Key(key)
+                    if key.kind != KeyEventKind::Release && key.code == KeyCode::Tab =>
+                {
+                    state.focused = !state.focused;
+                    if state.focused {
+                        EventResult::Captured
+                    } else {
+                        EventResult::BubbleUp(event)
+                    }
+                }
+                _ => EventResult::BubbleUp(event),
+            },
+            EventContext::BubblingDown => {
+                if !state.focused {
+                    return EventResult::BubbleUp(event);
+                }
+
+                if is_toggle_evidence_key(&event) {
+                    if let Some(data) = shared.structure_data.structures.get_mut(state.selected_y) {
+                        data.enabled = !data.enabled;
+                    }
+                    return EventResult::Captured;
+                }
+
+                if let (Some(inputs), Some(data)) = (
+                    state.inputs.get_mut(state.selected_y),
+                    shared.structure_data.structures.get_mut(state.selected_y),
+                ) {
+                    let event_result = match state.selected_x {
+                        0 => match event {
+                            Event::Key(key)
+                                if key.modifiers.is_empty()
+                                    && key.kind != KeyEventKind::Release
+                                    && (key.code == KeyCode::Up || key.code == KeyCode::Down) =>
+                            {
+                                let structure =
+                                    cycle_structure(data.structure, key.code == KeyCode::Down);
+                                inputs
+                                    .structure
+                                    .set_text(structure.name(), &mut data.structure);
+                                EventResult::Captured
+                            }
+                            _ => TextInputWidget::handle_event(
+                                &mut inputs.structure,
+                                event,
+                                context,
+                                &mut data.structure,
+                            ),
+                        },
+                        1 => TextInputWidget::handle_event(
+                            &mut inputs.block_x,
+                            event,
+                            context,
+                            &mut data.block_x,
+                        ),
+                        2 => TextInputWidget::handle_event(
+                            &mut inputs.block_z,
+                            event,
+                            context,
+                            &mut data.block_z,
+                        ),
+                        _ => unreachable!(),
+                    };
+                    match event_result {
+                        EventResult::Captured => EventResult::Captured,
+                        EventResult::BubbleUp(event) => {
+                            self.handle_event(state, shared, event, EventContext::BubblingUp)
+                        }
+                    }
+                } else {
+                    self.handle_event(state, shared, event, EventContext::BubblingUp)
+                }
+            }
+        }
+    }
+
+    fn on_focus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focused = true;
+        state.selected_x = 0;
+        state.selected_y = 0;
+    }
+
+    fn on_unfocus(&self, state: &mut Self::State, _shared: &mut SharedApplicationState) {
+        state.focused = false;
+        state.selected_x = 0;
+        state.selected_y = 0;
+    }
+
+    fn sync_from_shared(&self, state: &mut Self::State, shared: &mut SharedApplicationState) {
+        let structures = &mut shared.structure_data.structures;
+        state.inputs.truncate(structures.len());
+        while state.inputs.len() < structures.len() {
+            state.inputs.push(StructureInputs::new());
+        }
+        for (inputs, entry) in state.inputs.iter_mut().zip(structures.iter_mut()) {
+            inputs.block_x.show_number(&mut entry.block_x);
+            inputs.block_z.show_number(&mut entry.block_z);
+            if KnownStructure::from_name(&inputs.structure.value.iter().collect::<String>())
+                != entry.structure
+            {
+                let text = entry.structure.map_or("", |s| s.name());
+                inputs.structure.set_text(text, &mut entry.structure);
+            }
+        }
+        state.selected_y = state.selected_y.min(state.inputs.len().saturating_sub(1));
+    }
+}
+
+pub fn known_structure_validator() -> Validator<Option<KnownStructure>> {
+    Some(Box::new(|text, _, style, structure| {
+        let text = text.iter().collect::<String>();
+        *structure = KnownStructure::from_name(&text);
+        if structure.is_some() {
+            style.text_style.fg = Some(Color::Green);
+            style.cursor_style.bg = Some(Color::Green);
+        } else if KnownStructure::ALL
+            .iter()
+            .any(|s| s.name().starts_with(&text))
+        {
+            style.text_style.fg = Some(Color::White);
+            style.cursor_style.bg = Some(Color::White);
+        } else {
+            style.text_style.fg = Some(Color::Red);
+            style.cursor_style.bg = Some(Color::Red);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::search::{KnownStructure, WorldExtraData};
+    use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::tui::{
+        Component, EventContext,
+        application::ApplicationComponentState,
+        tabs::structures::{
+            StructureEntry, StructuresTabComponent, StructuresTabState, cycle_structure,
+        },
+    };
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn ctrl(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn test_structure_evidence() {
+        let mut state = ApplicationComponentState::new();
+        state.shared.structure_data.structures = vec![
+            StructureEntry {
+                structure: Some(KnownStructure::Monument),
+                block_x: 1232,
+                block_z: -432,
+                enabled: true,
+            },
+            StructureEntry {
+                structure: Some(KnownStructure::Fortress),
+                enabled: false,
+                ..Default::default()
+            },
+            StructureEntry::default(),
+        ];
+        let (evidence, usable) = state.shared.evidence_registry.collect(&state.shared);
+        assert!(usable.contains(&"known structures"));
+        assert!(evidence.world.contains(&WorldExtraData::structure_presence(
+            KnownStructure::Monument,
+            1232,
+            -432
+        )));
+        assert_eq!(
+            evidence
+                .world
+                .iter()
+                .filter(|d| matches!(d, WorldExtraData::StructurePresence { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_pick_structure() {
+        assert_eq!(cycle_structure(None, true), KnownStructure::Monument);
+        assert_eq!(
+            cycle_structure(Some(KnownStructure::Monument), false),
+            KnownStructure::Bastion
+        );
+        assert_eq!(
+            cycle_structure(Some(KnownStructure::Bastion), true),
+            KnownStructure::Monument
+        );
+
+        let mut shared = ApplicationComponentState::new().shared;
+        let mut state = StructuresTabState::default();
+        let tab = StructuresTabComponent;
+        tab.on_focus(&mut state, &mut shared);
+
+        let mut send = |state: &mut StructuresTabState, event: Event| {
+            tab.handle_event(state, &mut shared, event, EventContext::BubblingDown);
+        };
+        send(&mut state, ctrl(KeyCode::Char('n')));
+        send(&mut state, key(KeyCode::Down));
+        send(&mut state, key(KeyCode::Down));
+        send(&mut state, ctrl(KeyCode::Right));
+        for c in "-1232".chars() {
+            send(&mut state, key(KeyCode::Char(c)));
+        }
+        send(&mut state, ctrl(KeyCode::Right));
+        send(&mut state, key(KeyCode::Char('7')));
+
+        assert_eq!(
+            shared.structure_data.structures,
+            vec![StructureEntry {
+                structure: Some(KnownStructure::Village),
+                block_x: -1232,
+                block_z: 7,
+                enabled: true,
+            }]
+        );
+    }
+}
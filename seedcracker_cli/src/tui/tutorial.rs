@@ -0,0 +1,434 @@
+//! Walks through cracking the self test world, revealing its evidence one stage at a time. The steps only fill in the
+//! evidence and check the results, the user runs the searches with the usual keys.
+
+use mcseedcracker::{
+    cubiomes::enums::BiomeID,
+    features::end_pillars::PillarHeightHint,
+    fingerprint::EvidenceFingerprint,
+    self_test::{
+        BIOME_SAMPLE, BURIED_TREASURE_POS, PILLAR_SEED, PILLARS, STRUCTURE_SEED, WORLD_SEED,
+        buried_treasure_contents,
+    },
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::tui::{
+    application::{ApplicationComponentState, ApplicationTabs, SharedApplicationState},
+    history::EvidenceSnapshot,
+};
+
+/// Overworld biomes of [`WORLD_SEED`], enough to tell it apart from the other world seeds of [`STRUCTURE_SEED`]
+pub const TUTORIAL_BIOMES: [(i32, i32, i32, BiomeID); 3] = [
+    BIOME_SAMPLE,
+    (137, 73, -90, BiomeID::jungle),
+    (-404, 69, -51, BiomeID::beach),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checkpoint {
+    /// The search of the step didn't run or isn't done yet
+    Pending,
+    Passed,
+    /// The search gave another result, usually because the evidence was edited
+    Failed(String),
+}
+
+struct TutorialStep {
+    title: &'static str,
+    text: &'static [&'static str],
+    /// Fills in the evidence of the step when it starts
+    reveal: fn(&mut SharedApplicationState),
+    checkpoint: fn(&ApplicationComponentState) -> Checkpoint,
+}
+
+const STEPS: &[TutorialStep] = &[
+    TutorialStep {
+        title: "End pillars",
+        text: &[
+            "The heights and cages of the 10 End pillars only depend on a 16 bit pillar seed, taken from the world seed.",
+            "The End Pillars tab is filled in with the pillars of the tutorial world, and lists the pillar seeds that match them.",
+            "Press [RIGHT] until the Output tab is selected to see how many are left.",
+        ],
+        reveal: reveal_pillars,
+        checkpoint: pillar_checkpoint,
+    },
+    TutorialStep {
+        title: "Buried treasure",
+        text: &[
+            "One pillar seed is left. The buried treasure loot depends on the lower 48 bits of the world seed, the \
+             structure seed, of which the pillar seed gives 16 bits.",
+            "The Buried Treasure tab is now filled in with a chest of the tutorial world.",
+            "Press [TAB] to enter the Output tab, [TAB] again to select the structure seed search and [ENTER] to start \
+             it. It checks 2^32 candidates, this takes a while.",
+        ],
+        reveal: reveal_buried_treasure,
+        checkpoint: structure_checkpoint,
+    },
+    TutorialStep {
+        title: "Biome samples",
+        text: &[
+            "The structure seed is found. Its 65536 world seeds only differ by their upper 16 bits, which mostly change \
+             the biomes.",
+            "The Biomes tab is now filled in with a few biomes of the tutorial world.",
+            "Press [TAB] to select the world seed search and [ENTER] to start it.",
+        ],
+        reveal: reveal_biomes,
+        checkpoint: world_checkpoint,
+    },
+    TutorialStep {
+        title: "Done",
+        text: &[
+            "The world seed of the tutorial world is found, press [V] on it to list what else it generates.",
+            "Clear the evidence with [CTRL + Z] or restart the TUI to crack your own world.",
+        ],
+        reveal: |_| {},
+        checkpoint: |_| Checkpoint::Pending,
+    },
+];
+
+fn reveal_pillars(shared: &mut SharedApplicationState) {
+    shared.pillars_enabled = true;
+    for (pillar, (height, caged)) in shared.pillar_data.0.iter_mut().zip(PILLARS) {
+        pillar.height = PillarHeightHint::Exact(height);
+        pillar.caged = Some(caged);
+    }
+}
+
+fn reveal_buried_treasure(shared: &mut SharedApplicationState) {
+    let bt = &mut shared.buried_treasure_data;
+    (bt.pos_x, bt.pos_z) = BURIED_TREASURE_POS;
+    bt.luck = 0.0;
+    bt.contents = buried_treasure_contents();
+    bt.counts.clear();
+    bt.area = false;
+    bt.usable = true;
+}
+
+fn reveal_biomes(shared: &mut SharedApplicationState) {
+    if let Some(samples) = shared.biome_data.overworld_biomes.as_overworld_mut() {
+        samples.extend(TUTORIAL_BIOMES);
+    }
+}
+
+fn pillar_checkpoint(app: &ApplicationComponentState) -> Checkpoint {
+    if app.selected_tab != ApplicationTabs::OUTPUT {
+        return Checkpoint::Pending;
+    }
+    let fingerprint = EvidenceFingerprint::of(&app.shared.enabled_pillar_data());
+    match &app.shared.last_pillar_sim {
+        Some((f, seeds)) if *f == fingerprint => match seeds.as_slice() {
+            [(seed, _)] if *seed == PILLAR_SEED => Checkpoint::Passed,
+            seeds => Checkpoint::Failed(format!(
+                "expected pillar seed {} only, {} pillar seed(s) match the pillars",
+                PILLAR_SEED,
+                seeds.len()
+            )),
+        },
+        _ => Checkpoint::Pending,
+    }
+}
+
+fn structure_checkpoint(app: &ApplicationComponentState) -> Checkpoint {
+    let shared = &app.shared;
+    let sim = &shared.last_structure_seed_sim;
+    if shared.current_structure_seed_searcher.is_some()
+        || !shared.structure_seed_search_jobs.is_empty()
+        || sim.outdated_data
+    {
+        return Checkpoint::Pending;
+    }
+    let Some(data) = &sim.data else {
+        return Checkpoint::Pending;
+    };
+    let seeds = data
+        .per_pillar
+        .iter()
+        .flat_map(|p| p.structure_seeds.iter().map(|s| s.seed))
+        .collect::<Vec<_>>();
+    if seeds == [STRUCTURE_SEED] {
+        Checkpoint::Passed
+    } else {
+        Checkpoint::Failed(format!(
+            "expected structure seed {} only, found {:?}",
+            STRUCTURE_SEED, seeds
+        ))
+    }
+}
+
+fn world_checkpoint(app: &ApplicationComponentState) -> Checkpoint {
+    let shared = &app.shared;
+    if shared.current_world_seed_searcher.is_some()
+        || !shared.world_seed_search_jobs.is_empty()
+        || shared.world_seed_sim.per_structure.is_empty()
+    {
+        return Checkpoint::Pending;
+    }
+    let seeds = shared
+        .world_seed_sim
+        .per_structure
+        .iter()
+        .flat_map(|s| s.world_seeds.iter().map(|w| w.seed))
+        .collect::<Vec<_>>();
+    if seeds.contains(&WORLD_SEED) {
+        Checkpoint::Passed
+    } else {
+        Checkpoint::Failed(format!(
+            "expected world seed {}, found {:?}",
+            WORLD_SEED, seeds
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tutorial {
+    step: usize,
+    /// Why the checkpoint of the step failed, shown until it passes
+    failure: Option<String>,
+}
+
+impl Tutorial {
+    /// Starts on the first step, on the tab selector of the first tab
+    pub fn start(app: &mut ApplicationComponentState) -> Self {
+        app.template_picker = None;
+        if !app.focused_on_tab_selector {
+            app.tabs.on_unfocus(app.selected_tab, &mut app.shared);
+            app.focused_on_tab_selector = true;
+        }
+        app.selected_tab = 0;
+        Self::reveal(0, app);
+        Self {
+            step: 0,
+            failure: None,
+        }
+    }
+
+    /// Fills in the evidence of a step as an edit, so it can be undone
+    fn reveal(step: usize, app: &mut ApplicationComponentState) {
+        let before = EvidenceSnapshot::of(&app.shared);
+        (STEPS[step].reveal)(&mut app.shared);
+        app.shared
+            .history
+            .record(before, EvidenceSnapshot::of(&app.shared));
+        app.tabs.sync_from_shared(&mut app.shared);
+    }
+
+    /// Checks the checkpoint of the step, and moves on to the next step once it passed. True if it moved on.
+    pub fn update(&mut self, app: &mut ApplicationComponentState) -> bool {
+        match (STEPS[self.step].checkpoint)(app) {
+            Checkpoint::Pending => false,
+            Checkpoint::Failed(failure) => {
+                self.failure = Some(failure);
+                false
+            }
+            Checkpoint::Passed => {
+                self.failure = None;
+                self.step += 1;
+                Self::reveal(self.step, app);
+                true
+            }
+        }
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let step = &STEPS[self.step];
+        let mut lines = Vec::new();
+        for text in step.text {
+            lines.push(Line::from(*text));
+            lines.push(Line::default());
+        }
+        if let Some(failure) = &self.failure {
+            lines.push(
+                Line::from(format!("Unexpected result: {}", failure))
+                    .style(Style::default().fg(Color::Red).bold()),
+            );
+        }
+
+        let width = (area.width / 2).max(40).min(area.width);
+        // the wrapped lines and the borders
+        let inner_width = width.saturating_sub(2).max(1);
+        let height = lines
+            .iter()
+            .map(|line| (line.width() as u16).div_ceil(inner_width).max(1))
+            .sum::<u16>()
+            .saturating_add(2)
+            .min(area.height);
+        let popup_area = Rect::new(
+            area.x + area.width - width,
+            area.y + area.height - height,
+            width,
+            height,
+        );
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(Color::LightGreen))
+                    .title(format!(
+                        "Tutorial {}/{}: {}",
+                        self.step + 1,
+                        STEPS.len(),
+                        step.title
+                    ))
+                    .title_bottom("[CTRL + T] Leave the tutorial")
+                    .title_alignment(Alignment::Center),
+            )
+            .render(popup_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use mcseedcracker::{
+        candidates::StructureSeedCandidate,
+        features::end_pillars::PillarHeightHint,
+        rejections::RejectionStats,
+        self_test::{PILLARS, STRUCTURE_SEARCH_SLICE, STRUCTURE_SEED, WORLD_SEED},
+    };
+    use ratatui::{
+        buffer::Buffer,
+        crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+        layout::Rect,
+    };
+
+    use crate::{
+        tui::{
+            EventContext,
+            application::{
+                ApplicationComponent, ApplicationComponentState, ApplicationTabs,
+                PillarSeedStructureSim, StructureSeedSim, StructureSeedSimResultType,
+            },
+        },
+        tui_handler::poll_searches,
+    };
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn send(app: &mut ApplicationComponentState, event: Event) {
+        ApplicationComponent::handle_event(app, event, EventContext::BubblingDown);
+    }
+
+    /// One frame of the TUI loop
+    fn frame(app: &mut ApplicationComponentState) -> Buffer {
+        let area = Rect::new(0, 0, 160, 45);
+        let mut buf = Buffer::empty(area);
+        ApplicationComponent::render(area, &mut buf, app);
+        poll_searches(app);
+        app.update_tutorial();
+        buf
+    }
+
+    fn step(app: &ApplicationComponentState) -> usize {
+        app.tutorial.as_ref().unwrap().step
+    }
+
+    fn screen(buf: &Buffer) -> String {
+        buf.content.iter().map(|c| c.symbol()).collect()
+    }
+
+    #[test]
+    fn test_tutorial_walkthrough() {
+        let mut app = ApplicationComponentState::new();
+        app.start_tutorial();
+        assert!(screen(&frame(&mut app)).contains("Tutorial 1/4: End pillars"));
+        assert_eq!(step(&app), 0);
+
+        for _ in 0..ApplicationTabs::OUTPUT {
+            send(&mut app, key(KeyCode::Right));
+        }
+        // the output tab computes the pillar seeds when it is drawn
+        frame(&mut app);
+        assert_eq!(step(&app), 1);
+        assert!(app.shared.buried_treasure_data.usable);
+
+        send(&mut app, key(KeyCode::Tab));
+        send(&mut app, key(KeyCode::Tab));
+        send(&mut app, key(KeyCode::Enter));
+        assert!(app.shared.last_structure_seed_sim.outdated_data);
+        // the whole search takes too long for a test, search the slice with the structure seed instead
+        let job = app.shared.structure_seed_search_jobs.pop_front().unwrap();
+        assert!(app.shared.structure_seed_search_jobs.is_empty());
+        let structure_seeds = job.search_bounded(STRUCTURE_SEARCH_SLICE);
+        assert_eq!(structure_seeds, [STRUCTURE_SEED]);
+        app.shared.last_structure_seed_sim.data = Some(StructureSeedSim {
+            count_seeds: 1,
+            per_pillar: vec![PillarSeedStructureSim {
+                pillar_seed: job.pillar_seed,
+                result: StructureSeedSimResultType::Success,
+                structure_seeds: structure_seeds
+                    .iter()
+                    .map(|&seed| {
+                        StructureSeedCandidate::new(
+                            &job.data,
+                            seed,
+                            job.pillar_seed,
+                            job.pillar_chance,
+                        )
+                    })
+                    .collect(),
+                rejections: RejectionStats::default(),
+            }],
+        });
+        app.shared.last_structure_seed_sim.outdated_data = false;
+        frame(&mut app);
+        assert_eq!(step(&app), 2);
+
+        send(&mut app, key(KeyCode::Tab));
+        send(&mut app, key(KeyCode::Enter));
+        let deadline = Instant::now() + Duration::from_secs(600);
+        while step(&app) == 2 {
+            assert!(
+                Instant::now() < deadline,
+                "the world seed search didn't end"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+            frame(&mut app);
+        }
+        assert_eq!(step(&app), 3);
+        assert!(app.tutorial.as_ref().unwrap().failure.is_none());
+        assert!(
+            app.shared
+                .world_seed_sim
+                .per_structure
+                .iter()
+                .any(|s| s.world_seeds.iter().any(|w| w.seed == WORLD_SEED))
+        );
+        assert!(screen(&frame(&mut app)).contains("Tutorial 4/4: Done"));
+
+        send(
+            &mut app,
+            Event::Key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL)),
+        );
+        assert!(app.tutorial.is_none());
+    }
+
+    #[test]
+    fn test_tutorial_failed_checkpoint() {
+        let mut app = ApplicationComponentState::new();
+        app.start_tutorial();
+        // a wrong height, the pillar seed of the tutorial world doesn't match anymore
+        app.shared.pillar_data.0[0].height = PillarHeightHint::Exact(PILLARS[0].0 + 3);
+        app.tabs.sync_from_shared(&mut app.shared);
+
+        frame(&mut app);
+        assert!(app.tutorial.as_ref().unwrap().failure.is_none());
+        for _ in 0..ApplicationTabs::OUTPUT {
+            send(&mut app, key(KeyCode::Right));
+        }
+        frame(&mut app);
+        assert_eq!(step(&app), 0);
+        assert!(app.tutorial.as_ref().unwrap().failure.is_some());
+        assert!(screen(&frame(&mut app)).contains("Unexpected result"));
+    }
+}
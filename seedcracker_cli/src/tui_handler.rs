@@ -1,9 +1,18 @@
 use std::{
     io::{self, stdout},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use mcseedcracker::search::Status;
+use mcseedcracker::{
+    candidates::rank_structure_candidates,
+    cooperative::{Cooperation, SearchOptions},
+    loot_table::SlotThreshold,
+    pipeline::CrackingPipeline,
+    rejections::RejectionStats,
+    schedule::EarlyTermination,
+    search::{SearchOutcome, Status, StructureSeedSearch},
+    world_seed::SeedOrigin,
+};
 use ratatui::{
     Terminal,
     crossterm::{
@@ -14,26 +23,70 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use crate::tui::{
-    EventContext,
-    application::{
-        ApplicationComponent, ApplicationComponentState, PillarSeedStructureSim, StructureSeedSim,
-        StructureSeedSimResultType, StructureSeedWorldSim, WorldSeedSimResultType,
+use crate::{
+    loot_override::LootTableOverrides,
+    tui::{
+        EventContext,
+        application::{
+            ApplicationComponent, ApplicationComponentState, PillarSeedStructureSim,
+            SharedApplicationState, StructureSeedSim, StructureSeedSimResultType,
+            StructureSeedWorldSim, WorldSeedSimResultType,
+        },
+        history::EvidenceSnapshot,
+        recovery::{AutoSave, recovery_path},
     },
 };
 
-pub fn run_tui() -> Result<(), io::Error> {
+/// The TUI settings of the command line, see [`crate::Cli`]
+pub struct TuiOptions {
+    pub tutorial: bool,
+    /// In bytes
+    pub memory_cap: Option<usize>,
+    pub cooperation: Option<Cooperation>,
+    pub search: SearchOptions,
+    pub slot_threshold: Option<SlotThreshold>,
+    pub biome_row_cap: usize,
+    pub early_typed_hits: bool,
+    pub seed_origin: SeedOrigin,
+    pub early_termination: EarlyTermination,
+    /// In chunks, see [`mcseedcracker::pipeline::PipelineOptions::max_treasure_area`]
+    pub max_treasure_area: u64,
+}
+
+pub fn run_tui(overrides: LootTableOverrides, options: TuiOptions) -> Result<(), io::Error> {
+    let tutorial = options.tutorial;
     let mut stdout = stdout();
     crossterm::terminal::enable_raw_mode()?;
     crossterm::execute!(
         stdout,
         crossterm::terminal::EnterAlternateScreen,
-        crossterm::event::EnableMouseCapture
+        crossterm::event::EnableMouseCapture,
+        crossterm::event::EnableBracketedPaste
     )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let mut app_state = ApplicationComponentState::new();
+    app_state.shared.buried_treasure_loot_table = overrides.buried_treasure;
+    app_state.shared.memory.cap = options.memory_cap;
+    app_state.shared.cooperation = options.cooperation;
+    app_state.shared.search_options = options.search;
+    app_state.shared.slot_threshold = options.slot_threshold;
+    app_state.shared.biome_row_cap = options.biome_row_cap;
+    app_state.shared.early_typed_hits = options.early_typed_hits;
+    app_state.shared.seed_origin = options.seed_origin;
+    app_state.shared.early_termination = options.early_termination;
+    app_state.shared.max_treasure_area = options.max_treasure_area;
+    // the tutorial evidence isn't worth recovering, and must not replace a leftover recovery file
+    let mut autosave = if tutorial {
+        None
+    } else {
+        recovery_path().map(AutoSave::start)
+    };
+    app_state.recovery = autosave.as_ref().and_then(AutoSave::leftover);
+    if tutorial {
+        app_state.start_tutorial();
+    }
 
     'app: loop {
         terminal.draw(|f| {
@@ -57,105 +110,190 @@ pub fn run_tui() -> Result<(), io::Error> {
             ApplicationComponent::handle_event(&mut app_state, event, EventContext::BubblingDown);
         }
 
-        if let Some(searcher) = &app_state.shared.current_structure_seed_searcher {
-            if searcher.is_done() {
-                let status: Status = searcher.get_status();
-                let restype = match status {
-                    Status::Searching => unreachable!(),
-                    Status::Complete { .. } => StructureSeedSimResultType::Success,
-                    Status::Cancelled { .. } => StructureSeedSimResultType::Cancelled,
-                    Status::TooManySeeds { .. } => StructureSeedSimResultType::TooManySeeds,
-                };
-                match status {
-                    Status::Searching => unreachable!(),
-                    Status::Complete { seeds }
-                    | Status::Cancelled {
-                        seeds_incomplete: seeds,
-                    }
-                    | Status::TooManySeeds {
-                        seeds_incomplete: seeds,
-                    } => {
-                        let stateref = &mut app_state.shared.last_structure_seed_sim.data;
-                        match stateref {
-                            None => {
-                                *stateref = Some(StructureSeedSim {
-                                    count_seeds: seeds.len() as i64,
-                                    per_pillar: vec![PillarSeedStructureSim {
-                                        pillar_seed: searcher.get_pillar_seed(),
-                                        result: restype,
-                                        structure_seeds: seeds,
-                                    }],
-                                });
-                            }
-                            Some(v) => {
-                                v.count_seeds += seeds.len() as i64;
-                                v.per_pillar.push(PillarSeedStructureSim {
-                                    pillar_seed: searcher.get_pillar_seed(),
-                                    result: restype,
-                                    structure_seeds: seeds,
-                                });
-                            }
-                        }
-                    }
-                }
-
-                app_state.shared.current_structure_seed_searcher = None;
-                if app_state.shared.structure_seed_search_jobs.is_empty() {
-                    app_state.shared.last_structure_seed_sim.outdated_data = false;
-                }
-            }
+        poll_searches(&mut app_state);
+        app_state.shared.enforce_memory_cap();
+        app_state.update_tutorial();
+        if let Some(autosave) = &mut autosave {
+            let changed = app_state.shared.history.take_changed();
+            autosave.update(changed, Instant::now(), || {
+                EvidenceSnapshot::of(&app_state.shared)
+            });
         }
+    }
+
+    // Cleanup terminal
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::terminal::LeaveAlternateScreen,
+        crossterm::event::DisableMouseCapture,
+        crossterm::event::DisableBracketedPaste
+    )?;
+    terminal.show_cursor()?;
 
-        if app_state.shared.current_structure_seed_searcher.is_none() {
-            if let Some(job) = app_state.shared.structure_seed_search_jobs.pop_front() {
-                app_state.shared.current_structure_seed_searcher = Some(job.spawn_multithreaded());
+    // an unanswered recovery prompt keeps the file for the next start
+    match autosave {
+        Some(autosave) if app_state.recovery.is_none() => autosave.finish(),
+        _ => Ok(()),
+    }
+}
+
+/// Starts the next queued structure seed search, unless the early termination policy triggered: the queued pillar
+/// seeds are then listed as skipped
+fn start_structure_job(shared: &mut SharedApplicationState) {
+    let sim = &mut shared.last_structure_seed_sim;
+    match sim.schedule.as_mut().map(|s| (s.next_slice(), s.policy)) {
+        Some((None, policy)) => {
+            let skipped =
+                shared
+                    .structure_seed_search_jobs
+                    .drain()
+                    .map(|job| PillarSeedStructureSim {
+                        pillar_seed: job.pillar_seed,
+                        result: StructureSeedSimResultType::Skipped(policy),
+                        structure_seeds: Vec::new(),
+                        rejections: RejectionStats::default(),
+                    });
+            sim.data
+                .get_or_insert_with(|| StructureSeedSim {
+                    count_seeds: 0,
+                    per_pillar: Vec::new(),
+                })
+                .per_pillar
+                .extend(skipped);
+            sim.outdated_data = false;
+        }
+        slice => {
+            if let Some(job) = shared.structure_seed_search_jobs.pop_front() {
+                sim.searching = slice.and_then(|s| s.0);
+                shared.current_structure_seed_searcher = Some(StructureSeedSearch::start(job));
             }
         }
+    }
+}
 
-        if let Some(searcher) = &app_state.shared.current_world_seed_searcher {
-            if searcher.is_done() {
-                let status: Status = searcher.get_status();
-                let restype = match status {
-                    Status::Searching => unreachable!(),
-                    Status::Complete { .. } => WorldSeedSimResultType::Success,
-                    Status::Cancelled { .. } => unreachable!(),
-                    Status::TooManySeeds { .. } => WorldSeedSimResultType::TooManySeeds,
-                };
-                match status {
-                    Status::Searching | Status::Cancelled { .. } => unreachable!(),
-                    Status::Complete { seeds }
-                    | Status::TooManySeeds {
-                        seeds_incomplete: seeds,
-                    } => {
-                        let v = &mut app_state.shared.world_seed_sim;
-                        v.count_seeds += seeds.len() as i64;
-                        v.per_structure.push(StructureSeedWorldSim {
-                            structure_seed: searcher.get_structure_seed(),
+/// Collects the results of the finished searches and starts the next queued ones
+pub fn poll_searches(app_state: &mut ApplicationComponentState) {
+    if let Some(searcher) = &app_state.shared.current_structure_seed_searcher {
+        if let Some(outcome) = searcher.outcome() {
+            let restype = match outcome {
+                SearchOutcome::Complete(_) => StructureSeedSimResultType::Success,
+                // a search that panicked found nothing, like one cancelled right away
+                SearchOutcome::Cancelled(_) | SearchOutcome::Failed => {
+                    StructureSeedSimResultType::Cancelled
+                }
+                SearchOutcome::TooManySeeds(_) => StructureSeedSimResultType::TooManySeeds,
+            };
+            let mut seeds = searcher.candidates(outcome.seeds());
+            rank_structure_candidates(&mut seeds);
+            let rejections = searcher.rejections();
+            let shared = &app_state.shared;
+            if shared.early_typed_hits {
+                let evidence = shared.cracking_evidence();
+                let options = shared.pipeline_options();
+                let hits = seeds
+                    .iter()
+                    .flat_map(|s| CrackingPipeline::early_typed_hits(&evidence, &options, s.seed))
+                    .collect::<Vec<_>>();
+                app_state
+                    .shared
+                    .last_structure_seed_sim
+                    .early_hits
+                    .record(hits);
+            }
+            let shared = &app_state.shared;
+            let strengths = shared.last_structure_seed_sim.schedule.is_some().then(|| {
+                let evidence = shared.cracking_evidence();
+                let options = shared.pipeline_options();
+                seeds
+                    .iter()
+                    .map(|s| CrackingPipeline::match_strength(&evidence, &options, s.seed))
+                    .collect::<Vec<_>>()
+            });
+            let sim = &mut app_state.shared.last_structure_seed_sim;
+            if let (Some(schedule), Some(slice), Some(strengths)) =
+                (&mut sim.schedule, sim.searching.take(), strengths)
+            {
+                schedule.record(&slice, &strengths);
+            }
+            let stateref = &mut app_state.shared.last_structure_seed_sim.data;
+            match stateref {
+                None => {
+                    *stateref = Some(StructureSeedSim {
+                        count_seeds: seeds.len() as i64,
+                        per_pillar: vec![PillarSeedStructureSim {
+                            pillar_seed: searcher.pillar_seed(),
                             result: restype,
-                            world_seeds: seeds,
-                        });
-                    }
+                            structure_seeds: seeds,
+                            rejections,
+                        }],
+                    });
+                }
+                Some(v) => {
+                    v.count_seeds += seeds.len() as i64;
+                    v.per_pillar.push(PillarSeedStructureSim {
+                        pillar_seed: searcher.pillar_seed(),
+                        result: restype,
+                        structure_seeds: seeds,
+                        rejections,
+                    });
                 }
+            }
 
-                app_state.shared.current_world_seed_searcher = None;
+            app_state.shared.current_structure_seed_searcher = None;
+            if app_state.shared.structure_seed_search_jobs.is_empty() {
+                app_state.shared.last_structure_seed_sim.outdated_data = false;
             }
         }
+    }
+
+    if app_state.shared.current_structure_seed_searcher.is_none()
+        && !app_state.shared.structure_seed_search_jobs.is_empty()
+    {
+        start_structure_job(&mut app_state.shared);
+    }
 
-        if app_state.shared.current_world_seed_searcher.is_none() {
-            if let Some(job) = app_state.shared.world_seed_search_jobs.pop_front() {
-                app_state.shared.current_world_seed_searcher = Some(job.spawn_multithreaded());
+    if let Some(searcher) = &app_state.shared.current_world_seed_searcher {
+        if searcher.is_done() {
+            let status: Status = searcher.get_status();
+            let restype = match status {
+                Status::Searching => unreachable!(),
+                Status::Complete { .. } => WorldSeedSimResultType::Success,
+                Status::Cancelled { .. } => unreachable!(),
+                Status::TooManySeeds { .. } => WorldSeedSimResultType::TooManySeeds,
+            };
+            match status {
+                Status::Searching | Status::Cancelled { .. } => unreachable!(),
+                Status::Complete { seeds }
+                | Status::TooManySeeds {
+                    seeds_incomplete: seeds,
+                } => {
+                    let world_seeds = searcher.get_candidates(&seeds);
+                    app_state
+                        .shared
+                        .last_structure_seed_sim
+                        .early_hits
+                        .refound(&world_seeds);
+                    let v = &mut app_state.shared.world_seed_sim;
+                    v.count_seeds += seeds.len() as i64;
+                    v.per_structure.push(StructureSeedWorldSim {
+                        structure_seed: searcher.get_structure_seed(),
+                        result: restype,
+                        world_seeds,
+                        skipped_candidates: searcher.get_skipped_candidates(),
+                        fits: searcher.get_fits(),
+                        rejections: searcher.get_rejections(),
+                    });
+                }
             }
+
+            app_state.shared.current_world_seed_searcher = None;
         }
     }
 
-    // Cleanup terminal
-    crossterm::terminal::disable_raw_mode()?;
-    crossterm::execute!(
-        terminal.backend_mut(),
-        crossterm::terminal::LeaveAlternateScreen,
-        crossterm::event::DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    Ok(())
+    if app_state.shared.current_world_seed_searcher.is_none() {
+        if let Some(job) = app_state.shared.world_seed_search_jobs.pop_front() {
+            app_state.shared.current_world_seed_searcher = Some(job.spawn_multithreaded());
+        }
+    }
 }
@@ -90,5 +90,46 @@ fn reverse_pillar_seed(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, reverse_pillar_seed);
+fn seed_results_forms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seed_results_forms");
+
+    let mut real_pillars = EndPillars::new();
+    real_pillars.from_seed(13847);
+
+    let mut partial_input = PartialEndPillars::new();
+    for (ppillar, pillar) in partial_input.iter_mut().zip(real_pillars.iter()) {
+        if pillar.height >= 97 {
+            ppillar.height = PillarHeightHint::Big;
+        }
+    }
+
+    group.bench_with_input("vec", &partial_input, |b, input| {
+        b.iter(|| {
+            input
+                .seed_results()
+                .into_iter()
+                .filter(|(_, r)| !r.is_impossible_match())
+                .count()
+        })
+    });
+    group.bench_with_input("iter", &partial_input, |b, input| {
+        b.iter(|| {
+            input
+                .seed_results_iter()
+                .filter(|(_, r)| !r.is_impossible_match())
+                .count()
+        })
+    });
+    group.bench_with_input("for_each_matching", &partial_input, |b, input| {
+        b.iter(|| {
+            let mut count = 0;
+            input.for_each_matching(|_, _| count += 1);
+            count
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, reverse_pillar_seed, seed_results_forms);
 criterion_main!(benches);
@@ -366,7 +366,11 @@ pub fn reverse_seed_from_pillars_and_buried_treasure_benchmark(c: &mut Criterion
 
     let input = Input {
         pillars,
-        buried_treasure: buried_treasure::build_fast_inventory_compare_context(buried_treasure),
+        buried_treasure: buried_treasure::build_fast_inventory_compare_context(
+            buried_treasure,
+            &buried_treasure::get_loot_table(),
+        )
+        .unwrap(),
         buried_trasure_block_coords,
         biomes_coords,
     };
@@ -0,0 +1,47 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use cubiomes::enums::BiomeID;
+use mcseedcracker::search::{WorldExtraData, WorldSeedSearchData};
+
+/// Common biomes first, the rare one that rejects almost every seed last
+fn mixed_constraints() -> Vec<WorldExtraData> {
+    vec![
+        WorldExtraData::OverworldBiomeData(vec![
+            (400, 60, 800, BiomeID::beach),
+            (137, 73, -90, BiomeID::jungle),
+            (-404, 69, -51, BiomeID::beach),
+            (1200, 64, 300, BiomeID::plains),
+            (-800, 64, 1600, BiomeID::forest),
+            (2000, 63, -2000, BiomeID::ocean),
+            (-1500, 63, -700, BiomeID::river),
+            (600, 64, -1300, BiomeID::taiga),
+            (-2400, 64, 2400, BiomeID::mushroom_fields),
+        ]),
+        WorldExtraData::NetherBiomeData(vec![(100, 64, 100, BiomeID::nether_wastes)]),
+    ]
+}
+
+fn world_seed_constraint_order(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_seed_constraint_order");
+
+    let entry_order = WorldSeedSearchData::new(180066252004364, false, mixed_constraints(), 5);
+    let mut selectivity_order =
+        WorldSeedSearchData::new(180066252004364, false, mixed_constraints(), 5);
+    selectivity_order.order_constraints();
+
+    assert_eq!(
+        entry_order.search_bounded(39900..40000),
+        selectivity_order.search_bounded(39900..40000)
+    );
+
+    group.bench_function("entry_order", |b| {
+        b.iter(|| entry_order.search_bounded(39900..40000))
+    });
+    group.bench_function("selectivity_order", |b| {
+        b.iter(|| selectivity_order.search_bounded(39900..40000))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, world_seed_constraint_order);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+// Writes float_vectors.txt with java.util.Random and the float expressions of 1.16.5:
+//     java FloatVectors.java > float_vectors.txt
+import java.util.Random;
+
+public class FloatVectors {
+    static final long MULTIPLIER = 0x5DEECE66DL;
+    static final long INVERSE_MULTIPLIER = 0xDFE05BCB1365L;
+    static final long MASK = (1L << 48) - 1;
+    static final int SALT = 10387320;
+
+    static long regionOffset(int regionX, int regionZ) {
+        return regionX * 341873128712L + regionZ * 132897987541L + SALT;
+    }
+
+    // MathHelper.floor
+    static int floor(float value) {
+        int i = (int) value;
+        return value < (float) i ? i - 1 : i;
+    }
+
+    /// A seed whose first nextFloat() is raw / 2^24
+    static long seedWithFloatBits(int raw, long lowBits) {
+        long state = ((long) raw << 24 | (lowBits & 0xFFFFFF)) & MASK;
+        long previous = ((state - 0xBL) * INVERSE_MULTIPLIER) & MASK;
+        return previous ^ MULTIPLIER;
+    }
+
+    public static void main(String[] args) {
+        Random cases = new Random(20251014L);
+        StringBuilder out = new StringBuilder();
+        out.append("# Generated by FloatVectors.java with java.util.Random, do not edit\n");
+        out.append("# float <seed> <next(24) of the first nextFloat()> <bits of the first nextFloat()>\n");
+        out.append("# placement <world seed> <region x> <region z> <nextFloat() < 0.01F of the buried treasure region seed>\n");
+        out.append("# damage <seed> <durability> <min> <max> <floor((1 - uniform(min, max)) * durability)>\n");
+        out.append("# weight <weight> <quality> <luck> <max(floor(weight + quality * luck), 0)>\n");
+
+        for (int i = 0; i < 1000; i++) {
+            long seed = cases.nextLong();
+            Random r = new Random(seed);
+            float f = r.nextFloat();
+            out.append(String.format("float %d %d %08x\n", seed, (int) (f * (1 << 24)),
+                    Float.floatToIntBits(f)));
+        }
+
+        for (int i = 0; i < 1000; i++) {
+            long worldSeed = cases.nextLong();
+            int regionX = cases.nextInt(117188) - 58594;
+            int regionZ = cases.nextInt(117188) - 58594;
+            if (i % 2 == 0) {
+                // half of the cases are close to the threshold, nextFloat() < 0.01F is rare otherwise
+                int raw = 167772 + cases.nextInt(9) - 4;
+                worldSeed = seedWithFloatBits(raw, cases.nextLong()) - regionOffset(regionX, regionZ);
+                Random check = new Random(worldSeed + regionOffset(regionX, regionZ));
+                if ((int) (check.nextFloat() * (1 << 24)) != raw) {
+                    throw new AssertionError();
+                }
+            }
+            boolean generates = new Random(worldSeed + regionOffset(regionX, regionZ)).nextFloat() < 0.01F;
+            out.append(String.format("placement %d %d %d %b\n", worldSeed, regionX, regionZ, generates));
+        }
+
+        int[] durabilities = {33, 59, 131, 250, 1561, 2031};
+        float[][] ranges = {{0.0F, 1.0F}, {0.15F, 0.8F}, {0.2F, 0.9F}, {0.05F, 0.95F}, {0.5F, 0.5F}};
+        for (int i = 0; i < 1000; i++) {
+            long seed = cases.nextLong();
+            int durability = durabilities[cases.nextInt(durabilities.length)];
+            float[] range = ranges[cases.nextInt(ranges.length)];
+            float min = range[0];
+            float max = range[1];
+            Random r = new Random(seed);
+            float f = 1.0F - (min >= max ? min : r.nextFloat() * (max - min) + min);
+            out.append(String.format("damage %d %d %s %s %d\n", seed, durability, Float.toString(min),
+                    Float.toString(max), floor(f * (float) durability)));
+        }
+
+        float[] lucks = {0.0F, 1.0F, -1.0F, 0.1F, 0.5F, 1.5F, -2.25F, 3.7F, 1024.3F, 1.0E-7F};
+        for (int i = 0; i < 300; i++) {
+            int weight = cases.nextInt(100) + 1;
+            int quality = cases.nextInt(9) - 3;
+            float luck = lucks[cases.nextInt(lucks.length)];
+            out.append(String.format("weight %d %d %s %d\n", weight, quality, Float.toString(luck),
+                    Math.max(floor((float) weight + (float) quality * luck), 0)));
+        }
+
+        System.out.print(out);
+    }
+}
@@ -0,0 +1,55 @@
+//! The pillar seeds of a few observed end pillars, the first step of every crack.
+//!
+//! ```text
+//! cargo run --release --example pillar_crack
+//! ```
+
+use mcseedcracker::{
+    features::end_pillars::{PartialEndPillar, PartialEndPillars, PillarHeightHint},
+    self_test::{PILLAR_SEED, PILLARS},
+};
+
+/// The pillar seeds that can give the observed pillars
+fn pillar_seeds(pillars: &PartialEndPillars) -> Vec<i64> {
+    let mut seeds = Vec::new();
+    pillars.for_each_matching(|seed, _| seeds.push(seed));
+    seeds
+}
+
+/// The first pillars of the self test world, the others were not looked at
+fn observed_pillars(count: usize) -> PartialEndPillars {
+    let mut pillars = PartialEndPillars::new();
+    for (observed, (height, caged)) in pillars.0.iter_mut().zip(PILLARS).take(count) {
+        *observed = PartialEndPillar::new(Some(caged), PillarHeightHint::Exact(height));
+    }
+    pillars
+}
+
+fn main() {
+    for count in [3, 5, 10] {
+        let seeds = pillar_seeds(&observed_pillars(count));
+        println!(
+            "{} pillar(s) observed: {} pillar seed(s)",
+            count,
+            seeds.len()
+        );
+        if seeds.len() <= 10 {
+            println!("  {:?}", seeds);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_more_pillars_fewer_seeds() {
+        let (few, all) = (
+            pillar_seeds(&observed_pillars(3)),
+            pillar_seeds(&observed_pillars(10)),
+        );
+        assert!(few.contains(&PILLAR_SEED) && all.contains(&PILLAR_SEED));
+        assert!(all.len() <= few.len());
+    }
+}
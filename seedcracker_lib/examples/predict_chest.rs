@@ -0,0 +1,52 @@
+//! The buried treasures of a world seed around spawn, with the contents of their chests. Only the lower 48 bits of
+//! the world seed matter, its structure seed gives the same chests.
+//!
+//! ```text
+//! cargo run --release --example predict_chest
+//! ```
+
+use mcseedcracker::{
+    features::buried_treasure::{ChunkArea, get_buried_treasure, item_registry},
+    loot_table::SingleChest,
+    self_test::WORLD_SEED,
+    tooltip::{ChestDump, LanguageTable},
+};
+
+/// The chunks of each buried treasure of the area, with its chest
+fn predict(world_seed: i64, area: &ChunkArea) -> Vec<((i32, i32), SingleChest)> {
+    area.treasure_chunks(world_seed)
+        .into_iter()
+        .map(|chunk| (chunk, get_buried_treasure(world_seed, chunk, 0.0)))
+        .collect()
+}
+
+fn main() {
+    let (registry, language) = (item_registry(), LanguageTable::en_us());
+    let area = ChunkArea::new((-64, -64), (64, 64));
+    for ((x, z), chest) in predict(WORLD_SEED, &area) {
+        println!("Buried treasure at {} ~ {}", x * 16 + 9, z * 16 + 9);
+        println!("{}", ChestDump::new(&chest, &registry, &language));
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::{
+        math::Math,
+        self_test::{BURIED_TREASURE_POS, buried_treasure_contents},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_predict() {
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        let predicted = predict(WORLD_SEED, &ChunkArea::new((20, 45), (30, 55)));
+        let chest = predicted
+            .iter()
+            .find(|(at, _)| *at == chunk)
+            .map(|(_, chest)| chest);
+        assert_eq!(chest, Some(&buried_treasure_contents()));
+    }
+}
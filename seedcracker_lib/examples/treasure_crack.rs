@@ -0,0 +1,66 @@
+//! The structure seed of a pillar seed and a buried treasure chest. The full search covers 2^32 candidates, this one
+//! only the slice holding the answer so it ends in seconds.
+//!
+//! ```text
+//! cargo run --release --example treasure_crack
+//! ```
+
+use mcseedcracker::{
+    features::buried_treasure::{build_fast_inventory_compare_context, get_loot_table},
+    math::Math,
+    search::{SearchOutcome, StructureData, StructureSeedSearch, StructureSeedSearchData},
+    self_test::{
+        BURIED_TREASURE_POS, PILLAR_SEED, STRUCTURE_SEARCH_SLICE, buried_treasure_contents,
+    },
+};
+
+fn crack() -> SearchOutcome {
+    let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+    let contents =
+        build_fast_inventory_compare_context(buried_treasure_contents(), &get_loot_table())
+            .expect("the chest can't come from the loot table");
+    let data = StructureSeedSearchData::new(
+        PILLAR_SEED,
+        vec![StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            contents,
+            loot_table: None,
+        }],
+        10,
+    )
+    .with_state_hi(STRUCTURE_SEARCH_SLICE);
+
+    let handle = StructureSeedSearch::start(data);
+    while !handle.is_done() {
+        let (searched, total) = handle.progress();
+        eprint!("\r{}/{} candidates", searched, total);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    eprintln!();
+    handle.join()
+}
+
+fn main() {
+    match crack() {
+        SearchOutcome::Complete(seeds) => {
+            for seed in seeds {
+                println!("Structure seed: {}", seed);
+            }
+        }
+        outcome => println!("The search didn't complete: {:?}", outcome),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::self_test::STRUCTURE_SEED;
+
+    use super::*;
+
+    #[test]
+    fn test_crack() {
+        assert_eq!(crack(), SearchOutcome::Complete(vec![STRUCTURE_SEED]));
+    }
+}
@@ -0,0 +1,53 @@
+//! The world seeds of a structure seed, checked against a biome sample with cubiomes. A world seed generated by the
+//! game comes from a nextLong call, which leaves a few candidates instead of 65536.
+//!
+//! ```text
+//! cargo run --release --example world_verify
+//! ```
+
+use mcseedcracker::{
+    search::{WorldExtraData, WorldSeedFit, WorldSeedSearchData},
+    self_test::{BIOME_SAMPLE, STRUCTURE_SEED},
+};
+
+fn search() -> WorldSeedSearchData {
+    WorldSeedSearchData::new(
+        STRUCTURE_SEED,
+        true,
+        vec![WorldExtraData::OverworldBiomeData(vec![BIOME_SAMPLE])],
+        5,
+    )
+}
+
+fn world_seeds() -> Vec<i64> {
+    search()
+        .spawn_multithreaded()
+        .join()
+        .expect("the search thread panicked")
+}
+
+fn main() {
+    let search = search();
+    let seeds = world_seeds();
+    println!(
+        "{} world seed(s) of structure seed {}",
+        seeds.len(),
+        STRUCTURE_SEED
+    );
+    for seed in seeds {
+        let fit = WorldSeedFit::measure(seed, &search.data, search.version);
+        println!("  {} fits the sample at {:.2}", seed, fit.score());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mcseedcracker::self_test::WORLD_SEED;
+
+    use super::*;
+
+    #[test]
+    fn test_world_seeds() {
+        assert!(world_seeds().contains(&WORLD_SEED));
+    }
+}
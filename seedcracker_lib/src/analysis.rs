@@ -0,0 +1,716 @@
+//! Reverse lookup of a cracked world seed: the evidence a player could collect around a position to confirm it.
+//!
+//! Structure placement uses the 1.16 random spread constants, the version is only passed to cubiomes for the biome and
+//! viability checks.
+
+use std::{
+    fmt::Display,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use cubiomes::{
+    enums::{BiomeID, Dimension, MCVersion, StructureType},
+    generator::{BlockPosition, Generator, GeneratorFlags},
+};
+
+use crate::{
+    calibration::{Calibration, WORLD_CALIBRATION_SAMPLES},
+    features::{buried_treasure, end_pillars::EndPillars, monument},
+    random::random_with_region_seed,
+    rarity::BiomeRarityTable,
+    search::{WorldExtraData, order_by_selectivity},
+    world_seed::is_typed_numeric,
+};
+
+/// Larger radii are clamped, the scan grows with the square of the radius
+pub const MAX_DESCRIBE_RADIUS: i32 = 2048;
+/// Only the closest features of each kind are kept
+pub const MAX_FEATURES_PER_KIND: usize = 64;
+/// Distance in blocks between two biome samples of the summary
+pub const BIOME_SAMPLE_STEP: i32 = 64;
+/// Biome samples of a dimension past this many rarely narrow the world seeds down further, see
+/// [`BiomeRowEstimate::past_cap`]
+pub const DEFAULT_BIOME_ROW_CAP: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureKind {
+    BuriedTreasure,
+    Shipwreck,
+    Monument,
+}
+
+impl FeatureKind {
+    pub const ALL: [FeatureKind; 3] = [Self::BuriedTreasure, Self::Shipwreck, Self::Monument];
+
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::BuriedTreasure => "buried_treasure",
+            Self::Shipwreck => "shipwreck",
+            Self::Monument => "monument",
+        }
+    }
+
+    #[inline]
+    const fn structure_type(self) -> StructureType {
+        match self {
+            Self::BuriedTreasure => StructureType::Treasure,
+            Self::Shipwreck => StructureType::Shipwreck,
+            Self::Monument => StructureType::Monument,
+        }
+    }
+
+    #[inline]
+    const fn hint(self) -> &'static str {
+        match self {
+            Self::BuriedTreasure => "Buried treasure at",
+            Self::Shipwreck => "Shipwreck near",
+            Self::Monument => "Ocean monument near",
+        }
+    }
+}
+
+/// Random spread placement of a structure, one attempt per region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RandomSpread {
+    kind: FeatureKind,
+    /// Region size in chunks
+    spacing: i32,
+    separation: i32,
+    salt: i32,
+    /// Offsets are the average of two rolls instead of one
+    triangular: bool,
+}
+
+const SHIPWRECK_SPREAD: RandomSpread = RandomSpread {
+    kind: FeatureKind::Shipwreck,
+    spacing: 24,
+    separation: 4,
+    salt: 165745295,
+    triangular: false,
+};
+
+const MONUMENT_SPREAD: RandomSpread = RandomSpread {
+    kind: FeatureKind::Monument,
+    spacing: monument::SPACING,
+    separation: monument::SEPARATION,
+    salt: monument::SALT,
+    triangular: true,
+};
+
+impl RandomSpread {
+    /// Chunk of the generation attempt in the region
+    #[inline]
+    const fn attempt_chunk(&self, world_seed: i64, region: (i32, i32)) -> (i32, i32) {
+        let (mut rng, _) = random_with_region_seed(world_seed, region.0, region.1, self.salt);
+        let range = self.spacing - self.separation;
+        let (x, z) = if self.triangular {
+            let x = (rng.next_bounded_int(range) + rng.next_bounded_int(range)) / 2;
+            let z = (rng.next_bounded_int(range) + rng.next_bounded_int(range)) / 2;
+            (x, z)
+        } else {
+            let x = rng.next_bounded_int(range);
+            (x, rng.next_bounded_int(range))
+        };
+        (region.0 * self.spacing + x, region.1 * self.spacing + z)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearbyFeature {
+    pub kind: FeatureKind,
+    pub block_x: i32,
+    pub block_z: i32,
+    /// Horizontal distance to the center of the report, in blocks
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescribeRequest {
+    pub world_seed: i64,
+    pub center: (i32, i32),
+    pub radius: i32,
+    pub version: MCVersion,
+}
+
+impl DescribeRequest {
+    /// The radius is clamped to `0..=MAX_DESCRIBE_RADIUS`
+    #[inline]
+    pub const fn new(world_seed: i64, center: (i32, i32), radius: i32, version: MCVersion) -> Self {
+        Self {
+            world_seed,
+            center,
+            radius: if radius < 0 {
+                0
+            } else if radius > MAX_DESCRIBE_RADIUS {
+                MAX_DESCRIBE_RADIUS
+            } else {
+                radius
+            },
+            version,
+        }
+    }
+
+    #[inline]
+    const fn chunk_range(&self, center: i32) -> (i32, i32) {
+        ((center - self.radius) >> 4, (center + self.radius) >> 4)
+    }
+
+    #[inline]
+    const fn region_range(&self, center: i32, spacing: i32) -> (i32, i32) {
+        let (lo, hi) = self.chunk_range(center);
+        (lo.div_euclid(spacing), hi.div_euclid(spacing))
+    }
+
+    #[inline]
+    const fn biome_sample_count(&self) -> i32 {
+        self.radius / BIOME_SAMPLE_STEP * 2 + 1
+    }
+
+    /// Rows of every grid scanned, the unit of [`WorldDescriber::get_progress`]
+    pub const fn total_steps(&self) -> u64 {
+        let (lo, hi) = self.chunk_range(self.center.0);
+        let treasure_rows = (hi - lo + 1) as u64;
+        let (lo, hi) = self.region_range(self.center.0, SHIPWRECK_SPREAD.spacing);
+        let shipwreck_rows = (hi - lo + 1) as u64;
+        let (lo, hi) = self.region_range(self.center.0, MONUMENT_SPREAD.spacing);
+        let monument_rows = (hi - lo + 1) as u64;
+        treasure_rows + shipwreck_rows + monument_rows + self.biome_sample_count() as u64
+    }
+
+    #[inline]
+    fn feature(&self, kind: FeatureKind, block_x: i32, block_z: i32) -> Option<NearbyFeature> {
+        let dx = (block_x - self.center.0) as f64;
+        let dz = (block_z - self.center.1) as f64;
+        let distance = (dx * dx + dz * dz).sqrt();
+        if distance <= self.radius as f64 {
+            Some(NearbyFeature {
+                kind,
+                block_x,
+                block_z,
+                distance,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldReport {
+    pub world_seed: i64,
+    pub version: MCVersion,
+    pub center: (i32, i32),
+    pub radius: i32,
+    /// Closest first
+    pub features: Vec<NearbyFeature>,
+    /// True if some features were dropped by [`MAX_FEATURES_PER_KIND`]
+    pub truncated: bool,
+    /// Number of samples of each biome, most common first
+    pub biomes: Vec<(BiomeID, u32)>,
+    /// Height and cage of each end pillar
+    pub pillars: [(i32, bool); 10],
+}
+
+impl WorldReport {
+    #[inline]
+    pub fn features_of(&self, kind: FeatureKind) -> impl Iterator<Item = &NearbyFeature> {
+        self.features.iter().filter(move |f| f.kind == kind)
+    }
+}
+
+impl Display for WorldReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "World seed {} ({:?}), within {} blocks of ({}, {})",
+            self.world_seed, self.version, self.radius, self.center.0, self.center.1
+        )?;
+        if is_typed_numeric(self.world_seed) {
+            writeln!(
+                f,
+                "It fits in 48 bits, it was likely typed as a number rather than generated"
+            )?;
+        }
+        if self.features.is_empty() {
+            writeln!(f, "No buried treasure, shipwreck or monument in range")?;
+        }
+        for feature in self.features.iter() {
+            writeln!(
+                f,
+                "{} ({}, {}), {:.0} blocks away — go confirm",
+                feature.kind.hint(),
+                feature.block_x,
+                feature.block_z,
+                feature.distance
+            )?;
+        }
+        if self.truncated {
+            writeln!(
+                f,
+                "Only the closest {} features of each kind are listed",
+                MAX_FEATURES_PER_KIND
+            )?;
+        }
+        let samples = self.biomes.iter().map(|(_, n)| *n).sum::<u32>().max(1);
+        write!(f, "Biomes:")?;
+        for (biome, count) in self.biomes.iter() {
+            write!(f, " {:?} {}%", biome, count * 100 / samples)?;
+        }
+        writeln!(f)?;
+        write!(f, "End pillars:")?;
+        for (height, caged) in self.pillars.iter() {
+            write!(f, " {}{}", height, if *caged { " (caged)" } else { "" })?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct WorldDescriber {
+    request: DescribeRequest,
+
+    /// Number of grid rows scanned, out of [`DescribeRequest::total_steps`]
+    progress: AtomicU64,
+    stopsig: AtomicBool,
+    isdone: AtomicBool,
+}
+
+impl WorldDescriber {
+    #[inline]
+    pub fn new(request: DescribeRequest) -> Self {
+        Self {
+            request,
+            progress: AtomicU64::new(0),
+            stopsig: AtomicBool::new(false),
+            isdone: AtomicBool::new(false),
+        }
+    }
+
+    #[inline]
+    pub fn stop(&self) {
+        self.stopsig.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn get_progress(&self) -> u64 {
+        self.progress.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.isdone.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub const fn total_steps(&self) -> u64 {
+        self.request.total_steps()
+    }
+
+    #[inline]
+    fn step(&self) -> bool {
+        self.progress.fetch_add(1, Ordering::Relaxed);
+        !self.stopsig.load(Ordering::Relaxed)
+    }
+
+    /// `None` if stopped before the end
+    pub fn run(&self) -> Option<WorldReport> {
+        let report = self.compute();
+        self.isdone.store(true, Ordering::Relaxed);
+        report
+    }
+
+    fn compute(&self) -> Option<WorldReport> {
+        let request = &self.request;
+        let mut generator = Generator::new(
+            request.version,
+            request.world_seed,
+            Dimension::DIM_OVERWORLD,
+            GeneratorFlags::empty(),
+        );
+        let mut features = Vec::new();
+
+        let (x_lo, x_hi) = request.chunk_range(request.center.0);
+        let (z_lo, z_hi) = request.chunk_range(request.center.1);
+        for chunk_x in x_lo..=x_hi {
+            for chunk_z in z_lo..=z_hi {
+                if !buried_treasure::generates_at(request.world_seed, (chunk_x, chunk_z)) {
+                    continue;
+                }
+                let (x, z) = (chunk_x * 16 + 9, chunk_z * 16 + 9);
+                let Some(feature) = request.feature(FeatureKind::BuriedTreasure, x, z) else {
+                    continue;
+                };
+                if generator
+                    .verify_structure_generation_attempt(
+                        BlockPosition::new(x, z),
+                        StructureType::Treasure,
+                    )
+                    .unwrap_or(false)
+                {
+                    features.push(feature);
+                }
+            }
+            if !self.step() {
+                return None;
+            }
+        }
+
+        for spread in [SHIPWRECK_SPREAD, MONUMENT_SPREAD] {
+            let (x_lo, x_hi) = request.region_range(request.center.0, spread.spacing);
+            let (z_lo, z_hi) = request.region_range(request.center.1, spread.spacing);
+            for region_x in x_lo..=x_hi {
+                for region_z in z_lo..=z_hi {
+                    let (chunk_x, chunk_z) =
+                        spread.attempt_chunk(request.world_seed, (region_x, region_z));
+                    let (x, z) = (chunk_x * 16, chunk_z * 16);
+                    let Some(feature) = request.feature(spread.kind, x, z) else {
+                        continue;
+                    };
+                    if generator
+                        .verify_structure_generation_attempt(
+                            BlockPosition::new(x, z),
+                            spread.kind.structure_type(),
+                        )
+                        .unwrap_or(false)
+                    {
+                        features.push(feature);
+                    }
+                }
+                if !self.step() {
+                    return None;
+                }
+            }
+        }
+
+        let mut biomes: Vec<(BiomeID, u32)> = Vec::new();
+        let half = request.biome_sample_count() / 2;
+        for i in -half..=half {
+            for j in -half..=half {
+                let x = request.center.0 + i * BIOME_SAMPLE_STEP;
+                let z = request.center.1 + j * BIOME_SAMPLE_STEP;
+                let Ok(biome) = generator.get_biome_at(x, 64, z) else {
+                    continue;
+                };
+                match biomes.iter_mut().find(|(b, _)| *b == biome) {
+                    Some((_, count)) => *count += 1,
+                    None => biomes.push((biome, 1)),
+                }
+            }
+            if !self.step() {
+                return None;
+            }
+        }
+        // stable, ties keep the order they were first seen in
+        biomes.sort_by(|a, b| b.1.cmp(&a.1));
+
+        features.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        let mut truncated = false;
+        let mut kept = Vec::with_capacity(features.len());
+        for kind in FeatureKind::ALL {
+            let of_kind = features.iter().filter(|f| f.kind == kind);
+            truncated |= of_kind.clone().count() > MAX_FEATURES_PER_KIND;
+            kept.extend(of_kind.take(MAX_FEATURES_PER_KIND).copied());
+        }
+        kept.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        let mut end_pillars = EndPillars::new();
+        end_pillars.from_seed(EndPillars::pillar_seed(request.world_seed));
+        let mut pillars = [(0, false); 10];
+        for (slot, pillar) in pillars.iter_mut().zip(end_pillars.iter()) {
+            *slot = (pillar.height, pillar.caged);
+        }
+
+        Some(WorldReport {
+            world_seed: request.world_seed,
+            version: request.version,
+            center: request.center,
+            radius: request.radius,
+            features: kept,
+            truncated,
+            biomes,
+            pillars,
+        })
+    }
+
+    pub fn spawn(request: DescribeRequest) -> WorldDescriberHandle {
+        let job = Arc::new(Self::new(request));
+        let job2 = Arc::clone(&job);
+
+        let join_handle = std::thread::spawn(move || job.run());
+
+        WorldDescriberHandle {
+            join_handle,
+            describer: job2,
+        }
+    }
+}
+
+pub struct WorldDescriberHandle {
+    pub join_handle: JoinHandle<Option<WorldReport>>,
+    pub describer: Arc<WorldDescriber>,
+}
+
+impl WorldDescriberHandle {
+    #[inline]
+    pub fn join(self) -> thread::Result<Option<WorldReport>> {
+        self.join_handle.join()
+    }
+
+    #[inline]
+    pub fn cancel_join(self) -> thread::Result<Option<WorldReport>> {
+        self.describer.stop();
+        self.join()
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.describer.is_done()
+    }
+
+    #[inline]
+    pub fn get_progress(&self) -> u64 {
+        self.describer.get_progress()
+    }
+
+    #[inline]
+    pub fn total_steps(&self) -> u64 {
+        self.describer.total_steps()
+    }
+}
+
+/// Runs the whole enumeration on the current thread
+#[inline]
+pub fn describe_world(
+    world_seed: i64,
+    center: (i32, i32),
+    radius: i32,
+    version: MCVersion,
+) -> WorldReport {
+    WorldDescriber::new(DescribeRequest::new(world_seed, center, radius, version))
+        .run()
+        .expect("a describer that is never stopped returns a report")
+}
+
+/// Cost and benefit of one biome sample of the world seed search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiomeRowEstimate {
+    /// Fraction of the world seeds the sample rejects on its own
+    pub selectivity: f64,
+    /// Expected lookups of this sample per world seed candidate. The samples of a dimension are looked up rarest
+    /// first, each one only if the previous ones passed.
+    pub lookups_per_candidate: f64,
+    /// The sample comes after the soft cap, it mostly adds lookups once a rare biome is among the samples
+    pub past_cap: bool,
+}
+
+/// Estimates of the biome samples of a dimension, in the order of `biomes`. The samples from index `cap` on are past
+/// the cap.
+pub fn estimate_biome_rows(
+    dimension: Dimension,
+    biomes: &[BiomeID],
+    table: &BiomeRarityTable,
+    cap: usize,
+) -> Vec<BiomeRowEstimate> {
+    let frequency = |biome: BiomeID| table.frequency(dimension, biome);
+    // the order of `WorldExtraData::sort_samples_by_rarity`
+    let mut order = (0..biomes.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| frequency(biomes[*a]).total_cmp(&frequency(biomes[*b])));
+
+    let mut lookups = vec![0.0; biomes.len()];
+    let mut reached = 1.0;
+    for i in order {
+        lookups[i] = reached;
+        reached *= frequency(biomes[i]);
+    }
+    biomes
+        .iter()
+        .zip(lookups)
+        .enumerate()
+        .map(|(i, (biome, lookups))| BiomeRowEstimate {
+            selectivity: 1.0 - frequency(*biome),
+            lookups_per_candidate: lookups,
+            past_cap: i >= cap,
+        })
+        .collect()
+}
+
+/// Expected cost of the world evidence per world seed candidate, in biome lookups, with the checks in the order of
+/// [`order_by_selectivity`]. The tie-breakers and the empty sample lists cost nothing.
+pub fn expected_lookups(data: &[WorldExtraData], table: &BiomeRarityTable) -> f64 {
+    let mut data = data
+        .iter()
+        .filter(|d| match d {
+            WorldExtraData::StructurePresence { .. } => true,
+            _ => {
+                !d.is_tie_breaker()
+                    && d.as_overworld()
+                        .or_else(|| d.as_nether())
+                        .is_some_and(|samples| !samples.is_empty())
+            }
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    order_by_selectivity(&mut data, table);
+
+    let mut reached = 1.0;
+    let mut lookups = 0.0;
+    for d in data.iter() {
+        lookups += reached * d.check_cost(table);
+        reached *= d.pass_probability(table);
+    }
+    lookups
+}
+
+/// Expected duration of the world seed stage over `candidates` world seeds, scaled from the calibrated speed on
+/// [`WORLD_CALIBRATION_SAMPLES`]
+pub fn world_stage_time(
+    calibration: &Calibration,
+    data: &[WorldExtraData],
+    candidates: u64,
+) -> Duration {
+    let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+    let calibrated = expected_lookups(
+        &[WorldExtraData::OverworldBiomeData(
+            WORLD_CALIBRATION_SAMPLES.to_vec(),
+        )],
+        table,
+    );
+    let seconds_per_lookup = 1.0 / (calibration.world_seeds_per_second * calibrated);
+    let seconds = candidates as f64 * expected_lookups(data, table) * seconds_per_lookup;
+    Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cubiomes::enums::{BiomeID, Dimension, MCVersion};
+
+    use crate::{
+        analysis::{
+            DescribeRequest, FeatureKind, MAX_DESCRIBE_RADIUS, WorldDescriber, describe_world,
+            estimate_biome_rows, expected_lookups, world_stage_time,
+        },
+        calibration::{Calibration, WORLD_CALIBRATION_SAMPLES},
+        rarity::BiomeRarityTable,
+        search::WorldExtraData,
+        self_test,
+    };
+
+    #[test]
+    fn test_describe_golden_world() {
+        let report = describe_world(
+            self_test::WORLD_SEED,
+            self_test::BURIED_TREASURE_POS,
+            256,
+            MCVersion::MC_1_16_5,
+        );
+
+        let closest = report
+            .features_of(FeatureKind::BuriedTreasure)
+            .next()
+            .expect("the golden buried treasure is listed");
+        assert_eq!(
+            (closest.block_x, closest.block_z),
+            (25 * 16 + 9, 50 * 16 + 9)
+        );
+        assert_eq!(report.pillars, self_test::PILLARS);
+        assert!(
+            report
+                .features
+                .windows(2)
+                .all(|w| w[0].distance <= w[1].distance)
+        );
+        assert!(report.features.iter().all(|f| f.distance <= 256.0));
+        assert!(!report.biomes.is_empty());
+        assert!(report.to_string().contains("Buried treasure at (409, 809)"));
+        assert!(!report.to_string().contains("typed as a number"));
+    }
+
+    #[test]
+    fn test_describe_bounds_and_cancel() {
+        let request = DescribeRequest::new(0, (0, 0), 1 << 20, MCVersion::MC_1_16_5);
+        assert_eq!(request.radius, MAX_DESCRIBE_RADIUS);
+        assert_eq!(
+            DescribeRequest::new(0, (0, 0), -5, MCVersion::MC_1_16_5).radius,
+            0
+        );
+
+        let describer = WorldDescriber::new(request);
+        describer.stop();
+        assert!(describer.run().is_none());
+        assert!(describer.is_done());
+        assert_eq!(describer.get_progress(), 1);
+    }
+
+    #[test]
+    fn test_biome_row_estimates() {
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+        let overworld = Dimension::DIM_OVERWORLD;
+        let rows = estimate_biome_rows(
+            overworld,
+            &[BiomeID::plains, BiomeID::mushroom_fields, BiomeID::plains],
+            table,
+            2,
+        );
+        let plains = table.frequency(overworld, BiomeID::plains);
+        let mushroom = table.frequency(overworld, BiomeID::mushroom_fields);
+
+        assert!(rows[1].selectivity > 0.999);
+        assert!((rows[0].selectivity - (1.0 - plains)).abs() < 1e-12);
+        // the mushroom fields are looked up first, the plains only for the seeds that pass it
+        assert_eq!(rows[1].lookups_per_candidate, 1.0);
+        assert!((rows[0].lookups_per_candidate - mushroom).abs() < 1e-12);
+        assert!((rows[2].lookups_per_candidate - mushroom * plains).abs() < 1e-12);
+        assert_eq!(
+            rows.iter().map(|r| r.past_cap).collect::<Vec<_>>(),
+            [false, false, true]
+        );
+
+        assert!(estimate_biome_rows(overworld, &[], table, 2).is_empty());
+        assert!(
+            estimate_biome_rows(overworld, &[BiomeID::plains], table, 0)[0].past_cap,
+            "a zero cap marks every row"
+        );
+    }
+
+    #[test]
+    fn test_world_stage_time() {
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+        let calibration = Calibration {
+            format_version: 1,
+            crate_version: String::new(),
+            threads: 1,
+            structure_seeds_per_second: 1.0,
+            world_seeds_per_second: 1000.0,
+        };
+        let calibrated = [WorldExtraData::OverworldBiomeData(
+            WORLD_CALIBRATION_SAMPLES.to_vec(),
+        )];
+        let time = world_stage_time(&calibration, &calibrated, 2000);
+        assert!((time.as_secs_f64() - 2.0).abs() < 1e-9, "{:?}", time);
+
+        // no biome samples, the world seed search stops after the first seeds
+        let empty = [
+            WorldExtraData::OverworldBiomeData(vec![]),
+            WorldExtraData::NetherBiomeData(vec![]),
+        ];
+        assert_eq!(expected_lookups(&empty, table), 0.0);
+        assert_eq!(world_stage_time(&calibration, &empty, 2000), Duration::ZERO);
+
+        // every extra common sample adds lookups for the seeds passing the rare one, a little
+        let with_plains = |count| {
+            let mut samples = vec![(0, 64, 0, BiomeID::mushroom_fields)];
+            samples.extend((0..count).map(|i| (i * 64, 64, 0, BiomeID::plains)));
+            expected_lookups(&[WorldExtraData::OverworldBiomeData(samples)], table)
+        };
+        assert!(with_plains(1) > with_plains(0));
+        assert!(with_plains(40) - with_plains(0) < 0.01);
+    }
+}
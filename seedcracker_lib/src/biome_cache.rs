@@ -0,0 +1,306 @@
+//! Biome lookups kept between the checks of a session. Verifying a seed again after toggling one constraint asks
+//! cubiomes the same biomes as the previous check, and setting up the generator costs more than the lookups. The
+//! bulk searches never look up the same biome twice and don't use it.
+
+use std::{
+    cell::LazyCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use cubiomes::{
+    enums::{BiomeID, Dimension, MCVersion},
+    generator::{Generator, GeneratorFlags},
+};
+
+use crate::search::BiomeQuery;
+
+/// Lookups kept by [`BiomeCache::default`], a few bytes each
+pub const DEFAULT_BIOME_CACHE_CAPACITY: usize = 4096;
+
+/// A lookup of [`Generator::get_biome_at`], at block scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BiomeKey {
+    seed: i64,
+    version: i32,
+    dimension: i32,
+    pos: (i32, i32, i32),
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// The biome, `None` if it can't be looked up, and when it was last used
+    entries: HashMap<BiomeKey, (Option<BiomeID>, u64)>,
+    by_use: BTreeMap<u64, BiomeKey>,
+    clock: u64,
+}
+
+/// The biomes looked up last, the least recently used ones are dropped past the capacity
+#[derive(Debug)]
+pub struct BiomeCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BiomeCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+impl Display for BiomeCacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "biome cache: {} hit(s), {} miss(es), {}/{} lookups kept",
+            self.hits, self.misses, self.entries, self.capacity
+        )
+    }
+}
+
+impl Default for BiomeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BIOME_CACHE_CAPACITY)
+    }
+}
+
+impl BiomeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lru: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The lookups of the world of `seed`, the generator is only set up on the first miss
+    pub fn query(
+        &self,
+        seed: i64,
+        version: MCVersion,
+        dimension: Dimension,
+    ) -> CachedBiomeQuery<'_, Generator, impl FnOnce() -> Generator> {
+        CachedBiomeQuery::new(self, seed, version, dimension, move || {
+            Generator::new(version, seed, dimension, GeneratorFlags::empty())
+        })
+    }
+
+    pub fn stats(&self) -> BiomeCacheStats {
+        BiomeCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lru.lock().map_or(0, |lru| lru.entries.len()),
+            capacity: self.capacity,
+        }
+    }
+
+    fn get(&self, key: &BiomeKey) -> Option<Option<BiomeID>> {
+        let mut lru = self.lru.lock().ok()?;
+        let lru = &mut *lru;
+        lru.clock += 1;
+        let (biome, used) = lru.entries.get_mut(key)?;
+        lru.by_use.remove(used);
+        *used = lru.clock;
+        lru.by_use.insert(lru.clock, *key);
+        Some(*biome)
+    }
+
+    fn insert(&self, key: BiomeKey, biome: Option<BiomeID>) {
+        let Ok(mut lru) = self.lru.lock() else {
+            return;
+        };
+        let lru = &mut *lru;
+        lru.clock += 1;
+        if let Some((_, used)) = lru.entries.insert(key, (biome, lru.clock)) {
+            lru.by_use.remove(&used);
+        }
+        lru.by_use.insert(lru.clock, key);
+        while lru.entries.len() > self.capacity {
+            let Some((_, oldest)) = lru.by_use.pop_first() else {
+                break;
+            };
+            lru.entries.remove(&oldest);
+        }
+    }
+}
+
+/// Looks up the biomes of one world in the cache first, then with `inner`
+pub struct CachedBiomeQuery<'a, Q, F: FnOnce() -> Q> {
+    cache: &'a BiomeCache,
+    seed: i64,
+    version: MCVersion,
+    dimension: Dimension,
+    inner: LazyCell<Q, F>,
+}
+
+impl<'a, Q: BiomeQuery, F: FnOnce() -> Q> CachedBiomeQuery<'a, Q, F> {
+    /// `inner` must look up the world of `seed`, `version` and `dimension`, it is only called on the first miss
+    pub fn new(
+        cache: &'a BiomeCache,
+        seed: i64,
+        version: MCVersion,
+        dimension: Dimension,
+        inner: F,
+    ) -> Self {
+        Self {
+            cache,
+            seed,
+            version,
+            dimension,
+            inner: LazyCell::new(inner),
+        }
+    }
+}
+
+impl<Q: BiomeQuery, F: FnOnce() -> Q> BiomeQuery for CachedBiomeQuery<'_, Q, F> {
+    fn biome_at(&self, x: i32, y: i32, z: i32) -> Option<BiomeID> {
+        let key = BiomeKey {
+            seed: self.seed,
+            version: self.version as i32,
+            dimension: self.dimension as i32,
+            pos: (x, y, z),
+        };
+        if let Some(biome) = self.cache.get(&key) {
+            self.cache.hits.fetch_add(1, Ordering::Relaxed);
+            return biome;
+        }
+        self.cache.misses.fetch_add(1, Ordering::Relaxed);
+        let biome = self.inner.biome_at(x, y, z);
+        self.cache.insert(key, biome);
+        biome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use cubiomes::{
+        enums::{BiomeID, Dimension, MCVersion},
+        generator::{Generator, GeneratorFlags},
+    };
+
+    use crate::{
+        biome_cache::{BiomeCache, BiomeCacheStats, CachedBiomeQuery},
+        search::BiomeQuery,
+        self_test::WORLD_SEED,
+    };
+
+    /// Plains everywhere, counting its lookups
+    struct Counting<'a>(&'a Cell<u32>);
+
+    impl BiomeQuery for Counting<'_> {
+        fn biome_at(&self, _x: i32, _y: i32, _z: i32) -> Option<BiomeID> {
+            self.0.set(self.0.get() + 1);
+            Some(BiomeID::plains)
+        }
+    }
+
+    /// A query of the cache counting the lookups that reach it and the setups of its world
+    fn counting<'a>(
+        cache: &'a BiomeCache,
+        lookups: &'a Cell<u32>,
+        setups: &'a Cell<u32>,
+    ) -> CachedBiomeQuery<'a, Counting<'a>, impl FnOnce() -> Counting<'a>> {
+        CachedBiomeQuery::new(
+            cache,
+            1,
+            MCVersion::MC_1_16_5,
+            Dimension::DIM_OVERWORLD,
+            move || {
+                setups.set(setups.get() + 1);
+                Counting(lookups)
+            },
+        )
+    }
+
+    #[test]
+    fn test_hits_match_the_generator() {
+        let cache = BiomeCache::default();
+        let generator = Generator::new(
+            MCVersion::MC_1_16_5,
+            WORLD_SEED,
+            Dimension::DIM_OVERWORLD,
+            GeneratorFlags::empty(),
+        );
+        let positions = [
+            (400, 60, 800),
+            (-1200, 70, 300),
+            (0, 64, 0),
+            (5000, 40, -77),
+        ];
+
+        for _ in 0..3 {
+            let query = cache.query(WORLD_SEED, MCVersion::MC_1_16_5, Dimension::DIM_OVERWORLD);
+            for (x, y, z) in positions {
+                assert_eq!(query.biome_at(x, y, z), generator.biome_at(x, y, z));
+            }
+        }
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (8, 4, 4));
+
+        // another seed, version or dimension is another world
+        let nether = cache.query(WORLD_SEED, MCVersion::MC_1_16_5, Dimension::DIM_NETHER);
+        let (x, y, z) = positions[0];
+        assert_eq!(
+            nether.biome_at(x, y, z),
+            Generator::new(
+                MCVersion::MC_1_16_5,
+                WORLD_SEED,
+                Dimension::DIM_NETHER,
+                GeneratorFlags::empty(),
+            )
+            .biome_at(x, y, z)
+        );
+        assert_eq!(cache.stats().misses, 5);
+    }
+
+    #[test]
+    fn test_generator_set_up_on_miss() {
+        let (cache, lookups, setups) = (BiomeCache::new(8), Cell::new(0), Cell::new(0));
+
+        counting(&cache, &lookups, &setups).biome_at(0, 64, 0);
+        assert_eq!(
+            counting(&cache, &lookups, &setups).biome_at(0, 64, 0),
+            Some(BiomeID::plains)
+        );
+        assert_eq!((lookups.get(), setups.get()), (1, 1));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let (cache, lookups, setups) = (BiomeCache::new(2), Cell::new(0), Cell::new(0));
+        let query = counting(&cache, &lookups, &setups);
+
+        query.biome_at(0, 64, 0);
+        query.biome_at(4, 64, 0);
+        // the first lookup is now the most recently used, the second one is dropped
+        query.biome_at(0, 64, 0);
+        query.biome_at(8, 64, 0);
+        assert_eq!(lookups.get(), 3);
+
+        query.biome_at(0, 64, 0);
+        assert_eq!(lookups.get(), 3);
+        query.biome_at(4, 64, 0);
+        assert_eq!(lookups.get(), 4);
+
+        assert_eq!(
+            cache.stats(),
+            BiomeCacheStats {
+                hits: 2,
+                misses: 4,
+                entries: 2,
+                capacity: 2,
+            }
+        );
+    }
+}
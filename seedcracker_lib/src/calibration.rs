@@ -0,0 +1,414 @@
+//! Measures the search throughput of this machine, so search plans can show an ETA before the first job completes.
+//!
+//! The measurements are stored in the platform config directory. They are only reused by the same crate version with the
+//! same thread count, anything else runs at a different speed.
+
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    ops::Range,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use cubiomes::enums::BiomeID;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    cooperative::SearchOptions,
+    features::buried_treasure::{build_fast_inventory_compare_context, get_loot_table},
+    math::Math,
+    pipeline::{SearchPlan, StageKind},
+    search::{
+        ConstraintError, StructureData, StructureSeedSearchData, WorldExtraData,
+        WorldSeedSearchData,
+    },
+    self_test,
+};
+
+/// Biome samples of the world seed workload, from the fixture world
+pub const WORLD_CALIBRATION_SAMPLES: [(i32, i32, i32, BiomeID); 3] = [
+    (400, 60, 800, BiomeID::beach),
+    (-404, 69, -51, BiomeID::beach),
+    (137, 73, -90, BiomeID::jungle),
+];
+
+/// Bumped whenever the stored fields or the workloads change
+pub const CALIBRATION_FORMAT_VERSION: u64 = 1;
+pub const CALIBRATION_FILE_NAME: &str = "calibration.json";
+
+pub trait Clock {
+    /// Time elapsed since an arbitrary fixed point
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationWorkload {
+    /// Upper 16 bits of the RNG states searched, each covers 65536 structure seed candidates
+    pub structure_state_hi: Range<i64>,
+    /// Upper 16 bits of the world seeds searched
+    pub world_upper_bits: Range<i64>,
+}
+
+impl CalibrationWorkload {
+    /// 2^24 structure seed candidates and 1024 world seed candidates
+    #[inline]
+    pub const fn standard() -> Self {
+        Self {
+            structure_state_hi: 0..256,
+            world_upper_bits: 0..1024,
+        }
+    }
+
+    #[inline]
+    pub fn structure_candidates(&self) -> u64 {
+        (self.structure_state_hi.end - self.structure_state_hi.start).max(0) as u64 * 65536
+    }
+
+    #[inline]
+    pub fn world_candidates(&self) -> u64 {
+        (self.world_upper_bits.end - self.world_upper_bits.start).max(0) as u64
+    }
+}
+
+/// Some world seed checks of the workload failed, the measured rate would count candidates that weren't checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalibrationError {
+    pub skipped: u64,
+    pub first_error: ConstraintError,
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} calibration candidates skipped due to errors, first error: {}",
+            self.skipped, self.first_error
+        )
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    pub format_version: u64,
+    pub crate_version: String,
+    pub threads: usize,
+    pub structure_seeds_per_second: f64,
+    pub world_seeds_per_second: f64,
+}
+
+impl Calibration {
+    /// Runs the workload in the thread pool of the searches of `search`
+    pub fn measure(
+        clock: &impl Clock,
+        workload: &CalibrationWorkload,
+        search: &SearchOptions,
+    ) -> Result<Self, CalibrationError> {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let structure_search = StructureSeedSearchData::new(
+            self_test::PILLAR_SEED,
+            vec![StructureData::BuriedTreasureContents {
+                chunk_x,
+                chunk_z,
+                luck: 0.0,
+                contents: build_fast_inventory_compare_context(
+                    self_test::buried_treasure_contents(),
+                    &get_loot_table(),
+                )
+                .expect("the self test chest is a valid buried treasure"),
+                loot_table: None,
+            }],
+            10,
+        );
+        let start = clock.now();
+        let _ = search.install(|| {
+            workload
+                .structure_state_hi
+                .clone()
+                .into_par_iter()
+                .map(|hi| structure_search.search_bounded(hi..hi + 1).len())
+                .sum::<usize>()
+        });
+        let structure_time = clock.now().saturating_sub(start);
+
+        let mut world_search = WorldSeedSearchData::new(
+            self_test::STRUCTURE_SEED,
+            false,
+            vec![WorldExtraData::OverworldBiomeData(
+                WORLD_CALIBRATION_SAMPLES.to_vec(),
+            )],
+            5,
+        );
+        world_search.order_constraints();
+        let start = clock.now();
+        let (skipped, first_error) = search.install(|| {
+            workload
+                .world_upper_bits
+                .clone()
+                .into_par_iter()
+                .map(|hi| {
+                    let found = world_search.search_bounded(hi..hi + 1);
+                    (found.skipped, found.first_error)
+                })
+                .reduce(
+                    || (0, None),
+                    |(skipped, error), (other_skipped, other_error)| {
+                        (skipped + other_skipped, error.or(other_error))
+                    },
+                )
+        });
+        let world_time = clock.now().saturating_sub(start);
+        if let Some(first_error) = first_error {
+            return Err(CalibrationError {
+                skipped,
+                first_error,
+            });
+        }
+
+        Ok(Self {
+            format_version: CALIBRATION_FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            threads: search.thread_count(),
+            structure_seeds_per_second: throughput(workload.structure_candidates(), structure_time),
+            world_seeds_per_second: throughput(workload.world_candidates(), world_time),
+        })
+    }
+
+    /// If false, the measurements were made by another build or with another thread count and must be redone
+    #[inline]
+    pub fn is_valid_for(&self, threads: usize) -> bool {
+        self.format_version == CALIBRATION_FORMAT_VERSION
+            && self.crate_version == env!("CARGO_PKG_VERSION")
+            && self.threads == threads
+            && self.structure_seeds_per_second > 0.0
+            && self.world_seeds_per_second > 0.0
+    }
+
+    /// Time needed to run every stage of the plan. The world seed stage is counted with its upper bound of jobs, the
+    /// re-verification of the few structure seeds found is negligible. A buried treasure area multiplies the structure
+    /// seed stage by its chunk count.
+    pub fn estimate(&self, plan: &SearchPlan) -> Duration {
+        let stage_time = |kind: StageKind, rate: f64| {
+            plan.stage(kind)
+                .map(|stage| stage.total_candidates() as f64 / rate)
+                .unwrap_or(0.0)
+        };
+        let area_chunks = plan.treasure_area_cost.map_or(1, |cost| cost.chunks) as f64;
+        let seconds = stage_time(StageKind::StructureSeeds, self.structure_seeds_per_second)
+            * area_chunks
+            + stage_time(StageKind::WorldSeeds, self.world_seeds_per_second);
+        Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "format_version": self.format_version,
+            "crate_version": self.crate_version,
+            "threads": self.threads,
+            "structure_seeds_per_second": self.structure_seeds_per_second,
+            "world_seeds_per_second": self.world_seeds_per_second,
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            format_version: value.get("format_version")?.as_u64()?,
+            crate_version: value.get("crate_version")?.as_str()?.to_string(),
+            threads: value.get("threads")?.as_u64()? as usize,
+            structure_seeds_per_second: value.get("structure_seeds_per_second")?.as_f64()?,
+            world_seeds_per_second: value.get("world_seeds_per_second")?.as_f64()?,
+        })
+    }
+
+    pub fn save(&self, writer: impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.to_json())
+    }
+
+    /// The stored calibration, if it can be read and is still valid for this build and thread count
+    pub fn load_valid(reader: impl Read, threads: usize) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_reader(reader).ok()?;
+        Self::from_json(&value).filter(|c| c.is_valid_for(threads))
+    }
+
+    /// Reads the calibration stored at [`calibration_path`], if it was measured on `threads` threads, see
+    /// [`SearchOptions::thread_count`]
+    pub fn load_stored(threads: usize) -> Option<Self> {
+        let file = File::open(calibration_path()?).ok()?;
+        Self::load_valid(BufReader::new(file), threads)
+    }
+
+    /// Writes the calibration to [`calibration_path`], returns the path written
+    pub fn store(&self) -> std::io::Result<PathBuf> {
+        let path = calibration_path()
+            .ok_or_else(|| std::io::Error::other("no config directory on this platform"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut writer = BufWriter::new(File::create(&path)?);
+        self.save(&mut writer)?;
+        writer.flush()?;
+        Ok(path)
+    }
+}
+
+#[inline]
+fn throughput(candidates: u64, time: Duration) -> f64 {
+    candidates as f64 / time.as_secs_f64().max(1e-9)
+}
+
+/// `$XDG_CONFIG_HOME/mcseedcracker` or `~/.config/mcseedcracker` on Linux, the equivalents on Windows and macOS
+pub fn config_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")?
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME")?.join("Library").join("Application Support")
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))?
+    };
+    Some(base.join("mcseedcracker"))
+}
+
+#[inline]
+pub fn calibration_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CALIBRATION_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use crate::{
+        calibration::{CALIBRATION_FORMAT_VERSION, Calibration, CalibrationWorkload, Clock},
+        cooperative::SearchOptions,
+        pipeline::{CrackingEvidence, CrackingPipeline, PipelineOptions, StageKind},
+    };
+
+    /// Advances by a fixed step every time it is read
+    struct StepClock {
+        now: Cell<Duration>,
+        step: Duration,
+    }
+
+    impl Clock for StepClock {
+        fn now(&self) -> Duration {
+            let now = self.now.get();
+            self.now.set(now + self.step);
+            now
+        }
+    }
+
+    fn calibration(threads: usize) -> Calibration {
+        Calibration {
+            format_version: CALIBRATION_FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            threads,
+            structure_seeds_per_second: 1e8,
+            world_seeds_per_second: 1e4,
+        }
+    }
+
+    #[test]
+    fn test_measure_with_mock_clock() {
+        let clock = StepClock {
+            now: Cell::new(Duration::from_secs(100)),
+            step: Duration::from_millis(500),
+        };
+        let workload = CalibrationWorkload {
+            structure_state_hi: 0..2,
+            world_upper_bits: 0..4,
+        };
+        let measured = Calibration::measure(&clock, &workload, &SearchOptions::DEFAULT).unwrap();
+
+        // each stage reads the clock twice, 500ms apart
+        assert_eq!(measured.structure_seeds_per_second, 2.0 * 65536.0 / 0.5);
+        assert_eq!(measured.world_seeds_per_second, 4.0 / 0.5);
+        assert_eq!(measured.threads, rayon::current_num_threads());
+        assert!(measured.is_valid_for(measured.threads));
+
+        // measured in the pool of the configured thread count
+        let search = SearchOptions {
+            threads: Some(2),
+            ..SearchOptions::DEFAULT
+        };
+        let measured = Calibration::measure(&clock, &workload, &search).unwrap();
+        assert_eq!(measured.threads, 2);
+        assert!(!measured.is_valid_for(3));
+
+        assert_eq!(
+            CalibrationWorkload::standard().structure_candidates(),
+            1 << 24
+        );
+        assert_eq!(CalibrationWorkload::standard().world_candidates(), 1024);
+    }
+
+    #[test]
+    fn test_calibration_storage() {
+        let stored = calibration(8);
+        let mut file = Vec::new();
+        stored.save(&mut file).unwrap();
+        assert_eq!(Calibration::load_valid(&file[..], 8), Some(stored.clone()));
+
+        // invalidated by another thread count, crate version or format
+        assert_eq!(Calibration::load_valid(&file[..], 4), None);
+        let mut other_version = stored.clone();
+        other_version.crate_version = "0.0.0-old".to_string();
+        assert!(!other_version.is_valid_for(8));
+        let mut other_format = stored.clone();
+        other_format.format_version += 1;
+        assert!(!other_format.is_valid_for(8));
+
+        assert_eq!(Calibration::load_valid(&b"{}"[..], 8), None);
+        assert_eq!(Calibration::load_valid(&b"not json"[..], 8), None);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let plan =
+            CrackingPipeline::plan(&CrackingEvidence::default(), &PipelineOptions::default());
+        let candidates = |kind| plan.stage(kind).unwrap().total_candidates() as f64;
+        let structure_candidates = candidates(StageKind::StructureSeeds);
+        let world_candidates = candidates(StageKind::WorldSeeds);
+
+        let estimate = calibration(8).estimate(&plan);
+        let expected = structure_candidates / 1e8 + world_candidates / 1e4;
+        assert!(expected > 0.0);
+        assert!((estimate.as_secs_f64() - expected).abs() < 1e-6);
+    }
+}
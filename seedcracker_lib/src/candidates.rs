@@ -0,0 +1,492 @@
+//! The seeds found by each search stage, with where they come from. They are what the frontends keep, list and export,
+//! and their JSON form is stable so the exports can be read back by other tools.
+
+use serde_json::{Value, json};
+
+use crate::{
+    features::end_pillars::PillarMatchResult,
+    lcg,
+    math::Math,
+    pipeline::describe_structure_data,
+    search::{StructureData, WorldSeedFit},
+    world_seed::is_typed_numeric,
+};
+
+/// The nextLong call of the pillar seed, from the scrambled structure seed to the RNG state the structure search splits
+const PILLAR_NEXT_LONG: lcg::LinearCongruentialGenerator = lcg::JAVA_RANDOM.combine(2);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PillarSeedCandidate {
+    pub seed: i64,
+    /// Chance that the pillars of the seed look like the observed ones, 1 for an exact match
+    pub probability: f64,
+}
+
+impl PillarSeedCandidate {
+    #[inline]
+    pub fn new(seed: i64, result: PillarMatchResult) -> Self {
+        Self {
+            seed,
+            probability: result.chance(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "seed": self.seed,
+            "probability": self.probability,
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            seed: value.get("seed")?.as_i64()?,
+            probability: value.get("probability")?.as_f64()?,
+        })
+    }
+}
+
+/// How likely a found structure seed is the world's, see [`CandidateScore::rank`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CandidateScore {
+    /// Chance that the pillars of its pillar seed look like the observed ones, 1 for an exact match
+    pub pillar_chance: f64,
+    /// Every chest layout it satisfies holds the observed stacks slot for slot, see
+    /// [`StructureData::matches_exactly`]. False without a chest layout.
+    pub chest_exact: bool,
+    /// Number of structure evidence items it satisfies
+    pub n_constraints: u32,
+}
+
+impl CandidateScore {
+    pub fn new(data: &[StructureData], seed: i64, pillar_chance: f64) -> Self {
+        let satisfied = data
+            .iter()
+            .filter(|d| d.check_seed(seed))
+            .collect::<Vec<_>>();
+        let mut layouts = satisfied
+            .iter()
+            .filter(|d| !matches!(d, StructureData::BuriedTreasureCounts { .. }))
+            .peekable();
+        Self {
+            pillar_chance,
+            chest_exact: layouts.peek().is_some() && layouts.all(|d| d.matches_exactly(seed)),
+            n_constraints: satisfied.len() as u32,
+        }
+    }
+
+    /// Most likely first: more likely pillars, then exact chest layouts before the ones that only matched with some
+    /// tolerance, then more satisfied evidence
+    pub fn rank(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .pillar_chance
+            .total_cmp(&self.pillar_chance)
+            .then(other.chest_exact.cmp(&self.chest_exact))
+            .then(other.n_constraints.cmp(&self.n_constraints))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "pillar_chance": self.pillar_chance,
+            "chest_exact": self.chest_exact,
+            "n_constraints": self.n_constraints,
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            pillar_chance: value.get("pillar_chance")?.as_f64()?,
+            chest_exact: value.get("chest_exact")?.as_bool()?,
+            n_constraints: u32::try_from(value.get("n_constraints")?.as_u64()?).ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureSeedCandidate {
+    pub seed: i64,
+    pub pillar_seed: i64,
+    /// The structure evidence the seed satisfies, as described in the search plan
+    pub evidence_annotations: Vec<String>,
+    /// The chunk the buried treasure matched in, for the evidence that only gives an area
+    pub found_at: Option<(i32, i32)>,
+    /// Upper 16 bits of the RNG state the seed was found at, the structure search runs one shard per value
+    pub shard: u16,
+    pub score: CandidateScore,
+}
+
+impl StructureSeedCandidate {
+    /// Annotates a seed found for `pillar_seed` with the structure evidence of its search. `pillar_chance` is the
+    /// [`PillarMatchResult::chance`] of the pillar seed.
+    pub fn new(data: &[StructureData], seed: i64, pillar_seed: i64, pillar_chance: f64) -> Self {
+        Self {
+            seed,
+            pillar_seed,
+            evidence_annotations: data
+                .iter()
+                .filter(|d| d.check_seed(seed))
+                .map(describe_structure_data)
+                .collect(),
+            found_at: data
+                .iter()
+                .filter(|d| matches!(d, StructureData::BuriedTreasureContentsInArea { .. }))
+                .find_map(|d| d.matching_chunk(seed)),
+            shard: Self::shard_of(seed),
+            score: CandidateScore::new(data, seed, pillar_chance),
+        }
+    }
+
+    /// The shard of the structure search that finds `structure_seed`
+    #[inline]
+    pub const fn shard_of(structure_seed: i64) -> u16 {
+        let state = PILLAR_NEXT_LONG.next_seed(structure_seed ^ lcg::JAVA_RANDOM.get_multiplier());
+        (state >> 32) as u16
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "seed": self.seed,
+            "pillar_seed": self.pillar_seed,
+            "evidence_annotations": self.evidence_annotations,
+            "found_at": self.found_at.map(|(x, z)| json!([x, z])),
+            "shard": self.shard,
+            "score": self.score.to_json(),
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let found_at = match value.get("found_at")? {
+            Value::Null => None,
+            Value::Array(chunk) if chunk.len() == 2 => Some((
+                i32::try_from(chunk[0].as_i64()?).ok()?,
+                i32::try_from(chunk[1].as_i64()?).ok()?,
+            )),
+            _ => return None,
+        };
+        Some(Self {
+            seed: value.get("seed")?.as_i64()?,
+            pillar_seed: value.get("pillar_seed")?.as_i64()?,
+            evidence_annotations: value
+                .get("evidence_annotations")?
+                .as_array()?
+                .iter()
+                .map(|a| a.as_str().map(str::to_string))
+                .collect::<Option<_>>()?,
+            found_at,
+            shard: u16::try_from(value.get("shard")?.as_u64()?).ok()?,
+            score: CandidateScore::from_json(value.get("score")?)?,
+        })
+    }
+}
+
+/// Sorts the candidates with the most likely ones first, see [`CandidateScore::rank`]. Equal scores keep their order.
+#[inline]
+pub fn rank_structure_candidates(candidates: &mut [StructureSeedCandidate]) {
+    candidates.sort_by(|a, b| a.score.rank(&b.score));
+}
+
+/// How the player most likely got a world seed, from the shape of the seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldSeedOrigin {
+    /// Left blank in the world creation screen, `new Random().nextLong()`
+    Random,
+    /// A number typed in the seed field, see [`crate::world_seed::typed_numeric_completions`]
+    TypedNumber,
+    /// The `String::hashCode` of a text typed in the seed field. A number typed in the int range looks the same.
+    HashedWord,
+}
+
+impl WorldSeedOrigin {
+    pub fn of(world_seed: i64) -> Self {
+        if Math::is_string_hashcode_seed(world_seed) {
+            Self::HashedWord
+        } else if is_typed_numeric(world_seed) {
+            Self::TypedNumber
+        } else {
+            Self::Random
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::TypedNumber => "typed",
+            Self::HashedWord => "hashed-word",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Random, Self::TypedNumber, Self::HashedWord]
+            .into_iter()
+            .find(|o| o.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldSeedCandidate {
+    pub seed: i64,
+    pub structure_seed: i64,
+    pub origin: WorldSeedOrigin,
+    /// How well the biome samples fit, see [`WorldSeedFit::score`]
+    pub score: f64,
+}
+
+impl WorldSeedCandidate {
+    /// A seed found for `structure_seed`, scored by its fit if the biome samples were measured
+    pub fn new(structure_seed: i64, seed: i64, fit: Option<&WorldSeedFit>) -> Self {
+        Self {
+            seed,
+            structure_seed,
+            origin: WorldSeedOrigin::of(seed),
+            score: fit.map(WorldSeedFit::score).unwrap_or(1.0),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "seed": self.seed,
+            "structure_seed": self.structure_seed,
+            "origin": self.origin.name(),
+            "score": self.score,
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type
+    pub fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            seed: value.get("seed")?.as_i64()?,
+            structure_seed: value.get("structure_seed")?.as_i64()?,
+            origin: WorldSeedOrigin::from_name(value.get("origin")?.as_str()?)?,
+            score: value.get("score")?.as_f64()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        candidates::{
+            CandidateScore, PILLAR_NEXT_LONG, PillarSeedCandidate, StructureSeedCandidate,
+            WorldSeedCandidate, WorldSeedOrigin, rank_structure_candidates,
+        },
+        features::end_pillars::{EndPillars, PillarMatchResult},
+        lcg,
+        math::Math,
+        pipeline::{BuriedTreasureEvidence, CrackingEvidence, CrackingPipeline, PipelineOptions},
+        search::{StructureData, StructureSeedAnnotation, WorldSeedFit},
+        self_test::{
+            self, BURIED_TREASURE_POS, PILLAR_SEED, STRUCTURE_SEARCH_SLICE, STRUCTURE_SEED,
+            WORLD_SEED,
+        },
+    };
+
+    /// The fixture chest, somewhere between its position and a corner 20 blocks away
+    fn structure_data() -> Vec<StructureData> {
+        let (x, z) = BURIED_TREASURE_POS;
+        let evidence = CrackingEvidence {
+            buried_treasures: vec![BuriedTreasureEvidence {
+                pos_x: x,
+                pos_z: z,
+                luck: 0.0,
+                contents: self_test::buried_treasure_contents(),
+                area_corner: Some((x - 20, z - 20)),
+                observed_at: None,
+            }],
+            ..Default::default()
+        };
+        CrackingPipeline::structure_data(&evidence, &PipelineOptions::default())
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            PillarSeedCandidate::new(PILLAR_SEED, PillarMatchResult::ExactMatch).probability,
+            1.0
+        );
+        assert_eq!(
+            PillarSeedCandidate::new(1, PillarMatchResult::PossibleMatch(0.25)).probability,
+            0.25
+        );
+
+        // the same evidence counts and treasure chunk as the annotation used for ranking
+        let data = structure_data();
+        let candidate = StructureSeedCandidate::new(&data, STRUCTURE_SEED, PILLAR_SEED, 1.0);
+        let annotation = StructureSeedAnnotation::new(
+            &data,
+            STRUCTURE_SEED,
+            PILLAR_SEED,
+            PillarMatchResult::ExactMatch,
+        );
+        assert_eq!(
+            candidate.evidence_annotations.len(),
+            annotation.satisfied_evidence
+        );
+        assert_eq!(candidate.found_at, annotation.treasure_chunk);
+        assert_eq!(
+            candidate.found_at,
+            Some(Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS))
+        );
+        assert!(
+            StructureSeedCandidate::new(&data, 1, PILLAR_SEED, 1.0)
+                .evidence_annotations
+                .is_empty()
+        );
+
+        // the state of the shard carries the pillar seed in its middle bits, like the structure search builds it
+        let state = PILLAR_NEXT_LONG.next_seed(STRUCTURE_SEED ^ lcg::JAVA_RANDOM.get_multiplier());
+        assert_eq!(
+            (state >> 16) & 0xFFFF,
+            EndPillars::pillar_seed(STRUCTURE_SEED)
+        );
+        assert_eq!((state >> 16) & 0xFFFF, PILLAR_SEED);
+        let shard = StructureSeedCandidate::shard_of(STRUCTURE_SEED);
+        assert_eq!(shard as i64, state >> 32);
+        assert!(STRUCTURE_SEARCH_SLICE.contains(&(shard as i64)));
+        assert_eq!(shard, StructureSeedCandidate::shard_of(WORLD_SEED));
+        assert_eq!(candidate.shard, shard);
+
+        let fit = WorldSeedFit {
+            seed: WORLD_SEED,
+            margins: vec![8, 0],
+        };
+        let candidate = WorldSeedCandidate::new(STRUCTURE_SEED, WORLD_SEED, Some(&fit));
+        assert_eq!(candidate.origin, WorldSeedOrigin::Random);
+        assert_eq!(candidate.score, fit.score());
+        assert_eq!(
+            WorldSeedCandidate::new(STRUCTURE_SEED, WORLD_SEED, None).score,
+            1.0
+        );
+        assert_eq!(
+            WorldSeedOrigin::of(STRUCTURE_SEED),
+            WorldSeedOrigin::TypedNumber
+        );
+        assert_eq!(WorldSeedOrigin::of(-12345), WorldSeedOrigin::HashedWord);
+    }
+
+    #[test]
+    fn test_candidate_score() {
+        // the fixture chest is seen whole, and only matches its own seed
+        let data = structure_data();
+        let score = CandidateScore::new(&data, STRUCTURE_SEED, 1.0);
+        assert_eq!(
+            score,
+            CandidateScore {
+                pillar_chance: 1.0,
+                chest_exact: true,
+                n_constraints: 1,
+            }
+        );
+        let unmatched = CandidateScore::new(&data, 1, 0.5);
+        assert!(!unmatched.chest_exact);
+        assert_eq!(unmatched.n_constraints, 0);
+        assert!(!CandidateScore::new(&[], STRUCTURE_SEED, 1.0).chest_exact);
+
+        let score = |pillar_chance, chest_exact, n_constraints| CandidateScore {
+            pillar_chance,
+            chest_exact,
+            n_constraints,
+        };
+        let mut candidates = [
+            score(0.1, true, 3),
+            score(1.0, false, 2),
+            score(0.1, false, 3),
+            score(1.0, true, 1),
+            score(1.0, true, 2),
+            score(0.5, false, 0),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, score)| StructureSeedCandidate {
+            seed: i as i64,
+            pillar_seed: PILLAR_SEED,
+            evidence_annotations: Vec::new(),
+            found_at: None,
+            shard: 0,
+            score,
+        })
+        .collect::<Vec<_>>();
+        rank_structure_candidates(&mut candidates);
+        assert_eq!(
+            candidates.iter().map(|c| c.seed).collect::<Vec<_>>(),
+            [4, 3, 1, 5, 0, 2]
+        );
+
+        // equal scores keep the order they were found in
+        let mut tied = candidates.clone();
+        tied.iter_mut().for_each(|c| c.score = score(1.0, true, 1));
+        rank_structure_candidates(&mut tied);
+        assert_eq!(
+            tied.iter().map(|c| c.seed).collect::<Vec<_>>(),
+            [4, 3, 1, 5, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_serialized_form() {
+        let pillar = PillarSeedCandidate::new(PILLAR_SEED, PillarMatchResult::PossibleMatch(0.5));
+        let structure = StructureSeedCandidate {
+            seed: STRUCTURE_SEED,
+            pillar_seed: PILLAR_SEED,
+            evidence_annotations: vec!["Buried treasure in chunk (25, 50)".to_string()],
+            found_at: Some((25, 50)),
+            shard: 1234,
+            score: CandidateScore {
+                pillar_chance: 0.5,
+                chest_exact: true,
+                n_constraints: 1,
+            },
+        };
+        let world = WorldSeedCandidate {
+            seed: WORLD_SEED,
+            structure_seed: STRUCTURE_SEED,
+            origin: WorldSeedOrigin::HashedWord,
+            score: 0.75,
+        };
+
+        // field names and value types are part of the export format, changing them breaks the tools reading it
+        assert_eq!(
+            pillar.to_json().to_string(),
+            r#"{"probability":0.5,"seed":13847}"#
+        );
+        assert_eq!(
+            structure.to_json().to_string(),
+            r#"{"evidence_annotations":["Buried treasure in chunk (25, 50)"],"found_at":[25,50],"pillar_seed":13847,"score":{"chest_exact":true,"n_constraints":1,"pillar_chance":0.5},"seed":180066252004364,"shard":1234}"#
+        );
+        assert_eq!(
+            world.to_json().to_string(),
+            r#"{"origin":"hashed-word","score":0.75,"seed":-7193194438565520372,"structure_seed":180066252004364}"#
+        );
+
+        assert_eq!(
+            PillarSeedCandidate::from_json(&pillar.to_json()),
+            Some(pillar)
+        );
+        assert_eq!(
+            StructureSeedCandidate::from_json(&structure.to_json()),
+            Some(structure.clone())
+        );
+        let unlocated = StructureSeedCandidate {
+            found_at: None,
+            ..structure
+        };
+        assert_eq!(
+            StructureSeedCandidate::from_json(&unlocated.to_json()),
+            Some(unlocated)
+        );
+        assert_eq!(WorldSeedCandidate::from_json(&world.to_json()), Some(world));
+        for origin in [
+            WorldSeedOrigin::Random,
+            WorldSeedOrigin::TypedNumber,
+            WorldSeedOrigin::HashedWord,
+        ] {
+            assert_eq!(WorldSeedOrigin::from_name(origin.name()), Some(origin));
+        }
+
+        let mut broken = world.to_json();
+        broken["origin"] = "guessed".into();
+        assert_eq!(WorldSeedCandidate::from_json(&broken), None);
+    }
+}
@@ -0,0 +1,280 @@
+//! Searches that leave time to the frontend. The parallel searches keep every core of the rayon pool busy, on a single
+//! core machine the TUI then only gets to draw between two time slices of the workers and freezes for seconds. In
+//! cooperative mode a search runs on its own thread instead of the pool, in small chunks with a pause between them.
+
+use std::{
+    num::NonZeroUsize,
+    ops::Range,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use rayon::ThreadPool;
+
+/// Chunk sizes and pause of the cooperative mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cooperation {
+    /// Values of the upper 16 bits of the RNG state searched between two pauses, 65536 candidates each
+    pub structure_shards_per_chunk: u32,
+    /// World seed candidates checked between two pauses
+    pub world_seeds_per_chunk: u32,
+    /// A zero pause only yields the thread
+    pub pause: Duration,
+}
+
+impl Cooperation {
+    pub const DEFAULT: Self = Self {
+        structure_shards_per_chunk: 4,
+        world_seeds_per_chunk: 256,
+        pause: Duration::from_millis(1),
+    };
+
+    /// The cooperative mode if the machine has a single core, `force` turns it on anyway
+    pub fn detect(force: bool) -> Option<Self> {
+        let single_core = thread::available_parallelism().is_ok_and(|n| n == NonZeroUsize::MIN);
+        (force || single_core).then_some(Self::DEFAULT)
+    }
+
+    /// Gives way to the other threads between two chunks. On a rayon pool thread a pending job of the pool runs
+    /// first, so a frontend sharing the pool gets its turn too.
+    pub fn pause(&self) {
+        if rayon::yield_now() == Some(rayon::Yield::Executed) {
+            return;
+        }
+        if self.pause.is_zero() {
+            thread::yield_now();
+        } else {
+            thread::sleep(self.pause);
+        }
+    }
+}
+
+impl Default for Cooperation {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How many threads the parallel searches use and how often they report their progress. The cooperative mode ignores
+/// the threads, it runs on a single thread of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SearchOptions {
+    /// Size of the rayon pool the search runs in, the global pool (one thread per core) if None
+    pub threads: Option<usize>,
+    /// Candidates checked between two progress updates, 0 is read as 1
+    pub batch_size: u64,
+}
+
+impl SearchOptions {
+    pub const DEFAULT: Self = Self {
+        threads: None,
+        batch_size: 512,
+    };
+
+    /// Runs `f` in a pool of [`Self::threads`] threads, or in the global pool. The parallel iterators of `f` only use
+    /// the threads of the pool it runs in.
+    pub fn install<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        match self.threads.and_then(thread_pool) {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+
+    /// Threads of the pool [`Self::install`] runs in
+    #[inline]
+    pub fn thread_count(&self) -> usize {
+        self.install(rayon::current_num_threads)
+    }
+
+    /// The progress to report after `index` in a run of `len` candidates: a whole batch at the end of each batch, the
+    /// rest at the end of the run, 0 otherwise. Summed over the run, it adds up to `len`.
+    #[inline(always)]
+    pub fn batch_progress(&self, index: i64, len: i64) -> u64 {
+        let batch = self.batch_size.clamp(1, i64::MAX as u64) as i64;
+        let searched = index + 1;
+        if searched % batch == 0 {
+            batch as u64
+        } else if searched == len {
+            (searched % batch) as u64
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for SearchOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The pool of `threads` threads, built the first time it is asked for and then shared by every search. None if it
+/// can't be built.
+fn thread_pool(threads: usize) -> Option<Arc<ThreadPool>> {
+    static POOLS: Mutex<Vec<(usize, Arc<ThreadPool>)>> = Mutex::new(Vec::new());
+
+    let threads = threads.max(1);
+    let mut pools = POOLS.lock().ok()?;
+    if let Some((_, pool)) = pools.iter().find(|(n, _)| *n == threads) {
+        return Some(Arc::clone(pool));
+    }
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .ok()?,
+    );
+    pools.push((threads, Arc::clone(&pool)));
+    Some(pool)
+}
+
+/// Calls `chunk` on consecutive sub-ranges of `range` of at most `chunk_size` values, pausing between them. Stops
+/// early when `chunk` returns false.
+pub fn run_chunked(
+    range: Range<i64>,
+    chunk_size: u32,
+    cooperation: &Cooperation,
+    mut chunk: impl FnMut(Range<i64>) -> bool,
+) {
+    let chunk_size = chunk_size.max(1) as i64;
+    let mut start = range.start;
+    while start < range.end {
+        let end = start.saturating_add(chunk_size).min(range.end);
+        if !chunk(start..end) {
+            return;
+        }
+        start = end;
+        if start < range.end {
+            cooperation.pause();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
+    use rayon::{Scope, ThreadPoolBuilder};
+
+    use crate::cooperative::{Cooperation, SearchOptions, run_chunked, thread_pool};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Step {
+        Chunk,
+        Frame,
+    }
+
+    /// A frontend drawing one frame per job, queued again until the search is done
+    fn draw_frames<'a>(scope: &Scope<'a>, log: &'a Mutex<Vec<Step>>, done: &'a AtomicBool) {
+        if done.load(Ordering::Relaxed) {
+            return;
+        }
+        log.lock().unwrap().push(Step::Frame);
+        scope.spawn(move |scope| draw_frames(scope, log, done));
+    }
+
+    #[test]
+    fn test_chunks_interleave_with_frames() {
+        // a single thread, like a single core machine where the search and the frontend share the pool
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let log = Mutex::new(Vec::new());
+        let done = AtomicBool::new(false);
+        let cooperation = Cooperation {
+            pause: Duration::ZERO,
+            ..Cooperation::DEFAULT
+        };
+
+        let mut covered = Vec::new();
+        pool.install(|| {
+            rayon::scope(|scope| {
+                scope.spawn(|scope| draw_frames(scope, &log, &done));
+                run_chunked(0..1000, 64, &cooperation, |range| {
+                    covered.extend(range);
+                    log.lock().unwrap().push(Step::Chunk);
+                    true
+                });
+                done.store(true, Ordering::Relaxed);
+            });
+        });
+        assert_eq!(covered, (0..1000).collect::<Vec<_>>());
+
+        let log = log.into_inner().unwrap();
+        let chunks = log.iter().filter(|s| **s == Step::Chunk).count();
+        assert_eq!(chunks, 1000usize.div_ceil(64));
+        // a frame in every pause, never two chunks in a row
+        let frames = log.iter().filter(|s| **s == Step::Frame).count();
+        assert!(frames >= chunks - 1, "{:?}", log);
+        assert!(
+            log.windows(2).all(|w| w != [Step::Chunk, Step::Chunk]),
+            "{:?}",
+            log
+        );
+    }
+
+    #[test]
+    fn test_run_chunked_stops() {
+        let mut chunks = Vec::new();
+        run_chunked(10..20, 4, &Cooperation::DEFAULT, |range| {
+            chunks.push(range);
+            chunks.len() < 2
+        });
+        assert_eq!(chunks, vec![10..14, 14..18]);
+
+        // a chunk size of 0 is read as 1
+        let mut count = 0;
+        run_chunked(0..3, 0, &Cooperation::DEFAULT, |_| {
+            count += 1;
+            true
+        });
+        assert_eq!(count, 3);
+        assert_eq!(Cooperation::detect(true), Some(Cooperation::DEFAULT));
+    }
+
+    #[test]
+    fn test_search_options() {
+        for batch_size in [0, 1, 7, 512, 65536, 1 << 20] {
+            let options = SearchOptions {
+                batch_size,
+                ..SearchOptions::DEFAULT
+            };
+            let reports = (0..65536)
+                .map(|i| options.batch_progress(i, 65536))
+                .filter(|&p| p > 0)
+                .collect::<Vec<_>>();
+            assert_eq!(reports.iter().sum::<u64>(), 65536, "{}", batch_size);
+            assert_eq!(
+                reports.len() as u64,
+                65536u64.div_ceil(batch_size.max(1)),
+                "{}",
+                batch_size
+            );
+        }
+
+        let threads = |options: SearchOptions| options.install(rayon::current_num_threads);
+        let options = SearchOptions {
+            threads: Some(3),
+            ..SearchOptions::DEFAULT
+        };
+        assert_eq!(threads(options), 3);
+        assert_eq!(options.thread_count(), 3);
+        assert_eq!(
+            threads(SearchOptions::DEFAULT),
+            rayon::current_num_threads()
+        );
+
+        // the pool is built once for all the searches with the same thread count
+        assert!(Arc::ptr_eq(
+            &thread_pool(3).unwrap(),
+            &thread_pool(3).unwrap()
+        ));
+    }
+}
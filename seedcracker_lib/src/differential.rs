@@ -0,0 +1,411 @@
+//! Differential tests against the fixtures of `data/fixtures`, written by `tools/fixtures/ReferenceFixtures.java` with
+//! java.util.Random and the 1.16.5 code rewritten in Java. Each file holds its `schema` version and a list of `cases`,
+//! every case must agree bit for bit. New RNG code gets its cases in the generator and its test here.
+
+use serde_json::Value;
+
+use crate::{
+    features::{
+        bastion, buried_treasure, desert_temple, end_pillars::EndPillars, fortress, shipwreck,
+    },
+    lcg::JAVA_RANDOM,
+    loot_table::{DoubleChest, Inventory, ItemStack, LootTable, SingleChest},
+    math::Math,
+    random::{
+        self, JavaRandom, random_with_carver_seed, random_with_chunk_seed,
+        random_with_decorator_seed, random_with_population_seed, random_with_region_seed,
+        random_with_terrain_seed,
+    },
+};
+
+const SCHEMA: u64 = 1;
+
+/// The cases of `data/fixtures/v1/<name>.json`
+macro_rules! fixture {
+    ($name: literal) => {
+        load(
+            $name,
+            include_str!(concat!("../data/fixtures/v1/", $name, ".json")),
+        )
+    };
+}
+
+fn load(name: &str, text: &str) -> Vec<Value> {
+    let root: Value =
+        serde_json::from_str(text).unwrap_or_else(|err| panic!("{}.json: {}", name, err));
+    assert_eq!(
+        root["schema"].as_u64(),
+        Some(SCHEMA),
+        "{}.json has another schema",
+        name
+    );
+    match root["cases"].as_array() {
+        Some(cases) if !cases.is_empty() => cases.clone(),
+        _ => panic!("{}.json has no cases", name),
+    }
+}
+
+fn long(value: &Value) -> i64 {
+    value
+        .as_i64()
+        .unwrap_or_else(|| panic!("not an i64: {}", value))
+}
+
+fn int(value: &Value) -> i32 {
+    i32::try_from(long(value)).unwrap_or_else(|_| panic!("not an i32: {}", value))
+}
+
+fn list(value: &Value) -> &[Value] {
+    value
+        .as_array()
+        .unwrap_or_else(|| panic!("not a list: {}", value))
+}
+
+fn longs(value: &Value) -> Vec<i64> {
+    list(value).iter().map(long).collect()
+}
+
+fn ints(value: &Value) -> Vec<i32> {
+    list(value).iter().map(int).collect()
+}
+
+fn chunk(value: &Value) -> (i32, i32) {
+    match ints(value)[..] {
+        [x, z] => (x, z),
+        _ => panic!("not a chunk: {}", value),
+    }
+}
+
+/// An item of a fixture: null or `[name, count]`
+fn stack(value: &Value) -> Option<(&str, i32)> {
+    if value.is_null() {
+        return None;
+    }
+    match list(value) {
+        [name, count] => Some((name.as_str().expect("not an item name"), int(count))),
+        _ => panic!("not a stack: {}", value),
+    }
+}
+
+fn draw<T>(seed: i64, count: usize, mut next: impl FnMut(&mut JavaRandom) -> T) -> Vec<T> {
+    let mut rng = JavaRandom::new(seed);
+    (0..count).map(|_| next(&mut rng)).collect()
+}
+
+#[test]
+fn test_random() {
+    for case in fixture!("random") {
+        let seed = long(&case["seed"]);
+
+        let next_int = ints(&case["next_int"]);
+        assert_eq!(
+            draw(seed, next_int.len(), JavaRandom::next_int),
+            next_int,
+            "next_int of {}",
+            seed
+        );
+
+        let next_long = longs(&case["next_long"]);
+        assert_eq!(
+            draw(seed, next_long.len(), JavaRandom::next_long),
+            next_long,
+            "next_long of {}",
+            seed
+        );
+
+        let next_bool = ints(&case["next_bool"]);
+        assert_eq!(
+            draw(seed, next_bool.len(), |rng| rng.next_bool() as i32),
+            next_bool,
+            "next_bool of {}",
+            seed
+        );
+
+        let next_float = ints(&case["next_float_bits"]);
+        assert_eq!(
+            draw(seed, next_float.len(), |rng| rng.next_float().to_bits()
+                as i32),
+            next_float,
+            "next_float of {}",
+            seed
+        );
+
+        for bounded in list(&case["next_bounded_int"]) {
+            let bound = int(&bounded[0]);
+            let values = ints(&bounded[1]);
+            assert_eq!(
+                draw(seed, values.len(), |rng| rng.next_bounded_int(bound)),
+                values,
+                "next_bounded_int({}) of {}",
+                bound,
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn test_lcg_skip() {
+    for case in fixture!("lcg") {
+        let (seed, steps) = (long(&case["seed"]), long(&case["steps"]));
+        let state = JavaRandom::new(seed).get_seed();
+        let skipped = JAVA_RANDOM.combine(steps).next_seed(state);
+
+        let mut rng = JavaRandom::default();
+        rng.set_seed_raw(skipped);
+        assert_eq!(
+            rng.next_long(),
+            long(&case["next_long"]),
+            "{} steps from {}",
+            steps,
+            seed
+        );
+        assert_eq!(JAVA_RANDOM.combine(-steps).next_seed(skipped), state);
+    }
+}
+
+#[test]
+fn test_shuffle() {
+    for case in fixture!("shuffle") {
+        let (seed, size) = (long(&case["seed"]), int(&case["size"]));
+        let mut rng = JavaRandom::new(seed);
+        let mut permutation = (0..size).collect::<Vec<_>>();
+        random::shuffle(&mut permutation, &mut rng);
+
+        assert_eq!(
+            permutation,
+            ints(&case["permutation"]),
+            "{} elements with {}",
+            size,
+            seed
+        );
+        assert_eq!(rng.next_long(), long(&case["next_long"]));
+    }
+}
+
+#[test]
+fn test_seed_derivations() {
+    for case in fixture!("seeds") {
+        let world_seed = long(&case["world_seed"]);
+        let (x, z) = chunk(&case["chunk"]);
+        let block = Math::relative_chunk_coords((x, z), (0, 0));
+        let population_seed = random_with_population_seed(world_seed, block.0, block.1).1;
+        // the steps of random_with_decorator_seed count thousands, the generator's count ten thousands
+        let (index, step) = match ints(&case["decorator"])[..] {
+            [index, step] => (index, step * 10),
+            _ => panic!("not a decorator: {}", case["decorator"]),
+        };
+        let salt = int(&case["salt"]);
+
+        assert_eq!(
+            random_with_terrain_seed(x, z).1,
+            long(&case["terrain_seed"])
+        );
+        assert_eq!(population_seed, long(&case["population_seed"]));
+        assert_eq!(
+            random_with_decorator_seed(population_seed, index, step).1,
+            long(&case["decorator_seed"])
+        );
+        assert_eq!(
+            random_with_carver_seed(world_seed, x, z, long(&case["carver"])).1,
+            long(&case["carver_seed"])
+        );
+        assert_eq!(
+            random_with_region_seed(world_seed, x, z, salt).1,
+            long(&case["region_seed"])
+        );
+        assert_eq!(
+            random_with_chunk_seed(world_seed, x, z).1,
+            long(&case["chunk_seed"])
+        );
+    }
+}
+
+#[test]
+fn test_loot_table_seeds() {
+    for case in fixture!("seeds") {
+        let world_seed = long(&case["world_seed"]);
+        let chunk = chunk(&case["chunk"]);
+
+        assert_eq!(
+            buried_treasure::get_buried_treasure_loot_table_seed(world_seed, chunk),
+            long(&case["buried_treasure"]),
+            "buried treasure of {} in {:?}",
+            world_seed,
+            chunk
+        );
+
+        for (name, seed_of) in [
+            (
+                "bastion",
+                bastion::get_bastion_chest_loot_table_seed as fn(i64, (i32, i32), i32) -> i64,
+            ),
+            ("shipwreck", shipwreck::get_shipwreck_chest_loot_table_seed),
+            ("fortress", fortress::get_fortress_chest_loot_table_seed),
+            (
+                "desert_temple",
+                desert_temple::get_desert_temple_chest_loot_table_seed,
+            ),
+        ] {
+            let expected = longs(&case[name]);
+            let seeds = (0..expected.len() as i32)
+                .map(|index| seed_of(world_seed, chunk, index))
+                .collect::<Vec<_>>();
+            assert_eq!(seeds, expected, "{} of {} in {:?}", name, world_seed, chunk);
+        }
+
+        let stepped = bastion::get_bastion_chest_seeds(world_seed, chunk)
+            .loot_table_seeds(0..4)
+            .map(|(_, seed)| seed)
+            .collect::<Vec<_>>();
+        assert_eq!(stepped, longs(&case["bastion"]));
+    }
+}
+
+#[test]
+fn test_chests() {
+    let registry = buried_treasure::item_registry();
+    let table = buried_treasure::get_loot_table();
+    let named = |item: usize| registry.name_of(item).expect("unregistered item");
+
+    for case in fixture!("chests") {
+        assert_eq!(case["table"].as_str(), Some("buried_treasure"));
+        let seed = long(&case["loot_seed"]);
+
+        // no stack of the table reaches its stack size, splitting them changes nothing
+        let raw = table
+            .generate_raw_loot(&mut JavaRandom::new(seed), 0.0)
+            .into_iter()
+            .map(|stack| Some((named(stack.item), stack.count)))
+            .collect::<Vec<_>>();
+        let expected = list(&case["raw"]).iter().map(stack).collect::<Vec<_>>();
+        assert_eq!(raw, expected, "raw loot of {}", seed);
+
+        let fill = |chest: &mut dyn Inventory| {
+            table.generate_in_inventory(chest, &mut JavaRandom::new(seed), 0.0);
+            (0..chest.slot_count())
+                .map(|slot| chest.get_item(slot).map(|s| (named(s.item), s.count)))
+                .collect::<Vec<_>>()
+        };
+        let expected = list(&case["slots"]).iter().map(stack).collect::<Vec<_>>();
+        assert_eq!(fill(&mut SingleChest::new()), expected, "chest of {}", seed);
+        let expected = list(&case["double_slots"])
+            .iter()
+            .map(stack)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fill(&mut DoubleChest::new()),
+            expected,
+            "double chest of {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn test_split() {
+    // the items of the fixture are numbers
+    let stacks = |value: &Value| {
+        list(value)
+            .iter()
+            .map(|value| match stack(value) {
+                Some((item, count)) => ItemStack::of(item.parse().expect("not an item id"), count),
+                None => panic!("empty stack in {}", value),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for case in fixture!("split") {
+        let seed = long(&case["seed"]);
+        let mut loot = stacks(&case["stacks"]);
+        let mut rng = JavaRandom::new(seed);
+        LootTable::shuffle_loot(&mut loot, int(&case["empty_slots"]), &mut rng);
+
+        assert_eq!(
+            loot,
+            stacks(&case["shuffled"]),
+            "stacks split with {}",
+            seed
+        );
+        assert_eq!(rng.next_long(), long(&case["next_long"]));
+    }
+}
+
+#[test]
+fn test_nearly_full_chests() {
+    let registry = buried_treasure::item_registry();
+    let table = buried_treasure::get_loot_table();
+    let occupied = ItemStack::of(usize::MAX, 1);
+    let named = |stack: &ItemStack| match stack.item {
+        usize::MAX => "occupied",
+        item => registry.name_of(item).expect("unregistered item"),
+    };
+
+    for case in fixture!("nearly_full") {
+        assert_eq!(case["table"].as_str(), Some("buried_treasure"));
+        let seed = long(&case["loot_seed"]);
+        let mut chest = SingleChest::new();
+        for slot in ints(&case["occupied"]) {
+            chest.set_item(slot, Some(occupied.clone()));
+        }
+        table.generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), 0.0);
+
+        let slots = (0..chest.slot_count())
+            .map(|slot| chest.get_item(slot).map(|s| (named(s), s.count)))
+            .collect::<Vec<_>>();
+        let expected = list(&case["slots"]).iter().map(stack).collect::<Vec<_>>();
+        assert_eq!(slots, expected, "nearly full chest of {}", seed);
+    }
+}
+
+#[test]
+fn test_features() {
+    for case in fixture!("features") {
+        match case["feature"].as_str() {
+            Some("buried_treasure") => {
+                let (world_seed, chunk) = (long(&case["world_seed"]), chunk(&case["chunk"]));
+                assert_eq!(
+                    buried_treasure::generates_at(world_seed, chunk),
+                    case["generates"].as_bool().expect("not a bool"),
+                    "buried treasure of {} in {:?}",
+                    world_seed,
+                    chunk
+                );
+            }
+            Some("bastion") => {
+                let (seed, region) = (long(&case["structure_seed"]), chunk(&case["region"]));
+                let start = (!case["start"].is_null()).then(|| chunk(&case["start"]));
+                assert_eq!(
+                    bastion::get_bastion_start(seed, region),
+                    start,
+                    "bastion of {} in {:?}",
+                    seed,
+                    region
+                );
+            }
+            Some("end_pillars") => {
+                let pillar_seed = EndPillars::pillar_seed(long(&case["world_seed"]));
+                assert_eq!(pillar_seed, long(&case["pillar_seed"]));
+
+                let mut pillars = EndPillars::new();
+                pillars.from_seed(pillar_seed);
+                let heights = pillars.iter().map(|p| p.height).collect::<Vec<_>>();
+                let radii = pillars.iter().map(|p| p.radius).collect::<Vec<_>>();
+                let caged = pillars.iter().map(|p| p.caged).collect::<Vec<_>>();
+                let expected_caged = list(&case["caged"])
+                    .iter()
+                    .map(|caged| caged.as_bool().expect("not a bool"))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    heights,
+                    ints(&case["heights"]),
+                    "pillars of {}",
+                    pillar_seed
+                );
+                assert_eq!(radii, ints(&case["radii"]), "pillars of {}", pillar_seed);
+                assert_eq!(caged, expected_caged, "pillars of {}", pillar_seed);
+            }
+            _ => panic!("unknown feature: {}", case["feature"]),
+        }
+    }
+}
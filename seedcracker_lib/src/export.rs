@@ -0,0 +1,201 @@
+use std::io::{self, Write};
+
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SeedNumberFormat {
+    #[default]
+    Decimal,
+    /// Two's complement 64-bit hexadecimal, prefixed with `0x`
+    Hex,
+}
+
+/// Options for seed lists in the plain text format understood by Cubiomes Viewer: one seed per line.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SeedExportOptions {
+    /// Append `# comment` after each seed that has one, and write a header line for structure seed expansions
+    pub comments: bool,
+    pub number_format: SeedNumberFormat,
+}
+
+impl SeedExportOptions {
+    #[inline(always)]
+    pub const fn new(comments: bool, number_format: SeedNumberFormat) -> Self {
+        Self {
+            comments,
+            number_format,
+        }
+    }
+
+    #[inline]
+    pub fn format_seed(&self, seed: i64) -> String {
+        match self.number_format {
+            SeedNumberFormat::Decimal => format!("{}", seed),
+            SeedNumberFormat::Hex => format!("0x{:016x}", seed as u64),
+        }
+    }
+}
+
+/// All 65536 world seeds sharing the lower 48 bits of `structure_seed`, in ascending order of the upper 16 bits
+///
+/// ```
+/// use mcseedcracker::export::structure_seed_completions;
+///
+/// assert!(structure_seed_completions(180066252004364).any(|s| s == -7193194438565520372));
+/// ```
+#[inline]
+pub fn structure_seed_completions(structure_seed: i64) -> impl Iterator<Item = i64> {
+    let lower = structure_seed & 0xFFFF_FFFF_FFFF;
+    (0..65536i64).map(move |upper| (upper << 48) | lower)
+}
+
+/// Writes one world seed per line, followed by its comment if `options.comments` is set
+pub fn write_world_seeds<'a>(
+    writer: &mut impl Write,
+    seeds: impl IntoIterator<Item = (i64, Option<&'a str>)>,
+    options: &SeedExportOptions,
+) -> io::Result<()> {
+    for (seed, comment) in seeds {
+        match comment {
+            Some(comment) if options.comments => {
+                writeln!(writer, "{} # {}", options.format_seed(seed), comment)?
+            }
+            _ => writeln!(writer, "{}", options.format_seed(seed))?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes every world seed candidate of a structure seed, one per line
+pub fn write_structure_seed_completions(
+    writer: &mut impl Write,
+    structure_seed: i64,
+    options: &SeedExportOptions,
+) -> io::Result<()> {
+    if options.comments {
+        writeln!(
+            writer,
+            "# structure seed {}",
+            options.format_seed(structure_seed & 0xFFFF_FFFF_FFFF)
+        )?;
+    }
+    for seed in structure_seed_completions(structure_seed) {
+        writeln!(writer, "{}", options.format_seed(seed))?;
+    }
+    Ok(())
+}
+
+/// Writes one JSON value per line, e.g. the records of [`crate::candidates`]
+pub fn write_json_lines(
+    writer: &mut impl Write,
+    values: impl IntoIterator<Item = serde_json::Value>,
+) -> io::Result<()> {
+    for value in values {
+        writeln!(writer, "{}", value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::WorldSeedCandidate;
+
+    const RESULTS: [(i64, Option<&str>); 3] = [
+        (
+            -7193194438565520372,
+            Some("pillar seed 13847, buried treasure"),
+        ),
+        (180066252004364, None),
+        (42, Some("biomes")),
+    ];
+
+    #[test]
+    fn test_world_seeds_decimal() {
+        let mut out = Vec::new();
+        write_world_seeds(&mut out, RESULTS, &SeedExportOptions::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "-7193194438565520372\n180066252004364\n42\n"
+        );
+
+        let mut out = Vec::new();
+        write_world_seeds(
+            &mut out,
+            RESULTS,
+            &SeedExportOptions::new(true, SeedNumberFormat::Decimal),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "-7193194438565520372 # pillar seed 13847, buried treasure\n180066252004364\n42 # biomes\n"
+        );
+    }
+
+    #[test]
+    fn test_world_seeds_hex() {
+        let mut out = Vec::new();
+        write_world_seeds(
+            &mut out,
+            RESULTS,
+            &SeedExportOptions::new(false, SeedNumberFormat::Hex),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "0x9c2ca3c4f0fc9c0c\n0x0000a3c4f0fc9c0c\n0x000000000000002a\n"
+        );
+    }
+
+    #[test]
+    fn test_structure_seed_completions() {
+        let structure_seed = 180066252004364;
+
+        let completions = structure_seed_completions(structure_seed).collect::<Vec<_>>();
+        assert_eq!(completions.len(), 65536);
+        assert_eq!(completions[0], structure_seed);
+        assert!(completions.contains(&-7193194438565520372));
+        assert!(
+            completions
+                .iter()
+                .all(|s| s & 0xFFFF_FFFF_FFFF == structure_seed)
+        );
+
+        let mut out = Vec::new();
+        write_structure_seed_completions(
+            &mut out,
+            structure_seed,
+            &SeedExportOptions::new(true, SeedNumberFormat::Decimal),
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 65537);
+        assert_eq!(lines[0], "# structure seed 180066252004364");
+        assert_eq!(lines[1], "180066252004364");
+        assert_eq!(lines[2], "461541228715020");
+        assert_eq!(lines[65536], "-101408724706292");
+        assert!(out.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let candidates = [
+            WorldSeedCandidate::new(180066252004364, -7193194438565520372, None),
+            WorldSeedCandidate::new(180066252004364, 42, None),
+        ];
+        let mut out = Vec::new();
+        write_json_lines(&mut out, candidates.iter().map(WorldSeedCandidate::to_json)).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            r#"{"origin":"hashed-word","score":1.0,"seed":42,"structure_seed":180066252004364}"#
+        );
+
+        let read = lines
+            .iter()
+            .map(|l| WorldSeedCandidate::from_json(&serde_json::from_str(l).unwrap()))
+            .collect::<Option<Vec<_>>>();
+        assert_eq!(read.as_deref(), Some(&candidates[..]));
+    }
+}
@@ -0,0 +1,470 @@
+//! Conditions combining pieces of evidence, for what the flat evidence lists of the searches can't express: "the
+//! biome at A is a jungle, or the biome at B is a jungle and the buried treasure holds this chest". The searches check
+//! one on top of their lists, see [`crate::search::StructureSeedSearchData::with_condition`] and
+//! [`crate::search::WorldSeedSearchData::with_condition`].
+//!
+//! Each stage checks the leaves it can. The structure seed stage only knows the lower 48 bits of the world seed, so
+//! the biome leaves are unknown there: a structure seed is only dropped once the condition is false whatever they turn
+//! out to be.
+
+use std::fmt::Display;
+
+use cubiomes::enums::MCVersion;
+
+use crate::{
+    biome_cache::BiomeCache,
+    features::buried_treasure,
+    rarity::BiomeRarityTable,
+    search::{ConstraintError, StructureData, WorldExtraData},
+    templates::find_template,
+};
+
+/// A piece of evidence of an [`EvidenceExpr`]
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum EvidenceItem {
+    /// Checked from the structure seed stage on
+    Structure(StructureData),
+    /// Checked at the world seed stage. Decoration counts only rank the world seeds and can't be part of a condition.
+    World(WorldExtraData),
+}
+
+impl EvidenceItem {
+    /// Estimated probability that a random seed passes the check
+    pub fn pass_probability(&self, table: &BiomeRarityTable) -> f64 {
+        let data = match self {
+            EvidenceItem::World(data) => return data.pass_probability(table),
+            EvidenceItem::Structure(data) => data,
+        };
+        let generates = buried_treasure::PROB as f64;
+        let contents =
+            find_template("buried_treasure").map_or(1.0, |t| 0.5f64.powf(t.estimated_bits));
+        match data {
+            StructureData::BuriedTreasureContents { .. } => generates * contents,
+            StructureData::BuriedTreasureCounts { .. } => generates,
+            StructureData::BuriedTreasureContentsInArea { area, .. } => {
+                (generates * contents * area.chunk_count() as f64).min(1.0)
+            }
+            // the chunk is known to hold the chest, any of the allowed indices can match
+            StructureData::BastionChestContents { index, .. } => {
+                let contents = find_template("bastion_treasure")
+                    .map_or(1.0, |t| 0.5f64.powf(t.estimated_bits));
+                (contents * index.indices().len() as f64).min(1.0)
+            }
+        }
+    }
+}
+
+/// Why an [`EvidenceExpr`] can't be searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprError {
+    /// `Not` of anything but a leaf
+    NotOfExpression,
+    /// `Not` of a buried treasure: nearly every structure seed would pass it, each after regenerating the chest
+    NotOfStructure,
+    /// A decoration count leaf
+    TieBreaker,
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::NotOfExpression => {
+                write!(f, "Not can only negate biome samples, not All, Any or Not")
+            }
+            ExprError::NotOfStructure => write!(
+                f,
+                "Not can only negate biome samples, not a buried treasure"
+            ),
+            ExprError::TieBreaker => write!(
+                f,
+                "decoration counts only rank the seeds, they can't be part of a condition"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// A boolean combination of pieces of evidence, see the [module documentation](self)
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum EvidenceExpr {
+    /// Holds if every expression holds, an empty `All` always holds
+    All(Vec<EvidenceExpr>),
+    /// Holds if one of the expressions holds, an empty `Any` never holds
+    Any(Vec<EvidenceExpr>),
+    /// Only of a biome leaf, see [`ExprError`]
+    Not(Box<EvidenceExpr>),
+    Leaf(EvidenceItem),
+}
+
+impl EvidenceExpr {
+    #[inline]
+    pub const fn structure(data: StructureData) -> Self {
+        Self::Leaf(EvidenceItem::Structure(data))
+    }
+
+    #[inline]
+    pub const fn world(data: WorldExtraData) -> Self {
+        Self::Leaf(EvidenceItem::World(data))
+    }
+
+    /// Holds if both hold, `self` is extended if it is already an `All`
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::All(mut exprs) => {
+                exprs.push(other);
+                Self::All(exprs)
+            }
+            expr => Self::All(vec![expr, other]),
+        }
+    }
+
+    /// Holds if one of them holds, `self` is extended if it is already an `Any`
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::Any(mut exprs) => {
+                exprs.push(other);
+                Self::Any(exprs)
+            }
+            expr => Self::Any(vec![expr, other]),
+        }
+    }
+
+    /// Holds if `self` doesn't, only for biome leaves
+    pub fn negate(self) -> Result<Self, ExprError> {
+        Self::check_negated(&self)?;
+        Ok(Self::Not(Box::new(self)))
+    }
+
+    fn check_negated(expr: &Self) -> Result<(), ExprError> {
+        match expr {
+            Self::All(_) | Self::Any(_) | Self::Not(_) => Err(ExprError::NotOfExpression),
+            Self::Leaf(EvidenceItem::Structure(_)) => Err(ExprError::NotOfStructure),
+            leaf => leaf.validate(),
+        }
+    }
+
+    /// The restrictions of [`ExprError`], for the expressions that weren't built with [`EvidenceExpr::negate`]
+    pub fn validate(&self) -> Result<(), ExprError> {
+        match self {
+            Self::All(exprs) | Self::Any(exprs) => exprs.iter().try_for_each(Self::validate),
+            Self::Not(expr) => Self::check_negated(expr),
+            Self::Leaf(EvidenceItem::World(data)) if data.is_tie_breaker() => {
+                Err(ExprError::TieBreaker)
+            }
+            Self::Leaf(_) => Ok(()),
+        }
+    }
+
+    /// The biome samples that can never be checked in worlds of the version, whatever the seed
+    pub fn invalid_samples(&self, version: MCVersion) -> Vec<ConstraintError> {
+        match self {
+            Self::All(exprs) | Self::Any(exprs) => exprs
+                .iter()
+                .flat_map(|expr| expr.invalid_samples(version))
+                .collect(),
+            Self::Not(expr) => expr.invalid_samples(version),
+            Self::Leaf(EvidenceItem::World(data)) => data.invalid_samples(version),
+            Self::Leaf(EvidenceItem::Structure(_)) => Vec::new(),
+        }
+    }
+
+    /// Estimated probability that a random seed passes, taking the leaves as independent
+    pub fn pass_probability(&self, table: &BiomeRarityTable) -> f64 {
+        match self {
+            Self::All(exprs) => exprs.iter().map(|e| e.pass_probability(table)).product(),
+            Self::Any(exprs) => {
+                1.0 - exprs
+                    .iter()
+                    .map(|e| 1.0 - e.pass_probability(table))
+                    .product::<f64>()
+            }
+            Self::Not(expr) => 1.0 - expr.pass_probability(table),
+            Self::Leaf(item) => item.pass_probability(table),
+        }
+    }
+
+    /// Sorts the expressions of each `All` so the least likely to pass come first, and those of each `Any` so the most
+    /// likely do: the checks then stop at the first expression that decides. The result of a check doesn't depend on
+    /// the order, only its speed does.
+    pub fn order_by_selectivity(&mut self, table: &BiomeRarityTable) {
+        match self {
+            Self::All(exprs) | Self::Any(exprs) => {
+                for expr in exprs.iter_mut() {
+                    expr.order_by_selectivity(table);
+                }
+            }
+            Self::Not(expr) => expr.order_by_selectivity(table),
+            Self::Leaf(EvidenceItem::World(data)) => data.sort_samples_by_rarity(table),
+            Self::Leaf(EvidenceItem::Structure(_)) => {}
+        }
+        match self {
+            Self::All(exprs) => exprs.sort_by_cached_key(|e| e.pass_probability(table).to_bits()),
+            Self::Any(exprs) => {
+                exprs.sort_by_cached_key(|e| u64::MAX - e.pass_probability(table).to_bits())
+            }
+            _ => {}
+        }
+    }
+
+    /// The check of the structure seed stage, `None` while it depends on the biome leaves
+    pub fn check_structure_seed(&self, seed: i64) -> Option<bool> {
+        match self {
+            Self::All(exprs) => {
+                let mut known = true;
+                for expr in exprs.iter() {
+                    match expr.check_structure_seed(seed) {
+                        Some(false) => return Some(false),
+                        Some(true) => {}
+                        None => known = false,
+                    }
+                }
+                known.then_some(true)
+            }
+            Self::Any(exprs) => {
+                let mut known = true;
+                for expr in exprs.iter() {
+                    match expr.check_structure_seed(seed) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => known = false,
+                    }
+                }
+                known.then_some(false)
+            }
+            Self::Not(expr) => expr.check_structure_seed(seed).map(|passes| !passes),
+            Self::Leaf(EvidenceItem::Structure(data)) => Some(data.check_seed(seed)),
+            Self::Leaf(EvidenceItem::World(_)) => None,
+        }
+    }
+
+    /// The check of the world seed stage, every leaf is checked
+    pub fn check_world_seed(&self, seed: i64, version: MCVersion) -> Result<bool, ConstraintError> {
+        self.check_world_seed_with(seed, version, None)
+    }
+
+    /// [`EvidenceExpr::check_world_seed`] of a seed checked again and again, the biomes are looked up in `cache`
+    /// first
+    pub fn check_world_seed_cached(
+        &self,
+        seed: i64,
+        version: MCVersion,
+        cache: &BiomeCache,
+    ) -> Result<bool, ConstraintError> {
+        self.check_world_seed_with(seed, version, Some(cache))
+    }
+
+    fn check_world_seed_with(
+        &self,
+        seed: i64,
+        version: MCVersion,
+        cache: Option<&BiomeCache>,
+    ) -> Result<bool, ConstraintError> {
+        match self {
+            Self::All(exprs) => {
+                for expr in exprs.iter() {
+                    if !expr.check_world_seed_with(seed, version, cache)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Self::Any(exprs) => {
+                for expr in exprs.iter() {
+                    if expr.check_world_seed_with(seed, version, cache)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Self::Not(expr) => expr
+                .check_world_seed_with(seed, version, cache)
+                .map(|passes| !passes),
+            Self::Leaf(EvidenceItem::Structure(data)) => Ok(data.check_seed(seed)),
+            Self::Leaf(EvidenceItem::World(data)) => data.check_seed_with(seed, version, cache),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubiomes::enums::{BiomeID, MCVersion};
+
+    use crate::{
+        biome_cache::BiomeCache,
+        expr::{EvidenceExpr, ExprError},
+        features::buried_treasure::{build_fast_inventory_compare_context, get_loot_table},
+        math::Math,
+        rarity::BiomeRarityTable,
+        search::{StructureData, StructureSeedSearchData, WorldExtraData, WorldSeedSearchData},
+        self_test::{self, BURIED_TREASURE_POS, PILLAR_SEED, STRUCTURE_SEED, WORLD_SEED},
+    };
+
+    fn biome(x: i32, y: i32, z: i32, biome: BiomeID) -> EvidenceExpr {
+        EvidenceExpr::world(WorldExtraData::OverworldBiomeData(vec![(x, y, z, biome)]))
+    }
+
+    fn treasure() -> EvidenceExpr {
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        EvidenceExpr::structure(StructureData::BuriedTreasureContents {
+            chunk_x: chunk.0,
+            chunk_z: chunk.1,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        })
+    }
+
+    #[test]
+    fn test_nested_expressions() {
+        let jungle = biome(137, 73, -90, BiomeID::jungle);
+        let not_jungle = biome(400, 60, 800, BiomeID::jungle);
+        // the buried treasure is on a beach
+        let beach = biome(400, 60, 800, BiomeID::beach);
+
+        let expr = not_jungle.clone().or(jungle.clone().and(treasure()));
+        assert_eq!(
+            expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(true)
+        );
+        // the biome leaves are unknown, the treasure decides
+        assert_eq!(expr.check_structure_seed(STRUCTURE_SEED), None);
+        assert_eq!(expr.check_structure_seed(STRUCTURE_SEED + 1), None);
+
+        let expr = not_jungle.clone().or(treasure()).and(beach.clone());
+        assert_eq!(
+            expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(true)
+        );
+        // without the treasure the structure seed stage already knows
+        let expr = not_jungle.clone().and(treasure());
+        assert_eq!(expr.check_structure_seed(STRUCTURE_SEED + 1), Some(false));
+        assert_eq!(
+            expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(false)
+        );
+        assert_eq!(treasure().check_structure_seed(STRUCTURE_SEED), Some(true));
+
+        let expr = EvidenceExpr::Any(vec![
+            not_jungle.clone(),
+            EvidenceExpr::All(vec![beach.negate().unwrap(), jungle.clone()]),
+        ]);
+        assert_eq!(
+            expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(false)
+        );
+        let expr = jungle.and(not_jungle.negate().unwrap());
+        assert_eq!(
+            expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(true)
+        );
+
+        assert_eq!(
+            EvidenceExpr::All(vec![]).check_structure_seed(0),
+            Some(true)
+        );
+        assert_eq!(
+            EvidenceExpr::Any(vec![]).check_world_seed(0, MCVersion::MC_1_16_5),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_cached_checks() {
+        let cache = BiomeCache::default();
+        let jungle = biome(137, 73, -90, BiomeID::jungle);
+        let beach = biome(400, 60, 800, BiomeID::beach);
+
+        // toggling one leaf of the condition, the other one is looked up once
+        for expr in [
+            jungle.clone().and(beach.clone()),
+            jungle.clone().and(beach.negate().unwrap()),
+            jungle.clone().or(beach.clone()),
+        ] {
+            assert_eq!(
+                expr.check_world_seed_cached(WORLD_SEED, MCVersion::MC_1_16_5, &cache),
+                expr.check_world_seed(WORLD_SEED, MCVersion::MC_1_16_5)
+            );
+        }
+        // `or` stops at the jungle
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (3, 2));
+    }
+
+    #[test]
+    fn test_not_restrictions() {
+        assert_eq!(treasure().negate().err(), Some(ExprError::NotOfStructure));
+        let any = biome(0, 60, 0, BiomeID::plains).or(biome(16, 60, 0, BiomeID::plains));
+        assert_eq!(any.negate().err(), Some(ExprError::NotOfExpression));
+        let negated = biome(0, 60, 0, BiomeID::plains).negate().unwrap();
+        assert!(negated.validate().is_ok());
+        assert_eq!(negated.negate().err(), Some(ExprError::NotOfExpression));
+
+        // built without negate, caught by validate
+        let expr = biome(0, 60, 0, BiomeID::plains).and(EvidenceExpr::Not(Box::new(treasure())));
+        assert_eq!(expr.validate(), Err(ExprError::NotOfStructure));
+        let decoration = EvidenceExpr::world(WorldExtraData::DecorationCount {
+            chunk: (0, 0),
+            decoration: crate::features::decoration::DecorationKind::Cactus,
+            feature_index: 0,
+            surface_y: 64,
+            observed_count: 3,
+            tolerance: 0,
+        });
+        assert_eq!(
+            biome(0, 60, 0, BiomeID::plains).or(decoration).validate(),
+            Err(ExprError::TieBreaker)
+        );
+    }
+
+    #[test]
+    fn test_order_by_selectivity() {
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+        let plains = biome(0, 60, 0, BiomeID::plains);
+        let jungle = biome(137, 73, -90, BiomeID::jungle);
+        assert!(
+            jungle.pass_probability(table) < plains.pass_probability(table),
+            "the fixture needs jungles to be rarer than plains"
+        );
+
+        let mut all = plains.clone().and(treasure()).and(jungle.clone());
+        all.order_by_selectivity(table);
+        assert_eq!(all, treasure().and(jungle.clone()).and(plains.clone()));
+
+        let mut any = jungle.clone().or(plains.clone());
+        any.order_by_selectivity(table);
+        assert_eq!(any, plains.clone().or(jungle.clone()));
+        let p = any.pass_probability(table);
+        assert!(p > plains.pass_probability(table) && p < 1.0);
+    }
+
+    #[test]
+    fn test_searches_with_condition() {
+        // the biome is unknown to the structure seed search, the treasure decides
+        let condition = biome(137, 73, -90, BiomeID::jungle).and(treasure());
+        let structure = StructureSeedSearchData::new(PILLAR_SEED, vec![], 10)
+            .with_condition(Some(condition.clone()));
+        assert_eq!(structure.search_bounded(47520..47525), vec![STRUCTURE_SEED]);
+
+        let condition = biome(400, 60, 800, BiomeID::jungle).or(condition);
+        let world = WorldSeedSearchData::new(STRUCTURE_SEED, false, vec![], 5)
+            .with_condition(Some(condition));
+        assert!(
+            world
+                .search_bounded(39978..39982)
+                .seeds
+                .contains(&WORLD_SEED)
+        );
+        let world = world.with_condition(Some(biome(137, 73, -90, BiomeID::beach)));
+        assert!(
+            !world
+                .search_bounded(39978..39982)
+                .seeds
+                .contains(&WORLD_SEED)
+        );
+    }
+}
@@ -1,17 +1,26 @@
 use crate::{
-    lcg,
+    features::decorator_chest::{ChestIndex, DecoratorChestSeeds, detect_chest_index},
     loot_table::{
-        ItemLootPoolEntryBuilder, LootPoolBuilder, LootTable, LootTableBuilder, SetCountFunction,
+        FastInventoryCompareContext, InvalidObservation, ItemLootPoolEntryBuilder, ItemRegistry,
+        LootPoolBuilder, LootTable, LootTableBuilder, RegisteredItem, SetCountFunction,
         SetDamageFunction, SetEnchantsRandomlyFunction, SingleChest,
     },
     math::Math,
-    random::{JavaRandom, random_with_decorator_seed, random_with_population_seed},
+    random::{
+        DecoratorSalt, JavaRandom, random_with_decorator_seed, random_with_population_seed,
+        random_with_region_seed,
+    },
     utils::{
         durability::{ArmorMaterial, ItemWithDurability, ToolMaterial},
         enchants,
     },
 };
 
+/// Placement of the bastion remnants of 1.16, shared with the nether fortresses
+pub const SALT: i32 = 30084232;
+pub const SPACING: i32 = 27;
+pub const SEPARATION: i32 = 4;
+
 pub mod items {
     pub mod other {
         pub const DIAMOND_PICKAXE: usize = 1;
@@ -38,7 +47,7 @@ pub mod items {
         pub const GOLDEN_LEGGINGS: usize = 22;
         pub const CRYING_OBSIDIAN: usize = 24;
         pub const GILDED_BLACKSTONE: usize = 25;
-        pub const CHAIN: usize = 25;
+        pub const CHAIN: usize = 26;
         pub const MAGMA_CREAM: usize = 27;
         pub const BONE_BLOCK: usize = 28;
         pub const IRON_NUGGET: usize = 29;
@@ -99,7 +108,7 @@ pub mod items {
     pub mod bridges {
         pub const LODESTONE: usize = 1;
         pub const CROSSBOW: usize = 2;
-        pub const SPECTRAL_ARROW: usize = 2;
+        pub const SPECTRAL_ARROW: usize = 3;
         pub const GILDED_BLACKSTONE: usize = 4;
         pub const CRYING_OBSIDIAN: usize = 5;
         pub const GOLD_BLOCK: usize = 6;
@@ -119,12 +128,276 @@ pub mod items {
     }
 }
 
+/// Larger than every item id of the bastion loot tables
+pub const ITEM_COUNT: usize = 35;
+
 pub mod book_enchants {
     pub mod other {
         pub const SOUL_SPEED: (i32, i32, i32) = (1, 1, 3);
     }
 }
 
+/// An unstackable item `enchant_randomly` picks from `enchantments` for, with the resource names of
+/// [`crate::utils::enchants`]
+fn enchanted_item(id: usize, names: &[&str], enchantments: &[(i32, i32, i32)]) -> RegisteredItem {
+    names.iter().zip(enchantments).fold(
+        RegisteredItem::new(id).stack_size(1),
+        |item, (name, enchantment)| item.enchantment(name, *enchantment),
+    )
+}
+
+/// The items of the loot table of a chest type, for [`crate::tooltip::parse_tooltip_dump`]
+pub fn item_registry(chest_type: BastionChestType) -> ItemRegistry {
+    match chest_type {
+        BastionChestType::HoglinStables => hoglin_stables_item_registry(),
+        BastionChestType::TreasureRoom => treasure_room_item_registry(),
+        BastionChestType::Bridges => bridges_item_registry(),
+        BastionChestType::Other => other_item_registry(),
+    }
+}
+
+fn other_item_registry() -> ItemRegistry {
+    use crate::features::bastion::{book_enchants::other::SOUL_SPEED, items::other::*};
+
+    ItemRegistry::new()
+        .register(
+            "diamond_pickaxe",
+            enchanted_item(
+                DIAMOND_PICKAXE,
+                &enchants::PICKAXE_NAMES,
+                &enchants::PICKAXE,
+            ),
+        )
+        .register(
+            "diamond_shovel",
+            RegisteredItem::new(DIAMOND_SHOVEL).stack_size(1),
+        )
+        .register(
+            "crossbow",
+            enchanted_item(CROSSBOW, &enchants::CROSSBOW_NAMES, &enchants::CROSSBOW)
+                .durability(ItemWithDurability::Crossbow.durability()),
+        )
+        .register("ancient_debris", RegisteredItem::new(ANCIENT_DEBRIS))
+        .register("netherite_scrap", RegisteredItem::new(NETHERITE_SCRAP))
+        .register("spectral_arrow", RegisteredItem::new(SPECTRAL_ARROW))
+        .register(
+            "piglin_banner_pattern",
+            RegisteredItem::new(PIGLIN_BANNER_PATTERN).stack_size(1),
+        )
+        .register(
+            "music_disc_pigstep",
+            RegisteredItem::new(MUSIC_DISC_PIGSTEP).stack_size(1),
+        )
+        .register("golden_carrot", RegisteredItem::new(GOLDEN_CARROT))
+        .register("golden_apple", RegisteredItem::new(GOLDEN_APPLE))
+        .register(
+            "enchanted_book",
+            RegisteredItem::new(ENCHANTED_BOOK)
+                .stack_size(1)
+                .enchantment("soul_speed", SOUL_SPEED),
+        )
+        .register(
+            "iron_sword",
+            enchanted_item(IRON_SWORD, &enchants::SWORD_NAMES, &enchants::SWORD)
+                .durability(ItemWithDurability::Sword(ToolMaterial::Iron).durability()),
+        )
+        .register("iron_block", RegisteredItem::new(IRON_BLOCK))
+        .register(
+            "golden_boots",
+            RegisteredItem::new(GOLDEN_BOOTS)
+                .stack_size(1)
+                .enchantment("soul_speed", SOUL_SPEED),
+        )
+        .register(
+            "golden_axe",
+            enchanted_item(GOLDEN_AXE, &enchants::AXE_NAMES, &enchants::AXE),
+        )
+        .register("gold_block", RegisteredItem::new(GOLD_BLOCK))
+        .register("gold_ingot", RegisteredItem::new(GOLD_INGOT))
+        .register("iron_ingot", RegisteredItem::new(IRON_INGOT))
+        .register(
+            "golden_sword",
+            RegisteredItem::new(GOLDEN_SWORD).stack_size(1),
+        )
+        .register(
+            "golden_chestplate",
+            RegisteredItem::new(GOLDEN_CHESTPLATE).stack_size(1),
+        )
+        .register(
+            "golden_helmet",
+            RegisteredItem::new(GOLDEN_HELMET).stack_size(1),
+        )
+        .register(
+            "golden_leggings",
+            RegisteredItem::new(GOLDEN_LEGGINGS).stack_size(1),
+        )
+        .register("crying_obsidian", RegisteredItem::new(CRYING_OBSIDIAN))
+        .register("gilded_blackstone", RegisteredItem::new(GILDED_BLACKSTONE))
+        .register("chain", RegisteredItem::new(CHAIN))
+        .register("magma_cream", RegisteredItem::new(MAGMA_CREAM))
+        .register("bone_block", RegisteredItem::new(BONE_BLOCK))
+        .register("iron_nugget", RegisteredItem::new(IRON_NUGGET))
+        .register("obsidian", RegisteredItem::new(OBSIDIAN))
+        .register("gold_nugget", RegisteredItem::new(GOLD_NUGGET))
+        .register("string", RegisteredItem::new(STRING))
+        .register("arrow", RegisteredItem::new(ARROW))
+        .register("cooked_porkchop", RegisteredItem::new(COOKED_PORKCHOP))
+}
+
+fn hoglin_stables_item_registry() -> ItemRegistry {
+    use crate::features::bastion::items::hoglin_stables::*;
+
+    ItemRegistry::new()
+        .register(
+            "diamond_shovel",
+            enchanted_item(DIAMOND_SHOVEL, &enchants::SHOVEL_NAMES, &enchants::SHOVEL)
+                .durability(ItemWithDurability::Shovel(ToolMaterial::Diamond).durability()),
+        )
+        .register(
+            "diamond_pickaxe",
+            enchanted_item(
+                DIAMOND_PICKAXE,
+                &enchants::PICKAXE_NAMES,
+                &enchants::PICKAXE,
+            )
+            .durability(ItemWithDurability::Pickaxe(ToolMaterial::Diamond).durability()),
+        )
+        .register("netherite_scrap", RegisteredItem::new(NETHERITE_SCRAP))
+        .register("ancient_debris", RegisteredItem::new(ANCIENT_DEBRIS))
+        .register("saddle", RegisteredItem::new(SADDLE).stack_size(1))
+        .register("gold_block", RegisteredItem::new(GOLD_BLOCK))
+        .register("golden_carrot", RegisteredItem::new(GOLDEN_CARROT))
+        .register("golden_apple", RegisteredItem::new(GOLDEN_APPLE))
+        .register(
+            "golden_axe",
+            enchanted_item(GOLDEN_AXE, &enchants::AXE_NAMES, &enchants::AXE),
+        )
+        .register("crying_obsidian", RegisteredItem::new(CRYING_OBSIDIAN))
+        .register("glowstone", RegisteredItem::new(GLOWSTONE))
+        .register("gilded_blackstone", RegisteredItem::new(GILDED_BLACKSTONE))
+        .register("soul_sand", RegisteredItem::new(SOUL_SAND))
+        .register("crimson_nylium", RegisteredItem::new(CRIMSON_NYLIUM))
+        .register("gold_nugget", RegisteredItem::new(GOLD_NUGGET))
+        .register("leather", RegisteredItem::new(LEATHER))
+        .register("arrow", RegisteredItem::new(ARROW))
+        .register("string", RegisteredItem::new(STRING))
+        .register("porkchop", RegisteredItem::new(PORKCHOP))
+        .register("cooked_porkchop", RegisteredItem::new(COOKED_PORKCHOP))
+        .register("crimson_fungus", RegisteredItem::new(CRIMSON_FUNGUS))
+        .register("crimson_roots", RegisteredItem::new(CRIMSON_ROOTS))
+}
+
+fn treasure_room_item_registry() -> ItemRegistry {
+    use crate::features::bastion::items::treasure_room::*;
+
+    ItemRegistry::new()
+        .register("netherite_ingot", RegisteredItem::new(NETHERITE_INGOT))
+        .register("ancient_debris", RegisteredItem::new(ANCIENT_DEBRIS))
+        .register("netherite_scrap", RegisteredItem::new(NETHERITE_SCRAP))
+        .register(
+            "diamond_sword",
+            enchanted_item(DIAMOND_SWORD, &enchants::SWORD_NAMES, &enchants::SWORD)
+                .durability(ItemWithDurability::Sword(ToolMaterial::Diamond).durability()),
+        )
+        .register(
+            "diamond_chestplate",
+            enchanted_item(
+                DIAMOND_CHESTPLATE,
+                &enchants::CHESTPLATE_NAMES,
+                &enchants::CHESTPLATE,
+            )
+            .durability(ItemWithDurability::Chestplate(ArmorMaterial::Diamond).durability()),
+        )
+        .register(
+            "diamond_helmet",
+            enchanted_item(DIAMOND_HELMET, &enchants::HELMET_NAMES, &enchants::HELMET)
+                .durability(ItemWithDurability::Helmet(ArmorMaterial::Diamond).durability()),
+        )
+        .register(
+            "diamond_leggings",
+            enchanted_item(
+                DIAMOND_LEGGINGS,
+                &enchants::LEGGINGS_NAMES,
+                &enchants::LEGGINGS,
+            )
+            .durability(ItemWithDurability::Leggings(ArmorMaterial::Diamond).durability()),
+        )
+        .register(
+            "diamond_boots",
+            enchanted_item(DIAMOND_BOOTS, &enchants::BOOTS_NAMES, &enchants::BOOTS)
+                .durability(ItemWithDurability::Boots(ArmorMaterial::Diamond).durability()),
+        )
+        .register("diamond", RegisteredItem::new(DIAMOND))
+        .register(
+            "enchanted_golden_apple",
+            RegisteredItem::new(ENCHANTED_GOLDEN_APPLE),
+        )
+        .register("spectral_arrow", RegisteredItem::new(SPECTRAL_ARROW))
+        .register("gold_block", RegisteredItem::new(GOLD_BLOCK))
+        .register("iron_block", RegisteredItem::new(IRON_BLOCK))
+        .register("gold_ingot", RegisteredItem::new(GOLD_INGOT))
+        .register("iron_ingot", RegisteredItem::new(IRON_INGOT))
+        .register("crying_obsidian", RegisteredItem::new(CRYING_OBSIDIAN))
+        .register("quartz", RegisteredItem::new(QUARTZ))
+        .register("gilded_blackstone", RegisteredItem::new(GILDED_BLACKSTONE))
+        .register("magma_cream", RegisteredItem::new(MAGMA_CREAM))
+}
+
+fn bridges_item_registry() -> ItemRegistry {
+    use crate::features::bastion::items::bridges::*;
+
+    ItemRegistry::new()
+        .register("lodestone", RegisteredItem::new(LODESTONE))
+        .register(
+            "crossbow",
+            enchanted_item(CROSSBOW, &enchants::CROSSBOW_NAMES, &enchants::CROSSBOW)
+                .durability(ItemWithDurability::Crossbow.durability()),
+        )
+        .register("spectral_arrow", RegisteredItem::new(SPECTRAL_ARROW))
+        .register("gilded_blackstone", RegisteredItem::new(GILDED_BLACKSTONE))
+        .register("crying_obsidian", RegisteredItem::new(CRYING_OBSIDIAN))
+        .register("gold_block", RegisteredItem::new(GOLD_BLOCK))
+        .register("gold_ingot", RegisteredItem::new(GOLD_INGOT))
+        .register("iron_ingot", RegisteredItem::new(IRON_INGOT))
+        .register(
+            "golden_sword",
+            RegisteredItem::new(GOLDEN_SWORD).stack_size(1),
+        )
+        .register(
+            "golden_chestplate",
+            enchanted_item(
+                GOLDEN_CHESTPLATE,
+                &enchants::CHESTPLATE_NAMES,
+                &enchants::CHESTPLATE,
+            ),
+        )
+        .register(
+            "golden_helmet",
+            enchanted_item(GOLDEN_HELMET, &enchants::HELMET_NAMES, &enchants::HELMET),
+        )
+        .register(
+            "golden_leggings",
+            enchanted_item(
+                GOLDEN_LEGGINGS,
+                &enchants::LEGGINGS_NAMES,
+                &enchants::LEGGINGS,
+            ),
+        )
+        .register(
+            "golden_boots",
+            enchanted_item(GOLDEN_BOOTS, &enchants::BOOTS_NAMES, &enchants::BOOTS),
+        )
+        .register(
+            "golden_axe",
+            enchanted_item(GOLDEN_AXE, &enchants::AXE_NAMES, &enchants::AXE),
+        )
+        .register("string", RegisteredItem::new(STRING))
+        .register("leather", RegisteredItem::new(LEATHER))
+        .register("arrow", RegisteredItem::new(ARROW))
+        .register("iron_nugget", RegisteredItem::new(IRON_NUGGET))
+        .register("gold_nugget", RegisteredItem::new(GOLD_NUGGET))
+}
+
 #[inline]
 pub fn bastion_other_chest_loot_table() -> LootTable {
     use crate::features::bastion::book_enchants::other::SOUL_SPEED;
@@ -721,26 +994,31 @@ pub fn bastion_treasure_room_chest_loot_table() -> LootTable {
                 )
                 .entry_item(
                     ItemLootPoolEntryBuilder::new(DIAMOND_SWORD)
+                        .item_stack_size(1)
                         .weight(6)
                         .build(),
                 )
                 .entry_item(
                     ItemLootPoolEntryBuilder::new(DIAMOND_CHESTPLATE)
+                        .item_stack_size(1)
                         .weight(5)
                         .build(),
                 )
                 .entry_item(
                     ItemLootPoolEntryBuilder::new(DIAMOND_HELMET)
+                        .item_stack_size(1)
                         .weight(5)
                         .build(),
                 )
                 .entry_item(
                     ItemLootPoolEntryBuilder::new(DIAMOND_BOOTS)
+                        .item_stack_size(1)
                         .weight(5)
                         .build(),
                 )
                 .entry_item(
                     ItemLootPoolEntryBuilder::new(DIAMOND_LEGGINGS)
+                        .item_stack_size(1)
                         .weight(5)
                         .build(),
                 )
@@ -979,40 +1257,144 @@ pub fn bastion_bridges_chest_loot_table() -> LootTable {
         .build()
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BastionChestType {
     HoglinStables,
+    #[default]
     TreasureRoom,
     Bridges,
     Other,
 }
 
+impl BastionChestType {
+    pub const ALL: [BastionChestType; 4] = [
+        Self::TreasureRoom,
+        Self::Bridges,
+        Self::HoglinStables,
+        Self::Other,
+    ];
+
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::HoglinStables => "hoglin stables",
+            Self::TreasureRoom => "treasure room",
+            Self::Bridges => "bridges",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// The chunk the nether structure of a region starts in, and the roll after the position: 0 and 1 make it a fortress,
+/// 2 to 4 a bastion remnant
+#[inline(always)]
+pub(crate) const fn nether_structure_start(
+    structure_seed: i64,
+    region: (i32, i32),
+) -> ((i32, i32), i32) {
+    let mut rng = random_with_region_seed(structure_seed, region.0, region.1, SALT).0;
+    let x = region
+        .0
+        .wrapping_mul(SPACING)
+        .wrapping_add(rng.next_bounded_int(SPACING - SEPARATION));
+    let z = region
+        .1
+        .wrapping_mul(SPACING)
+        .wrapping_add(rng.next_bounded_int(SPACING - SEPARATION));
+    ((x, z), rng.next_bounded_int(5))
+}
+
+/// The chunk the bastion remnant of a region starts in. Fortresses share the placement, the roll after the position
+/// gives 2 in 5 regions a fortress instead: None for those. The biome isn't checked, bastions don't generate in basalt
+/// deltas.
+#[inline(always)]
+pub const fn get_bastion_start(structure_seed: i64, region: (i32, i32)) -> Option<(i32, i32)> {
+    match nether_structure_start(structure_seed, region) {
+        (start, roll) if roll >= 2 => Some(start),
+        _ => None,
+    }
+}
+
+/// If a bastion remnant starts in the chunk. Its chests can be a few chunks away from it.
+///
+/// ```
+/// use mcseedcracker::features::bastion::generates_at;
+///
+/// assert!(generates_at(734679766044180411, (4, 12)));
+/// assert!(!generates_at(734679766044180411, (6, 10)));
+/// ```
+#[inline(always)]
+pub const fn generates_at(structure_seed: i64, chunk_pos: (i32, i32)) -> bool {
+    let region = (
+        chunk_pos.0.div_euclid(SPACING),
+        chunk_pos.1.div_euclid(SPACING),
+    );
+    match get_bastion_start(structure_seed, region) {
+        Some((x, z)) => x == chunk_pos.0 && z == chunk_pos.1,
+        None => false,
+    }
+}
+
 #[inline(always)]
 pub const fn get_bastion_chest_random(world_seed: i64, chunk_pos: (i32, i32)) -> (JavaRandom, i64) {
     let block_pos = Math::relative_chunk_coords(chunk_pos, (0, 0));
 
     let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
 
-    random_with_decorator_seed(population_seed, 12, 40)
+    random_with_decorator_seed(
+        population_seed,
+        DecoratorSalt::BastionRemnant.index(),
+        DecoratorSalt::BastionRemnant.step(),
+    )
 }
 
+#[inline(always)]
+pub const fn get_bastion_chest_seeds(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+) -> DecoratorChestSeeds {
+    DecoratorChestSeeds::from_decorator_seed(get_bastion_chest_random(world_seed, chunk_pos).1)
+}
+
+/// ```
+/// use mcseedcracker::{features::bastion::get_bastion_chest_loot_table_seed, math::Math};
+///
+/// let chunk = Math::block_coords_to_chunk_coords((97, 166));
+/// assert_eq!(
+///     get_bastion_chest_loot_table_seed(734679766044180411, chunk, 0),
+///     2799166732823584713
+/// );
+/// ```
 #[inline(always)]
 pub const fn get_bastion_chest_loot_table_seed(
     world_seed: i64,
     chunk_pos: (i32, i32),
     num_chest_in_chunk: i32,
 ) -> i64 {
-    let generator = lcg::JAVA_RANDOM.combine(2 * num_chest_in_chunk as i64);
-
-    let inital_state = get_bastion_chest_random(world_seed, chunk_pos).1;
-
-    let state = generator.next_seed(inital_state ^ lcg::JAVA_RANDOM.get_multiplier());
-
-    let next1 = lcg::JAVA_RANDOM.next_seed(state);
-    let next2 = lcg::JAVA_RANDOM.next_seed(next1);
+    get_bastion_chest_seeds(world_seed, chunk_pos).loot_table_seed(num_chest_in_chunk)
+}
 
-    ((next1 & 0xFFFF_FFFF_0000) << 16).wrapping_add(((next2 >> 16) as i32) as i64)
+#[inline]
+pub fn get_loot_table(chest_type: BastionChestType) -> LootTable {
+    match chest_type {
+        BastionChestType::HoglinStables => bastion_hoglin_stables_chest_loot_table(),
+        BastionChestType::Other => bastion_other_chest_loot_table(),
+        BastionChestType::TreasureRoom => bastion_treasure_room_chest_loot_table(),
+        BastionChestType::Bridges => bastion_bridges_chest_loot_table(),
+    }
 }
 
+/// ```
+/// use mcseedcracker::{
+///     features::bastion::{BastionChestType, get_bastion_chest},
+///     loot_table::Inventory,
+///     math::Math,
+/// };
+///
+/// let chunk = Math::block_coords_to_chunk_coords((97, 166));
+/// let chest = get_bastion_chest(734679766044180411, chunk, 0, BastionChestType::HoglinStables, 0.0);
+/// assert!((0..chest.slot_count()).any(|i| chest.get_item(i).is_some()));
+/// ```
 #[inline]
 pub fn get_bastion_chest(
     world_seed: i64,
@@ -1023,26 +1405,55 @@ pub fn get_bastion_chest(
 ) -> SingleChest {
     let seed = get_bastion_chest_loot_table_seed(world_seed, chunk_pos, num_chest_in_chunk);
 
-    let loot_table = match chest_type {
-        BastionChestType::HoglinStables => bastion_hoglin_stables_chest_loot_table(),
-        BastionChestType::Other => bastion_other_chest_loot_table(),
-        BastionChestType::TreasureRoom => bastion_treasure_room_chest_loot_table(),
-        BastionChestType::Bridges => bastion_bridges_chest_loot_table(),
-    };
-
     let mut chest = SingleChest::new();
-    loot_table.generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), luck);
+    get_loot_table(chest_type).generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), luck);
     chest
 }
 
+#[inline]
+pub fn build_fast_inventory_compare_context(
+    contents: SingleChest,
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, ITEM_COUNT>, InvalidObservation> {
+    FastInventoryCompareContext::new(contents, loot_table)
+}
+
+/// Returns which chest of the chunk generates the compared contents, trying every index allowed by `index`
+#[inline]
+pub fn compare_bastion_chest_fast_noinv(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    index: ChestIndex,
+    loot_table: &LootTable,
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, ITEM_COUNT>,
+) -> Option<i32> {
+    detect_chest_index(
+        &get_bastion_chest_seeds(world_seed, chunk_pos),
+        index,
+        loot_table,
+        luck,
+        compare,
+    )
+}
+
 #[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
 pub mod tests {
     use crate::{
         features::bastion::{
-            BastionChestType, get_bastion_chest, get_bastion_chest_loot_table_seed,
+            BastionChestType, ITEM_COUNT, build_fast_inventory_compare_context, generates_at,
+            get_bastion_chest, get_bastion_chest_loot_table_seed, get_bastion_start,
+            get_loot_table, item_registry,
+        },
+        loot_table::{
+            ChestRow, EarlyRejection, FastInventoryCompareContext, Inventory, ItemProperty,
+            ItemStack, SingleChest,
         },
-        loot_table::{ChestRow, ItemProperty, ItemStack, SingleChest},
         math::Math,
+        random::JavaRandom,
+        self_test,
+        tooltip::{ChestDump, LanguageTable, parse_tooltip_dump},
         utils::{
             durability::{ArmorMaterial, ItemWithDurability, ToolMaterial},
             enchants,
@@ -1089,62 +1500,100 @@ pub mod tests {
     }
 
     #[test]
-    pub fn test_bastion_hoglin_stables_chest1() {
-        use crate::features::bastion::items::hoglin_stables::{
-            CRIMSON_FUNGUS, CRIMSON_NYLIUM, CRIMSON_ROOTS, GOLDEN_AXE, SADDLE,
-        };
+    fn test_item_registry() {
+        let language = LanguageTable::en_us();
+        for chest_type in BastionChestType::ALL {
+            let (registry, loot_table) = (item_registry(chest_type), get_loot_table(chest_type));
+            for item in 0..ITEM_COUNT {
+                assert_eq!(
+                    registry.by_id(item).map(|i| i.max_count),
+                    loot_table.max_stack_size(item),
+                    "{:?} item {}",
+                    chest_type,
+                    item
+                );
+            }
+            for (name, _) in registry.iter() {
+                assert!(language.item_display_name(name).is_some(), "{}", name);
+            }
+
+            // the tooltips of generated chests are read back
+            for index in 0..4 {
+                let chest =
+                    get_bastion_chest(734679766044180411, (-43, -52), index, chest_type, 0.0);
+                let dump = ChestDump::new(&chest, &registry, &language).to_string();
+                assert_eq!(
+                    parse_tooltip_dump(&dump, &registry, &language),
+                    Ok(chest),
+                    "{}",
+                    dump
+                );
+            }
+        }
+    }
 
-        let ingame = SingleChest {
-            rows: [
-                ChestRow {
-                    items: [
-                        None,
-                        None,
-                        Some(ItemStack::of(CRIMSON_FUNGUS, 1)),
-                        Some(ItemStack::new(SADDLE, 1, 1)),
-                        None,
-                        Some(ItemStack::of(CRIMSON_FUNGUS, 2)),
-                        None,
-                        None,
-                        Some(ItemStack::of(CRIMSON_ROOTS, 2)),
-                    ],
-                },
-                ChestRow {
-                    items: [
-                        Some(ItemStack::with_properties(
-                            GOLDEN_AXE,
-                            1,
-                            1,
-                            &[ItemProperty::Enchantment {
-                                enchantment: enchants::axe::SMITE.0,
-                                level: 2,
-                            }],
-                        )),
-                        Some(ItemStack::of(CRIMSON_ROOTS, 1)),
-                        None,
-                        None,
-                        Some(ItemStack::of(CRIMSON_NYLIUM, 1)),
-                        None,
-                        None,
-                        None,
-                        None,
-                    ],
-                },
-                ChestRow {
-                    items: [
-                        None,
-                        None,
-                        None,
-                        None,
-                        Some(ItemStack::of(CRIMSON_NYLIUM, 1)),
-                        None,
-                        None,
-                        Some(ItemStack::of(CRIMSON_NYLIUM, 2)),
-                        None,
-                    ],
-                },
-            ],
-        };
+    /// Every item of a chest type has its own id, or the comparisons can't tell them apart
+    #[test]
+    fn test_item_ids_round_trip() {
+        for chest_type in BastionChestType::ALL {
+            let registry = item_registry(chest_type);
+            assert_eq!(
+                registry.duplicate_ids(),
+                Vec::<usize>::new(),
+                "{:?}",
+                chest_type
+            );
+            for (name, item) in registry.iter() {
+                assert_eq!(registry.get(name), Some(item), "{:?} {}", chest_type, name);
+                assert_eq!(registry.name_of(item.id), Some(name), "{:?}", chest_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bastion_generates_at() {
+        let world_seed: i64 = 734679766044180411;
+
+        // the starts of the bastions of the chests of test_bastion_loot_table_seeds, a few chunks from their chests
+        let starts = [
+            ((97, 166), (4, 12)),
+            ((-198, 116), (-13, 9)),
+            ((-724, -94), (-44, -7)),
+            ((-674, -820), (-41, -50)),
+            ((-2564, -1022), (-159, -65)),
+            ((2229, -1107), (138, -68)),
+        ];
+        for (chest, start) in starts {
+            let chunk = Math::block_coords_to_chunk_coords(chest);
+            assert!(chunk.0.abs_diff(start.0) <= 3 && chunk.1.abs_diff(start.1) <= 3);
+            assert!(generates_at(world_seed, start), "{:?}", start);
+            // only the lower 48 bits of the seed matter
+            assert!(generates_at(world_seed & 0xFFFF_FFFF_FFFF, start));
+            for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1), (27, 0)] {
+                assert!(!generates_at(world_seed, (start.0 + dx, start.1 + dz)));
+            }
+        }
+
+        // a fortress region
+        assert_eq!(get_bastion_start(world_seed, (1, 0)), None);
+        assert!(!generates_at(world_seed, (43, 5)));
+    }
+
+    #[test]
+    pub fn test_bastion_loot_table_seeds_extreme() {
+        assert_eq!(
+            get_bastion_chest_loot_table_seed(i64::MAX, (1_875_000, -1_875_000), 3),
+            7677188359986197393
+        );
+        assert_eq!(
+            get_bastion_chest_loot_table_seed(i64::MIN, (-1_875_000, 1_875_000), 0),
+            3122873282779160672
+        );
+    }
+
+    #[test]
+    pub fn test_bastion_hoglin_stables_chest1() {
+        let ingame = self_test::bastion_chest_contents();
 
         let generated = get_bastion_chest(
             734679766044180411,
@@ -1313,6 +1762,60 @@ pub mod tests {
         assert_eq!(ingame, generated);
     }
 
+    #[test]
+    fn test_enchantment_rejected_early() {
+        use crate::features::bastion::items::hoglin_stables::DIAMOND_PICKAXE;
+
+        // the chest of test_bastion_hoglin_stables_chest3, remembered with fortune instead of silk touch
+        let chunk = Math::block_coords_to_chunk_coords((-199, 118));
+        let seed = get_bastion_chest_loot_table_seed(734679766044180411, chunk, 1);
+        let real = get_bastion_chest(
+            734679766044180411,
+            chunk,
+            1,
+            BastionChestType::HoglinStables,
+            0.0,
+        );
+        let mut observed = real.clone();
+        let slot = (0..observed.slot_count())
+            .find(|&slot| {
+                observed
+                    .get_item(slot)
+                    .is_some_and(|i| i.item == DIAMOND_PICKAXE)
+            })
+            .unwrap();
+        let mut pickaxe = observed.get_item(slot).unwrap().clone();
+        for property in pickaxe.properties.iter_mut() {
+            if let ItemProperty::Enchantment { enchantment, level } = property {
+                (*enchantment, *level) = (enchants::pickaxe::FORTUNE.0, 1);
+            }
+        }
+        observed.set_item(slot, Some(pickaxe));
+
+        let table = get_loot_table(BastionChestType::HoglinStables);
+        let ctx = build_fast_inventory_compare_context(observed, &table).unwrap();
+        let mut count_only = ctx.clone();
+        count_only.expected_enchantments.clear();
+        let real_ctx = build_fast_inventory_compare_context(real, &table).unwrap();
+        assert!(table.compare_fast_noinv(JavaRandom::new(seed), 0.0, &real_ctx));
+        assert!(!table.compare_fast_noinv(JavaRandom::new(seed), 0.0, &ctx));
+        assert!(!table.compare_fast_noinv(JavaRandom::new(seed), 0.0, &count_only));
+
+        let callbacks = |ctx: &FastInventoryCompareContext<SingleChest, _>| {
+            let mut early = EarlyRejection::new(ctx, 0);
+            let mut calls = 0;
+            table.generate_raw_loot_callback(&mut JavaRandom::new(seed), 0.0, |items, stop| {
+                calls += 1;
+                *stop = early.rejects(&items);
+            });
+            calls
+        };
+        // the pickaxe is rolled first, the count only path generates the whole loot
+        assert_eq!(callbacks(&ctx), 1);
+        assert_eq!(callbacks(&count_only), 5);
+        assert_eq!(callbacks(&count_only), callbacks(&real_ctx));
+    }
+
     #[test]
     pub fn test_bastion_treasure_room_chest1() {
         use crate::features::bastion::items::treasure_room::{
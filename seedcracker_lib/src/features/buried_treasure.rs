@@ -1,16 +1,22 @@
+use std::hash::{Hash, Hasher};
+
 use crate::{
+    lcg,
     loot_table::{
-        FastInventoryCompareContext, ItemLootPoolEntryBuilder, LootPoolBuilder, LootTable,
-        LootTableBuilder, SetCountFunction, SingleChest,
+        FastInventoryCompareContext, InvalidObservation, ItemCountConstraints,
+        ItemLootPoolEntryBuilder, ItemRegistry, LootPoolBuilder, LootTable, LootTableBuilder,
+        RegisteredItem, SetCountFunction, SingleChest,
     },
     math::Math,
     random::{
-        JavaRandom, random_with_decorator_seed, random_with_population_seed,
-        random_with_region_seed,
+        DecoratorSalt, JavaRandom, float_threshold, random_with_decorator_seed,
+        random_with_population_seed, random_with_region_seed,
     },
 };
 
 pub const PROB: f32 = 0.01;
+/// `next_float() < PROB` compared on the raw bits, see [`float_threshold`]
+pub const PROB_THRESHOLD: i32 = float_threshold(PROB);
 pub const SALT: i32 = 10387320;
 
 pub mod items {
@@ -27,12 +33,178 @@ pub mod items {
     pub const COOKED_SALMON: usize = 11;
 }
 
+/// The items of the buried treasure loot table, for [`crate::loot_table::from_datapack_json`]
+pub fn item_registry() -> ItemRegistry {
+    use crate::utils::durability::{ArmorMaterial, ItemWithDurability, ToolMaterial};
+
+    ItemRegistry::new()
+        .register(
+            "heart_of_the_sea",
+            RegisteredItem::new(items::HEART_OF_THE_SEA),
+        )
+        .register("iron_ingot", RegisteredItem::new(items::IRON_INGOT))
+        .register("gold_ingot", RegisteredItem::new(items::GOLD_INGOT))
+        .register("tnt", RegisteredItem::new(items::TNT))
+        .register("emerald", RegisteredItem::new(items::EMERALD))
+        .register("diamond", RegisteredItem::new(items::DIAMOND))
+        .register(
+            "prismarine_crystals",
+            RegisteredItem::new(items::PRISMARINE_CRYSTALS),
+        )
+        .register(
+            "leather_chestplate",
+            RegisteredItem::new(items::LEATHER_CHESTPLATE)
+                .stack_size(1)
+                .durability(ItemWithDurability::Chestplate(ArmorMaterial::Leather).durability()),
+        )
+        .register(
+            "iron_sword",
+            RegisteredItem::new(items::IRON_SWORD)
+                .stack_size(1)
+                .durability(ItemWithDurability::Sword(ToolMaterial::Iron).durability()),
+        )
+        .register("cooked_cod", RegisteredItem::new(items::COOKED_COD))
+        .register("cooked_salmon", RegisteredItem::new(items::COOKED_SALMON))
+}
+
+/// ```
+/// use mcseedcracker::{features::buried_treasure::generates_at, math::Math};
+///
+/// let chunk = Math::block_coords_to_chunk_coords((409, 809));
+/// assert!(generates_at(-7193194438565520372, chunk));
+/// ```
 #[inline(always)]
 pub const fn generates_at(world_seed: i64, chunk_pos: (i32, i32)) -> bool {
     random_with_region_seed(world_seed, chunk_pos.0, chunk_pos.1, SALT)
         .0
-        .next_float()
-        < PROB
+        .next_float_bits()
+        < PROB_THRESHOLD
+}
+
+/// The structure seeds whose pillar seed is `pillar_seed` and that have a buried treasure in `chunk_pos`, the same ones
+/// as checking [`generates_at`] on the 2^32 structure seeds of the pillar seed. The pillar seed only fixes the low 32
+/// bits of the scrambled seed, one value for each of the 2^16 low bits of the state it is read from. The high 16 bits
+/// then only move the top 16 bits of the state of the region seed's `next_float`, through an odd multiplier: only the
+/// ~650 values that make it small enough are solved for, about 2^25 seeds in all.
+pub fn candidate_structure_seeds(
+    chunk_pos: (i32, i32),
+    pillar_seed: i64,
+) -> impl Iterator<Item = i64> {
+    const LOW_MASK: i64 = 0xFFFF_FFFF;
+    const HIGH_MASK: i64 = 0xFFFF;
+    let multiplier = lcg::JAVA_RANDOM.get_multiplier();
+    let (multiplier_lo, multiplier_hi) = (multiplier & LOW_MASK, multiplier >> 32);
+    let inverse = Math::mod_inverse_pow_2(multiplier & HIGH_MASK, 16);
+    let offset = random_with_region_seed(0, chunk_pos.0, chunk_pos.1, SALT).1;
+    let (offset_lo, offset_hi) = (offset & LOW_MASK, (offset >> 32) & HIGH_MASK);
+    // the region seed's first state is below it
+    let bound = (PROB_THRESHOLD as i64) << 24;
+
+    (0i64..65536).flat_map(move |state_lo| {
+        let state = ((pillar_seed & 0xFFFF) << 16) | state_lo;
+        let seed_lo = (lcg::JAVA_RANDOM_REV2.next_seed(state) ^ multiplier) & LOW_MASK;
+        let region = seed_lo.wrapping_add(offset_lo);
+        let carry = region >> 32;
+        let first = lcg::JAVA_RANDOM.next_seed((region & LOW_MASK) ^ multiplier_lo);
+        let (first_lo, first_hi) = (first & LOW_MASK, first >> 32);
+        // the top 16 bits of the first state that keep it below the bound
+        let count = bound.wrapping_sub(first_lo).wrapping_add(LOW_MASK) >> 32;
+        (0..count).map(move |top| {
+            let scrambled_hi =
+                inverse.wrapping_mul(top.wrapping_sub(first_hi) & HIGH_MASK) & HIGH_MASK;
+            let region_hi = scrambled_hi ^ multiplier_hi;
+            let seed_hi = region_hi.wrapping_sub(offset_hi).wrapping_sub(carry) & HIGH_MASK;
+            (seed_hi << 32) | seed_lo
+        })
+    })
+}
+
+/// The chunks between two corners, both included, that a buried treasure could be in. The region seed offset of each
+/// chunk is computed once, checking a world seed only adds it.
+#[derive(Debug, Clone)]
+pub struct ChunkArea {
+    pub min: (i32, i32),
+    pub max: (i32, i32),
+    /// Row by row, with the region seed of the chunk for a world seed of 0
+    chunks: Box<[((i32, i32), i64)]>,
+}
+
+impl PartialEq for ChunkArea {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.min == other.min && self.max == other.max
+    }
+}
+
+impl Eq for ChunkArea {}
+
+impl Hash for ChunkArea {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.min.hash(state);
+        self.max.hash(state);
+    }
+}
+
+impl ChunkArea {
+    /// The corners can be given in any order, check [`ChunkArea::count_chunks`] first for areas typed by users
+    pub fn new(a: (i32, i32), b: (i32, i32)) -> Self {
+        let min = (a.0.min(b.0), a.1.min(b.1));
+        let max = (a.0.max(b.0), a.1.max(b.1));
+        let chunks = (min.1..=max.1)
+            .flat_map(|z| (min.0..=max.0).map(move |x| (x, z)))
+            .map(|chunk| (chunk, random_with_region_seed(0, chunk.0, chunk.1, SALT).1))
+            .collect();
+        Self { min, max, chunks }
+    }
+
+    /// Number of chunks of the area between the corners `a` and `b`
+    #[inline]
+    pub const fn count_chunks(a: (i32, i32), b: (i32, i32)) -> u64 {
+        (a.0.abs_diff(b.0) as u64)
+            .wrapping_add(1)
+            .saturating_mul((a.1.abs_diff(b.1) as u64).wrapping_add(1))
+    }
+
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// The first chunk, row by row, where a buried treasure generates and `matches` holds
+    #[inline(always)]
+    pub fn find_chunk(
+        &self,
+        world_seed: i64,
+        mut matches: impl FnMut((i32, i32)) -> bool,
+    ) -> Option<(i32, i32)> {
+        self.chunks
+            .iter()
+            .find(|(chunk, offset)| {
+                JavaRandom::new(world_seed.wrapping_add(*offset)).next_float_bits() < PROB_THRESHOLD
+                    && matches(*chunk)
+            })
+            .map(|(chunk, _)| *chunk)
+    }
+
+    /// Every chunk of the area a buried treasure generates in, row by row
+    ///
+    /// ```
+    /// use mcseedcracker::features::buried_treasure::{ChunkArea, generates_at};
+    ///
+    /// let world_seed = -7193194438565520372;
+    /// let chunks = ChunkArea::new((20, 45), (30, 55)).treasure_chunks(world_seed);
+    /// assert!(chunks.contains(&(25, 50)));
+    /// assert!(chunks.iter().all(|chunk| generates_at(world_seed, *chunk)));
+    /// ```
+    pub fn treasure_chunks(&self, world_seed: i64) -> Vec<(i32, i32)> {
+        self.chunks
+            .iter()
+            .filter(|(_, offset)| {
+                JavaRandom::new(world_seed.wrapping_add(*offset)).next_float_bits() < PROB_THRESHOLD
+            })
+            .map(|(chunk, _)| *chunk)
+            .collect()
+    }
 }
 
 #[inline(always)]
@@ -44,9 +216,22 @@ pub const fn get_buried_treasure_random(
 
     let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
 
-    random_with_decorator_seed(population_seed, 1, 30)
+    random_with_decorator_seed(
+        population_seed,
+        DecoratorSalt::BuriedTreasure.index(),
+        DecoratorSalt::BuriedTreasure.step(),
+    )
 }
 
+/// ```
+/// use mcseedcracker::{features::buried_treasure::get_buried_treasure_loot_table_seed, math::Math};
+///
+/// let chunk = Math::block_coords_to_chunk_coords((-87, -23));
+/// assert_eq!(
+///     get_buried_treasure_loot_table_seed(657814009800288117, chunk),
+///     1749169673229764907
+/// );
+/// ```
 #[inline(always)]
 pub const fn get_buried_treasure_loot_table_seed(world_seed: i64, chunk_pos: (i32, i32)) -> i64 {
     get_buried_treasure_random(world_seed, chunk_pos)
@@ -54,6 +239,19 @@ pub const fn get_buried_treasure_loot_table_seed(world_seed: i64, chunk_pos: (i3
         .next_long()
 }
 
+/// ```
+/// use mcseedcracker::{
+///     features::buried_treasure::{get_buried_treasure, items::HEART_OF_THE_SEA},
+///     loot_table::Inventory,
+/// };
+///
+/// let chest = get_buried_treasure(-7193194438565520372, (25, 50), 0.0);
+/// let hearts = (0..chest.slot_count())
+///     .filter_map(|i| chest.get_item(i))
+///     .filter(|stack| stack.item == HEART_OF_THE_SEA)
+///     .count();
+/// assert_eq!(hearts, 1);
+/// ```
 #[inline]
 pub fn get_buried_treasure(world_seed: i64, chunk_pos: (i32, i32), luck: f32) -> SingleChest {
     let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
@@ -62,22 +260,38 @@ pub fn get_buried_treasure(world_seed: i64, chunk_pos: (i32, i32), luck: f32) ->
     chest
 }
 
+/// ```
+/// use mcseedcracker::features::buried_treasure::{
+///     build_fast_inventory_compare_context, compare_buried_treasure_fast_noinv, get_buried_treasure,
+///     get_loot_table,
+/// };
+///
+/// let world_seed = -7193194438565520372;
+/// let chest = get_buried_treasure(world_seed, (25, 50), 0.0);
+/// let ctx = build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap();
+///
+/// assert!(compare_buried_treasure_fast_noinv(world_seed, (25, 50), 0.0, &ctx));
+/// for other in [(25, 51), (24, 50), (0, 0)] {
+///     assert!(!compare_buried_treasure_fast_noinv(world_seed, other, 0.0, &ctx));
+/// }
+/// ```
 #[inline]
 pub fn build_fast_inventory_compare_context(
     contents: SingleChest,
-) -> FastInventoryCompareContext<SingleChest, 12> {
-    let mut ctx = FastInventoryCompareContext {
-        inventory: contents,
-        items_count: [0; 12],
-        total_items: 0,
-    };
-    for row in ctx.inventory.rows.iter() {
-        for item in row.items.iter().flatten() {
-            ctx.items_count[item.item] += item.count;
-            ctx.total_items += item.count;
-        }
-    }
-    ctx
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, 12>, InvalidObservation> {
+    FastInventoryCompareContext::new(contents, loot_table)
+}
+
+/// A context for a buried treasure of which only the slots marked in `known_slots_mask` were seen, the stacks of
+/// `contents` in the other slots are ignored, see [`FastInventoryCompareContext::new_partial`]
+#[inline]
+pub fn build_partial_inventory_compare_context(
+    contents: SingleChest,
+    known_slots_mask: [bool; 27],
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, 12>, InvalidObservation> {
+    FastInventoryCompareContext::new_partial(contents, &known_slots_mask, loot_table)
 }
 
 #[inline]
@@ -103,6 +317,136 @@ pub fn compare_buried_treasure_fast_noinv(
     get_loot_table().compare_fast_noinv(JavaRandom::new(seed), luck, compare)
 }
 
+/// Same as [`compare_buried_treasure_fast_noinv`], for servers with a modified loot table
+#[inline]
+pub fn compare_buried_treasure_fast_noinv_with_table(
+    loot_table: &LootTable,
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, 12>,
+) -> bool {
+    let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
+    loot_table.compare_fast_noinv(JavaRandom::new(seed), luck, compare)
+}
+
+/// How many of the 27 slots of the generated chest hold the observed stack, 0 if the loot holds too many items to
+/// match, see [`LootTable::compare_scored`]
+///
+/// ```
+/// use mcseedcracker::{
+///     features::buried_treasure::{
+///         build_fast_inventory_compare_context, compare_buried_treasure_scored, get_buried_treasure,
+///         get_loot_table, items::TNT,
+///     },
+///     loot_table::{Inventory, ItemStack},
+/// };
+///
+/// let world_seed = -7193194438565520372;
+/// let mut chest = get_buried_treasure(world_seed, (25, 50), 0.0);
+/// let ctx = build_fast_inventory_compare_context(chest.clone(), &get_loot_table()).unwrap();
+/// assert_eq!(compare_buried_treasure_scored(world_seed, (25, 50), 0.0, &ctx), 27);
+///
+/// let empty = (0..27).find(|&slot| chest.get_item(slot).is_none()).unwrap();
+/// chest.set_item(empty, Some(ItemStack::of(TNT, 1)));
+/// let ctx = build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap();
+/// assert_eq!(compare_buried_treasure_scored(world_seed, (25, 50), 0.0, &ctx), 26);
+/// ```
+#[inline]
+pub fn compare_buried_treasure_scored(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, 12>,
+) -> u32 {
+    let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
+    get_loot_table().compare_scored(JavaRandom::new(seed), luck, compare)
+}
+
+/// Same as [`compare_buried_treasure_scored`], for servers with a modified loot table
+#[inline]
+pub fn compare_buried_treasure_scored_with_table(
+    loot_table: &LootTable,
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, 12>,
+) -> u32 {
+    let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
+    loot_table.compare_scored(JavaRandom::new(seed), luck, compare)
+}
+
+/// Whether at least [`FastInventoryCompareContext::min_matching_slots`] slots match, for chests remembered with a few
+/// mistakes
+#[inline]
+pub fn compare_buried_treasure_threshold(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, 12>,
+) -> bool {
+    compare_buried_treasure_scored(world_seed, chunk_pos, luck, compare)
+        >= compare.min_matching_slots()
+}
+
+/// Same as [`compare_buried_treasure_threshold`], for servers with a modified loot table
+#[inline]
+pub fn compare_buried_treasure_threshold_with_table(
+    loot_table: &LootTable,
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, 12>,
+) -> bool {
+    compare_buried_treasure_scored_with_table(loot_table, world_seed, chunk_pos, luck, compare)
+        >= compare.min_matching_slots()
+}
+
+/// Only checks the item totals of the chest, see [`ItemCountConstraints`]
+///
+/// ```
+/// use mcseedcracker::{
+///     features::buried_treasure::{
+///         compare_buried_treasure_counts,
+///         items::{HEART_OF_THE_SEA, IRON_INGOT},
+///     },
+///     loot_table::{CountBound, ItemCountConstraints},
+/// };
+///
+/// let world_seed = -7193194438565520372;
+/// let counts = ItemCountConstraints::new(vec![
+///     (IRON_INGOT, CountBound::AtLeast(8)),
+///     (HEART_OF_THE_SEA, CountBound::Exactly(1)),
+/// ]);
+/// assert!(compare_buried_treasure_counts(world_seed, (25, 50), 0.0, &counts));
+///
+/// let counts = ItemCountConstraints::new(vec![(IRON_INGOT, CountBound::AtMost(8))]);
+/// assert!(!compare_buried_treasure_counts(world_seed, (25, 50), 0.0, &counts));
+/// ```
+#[inline]
+pub fn compare_buried_treasure_counts(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    constraints: &ItemCountConstraints<12>,
+) -> bool {
+    let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
+    get_loot_table().compare_counts(JavaRandom::new(seed), luck, constraints)
+}
+
+/// Same as [`compare_buried_treasure_counts`], for servers with a modified loot table
+#[inline]
+pub fn compare_buried_treasure_counts_with_table(
+    loot_table: &LootTable,
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+    constraints: &ItemCountConstraints<12>,
+) -> bool {
+    let seed = get_buried_treasure_loot_table_seed(world_seed, chunk_pos);
+    loot_table.compare_counts(JavaRandom::new(seed), luck, constraints)
+}
+
 #[inline]
 pub fn get_loot_table() -> LootTable {
     LootTableBuilder::new()
@@ -192,18 +536,29 @@ pub fn get_loot_table() -> LootTable {
 }
 
 #[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
 mod tests {
+    use std::sync::Arc;
+
     use crate::{
         features::buried_treasure::{
-            build_fast_inventory_compare_context, compare_buried_treasure_fast,
-            get_buried_treasure, get_buried_treasure_loot_table_seed,
+            ChunkArea, build_fast_inventory_compare_context, compare_buried_treasure_counts,
+            compare_buried_treasure_counts_with_table, compare_buried_treasure_fast,
+            compare_buried_treasure_fast_noinv_with_table, generates_at, get_buried_treasure,
+            get_buried_treasure_loot_table_seed, get_loot_table, item_registry,
             items::{
-                COOKED_COD, COOKED_SALMON, EMERALD, GOLD_INGOT, HEART_OF_THE_SEA, IRON_INGOT,
-                IRON_SWORD, LEATHER_CHESTPLATE, PRISMARINE_CRYSTALS,
+                COOKED_COD, COOKED_SALMON, DIAMOND, EMERALD, GOLD_INGOT, HEART_OF_THE_SEA,
+                IRON_INGOT, IRON_SWORD, LEATHER_CHESTPLATE, PRISMARINE_CRYSTALS, TNT,
             },
         },
-        loot_table::{ChestRow, ItemStack, SingleChest},
+        loot_table::{
+            ChestRow, CountBound, ItemCountConstraints, ItemStack, LootTable, SingleChest,
+            from_datapack_json,
+        },
         math::Math,
+        random::{JavaRandom, float_vectors},
+        search::{StructureData, StructureSeedSearchData},
+        self_test::{BURIED_TREASURE_POS, WORLD_SEED},
     };
 
     fn try_seed(world_seed: i64, block_pos: (i32, i32), expected_seed: i64) {
@@ -351,12 +706,13 @@ mod tests {
     #[test]
     fn test_buried_treasure_fast_compare() {
         let mut chest = SingleChest::new();
+        let loot_table = get_loot_table();
 
         for seed in 0..100 {
             for chunk_x in 0..10 {
                 for chunk_z in 0..10 {
                     let standard = get_buried_treasure(seed, (chunk_x, chunk_z), 0.0);
-                    let ctx = build_fast_inventory_compare_context(standard);
+                    let ctx = build_fast_inventory_compare_context(standard, &loot_table).unwrap();
 
                     assert!(
                         compare_buried_treasure_fast(
@@ -376,14 +732,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_buried_treasure_luck() {
+        let mut chest = SingleChest::new();
+        let loot_table = get_loot_table();
+
+        // no entry of the table has a quality, luck never changes the chest
+        for seed in 0..50 {
+            let standard = get_buried_treasure(seed, (seed as i32, -3), 0.0);
+            let ctx = build_fast_inventory_compare_context(standard.clone(), &loot_table).unwrap();
+            for luck in [1.0, 2.5, -1.0] {
+                assert_eq!(get_buried_treasure(seed, (seed as i32, -3), luck), standard);
+                assert!(compare_buried_treasure_fast(
+                    seed,
+                    (seed as i32, -3),
+                    luck,
+                    &ctx,
+                    &mut chest
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_buried_treasure_seeds_extreme() {
+        assert_eq!(
+            get_buried_treasure_loot_table_seed(i64::MAX, (1_874_999, -1_875_000)),
+            -8598018997913746896
+        );
+        assert_eq!(
+            get_buried_treasure_loot_table_seed(i64::MIN, (-1_875_000, 1_874_999)),
+            -539548871505988602
+        );
+    }
+
     #[test]
     fn test_fast_compare_invariants() {
+        let loot_table = get_loot_table();
         for i in 0..100 {
             let seed: i64 = 0x123456789ABCDEF + i;
             let mystery_chest = get_buried_treasure(seed, (12, 34), 0.0);
 
             let mut chest = SingleChest::new();
-            let ctx = build_fast_inventory_compare_context(mystery_chest);
+            let ctx = build_fast_inventory_compare_context(mystery_chest, &loot_table).unwrap();
 
             for cx in 0..50 {
                 for cz in 0..50 {
@@ -398,4 +789,397 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_datapack_loot_table_round_trip() {
+        let json = include_str!("../../data/loot_tables/chests/buried_treasure.json");
+        let loaded = from_datapack_json(json.as_bytes(), &item_registry()).unwrap();
+        let builtin = get_loot_table();
+
+        for seed in [
+            -8385268767001419331i64,
+            -476893202187324250,
+            1749169673229764907,
+            0,
+            i64::MIN,
+            i64::MAX,
+        ] {
+            let mut expected = SingleChest::new();
+            builtin.generate_in_inventory(&mut expected, &mut JavaRandom::new(seed), 0.0);
+            let mut actual = SingleChest::new();
+            loaded.generate_in_inventory(&mut actual, &mut JavaRandom::new(seed), 0.0);
+            assert_eq!(actual, expected, "loot seed {}", seed);
+        }
+
+        let world_seed = -7193194438565520372;
+        let ctx = build_fast_inventory_compare_context(
+            get_buried_treasure(world_seed, (25, 50), 0.0),
+            &loaded,
+        )
+        .unwrap();
+        assert!(compare_buried_treasure_fast_noinv_with_table(
+            &loaded,
+            world_seed,
+            (25, 50),
+            0.0,
+            &ctx
+        ));
+    }
+
+    #[test]
+    fn test_datapack_extra_items_search() {
+        // the vanilla table with a stick after its pools, an item the observed chest can't hold
+        let mut json: serde_json::Value = serde_json::from_str(include_str!(
+            "../../data/loot_tables/chests/buried_treasure.json"
+        ))
+        .unwrap();
+        json["pools"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({
+                "rolls": 1,
+                "entries": [{"type": "item", "name": "minecraft:stick"}]
+            }));
+        let loot_table = from_datapack_json(json.to_string().as_bytes(), &item_registry()).unwrap();
+
+        let structure_seed = 180066252004364;
+        let contents = build_fast_inventory_compare_context(
+            get_buried_treasure(structure_seed, (25, 50), 0.0),
+            &loot_table,
+        )
+        .unwrap();
+        let search = |loot_table: Option<LootTable>| {
+            StructureSeedSearchData::new(
+                13847,
+                vec![StructureData::BuriedTreasureContents {
+                    chunk_x: 25,
+                    chunk_z: 50,
+                    luck: 0.0,
+                    contents: contents.clone(),
+                    loot_table: loot_table.map(Arc::new),
+                }],
+                10,
+            )
+            .search_bounded(47520..47525)
+        };
+        assert_eq!(search(None), vec![structure_seed]);
+        // the stick is always in excess, even in the chest of the structure seed
+        assert!(search(Some(loot_table)).is_empty());
+    }
+
+    #[test]
+    fn test_context_rejects_foreign_items() {
+        use crate::{
+            features::bastion::items::other::{CRYING_OBSIDIAN, GOLD_BLOCK},
+            loot_table::{InvalidObservation, Inventory},
+        };
+
+        let mut chest = SingleChest::new();
+        chest.set_item(0, Some(ItemStack::of(IRON_INGOT, 3)));
+        chest.set_item(4, Some(ItemStack::of(CRYING_OBSIDIAN, 2)));
+        chest.set_item(10, Some(ItemStack::of(GOLD_BLOCK, 1)));
+        assert_eq!(
+            build_fast_inventory_compare_context(chest.clone(), &get_loot_table()),
+            Err(InvalidObservation {
+                items: vec![(4, CRYING_OBSIDIAN), (10, GOLD_BLOCK)],
+                ..Default::default()
+            })
+        );
+
+        chest.set_item(4, None);
+        chest.set_item(10, None);
+        let ctx = build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap();
+        assert_eq!(ctx.items_count[IRON_INGOT], 3);
+        assert_eq!(ctx.total_items, 3);
+    }
+
+    #[test]
+    fn test_stack_counts() {
+        use crate::loot_table::{InvalidObservation, InvalidStack, Inventory};
+
+        let registry = item_registry();
+        assert!(ItemStack::validated(IRON_INGOT, 64, &registry).is_ok());
+        assert!(ItemStack::validated(IRON_SWORD, 1, &registry).is_ok());
+        for (item, count, max_count) in [
+            (IRON_INGOT, 65, 64),
+            (IRON_INGOT, 0, 64),
+            (IRON_SWORD, 2, 1),
+        ] {
+            assert_eq!(
+                ItemStack::validated(item, count, &registry),
+                Err(InvalidStack::CountOutOfRange { count, max_count })
+            );
+        }
+        assert_eq!(
+            ItemStack::validated(99, 1, &registry),
+            Err(InvalidStack::UnknownItem(99))
+        );
+
+        let loot_table = get_loot_table();
+        assert_eq!(loot_table.max_stack_size(IRON_INGOT), Some(64));
+        assert_eq!(loot_table.max_stack_size(LEATHER_CHESTPLATE), Some(1));
+
+        // the stack sizes entered with the stacks are not trusted
+        let mut chest = SingleChest::new();
+        chest.set_item(0, Some(ItemStack::of(IRON_INGOT, 64)));
+        chest.set_item(1, Some(ItemStack::of(IRON_INGOT, 70)));
+        chest.set_item(2, Some(ItemStack::of(IRON_SWORD, 3)));
+        chest.set_item(3, Some(ItemStack::of(TNT, 0)));
+        chest.set_item(4, Some(ItemStack::of(IRON_SWORD, 1)));
+        let invalid = build_fast_inventory_compare_context(chest.clone(), &loot_table).unwrap_err();
+        assert_eq!(
+            invalid,
+            InvalidObservation {
+                counts: vec![(1, 70, 64), (2, 3, 1), (3, 0, 64)],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            invalid.to_string(),
+            "the stack in slot 1 holds 70 items, not between 1 and 64; the stack in slot 2 holds 3 items, not between 1 and 1; the stack in slot 3 holds 0 items, not between 1 and 64"
+        );
+
+        for slot in 1..4 {
+            chest.set_item(slot, None);
+        }
+        let ctx = build_fast_inventory_compare_context(chest, &loot_table).unwrap();
+        assert_eq!(ctx.total_items, 65);
+        assert_eq!(
+            ctx.inventory.get_item(4),
+            Some(&ItemStack::new(IRON_SWORD, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_item_registry() {
+        let registry = item_registry();
+        for (name, item) in registry.iter() {
+            assert_eq!(registry.get(name), Some(item));
+            assert_eq!(registry.name_of(item.id), Some(name));
+        }
+        assert_eq!(registry.get("iron_ingot").map(|i| i.id), Some(IRON_INGOT));
+        assert_eq!(registry.get("minecraft:stick"), None);
+        assert_eq!(registry.duplicate_ids(), Vec::<usize>::new());
+    }
+
+    /// The shares kept are the ones documented on [`ItemCountConstraints`]
+    #[test]
+    fn test_count_constraints_filtering_power() {
+        use CountBound::{AtLeast, AtMost, Between, Exactly};
+
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        let mut rng = JavaRandom::new(0);
+        let structure_seeds = (0..20000)
+            .map(|_| rng.next_long() & ((1 << 48) - 1))
+            .collect::<Vec<_>>();
+        let kept = |counts: &ItemCountConstraints<12>| {
+            let kept = structure_seeds
+                .iter()
+                .filter(|&&seed| compare_buried_treasure_counts(seed, chunk, 0.0, counts))
+                .count();
+            kept as f64 / structure_seeds.len() as f64
+        };
+
+        let fixture = [
+            (IRON_INGOT, 9),
+            (COOKED_COD, 8),
+            (PRISMARINE_CRYSTALS, 4),
+            (GOLD_INGOT, 3),
+            (IRON_SWORD, 1),
+            (HEART_OF_THE_SEA, 1),
+        ];
+        for (bounds, expected) in [
+            (vec![(IRON_INGOT, AtLeast(8))], 0.65),
+            (vec![(IRON_INGOT, AtMost(9))], 0.54),
+            (vec![(IRON_INGOT, Between(8, 10))], 0.28),
+            (vec![(IRON_INGOT, Exactly(9))], 0.09),
+            (
+                vec![(IRON_INGOT, AtLeast(8)), (IRON_SWORD, Exactly(1))],
+                0.16,
+            ),
+            (fixture.map(|(item, n)| (item, AtLeast(n))).to_vec(), 0.0007),
+        ] {
+            let counts = ItemCountConstraints::new(bounds);
+            assert!(compare_buried_treasure_counts(
+                WORLD_SEED, chunk, 0.0, &counts
+            ));
+            let kept = kept(&counts);
+            assert!(
+                (kept - expected).abs() < 0.02,
+                "{:?}: kept {}",
+                counts.bounds,
+                kept
+            );
+        }
+
+        // the exact totals of every item, nothing else may be in the chest
+        let mut exact = fixture.map(|(item, n)| (item, Exactly(n))).to_vec();
+        for item in [TNT, EMERALD, DIAMOND, LEATHER_CHESTPLATE, COOKED_SALMON] {
+            exact.push((item, Exactly(0)));
+        }
+        let exact = ItemCountConstraints::new(exact);
+        assert!(compare_buried_treasure_counts(
+            WORLD_SEED, chunk, 0.0, &exact
+        ));
+        assert!(kept(&exact) < 0.001);
+        assert!(compare_buried_treasure_counts_with_table(
+            &get_loot_table(),
+            WORLD_SEED,
+            chunk,
+            0.0,
+            &exact
+        ));
+        assert!(!compare_buried_treasure_counts(
+            WORLD_SEED,
+            (chunk.0 + 1, chunk.1),
+            0.0,
+            &exact
+        ));
+    }
+
+    #[test]
+    fn test_scored_compare() {
+        use crate::{
+            features::buried_treasure::{
+                compare_buried_treasure_fast_noinv, compare_buried_treasure_scored,
+                compare_buried_treasure_threshold,
+            },
+            loot_table::{Inventory, SlotThreshold},
+            self_test,
+        };
+
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        // the 2 gold ingots of slot 9 remembered as 3
+        let mut chest = self_test::buried_treasure_contents();
+        chest.set_item(9, Some(ItemStack::of(GOLD_INGOT, 3)));
+        let ctx = build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap();
+        assert!(!compare_buried_treasure_fast_noinv(
+            WORLD_SEED, chunk, 0.0, &ctx
+        ));
+        assert_eq!(
+            compare_buried_treasure_scored(WORLD_SEED, chunk, 0.0, &ctx),
+            26
+        );
+        assert_eq!(ctx.min_matching_slots(), 27);
+        assert!(!compare_buried_treasure_threshold(
+            WORLD_SEED, chunk, 0.0, &ctx
+        ));
+
+        let ctx = ctx.with_slot_threshold(Some(SlotThreshold::new(26, 27)));
+        assert_eq!(ctx.slot_threshold.unwrap().item_tolerance, 64);
+        assert!(compare_buried_treasure_threshold(
+            WORLD_SEED, chunk, 0.0, &ctx
+        ));
+        assert!(!compare_buried_treasure_threshold(
+            WORLD_SEED,
+            (chunk.0 + 1, chunk.1),
+            0.0,
+            &ctx
+        ));
+
+        // only the heart of the sea was seen, the iron ingots exceed it and the tolerance
+        let mut chest = SingleChest::new();
+        chest.set_item(0, Some(ItemStack::of(HEART_OF_THE_SEA, 1)));
+        let ctx = build_fast_inventory_compare_context(chest, &get_loot_table())
+            .unwrap()
+            .with_slot_threshold(Some(SlotThreshold {
+                min_matching_slots: 0,
+                item_tolerance: 2,
+            }));
+        assert_eq!(
+            compare_buried_treasure_scored(WORLD_SEED, chunk, 0.0, &ctx),
+            0
+        );
+    }
+
+    #[test]
+    fn test_partial_compare() {
+        use crate::{
+            features::buried_treasure::{
+                build_partial_inventory_compare_context, compare_buried_treasure_fast_noinv,
+                compare_buried_treasure_scored,
+            },
+            loot_table::Inventory,
+            self_test,
+        };
+
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        let chest = self_test::buried_treasure_contents();
+        // the first two rows were noted, the last one was emptied before
+        let mut known = [false; 27];
+        known[..18].fill(true);
+        let mut seen = chest.clone();
+        for slot in 18..27 {
+            seen.set_item(slot, Some(ItemStack::of(TNT, 1)));
+        }
+        let ctx = build_partial_inventory_compare_context(seen, known, &get_loot_table()).unwrap();
+        assert_eq!(ctx.min_matching_slots(), 18);
+        assert_eq!(ctx.items_count[TNT], 0);
+        let known_items = (0..18)
+            .filter_map(|slot| chest.get_item(slot))
+            .map(|stack| stack.count)
+            .sum::<i32>();
+        assert_eq!(ctx.total_items, known_items);
+        assert!(ctx.accepts(&chest));
+        assert_eq!(
+            compare_buried_treasure_scored(WORLD_SEED, chunk, 0.0, &ctx),
+            18
+        );
+
+        let mut accepted = Vec::new();
+        for cx in chunk.0 - 25..chunk.0 + 25 {
+            for cz in chunk.1 - 25..chunk.1 + 25 {
+                if compare_buried_treasure_fast_noinv(WORLD_SEED, (cx, cz), 0.0, &ctx) {
+                    accepted.push((cx, cz));
+                }
+            }
+        }
+        assert_eq!(accepted, vec![chunk]);
+
+        // the same 18 slots as a full observation, the last row then must be empty
+        let mut truncated = chest.clone();
+        for slot in 18..27 {
+            truncated.set_item(slot, None);
+        }
+        let ctx = build_fast_inventory_compare_context(truncated, &get_loot_table()).unwrap();
+        assert!(!compare_buried_treasure_fast_noinv(
+            WORLD_SEED, chunk, 0.0, &ctx
+        ));
+
+        // a known slot with another count rejects the chest
+        let mut wrong = chest.clone();
+        let slot = (0..18)
+            .find(|&slot| chest.get_item(slot).is_some())
+            .unwrap();
+        let mut stack = chest.get_item(slot).unwrap().clone();
+        stack.count = if stack.count == 1 { 2 } else { 1 };
+        wrong.set_item(slot, Some(stack));
+        let ctx = build_partial_inventory_compare_context(wrong, known, &get_loot_table()).unwrap();
+        assert!(!compare_buried_treasure_fast_noinv(
+            WORLD_SEED, chunk, 0.0, &ctx
+        ));
+    }
+
+    #[test]
+    fn test_placement_vectors() {
+        let vectors = float_vectors("placement").collect::<Vec<_>>();
+        assert_eq!(vectors.len(), 1000);
+        // half of them are within a few 2^-24 of the probability
+        assert!(vectors.iter().filter(|fields| fields[3] == "true").count() > 100);
+        for fields in vectors {
+            let world_seed: i64 = fields[0].parse().unwrap();
+            let chunk: (i32, i32) = (fields[1].parse().unwrap(), fields[2].parse().unwrap());
+            let generates: bool = fields[3].parse().unwrap();
+
+            assert_eq!(
+                generates_at(world_seed, chunk),
+                generates,
+                "{} {:?}",
+                world_seed,
+                chunk
+            );
+            let area = ChunkArea::new(chunk, chunk);
+            assert_eq!(area.find_chunk(world_seed, |_| true).is_some(), generates);
+        }
+    }
 }
@@ -0,0 +1,274 @@
+//! Surface decoration counts (sugar cane, cactus) for 1.16.5.
+//!
+//! Placement of these plants depends on the terrain (exact ground height, nearby water, other blocks), which we
+//! can't know without generating the chunk. The count is reproduced assuming a flat valid ground at `surface_y`,
+//! so it must only be used as a consistent/inconsistent signal to rank the few surviving world seeds, never as a
+//! hard filter.
+
+use crate::{
+    math::Math,
+    random::{JavaRandom, random_with_decorator_seed, random_with_population_seed},
+};
+
+/// GenerationStep.Decoration.VEGETAL_DECORATION, in the units used by `random_with_decorator_seed`
+pub const VEGETAL_DECORATION_STEP: i32 = 80;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DecorationKind {
+    /// `patch_sugar_cane`, as found on beaches
+    SugarCane,
+    /// `patch_cactus_desert`
+    Cactus,
+}
+
+impl DecorationKind {
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            DecorationKind::SugarCane => "sugar_cane",
+            DecorationKind::Cactus => "cactus",
+        }
+    }
+
+    #[inline]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sugar_cane" => Some(DecorationKind::SugarCane),
+            "cactus" => Some(DecorationKind::Cactus),
+            _ => None,
+        }
+    }
+
+    /// Index of the feature in the vegetal decoration list of the biome it usually appears in (beach for sugar cane, desert for cactus)
+    #[inline(always)]
+    pub const fn default_feature_index(&self) -> i32 {
+        match self {
+            DecorationKind::SugarCane => 4,
+            DecorationKind::Cactus => 7,
+        }
+    }
+
+    /// Number of patches attempted per chunk (count decorator)
+    #[inline(always)]
+    pub const fn patch_count(&self) -> i32 {
+        match self {
+            DecorationKind::SugarCane => 10,
+            DecorationKind::Cactus => 10,
+        }
+    }
+
+    /// Placement tries per patch
+    #[inline(always)]
+    pub const fn tries(&self) -> i32 {
+        match self {
+            DecorationKind::SugarCane => 20,
+            DecorationKind::Cactus => 10,
+        }
+    }
+
+    /// (x, y, z) spread of a patch
+    #[inline(always)]
+    pub const fn spread(&self) -> (i32, i32, i32) {
+        match self {
+            DecorationKind::SugarCane => (4, 0, 4),
+            DecorationKind::Cactus => (7, 3, 7),
+        }
+    }
+
+    /// (min size, extra size) of the column placer
+    #[inline(always)]
+    pub const fn column(&self) -> (i32, i32) {
+        match self {
+            DecorationKind::SugarCane => (2, 2),
+            DecorationKind::Cactus => (1, 2),
+        }
+    }
+}
+
+#[inline(always)]
+pub const fn get_decoration_random(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    feature_index: i32,
+) -> (JavaRandom, i64) {
+    let block_pos = Math::relative_chunk_coords(chunk_pos, (0, 0));
+
+    let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
+
+    random_with_decorator_seed(population_seed, feature_index, VEGETAL_DECORATION_STEP)
+}
+
+#[inline(always)]
+fn spread_offset(rng: &mut JavaRandom, spread: i32) -> i32 {
+    let a = rng.next_bounded_int(spread.wrapping_add(1));
+    let b = rng.next_bounded_int(spread.wrapping_add(1));
+    a.wrapping_sub(b)
+}
+
+/// Number of plant columns the chunk's patches would place on a flat valid ground whose first air block is at `surface_y`
+///
+/// ```
+/// use mcseedcracker::features::decoration::{DecorationKind, predict_decoration_count};
+///
+/// let kind = DecorationKind::SugarCane;
+/// let count =
+///     predict_decoration_count(-7193194438565520372, (9, 50), kind, kind.default_feature_index(), 63);
+/// assert_eq!(count, 17);
+/// ```
+pub fn predict_decoration_count(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    decoration: DecorationKind,
+    feature_index: i32,
+    surface_y: i32,
+) -> u32 {
+    let mut rng = get_decoration_random(world_seed, chunk_pos, feature_index).0;
+    let (spread_x, spread_y, spread_z) = decoration.spread();
+    let (min_size, extra_size) = decoration.column();
+
+    // (x, z, height) relative to the chunk origin
+    let mut columns: Vec<(i32, i32, i32)> = Vec::new();
+
+    for _ in 0..decoration.patch_count() {
+        let x = rng.next_bounded_int(16);
+        let z = rng.next_bounded_int(16);
+        if surface_y <= 0 {
+            continue;
+        }
+        let y = rng.next_bounded_int(surface_y.wrapping_mul(2));
+
+        for _ in 0..decoration.tries() {
+            let px = x.wrapping_add(spread_offset(&mut rng, spread_x));
+            let py = y.wrapping_add(spread_offset(&mut rng, spread_y));
+            let pz = z.wrapping_add(spread_offset(&mut rng, spread_z));
+
+            let column = columns.iter_mut().find(|c| c.0 == px && c.1 == pz);
+            let can_place = match &column {
+                // growing on top of an existing column of the same plant
+                Some(c) => py == surface_y.wrapping_add(c.2),
+                None => py == surface_y,
+            };
+            if !can_place {
+                continue;
+            }
+
+            let extra_bound = rng
+                .next_bounded_int(extra_size.wrapping_add(1))
+                .wrapping_add(1);
+            let height = min_size.wrapping_add(rng.next_bounded_int(extra_bound));
+            match column {
+                Some(c) => c.2 = c.2.wrapping_add(height),
+                None => columns.push((px, pz, height)),
+            }
+        }
+    }
+
+    columns.len() as u32
+}
+
+#[inline]
+pub fn is_decoration_count_consistent(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    decoration: DecorationKind,
+    feature_index: i32,
+    surface_y: i32,
+    observed_count: u32,
+    tolerance: u32,
+) -> bool {
+    predict_decoration_count(world_seed, chunk_pos, decoration, feature_index, surface_y)
+        .abs_diff(observed_count)
+        <= tolerance
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::features::decoration::{
+        DecorationKind, get_decoration_random, is_decoration_count_consistent,
+        predict_decoration_count,
+    };
+
+    /// `(world seed, chunk, decoration, surface y, expected count)`
+    type Fixture = (i64, (i32, i32), DecorationKind, i32, u32);
+
+    const FIXTURES: [Fixture; 6] = [
+        (
+            -7193194438565520372,
+            (9, 50),
+            DecorationKind::SugarCane,
+            63,
+            17,
+        ),
+        (
+            -7193194438565520372,
+            (25, 50),
+            DecorationKind::SugarCane,
+            63,
+            0,
+        ),
+        (734679766044180411, (3, 10), DecorationKind::Cactus, 70, 4),
+        (734679766044180411, (15, 10), DecorationKind::Cactus, 70, 8),
+        (734679766044180411, (6, 10), DecorationKind::Cactus, 70, 0),
+        (0, (0, 0), DecorationKind::Cactus, 64, 1),
+    ];
+
+    #[test]
+    fn test_decoration_seed() {
+        let (_, seed) = get_decoration_random(0, (0, 0), 4);
+        let (_, seed2) = get_decoration_random(0, (0, 0), 5);
+        assert_eq!(seed + 1, seed2);
+    }
+
+    #[test]
+    fn test_decoration_count() {
+        for (world_seed, chunk, kind, surface_y, expected) in FIXTURES {
+            let index = kind.default_feature_index();
+            let count = predict_decoration_count(world_seed, chunk, kind, index, surface_y);
+            assert_eq!(count, expected);
+            // every column needs its own patch try
+            assert!(count <= (kind.patch_count() * kind.tries()) as u32);
+
+            assert!(is_decoration_count_consistent(
+                world_seed, chunk, kind, index, surface_y, count, 0
+            ));
+            assert!(is_decoration_count_consistent(
+                world_seed,
+                chunk,
+                kind,
+                index,
+                surface_y,
+                count + 2,
+                2
+            ));
+            assert!(!is_decoration_count_consistent(
+                world_seed,
+                chunk,
+                kind,
+                index,
+                surface_y,
+                count + 3,
+                2
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decoration_no_surface() {
+        for (world_seed, chunk, kind, _, _) in FIXTURES {
+            let index = kind.default_feature_index();
+            assert_eq!(
+                predict_decoration_count(world_seed, chunk, kind, index, 0),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn test_decoration_kind_names() {
+        for kind in [DecorationKind::SugarCane, DecorationKind::Cactus] {
+            assert_eq!(DecorationKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(DecorationKind::from_name("bamboo"), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! Chests filled by a decorator. All the chests of a chunk draw their loot table seed from the same decorator random,
+//! one `nextLong` after the other, so the seed of a chest depends on how many chests of the chunk were filled
+//! before it. That index depends on generation details the player can't see, so it can be detected instead of
+//! guessed.
+
+use std::ops::Range;
+
+use crate::{
+    lcg::{self, LinearCongruentialGenerator},
+    loot_table::{FastInventoryCompareContext, LootTable, SingleChest},
+    math::Math,
+    random::{DecoratorSalt, JavaRandom, random_with_decorator_seed, random_with_population_seed},
+};
+
+/// Number of chest indices tried when the index isn't known
+pub const DEFAULT_MAX_CHEST_INDEX: i32 = 4;
+
+/// Which chest of its chunk some chest evidence is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChestIndex {
+    Known(i32),
+    /// Tries every index in `0..max`
+    AutoDetect {
+        max: i32,
+    },
+}
+
+impl Default for ChestIndex {
+    #[inline]
+    fn default() -> Self {
+        Self::AutoDetect {
+            max: DEFAULT_MAX_CHEST_INDEX,
+        }
+    }
+}
+
+impl ChestIndex {
+    #[inline]
+    pub const fn indices(&self) -> Range<i32> {
+        match *self {
+            Self::Known(index) => index..index.wrapping_add(1),
+            Self::AutoDetect { max } => 0..max,
+        }
+    }
+}
+
+impl std::fmt::Display for ChestIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Known(index) => write!(f, "chest {}", index),
+            Self::AutoDetect { max } => write!(f, "any of the first {} chests", max),
+        }
+    }
+}
+
+/// The decorator random of one chunk, from which the loot table seeds of all its chests are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoratorChestSeeds {
+    /// Internal state of the random before the first chest's `nextLong`
+    state: i64,
+}
+
+impl DecoratorChestSeeds {
+    /// Every chest uses one `nextLong`, which is two LCG steps
+    const NEXT_CHEST: LinearCongruentialGenerator = lcg::JAVA_RANDOM.combine(2);
+
+    #[inline(always)]
+    pub const fn new(world_seed: i64, chunk_pos: (i32, i32), salt: DecoratorSalt) -> Self {
+        let block_pos = Math::relative_chunk_coords(chunk_pos, (0, 0));
+        let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
+
+        Self::from_decorator_seed(
+            random_with_decorator_seed(population_seed, salt.index(), salt.step()).1,
+        )
+    }
+
+    #[inline(always)]
+    pub const fn from_decorator_seed(decorator_seed: i64) -> Self {
+        Self {
+            state: lcg::JAVA_RANDOM._mod(decorator_seed ^ lcg::JAVA_RANDOM.get_multiplier()),
+        }
+    }
+
+    /// Seed of the loot table of the chest filled after `num_chest_in_chunk` other chests
+    #[inline(always)]
+    pub const fn loot_table_seed(&self, num_chest_in_chunk: i32) -> i64 {
+        let state = lcg::JAVA_RANDOM
+            .combine((num_chest_in_chunk as i64).wrapping_mul(2))
+            .next_seed(self.state);
+
+        Self::next_long(state)
+    }
+
+    /// Loot table seeds of the chests in `indices`. The random is stepped from one chest to the next, instead of
+    /// skipping ahead from the first chest for every index.
+    #[inline]
+    pub fn loot_table_seeds(&self, indices: Range<i32>) -> impl Iterator<Item = (i32, i64)> {
+        let mut state = lcg::JAVA_RANDOM
+            .combine((indices.start as i64).wrapping_mul(2))
+            .next_seed(self.state);
+
+        indices.map(move |index| {
+            let seed = Self::next_long(state);
+            state = Self::NEXT_CHEST.next_seed(state);
+            (index, seed)
+        })
+    }
+
+    #[inline(always)]
+    const fn next_long(state: i64) -> i64 {
+        let next1 = lcg::JAVA_RANDOM.next_seed(state);
+        let next2 = lcg::JAVA_RANDOM.next_seed(next1);
+
+        (next1 & 0xFFFF_FFFF_0000)
+            .wrapping_shl(16)
+            .wrapping_add((next2.wrapping_shr(16) as i32) as i64)
+    }
+}
+
+/// Returns the first chest index allowed by `index` whose loot matches `compare`
+#[inline]
+pub fn detect_chest_index<const N: usize>(
+    seeds: &DecoratorChestSeeds,
+    index: ChestIndex,
+    loot_table: &LootTable,
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, N>,
+) -> Option<i32> {
+    seeds
+        .loot_table_seeds(index.indices())
+        .find(|(_, seed)| loot_table.compare_fast_noinv(JavaRandom::new(*seed), luck, compare))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::{
+        features::{
+            bastion::{
+                self, BastionChestType, get_bastion_chest, get_bastion_chest_loot_table_seed,
+                get_bastion_chest_seeds,
+            },
+            decorator_chest::ChestIndex,
+        },
+        math::Math,
+    };
+
+    #[test]
+    fn test_loot_table_seeds() {
+        let world_seed: i64 = 734679766044180411;
+
+        for (pos, num, seed) in [
+            ((97, 166), 0, 2799166732823584713i64),
+            ((105, 207), 1, 4599594689264021764i64),
+            ((75, 203), 2, 3009594620990002609i64),
+            ((-674, -820), 1, 4548431199292841666i64),
+        ] {
+            let chunk = Math::block_coords_to_chunk_coords(pos);
+            let seeds = get_bastion_chest_seeds(world_seed, chunk);
+
+            assert_eq!(seeds.loot_table_seed(num), seed);
+            assert_eq!(
+                seeds.loot_table_seeds(0..8).nth(num as usize),
+                Some((num, seed))
+            );
+            assert_eq!(
+                seeds.loot_table_seeds(num..num + 1).next(),
+                Some((num, seed))
+            );
+        }
+
+        for (world_seed, chunk) in [
+            (i64::MAX, (1_875_000, -1_875_000)),
+            (i64::MIN, (-1_875_000, 1_875_000)),
+            (world_seed, (-100, 42)),
+        ] {
+            let seeds = get_bastion_chest_seeds(world_seed, chunk);
+            for (index, seed) in seeds.loot_table_seeds(0..8) {
+                assert_eq!(
+                    seed,
+                    get_bastion_chest_loot_table_seed(world_seed, chunk, index)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_chest_index() {
+        let world_seed: i64 = 734679766044180411;
+
+        for (pos, num, chest_type) in [
+            ((97, 166), 0, BastionChestType::HoglinStables),
+            ((105, 207), 1, BastionChestType::HoglinStables),
+            ((75, 203), 2, BastionChestType::HoglinStables),
+            ((-674, -820), 1, BastionChestType::TreasureRoom),
+        ] {
+            let chunk = Math::block_coords_to_chunk_coords(pos);
+            let loot_table = bastion::get_loot_table(chest_type);
+            let chest = get_bastion_chest(world_seed, chunk, num, chest_type, 0.0);
+            let compare =
+                bastion::build_fast_inventory_compare_context(chest, &loot_table).unwrap();
+
+            let detect = |index| {
+                bastion::compare_bastion_chest_fast_noinv(
+                    world_seed,
+                    chunk,
+                    index,
+                    &loot_table,
+                    0.0,
+                    &compare,
+                )
+            };
+
+            assert_eq!(detect(ChestIndex::Known(num)), Some(num));
+            assert_eq!(detect(ChestIndex::Known(num + 1)), None);
+            assert_eq!(detect(ChestIndex::default()), Some(num));
+            assert_eq!(detect(ChestIndex::AutoDetect { max: num }), None);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use crate::{
+    features::decorator_chest::{ChestIndex, DecoratorChestSeeds, detect_chest_index},
+    loot_table::{
+        FastInventoryCompareContext, InvalidObservation, ItemLootPoolEntryBuilder, ItemRegistry,
+        LootPoolBuilder, LootTable, LootTableBuilder, RegisteredItem, SetCountFunction,
+        SetEnchantsRandomlyFunction, SingleChest,
+    },
+    random::{DecoratorSalt, JavaRandom},
+    utils::enchants,
+};
+
+pub mod items {
+    pub const DIAMOND: usize = 1;
+    pub const IRON_INGOT: usize = 2;
+    pub const GOLD_INGOT: usize = 3;
+    pub const EMERALD: usize = 4;
+    pub const BONE: usize = 5;
+    pub const SPIDER_EYE: usize = 6;
+    pub const ROTTEN_FLESH: usize = 7;
+    pub const SADDLE: usize = 8;
+    pub const IRON_HORSE_ARMOR: usize = 9;
+    pub const GOLDEN_HORSE_ARMOR: usize = 10;
+    pub const DIAMOND_HORSE_ARMOR: usize = 11;
+    pub const ENCHANTED_BOOK: usize = 12;
+    pub const GOLDEN_APPLE: usize = 13;
+    pub const ENCHANTED_GOLDEN_APPLE: usize = 14;
+    pub const GUNPOWDER: usize = 15;
+    pub const STRING: usize = 16;
+    pub const SAND: usize = 17;
+}
+
+/// Larger than every item id of the desert pyramid loot table
+pub const ITEM_COUNT: usize = 18;
+
+/// Number of chests of a desert pyramid, all in the basement under the pressure plate
+pub const CHESTS: usize = 4;
+
+/// The items of the desert pyramid loot table, for [`crate::loot_table::from_datapack_json`]
+pub fn item_registry() -> ItemRegistry {
+    // the loot table puts books in the chests, enchant_randomly turns them into enchanted books
+    let mut book = RegisteredItem::new(items::ENCHANTED_BOOK).stack_size(1);
+    for (name, enchantment) in enchants::BOOK_NAMES.iter().zip(enchants::BOOK) {
+        book = book.enchantment(name, enchantment);
+    }
+
+    ItemRegistry::new()
+        .register("diamond", RegisteredItem::new(items::DIAMOND))
+        .register("iron_ingot", RegisteredItem::new(items::IRON_INGOT))
+        .register("gold_ingot", RegisteredItem::new(items::GOLD_INGOT))
+        .register("emerald", RegisteredItem::new(items::EMERALD))
+        .register("bone", RegisteredItem::new(items::BONE))
+        .register("spider_eye", RegisteredItem::new(items::SPIDER_EYE))
+        .register("rotten_flesh", RegisteredItem::new(items::ROTTEN_FLESH))
+        .register("saddle", RegisteredItem::new(items::SADDLE).stack_size(1))
+        .register(
+            "iron_horse_armor",
+            RegisteredItem::new(items::IRON_HORSE_ARMOR).stack_size(1),
+        )
+        .register(
+            "golden_horse_armor",
+            RegisteredItem::new(items::GOLDEN_HORSE_ARMOR).stack_size(1),
+        )
+        .register(
+            "diamond_horse_armor",
+            RegisteredItem::new(items::DIAMOND_HORSE_ARMOR).stack_size(1),
+        )
+        .register("book", book)
+        .register("golden_apple", RegisteredItem::new(items::GOLDEN_APPLE))
+        .register(
+            "enchanted_golden_apple",
+            RegisteredItem::new(items::ENCHANTED_GOLDEN_APPLE),
+        )
+        .register("gunpowder", RegisteredItem::new(items::GUNPOWDER))
+        .register("string", RegisteredItem::new(items::STRING))
+        .register("sand", RegisteredItem::new(items::SAND))
+}
+
+/// `chests/desert_pyramid` of 1.16.5
+#[inline]
+pub fn get_loot_table() -> LootTable {
+    use crate::features::desert_temple::items::*;
+
+    LootTableBuilder::new()
+        .pool(
+            LootPoolBuilder::new()
+                .rolls_uniform(2, 4)
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(DIAMOND)
+                        .weight(5)
+                        .function(SetCountFunction::uniform(1, 3).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_INGOT)
+                        .weight(15)
+                        .function(SetCountFunction::uniform(1, 5).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLD_INGOT)
+                        .weight(15)
+                        .function(SetCountFunction::uniform(2, 7).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(EMERALD)
+                        .weight(15)
+                        .function(SetCountFunction::uniform(1, 3).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(BONE)
+                        .weight(25)
+                        .function(SetCountFunction::uniform(4, 6).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(SPIDER_EYE)
+                        .weight(25)
+                        .function(SetCountFunction::uniform(1, 3).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(ROTTEN_FLESH)
+                        .weight(25)
+                        .function(SetCountFunction::uniform(3, 7).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(SADDLE)
+                        .item_stack_size(1)
+                        .weight(20)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(15)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLDEN_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(10)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(DIAMOND_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(5)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(ENCHANTED_BOOK)
+                        .item_stack_size(1)
+                        .weight(20)
+                        .function(
+                            SetEnchantsRandomlyFunction::builder()
+                                .all_of(&enchants::BOOK)
+                                .build()
+                                .as_function(),
+                        )
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLDEN_APPLE)
+                        .weight(20)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(ENCHANTED_GOLDEN_APPLE)
+                        .weight(2)
+                        .build(),
+                )
+                .entry_empty(15)
+                .build(),
+        )
+        .pool(
+            LootPoolBuilder::new()
+                .rolls_const(4)
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(BONE)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 8).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GUNPOWDER)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 8).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(ROTTEN_FLESH)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 8).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(STRING)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 8).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(SAND)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 8).as_function())
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// The chests of a desert pyramid are filled by the desert pyramid structure, the 4th surface structure (step 4), in
+/// the chunk the pyramid starts in. All its chests are in that chunk.
+#[inline(always)]
+pub const fn get_desert_temple_chest_seeds(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+) -> DecoratorChestSeeds {
+    DecoratorChestSeeds::new(world_seed, chunk_pos, DecoratorSalt::DesertPyramid)
+}
+
+/// Seed of the `chest_index`-th of the [`CHESTS`] chests. They are filled one after the other from the same random,
+/// each one after the `nextLong` of the previous ones: the chests of the piece's north, east, south then west side.
+#[inline(always)]
+pub const fn get_desert_temple_chest_loot_table_seed(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+) -> i64 {
+    get_desert_temple_chest_seeds(world_seed, chunk_pos).loot_table_seed(chest_index)
+}
+
+#[inline]
+pub fn get_desert_temple_chest(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+    luck: f32,
+) -> SingleChest {
+    let seed = get_desert_temple_chest_loot_table_seed(world_seed, chunk_pos, chest_index);
+
+    let mut chest = SingleChest::new();
+    get_loot_table().generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), luck);
+    chest
+}
+
+/// The [`CHESTS`] chests of the pyramid, in the order they are filled
+///
+/// ```
+/// use mcseedcracker::features::desert_temple::{get_desert_temple_chest, get_desert_temple_chests};
+///
+/// let chests = get_desert_temple_chests(-7193194438565520372, (12, -3), 0.0);
+/// assert_eq!(chests[2], get_desert_temple_chest(-7193194438565520372, (12, -3), 2, 0.0));
+/// ```
+pub fn get_desert_temple_chests(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    luck: f32,
+) -> [SingleChest; CHESTS] {
+    let loot_table = get_loot_table();
+    let mut chests: [SingleChest; CHESTS] = Default::default();
+    for (index, seed) in
+        get_desert_temple_chest_seeds(world_seed, chunk_pos).loot_table_seeds(0..CHESTS as i32)
+    {
+        loot_table.generate_in_inventory(
+            &mut chests[index as usize],
+            &mut JavaRandom::new(seed),
+            luck,
+        );
+    }
+    chests
+}
+
+#[inline]
+pub fn build_fast_inventory_compare_context(
+    contents: SingleChest,
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, ITEM_COUNT>, InvalidObservation> {
+    FastInventoryCompareContext::new(contents, loot_table)
+}
+
+/// Returns which chest of the pyramid generates the compared contents, trying every index allowed by `index`
+#[inline]
+pub fn compare_desert_temple_chest_fast_noinv(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    index: ChestIndex,
+    loot_table: &LootTable,
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, ITEM_COUNT>,
+) -> Option<i32> {
+    detect_chest_index(
+        &get_desert_temple_chest_seeds(world_seed, chunk_pos),
+        index,
+        loot_table,
+        luck,
+        compare,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::{
+        features::{
+            decorator_chest::ChestIndex,
+            desert_temple::{
+                CHESTS, build_fast_inventory_compare_context,
+                compare_desert_temple_chest_fast_noinv, get_desert_temple_chest,
+                get_desert_temple_chest_loot_table_seed, get_desert_temple_chests, get_loot_table,
+                item_registry, items,
+            },
+        },
+        loot_table::{Inventory, ItemProperty, SingleChest, from_datapack_json},
+        math::Math,
+        random::{JavaRandom, random_with_population_seed},
+        utils::enchants,
+    };
+
+    /// `chests/desert_pyramid` of the 1.16.5 data pack
+    const VANILLA_JSON: &str = r#"{"type": "minecraft:chest", "pools": [
+        {"rolls": {"min": 2.0, "max": 4.0}, "entries": [
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:diamond",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 3.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 15, "name": "minecraft:iron_ingot",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 5.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 15, "name": "minecraft:gold_ingot",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 2.0, "max": 7.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 15, "name": "minecraft:emerald",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 3.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 25, "name": "minecraft:bone",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 4.0, "max": 6.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 25, "name": "minecraft:spider_eye",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 3.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 25, "name": "minecraft:rotten_flesh",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 3.0, "max": 7.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 20, "name": "minecraft:saddle"},
+            {"type": "minecraft:item", "weight": 15, "name": "minecraft:iron_horse_armor"},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:golden_horse_armor"},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:diamond_horse_armor"},
+            {"type": "minecraft:item", "weight": 20, "name": "minecraft:book",
+                "functions": [{"function": "minecraft:enchant_randomly"}]},
+            {"type": "minecraft:item", "weight": 20, "name": "minecraft:golden_apple"},
+            {"type": "minecraft:item", "weight": 2, "name": "minecraft:enchanted_golden_apple"},
+            {"type": "minecraft:empty", "weight": 15}
+        ]},
+        {"rolls": 4.0, "entries": [
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:bone",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 8.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:gunpowder",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 8.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:rotten_flesh",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 8.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:string",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 8.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:sand",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 8.0, "type": "minecraft:uniform"}}]}
+        ]}
+    ]}"#;
+
+    fn item_counts(chest: &SingleChest) -> [i32; 18] {
+        let mut counts = [0i32; 18];
+        for i in 0..chest.slot_count() {
+            if let Some(stack) = chest.get_item(i) {
+                counts[stack.item] = counts[stack.item].wrapping_add(stack.count);
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_desert_temple_loot_table_seeds() {
+        let world_seed: i64 = -7193194438565520372;
+        let chunk = Math::block_coords_to_chunk_coords((-250, 1183));
+
+        // setFeatureSeed(decoration seed, 3, 4), then one nextLong per chest
+        let block_pos = Math::relative_chunk_coords(chunk, (0, 0));
+        let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
+        let mut rng = JavaRandom::new(population_seed.wrapping_add(3 + 40000));
+        for chest_index in 0..CHESTS as i32 {
+            assert_eq!(
+                get_desert_temple_chest_loot_table_seed(world_seed, chunk, chest_index),
+                rng.next_long()
+            );
+        }
+
+        let chests = get_desert_temple_chests(world_seed, chunk, 0.0);
+        for (chest_index, chest) in chests.iter().enumerate() {
+            assert_eq!(
+                *chest,
+                get_desert_temple_chest(world_seed, chunk, chest_index as i32, 0.0)
+            );
+        }
+        assert_ne!(chests[0], chests[1]);
+    }
+
+    #[test]
+    fn test_desert_temple_chest_contents() {
+        for world_seed in [-7193194438565520372, 734679766044180411] {
+            for z in -4..4 {
+                for chest in get_desert_temple_chests(world_seed, (z, 2 * z), 0.0) {
+                    let counts = item_counts(&chest);
+                    assert_eq!(counts[0], 0);
+
+                    // the second pool rolls 4 times 1 to 8 items, gunpowder, string and sand only come from it
+                    let second_pool =
+                        counts[items::GUNPOWDER] + counts[items::STRING] + counts[items::SAND];
+                    assert!(second_pool <= 32, "{:?}", counts);
+                    // at most 4 rolls of the first pool
+                    assert!(counts[items::DIAMOND] <= 12, "{:?}", counts);
+                    assert!(counts[items::ENCHANTED_BOOK] <= 4, "{:?}", counts);
+
+                    for i in 0..chest.slot_count() {
+                        let Some(stack) = chest.get_item(i) else {
+                            continue;
+                        };
+                        if stack.item != items::ENCHANTED_BOOK {
+                            assert!(stack.properties.is_empty());
+                            continue;
+                        }
+                        let [ItemProperty::Enchantment { enchantment, level }] =
+                            stack.properties[..]
+                        else {
+                            panic!("{:?}", stack);
+                        };
+                        let (_, min, max) = enchants::BOOK[enchantment as usize - 1];
+                        assert!((min..=max).contains(&level), "{:?}", stack);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_vanilla_json() {
+        let loaded = from_datapack_json(VANILLA_JSON.as_bytes(), &item_registry()).unwrap();
+        let loot_table = get_loot_table();
+        assert_eq!(loaded.possible_items(), loot_table.possible_items());
+
+        for seed in 0..200 {
+            let mut expected = SingleChest::new();
+            loot_table.generate_in_inventory(&mut expected, &mut JavaRandom::new(seed), 0.0);
+            let mut chest = SingleChest::new();
+            loaded.generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), 0.0);
+            assert_eq!(chest, expected, "loot table seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_compare_desert_temple_chest() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = (-20, 31);
+        let loot_table = get_loot_table();
+
+        for (chest_index, chest) in get_desert_temple_chests(world_seed, chunk, 0.0)
+            .into_iter()
+            .enumerate()
+        {
+            let compare = build_fast_inventory_compare_context(chest, &loot_table).unwrap();
+            assert_eq!(
+                compare_desert_temple_chest_fast_noinv(
+                    world_seed,
+                    chunk,
+                    ChestIndex::default(),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                Some(chest_index as i32)
+            );
+            assert_eq!(
+                compare_desert_temple_chest_fast_noinv(
+                    world_seed,
+                    (chunk.0, chunk.1 + 1),
+                    ChestIndex::Known(chest_index as i32),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                None
+            );
+        }
+    }
+}
@@ -1,11 +1,45 @@
-use std::{cmp::Ordering, f64::consts::PI};
+use std::{cmp::Ordering, error::Error, f64::consts::PI, fmt::Display};
+
+use cubiomes::enums::MCVersion;
 
 use crate::random::{JavaRandom, shuffle};
 
-#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct EndPillars(pub [EndPillar; 10]);
 
-#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+/// Height of the pillar of this index, from 76 for index 0 to 103 for index 9
+#[inline(always)]
+const fn pillar_height(index: i32) -> i32 {
+    76i32.wrapping_add(index.wrapping_mul(3))
+}
+
+/// The pillar seed of `world_seed` in `version`, `None` before 1.9 when the obsidian pillars were decorations of
+/// their chunks.
+///
+/// Since 1.9 the pillars are seeded with `new Random(worldSeed).nextLong() & 0xFFFF`. 1.13 moved it from
+/// `BiomeEndDecorator` to `SpikeFeature`, and 1.19 from `Random` to `RandomSource.create`, which is the same LCG. So
+/// every later version gives the seed of [`EndPillars::pillar_seed`], from the lower 48 bits of the world seed only:
+/// all the world seeds of a structure seed share their pillars.
+///
+/// ```
+/// use mcseedcracker::{
+///     cubiomes::enums::MCVersion,
+///     features::end_pillars::pillar_seed_from_world_seed,
+/// };
+///
+/// let seed = -7193194438565520372;
+/// assert_eq!(pillar_seed_from_world_seed(seed, MCVersion::MC_1_16_5), Some(13847));
+/// assert_eq!(pillar_seed_from_world_seed(seed, MCVersion::MC_1_8_9), None);
+/// ```
+#[inline]
+pub const fn pillar_seed_from_world_seed(world_seed: i64, version: MCVersion) -> Option<i64> {
+    if version as i32 <= MCVersion::MC_1_8_9 as i32 {
+        return None;
+    }
+    Some(EndPillars::pillar_seed(world_seed))
+}
+
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct EndPillar {
     pub index: i32,
     pub height: i32,
@@ -16,6 +50,11 @@ pub struct EndPillar {
 }
 
 impl EndPillars {
+    /// ```
+    /// use mcseedcracker::features::end_pillars::EndPillars;
+    ///
+    /// assert_eq!(EndPillars::pillar_seed(-7193194438565520372), 13847);
+    /// ```
     #[inline(always)]
     pub const fn pillar_seed(world_seed: i64) -> i64 {
         JavaRandom::new(world_seed).next_long() & 0xFFFF
@@ -45,6 +84,17 @@ impl EndPillars {
         self.0.iter_mut()
     }
 
+    /// ```
+    /// use mcseedcracker::features::end_pillars::EndPillars;
+    ///
+    /// let mut pillars = EndPillars::new();
+    /// pillars.from_seed(13847);
+    ///
+    /// let mut heights = pillars.iter().map(|p| p.height).collect::<Vec<_>>();
+    /// heights.sort();
+    /// assert_eq!(heights, [76, 79, 82, 85, 88, 91, 94, 97, 100, 103]);
+    /// assert_eq!(pillars.iter().filter(|p| p.caged).count(), 2);
+    /// ```
     #[inline]
     pub fn from_seed(&mut self, pillar_seed: i64) {
         let mut rng = JavaRandom::new(pillar_seed);
@@ -56,18 +106,248 @@ impl EndPillars {
             let index = indices[i];
 
             pillar.index = index;
-            pillar.height = 76 + 3 * index;
-            pillar.x = ((2.0 * (-PI + (PI / 10.0) * (i as f64))).cos() * 42.0).round() as i32;
-            pillar.z = ((2.0 * (-PI + (PI / 10.0) * (i as f64))).sin() * 42.0).round() as i32;
-            pillar.radius = 2 + index / 3;
+            pillar.height = pillar_height(index);
+            // floored like Mth.floor in SpikeFeature, pillar 5 is at z = -1
+            pillar.x = ((2.0 * (-PI + (PI / 10.0) * (i as f64))).cos() * 42.0).floor() as i32;
+            pillar.z = ((2.0 * (-PI + (PI / 10.0) * (i as f64))).sin() * 42.0).floor() as i32;
+            pillar.radius = (index / 3).wrapping_add(2);
             pillar.caged = index == 1 || index == 2;
         }
     }
+
+    /// The pillars in the order of their positions on the circle, to draw them
+    ///
+    /// ```
+    /// use mcseedcracker::features::end_pillars::EndPillars;
+    ///
+    /// let mut pillars = EndPillars::new();
+    /// pillars.from_seed(13847);
+    /// let layout = pillars.layout();
+    /// assert_eq!((layout[5].x, layout[5].z), (-42, -1));
+    /// assert_eq!(layout[5].angle, 180.0);
+    /// ```
+    pub fn layout(&self) -> [PillarInfo; 10] {
+        let mut layout = [PillarInfo::default(); 10];
+        for (position, (info, pillar)) in layout.iter_mut().zip(self.iter()).enumerate() {
+            *info = PillarInfo {
+                position,
+                angle: Self::angle(position),
+                x: pillar.x,
+                z: pillar.z,
+                radius: pillar.radius,
+                height: pillar.height,
+                caged: pillar.caged,
+            };
+        }
+        layout
+    }
+
+    /// Angle of pillar `index` around the origin, in degrees: `atan2(z, x)`, from +X (east) towards +Z (south). The
+    /// positions are the same in every world, only the heights are shuffled.
+    #[inline(always)]
+    pub const fn angle(index: usize) -> f64 {
+        36.0 * index as f64
+    }
+
+    /// Places the observed pillars on the circle and turns them into hints. An observation whose position fits
+    /// several pillars isn't guessed: a pillar gets a hint only when every way of placing the observations puts one
+    /// on it, the [`PillarHeightHint::Range`] of the heights of all the observations that can be there. The cage of
+    /// a pillar is set whenever its possible heights settle it, the caged pillars are at height 79 and 82.
+    ///
+    /// No pillar gets a hint when the observations can't be placed on distinct pillars.
+    ///
+    /// ```
+    /// use mcseedcracker::features::end_pillars::{
+    ///     EndPillars, PillarHeightHint, PillarObservation, PillarPosition,
+    /// };
+    ///
+    /// // between pillars 1 and 2, either one of them could be at height 88
+    /// let observed = EndPillars::identify_from_observations(&[PillarObservation {
+    ///     position: PillarPosition::Angle { degrees: 54.0, tolerance: 20.0 },
+    ///     height: PillarHeightHint::Exact(88),
+    ///     caged: None,
+    /// }]);
+    /// assert_eq!(observed.0[1].height, PillarHeightHint::Unknown);
+    ///
+    /// // two pillars side by side there: they are pillars 1 and 2, in some order
+    /// let observed = EndPillars::identify_from_observations(&[
+    ///     PillarObservation {
+    ///         position: PillarPosition::Angle { degrees: 50.0, tolerance: 25.0 },
+    ///         height: PillarHeightHint::Exact(88),
+    ///         caged: None,
+    ///     },
+    ///     PillarObservation {
+    ///         position: PillarPosition::Angle { degrees: 58.0, tolerance: 25.0 },
+    ///         height: PillarHeightHint::Range(94, 100),
+    ///         caged: None,
+    ///     },
+    /// ]);
+    /// for pillar in [1, 2] {
+    ///     assert_eq!(observed.0[pillar].height, PillarHeightHint::Range(88, 100));
+    ///     assert_eq!(observed.0[pillar].caged, Some(false));
+    /// }
+    /// ```
+    pub fn identify_from_observations(observations: &[PillarObservation]) -> PartialEndPillars {
+        let mut partial = PartialEndPillars::new();
+        let candidates = observations
+            .iter()
+            .map(|o| o.position.candidates())
+            .collect::<Vec<_>>();
+        if !has_assignment(&candidates, 0) {
+            return partial;
+        }
+
+        // bit i when some way of placing the observations puts the observation on pillar i
+        let placements = (0..candidates.len())
+            .map(|o| {
+                let others = candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != o)
+                    .map(|(_, c)| *c)
+                    .collect::<Vec<_>>();
+                (0..10)
+                    .filter(|i| candidates[o] & (1 << i) != 0 && has_assignment(&others, 1 << i))
+                    .fold(0u16, |mask, i| mask | (1 << i))
+            })
+            .collect::<Vec<_>>();
+        let heights = observations
+            .iter()
+            .map(|o| PartialEndPillar::new(o.caged, o.height).possible_heights())
+            .collect::<Vec<_>>();
+
+        for (i, pillar) in partial.iter_mut().enumerate() {
+            // some way of placing the observations leaves it unobserved
+            if has_assignment(&candidates, 1 << i) {
+                continue;
+            }
+            let observed = (0..observations.len())
+                .filter(|o| placements[*o] & (1 << i) != 0)
+                .collect::<Vec<_>>();
+            *pillar = match observed[..] {
+                [o] => PartialEndPillar::new(caged_of(heights[o]), observations[o].height),
+                _ => {
+                    let union = observed.iter().fold(0, |union, o| union | heights[*o]);
+                    let (lowest, highest) = (
+                        union.trailing_zeros(),
+                        15u32.wrapping_sub(union.leading_zeros()),
+                    );
+                    PartialEndPillar::new(
+                        caged_of(union),
+                        PillarHeightHint::Range(
+                            pillar_height(lowest as i32),
+                            pillar_height(highest as i32),
+                        ),
+                    )
+                }
+            };
+        }
+        partial
+    }
+}
+
+/// Whether the observations (bit i of a candidate mask for pillar i) can each be placed on their own pillar, without
+/// using the pillars of `excluded`
+fn has_assignment(candidates: &[u16], excluded: u16) -> bool {
+    // augmenting paths, owner[i] is the observation on pillar i
+    fn place(
+        o: usize,
+        candidates: &[u16],
+        excluded: u16,
+        owner: &mut [Option<usize>; 10],
+        visited: &mut u16,
+    ) -> bool {
+        for i in 0..10 {
+            if (candidates[o] & !excluded & !*visited) & (1 << i) == 0 {
+                continue;
+            }
+            *visited |= 1 << i;
+            let free = match owner[i] {
+                Some(other) => place(other, candidates, excluded, owner, visited),
+                None => true,
+            };
+            if free {
+                owner[i] = Some(o);
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut owner = [None; 10];
+    (0..candidates.len()).all(|o| place(o, candidates, excluded, &mut owner, &mut 0))
+}
+
+/// The cage of the pillars that can only have these heights (bit i for height 76 + 3 * i), None if both are possible
+fn caged_of(heights: u16) -> Option<bool> {
+    const CAGED: u16 = 0b110;
+    if heights & !CAGED == 0 {
+        Some(true)
+    } else if heights & CAGED == 0 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// A pillar as drawn from above, see [`EndPillars::layout`]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct PillarInfo {
+    /// Index of the pillar on the circle
+    pub position: usize,
+    /// In degrees, see [`EndPillars::angle`]
+    pub angle: f64,
+    /// Block coordinates of the center, 42 blocks from the origin
+    pub x: i32,
+    pub z: i32,
+    /// The obsidian spans `x - radius..=x + radius`
+    pub radius: i32,
+    pub height: i32,
+    pub caged: bool,
+}
+
+/// Where an observed pillar is on the circle of the 10 pillars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PillarPosition {
+    /// Pillar `i` of [`EndPillars`]
+    Index(usize),
+    /// Around [`EndPillars::angle`], give or take `tolerance` degrees. The nearest pillars always fit.
+    Angle { degrees: f64, tolerance: f64 },
+}
+
+impl PillarPosition {
+    /// The pillars at this position, bit i for pillar i
+    fn candidates(&self) -> u16 {
+        match *self {
+            Self::Index(index @ 0..10) => 1 << index,
+            Self::Index(_) => 0,
+            Self::Angle { degrees, tolerance } => {
+                let distance = |i: usize| {
+                    let d = (degrees - EndPillars::angle(i)).rem_euclid(360.0);
+                    d.min(360.0 - d)
+                };
+                let nearest = (0..10).map(distance).fold(f64::INFINITY, f64::min);
+                (0..10)
+                    .filter(|i| distance(*i) <= tolerance.max(0.0) || distance(*i) == nearest)
+                    .fold(0, |mask, i| mask | (1 << i))
+            }
+        }
+    }
+}
+
+/// One pillar seen from the end island, for [`EndPillars::identify_from_observations`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PillarObservation {
+    pub position: PillarPosition,
+    /// Usually a [`PillarHeightHint::Range`] measured on a screenshot
+    pub height: PillarHeightHint,
+    /// None if the top of the pillar wasn't seen
+    pub caged: Option<bool>,
 }
 
 /// Pillars height hints. There are 10 pillars, each having a unique height, from the following list: 76, 79, 82, 85, 88, 91, 94, 97, 100, 103. <br>
 /// Note: The two caged pillars are at height 79 and 82.
-#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum PillarHeightHint {
     /// A range of possible heights
     Range(i32, i32),
@@ -88,24 +368,81 @@ pub enum PillarHeightHint {
     Unknown,
 }
 
-#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct PartialEndPillar {
     pub caged: Option<bool>,
     pub height: PillarHeightHint,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PillarMatchResult {
     ImpossibleMatch,
     ExactMatch,
     // Wheight corresponds to a probability of a match
     PossibleMatch(f64),
+    /// The observed pillars can't all be right, no pillar seed matches them. See [`PartialEndPillars::validate`]
+    InvalidObservation(InvalidPillarObservation),
+}
+
+/// Observed pillars that no pillar layout can satisfy
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum InvalidPillarObservation {
+    /// Only 2 of the 10 pillars are caged
+    TooManyCaged { count: usize },
+    /// 8 of the 10 pillars are uncaged
+    TooManyUncaged { count: usize },
+    /// No height matches the hint of this pillar, given its cage. The caged pillars are at height 79 and 82.
+    NoPossibleHeight { pillar: usize },
+    /// These pillars (bit i for pillar i) have fewer possible heights (bit i for height 76 + 3 * i) than there are
+    /// pillars, but every pillar has its own height
+    Overconstrained { pillars: u16, heights: u16 },
+}
+
+impl Display for InvalidPillarObservation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |mask: u16, f: fn(i32) -> i32| {
+            (0..10)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| f(i).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::TooManyCaged { count } => write!(
+                f,
+                "{} pillars are marked caged, but only 2 pillars are caged",
+                count
+            ),
+            Self::TooManyUncaged { count } => write!(
+                f,
+                "{} pillars are marked uncaged, but 2 of the 10 pillars are caged",
+                count
+            ),
+            Self::NoPossibleHeight { pillar } => write!(
+                f,
+                "pillar {} can't have its height hint with its cage, the caged pillars are at height 79 and 82",
+                pillar
+            ),
+            Self::Overconstrained { pillars, heights } => write!(
+                f,
+                "pillars {} share the heights {}, but every pillar has its own height",
+                list(*pillars, |i| i),
+                list(*heights, pillar_height)
+            ),
+        }
+    }
 }
 
+impl Error for InvalidPillarObservation {}
+
 impl PillarMatchResult {
     #[inline(always)]
     pub const fn combine(self, other: Self) -> Self {
         match (self, other) {
+            (PillarMatchResult::InvalidObservation(e), _)
+            | (_, PillarMatchResult::InvalidObservation(e)) => {
+                PillarMatchResult::InvalidObservation(e)
+            }
             (PillarMatchResult::ImpossibleMatch, _) | (_, PillarMatchResult::ImpossibleMatch) => {
                 PillarMatchResult::ImpossibleMatch
             }
@@ -118,7 +455,9 @@ impl PillarMatchResult {
 
     #[inline(always)]
     pub const fn compare(&self, other: &Self) -> Ordering {
-        match (self, other) {
+        // an invalid observation sorts like an impossible match
+        let (this, other) = (self.or_impossible(), other.or_impossible());
+        match (&this, &other) {
             (PillarMatchResult::ImpossibleMatch, PillarMatchResult::ImpossibleMatch)
             | (PillarMatchResult::ExactMatch, PillarMatchResult::ExactMatch) => Ordering::Equal,
 
@@ -136,6 +475,16 @@ impl PillarMatchResult {
                     Ordering::Greater
                 }
             }
+            (PillarMatchResult::InvalidObservation(_), _)
+            | (_, PillarMatchResult::InvalidObservation(_)) => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    const fn or_impossible(&self) -> Self {
+        match self {
+            Self::InvalidObservation(_) => Self::ImpossibleMatch,
+            v => *v,
         }
     }
 
@@ -146,7 +495,15 @@ impl PillarMatchResult {
 
     #[inline(always)]
     pub fn is_impossible_match(&self) -> bool {
-        matches!(self, PillarMatchResult::ImpossibleMatch)
+        matches!(
+            self,
+            PillarMatchResult::ImpossibleMatch | PillarMatchResult::InvalidObservation(_)
+        )
+    }
+
+    #[inline(always)]
+    pub fn is_invalid_observation(&self) -> bool {
+        matches!(self, PillarMatchResult::InvalidObservation(_))
     }
 
     #[inline(always)]
@@ -157,7 +514,7 @@ impl PillarMatchResult {
     #[inline(always)]
     pub fn chance(&self) -> f64 {
         match self {
-            Self::ImpossibleMatch => 0.0,
+            Self::ImpossibleMatch | Self::InvalidObservation(_) => 0.0,
             Self::ExactMatch => 1.0,
             Self::PossibleMatch(w) => *w,
         }
@@ -205,7 +562,7 @@ impl PartialEndPillar {
                 if pillar.height < 97 {
                     return PillarMatchResult::ImpossibleMatch;
                 } else {
-                    let dist_from_min = pillar.height - 97; // no abs needed
+                    let dist_from_min = pillar.height.wrapping_sub(97); // no abs needed
                     let prob = (dist_from_min as f64) / (103.0 - 97.0);
                     PillarMatchResult::PossibleMatch((prob + 1.0) / 2.0) // [0.5-1]
                 }
@@ -256,9 +613,26 @@ impl PartialEndPillar {
 
         cage_match.combine(height_match)
     }
+
+    /// Heights (bit i for height 76 + 3 * i) matching the hint and the cage
+    fn possible_heights(&self) -> u16 {
+        let mut heights = 0;
+        for index in 0..10 {
+            let pillar = EndPillar {
+                index,
+                height: pillar_height(index),
+                caged: index == 1 || index == 2,
+                ..Default::default()
+            };
+            if !self.matches(&pillar).is_impossible_match() {
+                heights |= 1 << index;
+            }
+        }
+        heights
+    }
 }
 
-#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct PartialEndPillars(pub [PartialEndPillar; 10]);
 
 impl PartialEndPillars {
@@ -294,14 +668,582 @@ impl PartialEndPillars {
         result
     }
 
+    /// The match result of each pillar on its own, [`PartialEndPillars::matches`] combines them
+    #[inline]
+    pub fn match_details(&self, pillars: &EndPillars) -> [PillarMatchResult; 10] {
+        let mut details = [PillarMatchResult::ExactMatch; 10];
+        for ((detail, pillar), partial_pillar) in
+            details.iter_mut().zip(pillars.iter()).zip(self.iter())
+        {
+            *detail = partial_pillar.matches(pillar);
+        }
+        details
+    }
+
+    /// The pillar whose hint lowers the match result of `pillars` the most, none if every hint matches exactly
+    ///
+    /// ```
+    /// use mcseedcracker::features::end_pillars::{EndPillars, PartialEndPillars, PillarHeightHint};
+    ///
+    /// let mut real = EndPillars::new();
+    /// real.from_seed(13847);
+    ///
+    /// let mut observed = PartialEndPillars::new();
+    /// for (partial, pillar) in observed.iter_mut().zip(real.iter()) {
+    ///     partial.caged = Some(pillar.caged);
+    ///     partial.height = PillarHeightHint::Exact(pillar.height);
+    /// }
+    /// assert_eq!(observed.weakest_pillar(&real), None);
+    ///
+    /// observed.0[3].height = PillarHeightHint::Unknown;
+    /// assert_eq!(observed.weakest_pillar(&real).map(|(i, _)| i), Some(3));
+    /// ```
+    pub fn weakest_pillar(&self, pillars: &EndPillars) -> Option<(usize, PillarMatchResult)> {
+        self.match_details(pillars)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, result)| !result.is_exact_match())
+            .min_by(|a, b| a.1.compare(&b.1))
+    }
+
+    /// Heights (bit i for height 76 + 3 * i) matching the hint and the cage of every pillar
+    fn possible_heights(&self) -> [u16; 10] {
+        let mut heights = [0; 10];
+        for (partial, heights) in self.iter().zip(heights.iter_mut()) {
+            *heights = partial.possible_heights();
+        }
+        heights
+    }
+
+    /// Checks that some pillar layout satisfies every observed pillar at once: at most 2 caged and 8 uncaged
+    /// pillars, and enough distinct heights for every group of pillars. Pillar seeds can't match observations
+    /// failing this.
+    ///
+    /// ```
+    /// use mcseedcracker::features::end_pillars::{InvalidPillarObservation, PartialEndPillars};
+    ///
+    /// let mut observed = PartialEndPillars::new();
+    /// assert_eq!(observed.validate(), Ok(()));
+    ///
+    /// for pillar in observed.iter_mut().take(3) {
+    ///     pillar.caged = Some(true);
+    /// }
+    /// assert_eq!(observed.validate(), Err(InvalidPillarObservation::TooManyCaged { count: 3 }));
+    /// ```
+    pub fn validate(&self) -> Result<(), InvalidPillarObservation> {
+        let caged = self.iter().filter(|p| p.caged == Some(true)).count();
+        if caged > 2 {
+            return Err(InvalidPillarObservation::TooManyCaged { count: caged });
+        }
+        let uncaged = self.iter().filter(|p| p.caged == Some(false)).count();
+        if uncaged > 8 {
+            return Err(InvalidPillarObservation::TooManyUncaged { count: uncaged });
+        }
+
+        let heights = self.possible_heights();
+        if let Some(pillar) = heights.iter().position(|h| *h == 0) {
+            return Err(InvalidPillarObservation::NoPossibleHeight { pillar });
+        }
+        // every group of pillars needs at least as many possible heights as pillars (Hall's marriage theorem), the
+        // smallest group failing this is reported
+        let mut smallest: Option<(u16, u16)> = None;
+        for pillars in 1u16..1 << 10 {
+            let union = (0..10)
+                .filter(|i| pillars & (1 << i) != 0)
+                .fold(0, |union, i| union | heights[i]);
+            let too_small = union.count_ones() < pillars.count_ones();
+            if too_small && smallest.is_none_or(|(p, _)| pillars.count_ones() < p.count_ones()) {
+                smallest = Some((pillars, union));
+            }
+        }
+        match smallest {
+            Some((pillars, heights)) => {
+                Err(InvalidPillarObservation::Overconstrained { pillars, heights })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Lazily computes the match result of every one of the 65536 pillar seeds, in ascending seed order. Every seed
+    /// is an [`PillarMatchResult::InvalidObservation`] if the observations don't pass [`PartialEndPillars::validate`].
+    #[inline]
+    pub fn seed_results_iter(&self) -> impl Iterator<Item = (i64, PillarMatchResult)> {
+        let invalid = self.validate().err();
+        let mut pillars = EndPillars::new();
+        (0..65536).map(move |pillar_seed| match invalid {
+            Some(e) => (pillar_seed, PillarMatchResult::InvalidObservation(e)),
+            None => {
+                pillars.from_seed(pillar_seed);
+                (pillar_seed, self.matches(&pillars))
+            }
+        })
+    }
+
+    /// Calls `f` for every pillar seed that is not an impossible match, in ascending seed order
+    ///
+    /// ```
+    /// use mcseedcracker::features::end_pillars::{EndPillars, PartialEndPillars, PillarHeightHint};
+    ///
+    /// let mut real = EndPillars::new();
+    /// real.from_seed(13847);
+    ///
+    /// let mut observed = PartialEndPillars::new();
+    /// for (partial, pillar) in observed.iter_mut().zip(real.iter()) {
+    ///     partial.caged = Some(pillar.caged);
+    ///     partial.height = PillarHeightHint::Exact(pillar.height);
+    /// }
+    ///
+    /// let mut seeds = Vec::new();
+    /// observed.for_each_matching(|seed, _| seeds.push(seed));
+    /// assert!(seeds.contains(&13847));
+    /// ```
+    #[inline]
+    pub fn for_each_matching(&self, mut f: impl FnMut(i64, PillarMatchResult)) {
+        for (pillar_seed, result) in self.seed_results_iter() {
+            if !result.is_impossible_match() {
+                f(pillar_seed, result);
+            }
+        }
+    }
+
     #[inline]
     pub fn seed_results(&self) -> Vec<(i64, PillarMatchResult)> {
+        self.seed_results_iter().collect()
+    }
+
+    /// [`PartialEndPillars::seed_results_iter`] with the [`PartialEndPillars::match_details`] of every seed
+    #[inline]
+    pub fn seed_details_iter(
+        &self,
+    ) -> impl Iterator<Item = (i64, PillarMatchResult, [PillarMatchResult; 10])> {
         let mut pillars = EndPillars::new();
-        let mut results = Vec::new();
-        for pillar_seed in 0..65536 {
+        self.seed_results_iter().map(move |(pillar_seed, result)| {
             pillars.from_seed(pillar_seed);
-            results.push((pillar_seed, self.matches(&pillars)));
+            (pillar_seed, result, self.match_details(&pillars))
+        })
+    }
+
+    #[inline]
+    pub fn seed_details(&self) -> Vec<(i64, PillarMatchResult, [PillarMatchResult; 10])> {
+        self.seed_details_iter().collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> Vec<PartialEndPillars> {
+        let mut fixtures = vec![PartialEndPillars::new()];
+
+        for seed in [13847, 57809, 28025, 4002] {
+            let mut real_pillars = EndPillars::new();
+            real_pillars.from_seed(seed);
+
+            let mut exact = PartialEndPillars::new();
+            let mut fuzzy = PartialEndPillars::new();
+            for ((exact, fuzzy), pillar) in exact
+                .iter_mut()
+                .zip(fuzzy.iter_mut())
+                .zip(real_pillars.iter())
+            {
+                exact.caged = Some(pillar.caged);
+                exact.height = PillarHeightHint::Exact(pillar.height);
+
+                fuzzy.height = match pillar.height {
+                    97.. => PillarHeightHint::Big,
+                    85..=96 => PillarHeightHint::Medium,
+                    _ => PillarHeightHint::Small,
+                };
+            }
+            fixtures.push(exact);
+            fixtures.push(fuzzy);
+        }
+
+        fixtures
+    }
+
+    #[test]
+    fn test_seed_results_iter_matches_vec() {
+        for pillars in fixtures() {
+            let vec = pillars.seed_results();
+            assert_eq!(vec.len(), 65536);
+
+            let mut count = 0;
+            for (a, b) in vec.iter().zip(pillars.seed_results_iter()) {
+                assert_eq!(a.0, b.0);
+                assert_eq!(a.1.compare(&b.1), Ordering::Equal);
+                assert_eq!(a.1.chance(), b.1.chance());
+                count += 1;
+            }
+            assert_eq!(count, 65536);
+
+            let mut matching = Vec::new();
+            pillars.for_each_matching(|seed, result| matching.push((seed, result)));
+            let expected = vec
+                .iter()
+                .filter(|(_, r)| !r.is_impossible_match())
+                .collect::<Vec<_>>();
+            assert_eq!(matching.len(), expected.len());
+            for (a, b) in matching.iter().zip(expected) {
+                assert_eq!(a.0, b.0);
+                assert_eq!(a.1.chance(), b.1.chance());
+            }
         }
-        results
+    }
+
+    #[test]
+    fn test_for_each_matching_exact() {
+        let mut real_pillars = EndPillars::new();
+        real_pillars.from_seed(13847);
+
+        let mut partial = PartialEndPillars::new();
+        for (ppillar, pillar) in partial.iter_mut().zip(real_pillars.iter()) {
+            ppillar.caged = Some(pillar.caged);
+            ppillar.height = PillarHeightHint::Exact(pillar.height);
+        }
+
+        let mut found = Vec::new();
+        partial.for_each_matching(|seed, result| {
+            assert!(result.is_exact_match());
+            found.push(seed);
+        });
+        assert!(found.contains(&13847));
+    }
+
+    #[test]
+    fn test_validate_cages() {
+        let mut partial = PartialEndPillars::new();
+        for pillar in partial.iter_mut().take(3) {
+            pillar.caged = Some(true);
+        }
+        assert_eq!(
+            partial.validate(),
+            Err(InvalidPillarObservation::TooManyCaged { count: 3 })
+        );
+
+        let mut partial = PartialEndPillars::new();
+        for pillar in partial.iter_mut().take(9) {
+            pillar.caged = Some(false);
+        }
+        assert_eq!(
+            partial.validate(),
+            Err(InvalidPillarObservation::TooManyUncaged { count: 9 })
+        );
+
+        // no seed is generated for an invalid observation
+        assert!(
+            partial
+                .seed_results_iter()
+                .all(|(_, r)| r.is_invalid_observation() && r.is_impossible_match())
+        );
+        let mut count = 0;
+        partial.for_each_matching(|_, _| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_validate_heights() {
+        let mut partial = PartialEndPillars::new();
+        partial.0[4] = PartialEndPillar::new(Some(true), PillarHeightHint::Exact(103));
+        assert_eq!(
+            partial.validate(),
+            Err(InvalidPillarObservation::NoPossibleHeight { pillar: 4 })
+        );
+        partial.0[4] = PartialEndPillar::new(None, PillarHeightHint::Range(90, 80));
+        assert_eq!(
+            partial.validate(),
+            Err(InvalidPillarObservation::NoPossibleHeight { pillar: 4 })
+        );
+
+        let mut partial = PartialEndPillars::new();
+        for i in [2, 5, 7] {
+            partial.0[i].height = PillarHeightHint::Exact(103);
+        }
+        let error = partial.validate().unwrap_err();
+        assert_eq!(
+            error,
+            InvalidPillarObservation::Overconstrained {
+                pillars: 0b100100,
+                heights: 1 << 9
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "pillars 2, 5 share the heights 103, but every pillar has its own height"
+        );
+        assert!(
+            PillarMatchResult::ExactMatch
+                .combine(PillarMatchResult::InvalidObservation(error))
+                .is_invalid_observation()
+        );
+
+        // four small pillars, but only three small heights
+        let mut partial = PartialEndPillars::new();
+        for pillar in partial.iter_mut().take(4) {
+            pillar.height = PillarHeightHint::Small;
+        }
+        assert_eq!(
+            partial.validate(),
+            Err(InvalidPillarObservation::Overconstrained {
+                pillars: 0b1111,
+                heights: 0b111
+            })
+        );
+        // a third caged pillar among uncaged ones can't be at 79 or 82
+        let mut partial = PartialEndPillars::new();
+        partial.0[0] = PartialEndPillar::new(Some(true), PillarHeightHint::Unknown);
+        partial.0[1] = PartialEndPillar::new(None, PillarHeightHint::Exact(79));
+        partial.0[2] = PartialEndPillar::new(None, PillarHeightHint::Exact(82));
+        assert!(matches!(
+            partial.validate(),
+            Err(InvalidPillarObservation::Overconstrained { .. })
+        ));
+    }
+
+    #[test]
+    fn test_match_details() {
+        let mut real = EndPillars::new();
+        real.from_seed(13847);
+        let wrong = real.iter().position(|p| p.height == 100).unwrap();
+
+        let mut partial = PartialEndPillars::new();
+        partial.0[wrong].height = PillarHeightHint::Exact(103);
+        let details = partial.match_details(&real);
+        for (i, detail) in details.iter().enumerate() {
+            assert_eq!(detail.is_impossible_match(), i == wrong, "pillar {}", i);
+        }
+        assert!(partial.matches(&real).is_impossible_match());
+        assert_eq!(
+            partial.weakest_pillar(&real),
+            Some((wrong, PillarMatchResult::ImpossibleMatch))
+        );
+
+        partial.0[wrong].height = PillarHeightHint::Exact(100);
+        assert_eq!(partial.match_details(&real)[wrong].chance(), 1.0);
+    }
+
+    #[test]
+    fn test_seed_details_match_results() {
+        for pillars in fixtures() {
+            for ((seed, result), (detail_seed, detail_result, details)) in
+                pillars.seed_results_iter().zip(pillars.seed_details_iter())
+            {
+                assert_eq!(seed, detail_seed);
+                assert_eq!(result.chance(), detail_result.chance());
+                let combined = details
+                    .iter()
+                    .fold(PillarMatchResult::ExactMatch, |a, b| a.combine(*b));
+                assert_eq!(combined.chance(), result.chance());
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_observations_unaffected() {
+        for pillars in fixtures() {
+            assert_eq!(pillars.validate(), Ok(()));
+            assert!(
+                pillars
+                    .seed_results_iter()
+                    .all(|(_, r)| !r.is_invalid_observation())
+            );
+        }
+    }
+
+    #[test]
+    fn test_pillar_seed_versions() {
+        let (world_seed, structure_seed) = (-7193194438565520372, 180066252004364);
+        for version in [
+            MCVersion::MC_1_12_2,
+            MCVersion::MC_1_16_5,
+            MCVersion::MC_1_18_2,
+            MCVersion::MC_1_20_6,
+        ] {
+            assert_eq!(
+                pillar_seed_from_world_seed(world_seed, version),
+                Some(13847)
+            );
+            // the upper 16 bits don't reach the pillars
+            for upper in [0, 1, 39979, 65535] {
+                assert_eq!(
+                    pillar_seed_from_world_seed((upper << 48) | structure_seed, version),
+                    Some(13847)
+                );
+            }
+        }
+        assert_eq!(
+            pillar_seed_from_world_seed(world_seed, MCVersion::MC_1_8_9),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pillar_angles() {
+        let mut pillars = EndPillars::new();
+        pillars.from_seed(13847);
+        for (i, pillar) in pillars.iter().enumerate() {
+            let angle = (pillar.z as f64).atan2(pillar.x as f64).to_degrees();
+            let distance = (angle - EndPillars::angle(i)).rem_euclid(360.0);
+            // the centers are floored, up to a block from the circle
+            assert!(distance.min(360.0 - distance) < 2.0, "pillar {}", i);
+        }
+    }
+
+    #[test]
+    fn test_pillar_layout() {
+        let mut pillars = EndPillars::new();
+        pillars.from_seed(13847);
+        let layout = pillars.layout();
+
+        // the spike centers of every world
+        let centers = layout.map(|p| (p.x, p.z));
+        assert_eq!(
+            centers,
+            [
+                (42, 0),
+                (33, 24),
+                (12, 39),
+                (-13, 39),
+                (-34, 24),
+                (-42, -1),
+                (-34, -25),
+                (-13, -40),
+                (12, -40),
+                (33, -25),
+            ]
+        );
+        assert_eq!(
+            layout.map(|p| p.height),
+            [103, 94, 85, 76, 82, 88, 79, 100, 97, 91]
+        );
+        assert_eq!(layout.map(|p| p.radius), [5, 4, 3, 2, 2, 3, 2, 4, 4, 3]);
+        let caged = layout.iter().filter(|p| p.caged).map(|p| p.position);
+        assert_eq!(caged.collect::<Vec<_>>(), [4, 6]);
+        for (position, (info, pillar)) in layout.iter().zip(pillars.iter()).enumerate() {
+            assert_eq!(info.position, position);
+            assert_eq!(info.angle, EndPillars::angle(position));
+            assert_eq!((info.height, info.caged), (pillar.height, pillar.caged));
+        }
+    }
+
+    #[test]
+    fn test_identify_from_observations() {
+        let mut real = EndPillars::new();
+        real.from_seed(13847);
+        let bracket = |i: usize| {
+            let height = real.0[i].height;
+            PillarHeightHint::Range(height - 2, height + 2)
+        };
+        let observations = [
+            // the pillars near the exit portal, the cages are easy to tell apart
+            PillarObservation {
+                position: PillarPosition::Index(0),
+                height: bracket(0),
+                caged: Some(real.0[0].caged),
+            },
+            PillarObservation {
+                position: PillarPosition::Index(1),
+                height: bracket(1),
+                caged: Some(real.0[1].caged),
+            },
+            PillarObservation {
+                position: PillarPosition::Index(3),
+                height: bracket(3),
+                caged: Some(real.0[3].caged),
+            },
+            // two pillars behind each other in the distance, seen between pillars 5 and 6
+            PillarObservation {
+                position: PillarPosition::Angle {
+                    degrees: 196.0,
+                    tolerance: 25.0,
+                },
+                height: bracket(5),
+                caged: None,
+            },
+            PillarObservation {
+                position: PillarPosition::Angle {
+                    degrees: 200.0,
+                    tolerance: 25.0,
+                },
+                height: bracket(6),
+                caged: None,
+            },
+            PillarObservation {
+                position: PillarPosition::Angle {
+                    degrees: 245.0,
+                    tolerance: 10.0,
+                },
+                height: bracket(7),
+                caged: None,
+            },
+            PillarObservation {
+                position: PillarPosition::Angle {
+                    degrees: 290.0,
+                    tolerance: 10.0,
+                },
+                height: bracket(8),
+                caged: None,
+            },
+            PillarObservation {
+                position: PillarPosition::Angle {
+                    degrees: 330.0,
+                    tolerance: 10.0,
+                },
+                height: bracket(9),
+                caged: None,
+            },
+        ];
+        let partial = EndPillars::identify_from_observations(&observations);
+        assert_eq!(partial.validate(), Ok(()));
+        for i in [2, 4] {
+            assert_eq!(partial.0[i], PartialEndPillar::default(), "pillar {}", i);
+        }
+        assert_eq!(partial.0[8].height, bracket(8));
+        assert_eq!(partial.0[8].caged, Some(real.0[8].caged));
+        // pillars 5 and 6 get the heights of both
+        let (low, high) = match (bracket(5), bracket(6)) {
+            (PillarHeightHint::Range(a, b), PillarHeightHint::Range(c, d)) => {
+                (a.min(c) + 2, b.max(d) - 2)
+            }
+            _ => unreachable!(),
+        };
+        for i in [5, 6] {
+            assert_eq!(partial.0[i].height, PillarHeightHint::Range(low, high));
+        }
+        assert_eq!(partial.matches(&real).chance(), 1.0);
+
+        let mut seeds = Vec::new();
+        partial.for_each_matching(|seed, _| seeds.push(seed));
+        // 15531 puts heights 85 and 82 on pillars 5 and 6, within the range of both
+        assert_eq!(seeds, [13847, 15531]);
+    }
+
+    #[test]
+    fn test_identify_unplaceable() {
+        let at = |index| PillarObservation {
+            position: PillarPosition::Index(index),
+            height: PillarHeightHint::Exact(103),
+            caged: None,
+        };
+        assert_eq!(
+            EndPillars::identify_from_observations(&[at(3), at(3)]),
+            PartialEndPillars::new()
+        );
+        assert_eq!(
+            EndPillars::identify_from_observations(&[at(10)]),
+            PartialEndPillars::new()
+        );
+        // the nearest pillar fits whatever the tolerance
+        let observed = EndPillars::identify_from_observations(&[PillarObservation {
+            position: PillarPosition::Angle {
+                degrees: -40.0,
+                tolerance: 0.0,
+            },
+            ..at(0)
+        }]);
+        assert_eq!(observed.0[9].height, PillarHeightHint::Exact(103));
+        assert_eq!(observed.0[9].caged, Some(false));
     }
 }
@@ -0,0 +1,427 @@
+use crate::{
+    features::{
+        bastion::nether_structure_start,
+        decorator_chest::{ChestIndex, DecoratorChestSeeds, detect_chest_index},
+    },
+    loot_table::{
+        FastInventoryCompareContext, InvalidObservation, ItemLootPoolEntryBuilder, ItemRegistry,
+        LootPoolBuilder, LootTable, LootTableBuilder, RegisteredItem, SetCountFunction,
+        SingleChest,
+    },
+    random::{DecoratorSalt, JavaRandom},
+};
+
+pub mod items {
+    pub const DIAMOND: usize = 1;
+    pub const IRON_INGOT: usize = 2;
+    pub const GOLD_INGOT: usize = 3;
+    pub const GOLDEN_SWORD: usize = 4;
+    pub const GOLDEN_CHESTPLATE: usize = 5;
+    pub const FLINT_AND_STEEL: usize = 6;
+    pub const NETHER_WART: usize = 7;
+    pub const SADDLE: usize = 8;
+    pub const GOLDEN_HORSE_ARMOR: usize = 9;
+    pub const IRON_HORSE_ARMOR: usize = 10;
+    pub const DIAMOND_HORSE_ARMOR: usize = 11;
+    pub const OBSIDIAN: usize = 12;
+}
+
+/// Larger than every item id of the nether bridge loot table
+pub const ITEM_COUNT: usize = 13;
+
+/// The items of the nether bridge loot table, for [`crate::loot_table::from_datapack_json`]
+pub fn item_registry() -> ItemRegistry {
+    ItemRegistry::new()
+        .register("diamond", RegisteredItem::new(items::DIAMOND))
+        .register("iron_ingot", RegisteredItem::new(items::IRON_INGOT))
+        .register("gold_ingot", RegisteredItem::new(items::GOLD_INGOT))
+        .register(
+            "golden_sword",
+            RegisteredItem::new(items::GOLDEN_SWORD).stack_size(1),
+        )
+        .register(
+            "golden_chestplate",
+            RegisteredItem::new(items::GOLDEN_CHESTPLATE).stack_size(1),
+        )
+        .register(
+            "flint_and_steel",
+            RegisteredItem::new(items::FLINT_AND_STEEL).stack_size(1),
+        )
+        .register("nether_wart", RegisteredItem::new(items::NETHER_WART))
+        .register("saddle", RegisteredItem::new(items::SADDLE).stack_size(1))
+        .register(
+            "golden_horse_armor",
+            RegisteredItem::new(items::GOLDEN_HORSE_ARMOR).stack_size(1),
+        )
+        .register(
+            "iron_horse_armor",
+            RegisteredItem::new(items::IRON_HORSE_ARMOR).stack_size(1),
+        )
+        .register(
+            "diamond_horse_armor",
+            RegisteredItem::new(items::DIAMOND_HORSE_ARMOR).stack_size(1),
+        )
+        .register("obsidian", RegisteredItem::new(items::OBSIDIAN))
+}
+
+/// `chests/nether_bridge` of 1.16.5
+#[inline]
+pub fn get_loot_table() -> LootTable {
+    use crate::features::fortress::items::*;
+
+    LootTableBuilder::new()
+        .pool(
+            LootPoolBuilder::new()
+                .rolls_uniform(2, 4)
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(DIAMOND)
+                        .weight(5)
+                        .function(SetCountFunction::uniform(1, 3).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_INGOT)
+                        .weight(5)
+                        .function(SetCountFunction::uniform(1, 5).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLD_INGOT)
+                        .weight(15)
+                        .function(SetCountFunction::uniform(1, 3).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLDEN_SWORD)
+                        .item_stack_size(1)
+                        .weight(5)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLDEN_CHESTPLATE)
+                        .item_stack_size(1)
+                        .weight(5)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(FLINT_AND_STEEL)
+                        .item_stack_size(1)
+                        .weight(5)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(NETHER_WART)
+                        .weight(5)
+                        .function(SetCountFunction::uniform(3, 7).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(SADDLE)
+                        .item_stack_size(1)
+                        .weight(10)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLDEN_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(8)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(5)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(DIAMOND_HORSE_ARMOR)
+                        .item_stack_size(1)
+                        .weight(3)
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(OBSIDIAN)
+                        .weight(2)
+                        .function(SetCountFunction::uniform(2, 4).as_function())
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// The chunk the fortress of a region starts in, None for the 3 in 5 regions that get a bastion remnant instead, see
+/// [`crate::features::bastion::get_bastion_start`]
+#[inline(always)]
+pub const fn get_fortress_start(structure_seed: i64, region: (i32, i32)) -> Option<(i32, i32)> {
+    match nether_structure_start(structure_seed, region) {
+        (start, roll) if roll < 2 => Some(start),
+        _ => None,
+    }
+}
+
+/// The chests of a fortress are filled by the nether fortress structure, the 1st underground decoration (step 7), in
+/// the chunks of its corridors
+#[inline(always)]
+pub const fn get_fortress_chest_seeds(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+) -> DecoratorChestSeeds {
+    DecoratorChestSeeds::new(world_seed, chunk_pos, DecoratorSalt::NetherFortress)
+}
+
+/// Seed of the `chest_index`-th chest filled in the chunk. Two corridors rarely share a chunk, so it is almost
+/// always 0.
+///
+/// ```
+/// use mcseedcracker::features::fortress::get_fortress_chest_loot_table_seed;
+///
+/// assert_eq!(
+///     get_fortress_chest_loot_table_seed(734679766044180411, (43, 5), 0),
+///     -2360847405527775405
+/// );
+/// ```
+#[inline(always)]
+pub const fn get_fortress_chest_loot_table_seed(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+) -> i64 {
+    get_fortress_chest_seeds(world_seed, chunk_pos).loot_table_seed(chest_index)
+}
+
+#[inline]
+pub fn get_fortress_chest(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+    luck: f32,
+) -> SingleChest {
+    let seed = get_fortress_chest_loot_table_seed(world_seed, chunk_pos, chest_index);
+
+    let mut chest = SingleChest::new();
+    get_loot_table().generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), luck);
+    chest
+}
+
+#[inline]
+pub fn build_fast_inventory_compare_context(
+    contents: SingleChest,
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, ITEM_COUNT>, InvalidObservation> {
+    FastInventoryCompareContext::new(contents, loot_table)
+}
+
+/// Returns which chest of the chunk generates the compared contents, trying every index allowed by `index`
+#[inline]
+pub fn compare_fortress_chest_fast_noinv(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    index: ChestIndex,
+    loot_table: &LootTable,
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, ITEM_COUNT>,
+) -> Option<i32> {
+    detect_chest_index(
+        &get_fortress_chest_seeds(world_seed, chunk_pos),
+        index,
+        loot_table,
+        luck,
+        compare,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::{
+        features::{
+            bastion::get_bastion_start,
+            decorator_chest::ChestIndex,
+            fortress::{
+                build_fast_inventory_compare_context, compare_fortress_chest_fast_noinv,
+                get_fortress_chest, get_fortress_chest_loot_table_seed, get_fortress_start,
+                get_loot_table, item_registry, items,
+            },
+        },
+        loot_table::{Inventory, ItemStack, SingleChest, from_datapack_json},
+        math::Math,
+        random::{JavaRandom, random_with_population_seed},
+    };
+
+    /// `chests/nether_bridge` of the 1.16.5 data pack
+    const VANILLA_JSON: &str = r#"{"type": "minecraft:chest", "pools": [
+        {"rolls": {"min": 2.0, "max": 4.0, "type": "minecraft:uniform"}, "entries": [
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:diamond",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 3.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:iron_ingot",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 5.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 15, "name": "minecraft:gold_ingot",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 1.0, "max": 3.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:golden_sword"},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:golden_chestplate"},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:flint_and_steel"},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:nether_wart",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 3.0, "max": 7.0, "type": "minecraft:uniform"}}]},
+            {"type": "minecraft:item", "weight": 10, "name": "minecraft:saddle"},
+            {"type": "minecraft:item", "weight": 8, "name": "minecraft:golden_horse_armor"},
+            {"type": "minecraft:item", "weight": 5, "name": "minecraft:iron_horse_armor"},
+            {"type": "minecraft:item", "weight": 3, "name": "minecraft:diamond_horse_armor"},
+            {"type": "minecraft:item", "weight": 2, "name": "minecraft:obsidian",
+                "functions": [{"function": "minecraft:set_count", "count": {"min": 2.0, "max": 4.0, "type": "minecraft:uniform"}}]}
+        ]}
+    ]}"#;
+
+    /// `(slot, item, count, stack size)`
+    fn chest_of(slots: &[(i32, usize, i32, i32)]) -> SingleChest {
+        let mut chest = SingleChest::new();
+        for &(slot, item, count, max_count) in slots {
+            chest.set_item(slot, Some(ItemStack::new(item, count, max_count)));
+        }
+        chest
+    }
+
+    #[test]
+    fn test_fortress_loot_table_seeds() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = (43, 5);
+
+        // setFeatureSeed(decoration seed, 0, 7), then one nextLong per chest filled in the chunk
+        let block_pos = Math::relative_chunk_coords(chunk, (0, 0));
+        let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
+        let mut rng = JavaRandom::new(population_seed.wrapping_add(70000));
+        for chest_index in 0..3 {
+            assert_eq!(
+                get_fortress_chest_loot_table_seed(world_seed, chunk, chest_index),
+                rng.next_long()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fortress_start() {
+        // computed with the reference implementation of tools/fixtures
+        let starts = [
+            (734679766044180411, (1, 0), (43, 5)),
+            (734679766044180411, (-1, -1), (-18, -10)),
+            (734679766044180411, (1, 1), (40, 32)),
+            (-7193194438565520372, (0, -1), (5, -17)),
+            (-7193194438565520372, (1, 1), (38, 39)),
+        ];
+        for (world_seed, region, start) in starts {
+            assert_eq!(get_fortress_start(world_seed, region), Some(start));
+            assert_eq!(get_bastion_start(world_seed, region), None);
+        }
+        // a bastion region
+        assert_eq!(get_fortress_start(734679766044180411, (0, 0)), None);
+    }
+
+    #[test]
+    fn test_fortress_chest1() {
+        use crate::features::fortress::items::{
+            DIAMOND, FLINT_AND_STEEL, GOLD_INGOT, GOLDEN_CHESTPLATE, GOLDEN_HORSE_ARMOR,
+            IRON_HORSE_ARMOR,
+        };
+
+        // the reference implementation of tools/fixtures, filled like LootTable.fill
+        let expected = [
+            chest_of(&[
+                (5, GOLD_INGOT, 1, 64),
+                (12, GOLDEN_CHESTPLATE, 1, 1),
+                (15, FLINT_AND_STEEL, 1, 1),
+                (18, GOLD_INGOT, 1, 64),
+                (20, IRON_HORSE_ARMOR, 1, 1),
+                (26, GOLD_INGOT, 1, 64),
+            ]),
+            chest_of(&[
+                (1, DIAMOND, 1, 64),
+                (2, DIAMOND, 1, 64),
+                (20, DIAMOND, 1, 64),
+                (21, FLINT_AND_STEEL, 1, 1),
+                (24, GOLDEN_HORSE_ARMOR, 1, 1),
+                (25, GOLD_INGOT, 1, 64),
+            ]),
+        ];
+        for (chest_index, expected) in expected.iter().enumerate() {
+            let generated =
+                get_fortress_chest(734679766044180411, (43, 5), chest_index as i32, 0.0);
+            assert_eq!(generated, *expected, "chest {}", chest_index);
+        }
+    }
+
+    #[test]
+    fn test_fortress_chest2() {
+        use crate::features::fortress::items::{
+            GOLDEN_HORSE_ARMOR, GOLDEN_SWORD, IRON_HORSE_ARMOR, OBSIDIAN,
+        };
+
+        let expected = [
+            chest_of(&[(2, GOLDEN_HORSE_ARMOR, 1, 1), (16, GOLDEN_SWORD, 1, 1)]),
+            chest_of(&[
+                (9, OBSIDIAN, 2, 64),
+                (19, OBSIDIAN, 1, 64),
+                (21, OBSIDIAN, 1, 64),
+                (26, IRON_HORSE_ARMOR, 1, 1),
+            ]),
+        ];
+        assert_eq!(
+            get_fortress_chest_loot_table_seed(-7193194438565520372, (5, -17), 0),
+            6364092966904291395
+        );
+        for (chest_index, expected) in expected.iter().enumerate() {
+            let generated =
+                get_fortress_chest(-7193194438565520372, (5, -17), chest_index as i32, 0.0);
+            assert_eq!(generated, *expected, "chest {}", chest_index);
+        }
+    }
+
+    #[test]
+    fn test_vanilla_json() {
+        let loaded = from_datapack_json(VANILLA_JSON.as_bytes(), &item_registry()).unwrap();
+        let loot_table = get_loot_table();
+        assert_eq!(loaded.possible_items(), loot_table.possible_items());
+        assert!(loot_table.possible_items().contains(&items::OBSIDIAN));
+
+        for seed in 0..200 {
+            let mut expected = SingleChest::new();
+            loot_table.generate_in_inventory(&mut expected, &mut JavaRandom::new(seed), 0.0);
+            let mut chest = SingleChest::new();
+            loaded.generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), 0.0);
+            assert_eq!(chest, expected, "loot table seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_compare_fortress_chest() {
+        let world_seed: i64 = -7193194438565520372;
+        let chunk = (5, -17);
+        let loot_table = get_loot_table();
+
+        for chest_index in 0..3 {
+            let chest = get_fortress_chest(world_seed, chunk, chest_index, 0.0);
+            let compare = build_fast_inventory_compare_context(chest, &loot_table).unwrap();
+            assert_eq!(
+                compare_fortress_chest_fast_noinv(
+                    world_seed,
+                    chunk,
+                    ChestIndex::default(),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                Some(chest_index)
+            );
+            assert_eq!(
+                compare_fortress_chest_fast_noinv(
+                    world_seed,
+                    (chunk.0 + 1, chunk.1),
+                    ChestIndex::Known(chest_index),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                None
+            );
+        }
+    }
+}
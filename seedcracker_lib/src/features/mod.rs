@@ -1,3 +1,11 @@
+#![deny(arithmetic_overflow, clippy::arithmetic_side_effects)]
+
 pub mod bastion;
 pub mod buried_treasure;
+pub mod decoration;
+pub mod decorator_chest;
+pub mod desert_temple;
 pub mod end_pillars;
+pub mod fortress;
+pub mod monument;
+pub mod shipwreck;
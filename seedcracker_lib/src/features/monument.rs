@@ -0,0 +1,58 @@
+use crate::random::random_with_region_seed;
+
+/// Region size in chunks, each region has one generation attempt
+pub const SPACING: i32 = 32;
+pub const SEPARATION: i32 = 5;
+pub const SALT: i32 = 10387313;
+
+/// Chunk of the generation attempt of the region, only the lower 48 bits of the world seed matter. The offsets are the
+/// average of two rolls.
+#[inline(always)]
+pub const fn attempt_chunk(world_seed: i64, region: (i32, i32)) -> (i32, i32) {
+    let (mut rng, _) = random_with_region_seed(world_seed, region.0, region.1, SALT);
+    let range = SPACING - SEPARATION;
+    let (x0, x1) = (rng.next_bounded_int(range), rng.next_bounded_int(range));
+    let (z0, z1) = (rng.next_bounded_int(range), rng.next_bounded_int(range));
+    let (x, z) = (x0.wrapping_add(x1) / 2, z0.wrapping_add(z1) / 2);
+    (
+        region.0.wrapping_mul(SPACING).wrapping_add(x),
+        region.1.wrapping_mul(SPACING).wrapping_add(z),
+    )
+}
+
+/// Whether the region of the chunk tries to place its monument there, the biomes then decide if it generates
+#[inline(always)]
+pub const fn is_attempt_chunk(world_seed: i64, chunk_pos: (i32, i32)) -> bool {
+    let region = (
+        chunk_pos.0.div_euclid(SPACING),
+        chunk_pos.1.div_euclid(SPACING),
+    );
+    let (x, z) = attempt_chunk(world_seed, region);
+    x == chunk_pos.0 && z == chunk_pos.1
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::features::monument::{SEPARATION, SPACING, attempt_chunk, is_attempt_chunk};
+
+    #[test]
+    fn test_attempt_chunks() {
+        for seed in 0..200 {
+            for region in [(0, 0), (-1, 3), (7, -12), (-58593, -58594)] {
+                let (x, z) = attempt_chunk(seed, region);
+                let offset = (x - region.0 * SPACING, z - region.1 * SPACING);
+                assert!((0..SPACING - SEPARATION).contains(&offset.0));
+                assert!((0..SPACING - SEPARATION).contains(&offset.1));
+                assert!(is_attempt_chunk(seed, (x, z)));
+                assert!(!is_attempt_chunk(seed, (x + 1, z)));
+                assert!(!is_attempt_chunk(seed, (x, z - 1)));
+            }
+            // only the lower 48 bits of the world seed
+            assert_eq!(
+                attempt_chunk(seed, (2, 5)),
+                attempt_chunk(seed | (12345 << 48), (2, 5))
+            );
+        }
+    }
+}
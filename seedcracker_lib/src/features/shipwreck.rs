@@ -0,0 +1,262 @@
+use crate::{
+    features::decorator_chest::{ChestIndex, DecoratorChestSeeds, detect_chest_index},
+    loot_table::{
+        FastInventoryCompareContext, InvalidObservation, ItemLootPoolEntryBuilder, ItemRegistry,
+        LootPoolBuilder, LootTable, LootTableBuilder, RegisteredItem, SetCountFunction,
+        SingleChest,
+    },
+    random::{DecoratorSalt, JavaRandom},
+};
+
+pub mod items {
+    pub const IRON_INGOT: usize = 1;
+    pub const GOLD_INGOT: usize = 2;
+    pub const EMERALD: usize = 3;
+    pub const DIAMOND: usize = 4;
+    pub const EXPERIENCE_BOTTLE: usize = 5;
+    pub const IRON_NUGGET: usize = 6;
+    pub const GOLD_NUGGET: usize = 7;
+    pub const LAPIS_LAZULI: usize = 8;
+}
+
+/// Larger than every item id of the shipwreck treasure loot table
+pub const ITEM_COUNT: usize = 9;
+
+/// The items of the shipwreck treasure loot table, for [`crate::loot_table::from_datapack_json`]
+pub fn item_registry() -> ItemRegistry {
+    ItemRegistry::new()
+        .register("iron_ingot", RegisteredItem::new(items::IRON_INGOT))
+        .register("gold_ingot", RegisteredItem::new(items::GOLD_INGOT))
+        .register("emerald", RegisteredItem::new(items::EMERALD))
+        .register("diamond", RegisteredItem::new(items::DIAMOND))
+        .register(
+            "experience_bottle",
+            RegisteredItem::new(items::EXPERIENCE_BOTTLE),
+        )
+        .register("iron_nugget", RegisteredItem::new(items::IRON_NUGGET))
+        .register("gold_nugget", RegisteredItem::new(items::GOLD_NUGGET))
+        .register("lapis_lazuli", RegisteredItem::new(items::LAPIS_LAZULI))
+}
+
+/// `chests/shipwreck_treasure` of 1.16.5
+#[inline]
+pub fn get_loot_table() -> LootTable {
+    use crate::features::shipwreck::items::*;
+
+    LootTableBuilder::new()
+        .pool(
+            LootPoolBuilder::new()
+                .rolls_uniform(3, 6)
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_INGOT)
+                        .weight(90)
+                        .function(SetCountFunction::uniform(1, 5).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLD_INGOT)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 5).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(EMERALD)
+                        .weight(40)
+                        .function(SetCountFunction::uniform(1, 5).as_function())
+                        .build(),
+                )
+                .entry_item(ItemLootPoolEntryBuilder::new(DIAMOND).weight(5).build())
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(EXPERIENCE_BOTTLE)
+                        .weight(5)
+                        .build(),
+                )
+                .build(),
+        )
+        .pool(
+            LootPoolBuilder::new()
+                .rolls_uniform(2, 5)
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(IRON_NUGGET)
+                        .weight(50)
+                        .function(SetCountFunction::uniform(1, 10).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(GOLD_NUGGET)
+                        .weight(10)
+                        .function(SetCountFunction::uniform(1, 10).as_function())
+                        .build(),
+                )
+                .entry_item(
+                    ItemLootPoolEntryBuilder::new(LAPIS_LAZULI)
+                        .weight(20)
+                        .function(SetCountFunction::uniform(1, 10).as_function())
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// The chests of a shipwreck are filled by the shipwreck structure, the 7th surface structure (step 4)
+#[inline(always)]
+pub const fn get_shipwreck_chest_seeds(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+) -> DecoratorChestSeeds {
+    DecoratorChestSeeds::new(world_seed, chunk_pos, DecoratorSalt::Shipwreck)
+}
+
+/// Seed of the `chest_index`-th chest filled in the chunk. A shipwreck has up to 3 chests (supply, map and
+/// treasure), the ones in the same chunk as the treasure chest and filled before it shift its index.
+#[inline(always)]
+pub const fn get_shipwreck_chest_loot_table_seed(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+) -> i64 {
+    get_shipwreck_chest_seeds(world_seed, chunk_pos).loot_table_seed(chest_index)
+}
+
+/// ```
+/// use mcseedcracker::{features::shipwreck::get_shipwreck_chest, loot_table::Inventory, math::Math};
+///
+/// let chunk = Math::block_coords_to_chunk_coords((-1263, 391));
+/// let chest = get_shipwreck_chest(734679766044180411, chunk, 0, 0.0);
+/// assert!((0..chest.slot_count()).any(|i| chest.get_item(i).is_some()));
+/// ```
+#[inline]
+pub fn get_shipwreck_chest(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    chest_index: i32,
+    luck: f32,
+) -> SingleChest {
+    let seed = get_shipwreck_chest_loot_table_seed(world_seed, chunk_pos, chest_index);
+
+    let mut chest = SingleChest::new();
+    get_loot_table().generate_in_inventory(&mut chest, &mut JavaRandom::new(seed), luck);
+    chest
+}
+
+#[inline]
+pub fn build_fast_inventory_compare_context(
+    contents: SingleChest,
+    loot_table: &LootTable,
+) -> Result<FastInventoryCompareContext<SingleChest, ITEM_COUNT>, InvalidObservation> {
+    FastInventoryCompareContext::new(contents, loot_table)
+}
+
+/// Returns which chest of the chunk generates the compared contents, trying every index allowed by `index`
+#[inline]
+pub fn compare_shipwreck_chest_fast_noinv(
+    world_seed: i64,
+    chunk_pos: (i32, i32),
+    index: ChestIndex,
+    loot_table: &LootTable,
+    luck: f32,
+    compare: &FastInventoryCompareContext<SingleChest, ITEM_COUNT>,
+) -> Option<i32> {
+    detect_chest_index(
+        &get_shipwreck_chest_seeds(world_seed, chunk_pos),
+        index,
+        loot_table,
+        luck,
+        compare,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use crate::{
+        features::{
+            decorator_chest::ChestIndex,
+            shipwreck::{
+                build_fast_inventory_compare_context, compare_shipwreck_chest_fast_noinv,
+                get_loot_table, get_shipwreck_chest, get_shipwreck_chest_loot_table_seed, items,
+            },
+        },
+        loot_table::Inventory,
+        math::Math,
+        random::{JavaRandom, random_with_population_seed},
+    };
+
+    #[test]
+    fn test_shipwreck_loot_table_seeds() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = Math::block_coords_to_chunk_coords((-1263, 391));
+
+        // setFeatureSeed(decoration seed, 6, 4), then one nextLong per chest filled in the chunk
+        let block_pos = Math::relative_chunk_coords(chunk, (0, 0));
+        let population_seed = random_with_population_seed(world_seed, block_pos.0, block_pos.1).1;
+        let mut rng = JavaRandom::new(population_seed.wrapping_add(6 + 40000));
+        for chest_index in 0..3 {
+            assert_eq!(
+                get_shipwreck_chest_loot_table_seed(world_seed, chunk, chest_index),
+                rng.next_long()
+            );
+        }
+    }
+
+    #[test]
+    fn test_shipwreck_chest_contents() {
+        for z in -8..8 {
+            let chest = get_shipwreck_chest(-7193194438565520372, (3, z), 0, 0.0);
+            let mut counts = [0; 9];
+            for i in 0..chest.slot_count() {
+                if let Some(stack) = chest.get_item(i) {
+                    counts[stack.item] += stack.count;
+                }
+            }
+            assert_eq!(counts[0], 0);
+
+            // the diamonds and bottles are single items, 3 to 6 rolls of at most 5 items each
+            let rolled = counts[items::IRON_INGOT..=items::EXPERIENCE_BOTTLE]
+                .iter()
+                .sum::<i32>();
+            assert!((3..=30).contains(&rolled), "{:?}", counts);
+            assert!(counts[items::DIAMOND] + counts[items::EXPERIENCE_BOTTLE] <= 6);
+            // 2 to 5 rolls of at most 10 items each
+            let rolled = counts[items::IRON_NUGGET..=items::LAPIS_LAZULI]
+                .iter()
+                .sum::<i32>();
+            assert!((2..=50).contains(&rolled), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_compare_shipwreck_chest() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = Math::block_coords_to_chunk_coords((-1263, 391));
+        let loot_table = get_loot_table();
+
+        for chest_index in 0..3 {
+            let chest = get_shipwreck_chest(world_seed, chunk, chest_index, 0.0);
+            let compare = build_fast_inventory_compare_context(chest, &loot_table).unwrap();
+            assert_eq!(
+                compare_shipwreck_chest_fast_noinv(
+                    world_seed,
+                    chunk,
+                    ChestIndex::default(),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                Some(chest_index)
+            );
+            assert_eq!(
+                compare_shipwreck_chest_fast_noinv(
+                    world_seed,
+                    (chunk.0 + 1, chunk.1),
+                    ChestIndex::Known(chest_index),
+                    &loot_table,
+                    0.0,
+                    &compare
+                ),
+                None
+            );
+        }
+    }
+}
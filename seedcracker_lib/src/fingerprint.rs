@@ -0,0 +1,177 @@
+use std::hash::{Hash, Hasher};
+
+/// A 64-bit hash of some evidence, used as a cache key instead of keeping and comparing full copies.
+///
+/// Unlike `DefaultHasher`, the value is the same across runs and platforms. It goes through the derived `Hash` impls,
+/// whose output isn't promised to stay the same across compiler versions, so a fingerprint saved by another build may
+/// not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EvidenceFingerprint(pub u64);
+
+impl EvidenceFingerprint {
+    #[inline]
+    pub fn of<T: Hash + ?Sized>(value: &T) -> Self {
+        let mut hasher = FingerprintHasher::new();
+        value.hash(&mut hasher);
+        Self(hasher.finish())
+    }
+}
+
+/// FNV-1a, with every integer written as little-endian and `usize`/`isize` widened to 64 bits
+#[derive(Debug, Clone, Copy)]
+pub struct FingerprintHasher {
+    state: u64,
+}
+
+impl FingerprintHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+}
+
+impl Default for FingerprintHasher {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    #[inline(always)]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline(always)]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline(always)]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline(always)]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline(always)]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline(always)]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline(always)]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline(always)]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline(always)]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline(always)]
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        features::{
+            buried_treasure::{build_fast_inventory_compare_context, get_loot_table},
+            end_pillars::{PartialEndPillars, PillarHeightHint},
+        },
+        fingerprint::EvidenceFingerprint,
+        loot_table::{ItemStack, SingleChest},
+        search::StructureData,
+    };
+
+    #[test]
+    fn test_fingerprint_stability() {
+        assert_eq!(EvidenceFingerprint::of(&()).0, 0xcbf29ce484222325);
+        assert_eq!(EvidenceFingerprint::of(&0u8).0, 0xaf63bd4c8601b7df);
+        assert_eq!(EvidenceFingerprint::of(&1i64).0, 0x89cd31291d2aefa4);
+
+        let mut pillars = PartialEndPillars::new();
+        let empty = EvidenceFingerprint::of(&pillars);
+        assert_eq!(empty, EvidenceFingerprint::of(&PartialEndPillars::new()));
+
+        pillars.0[3].caged = Some(true);
+        pillars.0[3].height = PillarHeightHint::Exact(79);
+        let observed = EvidenceFingerprint::of(&pillars);
+        assert_ne!(empty, observed);
+        assert_eq!(observed, EvidenceFingerprint::of(&pillars.clone()));
+
+        pillars.0[3].height = PillarHeightHint::Range(79, 79);
+        assert_ne!(observed, EvidenceFingerprint::of(&pillars));
+
+        let mut chest = SingleChest::new();
+        let empty_chest = EvidenceFingerprint::of(&chest);
+        chest.rows[1].items[4] = Some(ItemStack::of(1, 1));
+        assert_ne!(empty_chest, EvidenceFingerprint::of(&chest));
+        assert_eq!(
+            EvidenceFingerprint::of(&chest),
+            EvidenceFingerprint::of(&chest.clone())
+        );
+    }
+
+    #[test]
+    fn test_structure_data_fingerprint() {
+        let make = |luck: f32| StructureData::BuriedTreasureContents {
+            chunk_x: 25,
+            chunk_z: 50,
+            luck,
+            contents: build_fast_inventory_compare_context(SingleChest::new(), &get_loot_table())
+                .unwrap(),
+            loot_table: None,
+        };
+
+        assert_eq!(make(0.0), make(0.0));
+        assert_eq!(
+            EvidenceFingerprint::of(&make(0.0)),
+            EvidenceFingerprint::of(&make(0.0).clone())
+        );
+        assert_eq!(make(0.0), make(-0.0));
+        assert_eq!(
+            EvidenceFingerprint::of(&make(0.0)),
+            EvidenceFingerprint::of(&make(-0.0))
+        );
+        assert_ne!(
+            EvidenceFingerprint::of(&make(0.0)),
+            EvidenceFingerprint::of(&make(1.0))
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Progress heartbeat of the search workers. From its progress alone, a search whose workers stopped, throttled by an
+//! antivirus or stuck in a bug, looks the same as a slow one.
+//!
+//! The workers only touch atomics, so beating costs next to nothing in the hot loops.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::calibration::{Clock, SystemClock};
+
+/// Time without progress after which a search is reported as stalled
+pub const STALL_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub struct Heartbeat<C: Clock = SystemClock> {
+    clock: C,
+    /// Milliseconds on `clock` of the last progress
+    last_progress: AtomicU64,
+    /// Progress of each thread of the rayon pool, the last counter is shared by the threads outside of it
+    threads: Box<[AtomicU64]>,
+}
+
+impl Heartbeat {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl Default for Heartbeat {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> Heartbeat<C> {
+    /// Starts as if progress was just made, a search that never starts is stalled after the threshold
+    pub fn with_clock(clock: C) -> Self {
+        let start = clock.now().as_millis() as u64;
+        Self {
+            clock,
+            last_progress: AtomicU64::new(start),
+            threads: (0..=rayon::current_num_threads())
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+        }
+    }
+
+    /// Records `amount` steps of progress by the calling thread
+    #[inline]
+    pub fn beat(&self, amount: u64) {
+        let last = self.threads.len() - 1;
+        let thread = rayon::current_thread_index().map_or(last, |i| i.min(last));
+        self.threads[thread].fetch_add(amount, Ordering::Relaxed);
+        self.last_progress
+            .fetch_max(self.clock.now().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn since_last_progress(&self) -> Duration {
+        let last = Duration::from_millis(self.last_progress.load(Ordering::Relaxed));
+        self.clock.now().saturating_sub(last)
+    }
+
+    #[inline]
+    pub fn is_stalled(&self, threshold: Duration) -> bool {
+        self.since_last_progress() >= threshold
+    }
+
+    /// Steps of progress made by each thread, see [`Heartbeat::beat`]
+    pub fn thread_progress(&self) -> Vec<u64> {
+        self.threads
+            .iter()
+            .map(|t| t.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// The threads that have made progress and their counters, e.g. `"#0: 512, #3: 1024"`
+    pub fn thread_report(&self) -> String {
+        let progress = self.thread_progress();
+        let last = progress.len() - 1;
+        progress
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| **p > 0)
+            .map(|(i, p)| {
+                if i == last {
+                    format!("other: {}", p)
+                } else {
+                    format!("#{}: {}", i, p)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use crate::{
+        calibration::Clock,
+        heartbeat::{Heartbeat, STALL_THRESHOLD},
+    };
+
+    #[derive(Default)]
+    struct ManualClock {
+        millis: AtomicU64,
+    }
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            self.millis
+                .fetch_add(by.as_millis() as u64, Ordering::Relaxed);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            Duration::from_millis(self.millis.load(Ordering::Relaxed))
+        }
+    }
+
+    /// Stops the workers, even if an assertion fails while they run
+    struct StopOnDrop<'a>(&'a AtomicBool);
+
+    impl Drop for StopOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Spins until `condition` holds, the workers are real threads
+    fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..10_000_000 {
+            if condition() {
+                return;
+            }
+            std::thread::yield_now();
+        }
+        panic!("the workers didn't react");
+    }
+
+    #[test]
+    fn test_stall_detection() {
+        const WORKERS: usize = 3;
+        let heartbeat = Heartbeat::with_clock(ManualClock::default());
+        assert!(!heartbeat.is_stalled(STALL_THRESHOLD));
+        let (paused, stop) = (AtomicBool::new(false), AtomicBool::new(false));
+        let parked = AtomicUsize::new(0);
+
+        std::thread::scope(|s| {
+            for _ in 0..WORKERS {
+                s.spawn(|| {
+                    let mut was_parked = false;
+                    while !stop.load(Ordering::Relaxed) {
+                        let pause = paused.load(Ordering::Relaxed);
+                        if pause != was_parked {
+                            was_parked = pause;
+                            if pause {
+                                parked.fetch_add(1, Ordering::Relaxed);
+                            } else {
+                                parked.fetch_sub(1, Ordering::Relaxed);
+                            }
+                        }
+                        if !pause {
+                            heartbeat.beat(1);
+                        }
+                        std::thread::yield_now();
+                    }
+                });
+            }
+
+            let _stop = StopOnDrop(&stop);
+
+            // running workers keep the search alive, however long it takes
+            heartbeat.clock.advance(STALL_THRESHOLD * 2);
+            wait_until(|| !heartbeat.is_stalled(STALL_THRESHOLD));
+
+            paused.store(true, Ordering::Relaxed);
+            wait_until(|| parked.load(Ordering::Relaxed) == WORKERS);
+            heartbeat
+                .clock
+                .advance(STALL_THRESHOLD - Duration::from_secs(1));
+            assert!(!heartbeat.is_stalled(STALL_THRESHOLD));
+            heartbeat.clock.advance(Duration::from_secs(2));
+            assert!(heartbeat.is_stalled(STALL_THRESHOLD));
+            assert!(heartbeat.since_last_progress() > STALL_THRESHOLD);
+
+            // progress clears the flag
+            paused.store(false, Ordering::Relaxed);
+            wait_until(|| !heartbeat.is_stalled(STALL_THRESHOLD));
+        });
+
+        // the workers aren't rayon threads, they share the last counter
+        let progress = heartbeat.thread_progress();
+        assert!(*progress.last().unwrap() > 0);
+        assert_eq!(progress[..progress.len() - 1].iter().sum::<u64>(), 0);
+        assert!(heartbeat.thread_report().starts_with("other: "));
+    }
+}
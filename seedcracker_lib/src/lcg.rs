@@ -30,6 +30,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 
 */
+#![deny(arithmetic_overflow, clippy::arithmetic_side_effects)]
 
 #[derive(Debug, Clone)]
 pub struct LinearCongruentialGenerator {
@@ -42,7 +43,7 @@ pub struct LinearCongruentialGenerator {
 }
 
 pub const JAVA_RANDOM: LinearCongruentialGenerator =
-    LinearCongruentialGenerator::new(0x5DEECE66D, 0xB, 1i64 << 48);
+    LinearCongruentialGenerator::new(0x5DEECE66D, 0xB, 0x1_0000_0000_0000);
 
 pub const JAVA_RANDOM_REV1: LinearCongruentialGenerator = JAVA_RANDOM.combine(-1);
 pub const JAVA_RANDOM_REV2: LinearCongruentialGenerator = JAVA_RANDOM.combine(-2);
@@ -50,7 +51,7 @@ pub const JAVA_RANDOM_REV2: LinearCongruentialGenerator = JAVA_RANDOM.combine(-2
 impl LinearCongruentialGenerator {
     #[inline(always)]
     pub const fn new(multiplier: i64, increment: i64, modulus: i64) -> Self {
-        let p2 = (modulus & (modulus - 1)) == 0;
+        let p2 = (modulus & modulus.wrapping_sub(1)) == 0;
         Self {
             multiplier,
             increment,
@@ -97,51 +98,98 @@ impl LinearCongruentialGenerator {
 
     #[inline(always)]
     pub const fn next_seed(&self, seed: i64) -> i64 {
-        self._mod(
-            self.multiplier
-                .wrapping_mul(seed)
-                .wrapping_add(self.increment),
-        )
+        self.mul_add_mod(self.multiplier, seed, self.increment)
     }
 
     #[inline(always)]
+    #[expect(
+        clippy::arithmetic_side_effects,
+        reason = "a modulus of 0 counts as a power of 2, so the remainder never divides by 0"
+    )]
     pub const fn _mod(&self, x: i64) -> i64 {
         if self.is_pow_2 {
-            x & (self.modulus - 1)
+            x & self.modulus.wrapping_sub(1)
         } else {
-            ((x as u64) % (self.modulus as u64)) as i64
+            (x as u64).wrapping_rem(self.modulus as u64) as i64
         }
     }
 
+    /// `a * b + c` modulo the modulus. Wrapping 64-bit arithmetic is exact modulo a power of two, other moduli take
+    /// the product on 128 bits.
+    #[inline(always)]
+    #[expect(
+        clippy::arithmetic_side_effects,
+        reason = "a modulus of 0 counts as a power of 2, so the remainder never divides by 0"
+    )]
+    const fn mul_add_mod(&self, a: i64, b: i64, c: i64) -> i64 {
+        if self.is_pow_2 {
+            self._mod(a.wrapping_mul(b).wrapping_add(c))
+        } else {
+            (a as i128)
+                .wrapping_mul(b as i128)
+                .wrapping_add(c as i128)
+                .wrapping_rem_euclid(self.modulus as i128) as i64
+        }
+    }
+
+    /// Inverse of `a` modulo the modulus, by the extended Euclidean algorithm
+    const fn inverse_mod(&self, a: i64) -> Option<i64> {
+        let m = self.modulus as i128;
+        let Some(a) = (a as i128).checked_rem_euclid(m) else {
+            return None;
+        };
+        let (mut r0, mut r1) = (m, a);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        // stops once r1 is 0
+        while let Some(q) = r0.checked_div(r1) {
+            (r0, r1) = (r1, r0.wrapping_sub(q.wrapping_mul(r1)));
+            (t0, t1) = (t1, t0.wrapping_sub(q.wrapping_mul(t1)));
+        }
+        match t0.checked_rem_euclid(m) {
+            Some(inverse) if r0 == 1 => Some(inverse as i64),
+            _ => None,
+        }
+    }
+
+    /// The generator advancing `steps` times at once, or going back `-steps` times. Going back needs the multiplier to
+    /// be invertible modulo the modulus, which it is for every full period generator.
     #[inline]
     pub const fn combine(&self, steps: i64) -> LinearCongruentialGenerator {
-        let mut multiplier = 1i64;
-        let mut increment = 0i64;
+        // x = a * x' + c  <=>  x' = a^-1 * x - a^-1 * c
+        let (mut intermediate_multiplier, mut intermediate_increment) = if steps < 0 {
+            let Some(inverse) = self.inverse_mod(self.multiplier) else {
+                panic!("the multiplier has no inverse, the generator can't go back");
+            };
+            (
+                inverse,
+                self.mul_add_mod(inverse.wrapping_neg(), self.increment, 0),
+            )
+        } else {
+            (self._mod(self.multiplier), self._mod(self.increment))
+        };
 
-        let mut intermediate_multiplier = self.multiplier;
-        let mut intermediate_increment = self.increment;
+        let mut multiplier = self._mod(1);
+        let mut increment = 0i64;
 
-        let mut k = steps;
+        let mut k = steps.unsigned_abs();
         while k != 0 {
             if (k & 1) != 0 {
-                multiplier = multiplier.wrapping_mul(intermediate_multiplier);
-                increment = intermediate_multiplier
-                    .wrapping_mul(increment)
-                    .wrapping_add(intermediate_increment);
+                multiplier = self.mul_add_mod(multiplier, intermediate_multiplier, 0);
+                increment =
+                    self.mul_add_mod(intermediate_multiplier, increment, intermediate_increment);
             }
 
-            intermediate_increment = intermediate_multiplier
-                .wrapping_add(1)
-                .wrapping_mul(intermediate_increment);
+            intermediate_increment = self.mul_add_mod(
+                intermediate_multiplier.wrapping_add(1),
+                intermediate_increment,
+                0,
+            );
+            intermediate_multiplier =
+                self.mul_add_mod(intermediate_multiplier, intermediate_multiplier, 0);
 
-            intermediate_multiplier = intermediate_multiplier.wrapping_mul(intermediate_multiplier);
-
-            k = ((k as u64) >> 1) as i64;
+            k = k.wrapping_shr(1);
         }
 
-        multiplier = self._mod(multiplier);
-        increment = self._mod(increment);
-
         LinearCongruentialGenerator::new(multiplier, increment, self.modulus)
     }
 
@@ -154,11 +202,8 @@ impl LinearCongruentialGenerator {
             None
         } else {
             Some(LinearCongruentialGenerator::new(
-                self.multiplier.wrapping_mul(other.multiplier),
-                other
-                    .multiplier
-                    .wrapping_mul(self.increment)
-                    .wrapping_add(other.increment),
+                self.mul_add_mod(self.multiplier, other.multiplier, 0),
+                self.mul_add_mod(other.multiplier, self.increment, other.increment),
                 self.modulus,
             ))
         }
@@ -169,3 +214,72 @@ impl LinearCongruentialGenerator {
         self.combine(-1)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::lcg::{JAVA_RANDOM, JAVA_RANDOM_REV1, LinearCongruentialGenerator};
+
+    /// Park and Miller's minimal standard generator, its modulus isn't a power of two
+    const MINSTD: LinearCongruentialGenerator =
+        LinearCongruentialGenerator::new(48271, 0, 0x7FFF_FFFF);
+
+    /// Skip counts of the properties, ±2^40
+    const SKIP: i64 = 0x100_0000_0000;
+    const MIN_SKIP: i64 = -0x100_0000_0000;
+
+    fn steps(lcg: &LinearCongruentialGenerator, mut seed: i64, n: usize) -> i64 {
+        for _ in 0..n {
+            seed = lcg.next_seed(seed);
+        }
+        seed
+    }
+
+    #[test]
+    fn test_combine_matches_steps() {
+        for lcg in [JAVA_RANDOM, MINSTD] {
+            let seed = 123456789;
+            assert_eq!(
+                lcg.combine(1_000_000).next_seed(seed),
+                steps(&lcg, seed, 1_000_000)
+            );
+            let back = lcg.combine(-1_000_000).next_seed(seed);
+            assert_eq!(steps(&lcg, back, 1_000_000), seed);
+        }
+        assert_eq!(JAVA_RANDOM.combine(0).next_seed(42), 42);
+        assert_eq!(JAVA_RANDOM_REV1.next_seed(JAVA_RANDOM.next_seed(42)), 42);
+
+        // the longest skips don't overflow
+        let seed = 180066252004364;
+        let there = JAVA_RANDOM.combine(i64::MIN).next_seed(seed);
+        assert_eq!(
+            JAVA_RANDOM.combine(i64::MAX).next_seed(there),
+            JAVA_RANDOM_REV1.next_seed(seed)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_combine_inverse(n in MIN_SKIP..=SKIP, seed in any::<i64>()) {
+            for lcg in [JAVA_RANDOM, MINSTD] {
+                let seed = lcg._mod(seed);
+                let there = lcg.combine(n).next_seed(seed);
+                prop_assert_eq!(lcg.combine(n.wrapping_neg()).next_seed(there), seed);
+            }
+        }
+
+        #[test]
+        fn prop_combine_additive(a in MIN_SKIP..=SKIP, b in MIN_SKIP..=SKIP) {
+            for lcg in [JAVA_RANDOM, MINSTD] {
+                let combined = lcg.combine(a).combine_with(&lcg.combine(b)).unwrap();
+                let direct = lcg.combine(a.wrapping_add(b));
+                prop_assert_eq!(
+                    (combined.get_multiplier(), combined.get_increment()),
+                    (direct.get_multiplier(), direct.get_increment())
+                );
+            }
+        }
+    }
+}
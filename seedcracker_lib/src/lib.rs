@@ -1,11 +1,32 @@
+pub mod analysis;
+pub mod biome_cache;
+pub mod calibration;
+pub mod candidates;
+pub mod cooperative;
+#[cfg(test)]
+mod differential;
 pub mod discrete_log;
+pub mod export;
+pub mod expr;
 pub mod features;
+pub mod fingerprint;
+pub mod heartbeat;
 pub mod lcg;
 pub mod loot_table;
 pub mod math;
+pub mod pipeline;
 pub mod random;
+pub mod rarity;
+pub mod rejections;
+pub mod schedule;
 pub mod search;
+pub mod self_test;
+pub mod templates;
+pub mod tooltip;
+pub mod trace;
 pub mod utils;
+pub mod view;
+pub mod world_seed;
 
 pub use cubiomes;
 
@@ -17,16 +38,17 @@ mod tests {
         enums::{BiomeID, Dimension, MCVersion, StructureType},
         generator::{BlockPosition, Generator, GeneratorFlags},
     };
-    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 
     use crate::{
         features::{
-            buried_treasure,
+            buried_treasure::{self, items::COOKED_COD},
             end_pillars::{EndPillars, PartialEndPillars, PillarHeightHint},
         },
         lcg,
-        loot_table::{ChestRow, ItemStack, SingleChest},
+        loot_table::{ItemStack, SlotThreshold},
         math::Math,
+        self_test,
     };
 
     #[test]
@@ -92,57 +114,13 @@ mod tests {
         let bt_chunk = Math::block_coords_to_chunk_coords((409, 809));
 
         let structure_seed = {
-            use crate::features::buried_treasure::items::{
-                COOKED_COD, GOLD_INGOT, HEART_OF_THE_SEA, IRON_INGOT, IRON_SWORD,
-                PRISMARINE_CRYSTALS,
-            };
-
-            let bt_contents = SingleChest {
-                rows: [
-                    ChestRow {
-                        items: [
-                            Some(ItemStack::of(COOKED_COD, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::new(IRON_SWORD, 1, 1)),
-                            Some(ItemStack::of(COOKED_COD, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            None,
-                            None,
-                            None,
-                        ],
-                    },
-                    ChestRow {
-                        items: [
-                            Some(ItemStack::of(GOLD_INGOT, 2)),
-                            Some(ItemStack::of(COOKED_COD, 2)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(HEART_OF_THE_SEA, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(COOKED_COD, 2)),
-                            None,
-                            None,
-                        ],
-                    },
-                    ChestRow {
-                        items: [
-                            Some(ItemStack::of(GOLD_INGOT, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(COOKED_COD, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(COOKED_COD, 1)),
-                            Some(ItemStack::of(IRON_INGOT, 1)),
-                            Some(ItemStack::of(PRISMARINE_CRYSTALS, 3)),
-                            Some(ItemStack::of(PRISMARINE_CRYSTALS, 1)),
-                            None,
-                        ],
-                    },
-                ],
-            };
-
-            let bt_compare_context =
-                buried_treasure::build_fast_inventory_compare_context(bt_contents);
+            let bt_contents = self_test::buried_treasure_contents();
+
+            let bt_compare_context = buried_treasure::build_fast_inventory_compare_context(
+                bt_contents,
+                &buried_treasure::get_loot_table(),
+            )
+            .unwrap();
 
             let rev = lcg::JAVA_RANDOM.combine(-2);
 
@@ -234,4 +212,99 @@ mod tests {
             world_seed, -7193194438565520372i64
         );
     }
+
+    /// The structure seed search of [`test_full_reverse`], with a chest misremembered in one slot
+    #[test]
+    fn test_reverse_with_wrong_slot() {
+        let bt_chunk = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let mut bt_contents = self_test::buried_treasure_contents();
+        // the 2 gold ingots remembered as 1 cooked cod
+        bt_contents.rows[1].items[0] = Some(ItemStack::of(COOKED_COD, 1));
+
+        let bt_compare_context = buried_treasure::build_fast_inventory_compare_context(
+            bt_contents,
+            &buried_treasure::get_loot_table(),
+        )
+        .unwrap()
+        .with_slot_threshold(Some(SlotThreshold::new(26, 27)));
+
+        let rev = lcg::JAVA_RANDOM.combine(-2);
+        let values: Vec<i64> = (0u64..(1u64 << 32))
+            .into_par_iter()
+            .filter_map(|i| {
+                let state_lo = i & 0xFFFF;
+                let state_hi = i >> 16;
+                let state =
+                    ((state_hi as i64) << 32) | (self_test::PILLAR_SEED << 16) | (state_lo as i64);
+                let seed = rev.next_seed(state) ^ lcg::JAVA_RANDOM.get_multiplier();
+
+                (buried_treasure::generates_at(seed, bt_chunk)
+                    && buried_treasure::compare_buried_treasure_threshold(
+                        seed,
+                        bt_chunk,
+                        0.0,
+                        &bt_compare_context,
+                    ))
+                .then_some(seed)
+            })
+            .collect();
+
+        assert_eq!(values, vec![self_test::STRUCTURE_SEED]);
+    }
+
+    /// The seeds of [`buried_treasure::candidate_structure_seeds`] are those of the brute loop of
+    /// [`test_full_reverse`] that pass [`buried_treasure::generates_at`], found without scanning the 2^32 seeds
+    #[test]
+    fn test_candidate_structure_seeds() {
+        let bt_chunk = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        // order independent, the two loops don't give the seeds in the same order
+        let fingerprint = |(count, sum, xor): (u64, i64, i64), seed: i64| {
+            (
+                count + 1,
+                sum.wrapping_add(seed),
+                xor ^ seed.wrapping_mul(0x9E37_79B9_7F4A_7C15u64 as i64),
+            )
+        };
+        let merge =
+            |a: (u64, i64, i64), b: (u64, i64, i64)| (a.0 + b.0, a.1.wrapping_add(b.1), a.2 ^ b.2);
+
+        let rev = lcg::JAVA_RANDOM.combine(-2);
+        let brute = (0u64..(1u64 << 32))
+            .into_par_iter()
+            .filter_map(|i| {
+                let state_lo = i & 0xFFFF;
+                let state_hi = i >> 16;
+                let state =
+                    ((state_hi as i64) << 32) | (self_test::PILLAR_SEED << 16) | (state_lo as i64);
+                let seed = rev.next_seed(state) ^ lcg::JAVA_RANDOM.get_multiplier();
+                buried_treasure::generates_at(seed, bt_chunk).then_some(seed)
+            })
+            .fold(|| (0, 0, 0), fingerprint)
+            .reduce(|| (0, 0, 0), merge);
+
+        let candidates =
+            || buried_treasure::candidate_structure_seeds(bt_chunk, self_test::PILLAR_SEED);
+        let found = candidates().fold((0, 0, 0), fingerprint);
+        assert_eq!(found, brute);
+        // every state it explores is a candidate, about 1 in 100 of the brute loop
+        assert!(found.0 < (1 << 32) / 64, "{}", found.0);
+
+        let bt_compare_context = buried_treasure::build_fast_inventory_compare_context(
+            self_test::buried_treasure_contents(),
+            &buried_treasure::get_loot_table(),
+        )
+        .unwrap();
+        let values = candidates()
+            .par_bridge()
+            .filter(|seed| {
+                buried_treasure::compare_buried_treasure_fast_noinv(
+                    *seed,
+                    bt_chunk,
+                    0.0,
+                    &bt_compare_context,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![self_test::STRUCTURE_SEED]);
+    }
 }
@@ -1,21 +1,71 @@
-use crate::random::JavaRandom;
+#![deny(arithmetic_overflow, clippy::arithmetic_side_effects)]
 
+use std::fmt::Display;
+
+use crate::random::McRandom;
+
+/// Block coordinates outside of the world border, see [`Math::is_inside_world_border`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfWorldBorder {
+    /// Wide enough for the positions `relative_chunk_coords` would wrap around
+    pub x: i64,
+    pub z: i64,
+}
+
+impl Display for OutOfWorldBorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}, {}) is outside of the world border (±{})",
+            self.x,
+            self.z,
+            Math::WORLD_BORDER
+        )
+    }
+}
+
+impl std::error::Error for OutOfWorldBorder {}
+
+/// The coordinate conversions work like Java's int arithmetic: `>> 4` floors towards negative infinity and the
+/// products wrap around. The searches rely on it and only see coordinates that were already checked. Coordinates
+/// typed by users go through the `checked_` variants first, which refuse anything outside of the world border
+/// instead of clamping it, since a clamped position would silently point to another chunk.
 pub struct Math;
 
 impl Math {
+    /// Blocks this far from the origin on either axis, or further, are outside of the world border
+    pub const WORLD_BORDER: i32 = 30_000_000;
+
+    #[inline(always)]
+    pub const fn is_inside_world_border(coords: (i32, i32)) -> bool {
+        Self::is_inside_world_border_wide(coords.0 as i64, coords.1 as i64)
+    }
+
+    #[inline(always)]
+    const fn is_inside_world_border_wide(x: i64, z: i64) -> bool {
+        x.unsigned_abs() < Self::WORLD_BORDER as u64 && z.unsigned_abs() < Self::WORLD_BORDER as u64
+    }
+
+    /// True if the seed is a 32 bit int, the `String::hashCode` of a text seed. The upper 32 bits are the sign
+    /// extension of bit 31, so a structure seed has at most one such world seed.
+    #[inline(always)]
+    pub const fn is_string_hashcode_seed(seed: i64) -> bool {
+        seed as i32 as i64 == seed
+    }
+
     #[inline(always)]
     pub const fn is_pow_2(n: i32) -> bool {
-        (n & (n - 1)) == 0
+        (n & n.wrapping_sub(1)) == 0
     }
 
     #[inline(always)]
     pub const fn pow_2(n: i32) -> i64 {
-        1i64 << n
+        1i64.wrapping_shl(n as u32)
     }
 
     #[inline(always)]
     pub const fn get_mask(n: i32) -> i64 {
-        Self::pow_2(n) - 1
+        Self::pow_2(n).wrapping_sub(1)
     }
 
     #[inline(always)]
@@ -25,7 +75,7 @@ impl Math {
 
     #[inline(always)]
     pub const fn mod_inverse_pow_2(value: i64, n: i32) -> i64 {
-        let mut x = (((value.wrapping_shl(1) ^ value) & 4) << 1) ^ value;
+        let mut x = ((value.wrapping_shl(1) ^ value) & 4).wrapping_shl(1) ^ value;
         x = x.wrapping_add(x.wrapping_sub(value.wrapping_mul(x).wrapping_mul(x)));
         x = x.wrapping_add(x.wrapping_sub(value.wrapping_mul(x).wrapping_mul(x)));
         x = x.wrapping_add(x.wrapping_sub(value.wrapping_mul(x).wrapping_mul(x)));
@@ -35,12 +85,26 @@ impl Math {
 
     #[inline(always)]
     pub const fn block_coords_to_chunk_coords(coords: (i32, i32)) -> (i32, i32) {
-        (coords.0 >> 4, coords.1 >> 4)
+        (coords.0.wrapping_shr(4), coords.1.wrapping_shr(4))
+    }
+
+    #[inline]
+    pub const fn checked_block_coords_to_chunk_coords(
+        coords: (i32, i32),
+    ) -> Result<(i32, i32), OutOfWorldBorder> {
+        if Self::is_inside_world_border(coords) {
+            Ok(Self::block_coords_to_chunk_coords(coords))
+        } else {
+            Err(OutOfWorldBorder {
+                x: coords.0 as i64,
+                z: coords.1 as i64,
+            })
+        }
     }
 
     #[inline(always)]
     pub const fn chunk_coords_to_region_coords(coords: (i32, i32)) -> (i32, i32) {
-        (coords.0 >> 5, coords.1 >> 5)
+        (coords.0.wrapping_shr(5), coords.1.wrapping_shr(5))
     }
 
     #[inline(always)]
@@ -54,30 +118,91 @@ impl Math {
         block_coords: (i32, i32),
     ) -> (i32, i32) {
         (
-            (chunk_coords.0 * 16) + block_coords.0,
-            (chunk_coords.1 * 16) + block_coords.1,
+            chunk_coords.0.wrapping_mul(16).wrapping_add(block_coords.0),
+            chunk_coords.1.wrapping_mul(16).wrapping_add(block_coords.1),
         )
     }
 
+    /// Also refuses offsets that land outside of the world border, e.g. a chunk near the border with a large offset
+    #[inline]
+    pub const fn checked_relative_chunk_coords(
+        chunk_coords: (i32, i32),
+        block_coords: (i32, i32),
+    ) -> Result<(i32, i32), OutOfWorldBorder> {
+        // never wraps in i64: |chunk * 16| <= 2^35 and |block| <= 2^31
+        let x = (chunk_coords.0 as i64)
+            .wrapping_mul(16)
+            .wrapping_add(block_coords.0 as i64);
+        let z = (chunk_coords.1 as i64)
+            .wrapping_mul(16)
+            .wrapping_add(block_coords.1 as i64);
+        if Self::is_inside_world_border_wide(x, z) {
+            Ok((x as i32, z as i32))
+        } else {
+            Err(OutOfWorldBorder { x, z })
+        }
+    }
+
     #[inline(always)]
     pub const fn region_coords_to_lower_chunk_coords(coords: (i32, i32)) -> (i32, i32) {
-        (coords.0 << 5, coords.1 << 5)
+        (coords.0.wrapping_shl(5), coords.1.wrapping_shl(5))
     }
 
     #[inline(always)]
-    /// Returns a random number between min and max (inclusive)
-    pub const fn next_int(rng: &mut JavaRandom, min: i32, max: i32) -> i32 {
+    /// Returns a random number between min and max (inclusive), like vanilla's `MathHelper.nextInt`: when
+    /// `min >= max` it returns `min` without drawing from the RNG, otherwise it draws exactly one
+    /// `nextInt(max - min + 1)`. Loot relies on it, e.g. splitting a stack of 1 item asks for `next_int(rng, 1, 0)`.
+    ///
+    /// A range wider than `i32::MAX` makes Java throw, so vanilla never asks for one; it returns `min` here, see
+    /// [`crate::random::JavaRandom::next_bounded_int`].
+    pub fn next_int<R: McRandom>(rng: &mut R, min: i32, max: i32) -> i32 {
         if min >= max {
             min
         } else {
-            rng.next_bounded_int(max - min + 1) + min
+            rng.next_bounded_int(max.wrapping_sub(min).wrapping_add(1))
+                .wrapping_add(min)
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
 mod tests {
-    use super::Math;
+    use proptest::prelude::*;
+
+    use super::{Math, OutOfWorldBorder};
+    use crate::random::JavaRandom;
+
+    #[test]
+    fn test_next_int_matches_java() {
+        // captured from MathHelper.nextInt on a java.util.Random, degenerate ranges included
+        let ranges = [
+            (0, 26),
+            (1, 0),
+            (1, 32),
+            (5, 5),
+            (-3, 3),
+            (3, -7),
+            (1, 64),
+            (0, 0),
+            (-100, -90),
+            (1, 1),
+        ];
+        let expected = [8, 1, 24, 5, -2, 3, 39, 0, -92, 1];
+        let mut rng = JavaRandom::new(-7193194438565520372);
+        let values = ranges.map(|(min, max)| Math::next_int(&mut rng, min, max));
+        assert_eq!(values, expected);
+        // only the non degenerate ranges drew from the RNG
+        assert_eq!(rng.next_long(), -231728752415955541);
+
+        let mut rng = JavaRandom::new(-7193194438565520372);
+        assert_eq!(Math::next_int(&mut rng, i32::MIN, i32::MAX), i32::MIN);
+        assert_eq!(Math::next_int(&mut rng, -10, i32::MAX), -10);
+        assert_eq!(
+            rng.next_long(),
+            JavaRandom::new(-7193194438565520372).next_long()
+        );
+    }
 
     #[test]
     fn test_mod_inverse_pow_2() {
@@ -99,4 +224,128 @@ mod tests {
             assert_eq!(final_res, 1);
         }
     }
+
+    #[test]
+    fn test_coords_near_world_border() {
+        assert_eq!(
+            Math::relative_chunk_coords((1_875_000, -1_875_000), (0, 0)),
+            (30_000_000, -30_000_000)
+        );
+        assert_eq!(
+            Math::relative_chunk_coords((-1_875_000, 1_874_999), (15, 15)),
+            (-29_999_985, 29_999_999)
+        );
+        assert_eq!(
+            Math::block_coords_to_chunk_coords((-30_000_000, 29_999_999)),
+            (-1_875_000, 1_874_999)
+        );
+        assert_eq!(
+            Math::block_coords_to_region_coords((30_000_000, -30_000_000)),
+            (58593, -58594)
+        );
+        assert_eq!(
+            Math::region_coords_to_lower_chunk_coords((58593, -58594)),
+            (1_874_976, -1_875_008)
+        );
+
+        assert_eq!(
+            Math::checked_block_coords_to_chunk_coords((-29_999_999, 29_999_999)),
+            Ok((-1_875_000, 1_874_999))
+        );
+        assert_eq!(
+            Math::checked_block_coords_to_chunk_coords((30_000_000, 0)),
+            Err(OutOfWorldBorder {
+                x: 30_000_000,
+                z: 0
+            })
+        );
+        assert_eq!(
+            Math::checked_relative_chunk_coords((1_874_999, 0), (16, 0)),
+            Err(OutOfWorldBorder {
+                x: 30_000_000,
+                z: 0
+            })
+        );
+        assert_eq!(
+            Math::checked_relative_chunk_coords((i32::MAX, 0), (1, 0))
+                .unwrap_err()
+                .to_string(),
+            "(34359738353, 0) is outside of the world border (±30000000)"
+        );
+
+        // Matches release-mode wrapping behavior instead of panicking in debug builds
+        assert_eq!(
+            Math::relative_chunk_coords((i32::MAX, i32::MIN), (1, -1)),
+            (
+                i32::MAX.wrapping_mul(16).wrapping_add(1),
+                i32::MIN.wrapping_mul(16).wrapping_sub(1)
+            )
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_chunk_coords_match_java_shift(x in any::<i32>(), z in any::<i32>()) {
+            // `x >> 4` on a Java int floors towards negative infinity
+            let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords((x, z));
+            prop_assert_eq!(chunk_x, x.div_euclid(16));
+            prop_assert_eq!(chunk_z, z.div_euclid(16));
+        }
+
+        #[test]
+        fn prop_string_hashcode_seeds(hashcode in any::<i32>(), upper in 1u32..) {
+            prop_assert!(Math::is_string_hashcode_seed(hashcode as i64));
+            // sign extension or nothing, any other upper bits
+            let seed = hashcode as i64 ^ ((upper as i64) << 32);
+            prop_assert!(!Math::is_string_hashcode_seed(seed));
+        }
+
+        #[test]
+        fn prop_chunk_coords_round_trip(
+            x in -29_999_999..30_000_000i32,
+            z in -29_999_999..30_000_000i32,
+        ) {
+            let chunk = Math::checked_block_coords_to_chunk_coords((x, z)).unwrap();
+            let offset = (x.rem_euclid(16), z.rem_euclid(16));
+            prop_assert_eq!(Math::relative_chunk_coords(chunk, offset), (x, z));
+            prop_assert_eq!(Math::checked_relative_chunk_coords(chunk, offset), Ok((x, z)));
+            prop_assert_eq!(
+                Math::checked_relative_chunk_coords(chunk, (0, 0))
+                    .map(Math::block_coords_to_chunk_coords),
+                Ok(chunk)
+            );
+        }
+
+        #[test]
+        fn prop_checked_coords_refuse_outside_of_border(x in any::<i32>(), z in any::<i32>()) {
+            let inside = x.unsigned_abs() < 30_000_000 && z.unsigned_abs() < 30_000_000;
+            prop_assert_eq!(Math::is_inside_world_border((x, z)), inside);
+            match Math::checked_block_coords_to_chunk_coords((x, z)) {
+                Ok(chunk) => {
+                    prop_assert!(inside);
+                    prop_assert_eq!(chunk, Math::block_coords_to_chunk_coords((x, z)));
+                }
+                Err(err) => {
+                    prop_assert!(!inside);
+                    prop_assert_eq!(err, OutOfWorldBorder { x: x as i64, z: z as i64 });
+                }
+            }
+        }
+
+        #[test]
+        fn prop_checked_relative_coords_never_wrap(
+            chunk in (any::<i32>(), any::<i32>()),
+            block in (any::<i32>(), any::<i32>()),
+        ) {
+            let x = (chunk.0 as i64).wrapping_mul(16).wrapping_add(block.0 as i64);
+            let z = (chunk.1 as i64).wrapping_mul(16).wrapping_add(block.1 as i64);
+            match Math::checked_relative_chunk_coords(chunk, block) {
+                Ok(pos) => {
+                    prop_assert_eq!((pos.0 as i64, pos.1 as i64), (x, z));
+                    prop_assert_eq!(pos, Math::relative_chunk_coords(chunk, block));
+                }
+                Err(err) => prop_assert_eq!(err, OutOfWorldBorder { x, z }),
+            }
+        }
+    }
 }
@@ -0,0 +1,2060 @@
+//! Turns the collected evidence into the search jobs of the pillar seed → structure seed → world seed pipeline.
+//!
+//! Both the real search and [`CrackingPipeline::plan`] go through the same job builders, so the plan always shows
+//! what the search is going to do.
+
+use std::{fmt::Display, sync::Arc};
+
+use cubiomes::enums::MCVersion;
+
+use crate::{
+    candidates::{PillarSeedCandidate, StructureSeedCandidate, WorldSeedCandidate},
+    cooperative::{Cooperation, SearchOptions},
+    features::{
+        buried_treasure::{self, ChunkArea, build_fast_inventory_compare_context},
+        end_pillars::{
+            EndPillars, InvalidPillarObservation, PartialEndPillar, PartialEndPillars,
+            PillarMatchResult,
+        },
+    },
+    loot_table::{
+        CountBound, FastInventoryCompareContext, InvalidObservation, ItemCountConstraints,
+        LootTable, SingleChest, SlotThreshold,
+    },
+    math::{Math, OutOfWorldBorder},
+    rarity::BiomeRarityTable,
+    schedule::{EarlyTermination, MatchStrength, StructureStageScheduler},
+    search::{
+        ConstraintError, DEFAULT_VERSION, KnownStructure, StructureData, StructureSeedAnnotation,
+        StructureSeedSearchData, WorldExtraData, WorldSeedSearchData, rank_structure_seeds,
+        typed_completions_passing,
+    },
+    templates::find_template,
+    utils::format::format_count,
+    world_seed::SeedOrigin,
+};
+
+/// When an observation was made. Loot only generates once and the dragon can be fought again, so evidence seen after
+/// the world was played on may no longer be what the seed generated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ObservedAt {
+    /// Free text, e.g. `"day 3"` or a date
+    pub when: String,
+    /// False if the world was fresh, true if it might have been modified before the observation
+    pub might_be_modified: bool,
+}
+
+impl ObservedAt {
+    #[inline]
+    pub fn new(when: impl Into<String>, might_be_modified: bool) -> Self {
+        Self {
+            when: when.into(),
+            might_be_modified,
+        }
+    }
+
+    /// True if the observation is flagged as possibly modified
+    #[inline]
+    pub fn is_modified(observed_at: &Option<ObservedAt>) -> bool {
+        observed_at.as_ref().is_some_and(|o| o.might_be_modified)
+    }
+}
+
+impl Display for ObservedAt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.when.is_empty() {
+            write!(f, "{}, ", self.when)?;
+        }
+        write!(
+            f,
+            "{}",
+            if self.might_be_modified {
+                "might have been modified"
+            } else {
+                "fresh world"
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuriedTreasureEvidence {
+    pub pos_x: i32,
+    pub pos_z: i32,
+    pub luck: f32,
+    pub contents: SingleChest,
+    /// The opposite corner, in blocks, of the area the chest is in when its chunk isn't known. `pos_x` and `pos_z`
+    /// are then the first corner.
+    pub area_corner: Option<(i32, i32)>,
+    pub observed_at: Option<ObservedAt>,
+}
+
+/// A buried treasure of which only some item totals are known, see [`ItemCountConstraints`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuriedTreasureCountEvidence {
+    pub pos_x: i32,
+    pub pos_z: i32,
+    pub luck: f32,
+    pub counts: Vec<(usize, CountBound)>,
+    pub observed_at: Option<ObservedAt>,
+}
+
+#[derive(Clone, Default)]
+pub struct CrackingEvidence {
+    pub pillars: PartialEndPillars,
+    pub pillars_observed_at: Option<ObservedAt>,
+    /// Every one of them has to match, each chest is in its own chunk or area
+    pub buried_treasures: Vec<BuriedTreasureEvidence>,
+    pub buried_treasure_counts: Option<BuriedTreasureCountEvidence>,
+    /// Replaces the vanilla buried treasure loot table
+    pub buried_treasure_loot_table: Option<Arc<LootTable>>,
+    pub world: Vec<WorldExtraData>,
+    /// Structure evidence without a field of its own, searched as it is
+    pub structures: Vec<StructureData>,
+    /// Of the biome samples of `world`, they don't change once generated
+    pub biomes_observed_at: Option<ObservedAt>,
+    /// Of the decoration counts of `world`
+    pub decorations_observed_at: Option<ObservedAt>,
+}
+
+impl CrackingEvidence {
+    /// True if any of the evidence has an observation time
+    pub fn has_observation_times(&self) -> bool {
+        self.pillars_observed_at.is_some()
+            || self
+                .buried_treasures
+                .iter()
+                .any(|bt| bt.observed_at.is_some())
+            || self
+                .buried_treasure_counts
+                .as_ref()
+                .is_some_and(|bt| bt.observed_at.is_some())
+            || self.biomes_observed_at.is_some()
+            || self.decorations_observed_at.is_some()
+    }
+}
+
+/// Evidence that might have been changed by gameplay since it generated, see [`ObservedAt`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineWarning {
+    /// The exact chest contents are compared, but items may have been taken or moved
+    ModifiedChest,
+    /// Number of bounds with a maximum on the item counts of a chest that may have been looted
+    ModifiedChestUpperBounds(usize),
+    /// Indices of the pillars observed without a cage, which may have been broken
+    ModifiedUncagedPillars(Vec<usize>),
+    /// The decorations may have been cut or picked
+    ModifiedDecorations,
+}
+
+impl Display for TimelineWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ModifiedChest => write!(
+                f,
+                "Buried treasure: the chest might have been modified, use the item counts you are sure of instead of the exact contents"
+            ),
+            Self::ModifiedChestUpperBounds(count) => write!(
+                f,
+                "Buried treasure: {} item count(s) are exact or at most, a looted chest only gives lower bounds",
+                count
+            ),
+            Self::ModifiedUncagedPillars(pillars) => write!(
+                f,
+                "End pillars: the cages of pillar(s) {} might have been broken, or rebuilt by a respawned dragon, leave their cage unknown",
+                pillars
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::ModifiedDecorations => write!(
+                f,
+                "Decorations: the counts might have changed, they only rank the results"
+            ),
+        }
+    }
+}
+
+/// See [`PipelineOptions::max_treasure_area`]
+pub const DEFAULT_MAX_TREASURE_AREA: u64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineOptions {
+    /// The structure seed search only runs if there are at most this many pillar seeds left
+    pub max_pillar_seeds: usize,
+    /// The world seed search only runs if there are at most this many structure seeds left
+    pub max_structure_seeds: usize,
+    pub max_structure_seeds_per_pillar_seed: u16,
+    pub max_world_seeds_per_structure_seed: u16,
+    pub is_random_world_seed: bool,
+    /// How the world seed was chosen, if known. Anything but [`SeedOrigin::Any`] replaces
+    /// [`Self::is_random_world_seed`], see [`WorldSeedSearchData::origin`]
+    pub origin: SeedOrigin,
+    /// Largest buried treasure area in chunks, the structure seed search checks each of its chunks
+    pub max_treasure_area: u64,
+    /// Searches leave time to the frontend, see [`Cooperation`]
+    pub cooperation: Option<Cooperation>,
+    /// Threads and progress granularity of the parallel searches
+    pub search: SearchOptions,
+    /// Accepts buried treasures matching only most of the observed slots
+    pub slot_threshold: Option<SlotThreshold>,
+    /// Checks the typed completions of every structure seed against the world evidence as soon as it is found, see
+    /// [`CrackingPipeline::early_typed_hits`]
+    pub early_typed_hits: bool,
+    /// When the structure stage stops before searching every pillar seed, see [`CrackingPipeline::structure_schedule`]
+    pub early_termination: EarlyTermination,
+    /// Drops the structure seeds no generated world seed has, see [`StructureSeedSearchData::nextlong_only`]. Off by
+    /// default, it also drops about 11% of the typed seeds that [`Self::is_random_world_seed`] still tries.
+    pub nextlong_only: bool,
+    /// Of the world the evidence comes from, the world seed stage checks its biomes, see [`crate::search::SUPPORTED_VERSIONS`]
+    pub version: MCVersion,
+}
+
+impl Default for PipelineOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_pillar_seeds: 5,
+            max_structure_seeds: 5,
+            max_structure_seeds_per_pillar_seed: 10,
+            max_world_seeds_per_structure_seed: 5,
+            is_random_world_seed: true,
+            origin: SeedOrigin::Any,
+            max_treasure_area: DEFAULT_MAX_TREASURE_AREA,
+            cooperation: None,
+            search: SearchOptions::DEFAULT,
+            slot_threshold: None,
+            early_typed_hits: false,
+            early_termination: EarlyTermination::Never,
+            nextlong_only: false,
+            version: DEFAULT_VERSION,
+        }
+    }
+}
+
+/// A buried treasure area larger than [`PipelineOptions::max_treasure_area`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OversizedArea {
+    pub chunks: u64,
+    pub limit: u64,
+}
+
+impl Display for OversizedArea {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the area covers {} chunks, the limit is {}",
+            self.chunks, self.limit
+        )
+    }
+}
+
+impl std::error::Error for OversizedArea {}
+
+/// What a buried treasure area adds to the structure seed search, see [`CrackingPipeline::treasure_area_cost`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreasureAreaCost {
+    /// Checked for every candidate, the structure seed search takes up to this many times longer
+    pub chunks: u64,
+    /// Chunks of the area where a buried treasure generates for an average candidate, each of their chests is
+    /// generated and compared to the observed one
+    pub treasures_per_candidate: f64,
+    /// Structure seeds of a pillar seed expected to match one of these chests by chance
+    pub false_matches_per_pillar_seed: f64,
+}
+
+impl Display for TreasureAreaCost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} chunks per candidate, about {:.2} chest(s) compared per candidate and {:.4} chance match(es) per pillar seed",
+            self.chunks, self.treasures_per_candidate, self.false_matches_per_pillar_seed
+        )
+    }
+}
+
+/// Human readable summary of a structure evidence item
+pub(crate) fn describe_structure_data(data: &StructureData) -> String {
+    match data {
+        StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck,
+            contents,
+            loot_table,
+        } => format!(
+            "Buried treasure contents in chunk ({}, {}), {} items, luck {}{}",
+            chunk_x,
+            chunk_z,
+            contents.total_items,
+            luck,
+            if loot_table.is_some() {
+                ", custom loot table"
+            } else {
+                ""
+            }
+        ),
+        StructureData::BuriedTreasureCounts {
+            chunk_x,
+            chunk_z,
+            luck,
+            counts,
+            loot_table,
+        } => format!(
+            "Buried treasure item counts in chunk ({}, {}), {} bound(s), luck {}{}",
+            chunk_x,
+            chunk_z,
+            counts.bounds.len(),
+            luck,
+            if loot_table.is_some() {
+                ", custom loot table"
+            } else {
+                ""
+            }
+        ),
+        StructureData::BuriedTreasureContentsInArea {
+            area,
+            luck,
+            contents,
+            loot_table,
+        } => format!(
+            "Buried treasure contents in one of the {} chunks from ({}, {}) to ({}, {}), {} items, luck {}{}",
+            area.chunk_count(),
+            area.min.0,
+            area.min.1,
+            area.max.0,
+            area.max.1,
+            contents.total_items,
+            luck,
+            if loot_table.is_some() {
+                ", custom loot table"
+            } else {
+                ""
+            }
+        ),
+        StructureData::BastionChestContents {
+            chunk_x,
+            chunk_z,
+            index,
+            chest_type,
+            luck,
+            contents,
+            ..
+        } => format!(
+            "Bastion {} chest in chunk ({}, {}), {}, {} items, luck {}",
+            chest_type.name(),
+            chunk_x,
+            chunk_z,
+            index,
+            contents.total_items,
+            luck
+        ),
+    }
+}
+
+/// Outcome of [`CrackingPipeline::check_evidence`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvidenceCheck {
+    /// Evidence no seed can match or that can't be checked, the search must not start until it is fixed
+    pub errors: Vec<String>,
+    /// Evidence the search runs with, but that may keep it from finding the seed
+    pub warnings: Vec<String>,
+    pub pillar_seed_count: usize,
+    /// See [`CrackingPipeline::estimated_bits`]
+    pub estimated_bits: f64,
+}
+
+impl EvidenceCheck {
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// Outcome of [`CrackingPipeline::reverify`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reverification {
+    /// The seeds consistent with all the evidence, in their original order
+    pub kept: Vec<i64>,
+    /// The other seeds, with the first evidence they contradict
+    pub eliminated: Vec<(i64, String)>,
+}
+
+impl Reverification {
+    #[inline]
+    pub fn checked(&self) -> usize {
+        self.kept.len() + self.eliminated.len()
+    }
+}
+
+impl Display for Reverification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} structure seeds eliminated in re-verification",
+            self.eliminated.len(),
+            self.checked()
+        )
+    }
+}
+
+/// World seeds found by [`CrackingPipeline::early_typed_hits`] while the structure seed stage runs. The world seed
+/// stage still runs and finds them again, a hit is only pending until then so no seed is counted twice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EarlyHits {
+    pending: Vec<WorldSeedCandidate>,
+    /// Seeds the world seed stage found, they are never pending again
+    refound: Vec<i64>,
+}
+
+impl EarlyHits {
+    /// Adds the hits that are neither pending nor already found by the world seed stage, returns how many were added
+    pub fn record(&mut self, hits: impl IntoIterator<Item = WorldSeedCandidate>) -> usize {
+        let before = self.pending.len();
+        for hit in hits {
+            if !self.refound.contains(&hit.seed) && !self.pending.iter().any(|p| p.seed == hit.seed)
+            {
+                self.pending.push(hit);
+            }
+        }
+        self.pending.len() - before
+    }
+
+    /// Called with the results of the world seed stage, the hits among them are listed with these results from now on
+    pub fn refound(&mut self, world_seeds: &[WorldSeedCandidate]) {
+        self.refound.extend(world_seeds.iter().map(|w| w.seed));
+        self.pending
+            .retain(|p| !world_seeds.iter().any(|w| w.seed == p.seed));
+    }
+
+    /// The hits the world seed stage hasn't found (yet)
+    #[inline]
+    pub fn pending(&self) -> &[WorldSeedCandidate] {
+        &self.pending
+    }
+}
+
+pub struct CrackingPipeline;
+
+impl CrackingPipeline {
+    /// All the pillar seeds that could have generated the observed pillars
+    pub fn pillar_seeds(evidence: &CrackingEvidence) -> Vec<(i64, PillarMatchResult)> {
+        let mut results = Vec::new();
+        evidence.pillars.for_each_matching(|seed, result| {
+            if !matches!(result, PillarMatchResult::PossibleMatch(v) if v == 0.0) {
+                results.push((seed, result));
+            }
+        });
+        results
+    }
+
+    /// The [`CrackingPipeline::pillar_seeds`] as records for the frontends
+    pub fn pillar_candidates(evidence: &CrackingEvidence) -> Vec<PillarSeedCandidate> {
+        Self::pillar_seeds(evidence)
+            .into_iter()
+            .map(|(seed, result)| PillarSeedCandidate::new(seed, result))
+            .collect()
+    }
+
+    #[inline]
+    fn buried_treasure_context(
+        evidence: &CrackingEvidence,
+        bt: &BuriedTreasureEvidence,
+    ) -> Result<FastInventoryCompareContext<SingleChest, 12>, InvalidObservation> {
+        match &evidence.buried_treasure_loot_table {
+            Some(loot_table) => {
+                build_fast_inventory_compare_context(bt.contents.clone(), loot_table)
+            }
+            None => build_fast_inventory_compare_context(
+                bt.contents.clone(),
+                &buried_treasure::get_loot_table(),
+            ),
+        }
+    }
+
+    /// Observed pillars that no pillar layout can satisfy, e.g. three caged pillars. No pillar seed can match them.
+    #[inline]
+    pub fn invalid_pillars(evidence: &CrackingEvidence) -> Option<InvalidPillarObservation> {
+        evidence.pillars.validate().err()
+    }
+
+    /// Observed chests holding items their loot table can't generate. No structure seed can match them, so the
+    /// structure seed search doesn't run until they are fixed.
+    pub fn invalid_observations(evidence: &CrackingEvidence) -> Vec<InvalidObservation> {
+        evidence
+            .buried_treasures
+            .iter()
+            .filter_map(|bt| Self::buried_treasure_context(evidence, bt).err())
+            .collect()
+    }
+
+    /// Item counts that no chest of their loot table can hold, e.g. a required item it never generates. No structure
+    /// seed can match them, so the structure seed search doesn't run until they are fixed.
+    pub fn invalid_count_bounds(evidence: &CrackingEvidence) -> Vec<(usize, CountBound)> {
+        let Some(bt) = &evidence.buried_treasure_counts else {
+            return Vec::new();
+        };
+        let counts = ItemCountConstraints::<12>::new(bt.counts.clone());
+        match &evidence.buried_treasure_loot_table {
+            Some(loot_table) => counts.unsatisfiable_bounds(loot_table),
+            None => counts.unsatisfiable_bounds(&buried_treasure::get_loot_table()),
+        }
+    }
+
+    /// Observed structures outside of the world border. Their chunk coordinates would be meaningless, so the
+    /// structure seed search doesn't run until they are fixed.
+    pub fn invalid_positions(evidence: &CrackingEvidence) -> Vec<OutOfWorldBorder> {
+        let bt_counts = evidence.buried_treasure_counts.iter();
+        let area_corners = evidence
+            .buried_treasures
+            .iter()
+            .filter_map(|bt| bt.area_corner);
+        evidence
+            .buried_treasures
+            .iter()
+            .map(|bt| (bt.pos_x, bt.pos_z))
+            .chain(area_corners)
+            .chain(bt_counts.map(|bt| (bt.pos_x, bt.pos_z)))
+            .filter_map(|pos| Math::checked_block_coords_to_chunk_coords(pos).err())
+            .collect()
+    }
+
+    /// The chunk corners of the buried treasure area, if the evidence gives one and it is inside of the world border
+    fn treasure_area_corners(bt: &BuriedTreasureEvidence) -> Option<((i32, i32), (i32, i32))> {
+        let corner = bt.area_corner?;
+        let a = Math::checked_block_coords_to_chunk_coords((bt.pos_x, bt.pos_z)).ok()?;
+        let b = Math::checked_block_coords_to_chunk_coords(corner).ok()?;
+        Some((a, b))
+    }
+
+    /// Buried treasure areas with more chunks than the options allow. Every chunk of an area is checked for every
+    /// candidate, so the structure seed search doesn't run until they are made smaller.
+    pub fn oversized_areas(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+    ) -> Vec<OversizedArea> {
+        evidence
+            .buried_treasures
+            .iter()
+            .filter_map(Self::treasure_area_corners)
+            .map(|(a, b)| ChunkArea::count_chunks(a, b))
+            .filter(|chunks| *chunks > options.max_treasure_area)
+            .map(|chunks| OversizedArea {
+                chunks,
+                limit: options.max_treasure_area,
+            })
+            .collect()
+    }
+
+    /// The cost of the largest buried treasure area, if the evidence gives one inside of the world border. Exact chest
+    /// contents are counted for the bits of the buried treasure template, like in [`CrackingPipeline::estimated_bits`].
+    pub fn treasure_area_cost(evidence: &CrackingEvidence) -> Option<TreasureAreaCost> {
+        let chunks = evidence
+            .buried_treasures
+            .iter()
+            .filter_map(Self::treasure_area_corners)
+            .map(|(a, b)| ChunkArea::count_chunks(a, b))
+            .max()?;
+        let contents_bits = find_template("buried_treasure").map_or(0.0, |t| t.estimated_bits);
+        let treasures = chunks as f64 * buried_treasure::PROB as f64;
+        Some(TreasureAreaCost {
+            chunks,
+            treasures_per_candidate: treasures,
+            false_matches_per_pillar_seed: treasures * 2f64.powf(32.0 - contents_bits),
+        })
+    }
+
+    /// The structure evidence, without the invalid observations, counts and positions and the oversized areas
+    pub fn structure_data(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+    ) -> Vec<StructureData> {
+        let mut data = Vec::new();
+        for bt in evidence.buried_treasures.iter() {
+            let contents = Self::buried_treasure_context(evidence, bt)
+                .map(|contents| contents.with_slot_threshold(options.slot_threshold));
+            let loot_table = evidence.buried_treasure_loot_table.clone();
+            match (bt.area_corner, contents) {
+                (None, Ok(contents)) => {
+                    if let Ok(c) = Math::checked_block_coords_to_chunk_coords((bt.pos_x, bt.pos_z))
+                    {
+                        data.push(StructureData::BuriedTreasureContents {
+                            chunk_x: c.0,
+                            chunk_z: c.1,
+                            luck: bt.luck,
+                            contents,
+                            loot_table,
+                        });
+                    }
+                }
+                (Some(_), Ok(contents)) => {
+                    let corners = Self::treasure_area_corners(bt).filter(|(a, b)| {
+                        ChunkArea::count_chunks(*a, *b) <= options.max_treasure_area
+                    });
+                    if let Some((a, b)) = corners {
+                        data.push(StructureData::BuriedTreasureContentsInArea {
+                            area: ChunkArea::new(a, b),
+                            luck: bt.luck,
+                            contents,
+                            loot_table,
+                        });
+                    }
+                }
+                (_, Err(_)) => {}
+            }
+        }
+        if let Some(bt) = &evidence.buried_treasure_counts {
+            let position = Math::checked_block_coords_to_chunk_coords((bt.pos_x, bt.pos_z))
+                .ok()
+                .filter(|_| Self::invalid_count_bounds(evidence).is_empty());
+            if let Some(c) = position {
+                data.push(StructureData::BuriedTreasureCounts {
+                    chunk_x: c.0,
+                    chunk_z: c.1,
+                    luck: bt.luck,
+                    counts: ItemCountConstraints::new(bt.counts.clone()),
+                    loot_table: evidence.buried_treasure_loot_table.clone(),
+                });
+            }
+        }
+        data.extend(evidence.structures.iter().cloned());
+        data
+    }
+
+    /// One job per pillar seed, most likely pillar seeds first. Empty if there are too many pillar seeds or if some
+    /// observations or positions are invalid.
+    pub fn structure_jobs(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        pillar_seeds: &[(i64, PillarMatchResult)],
+    ) -> Vec<StructureSeedSearchData> {
+        if !Self::invalid_observations(evidence).is_empty()
+            || !Self::invalid_count_bounds(evidence).is_empty()
+            || !Self::invalid_positions(evidence).is_empty()
+            || !Self::oversized_areas(evidence, options).is_empty()
+        {
+            return Vec::new();
+        }
+        let mut pillar_seeds = pillar_seeds
+            .iter()
+            .filter(|p| !p.1.is_impossible_match())
+            .collect::<Vec<_>>();
+        if pillar_seeds.len() > options.max_pillar_seeds {
+            return Vec::new();
+        }
+        pillar_seeds.sort_by(|a, b| b.1.compare(&a.1));
+
+        let data = Self::structure_data(evidence, options);
+        pillar_seeds
+            .into_iter()
+            .map(|pillar_seed| {
+                StructureSeedSearchData::new(
+                    pillar_seed.0,
+                    data.clone(),
+                    options.max_structure_seeds_per_pillar_seed,
+                )
+                .with_cooperation(options.cooperation)
+                .with_search_options(options.search)
+                .with_nextlong_only(options.nextlong_only)
+                .with_pillar_chance(pillar_seed.1.chance())
+            })
+            .collect()
+    }
+
+    /// The order of the structure jobs with the early termination policy of the options. A job searches the whole
+    /// range of its pillar seed, so each pillar seed is a single slice.
+    pub fn structure_schedule(
+        options: &PipelineOptions,
+        jobs: &[StructureSeedSearchData],
+    ) -> StructureStageScheduler {
+        StructureStageScheduler::new(
+            jobs.iter().map(|job| job.pillar_seed),
+            1,
+            options.early_termination,
+        )
+    }
+
+    /// [`MatchStrength::Exact`] if a found structure seed satisfies every piece of evidence exactly: the pillars of
+    /// its pillar seed match exactly and the chests match through the full loot generation, without a slot threshold
+    pub fn match_strength(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        structure_seed: i64,
+    ) -> MatchStrength {
+        let mut pillars = EndPillars::new();
+        pillars.from_seed(EndPillars::pillar_seed(structure_seed));
+        let exact = matches!(
+            evidence.pillars.matches(&pillars),
+            PillarMatchResult::ExactMatch
+        ) && options.slot_threshold.is_none()
+            && Self::structure_data(evidence, options)
+                .iter()
+                .all(|d| d.verify_seed(structure_seed));
+        if exact {
+            MatchStrength::Exact
+        } else {
+            MatchStrength::Partial
+        }
+    }
+
+    /// Re-checks the found structure seeds against all the evidence on their lower 48 bits at full precision: the
+    /// observed pillars against the seed's own pillar seed, and the chests through the full loot generation. There
+    /// are only a few of them, and each one surviving costs a world seed job. `progress` is called with the number of
+    /// seeds checked so far.
+    pub fn reverify(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        structure_seeds: &[i64],
+        mut progress: impl FnMut(usize),
+    ) -> Reverification {
+        let data = Self::structure_data(evidence, options);
+        let mut pillars = EndPillars::new();
+        let mut reverification = Reverification::default();
+        for (i, &structure_seed) in structure_seeds.iter().enumerate() {
+            pillars.from_seed(EndPillars::pillar_seed(structure_seed));
+            let contradicted = if evidence.pillars.matches(&pillars).is_impossible_match() {
+                Some("Observed pillars".to_string())
+            } else {
+                data.iter()
+                    .find(|d| !d.verify_seed(structure_seed))
+                    .map(describe_structure_data)
+            };
+            match contradicted {
+                Some(reason) => reverification.eliminated.push((structure_seed, reason)),
+                None => reverification.kept.push(structure_seed),
+            }
+            progress(i + 1);
+        }
+        reverification
+    }
+
+    /// The world seeds of a structure seed that was just found, in case it was typed as a number: its typed
+    /// completions are checked against the world evidence, a few biome lookups instead of a world seed job. Lucky
+    /// users can skip the world seed stage. Empty unless [`PipelineOptions::early_typed_hits`] is set, or if there are
+    /// no biome samples to check.
+    pub fn early_typed_hits(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        structure_seed: i64,
+    ) -> Vec<WorldSeedCandidate> {
+        if !options.early_typed_hits {
+            return Vec::new();
+        }
+        typed_completions_passing(structure_seed, &evidence.world, options.version)
+            .into_iter()
+            .map(|seed| WorldSeedCandidate::new(structure_seed, seed, None))
+            .collect()
+    }
+
+    /// One job per structure seed. Empty if there are no structure seeds or too many of them.
+    pub fn world_jobs(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        structure_seeds: &[i64],
+    ) -> Vec<WorldSeedSearchData> {
+        if structure_seeds.is_empty() || structure_seeds.len() > options.max_structure_seeds {
+            return Vec::new();
+        }
+
+        // without observed pillars every pillar seed matches, there is nothing to cross-check
+        let mut pillar_seeds = Self::pillar_seeds(evidence)
+            .into_iter()
+            .map(|(seed, _)| seed)
+            .collect::<Vec<_>>();
+        pillar_seeds.sort_unstable();
+        let pillar_seeds = (pillar_seeds.len() < 65536).then(|| Arc::<[i64]>::from(pillar_seeds));
+        structure_seeds
+            .iter()
+            .map(|&structure_seed| {
+                WorldSeedSearchData::new(
+                    structure_seed,
+                    options.is_random_world_seed,
+                    evidence.world.clone(),
+                    options.max_world_seeds_per_structure_seed,
+                )
+                .with_cooperation(options.cooperation)
+                .with_search_options(options.search)
+                .with_origin(options.origin)
+                .with_version(options.version)
+                .with_pillar_seeds(pillar_seeds.clone())
+            })
+            .collect()
+    }
+
+    /// The found `(pillar seed, structure seed)` pairs, annotated with the structure evidence they satisfy and scored
+    /// with how well the pillars of the pillar seed match
+    pub fn structure_candidates(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        found: &[(i64, i64)],
+    ) -> Vec<StructureSeedCandidate> {
+        let data = Self::structure_data(evidence, options);
+        let mut pillars = EndPillars::new();
+        found
+            .iter()
+            .map(|&(pillar_seed, seed)| {
+                pillars.from_seed(pillar_seed);
+                let pillar_chance = evidence.pillars.matches(&pillars).chance();
+                StructureSeedCandidate::new(&data, seed, pillar_seed, pillar_chance)
+            })
+            .collect()
+    }
+
+    /// The best supported `options.max_structure_seeds` of the found `(pillar seed, structure seed)` pairs, for
+    /// continuing to the world seed search anyway when too many structure seeds were found
+    pub fn top_structure_seeds(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+        found: &[(i64, i64)],
+    ) -> Vec<i64> {
+        let pillar_seeds = Self::pillar_seeds(evidence);
+        let data = Self::structure_data(evidence, options);
+        let mut annotations = found
+            .iter()
+            .map(|&(pillar_seed, structure_seed)| {
+                let pillar_match = pillar_seeds
+                    .iter()
+                    .find(|p| p.0 == pillar_seed)
+                    .map(|p| p.1)
+                    .unwrap_or(PillarMatchResult::ImpossibleMatch);
+                StructureSeedAnnotation::new(&data, structure_seed, pillar_seed, pillar_match)
+            })
+            .collect::<Vec<_>>();
+        rank_structure_seeds(&mut annotations);
+        annotations
+            .into_iter()
+            .take(options.max_structure_seeds)
+            .map(|a| a.structure_seed)
+            .collect()
+    }
+
+    /// Evidence rows that can't be checked for any seed. The world seed search skips every candidate it tests them on,
+    /// so frontends should refuse to start it until they are fixed.
+    pub fn invalid_samples(
+        evidence: &CrackingEvidence,
+        options: &PipelineOptions,
+    ) -> Vec<ConstraintError> {
+        evidence
+            .world
+            .iter()
+            .flat_map(|d| d.invalid_samples(options.version))
+            .collect()
+    }
+
+    /// Evidence flagged as possibly modified that the search still compares as if it was freshly generated
+    pub fn timeline_warnings(evidence: &CrackingEvidence) -> Vec<TimelineWarning> {
+        let mut warnings = Vec::new();
+        if evidence
+            .buried_treasures
+            .iter()
+            .any(|bt| ObservedAt::is_modified(&bt.observed_at))
+        {
+            warnings.push(TimelineWarning::ModifiedChest);
+        }
+        if let Some(counts) = evidence
+            .buried_treasure_counts
+            .as_ref()
+            .filter(|bt| ObservedAt::is_modified(&bt.observed_at))
+        {
+            let upper_bounds = counts
+                .counts
+                .iter()
+                .filter(|(_, bound)| !matches!(bound, CountBound::AtLeast(_)))
+                .count();
+            if upper_bounds > 0 {
+                warnings.push(TimelineWarning::ModifiedChestUpperBounds(upper_bounds));
+            }
+        }
+        if ObservedAt::is_modified(&evidence.pillars_observed_at) {
+            let uncaged = evidence
+                .pillars
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.caged == Some(false))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            if !uncaged.is_empty() {
+                warnings.push(TimelineWarning::ModifiedUncagedPillars(uncaged));
+            }
+        }
+        if ObservedAt::is_modified(&evidence.decorations_observed_at)
+            && evidence.world.iter().any(|d| d.is_tie_breaker())
+        {
+            warnings.push(TimelineWarning::ModifiedDecorations);
+        }
+        warnings
+    }
+
+    /// Rough bits of seed information the evidence gives, out of the 64 bits of a world seed. The pillar seeds left and
+    /// the buried treasures narrow down the 48 bits of the structure seed, the biome samples the upper 16 bits and the
+    /// decorations only rank the results. Exact chest contents count for the bits of the buried treasure template,
+    /// item counts only for the treasure being in its chunk.
+    pub fn estimated_bits(evidence: &CrackingEvidence, pillar_seed_count: usize) -> f64 {
+        let pillar_bits = if pillar_seed_count == 0 {
+            0.0
+        } else {
+            (65536.0 / pillar_seed_count as f64).log2()
+        };
+        let mut structure_bits = 0.0;
+        for bt in evidence.buried_treasures.iter() {
+            let chunks =
+                Self::treasure_area_corners(bt).map_or(1, |(a, b)| ChunkArea::count_chunks(a, b));
+            let contents_bits = find_template("buried_treasure").map_or(0.0, |t| t.estimated_bits);
+            structure_bits += (contents_bits - (chunks as f64).log2()).max(0.0);
+        }
+        if evidence.buried_treasure_counts.is_some() {
+            structure_bits += -(buried_treasure::PROB as f64).log2();
+        }
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+        let world_bits = evidence
+            .world
+            .iter()
+            .filter(|d| !d.is_tie_breaker())
+            .map(|d| -d.pass_probability(table).log2())
+            .sum::<f64>();
+        (pillar_bits + structure_bits).min(48.0) + world_bits.min(16.0)
+    }
+
+    /// The checks of [`CrackingPipeline::plan`] without building any search job, to validate evidence quickly
+    pub fn check_evidence(evidence: &CrackingEvidence, options: &PipelineOptions) -> EvidenceCheck {
+        let invalid_pillars = Self::invalid_pillars(evidence);
+        let pillar_seed_count = match invalid_pillars {
+            Some(_) => 0,
+            None => Self::pillar_seeds(evidence).len(),
+        };
+
+        let mut errors = invalid_evidence_lines(
+            &Self::invalid_samples(evidence, options),
+            invalid_pillars.as_ref(),
+            &Self::invalid_observations(evidence),
+            &Self::invalid_count_bounds(evidence),
+            &Self::invalid_positions(evidence),
+            &Self::oversized_areas(evidence, options),
+        );
+        if invalid_pillars.is_none() && pillar_seed_count == 0 {
+            errors.push("End pillars: no pillar seed generates the observed pillars".to_string());
+        }
+
+        let mut warnings = Self::timeline_warnings(evidence)
+            .iter()
+            .map(|warning| warning.to_string())
+            .collect::<Vec<_>>();
+        if pillar_seed_count > options.max_pillar_seeds {
+            warnings.push(format!(
+                "End pillars: {} pillar seeds left, the structure seed search only runs with at most {}",
+                pillar_seed_count, options.max_pillar_seeds
+            ));
+        }
+
+        EvidenceCheck {
+            errors,
+            warnings,
+            pillar_seed_count,
+            estimated_bits: Self::estimated_bits(evidence, pillar_seed_count),
+        }
+    }
+
+    pub fn plan(evidence: &CrackingEvidence, options: &PipelineOptions) -> SearchPlan {
+        let pillar_seeds = Self::pillar_seeds(evidence);
+        let structure_jobs = Self::structure_jobs(evidence, options, &pillar_seeds);
+        let invalid_pillars = Self::invalid_pillars(evidence);
+        let invalid_observations = Self::invalid_observations(evidence);
+        let invalid_count_bounds = Self::invalid_count_bounds(evidence);
+        let invalid_positions = Self::invalid_positions(evidence);
+        let oversized_areas = Self::oversized_areas(evidence, options);
+
+        let mut disabled_filters = Vec::new();
+
+        let observed_pillars = evidence
+            .pillars
+            .iter()
+            .filter(|p| **p != PartialEndPillar::default())
+            .count();
+        let pillar_stage = PlannedStage {
+            kind: StageKind::PillarSeeds,
+            name: "Pillar seeds",
+            evidence: vec![format!("{} observed pillar(s)", observed_pillars)],
+            jobs: 1,
+            jobs_upper_bound: false,
+            candidates_per_job: 1 << 16,
+        };
+
+        let structure_data = structure_jobs
+            .first()
+            .map(|job| job.data.clone())
+            .unwrap_or_else(|| Self::structure_data(evidence, options));
+        if invalid_pillars.is_some() {
+            disabled_filters
+                .push("Structure seed search: the observed pillars are invalid".to_string());
+        } else if !invalid_observations.is_empty() {
+            disabled_filters
+                .push("Structure seed search: the observed chest contents are invalid".to_string());
+        } else if !invalid_count_bounds.is_empty() {
+            disabled_filters.push(
+                "Structure seed search: the buried treasure item counts can't be satisfied"
+                    .to_string(),
+            );
+        } else if !invalid_positions.is_empty() {
+            disabled_filters.push(
+                "Structure seed search: the observed buried treasure is outside of the world border"
+                    .to_string(),
+            );
+        } else if !oversized_areas.is_empty() {
+            disabled_filters
+                .push("Structure seed search: the buried treasure area is too large".to_string());
+        } else if structure_jobs.is_empty() {
+            disabled_filters.push(format!(
+                "Structure seed search: {} pillar seed(s) left, the limit is {}",
+                pillar_seeds.len(),
+                options.max_pillar_seeds
+            ));
+        }
+        if structure_data.is_empty() {
+            disabled_filters.push(format!(
+                "Structure seed search has no evidence: it stops after {} seeds per pillar seed",
+                options.max_structure_seeds_per_pillar_seed
+            ));
+        }
+        let structure_stage = PlannedStage {
+            kind: StageKind::StructureSeeds,
+            name: "Structure seeds",
+            evidence: structure_data.iter().map(describe_structure_data).collect(),
+            jobs: structure_jobs.len() as u64,
+            jobs_upper_bound: false,
+            candidates_per_job: 1 << 32,
+        };
+
+        let reverification_stage = PlannedStage {
+            kind: StageKind::Reverification,
+            name: "Re-verification",
+            evidence: if structure_data.is_empty() {
+                vec!["Observed pillars".to_string()]
+            } else {
+                vec![format!(
+                    "Observed pillars and {} structure evidence item(s), full loot generation",
+                    structure_data.len()
+                )]
+            },
+            jobs: structure_jobs.len() as u64 * options.max_structure_seeds_per_pillar_seed as u64,
+            jobs_upper_bound: true,
+            candidates_per_job: 1,
+        };
+
+        // The world seed jobs depend on the structure seeds found, only their upper bound is known here
+        let world_job = Self::world_jobs(evidence, options, &[0]).pop();
+        let world_data = world_job
+            .as_ref()
+            .map(|job| job.data.clone())
+            .unwrap_or_else(|| evidence.world.clone());
+        let mut world_evidence = Vec::new();
+        let mut hard_world_filters = 0;
+        for d in world_data.iter() {
+            match d {
+                WorldExtraData::OverworldBiomeData(biomes) if biomes.is_empty() => {
+                    disabled_filters.push("Overworld biome check: no biome samples".to_string());
+                }
+                WorldExtraData::NetherBiomeData(biomes) if biomes.is_empty() => {
+                    disabled_filters.push("Nether biome check: no biome samples".to_string());
+                }
+                WorldExtraData::OverworldBiomeData(biomes) => {
+                    hard_world_filters += 1;
+                    world_evidence.push(format!("{} overworld biome sample(s)", biomes.len()));
+                }
+                WorldExtraData::NetherBiomeData(biomes) => {
+                    hard_world_filters += 1;
+                    world_evidence.push(format!("{} nether biome sample(s)", biomes.len()));
+                }
+                WorldExtraData::DecorationCount {
+                    chunk, decoration, ..
+                } => {
+                    world_evidence.push(format!(
+                        "{} count in chunk ({}, {}), ranking only",
+                        decoration.name(),
+                        chunk.0,
+                        chunk.1
+                    ));
+                }
+                WorldExtraData::StructurePresence {
+                    structure_type,
+                    block_x,
+                    block_z,
+                    ..
+                } => {
+                    hard_world_filters += 1;
+                    world_evidence.push(format!(
+                        "{} at ({}, {})",
+                        KnownStructure::of(*structure_type).map_or("structure", |s| s.name()),
+                        block_x,
+                        block_z
+                    ));
+                }
+            }
+        }
+        if hard_world_filters == 0 {
+            disabled_filters.push(format!(
+                "World seed search has no evidence: it stops after {} seeds per structure seed",
+                options.max_world_seeds_per_structure_seed
+            ));
+        }
+        if world_job.is_some_and(|job| !job.is_random_world_seed && job.origin == SeedOrigin::Any) {
+            disabled_filters
+                .push("Random world seed shortcut: world seed may be custom".to_string());
+        }
+        let world_stage = PlannedStage {
+            kind: StageKind::WorldSeeds,
+            name: "World seeds",
+            evidence: world_evidence,
+            jobs: options.max_structure_seeds as u64,
+            jobs_upper_bound: true,
+            candidates_per_job: 1 << 16,
+        };
+
+        SearchPlan {
+            pillar_seed_count: pillar_seeds.len(),
+            pillar_seed_order: structure_jobs
+                .iter()
+                .map(|job| {
+                    let result = pillar_seeds
+                        .iter()
+                        .find(|p| p.0 == job.pillar_seed)
+                        .map(|p| p.1)
+                        .unwrap_or(PillarMatchResult::ImpossibleMatch);
+                    (job.pillar_seed, result)
+                })
+                .collect(),
+            stages: vec![
+                pillar_stage,
+                structure_stage,
+                reverification_stage,
+                world_stage,
+            ],
+            disabled_filters,
+            invalid_samples: Self::invalid_samples(evidence, options),
+            invalid_pillars,
+            invalid_observations,
+            invalid_count_bounds,
+            invalid_positions,
+            oversized_areas,
+            treasure_area_cost: Self::treasure_area_cost(evidence),
+            timeline_warnings: Self::timeline_warnings(evidence),
+        }
+    }
+}
+
+/// The stages of a [`SearchPlan`], in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageKind {
+    PillarSeeds,
+    StructureSeeds,
+    Reverification,
+    WorldSeeds,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedStage {
+    pub kind: StageKind,
+    pub name: &'static str,
+    /// Human readable summary of the evidence checked at this stage
+    pub evidence: Vec<String>,
+    pub jobs: u64,
+    /// If true, `jobs` is the maximum number of jobs, the actual count depends on the previous stage
+    pub jobs_upper_bound: bool,
+    pub candidates_per_job: u64,
+}
+
+impl PlannedStage {
+    #[inline]
+    pub const fn total_candidates(&self) -> u64 {
+        self.jobs.saturating_mul(self.candidates_per_job)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPlan {
+    pub pillar_seed_count: usize,
+    /// The pillar seeds searched by the structure seed stage, in order
+    pub pillar_seed_order: Vec<(i64, PillarMatchResult)>,
+    pub stages: Vec<PlannedStage>,
+    /// Filters that were turned off or that won't narrow anything down
+    pub disabled_filters: Vec<String>,
+    /// See [`CrackingPipeline::invalid_samples`]
+    pub invalid_samples: Vec<ConstraintError>,
+    /// See [`CrackingPipeline::invalid_pillars`]
+    pub invalid_pillars: Option<InvalidPillarObservation>,
+    /// See [`CrackingPipeline::invalid_observations`]
+    pub invalid_observations: Vec<InvalidObservation>,
+    /// See [`CrackingPipeline::invalid_count_bounds`]
+    pub invalid_count_bounds: Vec<(usize, CountBound)>,
+    /// See [`CrackingPipeline::invalid_positions`]
+    pub invalid_positions: Vec<OutOfWorldBorder>,
+    /// See [`CrackingPipeline::oversized_areas`]
+    pub oversized_areas: Vec<OversizedArea>,
+    /// See [`CrackingPipeline::treasure_area_cost`]
+    pub treasure_area_cost: Option<TreasureAreaCost>,
+    /// See [`CrackingPipeline::timeline_warnings`], the search still runs
+    pub timeline_warnings: Vec<TimelineWarning>,
+}
+
+impl SearchPlan {
+    #[inline]
+    pub fn stage(&self, kind: StageKind) -> Option<&PlannedStage> {
+        self.stages.iter().find(|stage| stage.kind == kind)
+    }
+
+    /// True if some evidence can't be checked, the search must not start until it is fixed
+    #[inline]
+    pub fn has_invalid_evidence(&self) -> bool {
+        !self.invalid_samples.is_empty()
+            || self.invalid_pillars.is_some()
+            || !self.invalid_observations.is_empty()
+            || !self.invalid_count_bounds.is_empty()
+            || !self.invalid_positions.is_empty()
+            || !self.oversized_areas.is_empty()
+    }
+
+    /// One line per piece of invalid evidence, naming the evidence it is part of
+    pub fn invalid_evidence(&self) -> Vec<String> {
+        invalid_evidence_lines(
+            &self.invalid_samples,
+            self.invalid_pillars.as_ref(),
+            &self.invalid_observations,
+            &self.invalid_count_bounds,
+            &self.invalid_positions,
+            &self.oversized_areas,
+        )
+    }
+}
+
+fn invalid_evidence_lines(
+    invalid_samples: &[ConstraintError],
+    invalid_pillars: Option<&InvalidPillarObservation>,
+    invalid_observations: &[InvalidObservation],
+    invalid_count_bounds: &[(usize, CountBound)],
+    invalid_positions: &[OutOfWorldBorder],
+    oversized_areas: &[OversizedArea],
+) -> Vec<String> {
+    let mut lines = invalid_samples
+        .iter()
+        .map(|sample| sample.to_string())
+        .collect::<Vec<_>>();
+    if let Some(pillars) = invalid_pillars {
+        lines.push(format!("End pillars: {}", pillars));
+    }
+    for observation in invalid_observations.iter() {
+        lines.push(format!("Buried treasure: {}", observation));
+    }
+    for (item, bound) in invalid_count_bounds.iter() {
+        lines.push(format!(
+            "Buried treasure: item {} can never be {}",
+            item, bound
+        ));
+    }
+    for position in invalid_positions.iter() {
+        lines.push(format!("Buried treasure: {}", position));
+    }
+    for area in oversized_areas.iter() {
+        lines.push(format!("Buried treasure: {}", area));
+    }
+    lines
+}
+
+impl Display for SearchPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Search plan")?;
+        writeln!(f, "{} possible pillar seed(s)", self.pillar_seed_count)?;
+        if !self.pillar_seed_order.is_empty() {
+            write!(f, "Pillar seed order:")?;
+            for (seed, result) in self.pillar_seed_order.iter() {
+                write!(f, " {} ({:.2}%)", seed, result.chance() * 100.0)?;
+            }
+            writeln!(f)?;
+        }
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            writeln!(
+                f,
+                "{}. {}: {}{} job(s) x {} candidates = {}{} candidates",
+                i + 1,
+                stage.name,
+                if stage.jobs_upper_bound { "up to " } else { "" },
+                format_count(stage.jobs),
+                format_count(stage.candidates_per_job),
+                if stage.jobs_upper_bound { "up to " } else { "" },
+                format_count(stage.total_candidates())
+            )?;
+            for evidence in stage.evidence.iter() {
+                writeln!(f, "   - {}", evidence)?;
+            }
+        }
+        if let Some(cost) = &self.treasure_area_cost {
+            writeln!(f, "Buried treasure area: {}", cost)?;
+        }
+
+        if !self.disabled_filters.is_empty() {
+            writeln!(f, "Disabled:")?;
+            for filter in self.disabled_filters.iter() {
+                writeln!(f, "   - {}", filter)?;
+            }
+        }
+
+        if !self.timeline_warnings.is_empty() {
+            writeln!(f, "Warnings:")?;
+            for warning in self.timeline_warnings.iter() {
+                writeln!(f, "   - {}", warning)?;
+            }
+        }
+
+        if self.has_invalid_evidence() {
+            writeln!(f, "Invalid evidence:")?;
+            for invalid in self.invalid_evidence() {
+                writeln!(f, "   - {}", invalid)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubiomes::{
+        enums::{BiomeID, Dimension, MCVersion},
+        generator::{Generator, GeneratorFlags},
+    };
+
+    use crate::{
+        candidates::WorldSeedCandidate,
+        features::{
+            bastion,
+            buried_treasure::get_buried_treasure,
+            end_pillars::{
+                EndPillars, InvalidPillarObservation, PartialEndPillars, PillarHeightHint,
+            },
+        },
+        loot_table::{CountBound, InvalidObservation, Inventory, ItemStack, SlotThreshold},
+        math::OutOfWorldBorder,
+        pipeline::{
+            BuriedTreasureCountEvidence, BuriedTreasureEvidence, CrackingEvidence,
+            CrackingPipeline, EarlyHits, ObservedAt, OversizedArea, PipelineOptions,
+            Reverification, StageKind, TimelineWarning,
+        },
+        schedule::MatchStrength,
+        search::{ConstraintError, StructureData, WorldExtraData},
+        self_test::STRUCTURE_SEED,
+        world_seed::typed_numeric_completions,
+    };
+
+    fn fixture_evidence() -> CrackingEvidence {
+        let mut real_pillars = EndPillars::new();
+        real_pillars.from_seed(13847);
+
+        let mut pillars = PartialEndPillars::new();
+        for (ppillar, pillar) in pillars.iter_mut().zip(real_pillars.iter()) {
+            ppillar.caged = Some(pillar.caged);
+            ppillar.height = PillarHeightHint::Exact(pillar.height);
+        }
+
+        CrackingEvidence {
+            pillars,
+            buried_treasures: vec![BuriedTreasureEvidence {
+                pos_x: 409,
+                pos_z: 809,
+                luck: 0.0,
+                contents: get_buried_treasure(180066252004364, (25, 50), 0.0),
+                area_corner: None,
+                observed_at: None,
+            }],
+            buried_treasure_counts: None,
+            buried_treasure_loot_table: None,
+            world: vec![WorldExtraData::OverworldBiomeData(Vec::new())],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_matches_jobs() {
+        let evidence = fixture_evidence();
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        let jobs = CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds);
+        assert!(!jobs.is_empty());
+        assert!(jobs.iter().any(|job| job.pillar_seed == 13847));
+        assert!(jobs.iter().all(|job| job.data.len() == 1));
+        // the found seeds are scored with the pillar match of their job
+        for job in jobs.iter() {
+            let (_, result) = pillar_seeds
+                .iter()
+                .find(|p| p.0 == job.pillar_seed)
+                .unwrap();
+            assert_eq!(job.pillar_chance, result.chance());
+        }
+
+        assert_eq!(plan.pillar_seed_count, pillar_seeds.len());
+        assert_eq!(
+            plan.pillar_seed_order
+                .iter()
+                .map(|p| p.0)
+                .collect::<Vec<_>>(),
+            jobs.iter().map(|job| job.pillar_seed).collect::<Vec<_>>()
+        );
+        assert_eq!(plan.stages.len(), 4);
+        assert_eq!(plan.stage(StageKind::StructureSeeds), Some(&plan.stages[1]));
+        assert_eq!(plan.stage(StageKind::WorldSeeds), plan.stages.last());
+        assert_eq!(plan.stages[0].evidence, vec!["10 observed pillar(s)"]);
+        assert_eq!(plan.stages[1].jobs, jobs.len() as u64);
+        assert_eq!(plan.stages[1].evidence.len(), 1);
+        assert_eq!(plan.stages[2].jobs, jobs.len() as u64 * 10);
+        assert_eq!(plan.stages[3].jobs, 5);
+        assert!(plan.stages[3].jobs_upper_bound);
+
+        // empty biome list and no hard world filter
+        assert_eq!(plan.disabled_filters.len(), 2);
+        assert!(plan.invalid_samples.is_empty());
+
+        let text = plan.to_string();
+        assert!(text.contains("13847 (100.00%)"));
+        assert!(text.contains(" job(s) x 4,294,967,296 candidates"));
+        assert!(text.contains("3. Re-verification: up to "));
+        assert!(text.contains("4. World seeds: up to 5 job(s) x 65,536 candidates"));
+    }
+
+    #[test]
+    fn test_world_jobs_check_pillars() {
+        let mut evidence = fixture_evidence();
+        let options = PipelineOptions::default();
+        // structure seed 1 generates the pillars of pillar seed 40152
+        let jobs = CrackingPipeline::world_jobs(&evidence, &options, &[STRUCTURE_SEED, 1]);
+        assert_eq!(jobs[0].pillar_seeds.as_deref(), Some(&[13847][..]));
+        assert_eq!(jobs[0].search_bounded(39979..39982).seeds.len(), 3);
+        assert!(jobs[1].search_bounded(0..65536).seeds.is_empty());
+
+        // without observed pillars, any world seed passes
+        evidence.pillars = PartialEndPillars::new();
+        let jobs = CrackingPipeline::world_jobs(&evidence, &options, &[1]);
+        assert!(jobs[0].pillar_seeds.is_none());
+        assert_eq!(jobs[0].search_bounded(0..3).seeds.len(), 3);
+    }
+
+    #[test]
+    fn test_plan_invalid_samples() {
+        let mut evidence = fixture_evidence();
+        evidence.world = vec![WorldExtraData::OverworldBiomeData(vec![
+            (400, 60, 800, BiomeID::beach),
+            (400, 5000, 800, BiomeID::beach),
+        ])];
+        let plan = CrackingPipeline::plan(&evidence, &PipelineOptions::default());
+
+        assert_eq!(
+            plan.invalid_samples,
+            vec![ConstraintError::OutOfWorld {
+                dimension: "overworld",
+                pos: (400, 5000, 800),
+            }]
+        );
+        assert!(plan.to_string().contains(
+            "Invalid evidence:\n   - overworld biome sample at (400, 5000, 800) is outside of the world"
+        ));
+    }
+
+    #[test]
+    fn test_plan_invalid_observation() {
+        let mut evidence = fixture_evidence();
+        if let Some(bt) = evidence.buried_treasures.first_mut() {
+            bt.contents.set_item(
+                26,
+                Some(ItemStack::of(bastion::items::other::CRYING_OBSIDIAN, 1)),
+            );
+        }
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+
+        assert_eq!(
+            CrackingPipeline::invalid_observations(&evidence),
+            vec![InvalidObservation {
+                items: vec![(26, bastion::items::other::CRYING_OBSIDIAN)],
+                ..Default::default()
+            }]
+        );
+        assert!(CrackingPipeline::structure_data(&evidence, &options).is_empty());
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        assert!(CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds).is_empty());
+        assert_eq!(plan.stages[1].jobs, 0);
+        assert!(plan.to_string().contains(
+            "Invalid evidence:\n   - Buried treasure: the loot table can't generate item 24 in slot 26"
+        ));
+    }
+
+    #[test]
+    fn test_plan_invalid_pillars() {
+        let mut evidence = fixture_evidence();
+        for pillar in evidence.pillars.iter_mut() {
+            pillar.caged = Some(false);
+        }
+        let plan = CrackingPipeline::plan(&evidence, &PipelineOptions::default());
+
+        assert_eq!(
+            plan.invalid_pillars,
+            Some(InvalidPillarObservation::TooManyUncaged { count: 10 })
+        );
+        assert!(plan.has_invalid_evidence());
+        assert_eq!(plan.pillar_seed_count, 0);
+        assert_eq!(plan.stages[1].jobs, 0);
+        assert!(plan.to_string().contains(
+            "Invalid evidence:\n   - End pillars: 10 pillars are marked uncaged, but 2 of the 10 pillars are caged"
+        ));
+    }
+
+    #[test]
+    fn test_early_typed_hits() {
+        // a world created by typing the structure seed
+        let biome = Generator::new(
+            MCVersion::MC_1_16_5,
+            STRUCTURE_SEED,
+            Dimension::DIM_OVERWORLD,
+            GeneratorFlags::empty(),
+        )
+        .get_biome_at(400, 60, 800)
+        .unwrap();
+        let evidence = CrackingEvidence {
+            world: vec![WorldExtraData::OverworldBiomeData(vec![(
+                400, 60, 800, biome,
+            )])],
+            ..Default::default()
+        };
+        let options = PipelineOptions {
+            early_typed_hits: true,
+            ..Default::default()
+        };
+
+        let hits = CrackingPipeline::early_typed_hits(&evidence, &options, STRUCTURE_SEED);
+        assert!(hits.iter().any(|h| h.seed == STRUCTURE_SEED));
+        assert!(
+            hits.iter()
+                .all(|h| typed_numeric_completions(STRUCTURE_SEED).contains(&h.seed))
+        );
+        // opt-in, and nothing to check without biome samples
+        assert!(
+            CrackingPipeline::early_typed_hits(
+                &evidence,
+                &PipelineOptions::default(),
+                STRUCTURE_SEED
+            )
+            .is_empty()
+        );
+        assert!(
+            CrackingPipeline::early_typed_hits(
+                &CrackingEvidence::default(),
+                &options,
+                STRUCTURE_SEED
+            )
+            .is_empty()
+        );
+
+        let mut early = EarlyHits::default();
+        assert_eq!(early.record(hits.clone()), hits.len());
+        assert_eq!(early.record(hits.clone()), 0);
+        // the world seed stage finds the typed seed again, along with another one
+        let world_stage = [
+            WorldSeedCandidate::new(STRUCTURE_SEED, STRUCTURE_SEED, None),
+            WorldSeedCandidate::new(STRUCTURE_SEED, 1 << 48 | STRUCTURE_SEED, None),
+        ];
+        early.refound(&world_stage);
+        assert!(early.pending().iter().all(|h| h.seed != STRUCTURE_SEED));
+        assert_eq!(early.pending().len(), hits.len() - 1);
+        // a structure seed found again doesn't bring its hit back
+        assert_eq!(early.record(hits), 0);
+    }
+
+    #[test]
+    fn test_plan_buried_treasure_outside_of_border() {
+        let mut evidence = fixture_evidence();
+        if let Some(bt) = evidence.buried_treasures.first_mut() {
+            // a typo of 409
+            bt.pos_x = 409_000_000;
+        }
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+
+        assert_eq!(
+            CrackingPipeline::invalid_positions(&evidence),
+            vec![OutOfWorldBorder {
+                x: 409_000_000,
+                z: 809
+            }]
+        );
+        assert!(CrackingPipeline::structure_data(&evidence, &options).is_empty());
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        assert!(CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds).is_empty());
+        assert!(plan.has_invalid_evidence());
+        assert!(plan.to_string().contains(
+            "Invalid evidence:\n   - Buried treasure: (409000000, 809) is outside of the world border (±30000000)"
+        ));
+    }
+
+    #[test]
+    fn test_buried_treasure_counts_only() {
+        use crate::features::{bastion::items::other::GOLD_BLOCK, buried_treasure::items};
+
+        let mut evidence = fixture_evidence();
+        evidence.buried_treasures.clear();
+        evidence.buried_treasure_counts = Some(BuriedTreasureCountEvidence {
+            pos_x: 409,
+            pos_z: 809,
+            luck: 0.0,
+            counts: vec![
+                (items::IRON_INGOT, CountBound::AtLeast(8)),
+                (items::COOKED_COD, CountBound::Exactly(8)),
+                (items::HEART_OF_THE_SEA, CountBound::Exactly(1)),
+            ],
+            observed_at: None,
+        });
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+        assert!(!plan.has_invalid_evidence());
+        assert_eq!(
+            plan.stages[1].evidence,
+            vec!["Buried treasure item counts in chunk (25, 50), 3 bound(s), luck 0"]
+        );
+
+        // weaker than the full chest, the structure seed is still found
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        let jobs = CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds);
+        let job = jobs.iter().find(|job| job.pillar_seed == 13847).unwrap();
+        assert!(job.search_bounded(47520..47525).contains(&180066252004364));
+
+        // no buried treasure holds a gold block
+        if let Some(bt) = evidence.buried_treasure_counts.as_mut() {
+            bt.counts.push((GOLD_BLOCK, CountBound::AtLeast(1)));
+        }
+        let plan = CrackingPipeline::plan(&evidence, &options);
+        assert_eq!(
+            plan.invalid_count_bounds,
+            vec![(GOLD_BLOCK, CountBound::AtLeast(1))]
+        );
+        assert!(CrackingPipeline::structure_data(&evidence, &options).is_empty());
+        assert!(CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds).is_empty());
+        assert!(
+            plan.to_string()
+                .contains("   - Buried treasure: item 16 can never be 1+")
+        );
+    }
+
+    #[test]
+    fn test_buried_treasure_area() {
+        let mut evidence = fixture_evidence();
+        if let Some(bt) = evidence.buried_treasures.first_mut() {
+            // chunks (22, 47) to (28, 53), around the chunk (25, 50) of the chest
+            (bt.pos_x, bt.pos_z) = (455, 860);
+            bt.area_corner = Some((352, 752));
+        }
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+        assert!(!plan.has_invalid_evidence());
+        assert_eq!(
+            plan.stages[1].evidence,
+            vec![
+                "Buried treasure contents in one of the 49 chunks from (22, 47) to (28, 53), 26 items, luck 0"
+            ]
+        );
+
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        let jobs = CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds);
+        let job = jobs.iter().find(|job| job.pillar_seed == 13847).unwrap();
+        let found = job.search_bounded(47520..47525);
+        assert_eq!(found, vec![180066252004364]);
+        assert_eq!(
+            CrackingPipeline::reverify(&evidence, &options, &found, |_| {}).kept,
+            found
+        );
+        assert_eq!(
+            CrackingPipeline::top_structure_seeds(&evidence, &options, &[(13847, found[0])]),
+            found
+        );
+        let candidates =
+            CrackingPipeline::structure_candidates(&evidence, &options, &[(13847, found[0])]);
+        assert_eq!(candidates[0].found_at, Some((25, 50)));
+        assert_eq!(candidates[0].evidence_annotations, plan.stages[1].evidence);
+        assert!((47520..47525).contains(&(candidates[0].shard as i64)));
+        assert_eq!(candidates[0].score.pillar_chance, job.pillar_chance);
+        assert!(candidates[0].score.chest_exact);
+
+        let small = PipelineOptions {
+            max_treasure_area: 48,
+            ..Default::default()
+        };
+        let plan = CrackingPipeline::plan(&evidence, &small);
+        assert!(plan.has_invalid_evidence());
+        assert!(CrackingPipeline::structure_data(&evidence, &small).is_empty());
+        assert!(CrackingPipeline::structure_jobs(&evidence, &small, &pillar_seeds).is_empty());
+        assert!(
+            plan.to_string()
+                .contains("   - Buried treasure: the area covers 49 chunks, the limit is 48")
+        );
+
+        if let Some(bt) = evidence.buried_treasures.first_mut() {
+            bt.area_corner = Some((30_000_000, 752));
+        }
+        assert_eq!(CrackingPipeline::invalid_positions(&evidence).len(), 1);
+    }
+
+    #[test]
+    fn test_buried_treasure_area_decoys() {
+        let mut evidence = fixture_evidence();
+        if let Some(bt) = evidence.buried_treasures.first_mut() {
+            // chunks (19, 45) to (32, 54), buried treasures with other chests generate in (31, 48) and (19, 53)
+            (bt.pos_x, bt.pos_z) = (304, 720);
+            bt.area_corner = Some((527, 879));
+        }
+        let plan = CrackingPipeline::plan(&evidence, &PipelineOptions::default());
+        assert_eq!(
+            plan.oversized_areas,
+            vec![OversizedArea {
+                chunks: 140,
+                limit: 64
+            }]
+        );
+
+        let options = PipelineOptions {
+            max_treasure_area: 4096,
+            ..Default::default()
+        };
+        let plan = CrackingPipeline::plan(&evidence, &options);
+        assert!(!plan.has_invalid_evidence());
+        assert_eq!(plan.treasure_area_cost.map(|cost| cost.chunks), Some(140));
+        assert!(plan.to_string().contains(
+            "Buried treasure area: 140 chunks per candidate, about 1.40 chest(s) compared per candidate and 0.0055 chance match(es) per pillar seed"
+        ));
+
+        let data = CrackingPipeline::structure_data(&evidence, &options);
+        let StructureData::BuriedTreasureContentsInArea { area, .. } = &data[0] else {
+            panic!("expected an area, got {:?}", data);
+        };
+        assert_eq!(
+            area.treasure_chunks(STRUCTURE_SEED),
+            vec![(31, 48), (25, 50), (19, 53)]
+        );
+        assert_eq!(data[0].matching_chunk(STRUCTURE_SEED), Some((25, 50)));
+
+        let pillar_seeds = CrackingPipeline::pillar_seeds(&evidence);
+        let jobs = CrackingPipeline::structure_jobs(&evidence, &options, &pillar_seeds);
+        let job = jobs.iter().find(|job| job.pillar_seed == 13847).unwrap();
+        let found = job.search_bounded(47520..47525);
+        assert_eq!(found, vec![STRUCTURE_SEED]);
+        let candidates =
+            CrackingPipeline::structure_candidates(&evidence, &options, &[(13847, found[0])]);
+        assert_eq!(
+            candidates
+                .iter()
+                .map(|c| (c.seed, c.found_at))
+                .collect::<Vec<_>>(),
+            vec![(STRUCTURE_SEED, Some((25, 50)))]
+        );
+    }
+
+    #[test]
+    fn test_plan_too_many_pillar_seeds() {
+        let evidence = CrackingEvidence::default();
+        let options = PipelineOptions::default();
+        let plan = CrackingPipeline::plan(&evidence, &options);
+
+        assert_eq!(plan.pillar_seed_count, 65536);
+        assert!(plan.pillar_seed_order.is_empty());
+        assert_eq!(plan.stages[1].jobs, 0);
+        assert!(
+            plan.disabled_filters
+                .iter()
+                .any(|f| f.starts_with("Structure seed search: 65536 pillar seed(s) left"))
+        );
+
+        assert!(CrackingPipeline::world_jobs(&evidence, &options, &[]).is_empty());
+        assert!(CrackingPipeline::world_jobs(&evidence, &options, &[0; 6]).is_empty());
+        assert_eq!(
+            CrackingPipeline::world_jobs(&evidence, &options, &[0; 5]).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_reverify() {
+        use crate::features::buried_treasure::items::HEART_OF_THE_SEA;
+
+        // every buried treasure holds one heart of the sea, the structure seed search can't tell these seeds apart
+        let mut relaxed = fixture_evidence();
+        relaxed.buried_treasures.clear();
+        relaxed.buried_treasure_counts = Some(BuriedTreasureCountEvidence {
+            pos_x: 409,
+            pos_z: 809,
+            luck: 0.0,
+            counts: vec![(HEART_OF_THE_SEA, CountBound::Exactly(1))],
+            observed_at: None,
+        });
+        // pillar seed 13847, and a buried treasure in chunk (25, 50) holding another chest
+        let (real, other) = (180066252004364, 15789720294978);
+        // pillar seed 40152
+        let wrong_pillars = 1;
+
+        let seeds = [other, real, wrong_pillars];
+        let mut checked = Vec::new();
+        let reverification =
+            CrackingPipeline::reverify(&relaxed, &PipelineOptions::default(), &seeds, |done| {
+                checked.push(done)
+            });
+        assert_eq!(checked, vec![1, 2, 3]);
+        assert_eq!(reverification.kept, vec![other, real]);
+        assert_eq!(
+            reverification.eliminated,
+            vec![(wrong_pillars, "Observed pillars".to_string())]
+        );
+
+        // the full chest the player recorded afterwards only matches the real seed
+        let precise = CrackingPipeline::reverify(
+            &fixture_evidence(),
+            &PipelineOptions::default(),
+            &seeds,
+            |_| {},
+        );
+        assert_eq!(precise.kept, vec![real]);
+        assert_eq!(precise.eliminated.len(), 2);
+        assert_eq!(precise.eliminated[0].0, other);
+        assert!(
+            precise.eliminated[0]
+                .1
+                .starts_with("Buried treasure contents in chunk (25, 50)")
+        );
+        assert_eq!(
+            precise.eliminated[1],
+            (wrong_pillars, "Observed pillars".to_string())
+        );
+        assert_eq!(
+            precise.to_string(),
+            "2 of 3 structure seeds eliminated in re-verification"
+        );
+        assert_eq!(
+            CrackingPipeline::reverify(
+                &fixture_evidence(),
+                &PipelineOptions::default(),
+                &[],
+                |_| {}
+            ),
+            Reverification::default()
+        );
+    }
+
+    #[test]
+    fn test_match_strength() {
+        let evidence = fixture_evidence();
+        let options = PipelineOptions::default();
+        let (real, other) = (180066252004364, 15789720294978);
+        let strength = |evidence: &CrackingEvidence, options: &PipelineOptions, seed| {
+            CrackingPipeline::match_strength(evidence, options, seed)
+        };
+        assert_eq!(strength(&evidence, &options, real), MatchStrength::Exact);
+        // same pillar seed, another chest
+        assert_eq!(strength(&evidence, &options, other), MatchStrength::Partial);
+
+        // a chest allowed to differ in some slots or an unknown pillar height can't be an exact match
+        let relaxed = PipelineOptions {
+            slot_threshold: Some(SlotThreshold::new(20, 27)),
+            ..Default::default()
+        };
+        assert_eq!(strength(&evidence, &relaxed, real), MatchStrength::Partial);
+        let mut unknown = evidence.clone();
+        unknown.pillars.0[0].height = PillarHeightHint::Unknown;
+        assert_eq!(strength(&unknown, &options, real), MatchStrength::Partial);
+    }
+
+    #[test]
+    fn test_timeline_warnings() {
+        use crate::features::{buried_treasure::items, decoration::DecorationKind};
+
+        let fresh = Some(ObservedAt::new("day 1", false));
+        let modified = Some(ObservedAt::new("day 40", true));
+
+        let mut evidence = fixture_evidence();
+        evidence.pillars_observed_at = modified.clone();
+        evidence.buried_treasures.first_mut().unwrap().observed_at = fresh.clone();
+        evidence.world.push(WorldExtraData::DecorationCount {
+            chunk: (0, 0),
+            decoration: DecorationKind::SugarCane,
+            feature_index: 0,
+            surface_y: 63,
+            observed_count: 4,
+            tolerance: 0,
+        });
+        evidence.decorations_observed_at = fresh.clone();
+        assert!(evidence.has_observation_times());
+
+        // pillar seed 13847 has uncaged pillars, their cages could have been broken
+        let uncaged = (0..10)
+            .filter(|&i| evidence.pillars.0[i].caged == Some(false))
+            .collect::<Vec<_>>();
+        assert!(!uncaged.is_empty());
+        assert_eq!(
+            CrackingPipeline::timeline_warnings(&evidence),
+            vec![TimelineWarning::ModifiedUncagedPillars(uncaged.clone())]
+        );
+        // only the cage status is unreliable
+        let mut heights_only = evidence.clone();
+        for pillar in heights_only.pillars.iter_mut() {
+            pillar.caged = None;
+        }
+        assert!(CrackingPipeline::timeline_warnings(&heights_only).is_empty());
+
+        evidence.pillars_observed_at = fresh.clone();
+        evidence.buried_treasures.first_mut().unwrap().observed_at = modified.clone();
+        evidence.decorations_observed_at = modified.clone();
+        let plan = CrackingPipeline::plan(&evidence, &PipelineOptions::default());
+        assert_eq!(
+            plan.timeline_warnings,
+            vec![
+                TimelineWarning::ModifiedChest,
+                TimelineWarning::ModifiedDecorations
+            ]
+        );
+        // a warning, not invalid evidence
+        assert!(!plan.has_invalid_evidence());
+        assert!(
+            plan.to_string()
+                .contains("Warnings:\n   - Buried treasure: the chest might have been modified")
+        );
+
+        // decoration flags without decorations say nothing
+        evidence.world.pop();
+        evidence.buried_treasures.clear();
+        assert!(CrackingPipeline::timeline_warnings(&evidence).is_empty());
+
+        // lower bounds still hold after looting, the others don't
+        let mut counts = BuriedTreasureCountEvidence {
+            pos_x: 409,
+            pos_z: 809,
+            luck: 0.0,
+            counts: vec![
+                (items::IRON_INGOT, CountBound::AtLeast(8)),
+                (items::COOKED_COD, CountBound::Exactly(8)),
+                (items::EMERALD, CountBound::Between(2, 5)),
+                (items::DIAMOND, CountBound::AtMost(1)),
+            ],
+            observed_at: modified.clone(),
+        };
+        evidence.buried_treasure_counts = Some(counts.clone());
+        assert_eq!(
+            CrackingPipeline::timeline_warnings(&evidence),
+            vec![TimelineWarning::ModifiedChestUpperBounds(3)]
+        );
+        counts.counts.truncate(1);
+        evidence.buried_treasure_counts = Some(counts.clone());
+        assert!(CrackingPipeline::timeline_warnings(&evidence).is_empty());
+        counts.counts.push((items::DIAMOND, CountBound::AtMost(1)));
+        counts.observed_at = None;
+        evidence.buried_treasure_counts = Some(counts);
+        assert!(CrackingPipeline::timeline_warnings(&evidence).is_empty());
+
+        assert_eq!(
+            modified.unwrap().to_string(),
+            "day 40, might have been modified"
+        );
+        assert_eq!(ObservedAt::new("", false).to_string(), "fresh world");
+    }
+
+    #[test]
+    fn test_top_structure_seeds() {
+        let evidence = fixture_evidence();
+        let options = PipelineOptions {
+            max_structure_seeds: 2,
+            ..Default::default()
+        };
+
+        // unknown pillar seeds rank last, seeds satisfying the buried treasure first
+        let found = [(1, 5), (13847, 7), (13847, 180066252004364), (13847, 3)];
+        assert_eq!(
+            CrackingPipeline::top_structure_seeds(&evidence, &options, &found),
+            vec![180066252004364, 3]
+        );
+
+        let top = CrackingPipeline::top_structure_seeds(&evidence, &options, &found);
+        assert_eq!(
+            CrackingPipeline::world_jobs(&evidence, &options, &top).len(),
+            2
+        );
+        assert!(CrackingPipeline::top_structure_seeds(&evidence, &options, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_check_evidence() {
+        use crate::features::buried_treasure::items;
+
+        let options = PipelineOptions::default();
+        let check = CrackingPipeline::check_evidence(&fixture_evidence(), &options);
+        assert!(check.is_clean(), "{:?}", check);
+        assert!((1..=options.max_pillar_seeds).contains(&check.pillar_seed_count));
+        // the pillars and the chest give most of the structure seed, there are no biome samples
+        assert_eq!(check.estimated_bits, 48.0);
+
+        // nothing observed, every pillar seed is left
+        let check = CrackingPipeline::check_evidence(&CrackingEvidence::default(), &options);
+        assert!(check.errors.is_empty());
+        assert_eq!(check.pillar_seed_count, 65536);
+        assert_eq!(
+            check.warnings,
+            vec![
+                "End pillars: 65536 pillar seeds left, the structure seed search only runs with at most 5"
+            ]
+        );
+        assert_eq!(check.estimated_bits, 0.0);
+
+        // one problem of each kind
+        let mut evidence = fixture_evidence();
+        for pillar in evidence.pillars.iter_mut() {
+            pillar.caged = Some(false);
+        }
+        let bt = evidence.buried_treasures.first_mut().unwrap();
+        bt.contents.set_item(
+            26,
+            Some(ItemStack::of(bastion::items::other::CRYING_OBSIDIAN, 1)),
+        );
+        bt.area_corner = Some((409 + 16 * 20, 809 + 16 * 20));
+        bt.observed_at = Some(ObservedAt::new("day 40", true));
+        evidence.buried_treasure_counts = Some(BuriedTreasureCountEvidence {
+            pos_x: 40_000_000,
+            pos_z: 809,
+            luck: 0.0,
+            counts: vec![(items::TNT, CountBound::Exactly(50))],
+            observed_at: None,
+        });
+        evidence.world = vec![WorldExtraData::OverworldBiomeData(vec![
+            (400, 60, 800, BiomeID::beach),
+            (400, 5000, 800, BiomeID::beach),
+        ])];
+
+        let check = CrackingPipeline::check_evidence(&evidence, &options);
+        assert_eq!(
+            check.errors,
+            vec![
+                "overworld biome sample at (400, 5000, 800) is outside of the world".to_string(),
+                "End pillars: 10 pillars are marked uncaged, but 2 of the 10 pillars are caged"
+                    .to_string(),
+                "Buried treasure: the loot table can't generate item 24 in slot 26".to_string(),
+                format!(
+                    "Buried treasure: item {} can never be {}",
+                    items::TNT,
+                    CountBound::Exactly(50)
+                ),
+                "Buried treasure: (40000000, 809) is outside of the world border (±30000000)"
+                    .to_string(),
+                "Buried treasure: the area covers 441 chunks, the limit is 64".to_string(),
+            ]
+        );
+        assert_eq!(
+            check.warnings,
+            vec![TimelineWarning::ModifiedChest.to_string()]
+        );
+        assert_eq!(check.pillar_seed_count, 0);
+        // the same lines as the plan
+        let plan = CrackingPipeline::plan(&evidence, &options);
+        assert_eq!(check.errors, plan.invalid_evidence());
+        assert!(check.estimated_bits > 0.0 && check.estimated_bits < 48.0);
+    }
+}
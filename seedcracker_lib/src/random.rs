@@ -1,3 +1,5 @@
+#![deny(arithmetic_overflow, clippy::arithmetic_side_effects)]
+
 use crate::{lcg::JAVA_RANDOM, math::Math};
 
 #[derive(Default, Debug, Clone)]
@@ -10,7 +12,7 @@ impl JavaRandom {
     #[inline(always)]
     pub const fn new(seed: i64) -> Self {
         Self {
-            seed: (seed ^ JAVA_RANDOM.get_multiplier()) & (JAVA_RANDOM.get_modulus() - 1),
+            seed: (seed ^ JAVA_RANDOM.get_multiplier()) & JAVA_RANDOM.get_modulus().wrapping_sub(1),
         }
     }
 
@@ -33,7 +35,7 @@ impl JavaRandom {
     #[inline(always)]
     pub const fn next(&mut self, bit_count: i64) -> i32 {
         let s = self.next_seed();
-        (s >> (48 - bit_count)) as i32
+        s.wrapping_shr(48i64.wrapping_sub(bit_count) as u32) as i32
     }
 
     #[inline(always)]
@@ -41,20 +43,27 @@ impl JavaRandom {
         self.next(32)
     }
 
+    /// Java throws for `bound <= 0`, this returns 0 without drawing from the RNG instead
     #[inline(always)]
+    #[expect(
+        clippy::arithmetic_side_effects,
+        reason = "the remainders are only taken once bound is known to be positive"
+    )]
     pub const fn next_bounded_int(&mut self, bound: i32) -> i32 {
         if bound <= 0 {
             0
         } else if Math::is_pow_2(bound) {
-            ((bound as i64).wrapping_mul(self.next(31) as i64) >> 31) as i32
+            (bound as i64)
+                .wrapping_mul(self.next(31) as i64)
+                .wrapping_shr(31) as i32
         } else {
             let b = bound as i64;
             // Values in the range [0, max_try) when taken mod b will be uniformely distributed, as max_try is a constructed to be a multiple of b
-            let max_try = (Math::pow_2(31) - (Math::pow_2(31) % b)) as i32;
+            let max_try = Math::pow_2(31).wrapping_sub(Math::pow_2(31).wrapping_rem(b)) as i32;
             loop {
                 let try_value = self.next(31); // 31 bits so we don't get negative values
                 if try_value < max_try {
-                    return try_value % bound;
+                    return try_value.wrapping_rem(bound);
                 }
                 // If we get here, we need to try another value
             }
@@ -65,7 +74,7 @@ impl JavaRandom {
     pub const fn next_long(&mut self) -> i64 {
         let hi = self.next_int() as i64;
         let lo = self.next_int() as i64;
-        (hi << 32).wrapping_add(lo)
+        hi.wrapping_shl(32).wrapping_add(lo)
     }
 
     #[inline(always)]
@@ -75,21 +84,202 @@ impl JavaRandom {
 
     #[inline(always)]
     pub const fn next_float(&mut self) -> f32 {
-        self.next(f32::MANTISSA_DIGITS as i64) as f32
+        self.next_float_bits() as f32 / (Math::pow_2(f32::MANTISSA_DIGITS as i32) as f32)
+    }
+
+    /// The 24 bits `next_float` divides by 2^24, to compare against [`float_threshold`] without floats
+    #[inline(always)]
+    pub const fn next_float_bits(&mut self) -> i32 {
+        self.next(f32::MANTISSA_DIGITS as i64)
+    }
+}
+
+/// `next_float() < probability` exactly when `next_float_bits() < float_threshold(probability)`: n / 2^24 < p holds
+/// exactly when n < ceil(p * 2^24), and both the division and the product are exact in f32
+#[inline]
+pub const fn float_threshold(probability: f32) -> i32 {
+    let one = Math::pow_2(f32::MANTISSA_DIGITS as i32) as i32;
+    if probability.is_nan() || probability <= 0.0 {
+        return 0;
+    }
+    if probability >= 1.0 {
+        return one;
+    }
+    let scaled = probability * one as f32;
+    let threshold = scaled as i32;
+    if (threshold as f32) < scaled {
+        threshold.wrapping_add(1)
+    } else {
+        threshold
+    }
+}
+
+/// The calls loot generation makes on its RNG, so it can run on an instrumented one, see
+/// [`crate::trace::TracingJavaRandom`]
+pub trait McRandom {
+    fn next_bounded_int(&mut self, bound: i32) -> i32;
+    fn next_float(&mut self) -> f32;
+    fn next_bool(&mut self) -> bool;
+    fn next_long(&mut self) -> i64;
+}
+
+impl McRandom for JavaRandom {
+    #[inline(always)]
+    fn next_bounded_int(&mut self, bound: i32) -> i32 {
+        JavaRandom::next_bounded_int(self, bound)
+    }
+
+    #[inline(always)]
+    fn next_float(&mut self) -> f32 {
+        JavaRandom::next_float(self)
+    }
+
+    #[inline(always)]
+    fn next_bool(&mut self) -> bool {
+        JavaRandom::next_bool(self)
+    }
+
+    #[inline(always)]
+    fn next_long(&mut self) -> i64 {
+        JavaRandom::next_long(self)
+    }
+}
+
+pub const GOLDEN_RATIO_64: i64 = -7046029254386353131;
+pub const SILVER_RATIO_64: i64 = 7640891576956012809;
+
+/// `RandomSupport.mixStafford13`, the finalizer of SplitMix64
+#[inline(always)]
+pub const fn mix_stafford13(seed: i64) -> i64 {
+    let seed = (seed ^ ((seed as u64).wrapping_shr(30) as i64)).wrapping_mul(-4658895280553007687);
+    let seed = (seed ^ ((seed as u64).wrapping_shr(27) as i64)).wrapping_mul(-7723592293110705685);
+    seed ^ ((seed as u64).wrapping_shr(31) as i64)
+}
+
+/// `RandomSupport.upgradeSeedTo128bit`, the low and high halves of the state `XoroshiroRandomSource(seed)` starts from
+#[inline(always)]
+pub const fn upgrade_seed_to_128bit(seed: i64) -> (i64, i64) {
+    let lo = seed ^ SILVER_RATIO_64;
+    let hi = lo.wrapping_add(GOLDEN_RATIO_64);
+    (mix_stafford13(lo), mix_stafford13(hi))
+}
+
+/// `XoroshiroRandomSource` of 1.18+, on the xoroshiro128++ generator of `Xoroshiro128PlusPlus`. Minecraft seeds it with
+/// [`upgrade_seed_to_128bit`], its random sequences of 1.20 xor in a hash of their name first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xoroshiro128PlusPlus {
+    lo: i64,
+    hi: i64,
+}
+
+impl Xoroshiro128PlusPlus {
+    #[inline(always)]
+    pub const fn new(seed: i64) -> Self {
+        let (lo, hi) = upgrade_seed_to_128bit(seed);
+        Self::from_halves(lo, hi)
+    }
+
+    /// A state of zeros only gives zeros, Minecraft starts from the two ratios instead
+    #[inline(always)]
+    pub const fn from_halves(lo: i64, hi: i64) -> Self {
+        if lo == 0 && hi == 0 {
+            Self {
+                lo: GOLDEN_RATIO_64,
+                hi: SILVER_RATIO_64,
+            }
+        } else {
+            Self { lo, hi }
+        }
+    }
+
+    #[inline(always)]
+    pub const fn next_long(&mut self) -> i64 {
+        let (lo, hi) = (self.lo, self.hi);
+        let result = lo.wrapping_add(hi).rotate_left(17).wrapping_add(lo);
+        let hi = hi ^ lo;
+        self.lo = lo.rotate_left(49) ^ hi ^ hi.wrapping_shl(21);
+        self.hi = hi.rotate_left(28);
+        result
+    }
+
+    /// The top `bits` of a long
+    #[inline(always)]
+    const fn next_bits(&mut self, bits: u32) -> i64 {
+        (self.next_long() as u64).wrapping_shr(64u32.wrapping_sub(bits)) as i64
+    }
+
+    #[inline(always)]
+    pub const fn next_int(&mut self) -> i32 {
+        self.next_long() as i32
+    }
+
+    /// Lemire's multiply and shift, not the modulo of java.util.Random. Returns 0 for `bound <= 0` without drawing
+    /// from the RNG, like [`JavaRandom::next_bounded_int`].
+    #[inline(always)]
+    #[expect(
+        clippy::arithmetic_side_effects,
+        reason = "the remainder is only taken once bound is known to be positive"
+    )]
+    pub const fn next_bounded_int(&mut self, bound: i32) -> i32 {
+        if bound <= 0 {
+            return 0;
+        }
+        let bound = bound as u64;
+        let mut product = (self.next_int() as u32 as u64).wrapping_mul(bound);
+        if (product & 0xFFFF_FFFF) < bound {
+            let threshold = (bound as u32).wrapping_neg().wrapping_rem(bound as u32) as u64;
+            while (product & 0xFFFF_FFFF) < threshold {
+                product = (self.next_int() as u32 as u64).wrapping_mul(bound);
+            }
+        }
+        product.wrapping_shr(32) as i32
+    }
+
+    #[inline(always)]
+    pub const fn next_bool(&mut self) -> bool {
+        self.next_long() & 1 != 0
+    }
+
+    #[inline(always)]
+    pub const fn next_float(&mut self) -> f32 {
+        self.next_bits(f32::MANTISSA_DIGITS) as f32
             / (Math::pow_2(f32::MANTISSA_DIGITS as i32) as f32)
     }
 }
 
+impl McRandom for Xoroshiro128PlusPlus {
+    #[inline(always)]
+    fn next_bounded_int(&mut self, bound: i32) -> i32 {
+        Xoroshiro128PlusPlus::next_bounded_int(self, bound)
+    }
+
+    #[inline(always)]
+    fn next_float(&mut self) -> f32 {
+        Xoroshiro128PlusPlus::next_float(self)
+    }
+
+    #[inline(always)]
+    fn next_bool(&mut self) -> bool {
+        Xoroshiro128PlusPlus::next_bool(self)
+    }
+
+    #[inline(always)]
+    fn next_long(&mut self) -> i64 {
+        Xoroshiro128PlusPlus::next_long(self)
+    }
+}
+
 #[inline]
-pub const fn shuffle<T>(array: &mut [T], random: &mut JavaRandom) {
+pub fn shuffle<T, R: McRandom>(array: &mut [T], random: &mut R) {
     let mut i = array.len();
     while i > 1 {
         let swap_i = random.next_bounded_int(i as i32);
-        array.swap(i - 1, swap_i as usize);
-        i -= 1;
+        i = i.wrapping_sub(1);
+        array.swap(i, swap_i as usize);
     }
 }
 
+/// `setBaseChunkSeed` of 1.16.5, independent of the world seed
 #[inline(always)]
 pub const fn random_with_terrain_seed(chunk_x: i32, chunk_z: i32) -> (JavaRandom, i64) {
     let seed = (chunk_x as i64)
@@ -99,6 +289,8 @@ pub const fn random_with_terrain_seed(chunk_x: i32, chunk_z: i32) -> (JavaRandom
     (JavaRandom::new(seed), seed)
 }
 
+/// `setDecorationSeed` of 1.16.5, from the block coordinates of the chunk's corner. The decorations of a chunk derive
+/// their seeds from it, see [`random_with_decorator_seed`].
 #[inline(always)]
 pub const fn random_with_population_seed(
     world_seed: i64,
@@ -116,6 +308,7 @@ pub const fn random_with_population_seed(
     (JavaRandom::new(seed), seed)
 }
 
+/// `setFeatureSeed` of 1.16.5, seeds the decoration `index` of the generation `step`, e.g. the buried treasure chest
 #[inline(always)]
 pub const fn random_with_decorator_seed(
     population_seed: i64,
@@ -129,23 +322,70 @@ pub const fn random_with_decorator_seed(
     (JavaRandom::new(seed), seed)
 }
 
+/// The structures that fill chests while a chunk is decorated, each seeded by [`random_with_decorator_seed`] with its
+/// own `index` and `step`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecoratorSalt {
+    BuriedTreasure,
+    DesertPyramid,
+    Shipwreck,
+    BastionRemnant,
+    NetherFortress,
+}
+
+impl DecoratorSalt {
+    pub const ALL: [Self; 5] = [
+        Self::BuriedTreasure,
+        Self::DesertPyramid,
+        Self::Shipwreck,
+        Self::BastionRemnant,
+        Self::NetherFortress,
+    ];
+
+    /// Position of the structure among the structures of its generation step, in registration order
+    #[inline(always)]
+    pub const fn index(self) -> i32 {
+        match self {
+            Self::BuriedTreasure => 1,
+            Self::DesertPyramid => 3,
+            Self::Shipwreck => 6,
+            Self::BastionRemnant => 12,
+            Self::NetherFortress => 0,
+        }
+    }
+
+    /// 10 times the ordinal of the generation step: underground structures (3), surface structures (4) or
+    /// underground decoration (7)
+    #[inline(always)]
+    pub const fn step(self) -> i32 {
+        match self {
+            Self::BuriedTreasure => 30,
+            Self::DesertPyramid | Self::Shipwreck | Self::BastionRemnant => 40,
+            Self::NetherFortress => 70,
+        }
+    }
+}
+
+/// `setLargeFeatureSeed` of 1.16.5, seeds the cave and ravine carvers of a chunk. `salt` is added to the world seed,
+/// it is the index of the carver in the biome's carver list.
 #[inline(always)]
 pub const fn random_with_carver_seed(
     world_seed: i64,
     chunk_x: i32,
     chunk_z: i32,
+    salt: i64,
 ) -> (JavaRandom, i64) {
+    let world_seed = world_seed.wrapping_add(salt);
     let mut rng = JavaRandom::new(world_seed);
     let xmul = rng.next_long();
     let zmul = rng.next_long();
-    let seed = (chunk_x as i64)
-        .wrapping_mul(xmul)
-        .wrapping_add((chunk_z as i64).wrapping_mul(zmul))
-        ^ world_seed;
+    let seed =
+        (chunk_x as i64).wrapping_mul(xmul) ^ (chunk_z as i64).wrapping_mul(zmul) ^ world_seed;
 
     (JavaRandom::new(seed), seed)
 }
 
+/// `setLargeFeatureWithSalt` of 1.16.5, decides which chunk of a region gets a structure, e.g. buried treasures
 #[inline(always)]
 pub const fn random_with_region_seed(
     world_seed: i64,
@@ -161,3 +401,324 @@ pub const fn random_with_region_seed(
 
     (JavaRandom::new(seed), seed)
 }
+
+/// The chunk random of slime chunks before 1.18: a chunk is a slime chunk if its first `next_bounded_int(10)` is 0.
+/// The squares and products wrap as 32 bit ints like in Java.
+#[inline(always)]
+pub const fn random_with_chunk_seed(
+    world_seed: i64,
+    chunk_x: i32,
+    chunk_z: i32,
+) -> (JavaRandom, i64) {
+    let seed = world_seed
+        .wrapping_add(chunk_x.wrapping_mul(chunk_x).wrapping_mul(4987142) as i64)
+        .wrapping_add(chunk_x.wrapping_mul(5947611) as i64)
+        .wrapping_add((chunk_z.wrapping_mul(chunk_z) as i64).wrapping_mul(4392871))
+        .wrapping_add(chunk_z.wrapping_mul(389711) as i64)
+        ^ 987234911;
+
+    (JavaRandom::new(seed), seed)
+}
+
+/// Lines of `data/determinism/float_vectors.txt`, generated with java.util.Random, starting with `kind`, split on
+/// spaces without the kind
+#[cfg(test)]
+pub(crate) fn float_vectors(kind: &str) -> impl Iterator<Item = Vec<&'static str>> {
+    include_str!("../data/determinism/float_vectors.txt")
+        .lines()
+        .filter_map(move |line| {
+            let mut fields = line.split(' ');
+            (fields.next() == Some(kind)).then(|| fields.collect())
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use std::num::Wrapping;
+
+    use super::{
+        GOLDEN_RATIO_64, JavaRandom, Xoroshiro128PlusPlus, float_threshold, float_vectors,
+        mix_stafford13, random_with_carver_seed, random_with_chunk_seed,
+        random_with_population_seed, random_with_region_seed, upgrade_seed_to_128bit,
+    };
+
+    #[test]
+    fn test_extreme_seeds() {
+        assert_eq!(JavaRandom::new(i64::MIN).next_long(), -4962768465676381896);
+        assert_eq!(JavaRandom::new(i64::MAX).next_long(), 4961115982468162243);
+    }
+
+    /// The outputs of the reference xoroshiro128++ of Blackman and Vigna from the state (1, 2)
+    #[test]
+    fn test_xoroshiro_reference() {
+        let mut rng = Xoroshiro128PlusPlus::from_halves(1, 2);
+        let expected: [u64; 10] = [
+            393217,
+            669327710093319,
+            1732421326133921491,
+            11394790081659126983,
+            9555452776773192676,
+            3586421180005889563,
+            1691397964866707553,
+            10735626796753111697,
+            15216282715349408991,
+            14247243556711267923,
+        ];
+        for value in expected {
+            assert_eq!(rng.next_long(), value as i64);
+        }
+
+        // the zero state would stay zero
+        let mut zero = Xoroshiro128PlusPlus::from_halves(0, 0);
+        assert_ne!(zero.next_long(), 0);
+    }
+
+    /// SplitMix64 adds the golden ratio to its state and mixes it, its outputs from the seed 1234567
+    #[test]
+    fn test_mix_stafford13() {
+        let expected: [u64; 5] = [
+            6457827717110365317,
+            3203168211198807973,
+            9817491932198370423,
+            4593380528125082431,
+            16408922859458223821,
+        ];
+        for (step, value) in (1..).zip(expected) {
+            let state = 1234567i64.wrapping_add(GOLDEN_RATIO_64.wrapping_mul(step));
+            assert_eq!(mix_stafford13(state), value as i64);
+        }
+        assert_eq!(mix_stafford13(GOLDEN_RATIO_64) as u64, 0xe220a8397b1dcdaf);
+
+        let (lo, hi) = upgrade_seed_to_128bit(0);
+        assert_eq!(lo, mix_stafford13(super::SILVER_RATIO_64));
+        assert_eq!(
+            hi,
+            mix_stafford13(super::SILVER_RATIO_64.wrapping_add(GOLDEN_RATIO_64))
+        );
+    }
+
+    #[test]
+    fn test_xoroshiro_bounded_int() {
+        let mut rng = Xoroshiro128PlusPlus::new(42);
+        let mut copy = rng;
+        for bound in [1, 2, 3, 7, 10, 27, 1 << 20, i32::MAX] {
+            let expected = (copy.next_int() as u32 as u64)
+                .wrapping_mul(bound as u64)
+                .wrapping_shr(32) as i32;
+            // a rejection takes 1 in 2^32 / bound draws, none here
+            assert_eq!(rng.next_bounded_int(bound), expected);
+            assert!((0..bound).contains(&expected));
+        }
+        assert_eq!(rng.next_bounded_int(0), 0);
+        assert_eq!(rng, copy);
+    }
+
+    #[test]
+    fn test_population_seed_wrapping() {
+        for (world_seed, block_x, block_z) in [
+            (i64::MAX, 30_000_000, -30_000_000),
+            (i64::MIN, -30_000_000, 30_000_000),
+            (i64::MIN.wrapping_add(1), i32::MAX, i32::MIN),
+        ] {
+            let mut rng = JavaRandom::new(world_seed);
+            let xmul = Wrapping(rng.next_long() | 1);
+            let zmul = Wrapping(rng.next_long() | 1);
+            let expected =
+                (Wrapping(block_x as i64) * xmul + Wrapping(block_z as i64) * zmul).0 ^ world_seed;
+
+            assert_eq!(
+                random_with_population_seed(world_seed, block_x, block_z).1,
+                expected
+            );
+        }
+
+        assert_eq!(
+            random_with_population_seed(i64::MAX, 30_000_000, -30_000_000).1,
+            -4041204259572284929
+        );
+        assert_eq!(
+            random_with_population_seed(i64::MIN, -30_000_000, 30_000_000).1,
+            -6834405301412113152
+        );
+    }
+
+    #[test]
+    fn test_region_seed_wrapping() {
+        assert_eq!(
+            random_with_region_seed(i64::MAX, 58593, -58594, 10387320).1,
+            -9211127689295743627
+        );
+        assert_eq!(
+            random_with_region_seed(i64::MIN, -58594, 58593, 10387320).1,
+            9211127214545402013
+        );
+    }
+
+    /// `(world seed, chunk x, chunk z, carver seed, carver seed with salt 1, chunk seed, slime chunk)`, from
+    /// `java.util.Random` and the expressions of 1.16.5
+    const CHUNK_SEEDS: &[(i64, i32, i32, i64, i64, i64, bool)] = &[
+        (
+            -7193194438565520372,
+            0,
+            0,
+            -7193194438565520372,
+            -7193194438565520371,
+            -7193194439217209773,
+            false,
+        ),
+        (
+            -7193194438565520372,
+            25,
+            50,
+            2794507340878948727,
+            6810523736610177150,
+            -7193194427681434368,
+            false,
+        ),
+        (
+            -7193194438565520372,
+            -1,
+            -1,
+            6912200617405978091,
+            2918319190056260990,
+            -7193194439186904112,
+            true,
+        ),
+        (
+            -7193194438565520372,
+            -37,
+            12,
+            -6155390203721069063,
+            -8196114841131502136,
+            -7193194440023591616,
+            false,
+        ),
+        (
+            -7193194438565520372,
+            1875000,
+            -1875000,
+            -7937828087875514860,
+            7501532250759500981,
+            -7201774659704020045,
+            false,
+        ),
+        (12345, 0, 0, 12345, 12346, 987247206, false),
+        (
+            12345,
+            25,
+            50,
+            -5919071013641176448,
+            4219108166649392825,
+            10347227603,
+            false,
+        ),
+        (
+            12345,
+            -1,
+            -1,
+            -5600355928774179714,
+            3463934268098055318,
+            989241315,
+            false,
+        ),
+        (
+            12345,
+            -37,
+            12,
+            -5825497708122509198,
+            -8226357656285098067,
+            -1793603309,
+            false,
+        ),
+        (
+            12345,
+            1875000,
+            -1875000,
+            -1441417059799137471,
+            -4335805875143630550,
+            -8580219528924090,
+            false,
+        ),
+    ];
+
+    #[test]
+    fn test_carver_and_chunk_seeds() {
+        for &(world_seed, x, z, carver, carver_salted, chunk, slime) in CHUNK_SEEDS {
+            assert_eq!(random_with_carver_seed(world_seed, x, z, 0).1, carver);
+            assert_eq!(
+                random_with_carver_seed(world_seed, x, z, 1).1,
+                carver_salted
+            );
+            let (mut rng, seed) = random_with_chunk_seed(world_seed, x, z);
+            assert_eq!(seed, chunk);
+            assert_eq!(rng.next_bounded_int(10) == 0, slime, "chunk ({}, {})", x, z);
+        }
+    }
+
+    #[test]
+    fn test_float_vectors() {
+        let vectors = float_vectors("float").collect::<Vec<_>>();
+        assert_eq!(vectors.len(), 1000);
+        for fields in vectors {
+            let seed: i64 = fields[0].parse().unwrap();
+            let bits: i32 = fields[1].parse().unwrap();
+            let float = u32::from_str_radix(fields[2], 16).unwrap();
+
+            assert_eq!(
+                JavaRandom::new(seed).next_float_bits(),
+                bits,
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                JavaRandom::new(seed).next_float().to_bits(),
+                float,
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_float_threshold() {
+        const ONE: i32 = 16777216;
+        for probability in [
+            0.01,
+            0.5,
+            0.3,
+            1.0 / 3.0,
+            0.99,
+            1e-9,
+            0.0,
+            1.0,
+            -0.5,
+            2.0,
+            f32::NAN,
+        ] {
+            let threshold = float_threshold(probability);
+            assert!((0..=ONE).contains(&threshold));
+            // the values next_float can return on either side of the threshold
+            for bits in [
+                0,
+                1,
+                threshold.wrapping_sub(1),
+                threshold,
+                threshold.wrapping_add(1),
+                ONE.wrapping_sub(1),
+            ] {
+                if !(0..ONE).contains(&bits) {
+                    continue;
+                }
+                assert_eq!(
+                    bits < threshold,
+                    (bits as f32 / ONE as f32) < probability,
+                    "{} / 2^24 < {}",
+                    bits,
+                    probability
+                );
+            }
+        }
+        assert_eq!(float_threshold(0.01), 167773);
+    }
+}
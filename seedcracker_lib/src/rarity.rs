@@ -0,0 +1,145 @@
+//! Rough biome frequencies, used to estimate how selective a piece of evidence is.
+//!
+//! The values are rough estimates of the share of the surface covered by each biome in 1.16 worlds. They are only
+//! good enough for ordering checks and estimating information, never for rejecting seeds.
+
+use cubiomes::enums::{BiomeID, Dimension, MCVersion};
+
+#[derive(Debug)]
+pub struct BiomeRarityTable {
+    overworld: &'static [(BiomeID, f64)],
+    nether: &'static [(BiomeID, f64)],
+    /// Frequency of the biomes missing from the tables
+    default_frequency: f64,
+}
+
+pub const BIOME_RARITY_1_16: BiomeRarityTable = BiomeRarityTable {
+    overworld: &[
+        (BiomeID::deep_ocean, 0.06),
+        (BiomeID::ocean, 0.05),
+        (BiomeID::deep_lukewarm_ocean, 0.04),
+        (BiomeID::lukewarm_ocean, 0.03),
+        (BiomeID::deep_cold_ocean, 0.04),
+        (BiomeID::cold_ocean, 0.03),
+        (BiomeID::warm_ocean, 0.025),
+        (BiomeID::deep_frozen_ocean, 0.02),
+        (BiomeID::frozen_ocean, 0.005),
+        (BiomeID::plains, 0.09),
+        (BiomeID::forest, 0.07),
+        (BiomeID::river, 0.05),
+        (BiomeID::taiga, 0.04),
+        (BiomeID::desert, 0.04),
+        (BiomeID::mountains, 0.04),
+        (BiomeID::birch_forest, 0.025),
+        (BiomeID::dark_forest, 0.025),
+        (BiomeID::swamp, 0.025),
+        (BiomeID::savanna, 0.03),
+        (BiomeID::snowy_tundra, 0.03),
+        (BiomeID::wooded_hills, 0.02),
+        (BiomeID::taiga_hills, 0.01),
+        (BiomeID::desert_hills, 0.01),
+        (BiomeID::wooded_mountains, 0.015),
+        (BiomeID::savanna_plateau, 0.01),
+        (BiomeID::beach, 0.025),
+        (BiomeID::snowy_beach, 0.005),
+        (BiomeID::stone_shore, 0.008),
+        (BiomeID::mountain_edge, 0.002),
+        (BiomeID::jungle, 0.012),
+        (BiomeID::jungle_hills, 0.004),
+        (BiomeID::jungle_edge, 0.003),
+        (BiomeID::bamboo_jungle, 0.003),
+        (BiomeID::bamboo_jungle_hills, 0.001),
+        (BiomeID::giant_tree_taiga, 0.012),
+        (BiomeID::giant_tree_taiga_hills, 0.004),
+        (BiomeID::snowy_taiga, 0.012),
+        (BiomeID::snowy_taiga_hills, 0.003),
+        (BiomeID::snowy_mountains, 0.008),
+        (BiomeID::frozen_river, 0.005),
+        (BiomeID::birch_forest_hills, 0.006),
+        (BiomeID::badlands, 0.004),
+        (BiomeID::wooded_badlands_plateau, 0.003),
+        (BiomeID::badlands_plateau, 0.002),
+        (BiomeID::eroded_badlands, 0.0005),
+        (BiomeID::sunflower_plains, 0.004),
+        (BiomeID::flower_forest, 0.004),
+        (BiomeID::gravelly_mountains, 0.002),
+        (BiomeID::desert_lakes, 0.002),
+        (BiomeID::swamp_hills, 0.001),
+        (BiomeID::tall_birch_forest, 0.002),
+        (BiomeID::dark_forest_hills, 0.002),
+        (BiomeID::taiga_mountains, 0.002),
+        (BiomeID::shattered_savanna, 0.002),
+        (BiomeID::ice_spikes, 0.0008),
+        (BiomeID::mushroom_fields, 0.0005),
+        (BiomeID::mushroom_field_shore, 0.0002),
+    ],
+    nether: &[
+        (BiomeID::nether_wastes, 0.35),
+        (BiomeID::soul_sand_valley, 0.2),
+        (BiomeID::crimson_forest, 0.2),
+        (BiomeID::warped_forest, 0.15),
+        (BiomeID::basalt_deltas, 0.1),
+    ],
+    default_frequency: 0.001,
+};
+
+impl BiomeRarityTable {
+    /// Table to use for the given version. Only 1.16 frequencies were estimated, so every version uses them for now.
+    #[inline]
+    pub const fn for_version(version: MCVersion) -> &'static Self {
+        let _ = version;
+        &BIOME_RARITY_1_16
+    }
+
+    /// Probability that a random position of the dimension has this biome
+    pub fn frequency(&self, dimension: Dimension, biome: BiomeID) -> f64 {
+        let table = match dimension {
+            Dimension::DIM_NETHER => self.nether,
+            _ => self.overworld,
+        };
+        table
+            .iter()
+            .find(|(b, _)| *b == biome)
+            .map(|(_, f)| *f)
+            .unwrap_or(self.default_frequency)
+    }
+
+    /// Bits of seed information given by observing the biome at one position
+    #[inline]
+    pub fn information_bits(&self, dimension: Dimension, biome: BiomeID) -> f64 {
+        -self.frequency(dimension, biome).log2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubiomes::enums::{BiomeID, Dimension, MCVersion};
+
+    use crate::rarity::{BIOME_RARITY_1_16, BiomeRarityTable};
+
+    #[test]
+    fn test_biome_rarity() {
+        let table = BiomeRarityTable::for_version(MCVersion::MC_1_16_5);
+        let overworld = Dimension::DIM_OVERWORLD;
+        assert!(
+            table.frequency(overworld, BiomeID::mushroom_fields)
+                < table.frequency(overworld, BiomeID::plains)
+        );
+        assert!(table.information_bits(overworld, BiomeID::mushroom_fields) > 10.0);
+        assert!(table.information_bits(overworld, BiomeID::plains) < 4.0);
+
+        // nether biomes are looked up in the nether table only
+        assert_eq!(
+            table.frequency(overworld, BiomeID::crimson_forest),
+            BIOME_RARITY_1_16.default_frequency
+        );
+        let nether_total: f64 = BIOME_RARITY_1_16.nether.iter().map(|(_, f)| f).sum();
+        assert!((nether_total - 1.0).abs() < 1e-9);
+
+        let overworld_total: f64 = BIOME_RARITY_1_16.overworld.iter().map(|(_, f)| f).sum();
+        assert!(overworld_total <= 1.0);
+        for (_, f) in BIOME_RARITY_1_16.overworld.iter() {
+            assert!(*f > 0.0);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Live counts of the checks rejecting the candidates of a search, to spot misconfigured evidence while it runs: a
+//! buried treasure that never generates for a pillar seed is probably in the wrong chunk.
+//!
+//! The structure stage only counts one candidate out of [`STRUCTURE_SAMPLE_RATE`], its checks take a few nanoseconds
+//! and counting every candidate would slow it down. The world stage counts all of them, next to its biome lookups the
+//! counting is free.
+
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+pub const STRUCTURE_SAMPLE_RATE: i64 = 64;
+
+/// The first check a candidate failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rejection {
+    /// No buried treasure generates in the chunk, see [`crate::features::buried_treasure::generates_at`]
+    NoTreasure,
+    /// The loot doesn't hold the item totals of the observed chest
+    ItemCounts,
+    /// The loot holds the item totals, but not in the observed slots
+    Slots,
+    /// The condition of the search, see [`crate::expr`]
+    Condition,
+    /// No `nextLong` world seed has the structure seed, see [`crate::world_seed::structure_seed_possible_from_nextlong`]
+    NotFromNextLong,
+    /// The world seed generates other pillars than the observed ones, see
+    /// [`crate::features::end_pillars::pillar_seed_from_world_seed`]
+    PillarSeed,
+    OverworldBiome,
+    NetherBiome,
+    /// The biomes don't let a known structure generate, see [`crate::search::WorldExtraData::StructurePresence`]
+    Structure,
+    /// A check failed with an error, see [`crate::search::ConstraintError`]
+    Error,
+}
+
+impl Rejection {
+    pub const ALL: [Rejection; 10] = [
+        Rejection::NoTreasure,
+        Rejection::ItemCounts,
+        Rejection::Slots,
+        Rejection::Condition,
+        Rejection::NotFromNextLong,
+        Rejection::PillarSeed,
+        Rejection::OverworldBiome,
+        Rejection::NetherBiome,
+        Rejection::Structure,
+        Rejection::Error,
+    ];
+
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Rejection::NoTreasure => "no treasure",
+            Rejection::ItemCounts => "item counts",
+            Rejection::Slots => "slots",
+            Rejection::Condition => "condition",
+            Rejection::NotFromNextLong => "not from nextLong",
+            Rejection::PillarSeed => "pillar seed",
+            Rejection::OverworldBiome => "overworld biomes",
+            Rejection::NetherBiome => "nether biomes",
+            Rejection::Structure => "structures",
+            Rejection::Error => "errors",
+        }
+    }
+
+    /// The key of the counter in [`RejectionStats::to_json`]
+    #[inline]
+    pub const fn key(&self) -> &'static str {
+        match self {
+            Rejection::NoTreasure => "no_treasure",
+            Rejection::ItemCounts => "item_counts",
+            Rejection::Slots => "slots",
+            Rejection::Condition => "condition",
+            Rejection::NotFromNextLong => "not_from_nextlong",
+            Rejection::PillarSeed => "pillar_seed",
+            Rejection::OverworldBiome => "overworld_biome",
+            Rejection::NetherBiome => "nether_biome",
+            Rejection::Structure => "structure",
+            Rejection::Error => "error",
+        }
+    }
+}
+
+/// Shared by the threads of a search
+#[derive(Debug, Default)]
+pub struct RejectionCounters {
+    checked: AtomicU64,
+    rejected: [AtomicU64; Rejection::ALL.len()],
+}
+
+impl RejectionCounters {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a checked candidate, `None` if it passed every check
+    #[inline]
+    pub fn record(&self, rejection: Option<Rejection>) {
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        if let Some(rejection) = rejection {
+            self.rejected[rejection as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn stats(&self) -> RejectionStats {
+        RejectionStats {
+            checked: self.checked.load(Ordering::Relaxed),
+            rejected: self
+                .rejected
+                .each_ref()
+                .map(|count| count.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RejectionStats {
+    /// The counted candidates, sampled in the structure stage
+    pub checked: u64,
+    /// By [`Rejection`]
+    pub rejected: [u64; Rejection::ALL.len()],
+}
+
+impl RejectionStats {
+    #[inline]
+    pub const fn count(&self, rejection: Rejection) -> u64 {
+        self.rejected[rejection as usize]
+    }
+
+    /// Share of the counted candidates, 0 before the first one
+    #[inline]
+    pub fn ratio(&self, rejection: Rejection) -> f64 {
+        self.count(rejection) as f64 / self.checked.max(1) as f64
+    }
+
+    /// The counted candidates that passed every check
+    #[inline]
+    pub fn accepted(&self) -> u64 {
+        self.checked - self.rejected.iter().sum::<u64>()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let rejected = Rejection::ALL
+            .iter()
+            .map(|r| (r.key().to_string(), self.count(*r).into()))
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::json!({
+            "checked": self.checked,
+            "rejected": rejected,
+        })
+    }
+
+    /// `None` if a field is missing or has the wrong type, the missing counters are 0
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let rejected = value.get("rejected")?.as_object()?;
+        let mut stats = Self {
+            checked: value.get("checked")?.as_u64()?,
+            rejected: [0; Rejection::ALL.len()],
+        };
+        for rejection in Rejection::ALL {
+            if let Some(count) = rejected.get(rejection.key()) {
+                stats.rejected[rejection as usize] = count.as_u64()?;
+            }
+        }
+        Some(stats)
+    }
+}
+
+/// The rejections that happened, in the order of [`Rejection::ALL`]: `16384 checked: no treasure 99.0%, slots 1.0%`
+impl Display for RejectionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} checked", self.checked)?;
+        let mut first = true;
+        for rejection in Rejection::ALL {
+            if self.count(rejection) == 0 {
+                continue;
+            }
+            write!(
+                f,
+                "{} {} {:.1}%",
+                if first { ":" } else { "," },
+                rejection.name(),
+                self.ratio(rejection) * 100.0
+            )?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rejections::{Rejection, RejectionCounters, RejectionStats};
+
+    #[test]
+    fn test_counters() {
+        let counters = RejectionCounters::new();
+        for _ in 0..6 {
+            counters.record(Some(Rejection::NoTreasure));
+        }
+        counters.record(Some(Rejection::Slots));
+        counters.record(None);
+
+        let stats = counters.stats();
+        assert_eq!(stats.checked, 8);
+        assert_eq!(stats.count(Rejection::NoTreasure), 6);
+        assert_eq!(stats.accepted(), 1);
+        assert_eq!(stats.ratio(Rejection::Slots), 0.125);
+        assert_eq!(
+            stats.to_string(),
+            "8 checked: no treasure 75.0%, slots 12.5%"
+        );
+        assert_eq!(RejectionStats::from_json(&stats.to_json()), Some(stats));
+        assert_eq!(RejectionStats::default().to_string(), "0 checked");
+    }
+}
@@ -0,0 +1,395 @@
+//! Order of the structure stage. Each pillar seed left by the pillar stage is searched in slices of the upper 16 bits
+//! of the RNG state, most likely pillar seed first. Once a pillar seed gave a structure seed that satisfies every piece
+//! of evidence exactly, searching the other ones is usually wasted time: an [`EarlyTermination`] policy then stops
+//! scheduling slices.
+
+use std::{fmt::Display, ops::Range};
+
+/// When the structure stage stops before every pillar seed was searched
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EarlyTermination {
+    /// Every pillar seed is searched
+    #[default]
+    Never,
+    /// Stops at the first structure seed satisfying every piece of evidence exactly
+    OnExactMatch,
+    /// Stops once this many structure seeds were found, exact matches or not
+    OnNMatches(usize),
+}
+
+impl EarlyTermination {
+    #[inline]
+    pub const fn is_triggered(self, exact_matches: usize, matches: usize) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnExactMatch => exact_matches > 0,
+            Self::OnNMatches(n) => matches >= n,
+        }
+    }
+
+    /// `never`, `exact` or a number of matches, as written by [`Display`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "never" => Some(Self::Never),
+            "exact" => Some(Self::OnExactMatch),
+            n => n.parse().ok().filter(|n| *n > 0).map(Self::OnNMatches),
+        }
+    }
+}
+
+impl Display for EarlyTermination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::OnExactMatch => write!(f, "exact"),
+            Self::OnNMatches(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// How well a found structure seed fits the evidence, see [`crate::pipeline::CrackingPipeline::match_strength`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchStrength {
+    Exact,
+    Partial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PillarSeedOutcome {
+    Pending,
+    /// Some of its slices were searched
+    Searching,
+    /// Every slice was searched
+    Searched,
+    /// The policy triggered while it was searched, its other slices were left out
+    Stopped(EarlyTermination),
+    /// The policy triggered before it was searched
+    Skipped(EarlyTermination),
+}
+
+/// The structure seeds found for one pillar seed so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PillarSeedStats {
+    pub pillar_seed: i64,
+    pub exact_matches: usize,
+    pub partial_matches: usize,
+    pub slices_searched: u32,
+    pub outcome: PillarSeedOutcome,
+}
+
+impl PillarSeedStats {
+    #[inline]
+    pub const fn matches(&self) -> usize {
+        self.exact_matches + self.partial_matches
+    }
+}
+
+/// A slice to search, see [`StructureStageScheduler::next_slice`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScheduledSlice {
+    pub pillar_seed: i64,
+    /// Index of the pillar seed in the schedule
+    pub index: usize,
+    /// Index of the slice among the slices of the pillar seed
+    pub slice: u32,
+    /// The values of the upper 16 bits of the RNG state it covers, as taken by
+    /// [`crate::search::StructureSeedSearchData::search_bounded`]
+    pub state_hi: Range<i64>,
+}
+
+/// Hands out the slices of the pillar seeds in order, one at a time, and keeps the match counts of each pillar seed
+/// to stop once the policy triggers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureStageScheduler {
+    pub policy: EarlyTermination,
+    slices_per_pillar_seed: u32,
+    stats: Vec<PillarSeedStats>,
+    /// Pillar seed index and slice scheduled next
+    next: (usize, u32),
+}
+
+impl StructureStageScheduler {
+    /// The pillar seeds are searched in the given order, each in `slices_per_pillar_seed` slices (between 1 and
+    /// 65536)
+    pub fn new(
+        pillar_seeds: impl IntoIterator<Item = i64>,
+        slices_per_pillar_seed: u32,
+        policy: EarlyTermination,
+    ) -> Self {
+        Self {
+            policy,
+            slices_per_pillar_seed: slices_per_pillar_seed.clamp(1, 65536),
+            stats: pillar_seeds
+                .into_iter()
+                .map(|pillar_seed| PillarSeedStats {
+                    pillar_seed,
+                    exact_matches: 0,
+                    partial_matches: 0,
+                    slices_searched: 0,
+                    outcome: PillarSeedOutcome::Pending,
+                })
+                .collect(),
+            next: (0, 0),
+        }
+    }
+
+    #[inline]
+    pub fn stats(&self) -> &[PillarSeedStats] {
+        &self.stats
+    }
+
+    #[inline]
+    pub fn exact_matches(&self) -> usize {
+        self.stats.iter().map(|s| s.exact_matches).sum()
+    }
+
+    #[inline]
+    pub fn matches(&self) -> usize {
+        self.stats.iter().map(PillarSeedStats::matches).sum()
+    }
+
+    #[inline]
+    pub fn is_triggered(&self) -> bool {
+        self.policy
+            .is_triggered(self.exact_matches(), self.matches())
+    }
+
+    /// The next slice to search, after the matches of the previous one were recorded. None once every slice was
+    /// searched or once the policy triggered, the pillar seeds left are then marked as stopped or skipped.
+    pub fn next_slice(&mut self) -> Option<ScheduledSlice> {
+        if self.is_triggered() {
+            self.stop();
+            return None;
+        }
+        let (index, slice) = self.next;
+        let stats = self.stats.get_mut(index)?;
+        stats.outcome = PillarSeedOutcome::Searching;
+        let pillar_seed = stats.pillar_seed;
+        self.next = if slice + 1 == self.slices_per_pillar_seed {
+            (index + 1, 0)
+        } else {
+            (index, slice + 1)
+        };
+
+        let n = self.slices_per_pillar_seed as i64;
+        Some(ScheduledSlice {
+            pillar_seed,
+            index,
+            slice,
+            state_hi: 65536 * slice as i64 / n..65536 * (slice as i64 + 1) / n,
+        })
+    }
+
+    /// Counts the structure seeds found in a slice
+    pub fn record(&mut self, slice: &ScheduledSlice, matches: &[MatchStrength]) {
+        let Some(stats) = self.stats.get_mut(slice.index) else {
+            return;
+        };
+        stats.slices_searched += 1;
+        for strength in matches {
+            match strength {
+                MatchStrength::Exact => stats.exact_matches += 1,
+                MatchStrength::Partial => stats.partial_matches += 1,
+            }
+        }
+        if stats.slices_searched == self.slices_per_pillar_seed {
+            stats.outcome = PillarSeedOutcome::Searched;
+        }
+    }
+
+    /// Searches the slices with `search` until they are all searched or the policy triggers
+    pub fn run(&mut self, mut search: impl FnMut(&ScheduledSlice) -> Vec<MatchStrength>) {
+        while let Some(slice) = self.next_slice() {
+            let matches = search(&slice);
+            self.record(&slice, &matches);
+        }
+    }
+
+    /// Searches the pillar seeds no slice was handed out for in the given order, the ones left out are dropped. A
+    /// pillar seed that is partly searched keeps its place.
+    pub fn reschedule(&mut self, pillar_seeds: impl IntoIterator<Item = i64>) {
+        let (index, slice) = self.next;
+        let kept = if slice == 0 { index } else { index + 1 };
+        self.stats.truncate(kept);
+        self.stats
+            .extend(pillar_seeds.into_iter().map(|pillar_seed| PillarSeedStats {
+                pillar_seed,
+                exact_matches: 0,
+                partial_matches: 0,
+                slices_searched: 0,
+                outcome: PillarSeedOutcome::Pending,
+            }));
+    }
+
+    fn stop(&mut self) {
+        let policy = self.policy;
+        for stats in self.stats.iter_mut() {
+            stats.outcome = match stats.outcome {
+                PillarSeedOutcome::Pending => PillarSeedOutcome::Skipped(policy),
+                PillarSeedOutcome::Searching => PillarSeedOutcome::Stopped(policy),
+                outcome => outcome,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schedule::{
+        EarlyTermination, MatchStrength, PillarSeedOutcome, ScheduledSlice, StructureStageScheduler,
+    };
+
+    const PILLAR_SEEDS: [i64; 5] = [13847, 2, 40000, 512, 65535];
+
+    /// A partial match late in the first pillar seed and an exact one early in the second
+    fn mock_evidence(slice: &ScheduledSlice) -> Vec<MatchStrength> {
+        match (slice.pillar_seed, slice.slice) {
+            (13847, 6) => vec![MatchStrength::Partial],
+            (2, 1) => vec![MatchStrength::Exact],
+            _ => vec![],
+        }
+    }
+
+    fn run(policy: EarlyTermination) -> (StructureStageScheduler, Vec<ScheduledSlice>) {
+        let mut scheduler = StructureStageScheduler::new(PILLAR_SEEDS, 8, policy);
+        let mut searched = Vec::new();
+        scheduler.run(|slice| {
+            searched.push(slice.clone());
+            mock_evidence(slice)
+        });
+        (scheduler, searched)
+    }
+
+    #[test]
+    fn test_stops_on_exact_match() {
+        let (scheduler, searched) = run(EarlyTermination::OnExactMatch);
+        // the first pillar seed in full, then the second up to the exact match
+        assert_eq!(searched.len(), 8 + 2);
+        assert_eq!(
+            searched.last().map(|s| (s.pillar_seed, s.slice)),
+            Some((2, 1))
+        );
+        assert!(scheduler.is_triggered());
+        assert_eq!((scheduler.exact_matches(), scheduler.matches()), (1, 2));
+
+        let stats = scheduler.stats();
+        assert_eq!(stats[0].outcome, PillarSeedOutcome::Searched);
+        assert_eq!((stats[0].partial_matches, stats[0].exact_matches), (1, 0));
+        assert_eq!(
+            stats[1].outcome,
+            PillarSeedOutcome::Stopped(EarlyTermination::OnExactMatch)
+        );
+        assert_eq!(stats[1].slices_searched, 2);
+        for stats in &stats[2..] {
+            assert_eq!(
+                stats.outcome,
+                PillarSeedOutcome::Skipped(EarlyTermination::OnExactMatch)
+            );
+            assert_eq!(stats.slices_searched, 0);
+        }
+        assert_eq!(
+            stats.iter().map(|s| s.pillar_seed).collect::<Vec<_>>(),
+            PILLAR_SEEDS
+        );
+    }
+
+    #[test]
+    fn test_match_count_policies() {
+        let (scheduler, searched) = run(EarlyTermination::Never);
+        assert_eq!(searched.len(), 5 * 8);
+        assert!(
+            scheduler
+                .stats()
+                .iter()
+                .all(|s| s.outcome == PillarSeedOutcome::Searched)
+        );
+        // the slices of a pillar seed cover the whole upper 16 bits, in order
+        for pillar in searched.chunks(8) {
+            assert_eq!(pillar[0].state_hi.start, 0);
+            assert_eq!(pillar[7].state_hi.end, 65536);
+            assert!(
+                pillar
+                    .windows(2)
+                    .all(|w| w[0].state_hi.end == w[1].state_hi.start)
+            );
+        }
+
+        // the partial match counts too
+        let (scheduler, searched) = run(EarlyTermination::OnNMatches(1));
+        assert_eq!(searched.len(), 7);
+        assert_eq!(
+            scheduler.stats()[0].outcome,
+            PillarSeedOutcome::Stopped(EarlyTermination::OnNMatches(1))
+        );
+        let (_, searched) = run(EarlyTermination::OnNMatches(2));
+        assert_eq!(searched.len(), 10);
+        let (scheduler, searched) = run(EarlyTermination::OnNMatches(3));
+        assert_eq!(searched.len(), 40);
+        assert!(!scheduler.is_triggered());
+
+        // a match in the last slice of a pillar seed leaves it searched in full
+        let mut scheduler =
+            StructureStageScheduler::new(PILLAR_SEEDS, 1, EarlyTermination::OnExactMatch);
+        scheduler.run(|slice| match slice.index {
+            0 => vec![MatchStrength::Exact],
+            _ => unreachable!(),
+        });
+        assert_eq!(scheduler.stats()[0].outcome, PillarSeedOutcome::Searched);
+        assert_eq!(
+            scheduler.stats()[1].outcome,
+            PillarSeedOutcome::Skipped(EarlyTermination::OnExactMatch)
+        );
+        assert_eq!(scheduler.next_slice(), None);
+    }
+
+    #[test]
+    fn test_reschedule() {
+        let mut scheduler = StructureStageScheduler::new(PILLAR_SEEDS, 2, EarlyTermination::Never);
+        assert_eq!(scheduler.next_slice().map(|s| s.pillar_seed), Some(13847));
+
+        // the first pillar seed is half searched and stays first
+        scheduler.reschedule([512, 2]);
+        let slices = std::iter::from_fn(|| scheduler.next_slice())
+            .map(|s| (s.pillar_seed, s.index, s.slice))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            slices,
+            [
+                (13847, 0, 1),
+                (512, 1, 0),
+                (512, 1, 1),
+                (2, 2, 0),
+                (2, 2, 1)
+            ]
+        );
+        assert_eq!(
+            scheduler
+                .stats()
+                .iter()
+                .map(|s| s.pillar_seed)
+                .collect::<Vec<_>>(),
+            [13847, 512, 2]
+        );
+
+        // nothing left to reorder
+        scheduler.reschedule([]);
+        assert_eq!(scheduler.stats().len(), 3);
+        assert_eq!(scheduler.next_slice(), None);
+    }
+
+    #[test]
+    fn test_policy_names() {
+        for policy in [
+            EarlyTermination::Never,
+            EarlyTermination::OnExactMatch,
+            EarlyTermination::OnNMatches(3),
+        ] {
+            assert_eq!(
+                EarlyTermination::from_name(&policy.to_string()),
+                Some(policy)
+            );
+        }
+        assert_eq!(EarlyTermination::from_name("0"), None);
+        assert_eq!(EarlyTermination::from_name("sometimes"), None);
+    }
+}
@@ -1,22 +1,49 @@
 use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    io::{Read, Write},
+    ops::Range,
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
     },
     thread::{self, JoinHandle},
+    time::Duration,
 };
 
 use cubiomes::{
-    enums::{BiomeID, Dimension, MCVersion},
-    generator::{Generator, GeneratorFlags},
+    enums::{BiomeID, Dimension, MCVersion, StructureType},
+    generator::{BlockPosition, Generator, GeneratorFlags},
 };
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
 
 use crate::{
-    features::buried_treasure,
+    biome_cache::BiomeCache,
+    candidates::{StructureSeedCandidate, WorldSeedCandidate},
+    cooperative::{Cooperation, SearchOptions, run_chunked},
+    expr::EvidenceExpr,
+    features::{
+        bastion::{self, BastionChestType},
+        buried_treasure::{self, ChunkArea},
+        decoration::{self, DecorationKind},
+        decorator_chest::ChestIndex,
+        end_pillars::{PillarMatchResult, pillar_seed_from_world_seed},
+        monument,
+    },
+    fingerprint::EvidenceFingerprint,
+    heartbeat::Heartbeat,
     lcg,
-    loot_table::{FastInventoryCompareContext, SingleChest},
+    loot_table::{FastInventoryCompareContext, ItemCountConstraints, LootTable, SingleChest},
+    math::Math,
+    random::JavaRandom,
+    rarity::BiomeRarityTable,
+    rejections::{Rejection, RejectionCounters, RejectionStats, STRUCTURE_SAMPLE_RATE},
     utils::{likely, unlikely},
+    world_seed::{
+        SeedOrigin, next_long_world_seed, structure_seed_possible_from_nextlong,
+        typed_numeric_completions,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -26,255 +53,2000 @@ pub enum StructureData {
         chunk_z: i32,
         luck: f32,
         contents: FastInventoryCompareContext<SingleChest, 12>,
+        /// Replaces the vanilla loot table, see [`crate::loot_table::from_datapack_json`]
+        loot_table: Option<Arc<LootTable>>,
+    },
+    /// Only the item totals of the buried treasure are known
+    BuriedTreasureCounts {
+        chunk_x: i32,
+        chunk_z: i32,
+        luck: f32,
+        counts: ItemCountConstraints<12>,
+        loot_table: Option<Arc<LootTable>>,
+    },
+    /// The buried treasure contents are known, but only an area around its chunk
+    BuriedTreasureContentsInArea {
+        area: ChunkArea,
+        luck: f32,
+        contents: FastInventoryCompareContext<SingleChest, 12>,
+        loot_table: Option<Arc<LootTable>>,
     },
+    /// A bastion remnant chest, built with [`StructureData::bastion_chest`]. Its chunk is the chest's, which can be a
+    /// few chunks away from the bastion start.
+    BastionChestContents {
+        chunk_x: i32,
+        chunk_z: i32,
+        index: ChestIndex,
+        chest_type: BastionChestType,
+        luck: f32,
+        contents: FastInventoryCompareContext<SingleChest, { bastion::ITEM_COUNT }>,
+        /// The loot table of `chest_type`, kept so it isn't rebuilt for every seed
+        loot_table: Arc<LootTable>,
+    },
+}
+
+#[inline]
+fn same_loot_table(a: &Option<Arc<LootTable>>, b: &Option<Arc<LootTable>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+impl PartialEq for StructureData {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                StructureData::BuriedTreasureContents {
+                    chunk_x,
+                    chunk_z,
+                    luck,
+                    contents,
+                    loot_table,
+                },
+                StructureData::BuriedTreasureContents {
+                    chunk_x: other_chunk_x,
+                    chunk_z: other_chunk_z,
+                    luck: other_luck,
+                    contents: other_contents,
+                    loot_table: other_loot_table,
+                },
+            ) => {
+                chunk_x == other_chunk_x
+                    && chunk_z == other_chunk_z
+                    && luck == other_luck
+                    && contents == other_contents
+                    && same_loot_table(loot_table, other_loot_table)
+            }
+            (
+                StructureData::BuriedTreasureCounts {
+                    chunk_x,
+                    chunk_z,
+                    luck,
+                    counts,
+                    loot_table,
+                },
+                StructureData::BuriedTreasureCounts {
+                    chunk_x: other_chunk_x,
+                    chunk_z: other_chunk_z,
+                    luck: other_luck,
+                    counts: other_counts,
+                    loot_table: other_loot_table,
+                },
+            ) => {
+                chunk_x == other_chunk_x
+                    && chunk_z == other_chunk_z
+                    && luck == other_luck
+                    && counts == other_counts
+                    && same_loot_table(loot_table, other_loot_table)
+            }
+            (
+                StructureData::BuriedTreasureContentsInArea {
+                    area,
+                    luck,
+                    contents,
+                    loot_table,
+                },
+                StructureData::BuriedTreasureContentsInArea {
+                    area: other_area,
+                    luck: other_luck,
+                    contents: other_contents,
+                    loot_table: other_loot_table,
+                },
+            ) => {
+                area == other_area
+                    && luck == other_luck
+                    && contents == other_contents
+                    && same_loot_table(loot_table, other_loot_table)
+            }
+            (
+                StructureData::BastionChestContents {
+                    chunk_x,
+                    chunk_z,
+                    index,
+                    chest_type,
+                    luck,
+                    contents,
+                    ..
+                },
+                StructureData::BastionChestContents {
+                    chunk_x: other_chunk_x,
+                    chunk_z: other_chunk_z,
+                    index: other_index,
+                    chest_type: other_chest_type,
+                    luck: other_luck,
+                    contents: other_contents,
+                    ..
+                },
+            ) => {
+                // the loot table is the one of the chest type
+                chunk_x == other_chunk_x
+                    && chunk_z == other_chunk_z
+                    && index == other_index
+                    && chest_type == other_chest_type
+                    && luck == other_luck
+                    && contents == other_contents
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The bits of `luck` for the Hash impls, -0.0 compares equal to 0.0 so it gets its bits
+#[inline]
+fn luck_bits(luck: f32) -> u32 {
+    if luck == 0.0 { 0.0f32 } else { luck }.to_bits()
+}
+
+impl Hash for StructureData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            StructureData::BuriedTreasureContents {
+                chunk_x,
+                chunk_z,
+                luck,
+                contents,
+                loot_table,
+            } => {
+                0u8.hash(state);
+                chunk_x.hash(state);
+                chunk_z.hash(state);
+                luck_bits(*luck).hash(state);
+                contents.hash(state);
+                // loot tables are compared by identity, which can't be hashed in a stable way
+                loot_table.is_some().hash(state);
+            }
+            StructureData::BuriedTreasureCounts {
+                chunk_x,
+                chunk_z,
+                luck,
+                counts,
+                loot_table,
+            } => {
+                1u8.hash(state);
+                chunk_x.hash(state);
+                chunk_z.hash(state);
+                luck_bits(*luck).hash(state);
+                counts.hash(state);
+                loot_table.is_some().hash(state);
+            }
+            StructureData::BuriedTreasureContentsInArea {
+                area,
+                luck,
+                contents,
+                loot_table,
+            } => {
+                2u8.hash(state);
+                area.hash(state);
+                luck_bits(*luck).hash(state);
+                contents.hash(state);
+                loot_table.is_some().hash(state);
+            }
+            StructureData::BastionChestContents {
+                chunk_x,
+                chunk_z,
+                index,
+                chest_type,
+                luck,
+                contents,
+                ..
+            } => {
+                3u8.hash(state);
+                chunk_x.hash(state);
+                chunk_z.hash(state);
+                index.hash(state);
+                chest_type.hash(state);
+                luck_bits(*luck).hash(state);
+                contents.hash(state);
+            }
+        }
+    }
 }
 
 impl StructureData {
+    /// The contents of a bastion chest in `chunk`, compared against the vanilla loot table of `chest_type`
+    pub fn bastion_chest(
+        chunk: (i32, i32),
+        index: ChestIndex,
+        chest_type: BastionChestType,
+        luck: f32,
+        contents: FastInventoryCompareContext<SingleChest, { bastion::ITEM_COUNT }>,
+    ) -> Self {
+        StructureData::BastionChestContents {
+            chunk_x: chunk.0,
+            chunk_z: chunk.1,
+            index,
+            chest_type,
+            luck,
+            contents,
+            loot_table: Arc::new(bastion::get_loot_table(chest_type)),
+        }
+    }
+
     #[inline(always)]
-    fn check_seed(&self, seed: i64) -> bool {
+    pub(crate) fn check_seed(&self, seed: i64) -> bool {
         match self {
             StructureData::BuriedTreasureContents {
                 contents,
                 chunk_x,
                 chunk_z,
                 luck,
+                loot_table,
             } => {
                 unlikely(buried_treasure::generates_at(seed, (*chunk_x, *chunk_z)))
-                    && unlikely(buried_treasure::compare_buried_treasure_fast_noinv(
+                    && unlikely(compare_contents(
                         seed,
                         (*chunk_x, *chunk_z),
                         *luck,
                         contents,
+                        loot_table,
                     ))
             }
+            StructureData::BuriedTreasureCounts {
+                chunk_x,
+                chunk_z,
+                luck,
+                counts,
+                loot_table,
+            } => {
+                unlikely(buried_treasure::generates_at(seed, (*chunk_x, *chunk_z)))
+                    && unlikely(match loot_table {
+                        None => buried_treasure::compare_buried_treasure_counts(
+                            seed,
+                            (*chunk_x, *chunk_z),
+                            *luck,
+                            counts,
+                        ),
+                        Some(loot_table) => {
+                            buried_treasure::compare_buried_treasure_counts_with_table(
+                                loot_table,
+                                seed,
+                                (*chunk_x, *chunk_z),
+                                *luck,
+                                counts,
+                            )
+                        }
+                    })
+            }
+            StructureData::BuriedTreasureContentsInArea { .. } => {
+                unlikely(self.matching_chunk(seed).is_some())
+            }
+            StructureData::BastionChestContents {
+                chunk_x,
+                chunk_z,
+                index,
+                luck,
+                contents,
+                loot_table,
+                ..
+            } => unlikely(
+                bastion::compare_bastion_chest_fast_noinv(
+                    seed,
+                    (*chunk_x, *chunk_z),
+                    *index,
+                    loot_table,
+                    *luck,
+                    contents,
+                )
+                .is_some(),
+            ),
+        }
+    }
+
+    /// The chunk of the chest for `seed`, for the evidence that only gives an area the first chunk of the area that
+    /// matches. `None` if the seed doesn't match.
+    #[inline]
+    pub fn matching_chunk(&self, seed: i64) -> Option<(i32, i32)> {
+        match self {
+            StructureData::BuriedTreasureContents {
+                chunk_x, chunk_z, ..
+            }
+            | StructureData::BuriedTreasureCounts {
+                chunk_x, chunk_z, ..
+            }
+            | StructureData::BastionChestContents {
+                chunk_x, chunk_z, ..
+            } => self.check_seed(seed).then_some((*chunk_x, *chunk_z)),
+            StructureData::BuriedTreasureContentsInArea {
+                area,
+                luck,
+                contents,
+                loot_table,
+            } => area.find_chunk(seed, |chunk| {
+                compare_contents(seed, chunk, *luck, contents, loot_table)
+            }),
+        }
+    }
+
+    /// Same as the check of the structure seed search, but through the full loot generation instead of its early
+    /// exits. Slower, for re-checking the few seeds the search found.
+    pub fn verify_seed(&self, seed: i64) -> bool {
+        match self {
+            StructureData::BuriedTreasureContents {
+                chunk_x, chunk_z, ..
+            }
+            | StructureData::BuriedTreasureCounts {
+                chunk_x, chunk_z, ..
+            } => {
+                buried_treasure::generates_at(seed, (*chunk_x, *chunk_z))
+                    && self.verify_chest(seed, (*chunk_x, *chunk_z))
+            }
+            StructureData::BuriedTreasureContentsInArea { area, .. } => area
+                .find_chunk(seed, |chunk| self.verify_chest(seed, chunk))
+                .is_some(),
+            StructureData::BastionChestContents {
+                chunk_x, chunk_z, ..
+            } => self.verify_chest(seed, (*chunk_x, *chunk_z)),
+        }
+    }
+
+    /// Whether the chest of `seed` holds the observed stacks slot for slot, see
+    /// [`FastInventoryCompareContext::accepts_exactly`]. Always false for the evidence that only gives item totals.
+    pub fn matches_exactly(&self, seed: i64) -> bool {
+        let Some(chunk) = self.matching_chunk(seed) else {
+            return false;
+        };
+        self.loot_seeds(seed, chunk).into_iter().any(|loot_seed| {
+            self.with_loot(loot_seed, |loot_table, rng, luck| {
+                let mut chest = SingleChest::new();
+                match self {
+                    StructureData::BuriedTreasureContents { contents, .. }
+                    | StructureData::BuriedTreasureContentsInArea { contents, .. } => {
+                        loot_table.generate_in_inventory(&mut chest, rng, luck);
+                        contents.accepts_exactly(&chest)
+                    }
+                    StructureData::BuriedTreasureCounts { .. } => false,
+                    StructureData::BastionChestContents { contents, .. } => {
+                        loot_table.generate_in_inventory(&mut chest, rng, luck);
+                        contents.accepts_exactly(&chest)
+                    }
+                }
+            })
+        })
+    }
+
+    /// The first check of [`StructureData::check_seed`] that `seed` fails, `None` if it passes. Slower, for the
+    /// sampled candidates of [`RejectionCounters`].
+    pub(crate) fn rejection(&self, seed: i64) -> Option<Rejection> {
+        let chunks = match self {
+            StructureData::BuriedTreasureContents {
+                chunk_x, chunk_z, ..
+            }
+            | StructureData::BuriedTreasureCounts {
+                chunk_x, chunk_z, ..
+            } => [(*chunk_x, *chunk_z)]
+                .into_iter()
+                .filter(|chunk| buried_treasure::generates_at(seed, *chunk))
+                .collect(),
+            StructureData::BuriedTreasureContentsInArea { area, .. } => area.treasure_chunks(seed),
+            // the bastion start can be chunks away from its chests, there is no structure check
+            StructureData::BastionChestContents {
+                chunk_x, chunk_z, ..
+            } => vec![(*chunk_x, *chunk_z)],
+        };
+        if chunks.is_empty() {
+            return Some(Rejection::NoTreasure);
+        }
+        if self.check_seed(seed) {
+            return None;
+        }
+        if chunks.iter().any(|chunk| self.totals_match(seed, *chunk)) {
+            Some(Rejection::Slots)
+        } else {
+            Some(Rejection::ItemCounts)
+        }
+    }
+
+    /// Whether the loot of the chest in `chunk` holds the observed item totals, wherever they are
+    fn totals_match(&self, seed: i64, chunk: (i32, i32)) -> bool {
+        self.loot_seeds(seed, chunk).into_iter().any(|loot_seed| {
+            self.with_loot(loot_seed, |loot_table, rng, luck| match self {
+                StructureData::BuriedTreasureContents { contents, .. }
+                | StructureData::BuriedTreasureContentsInArea { contents, .. } => {
+                    known_totals_match(contents, &loot_totals(loot_table, rng, luck))
+                }
+                StructureData::BuriedTreasureCounts { counts, .. } => {
+                    counts.matches(&loot_totals(loot_table, rng, luck))
+                }
+                StructureData::BastionChestContents { contents, .. } => {
+                    known_totals_match(contents, &loot_totals(loot_table, rng, luck))
+                }
+            })
+        })
+    }
+
+    /// Regenerates the chest in `chunk`
+    fn verify_chest(&self, seed: i64, chunk: (i32, i32)) -> bool {
+        self.loot_seeds(seed, chunk).into_iter().any(|loot_seed| {
+            self.with_loot(loot_seed, |loot_table, rng, luck| {
+                let mut chest = SingleChest::new();
+                match self {
+                    StructureData::BuriedTreasureContents { contents, .. }
+                    | StructureData::BuriedTreasureContentsInArea { contents, .. } => {
+                        loot_table.generate_in_inventory(&mut chest, rng, luck);
+                        contents.accepts(&chest)
+                    }
+                    StructureData::BuriedTreasureCounts { counts, .. } => {
+                        counts.matches(&loot_totals(loot_table, rng, luck))
+                    }
+                    StructureData::BastionChestContents { contents, .. } => {
+                        loot_table.generate_in_inventory(&mut chest, rng, luck);
+                        contents.accepts(&chest)
+                    }
+                }
+            })
+        })
+    }
+
+    /// The loot table seeds of the chest in `chunk`: one for a buried treasure, one per allowed index for a bastion
+    /// chest
+    fn loot_seeds(&self, seed: i64, chunk: (i32, i32)) -> Vec<i64> {
+        match self {
+            StructureData::BuriedTreasureContents { .. }
+            | StructureData::BuriedTreasureCounts { .. }
+            | StructureData::BuriedTreasureContentsInArea { .. } => {
+                vec![buried_treasure::get_buried_treasure_loot_table_seed(
+                    seed, chunk,
+                )]
+            }
+            StructureData::BastionChestContents { index, .. } => {
+                bastion::get_bastion_chest_seeds(seed, chunk)
+                    .loot_table_seeds(index.indices())
+                    .map(|(_, loot_seed)| loot_seed)
+                    .collect()
+            }
+        }
+    }
+
+    /// Calls `f` with the loot table of the evidence, the loot RNG seeded with `loot_seed` and the luck
+    fn with_loot<T>(
+        &self,
+        loot_seed: i64,
+        f: impl FnOnce(&LootTable, &mut JavaRandom, f32) -> T,
+    ) -> T {
+        let (luck, loot_table) = match self {
+            StructureData::BuriedTreasureContents {
+                luck, loot_table, ..
+            }
+            | StructureData::BuriedTreasureCounts {
+                luck, loot_table, ..
+            }
+            | StructureData::BuriedTreasureContentsInArea {
+                luck, loot_table, ..
+            } => (*luck, loot_table.as_deref()),
+            StructureData::BastionChestContents {
+                luck, loot_table, ..
+            } => (*luck, Some(loot_table.as_ref())),
+        };
+
+        let vanilla;
+        let loot_table = match loot_table {
+            Some(loot_table) => loot_table,
+            None => {
+                vanilla = buried_treasure::get_loot_table();
+                &vanilla
+            }
+        };
+        f(loot_table, &mut JavaRandom::new(loot_seed), luck)
+    }
+}
+
+/// The total count of each item id of the loot
+fn loot_totals<const N: usize>(
+    loot_table: &LootTable,
+    rng: &mut JavaRandom,
+    luck: f32,
+) -> [i32; N] {
+    let mut totals = [0; N];
+    for stack in loot_table.generate_raw_loot(rng, luck) {
+        if let Some(total) = totals.get_mut(stack.item) {
+            *total += stack.count;
+        }
+    }
+    totals
+}
+
+/// Whether the generated totals can hold the observed chest
+fn known_totals_match<const N: usize>(
+    contents: &FastInventoryCompareContext<SingleChest, N>,
+    totals: &[i32; N],
+) -> bool {
+    match contents.known_slots {
+        // the unseen slots hold more items
+        Some(_) => totals
+            .iter()
+            .zip(contents.items_count.iter())
+            .all(|(generated, known)| generated >= known),
+        None => *totals == contents.items_count,
+    }
+}
+
+/// The fast chest comparison of the buried treasure in `chunk`, which is assumed to generate
+#[inline(always)]
+fn compare_contents(
+    seed: i64,
+    chunk: (i32, i32),
+    luck: f32,
+    contents: &FastInventoryCompareContext<SingleChest, 12>,
+    loot_table: &Option<Arc<LootTable>>,
+) -> bool {
+    match (loot_table, contents.slot_threshold) {
+        (None, None) => {
+            buried_treasure::compare_buried_treasure_fast_noinv(seed, chunk, luck, contents)
+        }
+        (Some(loot_table), None) => buried_treasure::compare_buried_treasure_fast_noinv_with_table(
+            loot_table, seed, chunk, luck, contents,
+        ),
+        (None, Some(_)) => {
+            buried_treasure::compare_buried_treasure_threshold(seed, chunk, luck, contents)
+        }
+        (Some(loot_table), Some(_)) => {
+            buried_treasure::compare_buried_treasure_threshold_with_table(
+                loot_table, seed, chunk, luck, contents,
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Searching,
+    /// It found some seeds (maybe none)
+    Complete {
+        /// The seeds that were found
+        seeds: Vec<i64>,
+    },
+    /// It found more seeds than the maximum number of seeds allowed
+    TooManySeeds {
+        /// Some of seeds that were found
+        seeds_incomplete: Vec<i64>,
+    },
+    /// The search was cancelled
+    Cancelled {
+        /// Some of seeds that were found
+        seeds_incomplete: Vec<i64>,
+    },
+}
+
+/// Rebuilds the structure seed from the 48 bit RNG state after the pillar seed's nextLong call,
+/// and returns it if it matches all the data
+#[inline(always)]
+fn check_structure_seed_candidate(
+    data: &[StructureData],
+    condition: Option<&EvidenceExpr>,
+    pillar_seed: i64,
+    nextlong_only: bool,
+    state_hi: i64,
+    state_lo: i64,
+) -> Option<i64> {
+    let seed = structure_seed_of(pillar_seed, state_hi, state_lo);
+
+    for d in data.iter() {
+        if likely(!d.check_seed(seed)) {
+            return None;
+        }
+    }
+    if condition.is_some_and(|c| c.check_structure_seed(seed) == Some(false)) {
+        return None;
+    }
+    // last, it loops over 65536 states
+    if nextlong_only && !structure_seed_possible_from_nextlong(seed) {
+        return None;
+    }
+
+    Some(seed)
+}
+
+#[inline(always)]
+fn structure_seed_of(pillar_seed: i64, state_hi: i64, state_lo: i64) -> i64 {
+    let state = (state_hi << 32) | ((pillar_seed & 0xFFFF) << 16) | state_lo;
+    let reversed_state = lcg::JAVA_RANDOM_REV2.next_seed(state);
+    reversed_state ^ lcg::JAVA_RANDOM.get_multiplier()
+}
+
+/// The first check of [`check_structure_seed_candidate`] that rejects `seed`
+fn structure_seed_rejection(
+    data: &[StructureData],
+    condition: Option<&EvidenceExpr>,
+    nextlong_only: bool,
+    seed: i64,
+) -> Option<Rejection> {
+    if let Some(rejection) = data.iter().find_map(|d| d.rejection(seed)) {
+        return Some(rejection);
+    }
+    if condition.is_some_and(|c| c.check_structure_seed(seed) == Some(false)) {
+        return Some(Rejection::Condition);
+    }
+    if nextlong_only && !structure_seed_possible_from_nextlong(seed) {
+        return Some(Rejection::NotFromNextLong);
+    }
+    None
+}
+
+pub struct StructureSeedSearchData {
+    pub pillar_seed: i64,
+    /// [`PillarMatchResult::chance`] of the pillar seed, for the [`crate::candidates::CandidateScore`] of the found
+    /// seeds
+    pub pillar_chance: f64,
+    pub data: Vec<StructureData>,
+    pub max_results: u16,
+    /// Runs the search in small chunks on its own thread instead of the rayon pool, see [`Cooperation`]
+    pub cooperation: Option<Cooperation>,
+    pub search_options: SearchOptions,
+    /// Drops the structure seeds no `new Random().nextLong()` world seed has, see
+    /// [`structure_seed_possible_from_nextlong`]. Only for worlds whose seed was generated, not typed.
+    pub nextlong_only: bool,
+    /// Checked on top of `data`, see [`crate::expr`]
+    pub condition: Option<Arc<EvidenceExpr>>,
+    /// The values of the upper 16 bits of the RNG state [`StructureSeedSearch::start`] searches, all of them by
+    /// default
+    pub state_hi: Range<i64>,
+    /// Values of `state_hi` searched between two [`SearchCheckpoint`]s
+    pub checkpoint_interval: u32,
+}
+
+impl StructureSeedSearchData {
+    #[inline]
+    pub const fn new(pillar_seed: i64, data: Vec<StructureData>, max_results: u16) -> Self {
+        Self {
+            pillar_seed,
+            pillar_chance: 1.0,
+            data,
+            max_results,
+            cooperation: None,
+            search_options: SearchOptions::DEFAULT,
+            nextlong_only: false,
+            condition: None,
+            state_hi: 0..65536,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
         }
     }
+
+    #[inline]
+    pub const fn with_cooperation(mut self, cooperation: Option<Cooperation>) -> Self {
+        self.cooperation = cooperation;
+        self
+    }
+
+    #[inline]
+    pub const fn with_nextlong_only(mut self, nextlong_only: bool) -> Self {
+        self.nextlong_only = nextlong_only;
+        self
+    }
+
+    #[inline]
+    pub const fn with_search_options(mut self, search_options: SearchOptions) -> Self {
+        self.search_options = search_options;
+        self
+    }
+
+    #[inline]
+    pub const fn with_pillar_chance(mut self, pillar_chance: f64) -> Self {
+        self.pillar_chance = pillar_chance;
+        self
+    }
+
+    /// The condition is ordered by selectivity, see [`EvidenceExpr::order_by_selectivity`]
+    pub fn with_condition(mut self, condition: Option<EvidenceExpr>) -> Self {
+        self.condition = condition.map(|mut condition| {
+            condition.order_by_selectivity(BiomeRarityTable::for_version(MCVersion::MC_1_16_5));
+            Arc::new(condition)
+        });
+        self
+    }
+
+    /// Each value of `state_hi` covers 65536 candidates, the range is clamped to `0..65536`
+    #[inline]
+    pub fn with_state_hi(mut self, state_hi: Range<i64>) -> Self {
+        self.state_hi = state_hi.start.clamp(0, 65536)..state_hi.end.clamp(0, 65536);
+        self
+    }
+
+    #[inline]
+    pub const fn with_checkpoint_interval(mut self, checkpoint_interval: u32) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self
+    }
+
+    /// Hash of everything the found seeds depend on, besides the pillar seed and the range, see [`EvidenceFingerprint`]
+    pub fn fingerprint(&self) -> EvidenceFingerprint {
+        EvidenceFingerprint::of(&(&self.data, self.nextlong_only, self.condition.as_deref()))
+    }
+
+    /// Single threaded search over a sub-range of the upper 16 bits of the RNG state (the full search covers `0..65536`).
+    /// Each value of `state_hi` covers 65536 candidates.
+    ///
+    /// ```
+    /// use mcseedcracker::{
+    ///     features::buried_treasure::{
+    ///         build_fast_inventory_compare_context, get_buried_treasure, get_loot_table,
+    ///     },
+    ///     search::{StructureData, StructureSeedSearchData},
+    /// };
+    ///
+    /// let structure_seed = 180066252004364;
+    /// let contents = get_buried_treasure(structure_seed, (25, 50), 0.0);
+    ///
+    /// let search = StructureSeedSearchData::new(
+    ///     13847,
+    ///     vec![StructureData::BuriedTreasureContents {
+    ///         chunk_x: 25,
+    ///         chunk_z: 50,
+    ///         luck: 0.0,
+    ///         contents: build_fast_inventory_compare_context(contents, &get_loot_table()).unwrap(),
+    ///         loot_table: None,
+    ///     }],
+    ///     10,
+    /// );
+    /// assert_eq!(search.search_bounded(47520..47525), vec![structure_seed]);
+    /// assert!(search.search_bounded(0..2).is_empty());
+    /// ```
+    pub fn search_bounded(&self, state_hi: Range<i64>) -> Vec<i64> {
+        let mut results = Vec::new();
+        for hi in state_hi {
+            for lo in 0..65536 {
+                if let Some(seed) = check_structure_seed_candidate(
+                    &self.data,
+                    self.condition.as_deref(),
+                    self.pillar_seed,
+                    self.nextlong_only,
+                    hi,
+                    lo,
+                ) {
+                    results.push(seed);
+                }
+            }
+        }
+        results
+    }
+}
+
+/// How well a found structure seed is supported by the evidence, used to pick which seeds to search when there are too
+/// many of them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StructureSeedAnnotation {
+    pub structure_seed: i64,
+    pub pillar_seed: i64,
+    pub pillar_match: PillarMatchResult,
+    /// Number of structure evidence items the seed satisfies
+    pub satisfied_evidence: usize,
+    /// The chunk the buried treasure matched in, for the evidence that only gives an area
+    pub treasure_chunk: Option<(i32, i32)>,
+}
+
+impl StructureSeedAnnotation {
+    pub fn new(
+        data: &[StructureData],
+        structure_seed: i64,
+        pillar_seed: i64,
+        pillar_match: PillarMatchResult,
+    ) -> Self {
+        Self {
+            structure_seed,
+            pillar_seed,
+            pillar_match,
+            satisfied_evidence: data.iter().filter(|d| d.check_seed(structure_seed)).count(),
+            treasure_chunk: data
+                .iter()
+                .filter(|d| matches!(d, StructureData::BuriedTreasureContentsInArea { .. }))
+                .find_map(|d| d.matching_chunk(structure_seed)),
+        }
+    }
+
+    /// Best supported first: exact pillar matches before partial ones (impossible ones last), then more satisfied
+    /// evidence, then more likely pillar matches. Ties are broken by seed so the order is stable.
+    pub fn rank(&self, other: &Self) -> std::cmp::Ordering {
+        let tier = |a: &Self| match a.pillar_match {
+            PillarMatchResult::ExactMatch => 0,
+            PillarMatchResult::PossibleMatch(_) => 1,
+            PillarMatchResult::ImpossibleMatch | PillarMatchResult::InvalidObservation(_) => 2,
+        };
+        tier(self)
+            .cmp(&tier(other))
+            .then(other.satisfied_evidence.cmp(&self.satisfied_evidence))
+            .then(other.pillar_match.compare(&self.pillar_match))
+            .then(self.structure_seed.cmp(&other.structure_seed))
+            .then(self.pillar_seed.cmp(&other.pillar_seed))
+    }
+}
+
+/// Sorts the seeds with the best supported ones first, see [`StructureSeedAnnotation::rank`]
+#[inline]
+pub fn rank_structure_seeds(seeds: &mut [StructureSeedAnnotation]) {
+    seeds.sort_by(StructureSeedAnnotation::rank);
+}
+
+/// Bumped whenever the fields of [`SearchCheckpoint`] change
+pub const CHECKPOINT_FORMAT_VERSION: u64 = 1;
+/// 256 checkpoints over the whole search
+pub const DEFAULT_CHECKPOINT_INTERVAL: u32 = 256;
+
+/// How far a structure seed search got, to continue it later with [`StructureSeedSearch::resume`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCheckpoint {
+    pub pillar_seed: i64,
+    /// The [`StructureSeedSearchData::fingerprint`] of the search
+    pub data_hash: u64,
+    /// The whole range of the search
+    pub state_hi: Range<i64>,
+    /// Every value of `state_hi` before this one was searched
+    pub completed: i64,
+    /// The seeds found until then
+    pub seeds: Vec<i64>,
+}
+
+impl SearchCheckpoint {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "format_version": CHECKPOINT_FORMAT_VERSION,
+            "pillar_seed": self.pillar_seed,
+            "data_hash": self.data_hash,
+            "state_hi": [self.state_hi.start, self.state_hi.end],
+            "completed": self.completed,
+            "seeds": self.seeds,
+        })
+    }
+
+    /// `None` if a field is missing, has the wrong type or the format version is another one
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        if value.get("format_version")?.as_u64()? != CHECKPOINT_FORMAT_VERSION {
+            return None;
+        }
+        let state_hi = match value.get("state_hi")?.as_array()?.as_slice() {
+            [start, end] => start.as_i64()?..end.as_i64()?,
+            _ => return None,
+        };
+        Some(Self {
+            pillar_seed: value.get("pillar_seed")?.as_i64()?,
+            data_hash: value.get("data_hash")?.as_u64()?,
+            state_hi,
+            completed: value.get("completed")?.as_i64()?,
+            seeds: value
+                .get("seeds")?
+                .as_array()?
+                .iter()
+                .map(|seed| seed.as_i64())
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    pub fn save(&self, writer: impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, &self.to_json())
+    }
+
+    pub fn load(reader: impl Read) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_reader(reader).ok()?;
+        Self::from_json(&value)
+    }
+
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.completed >= self.state_hi.end
+    }
+}
+
+/// Why a [`SearchCheckpoint`] can't continue a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointError {
+    /// The checkpoint is of another pillar seed
+    OtherPillarSeed { expected: i64, found: i64 },
+    /// The evidence changed since the checkpoint, or the checkpoint was written by another build, see
+    /// [`StructureSeedSearchData::fingerprint`]
+    OtherEvidence,
+    /// The checkpoint doesn't lie in its own range
+    OutOfRange,
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::OtherPillarSeed { expected, found } => write!(
+                f,
+                "the checkpoint is of pillar seed {}, not {}",
+                found, expected
+            ),
+            CheckpointError::OtherEvidence => {
+                write!(
+                    f,
+                    "the evidence changed since the checkpoint, or it was written by another build"
+                )
+            }
+            CheckpointError::OutOfRange => write!(f, "the checkpoint is corrupted"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+struct StructureSeedSearcher {
+    pillar_seed: i64,
+    pillar_chance: f64,
+    data: Vec<StructureData>,
+    max_results: usize,
+    cooperation: Option<Cooperation>,
+    search_options: SearchOptions,
+    nextlong_only: bool,
+    condition: Option<Arc<EvidenceExpr>>,
+    /// What is left to search, the end of the range of `resumed`
+    state_hi: Range<i64>,
+    checkpoint_interval: i64,
+    /// The checkpoint the search starts from
+    resumed: SearchCheckpoint,
+
+    /// The number of candidates that have been searched
+    progress: AtomicU64,
+    heartbeat: Heartbeat,
+    stopsig: AtomicBool,
+    isdone: AtomicBool,
+    status: Mutex<Status>,
+    rejections: RejectionCounters,
+    /// Every seed as soon as it is found, see [`SearchHandle::found_seeds`]
+    found: Sender<i64>,
+    checkpoints: Sender<SearchCheckpoint>,
+}
+
+impl StructureSeedSearcher {
+    #[inline]
+    fn compute(&self) -> Vec<i64> {
+        match &self.cooperation {
+            Some(cooperation) => self.compute_cooperative(cooperation),
+            None => self.search_options.install(|| self.compute_parallel()),
+        }
+    }
+
+    #[inline(always)]
+    fn report(&self, seed: i64) {
+        // nobody listens once the handle is dropped
+        let _ = self.found.send(seed);
+    }
+
+    /// Counts why the candidate was rejected, for one candidate out of [`STRUCTURE_SAMPLE_RATE`]
+    #[inline(always)]
+    fn sample(&self, state_hi: i64, state_lo: i64, found: Option<i64>) {
+        if likely(state_lo % STRUCTURE_SAMPLE_RATE != 0) {
+            return;
+        }
+        self.rejections.record(found.map_or_else(
+            || {
+                structure_seed_rejection(
+                    &self.data,
+                    self.condition.as_deref(),
+                    self.nextlong_only,
+                    structure_seed_of(self.pillar_seed, state_hi, state_lo),
+                )
+            },
+            |_| None,
+        ));
+    }
+
+    /// Every value of `state_hi` before `completed` was searched
+    fn checkpoint(&self, completed: i64, seeds: &[i64]) {
+        let _ = self.checkpoints.send(SearchCheckpoint {
+            pillar_seed: self.pillar_seed,
+            data_hash: self.resumed.data_hash,
+            state_hi: self.resumed.state_hi.clone(),
+            completed,
+            seeds: seeds.to_vec(),
+        });
+    }
+
+    fn compute_parallel(&self) -> Vec<i64> {
+        let mut results = self.resumed.seeds.clone();
+        let mut cancelled = false;
+        let mut start = self.state_hi.start;
+        // each interval is searched in parallel, the checkpoints need all of it done
+        while start < self.state_hi.end && results.len() <= self.max_results {
+            let end = start
+                .saturating_add(self.checkpoint_interval)
+                .min(self.state_hi.end);
+            let ack = AtomicBool::new(false);
+            let found = (start..end)
+                .into_par_iter()
+                .filter_map(|state_hi| {
+                    if self.stopsig.load(Ordering::Relaxed) {
+                        ack.store(true, Ordering::Relaxed);
+                        return None;
+                    }
+
+                    let data_clone = self.data.clone();
+                    let condition = self.condition.clone();
+                    let pillar_seed = self.pillar_seed;
+                    let nextlong_only = self.nextlong_only;
+
+                    Some(
+                        (0i64..65536i64)
+                            .into_par_iter()
+                            .filter_map(move |state_lo| {
+                                let searched = self.search_options.batch_progress(state_lo, 65536);
+                                if unlikely(searched > 0) {
+                                    self.progress.fetch_add(searched, Ordering::Relaxed);
+                                    self.heartbeat.beat(searched);
+                                }
+
+                                let found = check_structure_seed_candidate(
+                                    &data_clone,
+                                    condition.as_deref(),
+                                    pillar_seed,
+                                    nextlong_only,
+                                    state_hi,
+                                    state_lo,
+                                );
+                                self.sample(state_hi, state_lo, found);
+                                found.inspect(|seed| self.report(*seed))
+                            }),
+                    )
+                })
+                .flatten()
+                .take_any(self.max_results + 1 - results.len())
+                .collect::<Vec<i64>>();
+            results.extend(found);
+
+            if ack.load(Ordering::Relaxed) {
+                cancelled = true;
+                break;
+            }
+            // with too many seeds, the interval may not be searched to the end
+            if results.len() <= self.max_results {
+                self.checkpoint(end, &results);
+            }
+            start = end;
+        }
+
+        self.done(results, cancelled)
+    }
+
+    fn compute_cooperative(&self, cooperation: &Cooperation) -> Vec<i64> {
+        let mut results = self.resumed.seeds.clone();
+        let mut cancelled = false;
+        let mut checkpointed = self.state_hi.start;
+        run_chunked(
+            self.state_hi.clone(),
+            cooperation.structure_shards_per_chunk,
+            cooperation,
+            |chunk| {
+                if self.stopsig.load(Ordering::Relaxed) {
+                    cancelled = true;
+                    return false;
+                }
+                let (searched, end) = ((chunk.end - chunk.start) as u64 * 65536, chunk.end);
+                for state_hi in chunk {
+                    results.extend((0i64..65536i64).filter_map(|state_lo| {
+                        let found = check_structure_seed_candidate(
+                            &self.data,
+                            self.condition.as_deref(),
+                            self.pillar_seed,
+                            self.nextlong_only,
+                            state_hi,
+                            state_lo,
+                        );
+                        self.sample(state_hi, state_lo, found);
+                        found.inspect(|seed| self.report(*seed))
+                    }));
+                }
+                self.progress.fetch_add(searched, Ordering::Relaxed);
+                self.heartbeat.beat(searched);
+                if results.len() > self.max_results {
+                    return false;
+                }
+                if end - checkpointed >= self.checkpoint_interval || end == self.state_hi.end {
+                    self.checkpoint(end, &results);
+                    checkpointed = end;
+                }
+                true
+            },
+        );
+        results.truncate(self.max_results + 1);
+
+        self.done(results, cancelled)
+    }
+
+    fn done(&self, results: Vec<i64>, cancelled: bool) -> Vec<i64> {
+        let res = match self.status.lock() {
+            Ok(mut status) => {
+                if cancelled {
+                    *status = Status::Cancelled {
+                        seeds_incomplete: results.clone(),
+                    };
+                } else if results.len() > self.max_results {
+                    *status = Status::TooManySeeds {
+                        seeds_incomplete: results.clone(),
+                    };
+                } else {
+                    *status = Status::Complete {
+                        seeds: results.clone(),
+                    };
+                }
+                results
+            }
+            Err(_) => results,
+        };
+
+        self.isdone.store(true, Ordering::Relaxed);
+
+        res
+    }
+}
+
+/// How a search started with [`StructureSeedSearch::start`] ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// Every seed of the searched range
+    Complete(Vec<i64>),
+    /// More seeds than the maximum number of results, some of them
+    TooManySeeds(Vec<i64>),
+    /// Cancelled with [`SearchHandle::cancel`], some of the seeds found until then
+    Cancelled(Vec<i64>),
+    /// The search thread panicked
+    Failed,
+}
+
+impl SearchOutcome {
+    /// The seeds found, empty if the search failed
+    pub fn seeds(&self) -> &[i64] {
+        match self {
+            SearchOutcome::Complete(seeds)
+            | SearchOutcome::TooManySeeds(seeds)
+            | SearchOutcome::Cancelled(seeds) => seeds,
+            SearchOutcome::Failed => &[],
+        }
+    }
+
+    /// None while searching
+    fn of(status: Status) -> Option<Self> {
+        match status {
+            Status::Searching => None,
+            Status::Complete { seeds } => Some(SearchOutcome::Complete(seeds)),
+            Status::TooManySeeds { seeds_incomplete } => {
+                Some(SearchOutcome::TooManySeeds(seeds_incomplete))
+            }
+            Status::Cancelled { seeds_incomplete } => {
+                Some(SearchOutcome::Cancelled(seeds_incomplete))
+            }
+        }
+    }
+}
+
+/// Structure seed searches running on their own thread, without the TUI
+///
+/// ```
+/// use mcseedcracker::{
+///     features::buried_treasure::{
+///         build_fast_inventory_compare_context, get_buried_treasure, get_loot_table,
+///     },
+///     search::{SearchOutcome, StructureData, StructureSeedSearch, StructureSeedSearchData},
+/// };
+///
+/// let structure_seed = 180066252004364;
+/// let contents = get_buried_treasure(structure_seed, (25, 50), 0.0);
+/// let data = StructureSeedSearchData::new(
+///     13847,
+///     vec![StructureData::BuriedTreasureContents {
+///         chunk_x: 25,
+///         chunk_z: 50,
+///         luck: 0.0,
+///         contents: build_fast_inventory_compare_context(contents, &get_loot_table()).unwrap(),
+///         loot_table: None,
+///     }],
+///     10,
+/// )
+/// .with_state_hi(47456..47584);
+///
+/// let handle = StructureSeedSearch::start(data);
+/// assert_eq!(handle.progress().1, 128 * 65536);
+/// assert_eq!(handle.join(), SearchOutcome::Complete(vec![structure_seed]));
+/// ```
+pub struct StructureSeedSearch;
+
+impl StructureSeedSearch {
+    /// Searches the range of `data` on a new thread, see [`SearchHandle`]
+    pub fn start(data: StructureSeedSearchData) -> SearchHandle {
+        let checkpoint = SearchCheckpoint {
+            pillar_seed: data.pillar_seed,
+            data_hash: data.fingerprint().0,
+            state_hi: data.state_hi.clone(),
+            completed: data.state_hi.start,
+            seeds: Vec::new(),
+        };
+        Self::spawn(data, checkpoint)
+    }
+
+    /// Continues the search of `data` from the checkpoint, over the range of the checkpoint. The found seeds of the
+    /// checkpoint are reported again by [`SearchHandle::found_seeds`].
+    pub fn resume(
+        data: StructureSeedSearchData,
+        checkpoint: SearchCheckpoint,
+    ) -> Result<SearchHandle, CheckpointError> {
+        if checkpoint.pillar_seed != data.pillar_seed {
+            return Err(CheckpointError::OtherPillarSeed {
+                expected: data.pillar_seed,
+                found: checkpoint.pillar_seed,
+            });
+        }
+        if checkpoint.data_hash != data.fingerprint().0 {
+            return Err(CheckpointError::OtherEvidence);
+        }
+        let range = &checkpoint.state_hi;
+        if range.start < 0
+            || range.end > 65536
+            || !(range.start..=range.end).contains(&checkpoint.completed)
+        {
+            return Err(CheckpointError::OutOfRange);
+        }
+        Ok(Self::spawn(data, checkpoint))
+    }
+
+    fn spawn(data: StructureSeedSearchData, resumed: SearchCheckpoint) -> SearchHandle {
+        let (found, found_seeds) = mpsc::channel();
+        let (checkpoints, checkpoints_received) = mpsc::channel();
+        for seed in resumed.seeds.iter() {
+            let _ = found.send(*seed);
+        }
+        let searched = (resumed.completed - resumed.state_hi.start) as u64 * 65536;
+        let searcher = Arc::new(StructureSeedSearcher {
+            pillar_seed: data.pillar_seed,
+            pillar_chance: data.pillar_chance,
+            data: data.data,
+            max_results: data.max_results as usize,
+            cooperation: data.cooperation,
+            search_options: data.search_options,
+            nextlong_only: data.nextlong_only,
+            condition: data.condition,
+            state_hi: resumed.completed..resumed.state_hi.end,
+            checkpoint_interval: data.checkpoint_interval.max(1) as i64,
+            resumed,
+            progress: AtomicU64::new(searched),
+            heartbeat: Heartbeat::new(),
+            status: Mutex::new(Status::Searching),
+            stopsig: AtomicBool::new(false),
+            isdone: AtomicBool::new(false),
+            rejections: RejectionCounters::new(),
+            found,
+            checkpoints,
+        });
+        let job = Arc::clone(&searcher);
+        let join_handle = std::thread::spawn(move || job.compute());
+
+        SearchHandle {
+            join_handle,
+            searcher,
+            found_seeds,
+            checkpoints: checkpoints_received,
+        }
+    }
+}
+
+/// A running structure seed search
+pub struct SearchHandle {
+    join_handle: JoinHandle<Vec<i64>>,
+    searcher: Arc<StructureSeedSearcher>,
+    found_seeds: Receiver<i64>,
+    checkpoints: Receiver<SearchCheckpoint>,
+}
+
+impl SearchHandle {
+    /// Candidates searched, out of the candidates of the range
+    #[inline]
+    pub fn progress(&self) -> (u64, u64) {
+        let range = &self.searcher.resumed.state_hi;
+        (
+            self.searcher.progress.load(Ordering::Relaxed),
+            (range.end - range.start).max(0) as u64 * 65536,
+        )
+    }
+
+    /// The search stops at the next value of the upper 16 bits, [`Self::join`] then gives
+    /// [`SearchOutcome::Cancelled`]
+    #[inline]
+    pub fn cancel(&self) {
+        self.searcher.stopsig.store(true, Ordering::Relaxed);
+    }
+
+    /// Waits for the end of the search
+    pub fn join(self) -> SearchOutcome {
+        match self.join_handle.join() {
+            Ok(_) => self.outcome().unwrap_or(SearchOutcome::Failed),
+            Err(_) => SearchOutcome::Failed,
+        }
+    }
+
+    #[inline]
+    pub fn cancel_join(self) -> SearchOutcome {
+        self.cancel();
+        self.join()
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.searcher.isdone.load(Ordering::Relaxed)
+    }
+
+    /// None while searching
+    pub fn outcome(&self) -> Option<SearchOutcome> {
+        if !self.is_done() {
+            return self
+                .join_handle
+                .is_finished()
+                .then_some(SearchOutcome::Failed);
+        }
+        self.searcher
+            .status
+            .lock()
+            .map_or(Some(SearchOutcome::Failed), |status| {
+                SearchOutcome::of(status.clone())
+            })
+    }
+
+    /// The seeds found since the last call, in the order they were found. A search with too many seeds may report a
+    /// few more than its outcome holds.
+    #[inline]
+    pub fn found_seeds(&self) -> Vec<i64> {
+        self.found_seeds.try_iter().collect()
+    }
+
+    /// The checkpoints reached since the last call, every [`StructureSeedSearchData::checkpoint_interval`] values of
+    /// the upper 16 bits. The last one is the one to save.
+    #[inline]
+    pub fn checkpoints(&self) -> Vec<SearchCheckpoint> {
+        self.checkpoints.try_iter().collect()
+    }
+
+    #[inline]
+    pub fn pillar_seed(&self) -> i64 {
+        self.searcher.pillar_seed
+    }
+
+    /// Why the sampled candidates were rejected so far, see [`crate::rejections`]
+    #[inline]
+    pub fn rejections(&self) -> RejectionStats {
+        self.searcher.rejections.stats()
+    }
+
+    /// The found seeds annotated with the evidence of the search, see [`StructureSeedCandidate::new`]
+    pub fn candidates(&self, seeds: &[i64]) -> Vec<StructureSeedCandidate> {
+        seeds
+            .iter()
+            .map(|&seed| {
+                StructureSeedCandidate::new(
+                    &self.searcher.data,
+                    seed,
+                    self.searcher.pillar_seed,
+                    self.searcher.pillar_chance,
+                )
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub fn heartbeat(&self) -> &Heartbeat {
+        &self.searcher.heartbeat
+    }
+
+    /// No progress for `threshold` while still searching, see [`Heartbeat`]
+    #[inline]
+    pub fn is_stalled(&self, threshold: Duration) -> bool {
+        !self.is_done() && self.searcher.heartbeat.is_stalled(threshold)
+    }
 }
 
-#[derive(Debug, Clone)]
-pub enum Status {
-    Searching,
-    /// It found some seeds (maybe none)
-    Complete {
-        /// The seeds that were found
-        seeds: Vec<i64>,
-    },
-    /// It found more seeds than the maximum number of seeds allowed
-    TooManySeeds {
-        /// Some of seeds that were found
-        seeds_incomplete: Vec<i64>,
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldExtraData {
+    OverworldBiomeData(Vec<(i32, i32, i32, BiomeID)>),
+    NetherBiomeData(Vec<(i32, i32, i32, BiomeID)>),
+    /// Weak evidence, only used to rank the surviving world seeds, see [`crate::features::decoration`]
+    DecorationCount {
+        chunk: (i32, i32),
+        decoration: DecorationKind,
+        feature_index: i32,
+        surface_y: i32,
+        observed_count: u32,
+        tolerance: u32,
     },
-    /// The search was cancelled
-    Cancelled {
-        /// Some of seeds that were found
-        seeds_incomplete: Vec<i64>,
+    /// A structure seen at the block, see [`WorldExtraData::structure_presence`]. cubiomes checks that the biomes let
+    /// it generate there, monuments also check that their region tries to place them in that chunk.
+    StructurePresence {
+        structure_type: StructureType,
+        block_x: i32,
+        block_z: i32,
+        dimension: Dimension,
     },
 }
 
-pub struct StructureSeedSearchData {
-    pub pillar_seed: i64,
-    pub data: Vec<StructureData>,
-    pub max_results: u16,
+/// The structures of [`WorldExtraData::StructurePresence`] that can be entered as evidence
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum KnownStructure {
+    Monument,
+    Village,
+    PillagerOutpost,
+    EndCity,
+    Fortress,
+    Bastion,
 }
 
-impl StructureSeedSearchData {
-    #[inline]
-    pub const fn new(pillar_seed: i64, data: Vec<StructureData>, max_results: u16) -> Self {
-        Self {
-            pillar_seed,
-            data,
-            max_results,
+impl KnownStructure {
+    pub const ALL: [KnownStructure; 6] = [
+        KnownStructure::Monument,
+        KnownStructure::Village,
+        KnownStructure::PillagerOutpost,
+        KnownStructure::EndCity,
+        KnownStructure::Fortress,
+        KnownStructure::Bastion,
+    ];
+
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            KnownStructure::Monument => "monument",
+            KnownStructure::Village => "village",
+            KnownStructure::PillagerOutpost => "pillager_outpost",
+            KnownStructure::EndCity => "end_city",
+            KnownStructure::Fortress => "fortress",
+            KnownStructure::Bastion => "bastion",
         }
     }
 
     #[inline]
-    pub fn spawn_multithreaded(self) -> StructureSeedSearcherHandle {
-        StructureSeedSearcher::spawn_multithreaded(self.pillar_seed, self.data, self.max_results)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    #[inline]
+    pub fn of(structure_type: StructureType) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.structure_type() == structure_type)
+    }
+
+    #[inline(always)]
+    pub const fn structure_type(&self) -> StructureType {
+        match self {
+            KnownStructure::Monument => StructureType::Monument,
+            KnownStructure::Village => StructureType::Village,
+            KnownStructure::PillagerOutpost => StructureType::Outpost,
+            KnownStructure::EndCity => StructureType::End_City,
+            KnownStructure::Fortress => StructureType::Fortress,
+            KnownStructure::Bastion => StructureType::Bastion,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn dimension(&self) -> Dimension {
+        match self {
+            KnownStructure::Monument
+            | KnownStructure::Village
+            | KnownStructure::PillagerOutpost => Dimension::DIM_OVERWORLD,
+            KnownStructure::EndCity => Dimension::DIM_END,
+            KnownStructure::Fortress | KnownStructure::Bastion => Dimension::DIM_NETHER,
+        }
     }
 }
 
-pub struct StructureSeedSearcher {
-    pillar_seed: i64,
-    data: Vec<StructureData>,
-    max_results: usize,
+impl Hash for WorldExtraData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let hash_biomes = |data: &Vec<(i32, i32, i32, BiomeID)>, state: &mut H| {
+            data.len().hash(state);
+            for (x, y, z, biome) in data.iter() {
+                (*x, *y, *z, *biome as i32).hash(state);
+            }
+        };
 
-    /// The number of seeds out of 2^32 that have been searched
-    progress: AtomicU64,
-    stopsig: AtomicBool,
-    isdone: AtomicBool,
-    status: Mutex<Status>,
+        match self {
+            WorldExtraData::OverworldBiomeData(data) => {
+                0u8.hash(state);
+                hash_biomes(data, state);
+            }
+            WorldExtraData::NetherBiomeData(data) => {
+                1u8.hash(state);
+                hash_biomes(data, state);
+            }
+            WorldExtraData::DecorationCount {
+                chunk,
+                decoration,
+                feature_index,
+                surface_y,
+                observed_count,
+                tolerance,
+            } => {
+                2u8.hash(state);
+                (
+                    chunk,
+                    decoration,
+                    feature_index,
+                    surface_y,
+                    observed_count,
+                    tolerance,
+                )
+                    .hash(state);
+            }
+            WorldExtraData::StructurePresence {
+                structure_type,
+                block_x,
+                block_z,
+                dimension,
+            } => {
+                3u8.hash(state);
+                (*structure_type as i32, block_x, block_z, *dimension as i32).hash(state);
+            }
+        }
+    }
 }
 
-impl StructureSeedSearcher {
-    #[inline]
-    fn compute(&self) -> Vec<i64> {
-        let ack = AtomicBool::new(false);
-        let results = (0i64..65536i64)
-            .into_par_iter()
-            .filter_map(|state_hi| {
-                if self.stopsig.load(Ordering::Relaxed) {
-                    ack.store(true, Ordering::Relaxed);
-                    return None;
-                }
+/// See [`Math::WORLD_BORDER`]
+pub const WORLD_BORDER: i32 = Math::WORLD_BORDER;
+/// Build height limits of 1.16 worlds
+pub const MIN_SAMPLE_Y: i32 = 0;
+pub const MAX_SAMPLE_Y: i32 = 255;
 
-                let data_clone = self.data.clone();
-                let pillar_seed_shl_16 = self.pillar_seed << 16;
-
-                let orig = state_hi;
-                let state_hi = state_hi << 32;
-                Some(
-                    (0i64..65536i64)
-                        .into_par_iter()
-                        .filter_map(move |state_lo| {
-                            if unlikely(state_lo == 65535 && (orig % 32) == 0) {
-                                self.progress.fetch_add(65536 * 32, Ordering::Relaxed);
-                            }
-
-                            let state = state_hi | pillar_seed_shl_16 | state_lo;
-                            let reversed_state = lcg::JAVA_RANDOM_REV2.next_seed(state);
-                            let seed = reversed_state ^ lcg::JAVA_RANDOM.get_multiplier();
-
-                            for d in data_clone.iter() {
-                                if likely(!d.check_seed(seed)) {
-                                    return None;
-                                }
-                            }
+/// Versions whose biomes the world seed search checks. 1.20.1 generates the biomes of 1.20.6.
+pub const SUPPORTED_VERSIONS: [MCVersion; 5] = [
+    MCVersion::MC_1_16_5,
+    MCVersion::MC_1_17_1,
+    MCVersion::MC_1_18_2,
+    MCVersion::MC_1_19_2,
+    MCVersion::MC_1_20_6,
+];
+/// Version of the searches that don't pick one
+pub const DEFAULT_VERSION: MCVersion = MCVersion::MC_1_16_5;
 
-                            Some(seed)
-                        }),
-                )
-            })
-            .flatten()
-            .take_any(self.max_results + 1)
-            .collect::<Vec<i64>>();
+/// Build height limits of the dimension, the overworld goes from -64 to 319 since 1.18
+#[inline(always)]
+pub const fn sample_y_range(version: MCVersion, dimension: Dimension) -> (i32, i32) {
+    if matches!(dimension, Dimension::DIM_OVERWORLD)
+        && version as i32 >= MCVersion::MC_1_18_2 as i32
+    {
+        (-64, 319)
+    } else {
+        (MIN_SAMPLE_Y, MAX_SAMPLE_Y)
+    }
+}
 
-        let res = match self.status.lock() {
-            Ok(mut status) => {
-                if ack.load(Ordering::Relaxed) {
-                    *status = Status::Cancelled {
-                        seeds_incomplete: results.clone(),
-                    };
-                } else if results.len() > self.max_results {
-                    *status = Status::TooManySeeds {
-                        seeds_incomplete: results.clone(),
-                    };
-                } else {
-                    *status = Status::Complete {
-                        seeds: results.clone(),
-                    };
-                }
-                results
+#[inline(always)]
+pub const fn is_valid_sample_pos(
+    version: MCVersion,
+    dimension: Dimension,
+    x: i32,
+    y: i32,
+    z: i32,
+) -> bool {
+    let (min_y, max_y) = sample_y_range(version, dimension);
+    Math::is_inside_world_border((x, z)) && y >= min_y && y <= max_y
+}
+
+/// A biome sample that couldn't be checked. The seed it was checked against is treated as not matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// See [`is_valid_sample_pos`]
+    OutOfWorld {
+        dimension: &'static str,
+        pos: (i32, i32, i32),
+    },
+    /// cubiomes returned an error
+    LookupFailed {
+        dimension: &'static str,
+        pos: (i32, i32, i32),
+    },
+    /// A [`WorldExtraData::StructurePresence`] outside of the world border
+    StructureOutOfWorld { pos: (i32, i32) },
+    /// cubiomes couldn't check the structure, e.g. it doesn't generate in the version
+    StructureCheckFailed { pos: (i32, i32) },
+}
+
+impl Display for ConstraintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstraintError::OutOfWorld {
+                dimension,
+                pos: (x, y, z),
+            } => write!(
+                f,
+                "{} biome sample at ({}, {}, {}) is outside of the world",
+                dimension, x, y, z
+            ),
+            ConstraintError::LookupFailed {
+                dimension,
+                pos: (x, y, z),
+            } => write!(
+                f,
+                "cubiomes failed to get the {} biome at ({}, {}, {})",
+                dimension, x, y, z
+            ),
+            ConstraintError::StructureOutOfWorld { pos: (x, z) } => {
+                write!(f, "structure at ({}, {}) is outside of the world", x, z)
             }
-            Err(_) => results,
-        };
+            ConstraintError::StructureCheckFailed { pos: (x, z) } => {
+                write!(
+                    f,
+                    "cubiomes failed to check the structure at ({}, {})",
+                    x, z
+                )
+            }
+        }
+    }
+}
 
-        self.isdone.store(true, Ordering::Relaxed);
+impl std::error::Error for ConstraintError {}
 
-        res
+#[inline(always)]
+fn check_biome_samples(
+    seed: i64,
+    version: MCVersion,
+    dimension: Dimension,
+    dimension_name: &'static str,
+    data: &[(i32, i32, i32, BiomeID)],
+    cache: Option<&BiomeCache>,
+) -> Result<bool, ConstraintError> {
+    // test the positions first, so invalid samples are reported without asking cubiomes
+    for (x, y, z, _) in data.iter() {
+        if unlikely(!is_valid_sample_pos(version, dimension, *x, *y, *z)) {
+            return Err(ConstraintError::OutOfWorld {
+                dimension: dimension_name,
+                pos: (*x, *y, *z),
+            });
+        }
     }
 
-    pub fn spawn_multithreaded(
-        pillar_seed: i64,
-        data: Vec<StructureData>,
-        max_results: u16,
-    ) -> StructureSeedSearcherHandle {
-        let job = Arc::new(StructureSeedSearcher {
-            pillar_seed,
+    match cache {
+        Some(cache) => {
+            match_biome_samples(&cache.query(seed, version, dimension), dimension_name, data)
+        }
+        None => match_biome_samples(
+            &Generator::new(version, seed, dimension, GeneratorFlags::empty()),
+            dimension_name,
             data,
-            max_results: max_results as usize,
-            progress: AtomicU64::new(0),
-            status: Mutex::new(Status::Searching),
-            stopsig: AtomicBool::new(false),
-            isdone: AtomicBool::new(false),
-        });
-        let job2 = Arc::clone(&job);
-
-        let join_handle = std::thread::spawn(move || job.compute());
+        ),
+    }
+}
 
-        StructureSeedSearcherHandle {
-            join_handle,
-            searcher: job2,
+#[inline(always)]
+fn match_biome_samples(
+    query: &impl BiomeQuery,
+    dimension_name: &'static str,
+    data: &[(i32, i32, i32, BiomeID)],
+) -> Result<bool, ConstraintError> {
+    for (x, y, z, biome) in data.iter() {
+        match query.biome_at(*x, *y, *z) {
+            Some(b) if b == *biome => {}
+            Some(_) => return Ok(false),
+            None => {
+                return Err(ConstraintError::LookupFailed {
+                    dimension: dimension_name,
+                    pos: (*x, *y, *z),
+                });
+            }
         }
     }
+    Ok(true)
 }
 
-pub struct StructureSeedSearcherHandle {
-    pub join_handle: JoinHandle<Vec<i64>>,
-    pub searcher: Arc<StructureSeedSearcher>,
+/// Rough share of the world seeds whose biomes let a structure generate at a given position
+const STRUCTURE_PASS_PROBABILITY: f64 = 0.1;
+/// Biome lookups of a structure viability check, monuments look at an area around their center
+const STRUCTURE_CHECK_LOOKUPS: f64 = 8.0;
+
+/// The region attempt of monuments only depends on the structure seed, it is checked before creating a generator
+#[inline(always)]
+fn check_structure_presence(
+    seed: i64,
+    version: MCVersion,
+    structure_type: StructureType,
+    pos: (i32, i32),
+    dimension: Dimension,
+) -> Result<bool, ConstraintError> {
+    if unlikely(!Math::is_inside_world_border(pos)) {
+        return Err(ConstraintError::StructureOutOfWorld { pos });
+    }
+    if matches!(structure_type, StructureType::Monument)
+        && !monument::is_attempt_chunk(seed, Math::block_coords_to_chunk_coords(pos))
+    {
+        return Ok(false);
+    }
+
+    let mut generator = Generator::new(version, seed, dimension, GeneratorFlags::empty());
+    generator
+        .verify_structure_generation_attempt(BlockPosition::new(pos.0, pos.1), structure_type)
+        .map_err(|_| ConstraintError::StructureCheckFailed { pos })
+}
+
+/// Biome lookups in the world of a single seed and dimension
+pub trait BiomeQuery {
+    /// `None` if the biome can't be looked up
+    fn biome_at(&self, x: i32, y: i32, z: i32) -> Option<BiomeID>;
 }
 
-impl StructureSeedSearcherHandle {
+impl BiomeQuery for Generator {
     #[inline]
-    pub fn join(self) -> thread::Result<Vec<i64>> {
-        self.join_handle.join()
+    fn biome_at(&self, x: i32, y: i32, z: i32) -> Option<BiomeID> {
+        self.get_biome_at(x, y, z).ok()
     }
+}
 
-    #[inline]
-    pub fn cancel_join(self) -> thread::Result<Vec<i64>> {
-        self.searcher.stopsig.store(true, Ordering::Relaxed);
-        self.join()
+/// Distance in blocks searched around each biome sample by [`sample_margin`]
+pub const FIT_RADIUS: i32 = 32;
+/// Biomes are generated per 4x4 cell, the neighbouring cells are this far apart
+const BIOME_CELL_SIZE: i32 = 4;
+
+/// Distance from the sample to the nearest cell of another biome at the same height, capped at `radius`, 0 if the
+/// sample itself doesn't match. Cells whose biome can't be looked up count as another biome.
+pub fn sample_margin(
+    query: &impl BiomeQuery,
+    (x, y, z, biome): (i32, i32, i32, BiomeID),
+    radius: i32,
+) -> u32 {
+    if query.biome_at(x, y, z) != Some(biome) {
+        return 0;
+    }
+    let mut margin = radius.max(0) as f64;
+    for ring in 1..=radius / BIOME_CELL_SIZE {
+        // every cell of this ring and the next ones is at least this far
+        if (ring * BIOME_CELL_SIZE) as f64 >= margin {
+            break;
+        }
+        for dx in -ring..=ring {
+            for dz in -ring..=ring {
+                if dx.abs() != ring && dz.abs() != ring {
+                    continue;
+                }
+                let (ox, oz) = (dx * BIOME_CELL_SIZE, dz * BIOME_CELL_SIZE);
+                let distance = ((ox * ox + oz * oz) as f64).sqrt();
+                if distance < margin && query.biome_at(x + ox, y, z + oz) != Some(biome) {
+                    margin = distance;
+                }
+            }
+        }
     }
+    margin as u32
+}
 
-    #[inline]
-    pub fn is_done(&self) -> bool {
-        self.searcher.isdone.load(Ordering::Relaxed)
+/// How far inside their biomes the samples of a world seed are. Every surviving seed matches all the samples, but a
+/// seed where they sit at the edge of their biomes matches them by a hair, one block off and the biome would differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldSeedFit {
+    pub seed: i64,
+    /// One per biome sample, in the order of the evidence, see [`sample_margin`]
+    pub margins: Vec<u32>,
+}
+
+impl WorldSeedFit {
+    /// Looks up the biome samples of the evidence in the world of the seed
+    pub fn measure(seed: i64, data: &[WorldExtraData], version: MCVersion) -> Self {
+        let mut fit = Self {
+            seed,
+            margins: Vec::new(),
+        };
+        for d in data.iter() {
+            let (samples, dimension) = match d {
+                WorldExtraData::OverworldBiomeData(samples) => (samples, Dimension::DIM_OVERWORLD),
+                WorldExtraData::NetherBiomeData(samples) => (samples, Dimension::DIM_NETHER),
+                WorldExtraData::DecorationCount { .. }
+                | WorldExtraData::StructurePresence { .. } => continue,
+            };
+            let generator = Generator::new(version, seed, dimension, GeneratorFlags::empty());
+            fit.add_samples(&generator, samples);
+        }
+        fit
     }
 
-    #[inline]
-    pub fn get_status(&self) -> Status {
-        let lock = self.searcher.status.lock().unwrap();
-        (*lock).clone()
+    /// Measures the samples of one dimension with the given lookups
+    pub fn add_samples(&mut self, query: &impl BiomeQuery, samples: &[(i32, i32, i32, BiomeID)]) {
+        self.margins.extend(
+            samples
+                .iter()
+                .map(|sample| sample_margin(query, *sample, FIT_RADIUS)),
+        );
     }
 
-    #[inline]
-    pub fn get_pillar_seed(&self) -> i64 {
-        self.searcher.pillar_seed
+    /// From 0 if every sample is at the edge of its biome to 1 if they are all at least [`FIT_RADIUS`] blocks inside,
+    /// 1 without biome samples
+    pub fn score(&self) -> f64 {
+        if self.margins.is_empty() {
+            return 1.0;
+        }
+        let total = self
+            .margins
+            .iter()
+            .map(|margin| (*margin).min(FIT_RADIUS as u32) as f64 / FIT_RADIUS as f64)
+            .sum::<f64>();
+        total / self.margins.len() as f64
     }
 
+    /// The sample closest to the edge of its biome
     #[inline]
-    pub fn get_progress(&self) -> u64 {
-        self.searcher.progress.load(Ordering::Relaxed)
+    pub fn min_margin(&self) -> Option<u32> {
+        self.margins.iter().min().copied()
     }
 }
 
-#[derive(Clone)]
-pub enum WorldExtraData {
-    OverworldBiomeData(Vec<(i32, i32, i32, BiomeID)>),
-    NetherBiomeData(Vec<(i32, i32, i32, BiomeID)>),
+/// Fewest inconsistent tie-breakers first, then best [`WorldSeedFit::score`], stable otherwise
+pub fn rank_world_seeds(seeds: &mut [(usize, WorldSeedFit)]) {
+    seeds.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.score().total_cmp(&a.1.score())));
 }
 
 impl WorldExtraData {
     #[inline(always)]
-    fn check_seed(&self, seed: i64) -> bool {
+    pub(crate) fn check_seed(
+        &self,
+        seed: i64,
+        version: MCVersion,
+    ) -> Result<bool, ConstraintError> {
+        self.check_seed_with(seed, version, None)
+    }
+
+    /// [`WorldExtraData::check_seed`] looking up the biomes in `cache` first, for the checks of single seeds
+    #[inline(always)]
+    pub(crate) fn check_seed_with(
+        &self,
+        seed: i64,
+        version: MCVersion,
+        cache: Option<&BiomeCache>,
+    ) -> Result<bool, ConstraintError> {
         match self {
+            WorldExtraData::OverworldBiomeData(data) => check_biome_samples(
+                seed,
+                version,
+                Dimension::DIM_OVERWORLD,
+                "overworld",
+                data,
+                cache,
+            ),
+            WorldExtraData::NetherBiomeData(data) => {
+                check_biome_samples(seed, version, Dimension::DIM_NETHER, "nether", data, cache)
+            }
+            // Not a hard filter
+            WorldExtraData::DecorationCount { .. } => Ok(true),
+            WorldExtraData::StructurePresence {
+                structure_type,
+                block_x,
+                block_z,
+                dimension,
+            } => check_structure_presence(
+                seed,
+                version,
+                *structure_type,
+                (*block_x, *block_z),
+                *dimension,
+            ),
+        }
+    }
+
+    /// The samples that can never be checked in worlds of the version, whatever the seed
+    pub fn invalid_samples(&self, version: MCVersion) -> Vec<ConstraintError> {
+        let (data, dimension, dimension_name) = match self {
             WorldExtraData::OverworldBiomeData(data) => {
-                let generator = Generator::new(
-                    MCVersion::MC_1_16_5,
-                    seed,
-                    Dimension::DIM_OVERWORLD,
-                    GeneratorFlags::empty(),
-                );
-                for (x, y, z, biome) in data.iter() {
-                    if generator.get_biome_at(*x, *y, *z) != Ok(*biome) {
-                        return false;
-                    }
-                }
-                true
+                (data, Dimension::DIM_OVERWORLD, "overworld")
             }
-            WorldExtraData::NetherBiomeData(data) => {
-                let generator = Generator::new(
-                    MCVersion::MC_1_16_5,
-                    seed,
-                    Dimension::DIM_NETHER,
-                    GeneratorFlags::empty(),
-                );
-                for (x, y, z, biome) in data.iter() {
-                    if generator.get_biome_at(*x, *y, *z) != Ok(*biome) {
-                        return false;
-                    }
-                }
-                true
+            WorldExtraData::NetherBiomeData(data) => (data, Dimension::DIM_NETHER, "nether"),
+            WorldExtraData::DecorationCount { .. } => return Vec::new(),
+            WorldExtraData::StructurePresence {
+                block_x, block_z, ..
+            } => {
+                let pos = (*block_x, *block_z);
+                return if Math::is_inside_world_border(pos) {
+                    Vec::new()
+                } else {
+                    vec![ConstraintError::StructureOutOfWorld { pos }]
+                };
+            }
+        };
+        data.iter()
+            .filter(|(x, y, z, _)| !is_valid_sample_pos(version, dimension, *x, *y, *z))
+            .map(|(x, y, z, _)| ConstraintError::OutOfWorld {
+                dimension: dimension_name,
+                pos: (*x, *y, *z),
+            })
+            .collect()
+    }
+
+    /// Estimated probability that a random world seed passes this check
+    pub fn pass_probability(&self, table: &BiomeRarityTable) -> f64 {
+        match self {
+            WorldExtraData::OverworldBiomeData(data) => data
+                .iter()
+                .map(|(_, _, _, biome)| table.frequency(Dimension::DIM_OVERWORLD, *biome))
+                .product(),
+            WorldExtraData::NetherBiomeData(data) => data
+                .iter()
+                .map(|(_, _, _, biome)| table.frequency(Dimension::DIM_NETHER, *biome))
+                .product(),
+            WorldExtraData::DecorationCount { .. } => 1.0,
+            WorldExtraData::StructurePresence { .. } => STRUCTURE_PASS_PROBABILITY,
+        }
+    }
+
+    /// Expected cost of `check_seed` on a random seed, in biome lookups, with the samples in their current order
+    pub(crate) fn check_cost(&self, table: &BiomeRarityTable) -> f64 {
+        // creating a generator costs about as much as a few lookups
+        const GENERATOR_COST: f64 = 4.0;
+        let (data, dimension) = match self {
+            WorldExtraData::OverworldBiomeData(data) => (data, Dimension::DIM_OVERWORLD),
+            WorldExtraData::NetherBiomeData(data) => (data, Dimension::DIM_NETHER),
+            WorldExtraData::DecorationCount { .. } => return 0.0,
+            WorldExtraData::StructurePresence { .. } => {
+                return GENERATOR_COST + STRUCTURE_CHECK_LOOKUPS;
+            }
+        };
+        // a sample is only looked up if all the previous ones passed
+        let mut reached = 1.0;
+        let mut lookups = 0.0;
+        for (_, _, _, biome) in data.iter() {
+            lookups += reached;
+            reached *= table.frequency(dimension, *biome);
+        }
+        GENERATOR_COST + lookups
+    }
+
+    /// Sorts the biome samples so the rarest ones, which reject the most seeds, are looked up first
+    pub fn sort_samples_by_rarity(&mut self, table: &BiomeRarityTable) {
+        let (data, dimension) = match self {
+            WorldExtraData::OverworldBiomeData(data) => (data, Dimension::DIM_OVERWORLD),
+            WorldExtraData::NetherBiomeData(data) => (data, Dimension::DIM_NETHER),
+            WorldExtraData::DecorationCount { .. } | WorldExtraData::StructurePresence { .. } => {
+                return;
             }
+        };
+        data.sort_by(|a, b| {
+            table
+                .frequency(dimension, a.3)
+                .total_cmp(&table.frequency(dimension, b.3))
+        });
+    }
+
+    #[inline(always)]
+    pub const fn is_tie_breaker(&self) -> bool {
+        matches!(self, WorldExtraData::DecorationCount { .. })
+    }
+
+    /// Returns true if the seed is consistent with this evidence when used as a tie-breaker
+    #[inline]
+    fn is_consistent(&self, seed: i64) -> bool {
+        match self {
+            WorldExtraData::DecorationCount {
+                chunk,
+                decoration,
+                feature_index,
+                surface_y,
+                observed_count,
+                tolerance,
+            } => decoration::is_decoration_count_consistent(
+                seed,
+                *chunk,
+                *decoration,
+                *feature_index,
+                *surface_y,
+                *observed_count,
+                *tolerance,
+            ),
+            _ => true,
+        }
+    }
+
+    /// A [`WorldExtraData::StructurePresence`] in the dimension of the structure
+    #[inline(always)]
+    pub const fn structure_presence(structure: KnownStructure, block_x: i32, block_z: i32) -> Self {
+        WorldExtraData::StructurePresence {
+            structure_type: structure.structure_type(),
+            block_x,
+            block_z,
+            dimension: structure.dimension(),
         }
     }
 
@@ -307,12 +2079,80 @@ impl WorldExtraData {
     }
 }
 
+/// Orders the checks so the ones that reject the most seeds per unit of work run first, tie-breakers last.
+///
+/// The result of a search doesn't depend on the order, only its speed does.
+pub fn order_by_selectivity(data: &mut [WorldExtraData], table: &BiomeRarityTable) {
+    for d in data.iter_mut() {
+        d.sort_samples_by_rarity(table);
+    }
+    data.sort_by_cached_key(|d| {
+        if d.is_tie_breaker() {
+            (true, 0u64)
+        } else {
+            let rejected_per_cost = (1.0 - d.pass_probability(table)) / d.check_cost(table);
+            // higher is better, reversed so it sorts first
+            (false, u64::MAX - rejected_per_cost.to_bits())
+        }
+    });
+}
+
+/// The [`typed_numeric_completions`] of `structure_seed` passing every biome check of `data`, a handful of biome
+/// lookups instead of a world seed search. Empty if `data` has no biome samples, there would be nothing to check.
+pub fn typed_completions_passing(
+    structure_seed: i64,
+    data: &[WorldExtraData],
+    version: MCVersion,
+) -> Vec<i64> {
+    let has_samples = data.iter().any(|d| match d {
+        WorldExtraData::OverworldBiomeData(samples) | WorldExtraData::NetherBiomeData(samples) => {
+            !samples.is_empty()
+        }
+        WorldExtraData::DecorationCount { .. } => false,
+        WorldExtraData::StructurePresence { .. } => true,
+    });
+    if !has_samples {
+        return Vec::new();
+    }
+    typed_numeric_completions(structure_seed)
+        .into_iter()
+        .filter(|seed| {
+            data.iter()
+                .all(|d| matches!(d.check_seed(*seed, version), Ok(true)))
+        })
+        .collect()
+}
+
 pub struct WorldSeedSearchData {
     pub structure_seed: i64,
-    /// If the world seed has been randomly generated by the game, we can reverse the nextLong call that generated it and greatly reduce the seed count
+    /// If the world seed has been randomly generated by the game, we can reverse the nextLong call that generated it and greatly reduce the seed count.
+    /// The seeds that could have been typed as a number are tried too, see [`typed_numeric_completions`]
     pub is_random_world_seed: bool,
+    /// Only the world seeds this origin can give are tried, instead of the ones of `is_random_world_seed`. A handful
+    /// at most instead of the 65536 completions, see [`SeedOrigin::completions`]
+    pub origin: SeedOrigin,
     pub data: Vec<WorldExtraData>,
     pub max_results: u16,
+    /// Runs the search in small chunks on its own thread instead of the rayon pool, see [`Cooperation`]
+    pub cooperation: Option<Cooperation>,
+    pub search_options: SearchOptions,
+    /// Checked on top of `data`, see [`crate::expr`]
+    pub condition: Option<Arc<EvidenceExpr>>,
+    /// Generates the biomes of the world seeds, one of [`SUPPORTED_VERSIONS`]
+    pub version: MCVersion,
+    /// The pillar seeds of the observed pillars, sorted. The world seeds with other pillars are rejected, see
+    /// [`pillar_seed_from_world_seed`]
+    pub pillar_seeds: Option<Arc<[i64]>>,
+}
+
+/// Whether the world seed generates one of the sorted `pillar_seeds`, true without them or when the version has no
+/// pillar seed
+#[inline(always)]
+fn pillars_allowed(pillar_seeds: Option<&[i64]>, seed: i64, version: MCVersion) -> bool {
+    pillar_seeds.is_none_or(|seeds| {
+        pillar_seed_from_world_seed(seed, version)
+            .is_none_or(|pillar_seed| seeds.binary_search(&pillar_seed).is_ok())
+    })
 }
 
 impl WorldSeedSearchData {
@@ -326,123 +2166,477 @@ impl WorldSeedSearchData {
         Self {
             structure_seed,
             is_random_world_seed,
+            origin: SeedOrigin::Any,
             data,
             max_results,
+            cooperation: None,
+            search_options: SearchOptions::DEFAULT,
+            condition: None,
+            version: DEFAULT_VERSION,
+            pillar_seeds: None,
+        }
+    }
+
+    #[inline]
+    pub const fn with_cooperation(mut self, cooperation: Option<Cooperation>) -> Self {
+        self.cooperation = cooperation;
+        self
+    }
+
+    #[inline]
+    pub const fn with_search_options(mut self, search_options: SearchOptions) -> Self {
+        self.search_options = search_options;
+        self
+    }
+
+    #[inline]
+    pub const fn with_origin(mut self, origin: SeedOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Set it before [`Self::with_condition`], which orders the condition for the version
+    #[inline]
+    pub const fn with_version(mut self, version: MCVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Cross-checks the world seeds against the cracked pillar seeds, which have to be sorted
+    #[inline]
+    pub fn with_pillar_seeds(mut self, pillar_seeds: Option<Arc<[i64]>>) -> Self {
+        self.pillar_seeds = pillar_seeds;
+        self
+    }
+
+    /// The condition is ordered by selectivity, see [`EvidenceExpr::order_by_selectivity`]
+    pub fn with_condition(mut self, condition: Option<EvidenceExpr>) -> Self {
+        self.condition = condition.map(|mut condition| {
+            condition.order_by_selectivity(BiomeRarityTable::for_version(self.version));
+            Arc::new(condition)
+        });
+        self
+    }
+
+    /// Reorders the checks by selectivity, see [`order_by_selectivity`]
+    #[inline]
+    pub fn order_constraints(&mut self) {
+        order_by_selectivity(&mut self.data, BiomeRarityTable::for_version(self.version));
+    }
+
+    /// The checks in the order they run, one line per check
+    pub fn verification_order(&self) -> Vec<String> {
+        let table = BiomeRarityTable::for_version(self.version);
+        self.data
+            .iter()
+            .map(|d| match d {
+                WorldExtraData::OverworldBiomeData(data)
+                | WorldExtraData::NetherBiomeData(data) => {
+                    let samples = data
+                        .iter()
+                        .map(|(x, y, z, biome)| format!("{:?} at ({}, {}, {})", biome, x, y, z))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "{} biomes, ~{:.4}% pass: {}",
+                        if matches!(d, WorldExtraData::NetherBiomeData(_)) {
+                            "Nether"
+                        } else {
+                            "Overworld"
+                        },
+                        d.pass_probability(table) * 100.0,
+                        samples
+                    )
+                }
+                WorldExtraData::DecorationCount {
+                    chunk, decoration, ..
+                } => format!(
+                    "{} count in chunk ({}, {}), ranking only",
+                    decoration.name(),
+                    chunk.0,
+                    chunk.1
+                ),
+                WorldExtraData::StructurePresence {
+                    structure_type,
+                    block_x,
+                    block_z,
+                    ..
+                } => format!(
+                    "{} at ({}, {}), ~{:.4}% pass",
+                    KnownStructure::of(*structure_type).map_or("structure", |s| s.name()),
+                    block_x,
+                    block_z,
+                    d.pass_probability(table) * 100.0
+                ),
+            })
+            .collect()
+    }
+
+    /// Samples that can't be checked for any seed, to report before starting a search that would skip every candidate
+    pub fn invalid_samples(&self) -> Vec<ConstraintError> {
+        let mut invalid = self
+            .data
+            .iter()
+            .flat_map(|d| d.invalid_samples(self.version))
+            .collect::<Vec<_>>();
+        if let Some(condition) = &self.condition {
+            invalid.extend(condition.invalid_samples(self.version));
         }
+        invalid
     }
 
+    /// Orders the checks by selectivity and starts the search
     #[inline]
-    pub fn spawn_multithreaded(self) -> WorldSeedSearcherHandle {
-        WorldSeedSearcher::spawn_multithreaded(
-            self.structure_seed,
-            self.is_random_world_seed,
-            self.data,
-            self.max_results,
-        )
+    pub fn spawn_multithreaded(mut self) -> WorldSeedSearcherHandle {
+        self.order_constraints();
+        WorldSeedSearcher::spawn_multithreaded(self)
+    }
+
+    /// Single threaded search over a sub-range of the upper 16 bits of the world seed (the full search covers `0..65536`),
+    /// ignoring `is_random_world_seed` and `origin`. Like the threaded search, a candidate whose checks fail with an
+    /// error doesn't match and is counted in [`BoundedWorldSearch::skipped`].
+    ///
+    /// ```
+    /// use mcseedcracker::{cubiomes::enums::BiomeID, search::{WorldExtraData, WorldSeedSearchData}};
+    ///
+    /// let search = WorldSeedSearchData::new(
+    ///     180066252004364,
+    ///     false,
+    ///     vec![WorldExtraData::OverworldBiomeData(vec![(400, 60, 800, BiomeID::beach)])],
+    ///     5,
+    /// );
+    /// let found = search.search_bounded(39978..39982);
+    /// assert!(found.seeds.contains(&-7193194438565520372));
+    /// assert_eq!(found.skipped, 0);
+    /// ```
+    pub fn search_bounded(&self, upper_bits: Range<i64>) -> BoundedWorldSearch {
+        let mut results = BoundedWorldSearch::default();
+        for hi in upper_bits {
+            let seed = (hi << 48) | (self.structure_seed & 0xFFFF_FFFF_FFFF);
+            match self.passes_bounded(seed) {
+                Ok(true) => results.seeds.push(seed),
+                Ok(false) => {}
+                Err(err) => {
+                    results.skipped += 1;
+                    results.first_error.get_or_insert(err);
+                }
+            }
+        }
+        results
+    }
+
+    fn passes_bounded(&self, seed: i64) -> Result<bool, ConstraintError> {
+        if !pillars_allowed(self.pillar_seeds.as_deref(), seed, self.version) {
+            return Ok(false);
+        }
+        for d in self.data.iter().filter(|d| !d.is_tie_breaker()) {
+            if !d.check_seed(seed, self.version)? {
+                return Ok(false);
+            }
+        }
+        self.condition
+            .as_ref()
+            .map_or(Ok(true), |c| c.check_world_seed(seed, self.version))
     }
 }
 
+/// The world seeds found by [`WorldSeedSearchData::search_bounded`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoundedWorldSearch {
+    pub seeds: Vec<i64>,
+    /// Candidates treated as not matching because a check failed with an error
+    pub skipped: u64,
+    pub first_error: Option<ConstraintError>,
+}
+
 pub struct WorldSeedSearcher {
     structure_seed: i64,
     data: Vec<WorldExtraData>,
     max_results: usize,
     is_random_world_seed: bool,
+    origin: SeedOrigin,
+    cooperation: Option<Cooperation>,
+    search_options: SearchOptions,
+    condition: Option<Arc<EvidenceExpr>>,
+    version: MCVersion,
+    pillar_seeds: Option<Arc<[i64]>>,
 
     progress: AtomicU64,
+    heartbeat: Heartbeat,
     isdone: AtomicBool,
     status: Mutex<Status>,
+    /// Candidates for which a check failed with an error
+    skipped: AtomicU64,
+    rejections: RejectionCounters,
+    first_error: Mutex<Option<ConstraintError>>,
+    /// Of the results, in the same order, once the search is done
+    fits: Mutex<Vec<WorldSeedFit>>,
 }
 
 impl WorldSeedSearcher {
+    /// Runs every check, an error counts as a failed check
+    #[inline(always)]
+    fn passes(&self, seed: i64) -> bool {
+        let rejection = self.rejection(seed);
+        self.rejections.record(rejection);
+        rejection.is_none()
+    }
+
+    /// The first check `seed` fails
+    #[inline(always)]
+    fn rejection(&self, seed: i64) -> Option<Rejection> {
+        if !pillars_allowed(self.pillar_seeds.as_deref(), seed, self.version) {
+            return Some(Rejection::PillarSeed);
+        }
+        for d in self.data.iter() {
+            match d.check_seed(seed, self.version) {
+                Ok(true) => {}
+                // the tie-breakers always pass
+                Ok(false) => {
+                    return Some(match d {
+                        WorldExtraData::NetherBiomeData(_) => Rejection::NetherBiome,
+                        WorldExtraData::StructurePresence { .. } => Rejection::Structure,
+                        _ => Rejection::OverworldBiome,
+                    });
+                }
+                Err(err) => {
+                    self.record_error(err);
+                    return Some(Rejection::Error);
+                }
+            }
+        }
+        match self
+            .condition
+            .as_ref()
+            .map(|c| c.check_world_seed(seed, self.version))
+        {
+            None | Some(Ok(true)) => None,
+            Some(Ok(false)) => Some(Rejection::Condition),
+            Some(Err(err)) => {
+                self.record_error(err);
+                Some(Rejection::Error)
+            }
+        }
+    }
+
+    #[cold]
+    fn record_error(&self, err: ConstraintError) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut first_error) = self.first_error.lock() {
+            first_error.get_or_insert(err);
+        }
+    }
+
+    /// Why the candidates were rejected so far, every one of them is counted
+    #[inline]
+    pub fn rejections(&self) -> RejectionStats {
+        self.rejections.stats()
+    }
+
+    /// Number of candidates treated as non-matching because one of their checks failed with an error
+    #[inline]
+    pub fn skipped_candidates(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn first_error(&self) -> Option<ConstraintError> {
+        self.first_error.lock().ok().and_then(|e| *e)
+    }
+
     #[inline]
     fn compute(&self) -> Vec<i64> {
-        if self.is_random_world_seed {
-            self.compute_random()
-        } else {
-            self.compute_any()
+        let compute = || match self.origin.completions(self.structure_seed) {
+            Some(completions) => self.compute_completions(completions),
+            None if self.is_random_world_seed => self.compute_random(),
+            None => self.compute_any(),
+        };
+        match self.cooperation {
+            Some(_) => compute(),
+            None => self.search_options.install(compute),
         }
     }
 
     fn compute_random(&self) -> Vec<i64> {
-        // Since the random world seed is determined by a single nextLong call,
-        // and nextLong() is implemented as `(a << 32) + b` where a and b are
-        // the results of two nextInt() calls, we can guarantee that b is exactly
-        // the 32 low bits of the world seed, which conveniently are also the
-        // 32 low bits of the structure seed. By looping over the 2^16 possible
-        // remaining bits of the 48 bit RNG state that led to b, we can check
-        // which ones produce a value of a that combines with b to a world seed
-        // whose structure seed matches the one we're looking for.
-        let b_state_hi = (self.structure_seed & 0xFFFF_FFFF) << 16;
-        let structure_seed = self.structure_seed;
-
-        let results = (0i64..65536i64)
-            .into_par_iter()
-            .filter_map(move |b_state_lo| {
-                if b_state_lo % 512 == 511 {
-                    self.progress.fetch_add(512, Ordering::Relaxed);
-                }
-
-                let b_state = b_state_hi | b_state_lo;
-                let state_a = lcg::JAVA_RANDOM_REV1.next_seed(b_state);
-                let a_shifted = (state_a & 0xFFFF_FFFF_0000) << 16;
-                let reconstructed_b_value = ((b_state >> 16) as i32) as i64; // as i32 as i64 to bit extend negative values
-                let reconstructed_next_long = a_shifted.wrapping_add(reconstructed_b_value);
-                if (reconstructed_next_long & 0xFFFF_FFFF_FFFF) == structure_seed {
-                    Some(reconstructed_next_long)
-                } else {
-                    None
-                }
-            })
-            .collect_vec_list();
+        let results = match &self.cooperation {
+            Some(cooperation) => {
+                let mut results = Vec::new();
+                run_chunked(
+                    0..65536,
+                    cooperation.world_seeds_per_chunk,
+                    cooperation,
+                    |chunk| {
+                        let searched = (chunk.end - chunk.start) as u64;
+                        results.extend(chunk.filter_map(|b_state_lo| {
+                            next_long_world_seed(self.structure_seed, b_state_lo)
+                        }));
+                        self.progress.fetch_add(searched, Ordering::Relaxed);
+                        self.heartbeat.beat(searched);
+                        true
+                    },
+                );
+                results
+            }
+            None => (0i64..65536i64)
+                .into_par_iter()
+                .filter_map(|b_state_lo| {
+                    let searched = self.search_options.batch_progress(b_state_lo, 65536);
+                    if searched > 0 {
+                        self.progress.fetch_add(searched, Ordering::Relaxed);
+                        self.heartbeat.beat(searched);
+                    }
+                    next_long_world_seed(self.structure_seed, b_state_lo)
+                })
+                .collect_vec_list()
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
 
         let mut tried = Vec::new();
         let mut ok = Vec::new();
-        'a: for v in results {
-            'b: for val in v {
-                if !tried.contains(&val) {
-                    tried.push(val);
-                    for d in self.data.iter() {
-                        if unlikely(!d.check_seed(val)) {
-                            continue 'b;
-                        }
-                    }
-                    ok.push(val);
-                    if ok.len() > self.max_results {
-                        break 'a;
-                    }
+        for val in results {
+            if !tried.contains(&val) {
+                tried.push(val);
+                if unlikely(!self.passes(val)) {
+                    continue;
                 }
+                ok.push(val);
+                if ok.len() > self.max_results {
+                    break;
+                }
+            }
+        }
+        // a typed seed isn't the result of a nextLong call
+        for val in typed_numeric_completions(self.structure_seed) {
+            if ok.len() > self.max_results {
+                break;
+            }
+            if !tried.contains(&val) && self.passes(val) {
+                ok.push(val);
             }
         }
 
+        self.order_results(&mut ok);
         self.done(&ok);
 
         ok
     }
 
-    fn compute_any(&self) -> Vec<i64> {
-        let results = (0i64..65536i64)
-            .into_par_iter()
-            .filter_map(|seed_hi| {
-                if seed_hi % 512 == 511 {
-                    self.progress.fetch_add(512, Ordering::Relaxed);
-                }
+    /// Checks the few completions of [`SeedOrigin::completions`], the other upper bits are skipped
+    fn compute_completions(&self, completions: Vec<i64>) -> Vec<i64> {
+        let mut results = completions
+            .into_iter()
+            .filter(|seed| self.passes(*seed))
+            .take(self.max_results + 1)
+            .collect::<Vec<_>>();
+        self.progress.fetch_add(65536, Ordering::Relaxed);
+        self.heartbeat.beat(65536);
 
-                let seed = seed_hi << 48 | self.structure_seed;
-                for d in self.data.iter() {
-                    if unlikely(!d.check_seed(seed)) {
-                        return None;
-                    }
-                }
-                Some(seed)
-            })
-            .take_any(self.max_results + 1)
+        self.order_results(&mut results);
+        self.done(&results);
+
+        results
+    }
+
+    fn compute_any(&self) -> Vec<i64> {
+        // the typed seeds are the most likely ones, they are checked first so `take_any` can't drop them
+        let typed = typed_numeric_completions(self.structure_seed);
+        let mut results = typed
+            .iter()
+            .copied()
+            .filter(|seed| self.passes(*seed))
             .collect::<Vec<_>>();
+        let candidate = |seed_hi: i64| {
+            let seed = seed_hi << 48 | self.structure_seed;
+            if typed.contains(&seed) || unlikely(!self.passes(seed)) {
+                return None;
+            }
+            Some(seed)
+        };
+        match &self.cooperation {
+            Some(cooperation) => run_chunked(
+                0..65536,
+                cooperation.world_seeds_per_chunk,
+                cooperation,
+                |chunk| {
+                    let searched = (chunk.end - chunk.start) as u64;
+                    results.extend(chunk.filter_map(candidate));
+                    self.progress.fetch_add(searched, Ordering::Relaxed);
+                    self.heartbeat.beat(searched);
+                    results.len() <= self.max_results
+                },
+            ),
+            None => results.par_extend(
+                (0i64..65536i64)
+                    .into_par_iter()
+                    .filter_map(|seed_hi| {
+                        let searched = self.search_options.batch_progress(seed_hi, 65536);
+                        if searched > 0 {
+                            self.progress.fetch_add(searched, Ordering::Relaxed);
+                            self.heartbeat.beat(searched);
+                        }
+                        candidate(seed_hi)
+                    })
+                    .take_any((self.max_results + 1).saturating_sub(results.len())),
+            ),
+        }
+        results.truncate(self.max_results + 1);
 
+        self.order_results(&mut results);
         self.done(&results);
 
         results
     }
 
+    /// Moves the seeds that are consistent with the most tie-breaker evidences first, then the ones whose biome
+    /// samples fit best, keeping the order otherwise. Only the survivors are measured, so this costs next to nothing.
+    fn order_results(&self, results: &mut [i64]) {
+        let mut ranked = results
+            .iter()
+            .map(|seed| {
+                let inconsistent = self
+                    .data
+                    .iter()
+                    .filter(|d| d.is_tie_breaker() && !d.is_consistent(*seed))
+                    .count();
+                (
+                    inconsistent,
+                    WorldSeedFit::measure(*seed, &self.data, self.version),
+                )
+            })
+            .collect::<Vec<_>>();
+        rank_world_seeds(&mut ranked);
+
+        for (result, (_, fit)) in results.iter_mut().zip(ranked.iter()) {
+            *result = fit.seed;
+        }
+        if let Ok(mut fits) = self.fits.lock() {
+            *fits = ranked.into_iter().map(|(_, fit)| fit).collect();
+        }
+    }
+
+    /// How well the biome samples fit each result, empty until the search is done
+    #[inline]
+    pub fn fits(&self) -> Vec<WorldSeedFit> {
+        self.fits.lock().map(|f| f.clone()).unwrap_or_default()
+    }
+
+    /// The found seeds scored by their fit, see [`WorldSeedCandidate::new`]
+    pub fn candidates(&self, seeds: &[i64]) -> Vec<WorldSeedCandidate> {
+        let fits = self.fits();
+        seeds
+            .iter()
+            .map(|&seed| {
+                WorldSeedCandidate::new(
+                    self.structure_seed,
+                    seed,
+                    fits.iter().find(|f| f.seed == seed),
+                )
+            })
+            .collect()
+    }
+
     fn done(&self, results: &Vec<i64>) {
         match self.status.lock() {
             Ok(mut status) => {
@@ -462,20 +2656,27 @@ impl WorldSeedSearcher {
         self.isdone.store(true, Ordering::Relaxed);
     }
 
-    pub fn spawn_multithreaded(
-        structure_seed: i64,
-        is_random_world_seed: bool,
-        data: Vec<WorldExtraData>,
-        max_results: u16,
-    ) -> WorldSeedSearcherHandle {
+    /// Runs the search as given, see [`WorldSeedSearchData::spawn_multithreaded`] to order its checks first
+    pub fn spawn_multithreaded(search: WorldSeedSearchData) -> WorldSeedSearcherHandle {
         let job = Arc::new(WorldSeedSearcher {
-            structure_seed,
-            data,
-            is_random_world_seed,
-            max_results: max_results as usize,
+            structure_seed: search.structure_seed,
+            data: search.data,
+            is_random_world_seed: search.is_random_world_seed,
+            origin: search.origin,
+            cooperation: search.cooperation,
+            search_options: search.search_options,
+            condition: search.condition,
+            version: search.version,
+            pillar_seeds: search.pillar_seeds,
+            max_results: search.max_results as usize,
             progress: AtomicU64::new(0),
+            heartbeat: Heartbeat::new(),
             status: Mutex::new(Status::Searching),
             isdone: AtomicBool::new(false),
+            skipped: AtomicU64::new(0),
+            rejections: RejectionCounters::new(),
+            first_error: Mutex::new(None),
+            fits: Mutex::new(Vec::new()),
         });
         let job2 = Arc::clone(&job);
 
@@ -515,8 +2716,1069 @@ impl WorldSeedSearcherHandle {
         self.searcher.structure_seed
     }
 
+    #[inline]
+    pub fn get_skipped_candidates(&self) -> u64 {
+        self.searcher.skipped_candidates()
+    }
+
+    #[inline]
+    pub fn get_rejections(&self) -> RejectionStats {
+        self.searcher.rejections()
+    }
+
+    #[inline]
+    pub fn get_first_error(&self) -> Option<ConstraintError> {
+        self.searcher.first_error()
+    }
+
+    #[inline]
+    pub fn get_fits(&self) -> Vec<WorldSeedFit> {
+        self.searcher.fits()
+    }
+
+    /// The found seeds scored by their fit, call it once the search is done
+    #[inline]
+    pub fn get_candidates(&self, seeds: &[i64]) -> Vec<WorldSeedCandidate> {
+        self.searcher.candidates(seeds)
+    }
+
     #[inline]
     pub fn get_progress(&self) -> u64 {
         self.searcher.progress.load(Ordering::Relaxed)
     }
+
+    #[inline]
+    pub fn heartbeat(&self) -> &Heartbeat {
+        &self.searcher.heartbeat
+    }
+
+    /// No progress for `threshold` while still searching, see [`Heartbeat`]
+    #[inline]
+    pub fn is_stalled(&self, threshold: Duration) -> bool {
+        !self.is_done() && self.searcher.heartbeat.is_stalled(threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, atomic::Ordering};
+
+    use cubiomes::{
+        enums::{BiomeID, Dimension, MCVersion},
+        generator::{Generator, GeneratorFlags},
+    };
+
+    use crate::{
+        analysis::{FeatureKind, describe_world},
+        cooperative::{Cooperation, SearchOptions},
+        features::{
+            bastion::{self, BastionChestType, get_bastion_chest},
+            buried_treasure::{
+                ChunkArea, build_fast_inventory_compare_context,
+                build_partial_inventory_compare_context, generates_at, get_buried_treasure,
+                get_loot_table,
+                items::{HEART_OF_THE_SEA, PRISMARINE_CRYSTALS},
+            },
+            decoration::DecorationKind,
+            decorator_chest::ChestIndex,
+            end_pillars::PillarMatchResult,
+        },
+        loot_table::{CountBound, Inventory, ItemCountConstraints, ItemStack, SlotThreshold},
+        math::Math,
+        rarity::BiomeRarityTable,
+        rejections::{Rejection, STRUCTURE_SAMPLE_RATE},
+        search::{
+            BiomeQuery, CheckpointError, ConstraintError, FIT_RADIUS, KnownStructure,
+            SearchCheckpoint, SearchOutcome, StructureData, StructureSeedAnnotation,
+            StructureSeedSearch, StructureSeedSearchData, WorldExtraData, WorldSeedFit,
+            WorldSeedSearchData, check_structure_seed_candidate, order_by_selectivity,
+            rank_structure_seeds, rank_world_seeds, sample_margin, structure_seed_of,
+        },
+        self_test,
+        world_seed::{SeedOrigin, structure_seed_possible_from_nextlong},
+    };
+
+    #[test]
+    fn test_order_by_selectivity() {
+        let decoration = WorldExtraData::DecorationCount {
+            chunk: (9, 50),
+            decoration: DecorationKind::SugarCane,
+            feature_index: 4,
+            surface_y: 63,
+            observed_count: 17,
+            tolerance: 0,
+        };
+        let data = vec![
+            decoration.clone(),
+            WorldExtraData::NetherBiomeData(vec![]),
+            WorldExtraData::OverworldBiomeData(vec![
+                (400, 60, 800, BiomeID::beach),
+                (-404, 69, -51, BiomeID::beach),
+                (137, 73, -90, BiomeID::jungle),
+            ]),
+        ];
+
+        let mut ordered = data.clone();
+        order_by_selectivity(
+            &mut ordered,
+            BiomeRarityTable::for_version(MCVersion::MC_1_16_5),
+        );
+        assert!(
+            ordered
+                == vec![
+                    WorldExtraData::OverworldBiomeData(vec![
+                        (137, 73, -90, BiomeID::jungle),
+                        (400, 60, 800, BiomeID::beach),
+                        (-404, 69, -51, BiomeID::beach),
+                    ]),
+                    WorldExtraData::NetherBiomeData(vec![]),
+                    decoration,
+                ]
+        );
+
+        let mut search = WorldSeedSearchData::new(180066252004364, false, data, 5);
+        let unordered_results = search.search_bounded(39970..39990).seeds;
+        search.order_constraints();
+        assert_eq!(search.search_bounded(39970..39990).seeds, unordered_results);
+        assert!(unordered_results.contains(&-7193194438565520372));
+        assert_eq!(search.verification_order().len(), 3);
+        assert!(search.verification_order()[0].starts_with("Overworld biomes"));
+    }
+
+    #[test]
+    fn test_structure_seed_search() {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let search = || {
+            StructureSeedSearchData::new(
+                self_test::PILLAR_SEED,
+                vec![StructureData::BuriedTreasureContents {
+                    chunk_x,
+                    chunk_z,
+                    luck: 0.0,
+                    contents: build_fast_inventory_compare_context(
+                        self_test::buried_treasure_contents(),
+                        &get_loot_table(),
+                    )
+                    .unwrap(),
+                    loot_table: None,
+                }],
+                10,
+            )
+            .with_state_hi(self_test::STRUCTURE_SEARCH_SLICE)
+        };
+        let expected = vec![self_test::STRUCTURE_SEED];
+
+        let handle = StructureSeedSearch::start(search());
+        assert_eq!(handle.pillar_seed(), self_test::PILLAR_SEED);
+        while !handle.is_done() {
+            std::thread::yield_now();
+        }
+        assert_eq!(handle.progress(), (16 * 65536, 16 * 65536));
+        assert_eq!(handle.found_seeds(), expected);
+        assert!(handle.found_seeds().is_empty());
+        assert_eq!(
+            handle.outcome(),
+            Some(SearchOutcome::Complete(expected.clone()))
+        );
+        assert_eq!(handle.join(), SearchOutcome::Complete(expected.clone()));
+
+        let cooperation = Cooperation {
+            structure_shards_per_chunk: 4,
+            ..Cooperation::DEFAULT
+        };
+        let handle = StructureSeedSearch::start(search().with_cooperation(Some(cooperation)));
+        assert_eq!(handle.join(), SearchOutcome::Complete(expected));
+
+        let handle = StructureSeedSearch::start(search().with_state_hi(0..65536));
+        handle.cancel();
+        assert!(matches!(handle.join(), SearchOutcome::Cancelled(_)));
+    }
+
+    #[test]
+    fn test_search_threads() {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        // every buried treasure holds a heart of the sea, these are the seeds whose treasure generates in the chunk
+        let data = StructureData::BuriedTreasureCounts {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            counts: ItemCountConstraints::new(vec![(HEART_OF_THE_SEA, CountBound::AtLeast(1))]),
+            loot_table: None,
+        };
+        let options = |threads| SearchOptions {
+            threads: Some(threads),
+            batch_size: 1000,
+        };
+
+        let structure_seeds = |threads| {
+            let search =
+                StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![data.clone()], u16::MAX)
+                    .with_state_hi(self_test::STRUCTURE_SEARCH_SLICE)
+                    .with_search_options(options(threads));
+            let handle = StructureSeedSearch::start(search);
+            while !handle.is_done() {
+                std::thread::yield_now();
+            }
+            // the batches of the last candidates of each value of state_hi are shorter
+            assert_eq!(handle.progress(), (16 * 65536, 16 * 65536));
+            // only the threads of the pool of the search made progress
+            let progress = handle.heartbeat().thread_progress();
+            let last = progress.len() - 1;
+            assert!(progress[threads.min(last)..last].iter().all(|p| *p == 0));
+            let SearchOutcome::Complete(mut seeds) = handle.join() else {
+                panic!("the search didn't complete");
+            };
+            seeds.sort_unstable();
+            seeds
+        };
+        let single = structure_seeds(1);
+        assert!(single.len() > 1000);
+        assert!(single.contains(&self_test::STRUCTURE_SEED));
+        assert_eq!(single, structure_seeds(4));
+
+        let world_seeds = |threads| {
+            let mut seeds =
+                WorldSeedSearchData::new(self_test::STRUCTURE_SEED, true, Vec::new(), u16::MAX)
+                    .with_search_options(options(threads))
+                    .spawn_multithreaded()
+                    .join()
+                    .unwrap();
+            seeds.sort_unstable();
+            seeds
+        };
+        let single = world_seeds(1);
+        assert!(single.contains(&self_test::WORLD_SEED));
+        assert_eq!(single, world_seeds(4));
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let treasure = StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        };
+        let search = || {
+            StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![treasure.clone()], 10)
+                .with_state_hi(47488..47552)
+                .with_checkpoint_interval(16)
+        };
+
+        let handle = StructureSeedSearch::start(search());
+        while !handle.is_done() {
+            std::thread::yield_now();
+        }
+        let checkpoints = handle.checkpoints();
+        let uninterrupted = handle.join();
+        assert_eq!(
+            uninterrupted,
+            SearchOutcome::Complete(vec![self_test::STRUCTURE_SEED])
+        );
+        assert_eq!(
+            checkpoints.iter().map(|c| c.completed).collect::<Vec<_>>(),
+            [47504, 47520, 47536, 47552]
+        );
+        assert!(checkpoints[3].is_complete());
+
+        // interrupted before the seed and after it
+        for interrupted in &checkpoints[1..3] {
+            let mut file = Vec::new();
+            interrupted.save(&mut file).unwrap();
+            let loaded = SearchCheckpoint::load(file.as_slice()).unwrap();
+            assert_eq!(&loaded, interrupted);
+
+            let handle = StructureSeedSearch::resume(search(), loaded).unwrap();
+            while !handle.is_done() {
+                std::thread::yield_now();
+            }
+            assert_eq!(handle.progress(), (64 * 65536, 64 * 65536));
+            assert_eq!(handle.found_seeds(), [self_test::STRUCTURE_SEED]);
+            assert_eq!(handle.join(), uninterrupted);
+        }
+
+        let other = StructureSeedSearchData::new(0, vec![treasure.clone()], 10);
+        assert_eq!(
+            StructureSeedSearch::resume(other, checkpoints[1].clone()).err(),
+            Some(CheckpointError::OtherPillarSeed {
+                expected: 0,
+                found: self_test::PILLAR_SEED
+            })
+        );
+        let other = search().with_nextlong_only(true);
+        assert_eq!(
+            StructureSeedSearch::resume(other, checkpoints[1].clone()).err(),
+            Some(CheckpointError::OtherEvidence)
+        );
+        let corrupted = SearchCheckpoint {
+            completed: 0,
+            ..checkpoints[1].clone()
+        };
+        assert_eq!(
+            StructureSeedSearch::resume(search(), corrupted).err(),
+            Some(CheckpointError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_cooperative_world_search() {
+        let search = || {
+            WorldSeedSearchData::new(
+                self_test::STRUCTURE_SEED,
+                true,
+                vec![WorldExtraData::OverworldBiomeData(vec![(
+                    400,
+                    60,
+                    800,
+                    BiomeID::beach,
+                )])],
+                5,
+            )
+        };
+        let parallel = search().spawn_multithreaded().join().unwrap();
+
+        let cooperation = Cooperation {
+            world_seeds_per_chunk: 1000,
+            ..Cooperation::DEFAULT
+        };
+        let handle = search()
+            .with_cooperation(Some(cooperation))
+            .spawn_multithreaded();
+        let searcher = Arc::clone(&handle.searcher);
+        assert_eq!(handle.join().unwrap(), parallel);
+        assert_eq!(searcher.progress.load(Ordering::Relaxed), 65536);
+    }
+
+    #[test]
+    fn test_world_seed_origins() {
+        let search = |structure_seed, data, origin| {
+            let handle = WorldSeedSearchData::new(structure_seed, false, data, 5)
+                .with_origin(origin)
+                .spawn_multithreaded();
+            let searcher = Arc::clone(&handle.searcher);
+            let mut seeds = handle.join().unwrap();
+            assert_eq!(searcher.progress.load(Ordering::Relaxed), 65536);
+            seeds.sort_unstable();
+            seeds
+        };
+        let beach = || {
+            vec![WorldExtraData::OverworldBiomeData(vec![
+                self_test::BIOME_SAMPLE,
+            ])]
+        };
+
+        // only the nextLong completions are checked, not the typed ones or the 65536 others
+        let seeds = search(self_test::STRUCTURE_SEED, beach(), SeedOrigin::NextLong);
+        assert!(seeds.contains(&self_test::WORLD_SEED));
+        assert!(seeds.iter().all(|seed| SeedOrigin::NextLong.allows(*seed)));
+        // bits 32 to 47 of the structure seed aren't the sign extension of bit 31, no text hashes to it
+        assert!(
+            search(
+                self_test::STRUCTURE_SEED,
+                beach(),
+                SeedOrigin::StringHashcode
+            )
+            .is_empty()
+        );
+
+        let structure_seed = -12345 & 0xFFFF_FFFF_FFFF;
+        assert_eq!(
+            search(structure_seed, Vec::new(), SeedOrigin::StringHashcode),
+            [-12345]
+        );
+        assert_eq!(
+            search(structure_seed, Vec::new(), SeedOrigin::NumericText),
+            [-12345, structure_seed]
+        );
+        // without an origin every completion can be the world seed
+        assert_eq!(search(structure_seed, Vec::new(), SeedOrigin::Any).len(), 6);
+    }
+
+    #[test]
+    fn test_rejection_counters() {
+        let chunk = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let treasure = |chest| StructureData::BuriedTreasureContents {
+            chunk_x: chunk.0,
+            chunk_z: chunk.1,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap(),
+            loot_table: None,
+        };
+        let chest = self_test::buried_treasure_contents();
+        let data = treasure(chest.clone());
+
+        let handle = StructureSeedSearch::start(
+            StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![data.clone()], 10)
+                .with_state_hi(self_test::STRUCTURE_SEARCH_SLICE),
+        );
+        while !handle.is_done() {
+            std::thread::yield_now();
+        }
+        let stats = handle.rejections();
+        let found = handle.join();
+
+        // the sampled candidates, rejected by the same checks one by one
+        let sampled = self_test::STRUCTURE_SEARCH_SLICE
+            .flat_map(|hi| {
+                (0..65536)
+                    .step_by(STRUCTURE_SAMPLE_RATE as usize)
+                    .map(move |lo| structure_seed_of(self_test::PILLAR_SEED, hi, lo))
+            })
+            .collect::<Vec<_>>();
+        let no_treasure = sampled
+            .iter()
+            .filter(|seed| !generates_at(**seed, chunk))
+            .count() as u64;
+        let accepted = sampled
+            .iter()
+            .filter(|seed| data.check_seed(**seed))
+            .count() as u64;
+        assert_eq!(stats.checked, 16 * 65536 / 64);
+        assert_eq!(stats.checked, sampled.len() as u64);
+        assert_eq!(stats.count(Rejection::NoTreasure), no_treasure);
+        assert_eq!(
+            stats.count(Rejection::ItemCounts) + stats.count(Rejection::Slots),
+            stats.checked - no_treasure - accepted
+        );
+        assert_eq!(stats.accepted(), accepted);
+        assert!(accepted as usize <= found.seeds().len());
+        // buried treasures generate in 1% of the chunks
+        assert!((0.985..0.995).contains(&stats.ratio(Rejection::NoTreasure)));
+
+        // one seed of each kind
+        let seed = self_test::STRUCTURE_SEED;
+        assert_eq!(data.rejection(seed), None);
+        let a = (0..27)
+            .find(|slot| chest.get_item(*slot).is_some())
+            .unwrap();
+        let b = (a..27)
+            .find(|slot| chest.get_item(*slot) != chest.get_item(a))
+            .unwrap();
+        let (stack_a, stack_b) = (chest.get_item(a).cloned(), chest.get_item(b).cloned());
+        let mut swapped = chest.clone();
+        swapped.set_item(a, stack_b);
+        swapped.set_item(b, stack_a);
+        assert_eq!(treasure(swapped).rejection(seed), Some(Rejection::Slots));
+        let mut missing = chest.clone();
+        missing.set_item(a, None);
+        assert_eq!(
+            treasure(missing).rejection(seed),
+            Some(Rejection::ItemCounts)
+        );
+        let elsewhere = (0..).find(|seed| !generates_at(*seed, chunk)).unwrap();
+        assert_eq!(data.rejection(elsewhere), Some(Rejection::NoTreasure));
+
+        let handle = WorldSeedSearchData::new(
+            self_test::STRUCTURE_SEED,
+            true,
+            vec![WorldExtraData::OverworldBiomeData(vec![
+                self_test::BIOME_SAMPLE,
+            ])],
+            5,
+        )
+        .spawn_multithreaded();
+        let searcher = Arc::clone(&handle.searcher);
+        let seeds = handle.join().unwrap();
+        let stats = searcher.rejections();
+        assert_eq!(stats.accepted(), seeds.len() as u64);
+        assert_eq!(
+            stats.count(Rejection::OverworldBiome),
+            stats.checked - stats.accepted()
+        );
+    }
+
+    #[test]
+    fn test_bastion_chest() {
+        let world_seed: i64 = 734679766044180411;
+        let chunk = Math::block_coords_to_chunk_coords((-674, -820));
+        let chest_type = BastionChestType::TreasureRoom;
+        let chest = get_bastion_chest(world_seed, chunk, 1, chest_type, 0.0);
+        let bastion = |chest, index| {
+            let contents = bastion::build_fast_inventory_compare_context(
+                chest,
+                &bastion::get_loot_table(chest_type),
+            )
+            .unwrap();
+            StructureData::bastion_chest(chunk, index, chest_type, 0.0, contents)
+        };
+        let data = bastion(chest.clone(), ChestIndex::default());
+
+        // the loot only depends on the lower 48 bits
+        let seed = world_seed & ((1 << 48) - 1);
+        assert!(data.check_seed(seed));
+        assert!(data.verify_seed(seed));
+        assert_eq!(data.matching_chunk(seed), Some(chunk));
+        assert_eq!(data.rejection(seed), None);
+        assert!(bastion(chest.clone(), ChestIndex::Known(1)).verify_seed(seed));
+        let wrong_index = bastion(chest.clone(), ChestIndex::Known(0));
+        assert!(!wrong_index.check_seed(seed));
+        assert!(!wrong_index.verify_seed(seed));
+        assert_ne!(wrong_index, data);
+
+        let a = (0..27)
+            .find(|slot| chest.get_item(*slot).is_some())
+            .unwrap();
+        let b = (a..27)
+            .find(|slot| chest.get_item(*slot) != chest.get_item(a))
+            .unwrap();
+        let (stack_a, stack_b) = (chest.get_item(a).cloned(), chest.get_item(b).cloned());
+        let mut swapped = chest.clone();
+        swapped.set_item(a, stack_b);
+        swapped.set_item(b, stack_a);
+        assert_eq!(
+            bastion(swapped, ChestIndex::default()).rejection(seed),
+            Some(Rejection::Slots)
+        );
+        let mut missing = chest.clone();
+        missing.set_item(a, None);
+        let missing = bastion(missing, ChestIndex::default());
+        assert!(!missing.verify_seed(seed));
+        assert_eq!(missing.rejection(seed), Some(Rejection::ItemCounts));
+        // there is no structure to check
+        assert_ne!(data.rejection(seed + 1), Some(Rejection::NoTreasure));
+    }
+
+    #[test]
+    fn test_invalid_constraints() {
+        let bad_row = (0, 5000, 0, BiomeID::plains);
+        let search = WorldSeedSearchData::new(
+            180066252004364,
+            false,
+            vec![
+                WorldExtraData::OverworldBiomeData(vec![(400, 60, 800, BiomeID::beach)]),
+                WorldExtraData::OverworldBiomeData(vec![bad_row]),
+                WorldExtraData::NetherBiomeData(vec![(30_000_000, 64, 0, BiomeID::nether_wastes)]),
+            ],
+            5,
+        );
+        assert_eq!(
+            search.invalid_samples(),
+            vec![
+                ConstraintError::OutOfWorld {
+                    dimension: "overworld",
+                    pos: (0, 5000, 0),
+                },
+                ConstraintError::OutOfWorld {
+                    dimension: "nether",
+                    pos: (30_000_000, 64, 0),
+                },
+            ]
+        );
+        let found = search.search_bounded(39978..39982);
+        assert!(found.seeds.is_empty());
+        assert!(found.skipped > 0);
+        assert!(matches!(
+            found.first_error,
+            Some(ConstraintError::OutOfWorld { .. })
+        ));
+
+        // the search completes, and the candidates that passed the beach check are reported as skipped
+        let handle = search.spawn_multithreaded();
+        let searcher = Arc::clone(&handle.searcher);
+        let seeds = handle.join().unwrap();
+        assert!(seeds.is_empty());
+        assert!(searcher.skipped_candidates() > 0);
+        assert!(matches!(
+            searcher.first_error(),
+            Some(ConstraintError::OutOfWorld { .. })
+        ));
+
+        let valid = WorldSeedSearchData::new(
+            180066252004364,
+            false,
+            vec![WorldExtraData::OverworldBiomeData(vec![(
+                400,
+                60,
+                800,
+                BiomeID::beach,
+            )])],
+            5,
+        );
+        assert!(valid.invalid_samples().is_empty());
+        assert_eq!(
+            ConstraintError::OutOfWorld {
+                dimension: "overworld",
+                pos: (0, 5000, 0),
+            }
+            .to_string(),
+            "overworld biome sample at (0, 5000, 0) is outside of the world"
+        );
+    }
+
+    #[test]
+    fn test_structure_presence() {
+        let report = describe_world(
+            self_test::WORLD_SEED,
+            self_test::BURIED_TREASURE_POS,
+            1024,
+            MCVersion::MC_1_16_5,
+        );
+        let monument = report
+            .features_of(FeatureKind::Monument)
+            .next()
+            .expect("a monument near the golden buried treasure");
+
+        // without evidence every world seed of the slice passes
+        let candidates = WorldSeedSearchData::new(self_test::STRUCTURE_SEED, false, vec![], 5)
+            .search_bounded(39976..39984)
+            .seeds;
+        assert_eq!(candidates.len(), 8);
+        assert!(candidates.contains(&self_test::WORLD_SEED));
+
+        let presence = WorldExtraData::structure_presence(
+            KnownStructure::Monument,
+            monument.block_x,
+            monument.block_z,
+        );
+        let search = WorldSeedSearchData::new(self_test::STRUCTURE_SEED, false, vec![presence], 5);
+        assert_eq!(
+            search.search_bounded(39976..39984).seeds,
+            vec![self_test::WORLD_SEED]
+        );
+        assert!(search.verification_order()[0].starts_with("monument at"));
+
+        // the chunk next to it isn't the attempt of its region
+        let moved = WorldExtraData::structure_presence(
+            KnownStructure::Monument,
+            monument.block_x + 16,
+            monument.block_z,
+        );
+        assert_eq!(
+            moved.check_seed(self_test::WORLD_SEED, MCVersion::MC_1_16_5),
+            Ok(false)
+        );
+
+        let outside = WorldExtraData::structure_presence(KnownStructure::Village, 30_000_100, 0);
+        assert_eq!(
+            outside.invalid_samples(MCVersion::MC_1_16_5),
+            vec![ConstraintError::StructureOutOfWorld {
+                pos: (30_000_100, 0)
+            }]
+        );
+
+        for structure in KnownStructure::ALL {
+            assert_eq!(KnownStructure::from_name(structure.name()), Some(structure));
+            assert_eq!(
+                KnownStructure::of(structure.structure_type()),
+                Some(structure)
+            );
+        }
+        assert!(matches!(
+            WorldExtraData::structure_presence(KnownStructure::Bastion, 0, 0),
+            WorldExtraData::StructurePresence {
+                dimension: Dimension::DIM_NETHER,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_biome_versions() {
+        // biomes that only generate since 1.18
+        const CAVES_AND_CLIFFS: [BiomeID; 8] = [
+            BiomeID::meadow,
+            BiomeID::grove,
+            BiomeID::snowy_slopes,
+            BiomeID::jagged_peaks,
+            BiomeID::frozen_peaks,
+            BiomeID::stony_peaks,
+            BiomeID::dripstone_caves,
+            BiomeID::lush_caves,
+        ];
+        let world_seed = self_test::WORLD_SEED;
+        let generator = Generator::new(
+            MCVersion::MC_1_18_2,
+            world_seed,
+            Dimension::DIM_OVERWORLD,
+            GeneratorFlags::empty(),
+        );
+        let sample = (-16..16)
+            .flat_map(|x| (-16..16).map(move |z| (x * 256, z * 256)))
+            .find_map(|(x, z)| {
+                generator
+                    .biome_at(x, 64, z)
+                    .filter(|biome| CAVES_AND_CLIFFS.contains(biome))
+                    .map(|biome| (x, 64, z, biome))
+            })
+            .expect("no 1.18 biome within 4096 blocks");
+
+        let upper_bits = (world_seed as u64 >> 48) as i64;
+        let search = |version| {
+            WorldSeedSearchData::new(
+                world_seed & 0xFFFF_FFFF_FFFF,
+                false,
+                vec![WorldExtraData::OverworldBiomeData(vec![sample])],
+                5,
+            )
+            .with_version(version)
+        };
+        assert!(
+            search(MCVersion::MC_1_18_2)
+                .search_bounded(upper_bits..upper_bits + 1)
+                .seeds
+                .contains(&world_seed)
+        );
+        // the 1.16 generator never returns the biome
+        assert!(
+            search(MCVersion::MC_1_16_5)
+                .search_bounded(upper_bits..upper_bits + 1)
+                .seeds
+                .is_empty()
+        );
+
+        // the overworld goes below 0 since 1.18, the nether doesn't
+        let deep = WorldExtraData::OverworldBiomeData(vec![(0, -32, 0, BiomeID::plains)]);
+        assert!(deep.invalid_samples(MCVersion::MC_1_18_2).is_empty());
+        assert_eq!(deep.invalid_samples(MCVersion::MC_1_16_5).len(), 1);
+        let nether = WorldExtraData::NetherBiomeData(vec![(0, -32, 0, BiomeID::nether_wastes)]);
+        assert_eq!(nether.invalid_samples(MCVersion::MC_1_20_6).len(), 1);
+    }
+
+    #[test]
+    fn test_rank_structure_seeds() {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let data = vec![StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        }];
+
+        let golden = StructureSeedAnnotation::new(
+            &data,
+            self_test::STRUCTURE_SEED,
+            self_test::PILLAR_SEED,
+            PillarMatchResult::ExactMatch,
+        );
+        assert_eq!(golden.satisfied_evidence, 1);
+        let unsupported =
+            StructureSeedAnnotation::new(&data, 1234, 13847, PillarMatchResult::ExactMatch);
+        assert_eq!(unsupported.satisfied_evidence, 0);
+
+        let annotation =
+            |structure_seed, pillar_match, satisfied_evidence| StructureSeedAnnotation {
+                structure_seed,
+                pillar_seed: 0,
+                pillar_match,
+                satisfied_evidence,
+                treasure_chunk: None,
+            };
+        let mut seeds = vec![
+            annotation(1, PillarMatchResult::PossibleMatch(0.9), 3),
+            annotation(2, PillarMatchResult::ExactMatch, 0),
+            annotation(3, PillarMatchResult::PossibleMatch(0.1), 3),
+            annotation(4, PillarMatchResult::ExactMatch, 2),
+            annotation(5, PillarMatchResult::PossibleMatch(0.9), 1),
+            annotation(6, PillarMatchResult::ImpossibleMatch, 5),
+            annotation(0, PillarMatchResult::ExactMatch, 2),
+        ];
+        rank_structure_seeds(&mut seeds);
+        assert_eq!(
+            seeds.iter().map(|a| a.structure_seed).collect::<Vec<_>>(),
+            vec![0, 4, 2, 1, 3, 5, 6]
+        );
+
+        // stable regardless of the input order
+        let mut reversed = seeds.clone();
+        reversed.reverse();
+        rank_structure_seeds(&mut reversed);
+        assert_eq!(reversed, seeds);
+    }
+
+    /// Plains west of `edge`, ocean from it on, and no biome at all north of z = -100
+    struct Coast {
+        edge: i32,
+    }
+
+    impl BiomeQuery for Coast {
+        fn biome_at(&self, x: i32, _y: i32, z: i32) -> Option<BiomeID> {
+            if z < -100 {
+                None
+            } else if x < self.edge {
+                Some(BiomeID::plains)
+            } else {
+                Some(BiomeID::ocean)
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_margin() {
+        let coast = Coast { edge: 10 };
+        // the cells 4 and 8 blocks east are still plains
+        assert_eq!(
+            sample_margin(&coast, (0, 64, 0, BiomeID::plains), FIT_RADIUS),
+            12
+        );
+        assert_eq!(
+            sample_margin(&coast, (9, 64, 0, BiomeID::plains), FIT_RADIUS),
+            4
+        );
+        assert_eq!(
+            sample_margin(&coast, (10, 64, 0, BiomeID::plains), FIT_RADIUS),
+            0
+        );
+        assert_eq!(
+            sample_margin(&coast, (-100, 64, 0, BiomeID::plains), FIT_RADIUS),
+            FIT_RADIUS as u32
+        );
+        assert_eq!(sample_margin(&coast, (-100, 64, 0, BiomeID::plains), 8), 8);
+        // the cells that can't be looked up count as another biome
+        assert_eq!(
+            sample_margin(&coast, (-100, 64, -92, BiomeID::plains), FIT_RADIUS),
+            12
+        );
+        assert_eq!(
+            sample_margin(&coast, (0, 64, -200, BiomeID::plains), FIT_RADIUS),
+            0
+        );
+    }
+
+    #[test]
+    fn test_world_seed_fit() {
+        let samples = [(0, 64, 0, BiomeID::plains), (41, 64, 0, BiomeID::ocean)];
+        let mut centered = WorldSeedFit {
+            seed: 1,
+            margins: Vec::new(),
+        };
+        centered.add_samples(&Coast { edge: 100 }, &samples[..1]);
+        centered.add_samples(&Coast { edge: 0 }, &samples[1..]);
+        assert_eq!(centered.margins, vec![FIT_RADIUS as u32; 2]);
+        assert_eq!(centered.score(), 1.0);
+
+        let mut edge = WorldSeedFit {
+            seed: 2,
+            margins: Vec::new(),
+        };
+        edge.add_samples(&Coast { edge: 10 }, &samples);
+        assert_eq!(edge.margins, vec![12, 32]);
+        assert_eq!(edge.min_margin(), Some(12));
+        assert_eq!(edge.score(), (12.0 / 32.0 + 1.0) / 2.0);
+
+        let no_samples = WorldSeedFit {
+            seed: 3,
+            margins: Vec::new(),
+        };
+        assert_eq!(no_samples.score(), 1.0);
+
+        // the tie-breakers come first, the fit only orders the seeds they can't tell apart
+        let mut seeds = vec![
+            (1, no_samples.clone()),
+            (0, edge.clone()),
+            (0, centered.clone()),
+        ];
+        rank_world_seeds(&mut seeds);
+        assert_eq!(
+            seeds.iter().map(|(_, fit)| fit.seed).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        let fit = WorldSeedFit::measure(
+            self_test::WORLD_SEED,
+            &[
+                WorldExtraData::OverworldBiomeData(vec![(400, 60, 800, BiomeID::beach)]),
+                WorldExtraData::DecorationCount {
+                    chunk: (0, 0),
+                    decoration: DecorationKind::Cactus,
+                    feature_index: 0,
+                    surface_y: 63,
+                    observed_count: 0,
+                    tolerance: 0,
+                },
+            ],
+            MCVersion::MC_1_16_5,
+        );
+        assert_eq!(fit.margins.len(), 1);
+        assert!(fit.margins[0] >= 4);
+    }
+
+    #[test]
+    fn test_buried_treasure_area() {
+        let treasure = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let area = |a: (i32, i32), b: (i32, i32)| StructureData::BuriedTreasureContentsInArea {
+            area: ChunkArea::new(a, b),
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        };
+        let (min, max) = (
+            (treasure.0 - 3, treasure.1 - 3),
+            (treasure.0 + 3, treasure.1 + 3),
+        );
+        // the corners in any order
+        assert_eq!(area(max, min), area(min, max));
+        assert_eq!(ChunkArea::count_chunks(max, min), 49);
+        let data = area(max, min);
+
+        // other chunks of the area may have a buried treasure, but only one holds the observed chest
+        let seed = self_test::STRUCTURE_SEED;
+        let single = |chunk: (i32, i32)| StructureData::BuriedTreasureContents {
+            chunk_x: chunk.0,
+            chunk_z: chunk.1,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        };
+        let matching = (min.1..=max.1)
+            .flat_map(|z| (min.0..=max.0).map(move |x| (x, z)))
+            .filter(|chunk| generates_at(seed, *chunk) && single(*chunk).check_seed(seed))
+            .collect::<Vec<_>>();
+        assert_eq!(matching, vec![treasure]);
+
+        assert!(data.check_seed(seed));
+        assert!(data.verify_seed(seed));
+        assert_eq!(data.matching_chunk(seed), Some(treasure));
+        let annotation = StructureSeedAnnotation::new(
+            std::slice::from_ref(&data),
+            seed,
+            self_test::PILLAR_SEED,
+            PillarMatchResult::ExactMatch,
+        );
+        assert_eq!(annotation.treasure_chunk, Some(treasure));
+
+        // an area next to the treasure
+        let beside = area((treasure.0 + 1, min.1), max);
+        assert!(!beside.check_seed(seed));
+        assert!(!beside.verify_seed(seed));
+        assert_eq!(beside.matching_chunk(seed), None);
+
+        let search = StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![data], 10);
+        assert_eq!(
+            search.search_bounded(self_test::STRUCTURE_SEARCH_SLICE),
+            vec![seed]
+        );
+    }
+
+    #[test]
+    fn test_several_buried_treasures() {
+        let seed = self_test::STRUCTURE_SEED;
+        // the first three stacks of a chest, too few to find the seed alone
+        let glimpse = |chunk: (i32, i32)| {
+            let chest = get_buried_treasure(seed, chunk, 0.0);
+            let mut known = [false; 27];
+            (0..27)
+                .filter(|slot| chest.get_item(*slot).is_some())
+                .take(3)
+                .for_each(|slot| known[slot as usize] = true);
+            StructureData::BuriedTreasureContents {
+                chunk_x: chunk.0,
+                chunk_z: chunk.1,
+                luck: 0.0,
+                contents: build_partial_inventory_compare_context(chest, known, &get_loot_table())
+                    .unwrap(),
+                loot_table: None,
+            }
+        };
+        let search = |data| {
+            StructureSeedSearchData::new(self_test::PILLAR_SEED, data, 10)
+                .search_bounded(self_test::STRUCTURE_SEARCH_SLICE)
+        };
+        let (first, second) = ((25, 50), (31, 48));
+        assert!(generates_at(seed, second));
+
+        for chunk in [first, second] {
+            let found = search(vec![glimpse(chunk)]);
+            assert!(found.len() > 1);
+            assert!(found.contains(&seed));
+        }
+        assert_eq!(search(vec![glimpse(first), glimpse(second)]), vec![seed]);
+    }
+
+    #[test]
+    fn test_slot_threshold_search() {
+        let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(self_test::BURIED_TREASURE_POS);
+        let mut chest = self_test::buried_treasure_contents();
+        // a prismarine crystal that was never there
+        chest.set_item(26, Some(ItemStack::of(PRISMARINE_CRYSTALS, 1)));
+        let contents = build_fast_inventory_compare_context(chest, &get_loot_table()).unwrap();
+        let data = |slot_threshold| StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            contents: contents.clone().with_slot_threshold(slot_threshold),
+            loot_table: None,
+        };
+        let seed = self_test::STRUCTURE_SEED;
+
+        let exact = data(None);
+        assert!(!exact.check_seed(seed));
+        assert!(!exact.verify_seed(seed));
+
+        let relaxed = data(Some(SlotThreshold::new(26, 27)));
+        assert!(relaxed.check_seed(seed));
+        assert!(relaxed.verify_seed(seed));
+        let search = StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![relaxed], 10);
+        assert_eq!(
+            search.search_bounded(self_test::STRUCTURE_SEARCH_SLICE),
+            vec![seed]
+        );
+    }
+
+    #[test]
+    fn test_nextlong_only_search() {
+        // no evidence, every candidate passes the data
+        let kept = |nextlong_only| {
+            (0..256)
+                .filter_map(|state_lo| {
+                    check_structure_seed_candidate(
+                        &[],
+                        None,
+                        self_test::PILLAR_SEED,
+                        nextlong_only,
+                        47522,
+                        state_lo,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let all = kept(false);
+        let generated = kept(true);
+        assert_eq!(all.len(), 256);
+        assert!(generated.len() < all.len());
+        assert_eq!(
+            generated,
+            all.iter()
+                .copied()
+                .filter(|s| structure_seed_possible_from_nextlong(*s))
+                .collect::<Vec<_>>()
+        );
+
+        // the bundled world seed was generated
+        let data = StructureData::BuriedTreasureContents {
+            chunk_x: 25,
+            chunk_z: 50,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                self_test::buried_treasure_contents(),
+                &get_loot_table(),
+            )
+            .unwrap(),
+            loot_table: None,
+        };
+        let search = StructureSeedSearchData::new(self_test::PILLAR_SEED, vec![data], 10)
+            .with_nextlong_only(true);
+        assert_eq!(
+            search.search_bounded(self_test::STRUCTURE_SEARCH_SLICE),
+            vec![self_test::STRUCTURE_SEED]
+        );
+    }
 }
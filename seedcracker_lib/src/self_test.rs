@@ -0,0 +1,312 @@
+//! Known-answer checks of the whole pipeline, run by the test suite and by `seedcracker self-test`.
+//!
+//! They catch builds that give wrong results (miscompiled cubiomes, fast-math flags, ...) instead of failing loudly.
+
+use std::ops::Range;
+
+use cubiomes::{
+    enums::{BiomeID, Dimension, MCVersion},
+    generator::{Generator, GeneratorFlags},
+};
+
+use crate::{
+    features::{
+        bastion::{
+            BastionChestType, get_bastion_chest,
+            items::hoglin_stables::{
+                CRIMSON_FUNGUS, CRIMSON_NYLIUM, CRIMSON_ROOTS, GOLDEN_AXE, SADDLE,
+            },
+        },
+        buried_treasure::{
+            self, build_fast_inventory_compare_context, get_buried_treasure,
+            items::{
+                COOKED_COD, GOLD_INGOT, HEART_OF_THE_SEA, IRON_INGOT, IRON_SWORD,
+                PRISMARINE_CRYSTALS,
+            },
+        },
+        end_pillars::EndPillars,
+    },
+    loot_table::{ChestRow, ItemProperty, ItemStack, SingleChest},
+    math::Math,
+    search::{StructureData, StructureSeedSearchData},
+    utils::enchants,
+};
+
+pub const WORLD_SEED: i64 = -7193194438565520372;
+pub const STRUCTURE_SEED: i64 = 180066252004364;
+pub const PILLAR_SEED: i64 = 13847;
+
+/// Height and cage of each pillar generated by [`PILLAR_SEED`]
+pub const PILLARS: [(i32, bool); 10] = [
+    (103, false),
+    (94, false),
+    (85, false),
+    (76, false),
+    (82, true),
+    (88, false),
+    (79, true),
+    (100, false),
+    (97, false),
+    (91, false),
+];
+
+pub const BURIED_TREASURE_POS: (i32, i32) = (409, 809);
+
+/// 16 x 65536 = 2^20 structure seed candidates of [`PILLAR_SEED`], [`STRUCTURE_SEED`] is the only match
+pub const STRUCTURE_SEARCH_SLICE: Range<i64> = 47512..47528;
+
+/// An overworld biome of [`WORLD_SEED`]
+pub const BIOME_SAMPLE: (i32, i32, i32, BiomeID) = (400, 60, 800, BiomeID::beach);
+
+pub const BASTION_WORLD_SEED: i64 = 734679766044180411;
+/// A hoglin stables chest of [`BASTION_WORLD_SEED`], the first chest of its chunk
+pub const BASTION_CHEST_POS: (i32, i32) = (97, 166);
+
+/// The buried treasure at [`BURIED_TREASURE_POS`] in [`WORLD_SEED`], with no luck
+pub fn buried_treasure_contents() -> SingleChest {
+    SingleChest {
+        rows: [
+            ChestRow {
+                items: [
+                    Some(ItemStack::of(COOKED_COD, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::new(IRON_SWORD, 1, 1)),
+                    Some(ItemStack::of(COOKED_COD, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    None,
+                    None,
+                    None,
+                ],
+            },
+            ChestRow {
+                items: [
+                    Some(ItemStack::of(GOLD_INGOT, 2)),
+                    Some(ItemStack::of(COOKED_COD, 2)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(HEART_OF_THE_SEA, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(COOKED_COD, 2)),
+                    None,
+                    None,
+                ],
+            },
+            ChestRow {
+                items: [
+                    Some(ItemStack::of(GOLD_INGOT, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(COOKED_COD, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(COOKED_COD, 1)),
+                    Some(ItemStack::of(IRON_INGOT, 1)),
+                    Some(ItemStack::of(PRISMARINE_CRYSTALS, 3)),
+                    Some(ItemStack::of(PRISMARINE_CRYSTALS, 1)),
+                    None,
+                ],
+            },
+        ],
+    }
+}
+
+/// The hoglin stables chest at [`BASTION_CHEST_POS`] in [`BASTION_WORLD_SEED`], with no luck
+pub fn bastion_chest_contents() -> SingleChest {
+    SingleChest {
+        rows: [
+            ChestRow {
+                items: [
+                    None,
+                    None,
+                    Some(ItemStack::of(CRIMSON_FUNGUS, 1)),
+                    Some(ItemStack::new(SADDLE, 1, 1)),
+                    None,
+                    Some(ItemStack::of(CRIMSON_FUNGUS, 2)),
+                    None,
+                    None,
+                    Some(ItemStack::of(CRIMSON_ROOTS, 2)),
+                ],
+            },
+            ChestRow {
+                items: [
+                    Some(ItemStack::with_properties(
+                        GOLDEN_AXE,
+                        1,
+                        1,
+                        &[ItemProperty::Enchantment {
+                            enchantment: enchants::axe::SMITE.0,
+                            level: 2,
+                        }],
+                    )),
+                    Some(ItemStack::of(CRIMSON_ROOTS, 1)),
+                    None,
+                    None,
+                    Some(ItemStack::of(CRIMSON_NYLIUM, 1)),
+                    None,
+                    None,
+                    None,
+                    None,
+                ],
+            },
+            ChestRow {
+                items: [
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(ItemStack::of(CRIMSON_NYLIUM, 1)),
+                    None,
+                    None,
+                    Some(ItemStack::of(CRIMSON_NYLIUM, 2)),
+                    None,
+                ],
+            },
+        ],
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub run: fn() -> Result<(), String>,
+}
+
+pub const SELF_TEST_CHECKS: &[SelfTestCheck] = &[
+    SelfTestCheck {
+        name: "buried treasure loot",
+        run: check_buried_treasure_loot,
+    },
+    SelfTestCheck {
+        name: "bastion loot",
+        run: check_bastion_loot,
+    },
+    SelfTestCheck {
+        name: "end pillars",
+        run: check_end_pillars,
+    },
+    SelfTestCheck {
+        name: "structure seed search",
+        run: check_structure_seed_search,
+    },
+    SelfTestCheck {
+        name: "cubiomes biome",
+        run: check_biome,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestResult {
+    pub name: &'static str,
+    pub result: Result<(), String>,
+}
+
+/// Runs every check, in order
+pub fn run_self_test() -> Vec<SelfTestResult> {
+    SELF_TEST_CHECKS
+        .iter()
+        .map(|check| SelfTestResult {
+            name: check.name,
+            result: (check.run)(),
+        })
+        .collect()
+}
+
+fn check_buried_treasure_loot() -> Result<(), String> {
+    let generated = get_buried_treasure(
+        STRUCTURE_SEED,
+        Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS),
+        0.0,
+    );
+    if generated == buried_treasure_contents() {
+        Ok(())
+    } else {
+        Err("the generated chest differs from the expected one".to_string())
+    }
+}
+
+fn check_bastion_loot() -> Result<(), String> {
+    let generated = get_bastion_chest(
+        BASTION_WORLD_SEED,
+        Math::block_coords_to_chunk_coords(BASTION_CHEST_POS),
+        0,
+        BastionChestType::HoglinStables,
+        0.0,
+    );
+    if generated == bastion_chest_contents() {
+        Ok(())
+    } else {
+        Err("the generated chest differs from the expected one".to_string())
+    }
+}
+
+fn check_end_pillars() -> Result<(), String> {
+    let mut pillars = EndPillars::new();
+    pillars.from_seed(PILLAR_SEED);
+    let generated = pillars
+        .iter()
+        .map(|p| (p.height, p.caged))
+        .collect::<Vec<_>>();
+    if generated == PILLARS {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, got {:?}", PILLARS, generated))
+    }
+}
+
+fn check_structure_seed_search() -> Result<(), String> {
+    let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+    let search = StructureSeedSearchData::new(
+        PILLAR_SEED,
+        vec![StructureData::BuriedTreasureContents {
+            chunk_x,
+            chunk_z,
+            luck: 0.0,
+            contents: build_fast_inventory_compare_context(
+                buried_treasure_contents(),
+                &buried_treasure::get_loot_table(),
+            )
+            .map_err(|err| err.to_string())?,
+            loot_table: None,
+        }],
+        10,
+    );
+    let found = search.search_bounded(STRUCTURE_SEARCH_SLICE);
+    if found == [STRUCTURE_SEED] {
+        Ok(())
+    } else {
+        Err(format!("expected [{}], got {:?}", STRUCTURE_SEED, found))
+    }
+}
+
+fn check_biome() -> Result<(), String> {
+    let (x, y, z, expected) = BIOME_SAMPLE;
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        WORLD_SEED,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+    match generator.get_biome_at(x, y, z) {
+        Ok(biome) if biome == expected => Ok(()),
+        Ok(biome) => Err(format!("expected {:?}, got {:?}", expected, biome)),
+        Err(_) => Err(format!("the biome lookup at ({}, {}, {}) failed", x, y, z)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::self_test::{SELF_TEST_CHECKS, run_self_test};
+
+    #[test]
+    fn test_self_test() {
+        let results = run_self_test();
+        assert_eq!(results.len(), SELF_TEST_CHECKS.len());
+        for result in results {
+            assert_eq!(
+                result.result,
+                Ok(()),
+                "self test check {} failed",
+                result.name
+            );
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Prebuilt "common evidence" templates, describing which evidence is worth collecting for a few typical situations.
+//!
+//! Templates are plain data: frontends walk the fields of a template and prepare the matching inputs, so adding a
+//! template never requires changes in the code that displays it.
+
+use crate::features::bastion::BastionChestType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvidenceSection {
+    EndPillars,
+    BuriedTreasure,
+    OverworldBiomes,
+    NetherBiomes,
+    Decorations,
+    BastionChest,
+}
+
+impl EvidenceSection {
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            EvidenceSection::EndPillars => "End pillars",
+            EvidenceSection::BuriedTreasure => "Buried treasure",
+            EvidenceSection::OverworldBiomes => "Overworld biomes",
+            EvidenceSection::NetherBiomes => "Nether biomes",
+            EvidenceSection::Decorations => "Decorations",
+            EvidenceSection::BastionChest => "Bastion chest",
+        }
+    }
+}
+
+/// What a field asks for, the frontends find the inputs to prepare with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldId {
+    PillarHeights,
+    PillarCages,
+    TreasureX,
+    TreasureZ,
+    TreasureContents,
+    OverworldBiome,
+    /// The contents of a bastion chest of this type
+    BastionChestContents(BastionChestType),
+}
+
+impl FieldId {
+    #[inline(always)]
+    pub const fn section(&self) -> EvidenceSection {
+        match self {
+            FieldId::PillarHeights | FieldId::PillarCages => EvidenceSection::EndPillars,
+            FieldId::TreasureX | FieldId::TreasureZ | FieldId::TreasureContents => {
+                EvidenceSection::BuriedTreasure
+            }
+            FieldId::OverworldBiome => EvidenceSection::OverworldBiomes,
+            FieldId::BastionChestContents(_) => EvidenceSection::BastionChest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemplateField {
+    pub id: FieldId,
+    pub section: EvidenceSection,
+    /// Shown to the user, [`TemplateField::id`] tells the fields apart
+    pub label: &'static str,
+    /// What to look at in game to fill the field
+    pub guidance: &'static str,
+    pub required: bool,
+    /// Placeholder rows to add, for sections made of a list of observations
+    pub rows: usize,
+}
+
+impl TemplateField {
+    #[inline(always)]
+    const fn required(id: FieldId, label: &'static str, guidance: &'static str) -> Self {
+        Self {
+            id,
+            section: id.section(),
+            label,
+            guidance,
+            required: true,
+            rows: 0,
+        }
+    }
+
+    #[inline(always)]
+    const fn optional(id: FieldId, label: &'static str, guidance: &'static str) -> Self {
+        Self {
+            id,
+            section: id.section(),
+            label,
+            guidance,
+            required: false,
+            rows: 0,
+        }
+    }
+
+    #[inline(always)]
+    const fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvidenceTemplate {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Bits of seed information this evidence typically provides, out of the 64 bits of a world seed
+    pub estimated_bits: f64,
+    pub fields: &'static [TemplateField],
+}
+
+impl EvidenceTemplate {
+    pub fn fields_in(&self, section: EvidenceSection) -> impl Iterator<Item = &TemplateField> {
+        self.fields.iter().filter(move |f| f.section == section)
+    }
+
+    #[inline]
+    pub fn uses(&self, section: EvidenceSection) -> bool {
+        self.fields_in(section).next().is_some()
+    }
+
+    #[inline]
+    pub fn placeholder_rows(&self, section: EvidenceSection) -> usize {
+        self.fields_in(section).map(|f| f.rows).sum()
+    }
+
+    #[inline]
+    pub fn is_required(&self, id: FieldId) -> bool {
+        self.fields.iter().any(|f| f.required && f.id == id)
+    }
+
+    /// Section of the first field, where the user should start
+    #[inline]
+    pub fn first_section(&self) -> Option<EvidenceSection> {
+        self.fields.first().map(|f| f.section)
+    }
+}
+
+pub const EVIDENCE_TEMPLATES: &[EvidenceTemplate] = &[
+    EvidenceTemplate {
+        id: "buried_treasure",
+        title: "I have a buried treasure chest",
+        description: "The position and contents of an unopened buried treasure chest pin down most of the structure seed.",
+        estimated_bits: 40.0,
+        fields: &[
+            TemplateField::required(
+                FieldId::TreasureX,
+                "Treasure X",
+                "Block X of the chest, as shown by F3 when looking at it",
+            ),
+            TemplateField::required(
+                FieldId::TreasureZ,
+                "Treasure Z",
+                "Block Z of the chest, as shown by F3 when looking at it",
+            ),
+            TemplateField::required(
+                FieldId::TreasureContents,
+                "Contents",
+                "Copy every slot before taking anything out of the chest",
+            ),
+        ],
+    },
+    EvidenceTemplate {
+        id: "end_pillars",
+        title: "I visited the End",
+        description: "The heights and cages of the obsidian pillars give the 16 bits of the pillar seed.",
+        estimated_bits: 16.0,
+        fields: &[
+            TemplateField::required(
+                FieldId::PillarHeights,
+                "Pillar heights",
+                "Count the obsidian blocks of each pillar, or at least tell small, medium and big ones apart",
+            ),
+            TemplateField::optional(
+                FieldId::PillarCages,
+                "Cages",
+                "Note which pillars have an iron bar cage around their crystal",
+            ),
+        ],
+    },
+    EvidenceTemplate {
+        id: "spawn_biomes",
+        title: "I know three biomes near spawn",
+        description: "A few far apart biomes tell the world seeds of a structure seed apart.",
+        estimated_bits: 9.0,
+        fields: &[TemplateField::required(
+            FieldId::OverworldBiome,
+            "Biome",
+            "Stand in three distant spots and write down your position and the biome shown by F3",
+        )
+        .with_rows(3)],
+    },
+    EvidenceTemplate {
+        id: "bastion_treasure",
+        title: "I have a bastion treasure room",
+        description: "The contents of an unopened treasure room chest constrain the structure seed.",
+        estimated_bits: 32.0,
+        fields: &[TemplateField::required(
+            FieldId::BastionChestContents(BastionChestType::TreasureRoom),
+            "Treasure room chest",
+            "Copy every slot of one of the treasure room chests before opening the others",
+        )],
+    },
+];
+
+#[inline]
+pub fn find_template(id: &str) -> Option<&'static EvidenceTemplate> {
+    EVIDENCE_TEMPLATES.iter().find(|t| t.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        features::bastion::BastionChestType,
+        templates::{EVIDENCE_TEMPLATES, EvidenceSection, FieldId, find_template},
+    };
+
+    #[test]
+    fn test_templates() {
+        for (i, template) in EVIDENCE_TEMPLATES.iter().enumerate() {
+            assert!(
+                EVIDENCE_TEMPLATES[..i].iter().all(|t| t.id != template.id),
+                "duplicate template id {}",
+                template.id
+            );
+            assert_eq!(find_template(template.id), Some(template));
+            assert!(template.fields.iter().any(|f| f.required));
+            assert!(template.estimated_bits > 0.0 && template.estimated_bits <= 64.0);
+            assert!(template.first_section().is_some());
+        }
+        assert_eq!(find_template("stronghold"), None);
+    }
+
+    #[test]
+    fn test_template_fields() {
+        let biomes = find_template("spawn_biomes").unwrap();
+        assert_eq!(biomes.placeholder_rows(EvidenceSection::OverworldBiomes), 3);
+        assert_eq!(biomes.placeholder_rows(EvidenceSection::NetherBiomes), 0);
+        assert!(biomes.uses(EvidenceSection::OverworldBiomes));
+        assert!(!biomes.uses(EvidenceSection::EndPillars));
+
+        let treasure = find_template("buried_treasure").unwrap();
+        assert_eq!(
+            treasure.first_section(),
+            Some(EvidenceSection::BuriedTreasure)
+        );
+        assert!(treasure.is_required(FieldId::TreasureX));
+        assert!(!treasure.is_required(FieldId::PillarHeights));
+
+        let end = find_template("end_pillars").unwrap();
+        assert!(end.is_required(FieldId::PillarHeights));
+        assert!(!end.is_required(FieldId::PillarCages));
+
+        let bastion = find_template("bastion_treasure").unwrap();
+        assert!(bastion.is_required(FieldId::BastionChestContents(
+            BastionChestType::TreasureRoom
+        )));
+        assert!(!bastion.is_required(FieldId::BastionChestContents(BastionChestType::Bridges)));
+    }
+}
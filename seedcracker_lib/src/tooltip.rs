@@ -0,0 +1,1361 @@
+//! Chest contents transcribed from the item tooltips, one slot per line:
+//!
+//! ```text
+//! slot 1,1: Iron Ingot x4
+//! slot 1,2: Iron Sword durability 123/250
+//! slot 3,9: Diamond Pickaxe durability 1400/1561 [Efficiency III, Mending]
+//! ```
+//!
+//! Rows and columns start at 1. The count defaults to 1, since the game doesn't show it for single items.
+//! Items and enchantments are written with their display name (resolved through a [`LanguageTable`]) or with
+//! their resource name (`minecraft:iron_ingot`).
+//!
+//! [`ChestDump`] writes chest contents back in this format, e.g. to share a predicted chest as text.
+//!
+//! [`parse_count_constraints`] reads item totals remembered without their slots, one item per line followed by a
+//! [`CountBound`]:
+//!
+//! ```text
+//! Iron Ingot 8+
+//! Heart of the Sea 1
+//! Gold Ingot 2-4
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::loot_table::{
+    CountBound, InvalidStack, ItemProperty, ItemRegistry, ItemStack, RegisteredItem, SingleChest,
+    canonical_resource_name,
+};
+
+/// Display names of items and enchantments, as found in the game's `lang/*.json` files
+#[derive(Debug, Clone, Default)]
+pub struct LanguageTable {
+    /// (display name, resource name)
+    items: Vec<(String, String)>,
+    enchantments: Vec<(String, String)>,
+}
+
+impl LanguageTable {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            enchantments: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn item(mut self, name: &str, display_name: &str) -> Self {
+        self.items
+            .push((display_name.to_string(), canonical_resource_name(name)));
+        self
+    }
+
+    #[inline]
+    pub fn enchantment(mut self, name: &str, display_name: &str) -> Self {
+        self.enchantments
+            .push((display_name.to_string(), canonical_resource_name(name)));
+        self
+    }
+
+    /// Reads a language file of the game or of a resource pack. Only the `item.*`, `block.*` and `enchantment.*`
+    /// keys are kept.
+    pub fn from_lang_json(reader: impl std::io::Read) -> serde_json::Result<Self> {
+        let keys: Map<String, Value> = serde_json::from_reader(reader)?;
+
+        let mut table = Self::new();
+        for (key, value) in keys.iter() {
+            let Some(display_name) = value.as_str() else {
+                continue;
+            };
+            let mut parts = key.split('.');
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some("item" | "block"), Some(namespace), Some(name), None) => {
+                    table = table.item(&format!("{}:{}", namespace, name), display_name);
+                }
+                (Some("enchantment"), Some(namespace), Some(name), None) => {
+                    table = table.enchantment(&format!("{}:{}", namespace, name), display_name);
+                }
+                _ => {}
+            }
+        }
+        Ok(table)
+    }
+
+    /// English names of the items and enchantments found in the supported loot tables
+    pub fn en_us() -> Self {
+        const ITEMS: &[(&str, &str)] = &[
+            ("heart_of_the_sea", "Heart of the Sea"),
+            ("iron_ingot", "Iron Ingot"),
+            ("gold_ingot", "Gold Ingot"),
+            ("tnt", "TNT"),
+            ("emerald", "Emerald"),
+            ("diamond", "Diamond"),
+            ("prismarine_crystals", "Prismarine Crystals"),
+            ("leather_chestplate", "Leather Tunic"),
+            ("iron_sword", "Iron Sword"),
+            ("cooked_cod", "Cooked Cod"),
+            ("cooked_salmon", "Cooked Salmon"),
+            // bastion remnants
+            ("netherite_ingot", "Netherite Ingot"),
+            ("ancient_debris", "Ancient Debris"),
+            ("netherite_scrap", "Netherite Scrap"),
+            ("diamond_sword", "Diamond Sword"),
+            ("diamond_pickaxe", "Diamond Pickaxe"),
+            ("diamond_shovel", "Diamond Shovel"),
+            ("diamond_helmet", "Diamond Helmet"),
+            ("diamond_chestplate", "Diamond Chestplate"),
+            ("diamond_leggings", "Diamond Leggings"),
+            ("diamond_boots", "Diamond Boots"),
+            ("golden_sword", "Golden Sword"),
+            ("golden_axe", "Golden Axe"),
+            ("golden_helmet", "Golden Helmet"),
+            ("golden_chestplate", "Golden Chestplate"),
+            ("golden_leggings", "Golden Leggings"),
+            ("golden_boots", "Golden Boots"),
+            ("crossbow", "Crossbow"),
+            ("enchanted_book", "Enchanted Book"),
+            ("enchanted_golden_apple", "Enchanted Golden Apple"),
+            ("golden_apple", "Golden Apple"),
+            ("golden_carrot", "Golden Carrot"),
+            ("spectral_arrow", "Spectral Arrow"),
+            ("arrow", "Arrow"),
+            ("piglin_banner_pattern", "Banner Pattern"),
+            ("music_disc_pigstep", "Music Disc"),
+            ("saddle", "Saddle"),
+            ("lodestone", "Lodestone"),
+            ("gold_block", "Block of Gold"),
+            ("iron_block", "Block of Iron"),
+            ("crying_obsidian", "Crying Obsidian"),
+            ("obsidian", "Obsidian"),
+            ("quartz", "Nether Quartz"),
+            ("gilded_blackstone", "Gilded Blackstone"),
+            ("chain", "Chain"),
+            ("magma_cream", "Magma Cream"),
+            ("bone_block", "Bone Block"),
+            ("iron_nugget", "Iron Nugget"),
+            ("gold_nugget", "Gold Nugget"),
+            ("string", "String"),
+            ("leather", "Leather"),
+            ("porkchop", "Raw Porkchop"),
+            ("cooked_porkchop", "Cooked Porkchop"),
+            ("glowstone", "Glowstone"),
+            ("soul_sand", "Soul Sand"),
+            ("crimson_nylium", "Crimson Nylium"),
+            ("crimson_fungus", "Crimson Fungus"),
+            ("crimson_roots", "Crimson Roots"),
+        ];
+        const ENCHANTMENTS: &[(&str, &str)] = &[
+            ("protection", "Protection"),
+            ("fire_protection", "Fire Protection"),
+            ("feather_falling", "Feather Falling"),
+            ("blast_protection", "Blast Protection"),
+            ("projectile_protection", "Projectile Protection"),
+            ("respiration", "Respiration"),
+            ("aqua_affinity", "Aqua Affinity"),
+            ("thorns", "Thorns"),
+            ("depth_strider", "Depth Strider"),
+            ("frost_walker", "Frost Walker"),
+            ("binding_curse", "Curse of Binding"),
+            ("soul_speed", "Soul Speed"),
+            ("sharpness", "Sharpness"),
+            ("smite", "Smite"),
+            ("bane_of_arthropods", "Bane of Arthropods"),
+            ("knockback", "Knockback"),
+            ("fire_aspect", "Fire Aspect"),
+            ("looting", "Looting"),
+            ("sweeping", "Sweeping Edge"),
+            ("efficiency", "Efficiency"),
+            ("silk_touch", "Silk Touch"),
+            ("unbreaking", "Unbreaking"),
+            ("fortune", "Fortune"),
+            ("multishot", "Multishot"),
+            ("quick_charge", "Quick Charge"),
+            ("piercing", "Piercing"),
+            ("mending", "Mending"),
+            ("vanishing_curse", "Curse of Vanishing"),
+        ];
+
+        let table = ITEMS.iter().fold(Self::new(), |table, (name, display)| {
+            table.item(name, display)
+        });
+        ENCHANTMENTS.iter().fold(table, |table, (name, display)| {
+            table.enchantment(name, display)
+        })
+    }
+
+    /// Resource name of the item with this display name, ignoring case
+    #[inline]
+    pub fn item_named(&self, display_name: &str) -> Option<&str> {
+        Self::find(&self.items, display_name)
+    }
+
+    /// Resource name of the enchantment with this display name, ignoring case
+    #[inline]
+    pub fn enchantment_named(&self, display_name: &str) -> Option<&str> {
+        Self::find(&self.enchantments, display_name)
+    }
+
+    /// Display name of the item with this resource name, the `minecraft:` namespace is optional
+    #[inline]
+    pub fn item_display_name(&self, name: &str) -> Option<&str> {
+        Self::display_name(&self.items, name)
+    }
+
+    #[inline]
+    pub fn enchantment_display_name(&self, name: &str) -> Option<&str> {
+        Self::display_name(&self.enchantments, name)
+    }
+
+    #[inline]
+    fn find<'a>(names: &'a [(String, String)], display_name: &str) -> Option<&'a str> {
+        let display_name = display_name.to_lowercase();
+        names
+            .iter()
+            .find(|(d, _)| d.to_lowercase() == display_name)
+            .map(|(_, n)| n.as_str())
+    }
+
+    #[inline]
+    fn display_name<'a>(names: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        let name = canonical_resource_name(name);
+        names
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(d, _)| d.as_str())
+    }
+}
+
+const ROMAN_NUMERALS: [(i32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Parses a roman numeral in its canonical form ("IV", not "IIII"), between 1 and 3999
+pub fn parse_roman_numeral(s: &str) -> Option<i32> {
+    let mut rest = s;
+    let mut value = 0;
+    for (n, numeral) in ROMAN_NUMERALS {
+        while let Some(r) = rest.strip_prefix(numeral) {
+            rest = r;
+            value += n;
+        }
+    }
+
+    // only the canonical spelling of the value is accepted
+    if rest.is_empty() && (1..=3999).contains(&value) && to_roman_numeral(value) == s {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn to_roman_numeral(mut value: i32) -> String {
+    let mut s = String::new();
+    for (n, numeral) in ROMAN_NUMERALS {
+        while value >= n {
+            s.push_str(numeral);
+            value -= n;
+        }
+    }
+    s
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TooltipErrorKind {
+    /// The line doesn't start with `slot`
+    MissingSlot,
+    MissingColon,
+    InvalidSlot(String),
+    SlotOutOfRange {
+        row: usize,
+        column: usize,
+    },
+    DuplicateSlot {
+        row: usize,
+        column: usize,
+        first_line: usize,
+    },
+    MissingItemName,
+    UnknownItem(String),
+    /// The item exists but never appears in this loot table
+    NotInLootTable(String),
+    InvalidCount(String),
+    /// A line of item counts without its bound
+    MissingCount,
+    CountOutOfRange {
+        count: i32,
+        max_count: i32,
+    },
+    InvalidDurability(String),
+    NoDurability(String),
+    WrongMaxDurability {
+        item: String,
+        expected: i32,
+        found: i32,
+    },
+    UnclosedBracket,
+    UnknownEnchantment(String),
+    InvalidEnchantmentLevel(String),
+    /// The loot table never puts this enchantment on this item
+    NotApplicableEnchantment {
+        item: String,
+        enchantment: String,
+    },
+    EnchantmentLevelOutOfRange {
+        enchantment: String,
+        level: i32,
+        min_level: i32,
+        max_level: i32,
+    },
+    UnexpectedText(String),
+}
+
+impl std::fmt::Display for TooltipErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSlot => write!(f, "expected a line like \"slot 1,1: Iron Ingot x4\""),
+            Self::MissingColon => write!(f, "missing ':' after the slot"),
+            Self::InvalidSlot(slot) => {
+                write!(f, "invalid slot \"{}\", expected \"row,column\"", slot)
+            }
+            Self::SlotOutOfRange { row, column } => write!(
+                f,
+                "slot {},{} is outside of the chest (rows 1 to 3, columns 1 to 9)",
+                row, column
+            ),
+            Self::DuplicateSlot {
+                row,
+                column,
+                first_line,
+            } => write!(
+                f,
+                "slot {},{} was already given on line {}",
+                row, column, first_line
+            ),
+            Self::MissingItemName => write!(f, "missing item name"),
+            Self::UnknownItem(name) => write!(f, "unknown item \"{}\"", name),
+            Self::NotInLootTable(name) => write!(f, "{} can't be found in this chest", name),
+            Self::InvalidCount(count) => write!(f, "invalid count \"{}\"", count),
+            Self::MissingCount => write!(
+                f,
+                "expected a line like \"Iron Ingot 8+\", with a count, N+, <=N or N-M"
+            ),
+            Self::CountOutOfRange { count, max_count } => {
+                write!(f, "count {} is not between 1 and {}", count, max_count)
+            }
+            Self::InvalidDurability(durability) => write!(
+                f,
+                "invalid durability \"{}\", expected \"current/max\"",
+                durability
+            ),
+            Self::NoDurability(name) => write!(f, "{} has no durability", name),
+            Self::WrongMaxDurability {
+                item,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has a durability of {}, not {}",
+                item, expected, found
+            ),
+            Self::UnclosedBracket => write!(f, "missing ']' after the enchantments"),
+            Self::UnknownEnchantment(name) => write!(f, "unknown enchantment \"{}\"", name),
+            Self::InvalidEnchantmentLevel(level) => {
+                write!(f, "invalid enchantment level \"{}\"", level)
+            }
+            Self::NotApplicableEnchantment { item, enchantment } => {
+                write!(f, "{} can't have {} in this chest", item, enchantment)
+            }
+            Self::EnchantmentLevelOutOfRange {
+                enchantment,
+                level,
+                min_level,
+                max_level,
+            } => write!(
+                f,
+                "{} level {} is not between {} and {}",
+                enchantment, level, min_level, max_level
+            ),
+            Self::UnexpectedText(text) => write!(f, "unexpected \"{}\"", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooltipError {
+    /// Starting at 1
+    pub line: usize,
+    pub kind: TooltipErrorKind,
+}
+
+impl std::fmt::Display for TooltipError {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for TooltipError {}
+
+/// Parses a whole tooltip dump. Every line is checked, so all the mistakes are reported at once instead of one
+/// per attempt. Blank lines and lines starting with `#` are ignored.
+pub fn parse_tooltip_dump(
+    text: &str,
+    registry: &ItemRegistry,
+    language: &LanguageTable,
+) -> Result<SingleChest, Vec<TooltipError>> {
+    let mut chest = SingleChest::new();
+    let mut filled = [[None; 9]; 3];
+    let mut errors = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut line_errors = Vec::new();
+        let parsed = parse_line(line, registry, language, &mut line_errors);
+
+        if let Some(((row, column), stack)) = parsed {
+            if row == 0 || row > 3 || column == 0 || column > 9 {
+                line_errors.push(TooltipErrorKind::SlotOutOfRange { row, column });
+            } else if let Some(first_line) = filled[row - 1][column - 1] {
+                line_errors.push(TooltipErrorKind::DuplicateSlot {
+                    row,
+                    column,
+                    first_line,
+                });
+            } else if line_errors.is_empty() {
+                filled[row - 1][column - 1] = Some(line_number);
+                chest.rows[row - 1].items[column - 1] = stack;
+            }
+        }
+
+        errors.extend(line_errors.into_iter().map(|kind| TooltipError {
+            line: line_number,
+            kind,
+        }));
+    }
+
+    if errors.is_empty() {
+        Ok(chest)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses item counts remembered without their slots, see the [module documentation](self). Blank lines and lines
+/// starting with `#` are ignored, an item given twice must satisfy both bounds.
+pub fn parse_count_constraints(
+    text: &str,
+    registry: &ItemRegistry,
+    language: &LanguageTable,
+) -> Result<Vec<(usize, CountBound)>, Vec<TooltipError>> {
+    let mut bounds = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = match line.rsplit_once(' ') {
+            None => Err(TooltipErrorKind::MissingCount),
+            Some((name, bound)) => resolve_item(name.trim(), registry, language).and_then(|item| {
+                bound
+                    .parse::<CountBound>()
+                    .map(|bound| (item.id, bound))
+                    .map_err(|_| TooltipErrorKind::InvalidCount(bound.to_string()))
+            }),
+        };
+        match parsed {
+            Ok(bound) => bounds.push(bound),
+            Err(kind) => errors.push(TooltipError { line: i + 1, kind }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(bounds)
+    } else {
+        Err(errors)
+    }
+}
+
+/// A line of [`parse_count_constraints`], e.g. `Iron Ingot 8+`
+pub fn count_constraint_line(
+    item: usize,
+    bound: CountBound,
+    registry: &ItemRegistry,
+    language: &LanguageTable,
+) -> String {
+    let name = registry.name_of(item);
+    match name
+        .and_then(|name| language.item_display_name(name))
+        .or(name)
+    {
+        Some(name) => format!("{} {}", name, bound),
+        None => format!("item {} {}", item, bound),
+    }
+}
+
+/// Returns the slot as soon as it could be read, along with the stack when the rest of the line is valid
+fn parse_line(
+    line: &str,
+    registry: &ItemRegistry,
+    language: &LanguageTable,
+    errors: &mut Vec<TooltipErrorKind>,
+) -> Option<((usize, usize), Option<ItemStack>)> {
+    let Some(rest) = line
+        .get(..4)
+        .filter(|s| s.eq_ignore_ascii_case("slot"))
+        .map(|_| &line[4..])
+    else {
+        errors.push(TooltipErrorKind::MissingSlot);
+        return None;
+    };
+    let Some((slot, tooltip)) = rest.split_once(':') else {
+        errors.push(TooltipErrorKind::MissingColon);
+        return None;
+    };
+
+    let slot = match slot
+        .split_once(',')
+        .map(|(r, c)| (r.trim().parse::<usize>(), c.trim().parse::<usize>()))
+    {
+        Some((Ok(row), Ok(column))) => (row, column),
+        _ => {
+            errors.push(TooltipErrorKind::InvalidSlot(slot.trim().to_string()));
+            return None;
+        }
+    };
+
+    let (text, enchantments) = match tooltip.split_once('[') {
+        Some((text, enchantments)) => match enchantments.split_once(']') {
+            Some((enchantments, after)) => {
+                if !after.trim().is_empty() {
+                    errors.push(TooltipErrorKind::UnexpectedText(after.trim().to_string()));
+                }
+                (text, Some(enchantments))
+            }
+            None => {
+                errors.push(TooltipErrorKind::UnclosedBracket);
+                (text, None)
+            }
+        },
+        None => (tooltip, None),
+    };
+
+    let mut name_words = Vec::new();
+    let mut count = None;
+    let mut durability = None;
+
+    let mut words = text.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        if let Some(n) = word
+            .strip_prefix(['x', 'X'])
+            .filter(|n| n.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            match n.parse::<i32>() {
+                Ok(n) => count = Some(n),
+                Err(_) => errors.push(TooltipErrorKind::InvalidCount(word.to_string())),
+            }
+        } else if word
+            .trim_end_matches(':')
+            .eq_ignore_ascii_case("durability")
+        {
+            // accept "123/250" as well as "123 / 250"
+            let mut value = String::new();
+            while let Some(w) = words.peek() {
+                if value.contains('/') && !value.ends_with('/') {
+                    break;
+                }
+                value.push_str(w);
+                words.next();
+            }
+            match value
+                .split_once('/')
+                .map(|(current, max)| (current.parse::<i32>(), max.parse::<i32>()))
+            {
+                Some((Ok(current), Ok(max))) if 0 < current && current <= max => {
+                    durability = Some((current, max));
+                }
+                _ => errors.push(TooltipErrorKind::InvalidDurability(value)),
+            }
+        } else if count.is_some() || durability.is_some() {
+            errors.push(TooltipErrorKind::UnexpectedText(word.to_string()));
+        } else {
+            name_words.push(word);
+        }
+    }
+
+    if name_words.is_empty() {
+        errors.push(TooltipErrorKind::MissingItemName);
+        return Some((slot, None));
+    }
+    let display_name = name_words.join(" ");
+
+    let item = match resolve_item(&display_name, registry, language) {
+        Ok(item) => item,
+        Err(err) => {
+            errors.push(err);
+            return Some((slot, None));
+        }
+    };
+
+    let count = count.unwrap_or(1);
+    let mut stack = ItemStack::validated(item.id, count, registry).unwrap_or_else(|err| {
+        errors.push(match err {
+            InvalidStack::CountOutOfRange { count, max_count } => {
+                TooltipErrorKind::CountOutOfRange { count, max_count }
+            }
+            InvalidStack::UnknownItem(_) => TooltipErrorKind::NotInLootTable(display_name.clone()),
+        });
+        ItemStack::new(item.id, count, item.max_count)
+    });
+
+    let properties = &mut stack.properties;
+    if let Some((current, max)) = durability {
+        match item.durability {
+            Some(expected) if expected == max => properties.push(ItemProperty::Damage {
+                max_durability: max,
+                damage: max - current,
+            }),
+            Some(expected) => errors.push(TooltipErrorKind::WrongMaxDurability {
+                item: display_name.clone(),
+                expected,
+                found: max,
+            }),
+            None => errors.push(TooltipErrorKind::NoDurability(display_name.clone())),
+        }
+    }
+
+    for enchantment in enchantments
+        .into_iter()
+        .flat_map(|e| e.split(','))
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+    {
+        match resolve_enchantment(enchantment, &display_name, item, language) {
+            Ok(property) => properties.push(property),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    Some((slot, Some(stack)))
+}
+
+fn resolve_item<'a>(
+    display_name: &str,
+    registry: &'a ItemRegistry,
+    language: &LanguageTable,
+) -> Result<&'a RegisteredItem, TooltipErrorKind> {
+    match language.item_named(display_name) {
+        Some(name) => registry
+            .get(name)
+            .ok_or_else(|| TooltipErrorKind::NotInLootTable(display_name.to_string())),
+        None if !display_name.contains(' ') => registry
+            .get(display_name)
+            .ok_or_else(|| TooltipErrorKind::UnknownItem(display_name.to_string())),
+        None => Err(TooltipErrorKind::UnknownItem(display_name.to_string())),
+    }
+}
+
+/// Resolves "Efficiency III". Enchantments with a single level are shown without one, like "Mending".
+fn resolve_enchantment(
+    text: &str,
+    item_name: &str,
+    item: &RegisteredItem,
+    language: &LanguageTable,
+) -> Result<ItemProperty, TooltipErrorKind> {
+    let (display_name, level) = match text.rsplit_once(' ') {
+        Some((name, level)) if language.enchantment_named(text).is_none() => {
+            match parse_roman_numeral(level).or_else(|| level.parse::<i32>().ok()) {
+                Some(level) => (name.trim(), level),
+                None if language.enchantment_named(name).is_some() => {
+                    return Err(TooltipErrorKind::InvalidEnchantmentLevel(level.to_string()));
+                }
+                None => (text, 1),
+            }
+        }
+        _ => (text, 1),
+    };
+
+    // resource names are only recognized for the enchantments this item can have
+    let name = match language.enchantment_named(display_name) {
+        Some(name) => name.to_string(),
+        None if !display_name.contains(' ')
+            && item
+                .enchantments
+                .iter()
+                .any(|(n, _)| *n == canonical_resource_name(display_name)) =>
+        {
+            canonical_resource_name(display_name)
+        }
+        None => {
+            return Err(TooltipErrorKind::UnknownEnchantment(
+                display_name.to_string(),
+            ));
+        }
+    };
+
+    let Some((_, (enchantment, min_level, max_level))) =
+        item.enchantments.iter().find(|(n, _)| *n == name)
+    else {
+        return Err(TooltipErrorKind::NotApplicableEnchantment {
+            item: item_name.to_string(),
+            enchantment: display_name.to_string(),
+        });
+    };
+
+    if level < *min_level || level > *max_level {
+        return Err(TooltipErrorKind::EnchantmentLevelOutOfRange {
+            enchantment: display_name.to_string(),
+            level,
+            min_level: *min_level,
+            max_level: *max_level,
+        });
+    }
+
+    Ok(ItemProperty::Enchantment {
+        enchantment: *enchantment,
+        level,
+    })
+}
+
+/// Chest contents as a tooltip dump that [`parse_tooltip_dump`] reads back, one line per non-empty slot followed by
+/// a `#` line with the totals. With [`ChestDump::with_give_commands`], the `/give` commands (1.16 syntax) giving the
+/// same stacks are appended, also behind a `#`.
+///
+/// ```text
+/// slot 1,1: Iron Ingot x4
+/// slot 2,5: Iron Sword durability 123/250
+/// # 2 stacks, 5 items: Iron Ingot x4, Iron Sword x1
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ChestDump<'a> {
+    chest: &'a SingleChest,
+    registry: &'a ItemRegistry,
+    language: &'a LanguageTable,
+    give_commands: bool,
+}
+
+impl<'a> ChestDump<'a> {
+    #[inline]
+    pub const fn new(
+        chest: &'a SingleChest,
+        registry: &'a ItemRegistry,
+        language: &'a LanguageTable,
+    ) -> Self {
+        Self {
+            chest,
+            registry,
+            language,
+            give_commands: false,
+        }
+    }
+
+    #[inline]
+    pub const fn with_give_commands(mut self, give_commands: bool) -> Self {
+        self.give_commands = give_commands;
+        self
+    }
+
+    /// Non-empty slots with their row and column, starting at 1
+    fn stacks(&self) -> impl Iterator<Item = (usize, usize, &'a ItemStack)> {
+        self.chest.rows.iter().enumerate().flat_map(|(row, r)| {
+            r.items
+                .iter()
+                .enumerate()
+                .filter_map(move |(column, stack)| stack.as_ref().map(|s| (row + 1, column + 1, s)))
+        })
+    }
+
+    fn item_name(&self, item: usize) -> String {
+        match self.registry.name_of(item) {
+            Some(name) => self
+                .language
+                .item_display_name(name)
+                .map(str::to_string)
+                .unwrap_or_else(|| name.to_string()),
+            None => format!("item {}", item),
+        }
+    }
+
+    fn enchantment_name(&self, item: Option<&RegisteredItem>, enchantment: i32) -> String {
+        match item
+            .into_iter()
+            .flat_map(|i| i.enchantments.iter())
+            .find(|(_, (id, _, _))| *id == enchantment)
+        {
+            Some((name, _)) => self
+                .language
+                .enchantment_display_name(name)
+                .map(str::to_string)
+                .unwrap_or_else(|| name.clone()),
+            None => format!("enchantment {}", enchantment),
+        }
+    }
+
+    fn write_stack(&self, f: &mut std::fmt::Formatter<'_>, stack: &ItemStack) -> std::fmt::Result {
+        let item = self
+            .registry
+            .name_of(stack.item)
+            .and_then(|name| self.registry.get(name));
+        write!(f, "{}", self.item_name(stack.item))?;
+        if stack.count != 1 {
+            write!(f, " x{}", stack.count)?;
+        }
+
+        let mut enchantments = Vec::new();
+        for property in stack.properties.iter() {
+            match property {
+                ItemProperty::Damage {
+                    max_durability,
+                    damage,
+                } => write!(
+                    f,
+                    " durability {}/{}",
+                    max_durability - damage,
+                    max_durability
+                )?,
+                ItemProperty::Enchantment { enchantment, level } => {
+                    let name = self.enchantment_name(item, *enchantment);
+                    let single_level = item
+                        .into_iter()
+                        .flat_map(|i| i.enchantments.iter())
+                        .any(|(_, (id, _, max_level))| id == enchantment && *max_level == 1);
+                    enchantments.push(if single_level && *level == 1 {
+                        name
+                    } else {
+                        format!("{} {}", name, to_roman_numeral(*level))
+                    });
+                }
+            }
+        }
+        if !enchantments.is_empty() {
+            write!(f, " [{}]", enchantments.join(", "))?;
+        }
+        Ok(())
+    }
+
+    /// `None` for items missing from the registry, or with an enchantment the registry doesn't list for them
+    pub fn give_command(&self, stack: &ItemStack) -> Option<String> {
+        let name = self.registry.name_of(stack.item)?;
+        let item = self.registry.get(name);
+
+        let mut tags = Vec::new();
+        let mut enchantments = Vec::new();
+        for property in stack.properties.iter() {
+            match property {
+                ItemProperty::Damage { damage, .. } => tags.push(format!("Damage:{}", damage)),
+                ItemProperty::Enchantment { enchantment, level } => {
+                    let id = item
+                        .into_iter()
+                        .flat_map(|i| i.enchantments.iter())
+                        .find(|(_, (id, _, _))| id == enchantment)
+                        .map(|(name, _)| name.as_str())?;
+                    enchantments.push(format!("{{id:\"{}\",lvl:{}s}}", id, level));
+                }
+            }
+        }
+        if !enchantments.is_empty() {
+            tags.push(format!("Enchantments:[{}]", enchantments.join(",")));
+        }
+
+        let tag = if tags.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", tags.join(","))
+        };
+        Some(format!("/give @s {}{} {}", name, tag, stack.count))
+    }
+}
+
+impl std::fmt::Display for ChestDump<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // total count of each item, in order of first appearance
+        let mut totals: Vec<(usize, i32)> = Vec::new();
+        let mut stacks = 0;
+        for (row, column, stack) in self.stacks() {
+            write!(f, "slot {},{}: ", row, column)?;
+            self.write_stack(f, stack)?;
+            writeln!(f)?;
+
+            stacks += 1;
+            match totals.iter_mut().find(|(item, _)| *item == stack.item) {
+                Some((_, count)) => *count += stack.count,
+                None => totals.push((stack.item, stack.count)),
+            }
+        }
+
+        let items = totals.iter().map(|(_, count)| *count).sum::<i32>();
+        write!(
+            f,
+            "# {} {}, {} {}",
+            stacks,
+            if stacks == 1 { "stack" } else { "stacks" },
+            items,
+            if items == 1 { "item" } else { "items" }
+        )?;
+        for (i, (item, count)) in totals.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(f, "{}{} x{}", sep, self.item_name(*item), count)?;
+        }
+        writeln!(f)?;
+
+        if self.give_commands {
+            for (_, _, stack) in self.stacks() {
+                match self.give_command(stack) {
+                    Some(command) => writeln!(f, "# {}", command)?,
+                    None => writeln!(f, "# no /give command for item {}", stack.item)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        features::buried_treasure::{
+            item_registry,
+            items::{GOLD_INGOT, IRON_INGOT, IRON_SWORD, LEATHER_CHESTPLATE, TNT},
+        },
+        loot_table::{ItemProperty, ItemRegistry, ItemStack, RegisteredItem, SingleChest},
+        self_test::buried_treasure_contents,
+        tooltip::{
+            ChestDump, LanguageTable, TooltipError, TooltipErrorKind, count_constraint_line,
+            parse_count_constraints, parse_roman_numeral, parse_tooltip_dump,
+        },
+        utils::enchants::pickaxe,
+    };
+
+    #[test]
+    fn test_roman_numerals() {
+        for (s, n) in [
+            ("I", 1),
+            ("II", 2),
+            ("III", 3),
+            ("IV", 4),
+            ("V", 5),
+            ("IX", 9),
+            ("X", 10),
+            ("XIV", 14),
+            ("XL", 40),
+            ("XCIX", 99),
+            ("MCMXCIV", 1994),
+            ("MMMCMXCIX", 3999),
+        ] {
+            assert_eq!(parse_roman_numeral(s), Some(n), "{}", s);
+        }
+        for s in [
+            "", "IIII", "VV", "IC", "IIV", "iii", "3", "XIIII", "MMMM", "VX",
+        ] {
+            assert_eq!(parse_roman_numeral(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_tooltip_dump() {
+        let dump = "
+            # top row
+            slot 1,1: Iron Ingot x4
+            slot 1,2: minecraft:gold_ingot x2
+            SLOT 2 , 5 : iron sword durability 123 / 250
+            slot 3,9: Leather Tunic
+            slot 3,8: TNT x64
+        ";
+        let chest = parse_tooltip_dump(dump, &item_registry(), &LanguageTable::en_us()).unwrap();
+
+        assert_eq!(chest.rows[0].items[0], Some(ItemStack::of(IRON_INGOT, 4)));
+        assert_eq!(chest.rows[0].items[1], Some(ItemStack::of(GOLD_INGOT, 2)));
+        assert_eq!(
+            chest.rows[1].items[4],
+            Some(ItemStack::with_properties(
+                IRON_SWORD,
+                1,
+                1,
+                &[ItemProperty::Damage {
+                    max_durability: 250,
+                    damage: 127
+                }]
+            ))
+        );
+        assert_eq!(
+            chest.rows[2].items[8],
+            Some(ItemStack::new(LEATHER_CHESTPLATE, 1, 1))
+        );
+        assert_eq!(chest.rows[2].items[7], Some(ItemStack::of(TNT, 64)));
+        assert_eq!(chest.rows[0].items[2], None);
+    }
+
+    #[test]
+    fn test_parse_tooltip_enchantments() {
+        let registry = ItemRegistry::new().register(
+            "diamond_pickaxe",
+            RegisteredItem::new(1)
+                .stack_size(1)
+                .durability(1561)
+                .enchantment("efficiency", pickaxe::EFFICIENCY)
+                .enchantment("mending", pickaxe::MENDING)
+                .enchantment("vanishing_curse", pickaxe::VANISHING_CURSE),
+        );
+        let language = LanguageTable::en_us();
+
+        let chest = parse_tooltip_dump(
+            "slot 1,1: Diamond Pickaxe durability 1400/1561 [Efficiency III]\n\
+             slot 1,2: Diamond Pickaxe [Mending]\n\
+             slot 1,3: Diamond Pickaxe [Curse of Vanishing, efficiency 5]",
+            &registry,
+            &language,
+        )
+        .unwrap();
+
+        assert_eq!(
+            chest.rows[0].items[0].as_ref().unwrap().properties,
+            vec![
+                ItemProperty::Damage {
+                    max_durability: 1561,
+                    damage: 161
+                },
+                ItemProperty::Enchantment {
+                    enchantment: pickaxe::EFFICIENCY.0,
+                    level: 3
+                }
+            ]
+        );
+        assert_eq!(
+            chest.rows[0].items[1].as_ref().unwrap().properties,
+            vec![ItemProperty::Enchantment {
+                enchantment: pickaxe::MENDING.0,
+                level: 1
+            }]
+        );
+        assert_eq!(
+            chest.rows[0].items[2].as_ref().unwrap().properties,
+            vec![
+                ItemProperty::Enchantment {
+                    enchantment: pickaxe::VANISHING_CURSE.0,
+                    level: 1
+                },
+                ItemProperty::Enchantment {
+                    enchantment: pickaxe::EFFICIENCY.0,
+                    level: 5
+                }
+            ]
+        );
+
+        let errors = parse_tooltip_dump(
+            "slot 1,1: Diamond Pickaxe [Efficiency VI]\n\
+             slot 1,2: Diamond Pickaxe [Efficiency IIII]\n\
+             slot 1,3: Diamond Pickaxe [Sharpness II]\n\
+             slot 1,4: Diamond Pickaxe [Speed II]",
+            &registry,
+            &language,
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors.iter().map(|e| e.kind.clone()).collect::<Vec<_>>(),
+            vec![
+                TooltipErrorKind::EnchantmentLevelOutOfRange {
+                    enchantment: "Efficiency".to_string(),
+                    level: 6,
+                    min_level: 1,
+                    max_level: 5
+                },
+                TooltipErrorKind::InvalidEnchantmentLevel("IIII".to_string()),
+                TooltipErrorKind::NotApplicableEnchantment {
+                    item: "Diamond Pickaxe".to_string(),
+                    enchantment: "Sharpness".to_string()
+                },
+                TooltipErrorKind::UnknownEnchantment("Speed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tooltip_localized() {
+        let french = r#"{
+            "item.minecraft.iron_ingot": "Lingot de fer",
+            "item.minecraft.iron_sword": "Épée en fer",
+            "block.minecraft.tnt": "TNT",
+            "item.minecraft.potion.effect.water": "Fiole d'eau",
+            "enchantment.minecraft.unbreaking": "Solidité",
+            "gui.done": "Terminé"
+        }"#;
+        let language = LanguageTable::from_lang_json(french.as_bytes()).unwrap();
+        assert_eq!(
+            language.item_named("lingot de FER"),
+            Some("minecraft:iron_ingot")
+        );
+        assert_eq!(
+            language.enchantment_named("Solidité"),
+            Some("minecraft:unbreaking")
+        );
+        assert_eq!(language.item_named("Fiole d'eau"), None);
+        assert_eq!(language.item_named("Terminé"), None);
+
+        let chest = parse_tooltip_dump(
+            "slot 2,3: Lingot de fer x7\nslot 2,4: Épée en fer durability 250/250",
+            &item_registry(),
+            &language,
+        )
+        .unwrap();
+        assert_eq!(chest.rows[1].items[2], Some(ItemStack::of(IRON_INGOT, 7)));
+        assert_eq!(
+            chest.rows[1].items[3].as_ref().map(|s| s.item),
+            Some(IRON_SWORD)
+        );
+
+        // the english names are not part of the french table
+        let errors =
+            parse_tooltip_dump("slot 1,1: Iron Ingot", &item_registry(), &language).unwrap_err();
+        assert_eq!(
+            errors[0].kind,
+            TooltipErrorKind::UnknownItem("Iron Ingot".to_string())
+        );
+
+        assert!(LanguageTable::from_lang_json("[]".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_tooltip_malformed() {
+        let dump = "slot 1,1: Iron Ingot x4
+iron ingot x4
+slot 1,2 Iron Ingot
+slot a,b: Iron Ingot
+slot 4,1: Iron Ingot
+slot 1,1: Gold Ingot
+slot 1,3:
+slot 1,4: Netherite Ingot
+slot 1,5: Iron Ingot x65
+slot 1,6: Iron Ingot durability 3/2
+slot 1,7: Iron Ingot durability 1/250
+slot 1,8: Iron Sword durability 100/131
+slot 1,9: Iron Sword [Sharpness I
+slot 2,1: Iron Ingot x2 please
+slot 2,2: TNT x0
+slot 2,3: Diamond xx3
+slot 2,4: Iron Sword [Unbreaking III] extra
+slot 2,5: Iron Sword x2";
+        let errors =
+            parse_tooltip_dump(dump, &item_registry(), &LanguageTable::en_us()).unwrap_err();
+
+        let expected = vec![
+            (2, TooltipErrorKind::MissingSlot),
+            (3, TooltipErrorKind::MissingColon),
+            (4, TooltipErrorKind::InvalidSlot("a,b".to_string())),
+            (5, TooltipErrorKind::SlotOutOfRange { row: 4, column: 1 }),
+            (
+                6,
+                TooltipErrorKind::DuplicateSlot {
+                    row: 1,
+                    column: 1,
+                    first_line: 1,
+                },
+            ),
+            (7, TooltipErrorKind::MissingItemName),
+            (
+                8,
+                TooltipErrorKind::UnknownItem("Netherite Ingot".to_string()),
+            ),
+            (
+                9,
+                TooltipErrorKind::CountOutOfRange {
+                    count: 65,
+                    max_count: 64,
+                },
+            ),
+            (10, TooltipErrorKind::InvalidDurability("3/2".to_string())),
+            (11, TooltipErrorKind::NoDurability("Iron Ingot".to_string())),
+            (
+                12,
+                TooltipErrorKind::WrongMaxDurability {
+                    item: "Iron Sword".to_string(),
+                    expected: 250,
+                    found: 131,
+                },
+            ),
+            (13, TooltipErrorKind::UnclosedBracket),
+            (14, TooltipErrorKind::UnexpectedText("please".to_string())),
+            (
+                15,
+                TooltipErrorKind::CountOutOfRange {
+                    count: 0,
+                    max_count: 64,
+                },
+            ),
+            (16, TooltipErrorKind::UnknownItem("Diamond xx3".to_string())),
+            (17, TooltipErrorKind::UnexpectedText("extra".to_string())),
+            (
+                17,
+                TooltipErrorKind::NotApplicableEnchantment {
+                    item: "Iron Sword".to_string(),
+                    enchantment: "Unbreaking".to_string(),
+                },
+            ),
+            (
+                18,
+                TooltipErrorKind::CountOutOfRange {
+                    count: 2,
+                    max_count: 1,
+                },
+            ),
+        ];
+        assert_eq!(
+            errors,
+            expected
+                .into_iter()
+                .map(|(line, kind)| TooltipError { line, kind })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2: expected a line like \"slot 1,1: Iron Ingot x4\""
+        );
+    }
+
+    #[test]
+    fn test_chest_dump() {
+        let registry = item_registry().register(
+            "diamond_pickaxe",
+            RegisteredItem::new(20)
+                .stack_size(1)
+                .durability(1561)
+                .enchantment("efficiency", pickaxe::EFFICIENCY)
+                .enchantment("mending", pickaxe::MENDING),
+        );
+        let language = LanguageTable::en_us();
+
+        let mut chest = parse_tooltip_dump(
+            "slot 1,1: Iron Ingot x4\n\
+             slot 1,2: Iron Ingot\n\
+             slot 2,5: Iron Sword durability 123/250\n\
+             slot 3,9: Diamond Pickaxe durability 1400/1561 [Efficiency III, Mending]",
+            &registry,
+            &language,
+        )
+        .unwrap();
+
+        let dump = ChestDump::new(&chest, &registry, &language);
+        assert_eq!(
+            dump.to_string(),
+            "slot 1,1: Iron Ingot x4
+slot 1,2: Iron Ingot
+slot 2,5: Iron Sword durability 123/250
+slot 3,9: Diamond Pickaxe durability 1400/1561 [Efficiency III, Mending]
+# 4 stacks, 7 items: Iron Ingot x5, Iron Sword x1, Diamond Pickaxe x1
+"
+        );
+        assert_eq!(
+            dump.with_give_commands(true)
+                .to_string()
+                .lines()
+                .skip(5)
+                .collect::<Vec<_>>(),
+            vec![
+                "# /give @s minecraft:iron_ingot 4",
+                "# /give @s minecraft:iron_ingot 1",
+                "# /give @s minecraft:iron_sword{Damage:127} 1",
+                "# /give @s minecraft:diamond_pickaxe{Damage:161,Enchantments:[{id:\"minecraft:efficiency\",lvl:3s},{id:\"minecraft:mending\",lvl:1s}]} 1",
+            ]
+        );
+
+        // names missing from the language table fall back to the resource names, which are read back as well
+        let dump = ChestDump::new(&chest, &registry, &LanguageTable::new()).to_string();
+        assert!(dump.starts_with("slot 1,1: minecraft:iron_ingot x4\n"));
+        assert_eq!(
+            parse_tooltip_dump(&dump, &registry, &LanguageTable::new()),
+            Ok(chest.clone())
+        );
+
+        chest = SingleChest::new();
+        assert_eq!(
+            ChestDump::new(&chest, &registry, &language).to_string(),
+            "# 0 stacks, 0 items\n"
+        );
+    }
+
+    #[test]
+    fn test_chest_dump_round_trip() {
+        let chest = buried_treasure_contents();
+        let (registry, language) = (item_registry(), LanguageTable::en_us());
+
+        let dump = ChestDump::new(&chest, &registry, &language)
+            .with_give_commands(true)
+            .to_string();
+        assert_eq!(parse_tooltip_dump(&dump, &registry, &language), Ok(chest));
+    }
+
+    #[test]
+    fn test_count_constraints() {
+        use crate::{
+            features::buried_treasure::items::{COOKED_COD, HEART_OF_THE_SEA},
+            loot_table::CountBound,
+        };
+
+        let (registry, language) = (item_registry(), LanguageTable::en_us());
+        let text = "# remembered after closing the chest\nIron Ingot 8+\n\nheart of the sea 1\nminecraft:cooked_cod <=6\nGold Ingot 2-4\n";
+        let bounds = parse_count_constraints(text, &registry, &language).unwrap();
+        assert_eq!(
+            bounds,
+            vec![
+                (IRON_INGOT, CountBound::AtLeast(8)),
+                (HEART_OF_THE_SEA, CountBound::Exactly(1)),
+                (COOKED_COD, CountBound::AtMost(6)),
+                (GOLD_INGOT, CountBound::Between(2, 4)),
+            ]
+        );
+        let lines = bounds
+            .iter()
+            .map(|&(item, bound)| count_constraint_line(item, bound, &registry, &language))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "Iron Ingot 8+",
+                "Heart of the Sea 1",
+                "Cooked Cod <=6",
+                "Gold Ingot 2-4"
+            ]
+        );
+        assert_eq!(
+            parse_count_constraints(&lines.join("\n"), &registry, &language),
+            Ok(bounds)
+        );
+
+        assert_eq!(
+            parse_count_constraints("Diamond\nIron Ingot lots\nStick 2", &registry, &language),
+            Err(vec![
+                TooltipError {
+                    line: 1,
+                    kind: TooltipErrorKind::MissingCount
+                },
+                TooltipError {
+                    line: 2,
+                    kind: TooltipErrorKind::InvalidCount("lots".to_string())
+                },
+                TooltipError {
+                    line: 3,
+                    kind: TooltipErrorKind::UnknownItem("Stick".to_string())
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Records the RNG calls made while a chest is generated. When a new loot table doesn't reproduce the chests seen in
+//! game, comparing its trace with the trace of a known-good table (or of another seed) shows where the RNG streams
+//! diverge.
+
+use std::fmt::{Display, Write};
+
+use crate::{
+    loot_table::{Inventory, ItemRegistry, LootRandom, LootRng, LootTable, SingleChest},
+    random::{JavaRandom, McRandom},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RandomCall {
+    BoundedInt { bound: i32, value: i32 },
+    Float(f32),
+    Bool(bool),
+    Long(i64),
+}
+
+/// Named like the java.util.Random methods, e.g. `nextInt(4) = 2`
+impl Display for RandomCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BoundedInt { bound, value } => write!(f, "nextInt({}) = {}", bound, value),
+            Self::Float(value) => write!(f, "nextFloat() = {}", value),
+            Self::Bool(value) => write!(f, "nextBoolean() = {}", value),
+            Self::Long(value) => write!(f, "nextLong() = {}", value),
+        }
+    }
+}
+
+/// A [`JavaRandom`] remembering every call, in order
+#[derive(Debug, Clone)]
+pub struct TracingJavaRandom {
+    rng: JavaRandom,
+    pub calls: Vec<RandomCall>,
+}
+
+impl TracingJavaRandom {
+    #[inline]
+    pub const fn new(seed: i64) -> Self {
+        Self {
+            rng: JavaRandom::new(seed),
+            calls: Vec::new(),
+        }
+    }
+}
+
+impl McRandom for TracingJavaRandom {
+    fn next_bounded_int(&mut self, bound: i32) -> i32 {
+        let value = self.rng.next_bounded_int(bound);
+        self.calls.push(RandomCall::BoundedInt { bound, value });
+        value
+    }
+
+    fn next_float(&mut self) -> f32 {
+        let value = self.rng.next_float();
+        self.calls.push(RandomCall::Float(value));
+        value
+    }
+
+    fn next_bool(&mut self) -> bool {
+        let value = self.rng.next_bool();
+        self.calls.push(RandomCall::Bool(value));
+        value
+    }
+
+    fn next_long(&mut self) -> i64 {
+        let value = self.rng.next_long();
+        self.calls.push(RandomCall::Long(value));
+        value
+    }
+}
+
+impl LootRandom for TracingJavaRandom {
+    fn as_loot_rng(&mut self) -> LootRng<'_> {
+        LootRng::Traced(self)
+    }
+}
+
+/// A generated chest with the RNG calls that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChestTrace {
+    pub calls: Vec<RandomCall>,
+    pub chest: SingleChest,
+}
+
+impl ChestTrace {
+    /// Generates the chest of a loot table seed, the same way as [`LootTable::generate_in_inventory`]
+    pub fn generate(loot_table: &LootTable, seed: i64, luck: f32) -> Self {
+        let mut rng = TracingJavaRandom::new(seed);
+        let mut chest = SingleChest::new();
+        loot_table.generate_in_inventory(&mut chest, &mut rng, luck);
+        Self {
+            calls: rng.calls,
+            chest,
+        }
+    }
+
+    /// Index of the first call that differs from `other`, or the length of the shorter trace if one continues the
+    /// other. None if both made the same calls.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        match self
+            .calls
+            .iter()
+            .zip(other.calls.iter())
+            .position(|(a, b)| a != b)
+        {
+            Some(i) => Some(i),
+            None if self.calls.len() != other.calls.len() => {
+                Some(self.calls.len().min(other.calls.len()))
+            }
+            None => None,
+        }
+    }
+
+    /// The call log, marking the first divergence from `other` with `>>` and the other trace's call, followed by the
+    /// slot layout. Items are named by `registry` when given, by id otherwise.
+    pub fn render(&self, registry: Option<&ItemRegistry>, other: Option<&Self>) -> String {
+        let divergence = other.and_then(|other| self.first_divergence(other).map(|i| (i, other)));
+        let mut out = String::new();
+        for (i, call) in self.calls.iter().enumerate() {
+            match divergence {
+                Some((at, other)) if at == i => {
+                    let ours = call.to_string();
+                    let theirs = other
+                        .calls
+                        .get(i)
+                        .map_or_else(|| "no call".to_string(), RandomCall::to_string);
+                    let _ = writeln!(out, ">> {:4}  {:<24} other: {}", i, ours, theirs);
+                }
+                _ => {
+                    let _ = writeln!(out, "   {:4}  {}", i, call);
+                }
+            }
+        }
+        match divergence {
+            Some((at, other)) if at == self.calls.len() => {
+                let _ = writeln!(
+                    out,
+                    ">> {:4}  {:<24} other: {}",
+                    at, "no call", other.calls[at]
+                );
+            }
+            Some((at, _)) => {
+                let _ = writeln!(out, "first divergence at call {}", at);
+            }
+            None if other.is_some() => {
+                let _ = writeln!(out, "same calls");
+            }
+            None => {}
+        }
+        out.push_str(&self.render_chest(registry));
+        out
+    }
+
+    /// One line per row, `-` for the empty slots
+    pub fn render_chest(&self, registry: Option<&ItemRegistry>) -> String {
+        let mut out = String::new();
+        for row in 0..3 {
+            let cells = (0..9)
+                .map(|column| match self.chest.get_item(row * 9 + column) {
+                    Some(stack) => {
+                        let name = registry.and_then(|r| r.name_of(stack.item)).map_or_else(
+                            || format!("#{}", stack.item),
+                            |n| n.trim_start_matches("minecraft:").to_string(),
+                        );
+                        let marker = if stack.properties.is_empty() { "" } else { "*" };
+                        format!("{} x{}{}", name, stack.count, marker)
+                    }
+                    None => "-".to_string(),
+                })
+                .collect::<Vec<_>>();
+            let _ = writeln!(out, "row {}: {}", row + 1, cells.join(" | "));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        features::buried_treasure::{
+            get_buried_treasure, get_buried_treasure_loot_table_seed, get_loot_table,
+            item_registry,
+            items::{HEART_OF_THE_SEA, IRON_INGOT},
+        },
+        loot_table::{
+            ItemLootPoolEntryBuilder, LootPoolBuilder, LootTableBuilder, SetCountFunction,
+        },
+        math::Math,
+        random::JavaRandom,
+        self_test::{BURIED_TREASURE_POS, WORLD_SEED},
+        trace::{ChestTrace, RandomCall},
+    };
+
+    /// The trace of the buried treasure of the fixture world, whose chest matches the one found in game
+    #[test]
+    fn test_buried_treasure_trace() {
+        let chunk = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+        let seed = get_buried_treasure_loot_table_seed(WORLD_SEED, chunk);
+        let trace = ChestTrace::generate(&get_loot_table(), seed, 0.0);
+        assert_eq!(trace.chest, get_buried_treasure(WORLD_SEED, chunk, 0.0));
+
+        // the heart of the sea pool has a single entry and a constant roll, the first call rolls the 5 to 8 ingots
+        let Some(RandomCall::BoundedInt { bound: 4, value }) = trace.calls.first().copied() else {
+            panic!("{:?}", trace.calls.first());
+        };
+        let ingot_rolls = 5 + value as usize;
+        // then the weighted pick and the count of each roll
+        for roll in 0..ingot_rolls {
+            assert!(matches!(
+                &trace.calls[1 + 2 * roll..3 + 2 * roll],
+                [
+                    RandomCall::BoundedInt { bound: 35, .. },
+                    RandomCall::BoundedInt { bound: 4 | 2, .. }
+                ]
+            ));
+        }
+
+        // the calls are the JavaRandom stream
+        let mut rng = JavaRandom::new(seed);
+        for call in trace.calls.iter() {
+            let replayed = match *call {
+                RandomCall::BoundedInt { bound, .. } => RandomCall::BoundedInt {
+                    bound,
+                    value: rng.next_bounded_int(bound),
+                },
+                RandomCall::Float(_) => RandomCall::Float(rng.next_float()),
+                RandomCall::Bool(_) => RandomCall::Bool(rng.next_bool()),
+                RandomCall::Long(_) => RandomCall::Long(rng.next_long()),
+            };
+            assert_eq!(*call, replayed);
+        }
+
+        assert_eq!(trace.first_divergence(&trace.clone()), None);
+        let rendered = trace.render(Some(&item_registry()), Some(&trace));
+        assert!(rendered.starts_with(&format!("      0  nextInt(4) = {}\n", value)));
+        assert!(rendered.contains("heart_of_the_sea x1"));
+        assert!(rendered.contains("same calls\nrow 1: "));
+    }
+
+    #[test]
+    fn test_divergence() {
+        let table = |iron_max| {
+            LootTableBuilder::new()
+                .pool(
+                    LootPoolBuilder::new()
+                        .rolls_const(1)
+                        .entry_item(ItemLootPoolEntryBuilder::new(HEART_OF_THE_SEA).build())
+                        .build(),
+                )
+                .pool(
+                    LootPoolBuilder::new()
+                        .rolls_const(1)
+                        .entry_item(
+                            ItemLootPoolEntryBuilder::new(IRON_INGOT)
+                                .function(SetCountFunction::uniform(1, iron_max).as_function())
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build()
+        };
+        let good = ChestTrace::generate(&table(4), 42, 0.0);
+        let bad = ChestTrace::generate(&table(5), 42, 0.0);
+        // the count of the iron ingots is the first call
+        assert_eq!(good.first_divergence(&bad), Some(0));
+        assert!(matches!(
+            good.calls[0],
+            RandomCall::BoundedInt { bound: 4, .. }
+        ));
+        let rendered = good.render(None, Some(&bad));
+        assert!(rendered.starts_with(">>    0  nextInt(4) = "));
+        assert!(rendered.contains("other: nextInt(5) = "));
+        assert!(rendered.contains("first divergence at call 0"));
+        assert!(rendered.contains(&format!("#{} x1", HEART_OF_THE_SEA)));
+
+        let shorter = ChestTrace {
+            calls: good.calls[..2].to_vec(),
+            chest: good.chest.clone(),
+        };
+        assert_eq!(good.first_divergence(&shorter), Some(2));
+        assert!(
+            shorter
+                .render(None, Some(&good))
+                .contains(">>    2  no call")
+        );
+    }
+}
@@ -16,6 +16,16 @@ pub const SHOVEL: [(i32, i32, i32); 6] = [
     shovel::VANISHING_CURSE,
 ];
 
+/// Resource names of [`SHOVEL`], in the same order
+pub const SHOVEL_NAMES: [&str; 6] = [
+    "efficiency",
+    "silk_touch",
+    "unbreaking",
+    "fortune",
+    "mending",
+    "vanishing_curse",
+];
+
 pub mod pickaxe {
     pub const EFFICIENCY: (i32, i32, i32) = (1, 1, 5);
     pub const SILK_TOUCH: (i32, i32, i32) = (2, 1, 1);
@@ -34,6 +44,16 @@ pub const PICKAXE: [(i32, i32, i32); 6] = [
     pickaxe::VANISHING_CURSE,
 ];
 
+/// Resource names of [`PICKAXE`], in the same order
+pub const PICKAXE_NAMES: [&str; 6] = [
+    "efficiency",
+    "silk_touch",
+    "unbreaking",
+    "fortune",
+    "mending",
+    "vanishing_curse",
+];
+
 pub mod axe {
     pub const EFFICIENCY: (i32, i32, i32) = (1, 1, 5);
     pub const SILK_TOUCH: (i32, i32, i32) = (2, 1, 1);
@@ -59,6 +79,19 @@ pub const AXE: [(i32, i32, i32); 9] = [
     axe::VANISHING_CURSE,
 ];
 
+/// Resource names of [`AXE`], in the same order
+pub const AXE_NAMES: [&str; 9] = [
+    "sharpness",
+    "smite",
+    "bane_of_arthropods",
+    "efficiency",
+    "silk_touch",
+    "unbreaking",
+    "fortune",
+    "mending",
+    "vanishing_curse",
+];
+
 pub mod crossbow {
     pub const UNBREAKING: (i32, i32, i32) = (1, 1, 3);
     pub const MULTISHOT: (i32, i32, i32) = (2, 1, 1);
@@ -77,6 +110,16 @@ pub const CROSSBOW: [(i32, i32, i32); 6] = [
     crossbow::VANISHING_CURSE,
 ];
 
+/// Resource names of [`CROSSBOW`], in the same order
+pub const CROSSBOW_NAMES: [&str; 6] = [
+    "unbreaking",
+    "multishot",
+    "quick_charge",
+    "piercing",
+    "mending",
+    "vanishing_curse",
+];
+
 pub mod sword {
     pub const SHARPNESS: (i32, i32, i32) = (1, 1, 5);
     pub const SMITE: (i32, i32, i32) = (2, 1, 5);
@@ -103,6 +146,20 @@ pub const SWORD: [(i32, i32, i32); 10] = [
     sword::VANISHING_CURSE,
 ];
 
+/// Resource names of [`SWORD`], in the same order
+pub const SWORD_NAMES: [&str; 10] = [
+    "sharpness",
+    "smite",
+    "bane_of_arthropods",
+    "knockback",
+    "fire_aspect",
+    "looting",
+    "sweeping",
+    "unbreaking",
+    "mending",
+    "vanishing_curse",
+];
+
 pub mod armor {
     pub const PROTECTION: (i32, i32, i32) = (1, 1, 4);
     pub const FIRE_PROTECTION: (i32, i32, i32) = (2, 1, 4);
@@ -134,6 +191,21 @@ pub const HELMET: [(i32, i32, i32); 11] = [
     armor::VANISHING_CURSE,
 ];
 
+/// Resource names of [`HELMET`], in the same order
+pub const HELMET_NAMES: [&str; 11] = [
+    "protection",
+    "fire_protection",
+    "blast_protection",
+    "projectile_protection",
+    "respiration",
+    "aqua_affinity",
+    "thorns",
+    "binding_curse",
+    "unbreaking",
+    "mending",
+    "vanishing_curse",
+];
+
 pub const CHESTPLATE: [(i32, i32, i32); 9] = [
     armor::PROTECTION,
     armor::FIRE_PROTECTION,
@@ -146,6 +218,19 @@ pub const CHESTPLATE: [(i32, i32, i32); 9] = [
     armor::VANISHING_CURSE,
 ];
 
+/// Resource names of [`CHESTPLATE`], in the same order
+pub const CHESTPLATE_NAMES: [&str; 9] = [
+    "protection",
+    "fire_protection",
+    "blast_protection",
+    "projectile_protection",
+    "thorns",
+    "binding_curse",
+    "unbreaking",
+    "mending",
+    "vanishing_curse",
+];
+
 pub const LEGGINGS: [(i32, i32, i32); 9] = [
     armor::PROTECTION,
     armor::FIRE_PROTECTION,
@@ -158,6 +243,19 @@ pub const LEGGINGS: [(i32, i32, i32); 9] = [
     armor::VANISHING_CURSE,
 ];
 
+/// Resource names of [`LEGGINGS`], in the same order
+pub const LEGGINGS_NAMES: [&str; 9] = [
+    "protection",
+    "fire_protection",
+    "blast_protection",
+    "projectile_protection",
+    "thorns",
+    "binding_curse",
+    "unbreaking",
+    "mending",
+    "vanishing_curse",
+];
+
 pub const BOOTS: [(i32, i32, i32); 12] = [
     armor::PROTECTION,
     armor::FIRE_PROTECTION,
@@ -172,3 +270,142 @@ pub const BOOTS: [(i32, i32, i32); 12] = [
     armor::MENDING,
     armor::VANISHING_CURSE,
 ];
+
+/// Resource names of [`BOOTS`], in the same order
+pub const BOOTS_NAMES: [&str; 12] = [
+    "protection",
+    "fire_protection",
+    "feather_falling",
+    "blast_protection",
+    "projectile_protection",
+    "thorns",
+    "depth_strider",
+    "frost_walker",
+    "binding_curse",
+    "unbreaking",
+    "mending",
+    "vanishing_curse",
+];
+
+pub mod book {
+    pub const PROTECTION: (i32, i32, i32) = (1, 1, 4);
+    pub const FIRE_PROTECTION: (i32, i32, i32) = (2, 1, 4);
+    pub const FEATHER_FALLING: (i32, i32, i32) = (3, 1, 4);
+    pub const BLAST_PROTECTION: (i32, i32, i32) = (4, 1, 4);
+    pub const PROJECTILE_PROTECTION: (i32, i32, i32) = (5, 1, 4);
+    pub const RESPIRATION: (i32, i32, i32) = (6, 1, 3);
+    pub const AQUA_AFFINITY: (i32, i32, i32) = (7, 1, 1);
+    pub const THORNS: (i32, i32, i32) = (8, 1, 3);
+    pub const DEPTH_STRIDER: (i32, i32, i32) = (9, 1, 3);
+    pub const FROST_WALKER: (i32, i32, i32) = (10, 1, 2);
+    pub const BINDING_CURSE: (i32, i32, i32) = (11, 1, 1);
+    pub const SHARPNESS: (i32, i32, i32) = (12, 1, 5);
+    pub const SMITE: (i32, i32, i32) = (13, 1, 5);
+    pub const BANE_OF_ARTHROPODS: (i32, i32, i32) = (14, 1, 5);
+    pub const KNOCKBACK: (i32, i32, i32) = (15, 1, 2);
+    pub const FIRE_ASPECT: (i32, i32, i32) = (16, 1, 2);
+    pub const LOOTING: (i32, i32, i32) = (17, 1, 3);
+    pub const SWEEPING_EDGE: (i32, i32, i32) = (18, 1, 3);
+    pub const EFFICIENCY: (i32, i32, i32) = (19, 1, 5);
+    pub const SILK_TOUCH: (i32, i32, i32) = (20, 1, 1);
+    pub const UNBREAKING: (i32, i32, i32) = (21, 1, 3);
+    pub const FORTUNE: (i32, i32, i32) = (22, 1, 3);
+    pub const POWER: (i32, i32, i32) = (23, 1, 5);
+    pub const PUNCH: (i32, i32, i32) = (24, 1, 2);
+    pub const FLAME: (i32, i32, i32) = (25, 1, 1);
+    pub const INFINITY: (i32, i32, i32) = (26, 1, 1);
+    pub const LUCK_OF_THE_SEA: (i32, i32, i32) = (27, 1, 3);
+    pub const LURE: (i32, i32, i32) = (28, 1, 3);
+    pub const LOYALTY: (i32, i32, i32) = (29, 1, 3);
+    pub const IMPALING: (i32, i32, i32) = (30, 1, 5);
+    pub const RIPTIDE: (i32, i32, i32) = (31, 1, 3);
+    pub const CHANNELING: (i32, i32, i32) = (32, 1, 1);
+    pub const MULTISHOT: (i32, i32, i32) = (33, 1, 1);
+    pub const QUICK_CHARGE: (i32, i32, i32) = (34, 1, 3);
+    pub const PIERCING: (i32, i32, i32) = (35, 1, 4);
+    pub const MENDING: (i32, i32, i32) = (36, 1, 1);
+    pub const VANISHING_CURSE: (i32, i32, i32) = (37, 1, 1);
+}
+
+/// What `enchant_randomly` without a list picks from for a book: every enchantment, in the game's order, except soul
+/// speed which can't be found in loot
+pub const BOOK: [(i32, i32, i32); 37] = [
+    book::PROTECTION,
+    book::FIRE_PROTECTION,
+    book::FEATHER_FALLING,
+    book::BLAST_PROTECTION,
+    book::PROJECTILE_PROTECTION,
+    book::RESPIRATION,
+    book::AQUA_AFFINITY,
+    book::THORNS,
+    book::DEPTH_STRIDER,
+    book::FROST_WALKER,
+    book::BINDING_CURSE,
+    book::SHARPNESS,
+    book::SMITE,
+    book::BANE_OF_ARTHROPODS,
+    book::KNOCKBACK,
+    book::FIRE_ASPECT,
+    book::LOOTING,
+    book::SWEEPING_EDGE,
+    book::EFFICIENCY,
+    book::SILK_TOUCH,
+    book::UNBREAKING,
+    book::FORTUNE,
+    book::POWER,
+    book::PUNCH,
+    book::FLAME,
+    book::INFINITY,
+    book::LUCK_OF_THE_SEA,
+    book::LURE,
+    book::LOYALTY,
+    book::IMPALING,
+    book::RIPTIDE,
+    book::CHANNELING,
+    book::MULTISHOT,
+    book::QUICK_CHARGE,
+    book::PIERCING,
+    book::MENDING,
+    book::VANISHING_CURSE,
+];
+
+/// Resource names of [`BOOK`], in the same order
+pub const BOOK_NAMES: [&str; 37] = [
+    "protection",
+    "fire_protection",
+    "feather_falling",
+    "blast_protection",
+    "projectile_protection",
+    "respiration",
+    "aqua_affinity",
+    "thorns",
+    "depth_strider",
+    "frost_walker",
+    "binding_curse",
+    "sharpness",
+    "smite",
+    "bane_of_arthropods",
+    "knockback",
+    "fire_aspect",
+    "looting",
+    "sweeping",
+    "efficiency",
+    "silk_touch",
+    "unbreaking",
+    "fortune",
+    "power",
+    "punch",
+    "flame",
+    "infinity",
+    "luck_of_the_sea",
+    "lure",
+    "loyalty",
+    "impaling",
+    "riptide",
+    "channeling",
+    "multishot",
+    "quick_charge",
+    "piercing",
+    "mending",
+    "vanishing_curse",
+];
@@ -0,0 +1,135 @@
+//! Durations, rates and counts the way the TUI, the command line and the search plan print them. Scripts parse the
+//! search plan of `--dry-run`, keep the output of these stable.
+
+use std::time::Duration;
+
+/// Printed by [`format_eta`] when the remaining time is unknown
+pub const UNKNOWN_ETA: &str = "—";
+
+/// Remaining times longer than this are printed as `>99h` by [`format_eta`]
+pub const MAX_ETA: Duration = Duration::from_secs(99 * 3600);
+
+const RATE_PREFIXES: [&str; 4] = ["k", "M", "G", "T"];
+
+/// `0s`, `250ms`, `12.3s`, `4m 12s` or `1h 03m`: two units at most, truncated rather than rounded so a duration never
+/// reads as the next unit
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if duration.is_zero() {
+        "0s".to_string()
+    } else if secs == 0 {
+        match duration.as_millis() {
+            0 => "<1ms".to_string(),
+            millis => format!("{}ms", millis),
+        }
+    } else if secs < 60 {
+        format!("{}.{}s", secs, duration.subsec_millis() / 100)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Like [`format_duration`], [`UNKNOWN_ETA`] when unknown and `>99h` past [`MAX_ETA`]
+pub fn format_eta(remaining: Option<Duration>) -> String {
+    match remaining {
+        None => UNKNOWN_ETA.to_string(),
+        Some(remaining) if remaining > MAX_ETA => ">99h".to_string(),
+        Some(remaining) => format_duration(remaining),
+    }
+}
+
+/// `950 seeds/s` or `12.3 M seeds/s` with the `unit` "seeds", up to the T prefix. Negative and NaN rates are printed as
+/// 0
+pub fn format_rate(per_second: f64, unit: &str) -> String {
+    if per_second.is_nan() || per_second.round() < 1000.0 {
+        return format!("{:.0} {}/s", per_second.max(0.0), unit);
+    }
+    let mut value = per_second / 1000.0;
+    let mut prefix = 0;
+    // compared once rounded to 1 decimal, so 999.96k reads 1.0 M and not 1000.0 k
+    while (value * 10.0).round() >= 10000.0 && prefix < RATE_PREFIXES.len() - 1 {
+        value /= 1000.0;
+        prefix += 1;
+    }
+    format!("{:.1} {} {}/s", value, RATE_PREFIXES[prefix], unit)
+}
+
+/// `4,294,967,296`: groups of 3 digits separated by commas, whatever the locale
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::utils::format::{
+        MAX_ETA, UNKNOWN_ETA, format_count, format_duration, format_eta, format_rate,
+    };
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_micros(400)), "<1ms");
+        assert_eq!(format_duration(Duration::from_millis(1)), "1ms");
+        assert_eq!(format_duration(Duration::from_millis(999)), "999ms");
+        assert_eq!(format_duration(Duration::from_secs(1)), "1.0s");
+        assert_eq!(format_duration(Duration::from_millis(12_345)), "12.3s");
+        assert_eq!(format_duration(Duration::from_millis(59_999)), "59.9s");
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m 00s");
+        assert_eq!(format_duration(Duration::from_secs(252)), "4m 12s");
+        assert_eq!(format_duration(Duration::from_secs(3599)), "59m 59s");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h 00m");
+        assert_eq!(format_duration(Duration::from_secs(3780)), "1h 03m");
+        assert_eq!(format_duration(Duration::from_secs(200 * 3600)), "200h 00m");
+        assert_eq!(format_duration(Duration::MAX), "5124095576030431h 00m");
+    }
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(None), UNKNOWN_ETA);
+        assert_eq!(format_eta(Some(Duration::ZERO)), "0s");
+        assert_eq!(format_eta(Some(Duration::from_secs(252))), "4m 12s");
+        assert_eq!(format_eta(Some(MAX_ETA)), "99h 00m");
+        assert_eq!(format_eta(Some(MAX_ETA + Duration::from_millis(1))), ">99h");
+        assert_eq!(format_eta(Some(Duration::MAX)), ">99h");
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(0.0, "seeds"), "0 seeds/s");
+        assert_eq!(format_rate(-5.0, "seeds"), "0 seeds/s");
+        assert_eq!(format_rate(f64::NAN, "seeds"), "0 seeds/s");
+        assert_eq!(format_rate(0.4, "seeds"), "0 seeds/s");
+        assert_eq!(format_rate(1.0, "seeds"), "1 seeds/s");
+        assert_eq!(format_rate(999.4, "seeds"), "999 seeds/s");
+        assert_eq!(format_rate(999.6, "seeds"), "1.0 k seeds/s");
+        assert_eq!(format_rate(1000.0, "seeds"), "1.0 k seeds/s");
+        assert_eq!(format_rate(12_345_678.0, "seeds"), "12.3 M seeds/s");
+        assert_eq!(format_rate(999_960.0, "seeds"), "1.0 M seeds/s");
+        assert_eq!(format_rate(2.5e9, "chests"), "2.5 G chests/s");
+        assert_eq!(format_rate(4e15, "seeds"), "4000.0 T seeds/s");
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(1), "1");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1000), "1,000");
+        assert_eq!(format_count(65536), "65,536");
+        assert_eq!(format_count(123_456), "123,456");
+        assert_eq!(format_count(1 << 32), "4,294,967,296");
+        assert_eq!(format_count(u64::MAX), "18,446,744,073,709,551,615");
+    }
+}
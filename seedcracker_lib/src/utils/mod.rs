@@ -1,5 +1,6 @@
 pub mod durability;
 pub mod enchants;
+pub mod format;
 
 #[inline(always)]
 #[cold]
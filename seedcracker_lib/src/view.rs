@@ -0,0 +1,162 @@
+//! Terminal independent logic of the TUI tabs, kept here so it is tested without rendering anything.
+
+use crate::features::end_pillars::PillarMatchResult;
+
+/// Percentage with 2 decimals, padded to a constant width and capped at 100
+pub fn format_progress(mut percent: f64) -> String {
+    if percent > 100.0 {
+        percent = 100.0;
+    }
+    if (percent - 100.0).abs() < f64::EPSILON {
+        "100.0".to_string()
+    } else {
+        format!("{:05.2}", percent)
+    }
+}
+
+/// `done / total` as a percentage, 0 if there is nothing to do
+#[inline]
+pub fn progress_percent(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        done as f64 * 100.0 / total as f64
+    }
+}
+
+/// The pillar seeds that can still match, best match first
+pub fn valid_pillar_seeds(results: &[(i64, PillarMatchResult)]) -> Vec<(i64, PillarMatchResult)> {
+    let mut valid = results
+        .iter()
+        .filter(|(_, result)| !result.is_impossible_match())
+        .copied()
+        .collect::<Vec<_>>();
+    valid.sort_by(|a, b| b.1.compare(&a.1));
+    valid
+}
+
+/// Enabled flag of each row of an evidence list, rows without a flag are enabled
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnabledRows(Vec<bool>);
+
+impl EnabledRows {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// The flags as returned by [`EnabledRows::flags`]
+    #[inline]
+    pub const fn from_flags(flags: Vec<bool>) -> Self {
+        Self(flags)
+    }
+
+    /// The flag of each row toggled so far, the rows after them are enabled
+    #[inline]
+    pub fn flags(&self) -> &[bool] {
+        &self.0
+    }
+
+    /// Number of rows with a flag, never more than the rows toggled so far
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn is_enabled(&self, row: usize) -> bool {
+        self.0.get(row).copied().unwrap_or(true)
+    }
+
+    /// Flips the flag of the row, does nothing if the row is not one of the `row_count` rows
+    pub fn toggle(&mut self, row: usize, row_count: usize) {
+        if row >= row_count {
+            return;
+        }
+        if self.0.len() <= row {
+            self.0.resize(row + 1, true);
+        }
+        self.0[row] = !self.0[row];
+    }
+
+    /// To be called when the row is deleted, so the flags stay aligned with the rows below it
+    #[inline]
+    pub fn remove(&mut self, row: usize) {
+        if row < self.0.len() {
+            self.0.remove(row);
+        }
+    }
+
+    /// The rows that are enabled
+    pub fn filter<T: Clone>(&self, rows: &[T]) -> Vec<T> {
+        rows.iter()
+            .enumerate()
+            .filter(|(row, _)| self.is_enabled(*row))
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        features::end_pillars::PillarMatchResult,
+        view::{EnabledRows, format_progress, progress_percent, valid_pillar_seeds},
+    };
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(0.0), "00.00");
+        assert_eq!(format_progress(5.125), "05.13");
+        assert_eq!(format_progress(42.0), "42.00");
+        assert_eq!(format_progress(100.0), "100.0");
+        assert_eq!(format_progress(250.0), "100.0");
+
+        assert_eq!(progress_percent(1, 4), 25.0);
+        assert_eq!(progress_percent(3, 0), 0.0);
+    }
+
+    #[test]
+    fn test_valid_pillar_seeds() {
+        let results = [
+            (1, PillarMatchResult::PossibleMatch(0.25)),
+            (2, PillarMatchResult::ImpossibleMatch),
+            (3, PillarMatchResult::ExactMatch),
+            (4, PillarMatchResult::PossibleMatch(0.75)),
+        ];
+        let seeds = valid_pillar_seeds(&results)
+            .into_iter()
+            .map(|(seed, _)| seed)
+            .collect::<Vec<_>>();
+        assert_eq!(seeds, vec![3, 4, 1]);
+    }
+
+    #[test]
+    fn test_enabled_rows() {
+        let mut rows = EnabledRows::new();
+        let data = ['a', 'b', 'c'];
+        assert!(rows.is_enabled(7));
+        assert_eq!(rows.filter(&data), vec!['a', 'b', 'c']);
+
+        rows.toggle(1, data.len());
+        assert!(!rows.is_enabled(1));
+        assert_eq!(rows.filter(&data), vec!['a', 'c']);
+
+        // no flag past the last row
+        rows.toggle(3, data.len());
+        assert_eq!(rows.len(), 2);
+
+        // deleting a row above moves the flag up with its row
+        rows.remove(0);
+        assert!(!rows.is_enabled(0));
+        assert_eq!(rows.filter(&['b', 'c']), vec!['c']);
+
+        rows.toggle(0, 2);
+        assert_eq!(rows.filter(&['b', 'c']), vec!['b', 'c']);
+    }
+}
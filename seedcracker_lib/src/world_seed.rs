@@ -0,0 +1,386 @@
+//! World seeds a structure seed completes to, typed as a number or generated by `new Random().nextLong()`.
+
+use arrayvec::ArrayVec;
+
+use crate::{lcg, math::Math};
+
+const STRUCTURE_SEED_MASK: i64 = 0xFFFF_FFFF_FFFF;
+
+/// The world seeds of `structure_seed` that could have been typed as a number in the world creation screen, most
+/// likely first. Every typed number between -2^47 and 2^48 keeps its lower 48 bits, so:
+///
+/// - with bit 47 clear the only completion is the structure seed itself, typed from 0 to 2^47 - 1
+/// - with bit 47 set the seed was either typed as a negative number, the structure seed sign-extended from bit 47,
+///   or as a number from 2^47 to 2^48 - 1, the structure seed itself
+///
+/// Seeds typed as text are `String::hashCode` values, 32 bit ints, so they are covered too. Only the lower 48 bits of
+/// `structure_seed` are used. A generated world seed has the upper bits of a completion by chance once in 65536
+/// seeds, a completion matching the evidence is likely but doesn't prove the seed was typed.
+///
+/// ```
+/// use mcseedcracker::world_seed::typed_numeric_completions;
+///
+/// assert_eq!(typed_numeric_completions(12345).as_slice(), [12345]);
+/// assert_eq!(
+///     typed_numeric_completions(-12345).as_slice(),
+///     [-12345, -12345 & 0xFFFF_FFFF_FFFF]
+/// );
+/// ```
+#[inline]
+pub fn typed_numeric_completions(structure_seed: i64) -> ArrayVec<i64, 2> {
+    let zero_extended = structure_seed & STRUCTURE_SEED_MASK;
+    let sign_extended = (zero_extended << 16) >> 16;
+
+    let mut completions = ArrayVec::new();
+    completions.push(sign_extended);
+    if zero_extended != sign_extended {
+        completions.push(zero_extended);
+    }
+    completions
+}
+
+/// True if the world seed is one of the [`typed_numeric_completions`] of its structure seed
+#[inline]
+pub fn is_typed_numeric(world_seed: i64) -> bool {
+    typed_numeric_completions(world_seed).contains(&world_seed)
+}
+
+/// The world seed generated by a nextLong call whose second nextInt left the RNG in the state with these lower 16
+/// bits, if its lower 48 bits are `structure_seed`
+#[inline(always)]
+pub fn next_long_world_seed(structure_seed: i64, b_state_lo: i64) -> Option<i64> {
+    // Since the random world seed is determined by a single nextLong call,
+    // and nextLong() is implemented as `(a << 32) + b` where a and b are
+    // the results of two nextInt() calls, we can guarantee that b is exactly
+    // the 32 low bits of the world seed, which conveniently are also the
+    // 32 low bits of the structure seed. By looping over the 2^16 possible
+    // remaining bits of the 48 bit RNG state that led to b, we can check
+    // which ones produce a value of a that combines with b to a world seed
+    // whose structure seed matches the one we're looking for.
+    let structure_seed = structure_seed & STRUCTURE_SEED_MASK;
+    let b_state_hi = (structure_seed & 0xFFFF_FFFF) << 16;
+    let b_state = b_state_hi | b_state_lo;
+    let state_a = lcg::JAVA_RANDOM_REV1.next_seed(b_state);
+    let a_shifted = (state_a & 0xFFFF_FFFF_0000) << 16;
+    let reconstructed_b_value = ((b_state >> 16) as i32) as i64; // as i32 as i64 to bit extend negative values
+    let reconstructed_next_long = a_shifted.wrapping_add(reconstructed_b_value);
+    ((reconstructed_next_long & STRUCTURE_SEED_MASK) == structure_seed)
+        .then_some(reconstructed_next_long)
+}
+
+/// The world seeds of `structure_seed` that `new Random().nextLong()` generates, one or two of them for about 89% of
+/// the structure seeds and none for the others. Only the lower 48 bits of `structure_seed` are used.
+///
+/// ```
+/// use mcseedcracker::world_seed::recover_nextlong_world_seeds;
+///
+/// assert!(recover_nextlong_world_seeds(180066252004364).contains(&-7193194438565520372));
+/// ```
+pub fn recover_nextlong_world_seeds(structure_seed: i64) -> Vec<i64> {
+    (0..65536)
+        .filter_map(|b_state_lo| next_long_world_seed(structure_seed, b_state_lo))
+        .collect()
+}
+
+/// True if `new Random().nextLong()` generates `world_seed`. Needs no search over the 65536 states of the first
+/// nextInt: its state times the multiplier has to land within 2^16 of the second state, which leaves at most one
+/// candidate state per time the product wraps around 2^48.
+///
+/// ```
+/// use mcseedcracker::world_seed::is_nextlong_world_seed;
+///
+/// assert!(is_nextlong_world_seed(-7193194438565520372));
+/// assert!(!is_nextlong_world_seed(180066252004364));
+/// ```
+pub fn is_nextlong_world_seed(world_seed: i64) -> bool {
+    const MODULUS: i64 = 1 << 48;
+    let b = world_seed as i32 as i64;
+    let a = world_seed.wrapping_sub(b) >> 32;
+    let a_state_hi = (a & 0xFFFF_FFFF) << 16;
+    let b_state_hi = (b & 0xFFFF_FFFF) << 16;
+    let multiplier = lcg::JAVA_RANDOM.get_multiplier();
+    // the state of b is next_seed(a_state_hi) + a_state_lo * multiplier, with b in its upper 32 bits
+    let gap = (b_state_hi - lcg::JAVA_RANDOM.next_seed(a_state_hi)).rem_euclid(MODULUS);
+    // a_state_lo * multiplier is below 2^51, the window of the product starts below 0 when it wraps around too
+    (-1..8).any(|wraps| {
+        let low = gap + wraps * MODULUS;
+        let a_state_lo = (low.max(0) + multiplier - 1) / multiplier;
+        a_state_lo < 65536 && a_state_lo * multiplier < low + 65536
+    })
+}
+
+/// How the seed of a world was chosen in the world creation screen, to restrict the world seed search to the seeds
+/// one origin can give
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeedOrigin {
+    /// Unknown, every completion of the structure seed is possible
+    #[default]
+    Any,
+    /// Left blank, generated by `new Random().nextLong()`
+    NextLong,
+    /// A text that isn't a number, its `String::hashCode`
+    StringHashcode,
+    /// A number, see [`typed_numeric_completions`]
+    NumericText,
+}
+
+impl SeedOrigin {
+    /// Every origin but [`SeedOrigin::Any`]
+    pub const ORIGINS: [Self; 3] = [Self::NextLong, Self::StringHashcode, Self::NumericText];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::NextLong => "nextlong",
+            Self::StringHashcode => "hashcode",
+            Self::NumericText => "number",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Any]
+            .into_iter()
+            .chain(Self::ORIGINS)
+            .find(|o| o.name() == name)
+    }
+
+    /// True if a seed chosen this way can be `world_seed`
+    #[inline]
+    pub fn allows(self, world_seed: i64) -> bool {
+        match self {
+            Self::Any => true,
+            Self::NextLong => is_nextlong_world_seed(world_seed),
+            Self::StringHashcode => Math::is_string_hashcode_seed(world_seed),
+            Self::NumericText => is_typed_numeric(world_seed),
+        }
+    }
+
+    /// The origins that can give `world_seed`. A hashCode is an int, it could have been typed as a number too.
+    pub fn possible(world_seed: i64) -> Vec<Self> {
+        Self::ORIGINS
+            .into_iter()
+            .filter(|o| o.allows(world_seed))
+            .collect()
+    }
+
+    /// The world seeds of `structure_seed` a seed chosen this way can be, None for [`SeedOrigin::Any`]: all 65536 of
+    /// them. Only the lower 48 bits of `structure_seed` are used.
+    pub fn completions(self, structure_seed: i64) -> Option<Vec<i64>> {
+        match self {
+            Self::Any => None,
+            Self::NextLong => Some(recover_nextlong_world_seeds(structure_seed)),
+            Self::StringHashcode => {
+                let hashcode = structure_seed as i32 as i64;
+                Some(
+                    ((hashcode ^ structure_seed) & STRUCTURE_SEED_MASK == 0)
+                        .then_some(hashcode)
+                        .into_iter()
+                        .collect(),
+                )
+            }
+            Self::NumericText => Some(typed_numeric_completions(structure_seed).to_vec()),
+        }
+    }
+}
+
+/// False if no world generated by `new Random().nextLong()` has this structure seed, so a world whose seed wasn't
+/// typed can't have it
+#[inline]
+pub fn structure_seed_possible_from_nextlong(structure_seed: i64) -> bool {
+    (0..65536).any(|b_state_lo| next_long_world_seed(structure_seed, b_state_lo).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        random::JavaRandom,
+        self_test::{STRUCTURE_SEED, WORLD_SEED},
+        world_seed::{
+            STRUCTURE_SEED_MASK, SeedOrigin, is_nextlong_world_seed, is_typed_numeric,
+            recover_nextlong_world_seeds, structure_seed_possible_from_nextlong,
+            typed_numeric_completions,
+        },
+    };
+
+    const BIT_47: i64 = 1 << 47;
+
+    #[test]
+    fn test_typed_numeric_completions() {
+        let lower_bits = [
+            0,
+            1,
+            12345,
+            STRUCTURE_SEED & !BIT_47,
+            0x1234_5678_9ABC,
+            (BIT_47 - 1) & !0xFF,
+            BIT_47 - 1,
+        ];
+        for lower in lower_bits {
+            for bit_47 in [0, BIT_47] {
+                let structure_seed = lower | bit_47;
+                let completions = typed_numeric_completions(structure_seed);
+                if bit_47 == 0 {
+                    assert_eq!(completions.as_slice(), [structure_seed]);
+                } else {
+                    assert_eq!(completions.len(), 2);
+                    assert!(completions[0] < 0);
+                    assert_eq!(completions[0], structure_seed - (1 << 48));
+                    assert_eq!(completions[1], structure_seed);
+                }
+
+                for completion in completions.iter() {
+                    // the lower 48 bits round-trip, and the upper bits are only ever the sign extension or zero
+                    assert_eq!(completion & STRUCTURE_SEED_MASK, structure_seed);
+                    assert!(matches!(completion >> 48, 0 | -1));
+                    assert!(is_typed_numeric(*completion));
+                    // garbage in the upper bits of the argument is ignored
+                    assert_eq!(typed_numeric_completions(*completion), completions);
+                    assert_eq!(
+                        typed_numeric_completions(completion ^ (0x5A5A << 48)),
+                        completions
+                    );
+                }
+            }
+        }
+
+        // typed negative numbers, text seeds hash to ints
+        for typed in [-1, -12345, i32::MIN as i64, -BIT_47] {
+            assert_eq!(typed_numeric_completions(typed)[0], typed);
+        }
+
+        // the bundled world seed was generated
+        assert!(!is_typed_numeric(WORLD_SEED));
+        assert!(is_typed_numeric(STRUCTURE_SEED));
+        // beyond 2^48 the upper bits are neither zero nor the sign extension
+        assert!(!is_typed_numeric(1 << 48));
+        assert!(!is_typed_numeric(-BIT_47 - 1));
+    }
+
+    #[test]
+    fn test_nextlong_world_seeds() {
+        // the bundled world seed was generated by nextLong
+        assert!(structure_seed_possible_from_nextlong(STRUCTURE_SEED));
+        assert_eq!(
+            recover_nextlong_world_seeds(STRUCTURE_SEED),
+            vec![-1253228005040546804, WORLD_SEED]
+        );
+        // garbage in the upper bits is ignored
+        assert_eq!(
+            recover_nextlong_world_seeds(WORLD_SEED),
+            recover_nextlong_world_seeds(STRUCTURE_SEED)
+        );
+
+        let mut rng = JavaRandom::new(42);
+        for _ in 0..20 {
+            let world_seed = rng.next_long();
+            let seeds = recover_nextlong_world_seeds(world_seed);
+            assert!(seeds.contains(&world_seed), "{}", world_seed);
+            assert!(
+                seeds
+                    .iter()
+                    .all(|s| s & STRUCTURE_SEED_MASK == world_seed & STRUCTURE_SEED_MASK)
+            );
+        }
+
+        // no nextLong call ends in these 48 bits, only a typed seed has them
+        for structure_seed in [7, 11, 15, 3 << 32] {
+            assert!(!structure_seed_possible_from_nextlong(structure_seed));
+            assert!(recover_nextlong_world_seeds(structure_seed).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_is_nextlong_world_seed() {
+        let mut rng = JavaRandom::new(42);
+        for _ in 0..200 {
+            let world_seed = rng.next_long();
+            assert!(is_nextlong_world_seed(world_seed), "{}", world_seed);
+            // the same structure seed with other upper bits, generated about once in 65536
+            for other in [
+                world_seed ^ (1 << 48),
+                world_seed & STRUCTURE_SEED_MASK,
+                world_seed as i32 as i64,
+            ] {
+                assert_eq!(
+                    is_nextlong_world_seed(other),
+                    recover_nextlong_world_seeds(other).contains(&other),
+                    "{}",
+                    other
+                );
+            }
+        }
+        for structure_seed in [STRUCTURE_SEED, 7, 3 << 32, -12345] {
+            for world_seed in recover_nextlong_world_seeds(structure_seed) {
+                assert!(is_nextlong_world_seed(world_seed));
+            }
+        }
+        assert!(is_nextlong_world_seed(WORLD_SEED));
+        assert!(!is_nextlong_world_seed(STRUCTURE_SEED));
+    }
+
+    #[test]
+    fn test_seed_origins() {
+        assert_eq!(
+            SeedOrigin::NextLong.completions(STRUCTURE_SEED),
+            Some(recover_nextlong_world_seeds(STRUCTURE_SEED))
+        );
+        assert_eq!(SeedOrigin::Any.completions(STRUCTURE_SEED), None);
+        for structure_seed in [0, 12345, -12345, STRUCTURE_SEED, 1 << 40, BIT_47 | 5] {
+            assert_eq!(
+                SeedOrigin::NumericText.completions(structure_seed),
+                Some(typed_numeric_completions(structure_seed).to_vec())
+            );
+            for origin in SeedOrigin::ORIGINS {
+                let completions = origin.completions(structure_seed).unwrap();
+                for completion in completions.iter() {
+                    assert_eq!(
+                        completion & STRUCTURE_SEED_MASK,
+                        structure_seed & STRUCTURE_SEED_MASK
+                    );
+                    assert!(origin.allows(*completion), "{:?} {}", origin, completion);
+                }
+                // the completions are every world seed of the structure seed the origin allows
+                let allowed = (0..65536)
+                    .map(|hi| (hi << 48) | (structure_seed & STRUCTURE_SEED_MASK))
+                    .filter(|seed| origin.allows(*seed))
+                    .count();
+                assert_eq!(
+                    completions.len(),
+                    allowed,
+                    "{:?} {}",
+                    origin,
+                    structure_seed
+                );
+            }
+        }
+
+        // a hashCode is in the int range, its upper 32 bits are the sign extension
+        assert_eq!(
+            SeedOrigin::StringHashcode.completions(-12345 & STRUCTURE_SEED_MASK),
+            Some(vec![-12345])
+        );
+        assert_eq!(
+            SeedOrigin::StringHashcode.completions(1 << 40),
+            Some(vec![])
+        );
+        assert_eq!(
+            SeedOrigin::StringHashcode.completions(1 << 31),
+            Some(vec![])
+        );
+
+        assert_eq!(SeedOrigin::possible(WORLD_SEED), [SeedOrigin::NextLong]);
+        assert_eq!(
+            SeedOrigin::possible(STRUCTURE_SEED),
+            [SeedOrigin::NumericText]
+        );
+        assert_eq!(
+            SeedOrigin::possible(-12345),
+            [SeedOrigin::StringHashcode, SeedOrigin::NumericText]
+        );
+        assert!(SeedOrigin::possible(1 << 50).is_empty());
+        for origin in [SeedOrigin::Any].into_iter().chain(SeedOrigin::ORIGINS) {
+            assert_eq!(SeedOrigin::from_name(origin.name()), Some(origin));
+        }
+        assert_eq!(SeedOrigin::from_name("text"), None);
+    }
+}
@@ -0,0 +1,59 @@
+//! The structure seed search through the public API only, the way a program other than the TUI runs it
+
+use std::{thread, time::Duration};
+
+use mcseedcracker::{
+    features::buried_treasure::{build_fast_inventory_compare_context, get_loot_table},
+    math::Math,
+    search::{SearchOutcome, StructureData, StructureSeedSearch, StructureSeedSearchData},
+    self_test::{
+        BURIED_TREASURE_POS, PILLAR_SEED, STRUCTURE_SEARCH_SLICE, STRUCTURE_SEED,
+        buried_treasure_contents,
+    },
+};
+
+fn treasure() -> StructureData {
+    let (chunk_x, chunk_z) = Math::block_coords_to_chunk_coords(BURIED_TREASURE_POS);
+    StructureData::BuriedTreasureContents {
+        chunk_x,
+        chunk_z,
+        luck: 0.0,
+        contents: build_fast_inventory_compare_context(
+            buried_treasure_contents(),
+            &get_loot_table(),
+        )
+        .unwrap(),
+        loot_table: None,
+    }
+}
+
+#[test]
+fn bounded_search_of_one_pillar_seed() {
+    let data = StructureSeedSearchData::new(PILLAR_SEED, vec![treasure()], 10)
+        .with_state_hi(STRUCTURE_SEARCH_SLICE);
+    let handle = StructureSeedSearch::start(data);
+
+    let mut streamed = Vec::new();
+    while !handle.is_done() {
+        streamed.extend(handle.found_seeds());
+        let (searched, total) = handle.progress();
+        assert!(searched <= total);
+        thread::sleep(Duration::from_millis(1));
+    }
+    streamed.extend(handle.found_seeds());
+    assert_eq!(streamed, vec![STRUCTURE_SEED]);
+
+    let candidates = handle.candidates(&streamed);
+    assert_eq!(candidates[0].pillar_seed, PILLAR_SEED);
+    let outcome = handle.join();
+    assert_eq!(outcome, SearchOutcome::Complete(vec![STRUCTURE_SEED]));
+    assert_eq!(outcome.seeds(), [STRUCTURE_SEED]);
+}
+
+#[test]
+fn too_many_seeds() {
+    // without evidence, every candidate is a structure seed
+    let data = StructureSeedSearchData::new(PILLAR_SEED, vec![], 3).with_state_hi(0..1);
+    let outcome = StructureSeedSearch::start(data).join();
+    assert!(matches!(&outcome, SearchOutcome::TooManySeeds(seeds) if seeds.len() == 4));
+}
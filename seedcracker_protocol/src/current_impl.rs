@@ -1,20 +1,32 @@
 use std::{
     borrow::Borrow,
     io::{BufRead, Write},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
 };
 
 use chumsky::{Parser, error::Rich};
 
 use crate::{
     McSeedCrackingProtocol,
+    framing::{Framing, MessageReader, MessageWriter},
     traits::{CharsIter, IntoCharsIter, StringOrSlice, VecOrSlice},
     v0::{
         self, ParsetimeProtocolValue, ProtocolVersion0, SimpleV0Extension,
-        SimpleV0ProblemBruteCalculation,
+        SimpleV0ProblemBruteCalculation, v0_format_result,
     },
 };
 
+/// How often the server checks on a running computation while it waits for a command
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A message of the input, None at its end
+type InputMessage = std::io::Result<Option<String>>;
+
 pub enum ProtocolCommand<'a> {
     V0(v0::ProtocolCommand<'a>),
 }
@@ -30,19 +42,25 @@ pub struct MCSCIProtocol {
     extensions: Vec<Box<dyn SimpleV0Extension>>,
 
     current_pb: Option<Box<dyn SimpleV0ProblemBruteCalculation>>,
+    /// The current computation was started and neither stopped nor answered yet
+    awaiting_result: bool,
 }
 
 #[derive(Clone)]
 struct ArcMutexWriter<'a> {
-    inner: Arc<Mutex<&'a mut dyn Write>>,
+    inner: Arc<Mutex<MessageWriter<&'a mut dyn Write>>>,
 }
 
 impl<'a> ArcMutexWriter<'a> {
     pub fn new(writer: &'a mut dyn Write) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(writer)),
+            inner: Arc::new(Mutex::new(MessageWriter::new(writer))),
         }
     }
+
+    pub fn set_framing(&self, framing: Framing) {
+        self.inner.lock().unwrap().set_framing(framing);
+    }
 }
 
 impl<'a> Write for ArcMutexWriter<'a> {
@@ -142,10 +160,12 @@ impl MCSCIProtocol {
         &self,
         extid: u32,
         ext: &'a dyn SimpleV0Extension,
+        verbose: bool,
     ) -> ProtocolResponse<'a> {
         ProtocolResponse::V0(v0::ProtocolResponse::TypeList {
             extension: extid,
             types: ext.list_extension_types(),
+            verbose,
         })
     }
 
@@ -153,198 +173,336 @@ impl MCSCIProtocol {
         &self,
         extid: u32,
         ext: &'a dyn SimpleV0Extension,
+        verbose: bool,
     ) -> ProtocolResponse<'a> {
         ProtocolResponse::V0(v0::ProtocolResponse::ProblemList {
             extension: extid,
             problems: ext.list_extension_problems(),
+            verbose,
         })
     }
 
+    /// Sends the values the computation found since the last poll, then its result once it is done, nothing if it was
+    /// stopped. False if they couldn't be sent: the client can't be reached anymore and the session ends.
+    fn poll_computation(&mut self, output: &mut impl Write, errout: &mut impl Write) -> bool {
+        let Some(pb) = self.current_pb.as_mut().filter(|_| self.awaiting_result) else {
+            return true;
+        };
+        // checked before taking the values, so none is left behind once it ended
+        let running = pb.is_running();
+        let partials = pb.take_new_results();
+        if running && partials.is_empty() {
+            return true;
+        }
+        let written = partials
+            .into_iter()
+            .try_for_each(|value| {
+                self.format_response(
+                    output,
+                    &ProtocolResponse::V0(v0::ProtocolResponse::PartialResult(value)),
+                )
+            })
+            .and_then(|_| match &self.current_pb {
+                _ if running => Ok(()),
+                Some(pb) if pb.is_done() => v0_format_result(output, pb.result().as_ref()),
+                _ => self.format_response(output, &self.unexpected("computation failed")),
+            });
+        self.awaiting_result = running;
+        match written.and_then(|_| output.flush()) {
+            Ok(()) => true,
+            Err(err) => {
+                let _ = writeln!(errout, "Ending the session, output failed: {}", err);
+                false
+            }
+        }
+    }
+
+    /// Reads the messages on its own thread, so the server can send the result of a computation while it waits for
+    /// the next command. The thread reads a message once the previous one was handled, and gets the framing to read it
+    /// with then.
+    fn spawn_reader<'scope, 'env>(
+        scope: &'scope thread::Scope<'scope, 'env>,
+        input: &'env mut (impl BufRead + Send),
+    ) -> (mpsc::Receiver<InputMessage>, mpsc::Sender<Option<Framing>>) {
+        let (message_tx, messages) = mpsc::channel();
+        let (framings, framing_rx) = mpsc::channel::<Option<Framing>>();
+        scope.spawn(move || {
+            let mut input = MessageReader::new(input);
+            loop {
+                let mut line = String::new();
+                let message = match input.read_message(&mut line) {
+                    Ok(true) => Ok(Some(line)),
+                    Ok(false) => Ok(None),
+                    Err(err) => Err(err),
+                };
+                let last = !matches!(message, Ok(Some(_)));
+                if message_tx.send(message).is_err() || last {
+                    break;
+                }
+                match framing_rx.recv() {
+                    Ok(Some(framing)) => input.set_framing(framing),
+                    Ok(None) => {}
+                    // the session ended
+                    Err(_) => break,
+                }
+            }
+        });
+        (messages, framings)
+    }
+
+    /// Runs the session until `quit` or the end of the input, where it waits for the running computation. If the
+    /// client can't be reached anymore, it also waits for the next message, the reader thread ends then.
     pub fn server_loop(
         mut self,
-        input: &mut impl BufRead,
+        input: &mut (impl BufRead + Send),
         output: &mut impl Write,
         errout: &mut impl Write,
     ) -> Result<(), std::io::Error> {
-        let output = &mut ArcMutexWriter::new(output);
-
-        let mut line = String::new();
-
-        loop {
-            line.clear();
-            if input.read_line(&mut line).unwrap() == 0 {
-                // EOF
-                break;
+        thread::scope(|scope| {
+            let output = &mut ArcMutexWriter::new(output);
+            let (messages, framings) = Self::spawn_reader(scope, input);
+
+            loop {
+                let line = match messages.recv_timeout(POLL_INTERVAL) {
+                    Ok(Ok(Some(line))) => line,
+                    Ok(Ok(None)) | Err(RecvTimeoutError::Disconnected) => {
+                        // EOF
+                        while self.awaiting_result {
+                            if !self.poll_computation(output, errout) {
+                                break;
+                            }
+                            thread::sleep(POLL_INTERVAL);
+                        }
+                        break;
+                    }
+                    Ok(Err(err)) => return Err(err),
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !self.poll_computation(output, errout) {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                // the result of a computation that just ended comes before the answer to the command
+                if !self.poll_computation(output, errout) {
+                    break;
+                }
+                let mut framing = None;
+                let running = self.handle_message(&line, output, errout, &mut framing)?;
+                output.flush()?;
+                if !running {
+                    break;
+                }
+                if !self.poll_computation(output, errout) {
+                    break;
+                }
+                // the reader is gone only at the end of the input, seen on the next receive
+                let _ = framings.send(framing);
             }
+            Ok(())
+        })
+    }
 
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
+    /// Answers a message, false if the session ends. A `framing` command sets the framing of the next message.
+    fn handle_message(
+        &mut self,
+        line: &str,
+        output: &mut ArcMutexWriter,
+        errout: &mut impl Write,
+        next_framing: &mut Option<Framing>,
+    ) -> Result<bool, std::io::Error> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(true);
+        }
 
-            let command = match self.parse_command(&line) {
-                Ok(command) => command,
-                Err(errors) => {
-                    for error in errors {
-                        writeln!(errout, "{}", error)?;
-                    }
+        let command = match self.parse_command(line) {
+            Ok(command) => command,
+            Err(errors) => {
+                for error in errors {
+                    writeln!(errout, "{}", error)?;
+                }
+                self.format_response(
+                    output,
+                    &ProtocolResponse::V0(v0::ProtocolResponse::ParseFail),
+                )?;
+                return Ok(true);
+            }
+        };
+
+        match command {
+            ProtocolCommand::V0(command) => match command {
+                _ if self.current_pb.as_ref().is_some_and(|v| v.is_running())
+                    && !matches!(
+                        command,
+                        v0::ProtocolCommand::Stop | v0::ProtocolCommand::Progress
+                    ) =>
+                {
+                    self.format_response(output, &self.unexpected("computation running"))?
+                }
+                v0::ProtocolCommand::Hello => {
+                    self.client_did_hello();
+                    self.format_response(output, &self.acknowledge())?;
+                }
+                _ if !self.client_helloed() => {
+                    self.format_response(output, &self.unexpected("not initialized"))?
+                }
+                v0::ProtocolCommand::Help => {
+                    self.format_response(output, &self.acknowledge())?;
                     self.format_response(
-                        output,
-                        &ProtocolResponse::V0(v0::ProtocolResponse::ParseFail),
-                    )?;
-                    continue;
+                    output,
+                    &self.response_infos([
+                        "Help",
+                        "quit: Quits the program",
+                        "help: Prints this help message",
+                        "version: Prints the version",
+                        "setup-problem <problem name string> [args: <name>=<typed value>]+: Sets up the server to handle a computation problem with the given name and arguments",
+                        "progress: Prints the progress of the problem that was set up, as the steps done and the total",
+                        "framing <text|binary>: Switches the framing of the following messages, binary sends each one as its 4-byte big-endian length followed by the UTF-8 text",
+                    ]),
+                )?;
                 }
-            };
-
-            match command {
-                ProtocolCommand::V0(command) => match command {
-                    _ if self.current_pb.as_ref().is_some_and(|v| v.is_running())
-                        && !matches!(command, v0::ProtocolCommand::Stop) =>
-                    {
-                        self.format_response(output, &self.unexpected("computation running"))?
-                    }
-                    v0::ProtocolCommand::Hello => {
-                        self.client_did_hello();
-                        self.format_response(output, &self.acknowledge())?;
-                    }
-                    _ if !self.client_helloed() => {
-                        self.format_response(output, &self.unexpected("not initialized"))?
-                    }
-                    v0::ProtocolCommand::Help => {
-                        self.format_response(output, &self.acknowledge())?;
-                        self.format_response(
-                        output,
-                        &self.response_infos([
-                            "Help",
-                            "quit: Quits the program",
-                            "help: Prints this help message",
-                            "version: Prints the version",
-                            "setup-problem <problem name string> [args: <name>=<typed value>]+: Sets up the server to handle a computation problem with the given name and arguments",
-                        ]),
-                    )?;
-                    }
-                    v0::ProtocolCommand::Quit => {
-                        self.format_response(output, &self.acknowledge())?;
-                        break;
-                    }
-                    v0::ProtocolCommand::Version => {
-                        self.format_response(output, &self.acknowledge())?;
-                        self.format_response(output, &self.version_response(None))?;
-                    }
-                    v0::ProtocolCommand::SetupProblem {
-                        name,
-                        args,
-                        extension,
-                    } => {
-                        self.format_response(output, &self.acknowledge())?;
-                        match self.extensions.get(extension as usize) {
-                            None => {
+                v0::ProtocolCommand::Framing(framing) => {
+                    self.format_response(output, &self.acknowledge())?;
+                    output.flush()?;
+                    output.set_framing(framing);
+                    *next_framing = Some(framing);
+                }
+                v0::ProtocolCommand::Quit => {
+                    self.format_response(output, &self.acknowledge())?;
+                    return Ok(false);
+                }
+                v0::ProtocolCommand::Version => {
+                    self.format_response(output, &self.acknowledge())?;
+                    self.format_response(output, &self.version_response(None))?;
+                }
+                v0::ProtocolCommand::SetupProblem {
+                    name,
+                    args,
+                    extension,
+                } => {
+                    self.format_response(output, &self.acknowledge())?;
+                    match self.extensions.get(extension as usize) {
+                        None => {
+                            self.format_response(
+                                output,
+                                &self.no_such_extension_response(extension),
+                            )?;
+                        }
+                        Some(ext) => {
+                            let Some(pb) = ext.get_problem(name.as_slice()) else {
                                 self.format_response(
                                     output,
-                                    &self.no_such_extension_response(extension),
+                                    &self.setup_problem_err("No such problem"),
                                 )?;
-                            }
-                            Some(ext) => {
-                                let Some(pb) = ext.get_problem(name.as_slice()) else {
+                                return Ok(true);
+                            };
+                            match pb.setup(VecOrSlice::V(args)) {
+                                Ok(res) => {
+                                    self.current_pb = Some(res);
+                                    self.awaiting_result = false;
                                     self.format_response(
                                         output,
-                                        &self.setup_problem_err("No such problem"),
+                                        &ProtocolResponse::V0(v0::ProtocolResponse::SetupOk),
+                                    )?;
+                                }
+                                Err(err) => {
+                                    self.format_response(
+                                        output,
+                                        &self.setup_problem_err_value(err),
                                     )?;
-                                    continue;
-                                };
-                                match pb.setup(VecOrSlice::V(args)) {
-                                    Ok(res) => {
-                                        self.current_pb = Some(res);
-                                        self.format_response(
-                                            output,
-                                            &ProtocolResponse::V0(v0::ProtocolResponse::SetupOk),
-                                        )?;
-                                    }
-                                    Err(err) => {
-                                        self.format_response(
-                                            output,
-                                            &self.setup_problem_err_value(err),
-                                        )?;
-                                    }
                                 }
                             }
                         }
                     }
-                    v0::ProtocolCommand::ListTypes { extension } => {
-                        self.format_response(output, &self.acknowledge())?;
-                        match self.extensions.get(extension as usize) {
-                            None => {
-                                self.format_response(
-                                    output,
-                                    &self.no_such_extension_response(extension),
-                                )?;
-                            }
-                            Some(ext) => {
-                                self.format_response(
-                                    output,
-                                    &self.extension_types_response(extension, &**ext),
-                                )?;
-                            }
+                }
+                v0::ProtocolCommand::ListTypes { extension, verbose } => {
+                    self.format_response(output, &self.acknowledge())?;
+                    match self.extensions.get(extension as usize) {
+                        None => {
+                            self.format_response(
+                                output,
+                                &self.no_such_extension_response(extension),
+                            )?;
+                        }
+                        Some(ext) => {
+                            self.format_response(
+                                output,
+                                &self.extension_types_response(extension, &**ext, verbose),
+                            )?;
                         }
                     }
-                    v0::ProtocolCommand::ListProblems { extension } => {
-                        self.format_response(output, &self.acknowledge())?;
-                        match self.extensions.get(extension as usize) {
-                            None => {
-                                self.format_response(
-                                    output,
-                                    &self.no_such_extension_response(extension),
-                                )?;
-                            }
-                            Some(ext) => {
-                                self.format_response(
-                                    output,
-                                    &self.extension_problems_response(extension, &**ext),
-                                )?;
-                            }
+                }
+                v0::ProtocolCommand::ListProblems { extension, verbose } => {
+                    self.format_response(output, &self.acknowledge())?;
+                    match self.extensions.get(extension as usize) {
+                        None => {
+                            self.format_response(
+                                output,
+                                &self.no_such_extension_response(extension),
+                            )?;
                         }
+                        Some(ext) => {
+                            self.format_response(
+                                output,
+                                &self.extension_problems_response(extension, &**ext, verbose),
+                            )?;
+                        }
+                    }
+                }
+                v0::ProtocolCommand::Extensions => {
+                    self.format_response(output, &self.acknowledge())?;
+                    self.format_response(
+                        output,
+                        &ProtocolResponse::V0(v0::ProtocolResponse::Extensions {
+                            count: self.extensions.len() as u32,
+                            extensions: if self.extensions.is_empty() {
+                                None
+                            } else {
+                                Some(
+                                    self.extensions
+                                        .iter()
+                                        .map(|ext| ext.protocol_extension_info())
+                                        .collect::<Vec<_>>(),
+                                )
+                            },
+                        }),
+                    )?
+                }
+                v0::ProtocolCommand::Go => {
+                    if let Some(pb) = &mut self.current_pb {
+                        pb.go();
+                        self.awaiting_result = true;
+                    } else {
+                        self.format_response(output, &self.unexpected("no problem to solve"))?
+                    }
+                }
+                v0::ProtocolCommand::Stop => {
+                    if let Some(pb) = &mut self.current_pb {
+                        // a stopped computation has no result
+                        pb.stop();
+                        self.awaiting_result = false;
+                    } else {
+                        self.format_response(output, &self.unexpected("no computation to stop"))?
                     }
-                    v0::ProtocolCommand::Extensions => {
-                        self.format_response(output, &self.acknowledge())?;
+                }
+                v0::ProtocolCommand::Progress => {
+                    if let Some(pb) = &self.current_pb {
+                        let (current, total) = pb.progress();
                         self.format_response(
                             output,
-                            &ProtocolResponse::V0(v0::ProtocolResponse::Extensions {
-                                count: self.extensions.len() as u32,
-                                extensions: if self.extensions.is_empty() {
-                                    None
-                                } else {
-                                    Some(
-                                        self.extensions
-                                            .iter()
-                                            .map(|ext| ext.protocol_extension_info())
-                                            .collect::<Vec<_>>(),
-                                    )
-                                },
+                            &ProtocolResponse::V0(v0::ProtocolResponse::Progress {
+                                current,
+                                total,
                             }),
                         )?
+                    } else {
+                        self.format_response(output, &self.unexpected("no active problem"))?
                     }
-                    v0::ProtocolCommand::Go => {
-                        if let Some(pb) = &mut self.current_pb {
-                            pb.go(output);
-                        } else {
-                            self.format_response(output, &self.unexpected("no problem to solve"))?
-                        }
-                    }
-                    v0::ProtocolCommand::Stop => {
-                        if let Some(pb) = &mut self.current_pb {
-                            pb.stop();
-                        } else {
-                            self.format_response(
-                                output,
-                                &self.unexpected("no computation to stop"),
-                            )?
-                        }
-                    }
-                },
-            };
-            output.flush()?;
-        }
-        Ok(())
+                }
+            },
+        };
+        Ok(true)
     }
 
     pub fn register_extension(&mut self, extension: impl SimpleV0Extension + 'static) {
@@ -388,3 +546,221 @@ impl McSeedCrackingProtocol for MCSCIProtocol {
             .into_result()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc, Mutex,
+            atomic::{AtomicBool, AtomicU64, Ordering},
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    use crate::{
+        current_impl::MCSCIProtocol,
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{
+            ParsetimeProtocolValue, ProtocolExtensionInfo, SimpleV0Extension, SimpleV0Problem,
+            SimpleV0ProblemBruteCalculation, TypeDeclaration, V0ProblemArgumentDescription,
+        },
+    };
+
+    /// Counts to `total` on its own thread, a step per millisecond. With `found`, each step is also a partial result.
+    #[derive(Debug)]
+    struct Counter {
+        total: u64,
+        progress: Arc<AtomicU64>,
+        stopped: Arc<AtomicBool>,
+        found: Option<Arc<Mutex<Vec<u64>>>>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl SimpleV0ProblemBruteCalculation for Counter {
+        fn go(&mut self) {
+            let (total, progress, stopped, found) = (
+                self.total,
+                Arc::clone(&self.progress),
+                Arc::clone(&self.stopped),
+                self.found.clone(),
+            );
+            self.handle = Some(thread::spawn(move || {
+                while progress.load(Ordering::Relaxed) < total && !stopped.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(1));
+                    let step = progress.fetch_add(1, Ordering::Relaxed) + 1;
+                    if let Some(found) = &found {
+                        found.lock().unwrap().push(step);
+                    }
+                }
+            }));
+        }
+
+        fn stop(&mut self) {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+
+        fn is_failed(&self) -> bool {
+            false
+        }
+
+        fn is_done(&self) -> bool {
+            !self.is_running() && self.progress.load(Ordering::Relaxed) == self.total
+        }
+
+        fn is_running(&self) -> bool {
+            self.handle.as_ref().is_some_and(|h| !h.is_finished())
+        }
+
+        fn progress(&self) -> (u64, u64) {
+            (self.progress.load(Ordering::Relaxed), self.total)
+        }
+
+        fn result(&self) -> Option<ParsetimeProtocolValue<'_>> {
+            self.is_done()
+                .then_some(ParsetimeProtocolValue::U64(self.total))
+        }
+
+        fn take_new_results(&mut self) -> Vec<ParsetimeProtocolValue<'static>> {
+            self.found.as_ref().map_or_else(Vec::new, |found| {
+                std::mem::take(&mut *found.lock().unwrap())
+                    .into_iter()
+                    .map(ParsetimeProtocolValue::U64)
+                    .collect()
+            })
+        }
+    }
+
+    /// The total to count to, and whether the steps are partial results
+    #[derive(Debug)]
+    struct CounterProblem(u64, bool);
+
+    impl SimpleV0Problem for CounterProblem {
+        fn name<'a>(&'a self) -> StringOrSlice<'a> {
+            StringOrSlice::Sl("counter")
+        }
+
+        fn description<'a>(&'a self) -> StringOrSlice<'a> {
+            StringOrSlice::Sl("Counts")
+        }
+
+        fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+            VecOrSlice::S(&[])
+        }
+
+        fn setup<'a>(
+            &'a self,
+            _args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+        ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+            Ok(Box::new(Counter {
+                total: self.0,
+                progress: Arc::new(AtomicU64::new(0)),
+                stopped: Arc::new(AtomicBool::new(false)),
+                found: self.1.then(Arc::default),
+                handle: None,
+            }))
+        }
+    }
+
+    #[derive(Debug)]
+    struct CounterExtension;
+
+    impl SimpleV0Extension for CounterExtension {
+        fn protocol_extension_info(&self) -> ProtocolExtensionInfo {
+            ProtocolExtensionInfo {
+                name: StringOrSlice::Sl("counter"),
+                version: StringOrSlice::Sl("0"),
+                description: StringOrSlice::Sl("Counts"),
+                authors: VecOrSlice::S(&[]),
+                commands: VecOrSlice::S(&[]),
+            }
+        }
+
+        fn list_extension_types<'a>(
+            &'a self,
+        ) -> VecOrSlice<'a, (StringOrSlice<'a>, TypeDeclaration<'a>)> {
+            VecOrSlice::V(Vec::new())
+        }
+
+        fn list_extension_problems<'a>(&'a self) -> VecOrSlice<'a, Box<dyn SimpleV0Problem>> {
+            VecOrSlice::V(Vec::new())
+        }
+
+        fn get_problem<'a>(&'a self, name: &str) -> Option<Box<dyn SimpleV0Problem>> {
+            match name {
+                "fast" => Some(Box::new(CounterProblem(5, false))),
+                "slow" => Some(Box::new(CounterProblem(1_000_000, false))),
+                "streamed" => Some(Box::new(CounterProblem(3, true))),
+                _ => None,
+            }
+        }
+    }
+
+    fn run(input: &str) -> String {
+        let mut protocol = MCSCIProtocol::default();
+        protocol.register_extension(CounterExtension);
+        let mut output = Vec::new();
+        protocol
+            .server_loop(&mut input.as_bytes(), &mut output, &mut std::io::sink())
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_background_computation_result() {
+        let output = run("hello\nsetup-problem 0 \"fast\"\ngo\n");
+        // the server waits for the computation at the end of the input
+        assert_eq!(output, "ack\nack\nsetup-ok\nresult u64(5)\n");
+    }
+
+    #[test]
+    fn test_partial_results() {
+        let output = run("hello\nsetup-problem 0 \"streamed\"\ngo\n");
+        assert_eq!(
+            output,
+            "ack\nack\nsetup-ok\nresult-partial u64(1)\nresult-partial u64(2)\nresult-partial u64(3)\nresult u64(3)\n"
+        );
+    }
+
+    #[test]
+    fn test_stop_cancels_result() {
+        let output = run("hello\nsetup-problem 0 \"slow\"\ngo\nversion\nstop\nversion\n");
+        assert!(
+            output.contains("unexpected \"computation running\"\n"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("result"), "{}", output);
+    }
+
+    #[test]
+    fn test_progress() {
+        let output = run("hello\nprogress\nsetup-problem 0 \"fast\"\nprogress\ngo\nprogress\n");
+        assert!(
+            output.starts_with(
+                "ack\nunexpected \"no active problem\"\nack\nsetup-ok\nprogress 0 5\n"
+            ),
+            "{}",
+            output
+        );
+        // the result comes before or after the progress of the running computation
+        let lines = output.lines().skip(4).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2, "{}", output);
+        assert!(lines.contains(&"result u64(5)"), "{}", output);
+        let current = lines
+            .iter()
+            .find_map(|line| line.strip_prefix("progress ")?.strip_suffix(" 5"))
+            .and_then(|current| current.parse::<u64>().ok());
+        assert!(current.is_some_and(|current| current <= 5), "{}", output);
+    }
+
+    #[test]
+    fn test_progress_while_running() {
+        let output = run("hello\nsetup-problem 0 \"slow\"\ngo\nprogress\nstop\n");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[..3], ["ack", "ack", "setup-ok"], "{}", output);
+        assert_eq!(lines.len(), 4, "{}", output);
+        assert!(lines[3].starts_with("progress "), "{}", output);
+        assert!(lines[3].ends_with(" 1000000"), "{}", output);
+    }
+}
@@ -0,0 +1,251 @@
+//! Transport framing of the messages. Messages are lines of text by default, the length-prefixed framing lets a
+//! program embedding the server send messages containing any character, and tell them apart from its other output.
+//!
+//! The adapters only need a [`BufRead`] and a [`Write`], so they work the same over stdin/stdout or a socket, on
+//! both ends of the connection.
+
+use std::io::{self, BufRead, Write};
+
+/// Longest length-prefixed message accepted, a longer length means the stream is corrupted
+pub const MAX_FRAME_LEN: u32 = 16 * 1024 * 1024;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Framing {
+    /// One message per line
+    #[default]
+    Text,
+    /// Each message is its length in bytes as a big-endian u32, followed by the message in UTF-8. The message is the
+    /// content of the line it would be in text framing, without the line terminator.
+    LengthPrefixed,
+}
+
+pub struct MessageReader<R> {
+    inner: R,
+    framing: Framing,
+}
+
+impl<R: BufRead> MessageReader<R> {
+    #[inline]
+    pub const fn new(inner: R) -> Self {
+        Self {
+            inner,
+            framing: Framing::Text,
+        }
+    }
+
+    #[inline]
+    pub const fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// Applies from the next message on
+    #[inline]
+    pub fn set_framing(&mut self, framing: Framing) {
+        self.framing = framing;
+    }
+
+    /// Appends the next message to `buf`, false at the end of the stream. Text messages keep their line terminator.
+    pub fn read_message(&mut self, buf: &mut String) -> io::Result<bool> {
+        match self.framing {
+            Framing::Text => Ok(self.inner.read_line(buf)? != 0),
+            Framing::LengthPrefixed => {
+                // the stream may only end between two messages
+                if self.inner.fill_buf()?.is_empty() {
+                    return Ok(false);
+                }
+                let mut len = [0u8; 4];
+                self.inner.read_exact(&mut len)?;
+                let len = u32::from_be_bytes(len);
+                if len > MAX_FRAME_LEN {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("message of {} bytes is too long", len),
+                    ));
+                }
+                let mut message = vec![0u8; len as usize];
+                self.inner.read_exact(&mut message)?;
+                let message = String::from_utf8(message)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                buf.push_str(&message);
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// Frames every line written to it. In [`Framing::LengthPrefixed`], a line is only sent once its line terminator
+/// was written.
+pub struct MessageWriter<W> {
+    inner: W,
+    framing: Framing,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> MessageWriter<W> {
+    #[inline]
+    pub const fn new(inner: W) -> Self {
+        Self {
+            inner,
+            framing: Framing::Text,
+            pending: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub const fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// Applies from the next message on, to be called between two messages
+    #[inline]
+    pub fn set_framing(&mut self, framing: Framing) {
+        self.framing = framing;
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for MessageWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.framing {
+            Framing::Text => self.inner.write(buf),
+            Framing::LengthPrefixed => {
+                self.pending.extend_from_slice(buf);
+                while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+                    let line = &self.pending[..end];
+                    write_frame(&mut self.inner, line.strip_suffix(b"\r").unwrap_or(line))?;
+                    self.pending.drain(..=end);
+                }
+                Ok(buf.len())
+            }
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes a single length-prefixed message
+pub fn write_frame(writer: &mut impl Write, message: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(message.len())
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_LEN)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("message of {} bytes is too long", message.len()),
+            )
+        })?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::{
+        current_impl::MCSCIProtocol,
+        framing::{Framing, MAX_FRAME_LEN, MessageReader, MessageWriter, write_frame},
+    };
+
+    fn frame(message: &str) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_frame(&mut out, message.as_bytes()).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_message_adapters() {
+        let mut writer = MessageWriter::new(Vec::new());
+        writer.write_all(b"text\n").unwrap();
+        writer.set_framing(Framing::LengthPrefixed);
+        // a line is sent once it is complete, whatever the number of writes
+        writer.write_all(b"ack").unwrap();
+        writer.write_all(b"\r\ninfo:  a\ninfo:  b\n").unwrap();
+        let written = writer.into_inner();
+
+        let mut expected = b"text\n".to_vec();
+        expected.extend(frame("ack"));
+        expected.extend(frame("info:  a"));
+        expected.extend(frame("info:  b"));
+        assert_eq!(written, expected);
+
+        let mut reader = MessageReader::new(&written[..]);
+        let mut message = String::new();
+        assert!(reader.read_message(&mut message).unwrap());
+        assert_eq!(message, "text\n");
+        reader.set_framing(Framing::LengthPrefixed);
+        for expected in ["ack", "info:  a", "info:  b"] {
+            message.clear();
+            assert!(reader.read_message(&mut message).unwrap());
+            assert_eq!(message, expected);
+        }
+        assert!(!reader.read_message(&mut message).unwrap());
+
+        // truncated, too long and invalid UTF-8 messages
+        let mut message = String::new();
+        let truncated = &frame("hello")[..6];
+        let mut reader = MessageReader::new(truncated);
+        reader.set_framing(Framing::LengthPrefixed);
+        assert!(reader.read_message(&mut message).is_err());
+        let mut too_long = (MAX_FRAME_LEN + 1).to_be_bytes().to_vec();
+        too_long.extend(b"x");
+        let mut reader = MessageReader::new(&too_long[..]);
+        reader.set_framing(Framing::LengthPrefixed);
+        assert!(reader.read_message(&mut message).is_err());
+        let invalid = [0, 0, 0, 2, 0xC3, 0x28];
+        let mut reader = MessageReader::new(&invalid[..]);
+        reader.set_framing(Framing::LengthPrefixed);
+        assert!(reader.read_message(&mut message).is_err());
+    }
+
+    #[test]
+    fn test_framed_conversation() {
+        let mut input = b"hello\nframing binary\n".to_vec();
+        for command in [
+            "version",
+            // the line break would end the command in text framing
+            "setup-problem 0 \"two\nlines\"",
+            "go",
+            "framing text",
+        ] {
+            input.extend(frame(command));
+        }
+        input.extend(b"quit\n");
+
+        let mut output = Vec::new();
+        let mut errout = Vec::new();
+        MCSCIProtocol::default()
+            .server_loop(&mut &input[..], &mut output, &mut errout)
+            .unwrap();
+        assert!(errout.is_empty());
+
+        let mut reader = MessageReader::new(&output[..]);
+        let mut read = |framing| {
+            let mut message = String::new();
+            reader.set_framing(framing);
+            assert!(reader.read_message(&mut message).unwrap());
+            message
+        };
+        assert_eq!(read(Framing::Text), "ack\n");
+        assert_eq!(read(Framing::Text), "ack\n");
+        for expected in [
+            "ack",
+            "version 0",
+            "ack",
+            "no-such-extension 0",
+            "unexpected \"no problem to solve\"",
+            "ack",
+        ] {
+            assert_eq!(read(Framing::LengthPrefixed), expected);
+        }
+        assert_eq!(read(Framing::Text), "ack\n");
+        assert!(!reader.read_message(&mut String::new()).unwrap());
+    }
+}
@@ -1,6 +1,11 @@
 pub mod current_impl;
+pub mod framing;
+#[cfg(test)]
+mod test_support;
 pub mod traits;
 pub mod v0;
+#[cfg(test)]
+mod v0_golden;
 
 pub trait McSeedCrackingProtocol {
     type Command<'a>;
@@ -0,0 +1,73 @@
+//! Values for the tests that can't be written as literals, like the boxed problems of a problem list
+
+use crate::{
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        ParsetimeProtocolValue, SimpleV0Problem, SimpleV0ProblemBruteCalculation,
+        V0ProblemArgumentDescription,
+    },
+};
+
+/// A problem that is only listed, its setup always fails
+#[derive(Debug, Clone, Copy)]
+pub struct StaticProblem {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub args: &'static [V0ProblemArgumentDescription<'static>],
+}
+
+impl StaticProblem {
+    #[inline]
+    pub const fn new(
+        name: &'static str,
+        description: &'static str,
+        args: &'static [V0ProblemArgumentDescription<'static>],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            args,
+        }
+    }
+
+    #[inline]
+    pub fn boxed(self) -> Box<dyn SimpleV0Problem> {
+        Box::new(self)
+    }
+}
+
+impl SimpleV0Problem for StaticProblem {
+    fn name<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(self.name)
+    }
+
+    fn description<'a>(&'a self) -> StringOrSlice<'a> {
+        StringOrSlice::Sl(self.description)
+    }
+
+    fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+        VecOrSlice::S(self.args)
+    }
+
+    fn setup<'a>(
+        &'a self,
+        _args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+    ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+        Err(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+            "Listed only",
+        )))
+    }
+}
+
+#[inline]
+pub const fn arg(
+    name: &'static str,
+    optional: bool,
+    argtype: &'static str,
+) -> V0ProblemArgumentDescription<'static> {
+    V0ProblemArgumentDescription {
+        name: StringOrSlice::Sl(name),
+        optional,
+        argtype: StringOrSlice::Sl(argtype),
+    }
+}
@@ -397,6 +397,13 @@ pub enum VecOrSlice<'a, T> {
     S(&'a [T]),
 }
 
+/// Equal if the elements are, whether they are owned or borrowed
+impl<'a, T: PartialEq> PartialEq for VecOrSlice<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
 impl<'a, T> From<&'a [T]> for VecOrSlice<'a, T> {
     fn from(value: &'a [T]) -> Self {
         VecOrSlice::S(value)
@@ -8,9 +8,12 @@ use chumsky::{
     text::{ascii::ident, digits, int},
 };
 
-use crate::traits::{
-    CharsIter, FloatFromStr, FloatType, FromRadix, FromRadixNegative, IntoCharsIter, StringOrSlice,
-    ToVecOrSliceOwned, VecOrSlice,
+use crate::{
+    framing::Framing,
+    traits::{
+        CharsIter, FloatFromStr, FloatType, FromRadix, FromRadixNegative, IntoCharsIter,
+        StringOrSlice, ToVecOrSliceOwned, VecOrSlice,
+    },
 };
 
 pub trait ProtocolVersion0 {
@@ -27,28 +30,35 @@ pub enum ProtocolCommand<'a> {
     Extensions,
     Go,
     Stop,
+    /// Progress of the problem that was set up, see [`ProtocolResponse::Progress`]
+    Progress,
     ListTypes {
         extension: u32,
+        /// Multi-line response, see [`ProtocolResponse::TypeList`]
+        verbose: bool,
     },
     ListProblems {
         extension: u32,
+        verbose: bool,
     },
     SetupProblem {
         extension: u32,
         name: StringOrSlice<'a>,
         args: Vec<(StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
     },
+    /// Framing of the messages after its acknowledgment, in both directions
+    Framing(Framing),
 }
 
 pub type TypeAlias<'a> = StringOrSlice<'a>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnumerationConstructor<'a> {
     pub name: StringOrSlice<'a>,
     pub argtype: Option<TypeDeclaration<'a>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TypeDeclaration<'a> {
     Alias(TypeAlias<'a>),
     Tuple(VecOrSlice<'a, TypeDeclaration<'a>>),
@@ -105,16 +115,29 @@ pub enum ProtocolResponse<'a> {
         count: u32,
         extensions: Option<Vec<ProtocolExtensionInfo<'a>>>,
     },
+    /// Types are sorted by name. The verbose form is a `type-list-begin` line, one `type` line per type and a
+    /// `type-list-end` line.
     TypeList {
         extension: u32,
         types: VecOrSlice<'a, (StringOrSlice<'a>, TypeDeclaration<'a>)>,
+        verbose: bool,
     },
+    /// Problems are sorted by name. The verbose form is a `problem-list-begin` line, one `problem` line per problem
+    /// and a `problem-list-end` line.
     ProblemList {
         extension: u32,
         problems: VecOrSlice<'a, Box<dyn SimpleV0Problem>>,
+        verbose: bool,
     },
     NoSuchExtension(u32),
     ParseFail,
+    /// Steps done out of the total, as reported by [`SimpleV0ProblemBruteCalculation::progress`]
+    Progress {
+        current: u64,
+        total: u64,
+    },
+    /// A value found by the running computation, before its `result` line
+    PartialResult(ParsetimeProtocolValue<'a>),
 }
 
 pub fn command_hello_parser<'a>()
@@ -152,6 +175,11 @@ pub fn command_stop_parser<'a>()
     just("stop").to(ProtocolCommand::Stop)
 }
 
+pub fn command_progress_parser<'a>()
+-> impl Parser<'a, &'a str, ProtocolCommand<'a>, Err<Rich<'a, char>>> {
+    just("progress").to(ProtocolCommand::Progress)
+}
+
 pub fn raw_string_value_parser<'a>() -> impl Parser<'a, &'a str, &'a str, Err<Rich<'a, char>>> {
     just('"')
         .ignore_then(
@@ -463,12 +491,13 @@ pub fn type_native_declaration_parser<'a>()
             .map(TypeDeclaration::Alias))
 }
 
-pub fn type_tuple_declaration_parser<'a>()
--> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+fn type_tuple_declaration_parser0<'a>(
+    decl_parser: impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> + 'a,
+) -> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
     just("tuple")
         .then(just("(").padded())
         .ignore_then(
-            type_native_declaration_parser()
+            decl_parser
                 .padded()
                 .separated_by(just(","))
                 .allow_trailing()
@@ -480,31 +509,91 @@ pub fn type_tuple_declaration_parser<'a>()
         .map(|res| TypeDeclaration::Tuple(res.unwrap_or_default().to_vec_or_slice()))
 }
 
-pub fn type_list_declaration_parser<'a>()
--> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+fn type_list_declaration_parser0<'a>(
+    decl_parser: impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> + 'a,
+) -> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
     just("list")
         .then(just("(").padded())
-        .ignore_then(type_native_declaration_parser().padded())
+        .ignore_then(decl_parser.padded())
         .then_ignore(just(")").padded())
         .map(|res| TypeDeclaration::List(Box::new(res)))
 }
 
-pub fn type_array_declaration_parser<'a>()
--> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+fn type_array_declaration_parser0<'a>(
+    decl_parser: impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> + 'a,
+) -> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
     just("array")
         .ignore_then(just("(").padded())
-        .ignore_then(type_native_declaration_parser().padded())
+        .ignore_then(decl_parser.padded())
         .then_ignore(just(",").padded())
         .then(int_value_unsigned_parser::<u32>("u32").padded())
         .then_ignore(just(")").padded())
         .map(|(t, len)| TypeDeclaration::Array(Box::new(t), len))
 }
 
+fn type_enumeration_declaration_parser0<'a>(
+    decl_parser: impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> + 'a,
+) -> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+    let constructor = ident()
+        .then(
+            just("(")
+                .padded()
+                .ignore_then(decl_parser.padded())
+                .then_ignore(just(")"))
+                .or_not(),
+        )
+        .map(|(name, argtype)| EnumerationConstructor {
+            name: StringOrSlice::Sl(name),
+            argtype,
+        });
+    just("enum")
+        .then(just("(").padded())
+        .ignore_then(
+            constructor
+                .padded()
+                .separated_by(just(","))
+                .allow_trailing()
+                .collect::<Vec<_>>(),
+        )
+        .then_ignore(just(")").padded())
+        .map(TypeDeclaration::Enumeration)
+}
+
+pub fn type_tuple_declaration_parser<'a>()
+-> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+    type_tuple_declaration_parser0(type_declaration_parser())
+}
+
+pub fn type_list_declaration_parser<'a>()
+-> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+    type_list_declaration_parser0(type_declaration_parser())
+}
+
+pub fn type_array_declaration_parser<'a>()
+-> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+    type_array_declaration_parser0(type_declaration_parser())
+}
+
+/// `enum(Name, Name(type), ...)`, as written by [`v0_format_type_decl`]
+pub fn type_enumeration_declaration_parser<'a>()
+-> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
+    type_enumeration_declaration_parser0(type_declaration_parser())
+}
+
+/// Any type declaration, the aliases either bare native types or quoted names as written by [`v0_format_type_decl`]
 pub fn type_declaration_parser<'a>()
 -> impl Parser<'a, &'a str, TypeDeclaration<'a>, Err<Rich<'a, char>>> {
-    type_native_declaration_parser()
-        .or(type_tuple_declaration_parser())
-        .boxed()
+    recursive(|decl_parser| {
+        type_native_declaration_parser()
+            .or(raw_string_value_parser()
+                .map(StringOrSlice::Sl)
+                .map(TypeDeclaration::Alias))
+            .or(type_tuple_declaration_parser0(decl_parser.clone()))
+            .or(type_list_declaration_parser0(decl_parser.clone()))
+            .or(type_array_declaration_parser0(decl_parser.clone()))
+            .or(type_enumeration_declaration_parser0(decl_parser))
+            .boxed()
+    })
 }
 
 pub fn single_word_commands_parser<'a>()
@@ -516,28 +605,106 @@ pub fn single_word_commands_parser<'a>()
         .or(command_extensions_parser())
         .or(command_go_parser())
         .or(command_stop_parser())
+        .or(command_progress_parser())
+}
+
+fn extension_id_parser<'a>() -> impl Parser<'a, &'a str, u32, Err<Rich<'a, char>>> {
+    int(10).try_map(|s: &str, span| u32::from_str_radix(s, 10).map_err(|e| Rich::custom(span, e)))
+}
+
+fn verbose_flag_parser<'a>() -> impl Parser<'a, &'a str, bool, Err<Rich<'a, char>>> {
+    just("verbose").padded().or_not().map(|v| v.is_some())
 }
 
 pub fn list_types_command_parser<'a>()
 -> impl Parser<'a, &'a str, ProtocolCommand<'a>, Err<Rich<'a, char>>> {
     just("list-types")
-        .ignore_then(int(10).padded())
-        .try_map(|s, span| {
-            u32::from_str_radix(s, 10)
-                .map(|i| ProtocolCommand::ListTypes { extension: i })
-                .map_err(|e| Rich::custom(span, e))
-        })
+        .ignore_then(extension_id_parser().padded())
+        .then(verbose_flag_parser())
+        .map(|(extension, verbose)| ProtocolCommand::ListTypes { extension, verbose })
 }
 
 pub fn list_problems_command_parser<'a>()
 -> impl Parser<'a, &'a str, ProtocolCommand<'a>, Err<Rich<'a, char>>> {
     just("list-problems")
-        .ignore_then(int(10).padded())
-        .try_map(|s, span| {
-            u32::from_str_radix(s, 10)
-                .map(|i| ProtocolCommand::ListProblems { extension: i })
-                .map_err(|e| Rich::custom(span, e))
-        })
+        .ignore_then(extension_id_parser().padded())
+        .then(verbose_flag_parser())
+        .map(|(extension, verbose)| ProtocolCommand::ListProblems { extension, verbose })
+}
+
+pub fn framing_command_parser<'a>()
+-> impl Parser<'a, &'a str, ProtocolCommand<'a>, Err<Rich<'a, char>>> {
+    just("framing")
+        .ignore_then(
+            just("binary")
+                .to(Framing::LengthPrefixed)
+                .or(just("text").to(Framing::Text))
+                .padded(),
+        )
+        .map(ProtocolCommand::Framing)
+}
+
+/// The text of a formatted type declaration, without interpreting it
+pub fn raw_type_declaration_parser<'a>() -> impl Parser<'a, &'a str, &'a str, Err<Rich<'a, char>>> {
+    recursive(|decl| {
+        let arguments = decl
+            .padded()
+            .separated_by(just(","))
+            .allow_trailing()
+            .delimited_by(just("("), just(")"));
+        raw_string_value_parser()
+            .ignored()
+            .or(digits(10).ignored())
+            .or(ident().then(arguments.or_not()).ignored())
+    })
+    .to_slice()
+}
+
+/// Entries of a `type-list` response, in either form, as `(name, declaration text)`
+pub fn type_list_response_parser<'a>()
+-> impl Parser<'a, &'a str, (u32, Vec<(&'a str, &'a str)>), Err<Rich<'a, char>>> {
+    let entry = ident()
+        .then_ignore(just("=").padded())
+        .then(raw_type_declaration_parser());
+    let single_line = just("type-list")
+        .ignore_then(extension_id_parser().padded())
+        .then(entry.clone().padded().repeated().collect::<Vec<_>>());
+    let multi_line = just("type-list-begin")
+        .ignore_then(extension_id_parser().padded())
+        .then(
+            just("type ")
+                .ignore_then(entry.padded())
+                .repeated()
+                .collect::<Vec<_>>(),
+        )
+        .then_ignore(just("type-list-end").padded());
+    multi_line.or(single_line).then_ignore(end())
+}
+
+/// Problem tuples of a `problem-list` response, in either form
+pub fn problem_list_response_parser<'a>()
+-> impl Parser<'a, &'a str, (u32, Vec<ParsetimeProtocolValue<'a>>), Err<Rich<'a, char>>> {
+    let single_line = just("problem-list")
+        .ignore_then(extension_id_parser().padded())
+        .then(
+            generic_value_parser()
+                .padded()
+                .separated_by(just(","))
+                .allow_trailing()
+                .collect::<Vec<_>>()
+                .delimited_by(just("["), just("]"))
+                .padded(),
+        );
+    let multi_line = just("problem-list-begin")
+        .ignore_then(extension_id_parser().padded())
+        .then(
+            just("problem ")
+                .ignore_then(generic_value_parser().padded())
+                .repeated()
+                .collect::<Vec<_>>(),
+        )
+        .then_ignore(just("problem-list-end").padded());
+    multi_line.or(single_line).then_ignore(end())
 }
 
 pub fn setup_problem_argument_parser<'a>()
@@ -572,6 +739,7 @@ pub fn full_v0_parser<'a>() -> impl Parser<'a, &'a str, ProtocolCommand<'a>, Err
         .or(list_types_command_parser())
         .or(list_problems_command_parser())
         .or(setup_problem_command_parser())
+        .or(framing_command_parser())
         .then_ignore(just("\r\n").or(just("\n")).or_not())
         .then_ignore(end())
 }
@@ -641,6 +809,21 @@ pub fn v0_format_type_decl(
     }
 }
 
+/// Writes the `result` line of a computation with a single `write_all`, so a failing writer never leaves part of the
+/// line behind another message
+pub fn v0_format_result(
+    writer: &mut dyn std::io::Write,
+    result: Option<&ParsetimeProtocolValue>,
+) -> Result<(), std::io::Error> {
+    let mut line = b"result".to_vec();
+    if let Some(result) = result {
+        line.push(b' ');
+        v0_format_value(&mut line, result)?;
+    }
+    line.push(b'\n');
+    writer.write_all(&line)
+}
+
 pub fn v0_format_value(
     writer: &mut dyn std::io::Write,
     value: &ParsetimeProtocolValue,
@@ -709,6 +892,30 @@ pub fn v0_format_value(
     }
 }
 
+fn v0_format_problem(
+    writer: &mut dyn std::io::Write,
+    pb: &dyn SimpleV0Problem,
+) -> Result<(), std::io::Error> {
+    write!(writer, "(")?;
+    v0_format_string(writer, pb.name())?;
+    write!(writer, ", ")?;
+    v0_format_string(writer, pb.description())?;
+    write!(writer, ", [")?;
+    let mut not_first = false;
+    for arg in pb.args().as_slice() {
+        if not_first {
+            write!(writer, ", ")?;
+        }
+        not_first = true;
+        write!(writer, "(")?;
+        v0_format_string(writer, &arg.name)?;
+        write!(writer, ", {}, ", arg.optional)?;
+        v0_format_string(writer, &arg.argtype)?;
+        write!(writer, ")")?;
+    }
+    write!(writer, "])")
+}
+
 pub fn format_response(
     writer: &mut dyn std::io::Write,
     response: &ProtocolResponse,
@@ -793,45 +1000,67 @@ pub fn format_response(
         ProtocolResponse::NoSuchExtension(ext) => {
             writeln!(writer, "no-such-extension {}", *ext)
         }
-        ProtocolResponse::TypeList { extension, types } => {
-            write!(writer, "type-list {}", *extension)?;
-            for (name, decl) in types.as_slice() {
-                write!(writer, " {} = ", name.as_slice())?;
-                v0_format_type_decl(writer, decl)?;
-            }
+        ProtocolResponse::Progress { current, total } => {
+            writeln!(writer, "progress {} {}", *current, *total)
+        }
+        ProtocolResponse::PartialResult(value) => {
+            write!(writer, "result-partial ")?;
+            v0_format_value(writer, value)?;
             writeln!(writer)
         }
+        ProtocolResponse::TypeList {
+            extension,
+            types,
+            verbose,
+        } => {
+            let mut types = types.as_slice().iter().collect::<Vec<_>>();
+            types.sort_by(|a, b| a.0.as_slice().cmp(b.0.as_slice()));
+
+            if *verbose {
+                writeln!(writer, "type-list-begin {}", *extension)?;
+                for (name, decl) in types {
+                    write!(writer, "type {} = ", name.as_slice())?;
+                    v0_format_type_decl(writer, decl)?;
+                    writeln!(writer)?;
+                }
+                writeln!(writer, "type-list-end")
+            } else {
+                write!(writer, "type-list {}", *extension)?;
+                for (name, decl) in types {
+                    write!(writer, " {} = ", name.as_slice())?;
+                    v0_format_type_decl(writer, decl)?;
+                }
+                writeln!(writer)
+            }
+        }
         ProtocolResponse::ProblemList {
             extension,
             problems,
+            verbose,
         } => {
-            write!(writer, "problem-list {} [", *extension)?;
-            let mut not_first = false;
-            for pb in problems.as_slice() {
-                if not_first {
-                    write!(writer, ", ")?;
+            let mut problems = problems.as_slice().iter().collect::<Vec<_>>();
+            problems.sort_by(|a, b| a.name().as_slice().cmp(b.name().as_slice()));
+
+            if *verbose {
+                writeln!(writer, "problem-list-begin {}", *extension)?;
+                for pb in problems {
+                    write!(writer, "problem ")?;
+                    v0_format_problem(writer, pb.as_ref())?;
+                    writeln!(writer)?;
                 }
-                not_first = true;
-                write!(writer, "(")?;
-                v0_format_string(writer, pb.name())?;
-                write!(writer, ", ")?;
-                v0_format_string(writer, pb.description())?;
-                write!(writer, ", [")?;
-                let mut not_first2 = false;
-                for arg in pb.args().as_slice() {
-                    if not_first2 {
+                writeln!(writer, "problem-list-end")
+            } else {
+                write!(writer, "problem-list {} [", *extension)?;
+                let mut not_first = false;
+                for pb in problems {
+                    if not_first {
                         write!(writer, ", ")?;
                     }
-                    not_first2 = true;
-                    write!(writer, "(")?;
-                    v0_format_string(writer, &arg.name)?;
-                    write!(writer, ", {}, ", arg.optional)?;
-                    v0_format_string(writer, &arg.argtype)?;
-                    write!(writer, ")")?;
+                    not_first = true;
+                    v0_format_problem(writer, pb.as_ref())?;
                 }
-                write!(writer, "])")?;
+                writeln!(writer, "]")
             }
-            writeln!(writer, "]")
         }
     }
 }
@@ -846,12 +1075,29 @@ pub trait SimpleV0Extension: Debug {
 }
 
 pub trait SimpleV0ProblemBruteCalculation: Debug {
-    fn go(&mut self, output: &mut dyn Write);
+    /// Starts the computation. It either runs to the end before returning, or starts threads and returns at once, the
+    /// computation then runs while [`SimpleV0ProblemBruteCalculation::is_running`] holds. The server sends the result
+    /// once it is done. It must not panic.
+    fn go(&mut self);
+    /// Asks the running computation to end early, it ends without a result. Its threads check it between batches.
     fn stop(&mut self);
+    /// True if the computation ended without a result but wasn't stopped, like when one of its threads panicked
+    fn is_failed(&self) -> bool;
     fn is_done(&self) -> bool;
     fn is_running(&self) -> bool;
+    /// The work done so far and the total work, updated while the computation runs
     fn progress(&self) -> (u64, u64);
+    /// True if the computation is running but made no progress for `threshold`, to tell a stuck computation from a
+    /// slow one. Computations that don't track it are never stalled.
+    fn is_stalled(&self, _threshold: std::time::Duration) -> bool {
+        false
+    }
     fn result(&self) -> Option<ParsetimeProtocolValue<'_>>;
+    /// The values found since the last call, that the server sends as `result-partial` lines while the computation
+    /// runs. They come before the result once it is done. Computations that don't stream them find none.
+    fn take_new_results(&mut self) -> Vec<ParsetimeProtocolValue<'static>> {
+        Vec::new()
+    }
 }
 
 pub trait SimpleV0Problem: Debug {
@@ -875,9 +1121,358 @@ pub struct V0ProblemArgumentDescription<'a> {
 mod tests {
     use core::{f32, f64};
 
-    use chumsky::Parser;
+    use chumsky::{Parser, prelude::end};
+
+    use crate::{
+        traits::{StringOrSlice, VecOrSlice},
+        v0::{
+            EnumerationConstructor, ParsetimeProtocolValue, ProtocolCommand, ProtocolResponse,
+            SimpleV0Problem, SimpleV0ProblemBruteCalculation, TypeDeclaration,
+            V0ProblemArgumentDescription, format_response, full_v0_parser, int_value_signed_parser,
+            int_value_unsigned_parser, parse_f32, parse_f64, problem_list_response_parser,
+            type_declaration_parser, type_list_response_parser, v0_format_type_decl,
+        },
+    };
 
-    use crate::v0::{int_value_signed_parser, int_value_unsigned_parser, parse_f32, parse_f64};
+    #[derive(Debug)]
+    struct NamedProblem(&'static str);
+
+    impl SimpleV0Problem for NamedProblem {
+        fn name<'a>(&'a self) -> StringOrSlice<'a> {
+            StringOrSlice::Sl(self.0)
+        }
+
+        fn description<'a>(&'a self) -> StringOrSlice<'a> {
+            StringOrSlice::Sl("A problem")
+        }
+
+        fn args<'a>(&'a self) -> VecOrSlice<'a, V0ProblemArgumentDescription<'a>> {
+            VecOrSlice::V(vec![V0ProblemArgumentDescription {
+                name: StringOrSlice::Sl("pos"),
+                optional: true,
+                argtype: StringOrSlice::Sl("block_pos"),
+            }])
+        }
+
+        fn setup<'a>(
+            &'a self,
+            _args: VecOrSlice<'a, (StringOrSlice<'a>, ParsetimeProtocolValue<'a>)>,
+        ) -> Result<Box<dyn SimpleV0ProblemBruteCalculation>, ParsetimeProtocolValue<'a>> {
+            Err(ParsetimeProtocolValue::Bool(false))
+        }
+    }
+
+    fn sample_types() -> Vec<(StringOrSlice<'static>, TypeDeclaration<'static>)> {
+        let i32_alias = || TypeDeclaration::Alias(StringOrSlice::Sl("i32"));
+        vec![
+            (
+                StringOrSlice::Sl("pillar_caged_status"),
+                TypeDeclaration::Enumeration(vec![
+                    EnumerationConstructor {
+                        name: StringOrSlice::Sl("Caged"),
+                        argtype: None,
+                    },
+                    EnumerationConstructor {
+                        name: StringOrSlice::Sl("Range"),
+                        argtype: Some(TypeDeclaration::Tuple(VecOrSlice::V(vec![
+                            i32_alias(),
+                            i32_alias(),
+                        ]))),
+                    },
+                ]),
+            ),
+            (
+                StringOrSlice::Sl("block_pos"),
+                TypeDeclaration::Tuple(VecOrSlice::V(vec![i32_alias(), i32_alias(), i32_alias()])),
+            ),
+            (
+                StringOrSlice::Sl("chunk_list"),
+                TypeDeclaration::List(Box::new(TypeDeclaration::Array(Box::new(i32_alias()), 2))),
+            ),
+            (StringOrSlice::Sl("height"), i32_alias()),
+        ]
+    }
+
+    /// The `pillar_height_hint` type of the seedcracker extension
+    fn pillar_height_hint() -> TypeDeclaration<'static> {
+        let pillar_height = || TypeDeclaration::Alias(StringOrSlice::Sl("pillar_height"));
+        let mut constructors = [
+            "Unknown",
+            "Small",
+            "Medium",
+            "Big",
+            "MediumSmall",
+            "MediumBig",
+        ]
+        .map(|name| EnumerationConstructor {
+            name: StringOrSlice::Sl(name),
+            argtype: None,
+        })
+        .to_vec();
+        constructors.push(EnumerationConstructor {
+            name: StringOrSlice::Sl("Exact"),
+            argtype: Some(pillar_height()),
+        });
+        constructors.push(EnumerationConstructor {
+            name: StringOrSlice::Sl("Range"),
+            argtype: Some(TypeDeclaration::Tuple(VecOrSlice::V(vec![
+                pillar_height(),
+                pillar_height(),
+            ]))),
+        });
+        TypeDeclaration::Enumeration(constructors)
+    }
+
+    fn format_to_string(response: &ProtocolResponse) -> String {
+        let mut out = Vec::new();
+        format_response(&mut out, response).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_list_commands_parser() {
+        assert!(matches!(
+            full_v0_parser().parse("list-types 2\n").into_result(),
+            Ok(ProtocolCommand::ListTypes {
+                extension: 2,
+                verbose: false
+            })
+        ));
+        assert!(matches!(
+            full_v0_parser()
+                .parse("list-types 2 verbose\n")
+                .into_result(),
+            Ok(ProtocolCommand::ListTypes {
+                extension: 2,
+                verbose: true
+            })
+        ));
+        assert!(matches!(
+            full_v0_parser()
+                .parse("list-problems 0 verbose")
+                .into_result(),
+            Ok(ProtocolCommand::ListProblems {
+                extension: 0,
+                verbose: true
+            })
+        ));
+        assert!(full_v0_parser().parse("list-types 2 loud").has_errors());
+    }
+
+    #[test]
+    fn test_progress_command_parser() {
+        assert!(matches!(
+            full_v0_parser().parse("progress\n").into_result(),
+            Ok(ProtocolCommand::Progress)
+        ));
+        assert!(matches!(
+            full_v0_parser().parse("progress").into_result(),
+            Ok(ProtocolCommand::Progress)
+        ));
+        assert!(full_v0_parser().parse("progress 2").has_errors());
+    }
+
+    #[test]
+    fn test_type_list_round_trip() {
+        let types = sample_types();
+        let mut expected = types
+            .iter()
+            .map(|(name, decl)| {
+                let mut text = Vec::new();
+                v0_format_type_decl(&mut text, decl).unwrap();
+                (name.as_slice(), String::from_utf8(text).unwrap())
+            })
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        for verbose in [false, true] {
+            let text = format_to_string(&ProtocolResponse::TypeList {
+                extension: 7,
+                types: VecOrSlice::S(&types),
+                verbose,
+            });
+            assert_eq!(text.lines().count(), if verbose { 6 } else { 1 });
+
+            let (extension, parsed) = type_list_response_parser()
+                .parse(&text)
+                .into_result()
+                .unwrap();
+            assert_eq!(extension, 7);
+            assert_eq!(
+                parsed
+                    .iter()
+                    .map(|(name, decl)| (*name, decl.to_string()))
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+
+        let empty = format_to_string(&ProtocolResponse::TypeList {
+            extension: 0,
+            types: VecOrSlice::V(Vec::new()),
+            verbose: true,
+        });
+        assert_eq!(empty, "type-list-begin 0\ntype-list-end\n");
+        assert_eq!(
+            type_list_response_parser()
+                .parse(&empty)
+                .into_result()
+                .unwrap(),
+            (0, Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_type_declaration_round_trip() {
+        let alias = |name| TypeDeclaration::Alias(StringOrSlice::Sl(name));
+        let mut decls = sample_types()
+            .into_iter()
+            .map(|(_, decl)| decl)
+            .collect::<Vec<_>>();
+        decls.extend([
+            pillar_height_hint(),
+            TypeDeclaration::List(Box::new(TypeDeclaration::Tuple(VecOrSlice::V(vec![
+                alias("string"),
+                alias("block_pos"),
+            ])))),
+            TypeDeclaration::Array(Box::new(pillar_height_hint()), 10),
+            TypeDeclaration::Tuple(VecOrSlice::V(Vec::new())),
+            TypeDeclaration::Enumeration(Vec::new()),
+            TypeDeclaration::Enumeration(vec![EnumerationConstructor {
+                name: StringOrSlice::Sl("Nested"),
+                argtype: Some(TypeDeclaration::List(Box::new(TypeDeclaration::Array(
+                    Box::new(alias("f64")),
+                    3,
+                )))),
+            }]),
+        ]);
+
+        for decl in decls {
+            let mut text = Vec::new();
+            v0_format_type_decl(&mut text, &decl).unwrap();
+            let text = String::from_utf8(text).unwrap();
+            let parsed = type_declaration_parser()
+                .then_ignore(end())
+                .parse(&text)
+                .into_result();
+            assert_eq!(parsed.as_ref().ok(), Some(&decl), "{}", text);
+        }
+
+        // declarations written by hand can use the bare native types
+        assert_eq!(
+            type_declaration_parser()
+                .parse("list( tuple(i32, u8,) )")
+                .into_result()
+                .ok(),
+            Some(TypeDeclaration::List(Box::new(TypeDeclaration::Tuple(
+                VecOrSlice::V(vec![alias("i32"), alias("u8")])
+            ))))
+        );
+        assert!(
+            type_declaration_parser()
+                .then_ignore(end())
+                .parse("enum(A(), B)")
+                .has_errors()
+        );
+        assert!(
+            type_declaration_parser()
+                .then_ignore(end())
+                .parse("array(i32)")
+                .has_errors()
+        );
+    }
+
+    #[test]
+    fn test_problem_list_round_trip() {
+        let problems: Vec<Box<dyn SimpleV0Problem>> = vec![
+            Box::new(NamedProblem("zeta")),
+            Box::new(NamedProblem("alpha")),
+        ];
+
+        for verbose in [false, true] {
+            let text = format_to_string(&ProtocolResponse::ProblemList {
+                extension: 1,
+                problems: VecOrSlice::S(&problems),
+                verbose,
+            });
+            let (extension, parsed) = problem_list_response_parser()
+                .parse(&text)
+                .into_result()
+                .unwrap();
+            assert_eq!(extension, 1);
+            let names = parsed
+                .iter()
+                .map(|pb| match pb {
+                    ParsetimeProtocolValue::Tuple(None, fields) => match &fields[0] {
+                        ParsetimeProtocolValue::RawString(name) => name.as_slice().to_string(),
+                        other => panic!("unexpected problem name {:?}", other),
+                    },
+                    other => panic!("unexpected problem {:?}", other),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["alpha", "zeta"]);
+        }
+    }
+
+    #[test]
+    fn test_list_ordering_stability() {
+        let types = sample_types();
+        let mut scrambled = sample_types();
+        scrambled.reverse();
+        scrambled.swap(0, 2);
+
+        for verbose in [false, true] {
+            let a = format_to_string(&ProtocolResponse::TypeList {
+                extension: 0,
+                types: VecOrSlice::S(&types),
+                verbose,
+            });
+            let b = format_to_string(&ProtocolResponse::TypeList {
+                extension: 0,
+                types: VecOrSlice::S(&scrambled),
+                verbose,
+            });
+            assert_eq!(a, b);
+        }
+
+        let text = format_to_string(&ProtocolResponse::TypeList {
+            extension: 0,
+            types: VecOrSlice::S(&scrambled),
+            verbose: true,
+        });
+        let names = text
+            .lines()
+            .filter_map(|l| l.strip_prefix("type "))
+            .map(|l| l.split(' ').next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["block_pos", "chunk_list", "height", "pillar_caged_status"]
+        );
+
+        let problems: Vec<Box<dyn SimpleV0Problem>> = vec![
+            Box::new(NamedProblem("b")),
+            Box::new(NamedProblem("c")),
+            Box::new(NamedProblem("a")),
+        ];
+        let problems_reordered: Vec<Box<dyn SimpleV0Problem>> = vec![
+            Box::new(NamedProblem("c")),
+            Box::new(NamedProblem("a")),
+            Box::new(NamedProblem("b")),
+        ];
+        assert_eq!(
+            format_to_string(&ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::S(&problems),
+                verbose: false,
+            }),
+            format_to_string(&ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::S(&problems_reordered),
+                verbose: false,
+            })
+        );
+    }
 
     #[test]
     fn test_int_value_unsigned_parser() {
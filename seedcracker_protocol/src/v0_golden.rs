@@ -0,0 +1,376 @@
+//! Byte-for-byte expected output of [`format_response`] for every [`ProtocolResponse`] variant. Clients parse these
+//! lines, so a change here is a protocol change.
+//!
+//! After an intended change, print the new table with
+//! `cargo test -p mcsci print_goldens -- --ignored --nocapture` and replace [`GOLDENS`] with it.
+
+use crate::{
+    test_support::{StaticProblem, arg},
+    traits::{StringOrSlice, VecOrSlice},
+    v0::{
+        EnumerationConstructor, ParsetimeProtocolValue, ProtocolExtensionInfo, ProtocolResponse,
+        SimpleV0Problem, TypeDeclaration, V0ProblemArgumentDescription, format_response,
+    },
+};
+
+const PILLAR_ARGS: &[V0ProblemArgumentDescription<'static>] = &[
+    arg("pillar0height", true, "pillar_height_hint"),
+    arg("seed", false, "i64"),
+];
+
+const GOLDENS: &[(&str, &str)] = &[
+    ("ack", "ack\n"),
+    ("info", "info:  Searching \"pillar\" seeds\n"),
+    ("infos_empty", ""),
+    ("infos", "info:  first\ninfo:  second\n"),
+    ("version", "version 0\n"),
+    ("version_with_server", "version 0\"0.1.0\"\n"),
+    ("setup_ok", "setup-ok\n"),
+    (
+        "setup_error_string",
+        "setup-error \"Invalid \\\"seed\\\"\\n\"\n",
+    ),
+    (
+        "setup_error_enum",
+        "setup-error pillar_height_hint::Range((i32(76), i32(85)))\n",
+    ),
+    ("unexpected", "unexpected\n"),
+    ("unexpected_message", "unexpected \"tab\\there\"\n"),
+    ("extensions_none", "extensions 0\n"),
+    (
+        "extensions",
+        "extensions 2 extension_info(\"mcseedcracker\", \"0.1.0\", \"Cracks the world\\'s seed\", [\"AilPhaune\"], [])  extension_info(\"\\u{e9}\", \"\", \"\", [], [\"a\", \"b\"]) \n",
+    ),
+    ("no_such_extension", "no-such-extension 3\n"),
+    ("parse_fail", "parsefail\n"),
+    ("progress", "progress 3 5\n"),
+    ("partial_result", "result-partial (i64(-7), f64(0.5))\n"),
+    (
+        "type_list",
+        "type-list 1 chunks = list(array(\"i32\", 2)) empty_tuple = tuple() nested = enum(Outer(enum(Inner))) pillar_height_hint = enum(Small, Exact(\"pillar_height\"), Range(tuple(\"pillar_height\", \"pillar_height\")))\n",
+    ),
+    (
+        "type_list_verbose",
+        "type-list-begin 1\ntype chunks = list(array(\"i32\", 2))\ntype empty_tuple = tuple()\ntype nested = enum(Outer(enum(Inner)))\ntype pillar_height_hint = enum(Small, Exact(\"pillar_height\"), Range(tuple(\"pillar_height\", \"pillar_height\")))\ntype-list-end\n",
+    ),
+    ("type_list_empty", "type-list 0\n"),
+    (
+        "type_list_empty_verbose",
+        "type-list-begin 0\ntype-list-end\n",
+    ),
+    (
+        "problem_list",
+        "problem-list 0 [(\"empty\", \"\", []), (\"pillar-seed-cracker\", \"Finds the \\\"pillar\\\" seeds\", [(\"pillar0height\", true, \"pillar_height_hint\"), (\"seed\", false, \"i64\")])]\n",
+    ),
+    (
+        "problem_list_verbose",
+        "problem-list-begin 0\nproblem (\"empty\", \"\", [])\nproblem (\"pillar-seed-cracker\", \"Finds the \\\"pillar\\\" seeds\", [(\"pillar0height\", true, \"pillar_height_hint\"), (\"seed\", false, \"i64\")])\nproblem-list-end\n",
+    ),
+    ("problem_list_empty", "problem-list 0 []\n"),
+    (
+        "problem_list_empty_verbose",
+        "problem-list-begin 0\nproblem-list-end\n",
+    ),
+];
+
+/// Fails to compile when a variant is added, so it gets a golden too
+fn variant_name(response: &ProtocolResponse) -> &'static str {
+    match response {
+        ProtocolResponse::Acknowledge => "Acknowledge",
+        ProtocolResponse::Info(_) => "Info",
+        ProtocolResponse::Infos(_) => "Infos",
+        ProtocolResponse::Version { .. } => "Version",
+        ProtocolResponse::SetupOk => "SetupOk",
+        ProtocolResponse::SetupError(_) => "SetupError",
+        ProtocolResponse::Unexpected(_) => "Unexpected",
+        ProtocolResponse::Extensions { .. } => "Extensions",
+        ProtocolResponse::TypeList { .. } => "TypeList",
+        ProtocolResponse::ProblemList { .. } => "ProblemList",
+        ProtocolResponse::NoSuchExtension(_) => "NoSuchExtension",
+        ProtocolResponse::ParseFail => "ParseFail",
+        ProtocolResponse::Progress { .. } => "Progress",
+        ProtocolResponse::PartialResult(_) => "PartialResult",
+    }
+}
+
+const VARIANT_COUNT: usize = 14;
+
+fn alias(name: &'static str) -> TypeDeclaration<'static> {
+    TypeDeclaration::Alias(StringOrSlice::Sl(name))
+}
+
+fn constructor(
+    name: &'static str,
+    argtype: Option<TypeDeclaration<'static>>,
+) -> EnumerationConstructor<'static> {
+    EnumerationConstructor {
+        name: StringOrSlice::Sl(name),
+        argtype,
+    }
+}
+
+/// Not sorted by name, the formatter sorts them
+fn types() -> Vec<(StringOrSlice<'static>, TypeDeclaration<'static>)> {
+    vec![
+        (
+            StringOrSlice::Sl("pillar_height_hint"),
+            TypeDeclaration::Enumeration(vec![
+                constructor("Small", None),
+                constructor("Exact", Some(alias("pillar_height"))),
+                constructor(
+                    "Range",
+                    Some(TypeDeclaration::Tuple(VecOrSlice::V(vec![
+                        alias("pillar_height"),
+                        alias("pillar_height"),
+                    ]))),
+                ),
+            ]),
+        ),
+        (
+            StringOrSlice::Sl("nested"),
+            TypeDeclaration::Enumeration(vec![constructor(
+                "Outer",
+                Some(TypeDeclaration::Enumeration(vec![constructor(
+                    "Inner", None,
+                )])),
+            )]),
+        ),
+        (
+            StringOrSlice::Sl("chunks"),
+            TypeDeclaration::List(Box::new(TypeDeclaration::Array(Box::new(alias("i32")), 2))),
+        ),
+        (
+            StringOrSlice::Sl("empty_tuple"),
+            TypeDeclaration::Tuple(VecOrSlice::S(&[])),
+        ),
+    ]
+}
+
+fn problems() -> Vec<Box<dyn SimpleV0Problem>> {
+    vec![
+        StaticProblem::new(
+            "pillar-seed-cracker",
+            "Finds the \"pillar\" seeds",
+            PILLAR_ARGS,
+        )
+        .boxed(),
+        StaticProblem::new("empty", "", &[]).boxed(),
+    ]
+}
+
+fn cases() -> Vec<(&'static str, ProtocolResponse<'static>)> {
+    vec![
+        ("ack", ProtocolResponse::Acknowledge),
+        (
+            "info",
+            ProtocolResponse::Info(StringOrSlice::Sl("Searching \"pillar\" seeds")),
+        ),
+        ("infos_empty", ProtocolResponse::Infos(vec![])),
+        (
+            "infos",
+            ProtocolResponse::Infos(vec![
+                StringOrSlice::Sl("first"),
+                StringOrSlice::St("second".to_string()),
+            ]),
+        ),
+        (
+            "version",
+            ProtocolResponse::Version {
+                protocol_version: 0,
+                server_version: None,
+            },
+        ),
+        (
+            "version_with_server",
+            ProtocolResponse::Version {
+                protocol_version: 0,
+                server_version: Some(StringOrSlice::Sl("0.1.0")),
+            },
+        ),
+        ("setup_ok", ProtocolResponse::SetupOk),
+        (
+            "setup_error_string",
+            ProtocolResponse::SetupError(ParsetimeProtocolValue::String(StringOrSlice::Sl(
+                "Invalid \"seed\"\n",
+            ))),
+        ),
+        (
+            "setup_error_enum",
+            ProtocolResponse::SetupError(ParsetimeProtocolValue::Enumeration(
+                Some(StringOrSlice::Sl("pillar_height_hint")),
+                StringOrSlice::Sl("Range"),
+                Some(Box::new(ParsetimeProtocolValue::Tuple(
+                    None,
+                    vec![
+                        ParsetimeProtocolValue::I32(76),
+                        ParsetimeProtocolValue::I32(85),
+                    ],
+                ))),
+            )),
+        ),
+        ("unexpected", ProtocolResponse::Unexpected(None)),
+        (
+            "unexpected_message",
+            ProtocolResponse::Unexpected(Some(StringOrSlice::Sl("tab\there"))),
+        ),
+        (
+            "extensions_none",
+            ProtocolResponse::Extensions {
+                count: 0,
+                extensions: None,
+            },
+        ),
+        (
+            "extensions",
+            ProtocolResponse::Extensions {
+                count: 2,
+                extensions: Some(vec![
+                    ProtocolExtensionInfo {
+                        name: StringOrSlice::Sl("mcseedcracker"),
+                        version: StringOrSlice::Sl("0.1.0"),
+                        description: StringOrSlice::Sl("Cracks the world's seed"),
+                        authors: VecOrSlice::V(vec![StringOrSlice::Sl("AilPhaune")]),
+                        commands: VecOrSlice::S(&[]),
+                    },
+                    ProtocolExtensionInfo {
+                        name: StringOrSlice::Sl("é"),
+                        version: StringOrSlice::Sl(""),
+                        description: StringOrSlice::Sl(""),
+                        authors: VecOrSlice::V(vec![]),
+                        commands: VecOrSlice::V(vec![
+                            StringOrSlice::Sl("a"),
+                            StringOrSlice::Sl("b"),
+                        ]),
+                    },
+                ]),
+            },
+        ),
+        ("no_such_extension", ProtocolResponse::NoSuchExtension(3)),
+        ("parse_fail", ProtocolResponse::ParseFail),
+        (
+            "progress",
+            ProtocolResponse::Progress {
+                current: 3,
+                total: 5,
+            },
+        ),
+        (
+            "partial_result",
+            ProtocolResponse::PartialResult(ParsetimeProtocolValue::Tuple(
+                None,
+                vec![
+                    ParsetimeProtocolValue::I64(-7),
+                    ParsetimeProtocolValue::F64(0.5),
+                ],
+            )),
+        ),
+        (
+            "type_list",
+            ProtocolResponse::TypeList {
+                extension: 1,
+                types: VecOrSlice::V(types()),
+                verbose: false,
+            },
+        ),
+        (
+            "type_list_verbose",
+            ProtocolResponse::TypeList {
+                extension: 1,
+                types: VecOrSlice::V(types()),
+                verbose: true,
+            },
+        ),
+        (
+            "type_list_empty",
+            ProtocolResponse::TypeList {
+                extension: 0,
+                types: VecOrSlice::V(vec![]),
+                verbose: false,
+            },
+        ),
+        (
+            "type_list_empty_verbose",
+            ProtocolResponse::TypeList {
+                extension: 0,
+                types: VecOrSlice::V(vec![]),
+                verbose: true,
+            },
+        ),
+        (
+            "problem_list",
+            ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::V(problems()),
+                verbose: false,
+            },
+        ),
+        (
+            "problem_list_verbose",
+            ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::V(problems()),
+                verbose: true,
+            },
+        ),
+        (
+            "problem_list_empty",
+            ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::V(vec![]),
+                verbose: false,
+            },
+        ),
+        (
+            "problem_list_empty_verbose",
+            ProtocolResponse::ProblemList {
+                extension: 0,
+                problems: VecOrSlice::V(vec![]),
+                verbose: true,
+            },
+        ),
+    ]
+}
+
+fn format_to_string(response: &ProtocolResponse) -> String {
+    let mut out = Vec::new();
+    format_response(&mut out, response).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_v0_response_goldens() {
+    let cases = cases();
+    assert_eq!(
+        cases.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        GOLDENS.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        "every case needs a golden, see print_goldens"
+    );
+
+    let mut variants = cases
+        .iter()
+        .map(|(_, response)| variant_name(response))
+        .collect::<Vec<_>>();
+    variants.sort();
+    variants.dedup();
+    assert_eq!(variants.len(), VARIANT_COUNT);
+
+    for ((name, response), (_, golden)) in cases.iter().zip(GOLDENS.iter()) {
+        assert_eq!(
+            format_to_string(response),
+            *golden,
+            "case {} changed, see print_goldens",
+            name
+        );
+    }
+}
+
+/// Prints the goldens of the current formatter, as the source of [`GOLDENS`]
+#[test]
+#[ignore = "regenerates the goldens, run it with --ignored --nocapture"]
+fn print_goldens() {
+    println!("const GOLDENS: &[(&str, &str)] = &[");
+    for (name, response) in cases() {
+        println!("    ({:?}, {:?}),", name, format_to_string(&response));
+    }
+    println!("];");
+}
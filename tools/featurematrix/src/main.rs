@@ -0,0 +1,203 @@
+//! Builds the library crates under a curated matrix of feature combinations, so a `cfg` that only compiles with
+//! some other feature turned on is caught before a user hits it.
+//!
+//! ```sh
+//! cargo run -p featurematrix
+//! ```
+//!
+//! Every crate is built with no features, with each feature alone, with every feature and with the risky
+//! combinations of [`MATRIX`]. A new feature must be added to the matrix, `cargo test -p featurematrix` fails while
+//! a `[features]` entry of a manifest is missing from it.
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+struct CrateMatrix {
+    package: &'static str,
+    /// Relative to the workspace root
+    manifest: &'static str,
+    /// Every feature of the `[features]` table, `default` excepted
+    features: &'static [&'static str],
+    /// Combinations that broke before or that gate code of each other, built on top of each feature alone
+    risky: &'static [&'static [&'static str]],
+}
+
+const MATRIX: &[CrateMatrix] = &[
+    CrateMatrix {
+        package: "mcseedcracker",
+        manifest: "seedcracker_lib/Cargo.toml",
+        features: &[],
+        risky: &[],
+    },
+    CrateMatrix {
+        package: "mcsci",
+        manifest: "seedcracker_protocol/Cargo.toml",
+        features: &[],
+        risky: &[],
+    },
+];
+
+impl CrateMatrix {
+    /// No features first, then each feature alone, every feature and the risky combinations, without duplicates.
+    /// The features of a combination are sorted.
+    fn combinations(&self) -> Vec<Vec<&'static str>> {
+        let mut combinations = vec![Vec::new()];
+        combinations.extend(self.features.iter().map(|feature| vec![*feature]));
+        combinations.push(self.features.to_vec());
+        combinations.extend(self.risky.iter().map(|combination| combination.to_vec()));
+
+        let mut unique: Vec<Vec<&'static str>> = Vec::new();
+        for mut combination in combinations {
+            combination.sort_unstable();
+            combination.dedup();
+            if !unique.contains(&combination) {
+                unique.push(combination);
+            }
+        }
+        unique
+    }
+}
+
+/// The keys of the `[features]` table of a manifest, `default` excepted
+fn declared_features(manifest: &str) -> Vec<String> {
+    let mut features = Vec::new();
+    let mut in_features = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_features = line == "[features]";
+            continue;
+        }
+        if !in_features || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, _)) = line.split_once('=') {
+            let key = key.trim().trim_matches('"');
+            if key != "default" {
+                features.push(key.to_string());
+            }
+        }
+    }
+    features
+}
+
+/// The features of the manifest that the matrix doesn't list
+fn unlisted_features(krate: &CrateMatrix) -> std::io::Result<Vec<String>> {
+    let manifest = std::fs::read_to_string(workspace_root().join(krate.manifest))?;
+    Ok(declared_features(&manifest)
+        .into_iter()
+        .filter(|feature| !krate.features.contains(&feature.as_str()))
+        .collect())
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn build(krate: &CrateMatrix, features: &[&str]) -> bool {
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .current_dir(workspace_root())
+        .args(["build", "--all-targets", "--no-default-features"])
+        .arg("--manifest-path")
+        .arg(workspace_root().join(krate.manifest));
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+    eprintln!(
+        "info:  Building {} with {}",
+        krate.package,
+        if features.is_empty() {
+            "no features".to_string()
+        } else {
+            features.join(", ")
+        }
+    );
+    command.status().is_ok_and(|status| status.success())
+}
+
+fn main() -> ExitCode {
+    let mut failed = Vec::new();
+    for krate in MATRIX {
+        match unlisted_features(krate) {
+            Ok(unlisted) if unlisted.is_empty() => {}
+            Ok(unlisted) => {
+                eprintln!(
+                    "error: Add the features {} of {} to the matrix",
+                    unlisted.join(", "),
+                    krate.package
+                );
+                return ExitCode::FAILURE;
+            }
+            Err(err) => {
+                eprintln!("error: Can't read {}: {}", krate.manifest, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        for combination in krate.combinations() {
+            if !build(krate, &combination) {
+                failed.push(format!("{} [{}]", krate.package, combination.join(", ")));
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        eprintln!("info:  Every combination builds");
+        ExitCode::SUCCESS
+    } else {
+        for combination in failed.iter() {
+            eprintln!("error: {} doesn't build", combination);
+        }
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CrateMatrix, MATRIX, declared_features, unlisted_features, workspace_root};
+
+    #[test]
+    fn test_matrix_covers_declared_features() {
+        for krate in MATRIX {
+            let manifest = std::fs::read_to_string(workspace_root().join(krate.manifest)).unwrap();
+            assert!(manifest.contains(&format!("name = \"{}\"", krate.package)));
+            assert_eq!(unlisted_features(krate).unwrap(), Vec::<String>::new());
+            // and no feature that was removed
+            let declared = declared_features(&manifest);
+            assert!(
+                krate
+                    .features
+                    .iter()
+                    .all(|f| declared.iter().any(|d| d == f))
+            );
+            for combination in krate.risky {
+                assert!(combination.iter().all(|f| krate.features.contains(f)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_combinations() {
+        let krate = CrateMatrix {
+            package: "test",
+            manifest: "",
+            features: &["serde", "biomes", "simd"],
+            risky: &[&["serde", "biomes"], &["simd", "serde", "biomes"]],
+        };
+        assert_eq!(
+            krate.combinations(),
+            vec![
+                vec![],
+                vec!["serde"],
+                vec!["biomes"],
+                vec!["simd"],
+                vec!["biomes", "serde", "simd"],
+                vec!["biomes", "serde"],
+            ]
+        );
+
+        let manifest = "[package]\nname = \"test\"\n\n[features]\ndefault = [\"serde\"]\nserde = [\"dep:serde\"]\n# the biome lookups\nbiomes = []\n\n[dependencies]\nserde = { version = \"1\", optional = true }\n";
+        assert_eq!(declared_features(manifest), vec!["serde", "biomes"]);
+    }
+}
@@ -0,0 +1,482 @@
+// Writes the reference fixtures of the differential tests of seedcracker_lib (src/differential.rs), from the
+// repository root:
+//     java tools/fixtures/ReferenceFixtures.java seedcracker_lib/data/fixtures/v1
+//
+// Every value comes from java.util.Random and java.util.Collections, the seed derivations and the loot code are
+// written the way the 1.16.5 code is (WorldgenRandom, LootTable.fill, SpikeFeature) rather than after the Rust crate.
+// Bump SCHEMA and write to a new directory when the layout of a file changes, new cases only need a rerun.
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.Iterator;
+import java.util.List;
+import java.util.Random;
+import java.util.function.Consumer;
+import java.util.stream.Collectors;
+import java.util.stream.IntStream;
+
+public class ReferenceFixtures {
+    static final int SCHEMA = 1;
+    static final long MULTIPLIER = 0x5DEECE66DL;
+    static final long INVERSE_MULTIPLIER = 0xDFE05BCB1365L;
+    static final long MASK = (1L << 48) - 1;
+
+    static final int BURIED_TREASURE_SALT = 10387320;
+    static final int BASTION_SALT = 30084232;
+    static final int BASTION_SPACING = 27;
+    static final int BASTION_SEPARATION = 4;
+
+    // Seeds the code is most likely to get wrong
+    static final long[] EDGE_SEEDS = {0L, 1L, -1L, Long.MIN_VALUE, Long.MAX_VALUE, MULTIPLIER, MASK, 1L << 48,
+            -7193194438565520372L, 180066252004364L};
+
+    public static void main(String[] args) throws IOException {
+        Path dir = Path.of(args.length > 0 ? args[0] : ".");
+        Files.createDirectories(dir);
+        Random cases = new Random(20261014L);
+
+        write(dir, "random", randomCases(cases));
+        write(dir, "lcg", lcgCases(cases));
+        write(dir, "shuffle", shuffleCases(cases));
+        write(dir, "seeds", seedCases(cases));
+        write(dir, "chests", chestCases(cases));
+        write(dir, "features", featureCases(cases));
+        write(dir, "split", splitCases(cases));
+        write(dir, "nearly_full", nearlyFullCases(cases));
+    }
+
+    static void write(Path dir, String name, List<String> cases) throws IOException {
+        StringBuilder out = new StringBuilder();
+        out.append("{\"schema\": ").append(SCHEMA).append(", \"generator\": \"tools/fixtures/ReferenceFixtures.java\", ");
+        out.append("\"cases\": [\n");
+        for (int i = 0; i < cases.size(); i++) {
+            out.append("  ").append(cases.get(i)).append(i + 1 < cases.size() ? ",\n" : "\n");
+        }
+        out.append("]}\n");
+        Files.writeString(dir.resolve(name + ".json"), out);
+    }
+
+    static long[] seeds(Random cases, int random) {
+        long[] seeds = new long[EDGE_SEEDS.length + random];
+        System.arraycopy(EDGE_SEEDS, 0, seeds, 0, EDGE_SEEDS.length);
+        for (int i = EDGE_SEEDS.length; i < seeds.length; i++) {
+            seeds[i] = cases.nextLong();
+        }
+        return seeds;
+    }
+
+    static String ints(IntStream values) {
+        return values.mapToObj(Integer::toString).collect(Collectors.joining(", ", "[", "]"));
+    }
+
+    static String longs(long[] values) {
+        StringBuilder out = new StringBuilder("[");
+        for (int i = 0; i < values.length; i++) {
+            out.append(i == 0 ? "" : ", ").append(values[i]);
+        }
+        return out.append("]").toString();
+    }
+
+    static String chunk(int x, int z) {
+        return "[" + x + ", " + z + "]";
+    }
+
+    // java.util.Random
+
+    static final int[] BOUNDS = {1, 2, 3, 5, 7, 10, 16, 23, 27, 64, 100, 1000, 65536, 0x40000000, 0x40000001,
+            0x55555555, 0x7FFFFFFF};
+
+    static List<String> randomCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 40)) {
+            Random ints = new Random(seed);
+            Random longs = new Random(seed);
+            Random bools = new Random(seed);
+            Random floats = new Random(seed);
+            long[] nextLong = new long[8];
+            for (int i = 0; i < nextLong.length; i++) {
+                nextLong[i] = longs.nextLong();
+            }
+            StringBuilder bounded = new StringBuilder("[");
+            for (int i = 0; i < BOUNDS.length; i++) {
+                Random r = new Random(seed);
+                int bound = BOUNDS[i];
+                bounded.append(i == 0 ? "" : ", ").append("[").append(bound).append(", ")
+                        .append(ints(IntStream.range(0, 4).map(j -> r.nextInt(bound)))).append("]");
+            }
+            bounded.append("]");
+            out.add("{\"seed\": " + seed
+                    + ", \"next_int\": " + ints(IntStream.range(0, 8).map(i -> ints.nextInt()))
+                    + ", \"next_long\": " + longs(nextLong)
+                    + ", \"next_bool\": " + ints(IntStream.range(0, 16).map(i -> bools.nextBoolean() ? 1 : 0))
+                    + ", \"next_float_bits\": "
+                    + ints(IntStream.range(0, 8).map(i -> Float.floatToIntBits(floats.nextFloat())))
+                    + ", \"next_bounded_int\": " + bounded + "}");
+        }
+        return out;
+    }
+
+    // Skipping ahead: the first nextLong after `steps` steps of the generator, nextInt() is one step
+
+    static List<String> lcgCases(Random cases) {
+        int[] steps = {0, 1, 2, 3, 4, 10, 100, 1000, 12345, 65536, 1 << 20};
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 20)) {
+            for (int i = 0; i < steps.length + 2; i++) {
+                int n = i < steps.length ? steps[i] : cases.nextInt(1 << 20);
+                Random r = new Random(seed);
+                for (int j = 0; j < n; j++) {
+                    r.nextInt();
+                }
+                out.add("{\"seed\": " + seed + ", \"steps\": " + n + ", \"next_long\": " + r.nextLong() + "}");
+            }
+        }
+        return out;
+    }
+
+    // Collections.shuffle of the list 0..size, and the nextLong after it
+
+    static List<String> shuffleCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 10)) {
+            for (int size : IntStream.concat(IntStream.rangeClosed(0, 30), IntStream.of(54, 100)).toArray()) {
+                List<Integer> list = IntStream.range(0, size).boxed().collect(Collectors.toList());
+                Random r = new Random(seed);
+                Collections.shuffle(list, r);
+                out.add("{\"seed\": " + seed + ", \"size\": " + size + ", \"permutation\": "
+                        + ints(list.stream().mapToInt(Integer::intValue)) + ", \"next_long\": " + r.nextLong() + "}");
+            }
+        }
+        return out;
+    }
+
+    // WorldgenRandom of 1.16.5, each method returns the seed it sets
+
+    static long setBaseChunkSeed(int x, int z) {
+        return (long) x * 341873128712L + (long) z * 132897987541L;
+    }
+
+    static long setDecorationSeed(long seed, int x, int z) {
+        Random r = new Random(seed);
+        long a = r.nextLong() | 1L;
+        long b = r.nextLong() | 1L;
+        return (long) x * a + (long) z * b ^ seed;
+    }
+
+    static long setFeatureSeed(long decorationSeed, int index, int step) {
+        return decorationSeed + (long) index + (long) (10000 * step);
+    }
+
+    static long setLargeFeatureSeed(long seed, int x, int z) {
+        Random r = new Random(seed);
+        long a = r.nextLong();
+        long b = r.nextLong();
+        return (long) x * a ^ (long) z * b ^ seed;
+    }
+
+    static long setLargeFeatureWithSalt(long seed, int x, int z, int salt) {
+        return (long) x * 341873128712L + (long) z * 132897987541L + seed + (long) salt;
+    }
+
+    // SharedSeedRandom.seedSlimeChunk
+    static long slimeChunkSeed(long seed, int x, int z) {
+        return seed + (long) (x * x * 4987142) + (long) (x * 5947611) + (long) (z * z) * 4392871L
+                + (long) (z * 389711) ^ 987234911L;
+    }
+
+    static long[] chestSeeds(long decoratorSeed, int count) {
+        Random r = new Random(decoratorSeed);
+        long[] seeds = new long[count];
+        for (int i = 0; i < count; i++) {
+            seeds[i] = r.nextLong();
+        }
+        return seeds;
+    }
+
+    static int coordinate(Random cases) {
+        return cases.nextInt(4) == 0 ? cases.nextInt() : cases.nextInt(3750000) - 1875000;
+    }
+
+    static List<String> seedCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 90)) {
+            int x = coordinate(cases);
+            int z = coordinate(cases);
+            int salt = cases.nextInt(3) == 0 ? cases.nextInt() : cases.nextInt(100000000);
+            int carver = cases.nextInt(4);
+            int[] decorator = {cases.nextInt(20), cases.nextInt(10)};
+            // block coordinates of the corner of the chunk, as ints like ChunkPos does
+            long population = setDecorationSeed(seed, x * 16, z * 16);
+            out.add("{\"world_seed\": " + seed + ", \"chunk\": " + chunk(x, z) + ", \"salt\": " + salt
+                    + ", \"carver\": " + carver + ", \"decorator\": " + ints(IntStream.of(decorator))
+                    + ", \"terrain_seed\": " + setBaseChunkSeed(x, z)
+                    + ", \"population_seed\": " + population
+                    + ", \"decorator_seed\": " + setFeatureSeed(population, decorator[0], decorator[1])
+                    + ", \"carver_seed\": " + setLargeFeatureSeed(seed + carver, x, z)
+                    + ", \"region_seed\": " + setLargeFeatureWithSalt(seed, x, z, salt)
+                    + ", \"chunk_seed\": " + slimeChunkSeed(seed, x, z)
+                    + ", \"buried_treasure\": " + chestSeeds(setFeatureSeed(population, 1, 3), 1)[0]
+                    + ", \"desert_temple\": " + longs(chestSeeds(setFeatureSeed(population, 3, 4), 4))
+                    + ", \"shipwreck\": " + longs(chestSeeds(setFeatureSeed(population, 6, 4), 4))
+                    + ", \"bastion\": " + longs(chestSeeds(setFeatureSeed(population, 12, 4), 4))
+                    + ", \"fortress\": " + longs(chestSeeds(setFeatureSeed(population, 0, 7), 4)) + "}");
+        }
+        return out;
+    }
+
+    // The buried treasure loot table, filled like LootTable.fill does
+
+    record Stack(String item, int count, int maxStackSize) {
+        Stack withCount(int count) {
+            return new Stack(item, count, maxStackSize);
+        }
+
+        String json() {
+            return "[\"" + item + "\", " + count + "]";
+        }
+    }
+
+    record Entry(String item, int weight, int min, int max, int maxStackSize) {
+    }
+
+    record Pool(int minRolls, int maxRolls, Entry... entries) {
+    }
+
+    static final Pool[] BURIED_TREASURE = {
+            new Pool(1, 1, new Entry("minecraft:heart_of_the_sea", 1, 1, 1, 64)),
+            new Pool(5, 8, new Entry("minecraft:iron_ingot", 20, 1, 4, 64), new Entry("minecraft:gold_ingot", 10, 1, 4, 64),
+                    new Entry("minecraft:tnt", 5, 1, 2, 64)),
+            new Pool(1, 3, new Entry("minecraft:emerald", 5, 4, 8, 64), new Entry("minecraft:diamond", 5, 1, 2, 64),
+                    new Entry("minecraft:prismarine_crystals", 5, 1, 5, 64)),
+            new Pool(0, 1, new Entry("minecraft:leather_chestplate", 1, 1, 1, 1), new Entry("minecraft:iron_sword", 1, 1, 1, 1)),
+            new Pool(2, 2, new Entry("minecraft:cooked_cod", 1, 2, 4, 64), new Entry("minecraft:cooked_salmon", 1, 2, 4, 64)),
+    };
+
+    // MathHelper.nextInt
+    static int nextInt(Random random, int min, int max) {
+        return min >= max ? min : random.nextInt(max - min + 1) + min;
+    }
+
+    // LootPool.addRandomItems, the entries have no quality so luck changes nothing
+    static void addRandomItems(Pool pool, Random random, Consumer<Stack> consumer) {
+        int rolls = nextInt(random, pool.minRolls(), pool.maxRolls());
+        for (int roll = 0; roll < rolls; roll++) {
+            int total = 0;
+            for (Entry entry : pool.entries()) {
+                total += entry.weight();
+            }
+            Entry chosen = pool.entries()[0];
+            if (pool.entries().length > 1) {
+                int j = random.nextInt(total);
+                for (Entry entry : pool.entries()) {
+                    j -= entry.weight();
+                    if (j < 0) {
+                        chosen = entry;
+                        break;
+                    }
+                }
+            }
+            // set_count, the items without it have a count of 1
+            int count = nextInt(random, chosen.min(), chosen.max());
+            consumer.accept(new Stack(chosen.item(), count, chosen.maxStackSize()));
+        }
+    }
+
+    // LootTable.getRandomItems with the stack splitter
+    static List<Stack> getRandomItems(Pool[] pools, Random random) {
+        List<Stack> items = new ArrayList<>();
+        for (Pool pool : pools) {
+            addRandomItems(pool, random, stack -> {
+                if (stack.count() < stack.maxStackSize()) {
+                    items.add(stack);
+                } else {
+                    int i = stack.count();
+                    while (i > 0) {
+                        Stack copy = stack.withCount(Math.min(stack.maxStackSize(), i));
+                        i -= copy.count();
+                        items.add(copy);
+                    }
+                }
+            });
+        }
+        return items;
+    }
+
+    static void shuffleAndSplitItems(List<Stack> stacks, int emptySlots, Random random) {
+        List<Stack> list = new ArrayList<>();
+        Iterator<Stack> iterator = stacks.iterator();
+        while (iterator.hasNext()) {
+            Stack stack = iterator.next();
+            if (stack.count() <= 0) {
+                iterator.remove();
+            } else if (stack.count() > 1) {
+                list.add(stack);
+                iterator.remove();
+            }
+        }
+
+        while (emptySlots - stacks.size() - list.size() > 0 && !list.isEmpty()) {
+            Stack stack = list.remove(nextInt(random, 0, list.size() - 1));
+            int i = nextInt(random, 1, stack.count() / 2);
+            // ItemStack.split
+            Stack split = stack.withCount(i);
+            Stack rest = stack.withCount(stack.count() - i);
+            if (rest.count() > 1 && random.nextBoolean()) {
+                list.add(rest);
+            } else {
+                stacks.add(rest);
+            }
+            if (split.count() > 1 && random.nextBoolean()) {
+                list.add(split);
+            } else {
+                stacks.add(split);
+            }
+        }
+
+        stacks.addAll(list);
+        Collections.shuffle(stacks, random);
+    }
+
+    static Stack[] fill(Pool[] pools, int slotCount, Random random, List<Stack> raw) {
+        return fill(pools, new Stack[slotCount], random, raw);
+    }
+
+    // The occupied slots of `slots` are kept, like the items of a container filled twice
+    static Stack[] fill(Pool[] pools, Stack[] slots, Random random, List<Stack> raw) {
+        List<Stack> stacks = getRandomItems(pools, random);
+        raw.addAll(stacks);
+        List<Integer> free = IntStream.range(0, slots.length).filter(slot -> slots[slot] == null).boxed()
+                .collect(Collectors.toList());
+        Collections.shuffle(free, random);
+        shuffleAndSplitItems(stacks, free.size(), random);
+        for (Stack stack : stacks) {
+            if (free.isEmpty()) {
+                break;
+            }
+            slots[free.remove(free.size() - 1)] = stack.count() <= 0 ? null : stack;
+        }
+        return slots;
+    }
+
+    static List<String> chestCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        // the chest of test_buried_treasure_chest1, at block (-55, 3241)
+        long inGame = chestSeeds(setFeatureSeed(setDecorationSeed(1094031370582075292L, -64, 3232), 1, 3), 1)[0];
+        long[] seeds = seeds(cases, 200);
+        seeds[0] = inGame;
+        for (long seed : seeds) {
+            List<Stack> raw = new ArrayList<>();
+            Stack[] slots = fill(BURIED_TREASURE, 27, new Random(seed), raw);
+            // the same loot in a double chest, more free slots split more stacks
+            Stack[] doubleSlots = fill(BURIED_TREASURE, 54, new Random(seed), new ArrayList<>());
+            out.add("{\"table\": \"buried_treasure\", \"loot_seed\": " + seed + ", \"raw\": "
+                    + raw.stream().map(Stack::json).collect(Collectors.joining(", ", "[", "]"))
+                    + ", \"slots\": " + slotsJson(slots) + ", \"double_slots\": " + slotsJson(doubleSlots) + "}");
+        }
+        return out;
+    }
+
+    // shuffleAndSplitItems alone, with single items mixed with splittable stacks and about as many free slots as
+    // stacks: the splits that happen then depend on every call before them
+    static List<String> splitCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 150)) {
+            int count = 1 + cases.nextInt(12);
+            List<Stack> stacks = new ArrayList<>();
+            for (int i = 0; i < count; i++) {
+                int items = cases.nextInt(3) == 0 ? 1 : 1 + cases.nextInt(cases.nextBoolean() ? 4 : 64);
+                stacks.add(new Stack(Integer.toString(cases.nextInt(4)), items, 64));
+            }
+            int emptySlots = count - 1 + cases.nextInt(6);
+            String input = stacks.stream().map(Stack::json).collect(Collectors.joining(", ", "[", "]"));
+            Random r = new Random(seed);
+            shuffleAndSplitItems(stacks, emptySlots, r);
+            out.add("{\"seed\": " + seed + ", \"empty_slots\": " + emptySlots + ", \"stacks\": " + input
+                    + ", \"shuffled\": " + stacks.stream().map(Stack::json).collect(Collectors.joining(", ", "[", "]"))
+                    + ", \"next_long\": " + r.nextLong() + "}");
+        }
+        return out;
+    }
+
+    // Buried treasure chests with only a few more free slots than loot stacks, the other slots hold "occupied"
+    static List<String> nearlyFullCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (long seed : seeds(cases, 30)) {
+            int stacks = getRandomItems(BURIED_TREASURE, new Random(seed)).size();
+            int free = Math.min(27, stacks + cases.nextInt(3));
+            List<Integer> order = IntStream.range(0, 27).boxed().collect(Collectors.toList());
+            Collections.shuffle(order, cases);
+            Stack[] slots = new Stack[27];
+            for (int slot : order.subList(free, 27)) {
+                slots[slot] = new Stack("occupied", 1, 64);
+            }
+            String occupied = ints(order.subList(free, 27).stream().sorted().mapToInt(Integer::intValue));
+            fill(BURIED_TREASURE, slots, new Random(seed), new ArrayList<>());
+            out.add("{\"table\": \"buried_treasure\", \"loot_seed\": " + seed + ", \"occupied\": " + occupied
+                    + ", \"slots\": " + slotsJson(slots) + "}");
+        }
+        return out;
+    }
+
+    static String slotsJson(Stack[] slots) {
+        return Arrays.stream(slots).map(stack -> stack == null ? "null" : stack.json())
+                .collect(Collectors.joining(", ", "[", "]"));
+    }
+
+    // Placements and the end spikes
+
+    // A seed whose first nextFloat() is raw / 2^24
+    static long seedWithFloatBits(int raw, long lowBits) {
+        long state = ((long) raw << 24 | (lowBits & 0xFFFFFF)) & MASK;
+        long previous = ((state - 0xBL) * INVERSE_MULTIPLIER) & MASK;
+        return previous ^ MULTIPLIER;
+    }
+
+    static List<String> featureCases(Random cases) {
+        List<String> out = new ArrayList<>();
+        for (int i = 0; i < 200; i++) {
+            long seed = cases.nextLong();
+            int x = cases.nextInt(3750000) - 1875000;
+            int z = cases.nextInt(3750000) - 1875000;
+            if (i % 2 == 0) {
+                // close to the threshold, nextFloat() < 0.01F is rare otherwise
+                int raw = 167772 + cases.nextInt(9) - 4;
+                seed = seedWithFloatBits(raw, cases.nextLong()) - setLargeFeatureWithSalt(0, x, z,
+                        BURIED_TREASURE_SALT);
+            }
+            boolean generates = new Random(setLargeFeatureWithSalt(seed, x, z, BURIED_TREASURE_SALT))
+                    .nextFloat() < 0.01F;
+            out.add("{\"feature\": \"buried_treasure\", \"world_seed\": " + seed + ", \"chunk\": " + chunk(x, z)
+                    + ", \"generates\": " + generates + "}");
+        }
+
+        for (int i = 0; i < 200; i++) {
+            long seed = cases.nextLong() & MASK;
+            int x = cases.nextInt(140000) - 70000;
+            int z = cases.nextInt(140000) - 70000;
+            // StructureFeature.getPotentialFeatureChunk then BastionFeature.isFeatureChunk on the same random
+            Random r = new Random(setLargeFeatureWithSalt(seed, x, z, BASTION_SALT));
+            int chunkX = x * BASTION_SPACING + r.nextInt(BASTION_SPACING - BASTION_SEPARATION);
+            int chunkZ = z * BASTION_SPACING + r.nextInt(BASTION_SPACING - BASTION_SEPARATION);
+            String start = r.nextInt(5) >= 2 ? chunk(chunkX, chunkZ) : "null";
+            out.add("{\"feature\": \"bastion\", \"structure_seed\": " + seed + ", \"region\": " + chunk(x, z)
+                    + ", \"start\": " + start + "}");
+        }
+
+        for (long seed : seeds(cases, 50)) {
+            // SpikeFeature.SpikeCacheLoader, keyed by the pillar seed
+            long pillarSeed = new Random(seed).nextLong() & 65535L;
+            List<Integer> list = IntStream.range(0, 10).boxed().collect(Collectors.toList());
+            Collections.shuffle(list, new Random(pillarSeed));
+            out.add("{\"feature\": \"end_pillars\", \"world_seed\": " + seed + ", \"pillar_seed\": " + pillarSeed
+                    + ", \"heights\": " + ints(list.stream().mapToInt(l -> 76 + l * 3))
+                    + ", \"radii\": " + ints(list.stream().mapToInt(l -> 2 + l / 3))
+                    + ", \"caged\": " + list.stream().map(l -> Boolean.toString(l == 1 || l == 2))
+                            .collect(Collectors.joining(", ", "[", "]")) + "}");
+        }
+        return out;
+    }
+}